@@ -6,20 +6,30 @@ use crate::scheme::AdkgCliSchemeConfig;
 use crate::transcripts::{
     BroadcastMessages, DirectMessages, EncryptedAdkgTranscript, SerializedBytes,
 };
+use crate::verify::{VerificationReport, check_public_keys, deser_node_pks};
 use crate::{
-    AdkgConfig, AdkgOutputDual, AdkgPubOutput, InMemoryWriter, write_adkg_keys, write_transcript,
+    AdkgConfig, AdkgOutputDual, AdkgPubOutput, InMemoryWriter, ReshareConfig, write_adkg_keys,
+    write_transcript,
 };
 use adkg::aba::AbaConfig;
-use adkg::adkg::{AbaCrainInput, AdkgOutput, ShareWithPoly};
+use adkg::aba::crain20::{AbaCrain20Config, CoinKeys, CoinToss, EcdhCoinToss, EcdhCoinTossParams};
+use adkg::adkg::{
+    ADKG_TOPIC, AbaCrainInput, AdkgOutput, AdkgRefreshInput, ShareWithPoly, verify_key_message,
+};
 use adkg::helpers::{PartyId, lagrange_points_interpolate_at, u64_from_usize};
+use adkg::network::RetryStrategy;
+use adkg::nizk::NIZKDleqProof;
 use adkg::pke::ec_hybrid_chacha20poly1305;
 use adkg::pke::ec_hybrid_chacha20poly1305::{
     HybridCiphertext, MultiHybridCiphertext, NONCE_LENGTH,
 };
 use adkg::rand::AdkgRng;
+use adkg::rbc::r4::Rbc4RoundsConfig;
+use adkg::reshare::{NewCommittee, OldCommittee, Reshare};
 use adkg::scheme::DXKR23AdkgScheme;
 use adkg::scheme::bls12_381::DXKR23Bls12_381G1Sha256;
 use adkg::scheme::bn254::DXKR23Bn254G1Keccak256;
+use adkg::scheme::secp256k1::DXKR23Secp256k1Sha256;
 use adkg::vss::acss::AcssConfig;
 use anyhow::{Context, anyhow};
 use ark_ec::pairing::Pairing;
@@ -28,27 +38,33 @@ use ark_std::Zero;
 use ark_std::iterable::Iterable;
 use chacha20poly1305::aead::Aead;
 use chacha20poly1305::{AeadCore, ChaCha20Poly1305, Key, KeyInit, Nonce};
-use config::adkg::GroupConfig;
+use config::adkg::{AdkgPublic, AdkgSecret, GroupConfig};
 use dcipher_network::topic::TopicBasedTransport;
-use dcipher_network::topic::dispatcher::TopicDispatcher;
+use dcipher_network::topic::dispatcher::{TopicDispatcher, decode_topic_message};
 use dcipher_network::transports::replayable::reader::InMemoryReaderTransport;
 use dcipher_network::transports::replayable::writer::{InMemoryEntry, InMemoryEntryType};
 use dcipher_network::{ReceivedMessage, Recipient, Transport, TransportSender};
+use digest::core_api::BlockSizeUser;
+use digest::{DynDigest, FixedOutputReset};
 use futures_util::StreamExt;
 use itertools::Itertools;
 use rand::{CryptoRng, Rng, thread_rng};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::ops::Neg;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::oneshot;
+use tokio_util::sync::CancellationToken;
 use utils::dst::{NamedCurveGroup, NamedDynDigest};
 use utils::hash_to_curve::HashToCurve;
 use utils::serialize::fq::{FqDeserialize, FqSerialize};
 use utils::serialize::point::{PointDeserializeCompressed, PointSerializeCompressed};
 
 const TOPIC_SWAP_G1_TO_G2: &str = "adkg_dxkr23_swap_g1_to_g2";
+const TOPIC_SWAP_GENERATOR: &str = "adkg_dxkr23_swap_generator";
+const SWAP_GENERATOR_DLEQ_DST: &[u8] = b"ADKG-CLI-SWAP-GENERATOR-DLEQ";
 
 /// Run adkg for BN254 on G1, and swap ADKG output on G2
 pub async fn adkg_dxkr23_bn254_g1_keccak256_out_g2<TBT>(
@@ -107,43 +123,120 @@ where
     .await
 }
 
+/// Run adkg for secp256k1, and swap ADKG output to the standard secp256k1 generator
+pub async fn adkg_dxkr23_secp256k1_sha256_out_std<TBT>(
+    adkg_sk: &str,
+    adkg_config: AdkgConfig,
+    group_config: &GroupConfig,
+    scheme_config: AdkgCliSchemeConfig,
+    topic_transport: Arc<TBT>,
+    writer: Option<InMemoryWriter>,
+    rng: impl AdkgRng + 'static,
+) -> anyhow::Result<()>
+where
+    TBT: TopicBasedTransport<Identity = PartyId> + Send + Sync + 'static,
+{
+    let output_generator = scheme_config.output_generator;
+    let scheme = DXKR23Secp256k1Sha256::try_from(scheme_config.adkg_config)?;
+    adkg_dleq_out_generator(
+        adkg_sk,
+        adkg_config,
+        group_config,
+        &output_generator,
+        scheme,
+        topic_transport,
+        writer,
+        rng,
+    )
+    .await
+}
+
 /// Execute the adkg on g1, and then the swapping protocol to write an adkg output on both g1 & g2
+#[allow(clippy::too_many_arguments)]
+async fn adkg_pairing_out_g2<'a, E, S, TBT>(
+    adkg_sk: &str,
+    adkg_config: AdkgConfig,
+    group_config: &GroupConfig,
+    g2: &str,
+    adkg_scheme: S,
+    topic_transport: Arc<TBT>,
+    writer: Option<InMemoryWriter>,
+    rng: impl AdkgRng + 'static,
+) -> anyhow::Result<()>
+where
+    E: Pairing,
+    E::ScalarField: FqSerialize + FqDeserialize,
+    E::G1: PointSerializeCompressed + PointDeserializeCompressed,
+    E::G2: PointSerializeCompressed + PointDeserializeCompressed,
+    S: DXKR23AdkgScheme<Curve = E::G1>,
+    S::Curve: NamedCurveGroup,
+    S::Hash: NamedDynDigest,
+    S::ABAConfig: AbaConfig<'static, PartyId, Input = AbaCrainInput<S::Curve>>,
+    <S::ACSSConfig as AcssConfig<'static, S::Curve, PartyId>>::Output:
+        Into<ShareWithPoly<S::Curve>>,
+    TBT: TopicBasedTransport<Identity = PartyId> + Send + Sync + 'static,
+{
+    let t_reconstruction = group_config.t_reconstruction.get();
+    let g = adkg_scheme.generator_g();
+    let g2 = E::G2::deser_compressed_base64(g2)?;
+
+    adkg_out_swap::<S, E::G2, _, _, _>(
+        adkg_sk,
+        adkg_config,
+        group_config,
+        adkg_scheme,
+        topic_transport,
+        writer,
+        rng,
+        TOPIC_SWAP_G1_TO_G2,
+        move |adkg_out, transport| async move {
+            pairing_swap_g1_to_g2::<E, _>(t_reconstruction, adkg_out, &g, &g2, Some, transport)
+                .await
+        },
+    )
+    .await
+}
+
+/// Execute the adkg on the scheme's group, and then the swapping protocol to write an adkg output
+/// on both the source and the destination group / generator.
 ///
 /// This protocol is executed in the following stages:
-///  1. Execute standard ADKG on G1
+///  1. Execute standard ADKG on the source group
 ///     1a. The ADKG has sent an output through the oneshot channel, continue to 2.
 ///     1b. The ADKG has timed out, or returned an error => exit now
 ///  2. Write the priv/pub output to the specified files
-///  3. Execute the G1 to G2 swap protocol
+///  3. Execute the swap protocol over the `swap_topic` transport
 ///     3.a. The swap protocol completes, write the priv/pub output to the specified files, continue to 4.
 ///     3.b. The ADKG task is finished, continue to 4. <-- The swap protocol has failed.
 ///  4. Wait for the ADKG task to complete its grace period
 ///  5. Write the transcripts to disk
 #[allow(clippy::too_many_arguments)]
-async fn adkg_pairing_out_g2<'a, E, S, TBT>(
+async fn adkg_out_swap<S, CGDest, TBT, F, Fut>(
     adkg_sk: &str,
     adkg_config: AdkgConfig,
     group_config: &GroupConfig,
-    g2: &str,
     adkg_scheme: S,
     topic_transport: Arc<TBT>,
     writer: Option<InMemoryWriter>,
     rng: impl AdkgRng + 'static,
+    swap_topic: &'static str,
+    swap: F,
 ) -> anyhow::Result<()>
 where
-    E: Pairing,
-    E::ScalarField: FqSerialize + FqDeserialize,
-    E::G1: PointSerializeCompressed + PointDeserializeCompressed,
-    E::G2: PointSerializeCompressed + PointDeserializeCompressed,
-    S: DXKR23AdkgScheme<Curve = E::G1>,
+    S: DXKR23AdkgScheme,
     S::Curve: NamedCurveGroup,
+    <S::Curve as PrimeGroup>::ScalarField: FqSerialize + FqDeserialize,
     S::Hash: NamedDynDigest,
     S::ABAConfig: AbaConfig<'static, PartyId, Input = AbaCrainInput<S::Curve>>,
     <S::ACSSConfig as AcssConfig<'static, S::Curve, PartyId>>::Output:
         Into<ShareWithPoly<S::Curve>>,
+    CGDest:
+        CurveGroup<ScalarField = <S::Curve as PrimeGroup>::ScalarField> + PointSerializeCompressed,
     TBT: TopicBasedTransport<Identity = PartyId> + Send + Sync + 'static,
+    F: FnOnce(AdkgOutput<S::Curve>, TBT::Transport) -> Fut,
+    Fut: Future<Output = anyhow::Result<AdkgOutput<CGDest>>>,
 {
-    let sk = E::ScalarField::deser_base64(adkg_sk)?;
+    let sk = <S::Curve as PrimeGroup>::ScalarField::deser_base64(adkg_sk)?;
     let pks = group_config
         .nodes
         .iter()
@@ -151,11 +244,8 @@ where
         .collect::<Result<Vec<_>, _>>()?;
 
     let transport = topic_transport
-        .get_transport_for(TOPIC_SWAP_G1_TO_G2)
+        .get_transport_for(swap_topic)
         .context("failed to obtain transport")?;
-    let t_reconstruction = group_config.t_reconstruction.get();
-    let g = adkg_scheme.generator_g();
-    let g2 = E::G2::deser_compressed_base64(g2)?;
 
     // Spawn a task to run the adkg in the background.
     let (tx_adkg_out, rx_adkg_out) = oneshot::channel();
@@ -212,7 +302,7 @@ where
         node_pks: adkg_out.node_pks.clone(),
         group_pk: adkg_out.group_pk,
     };
-    let mut adkg_dual_out = AdkgOutputDual::<E::G1, E::G2> {
+    let mut adkg_dual_out = AdkgOutputDual::<S::Curve, CGDest> {
         sk: adkg_out.sk,
         out_pub_source: adkg_pub_out.clone(),
         out_pub_dest: None,
@@ -227,12 +317,12 @@ where
         tracing::error!(error = ?e, "Failed to save initial adkg output");
     }
 
-    // We have an ADKG output, keep running it in the background, and execute the pairing swap protocol
+    // We have an ADKG output, keep running it in the background, and execute the swap protocol
     tokio::select! {
         join_res = &mut adkg_task => {
             match join_res {
                 Ok(Ok(())) => {
-                    tracing::error!("Failed to execute g1 to g2 swap within grace period");
+                    tracing::error!("Failed to execute swap within grace period");
                 },
                 Ok(Err(e)) => {
                     tracing::error!(error = ?e, "ADKG task exited with an error");
@@ -243,13 +333,13 @@ where
             }
         }
 
-        adkg_out_g2 = pairing_swap_g1_to_g2::<E, _>(t_reconstruction, adkg_out, &g, &g2, transport) => {
-            match adkg_out_g2 {
-                Ok(out_g2) => {
-                    // We got an adkg output on g2, re-write the output files with the new keys
+        adkg_out_dest = swap(adkg_out, transport) => {
+            match adkg_out_dest {
+                Ok(out_dest) => {
+                    // We got an adkg output on the destination group, re-write the output files with the new keys
                     adkg_dual_out.out_pub_dest = Some(AdkgPubOutput {
-                        group_pk: out_g2.group_pk,
-                        node_pks: out_g2.node_pks,
+                        group_pk: out_dest.group_pk,
+                        node_pks: out_dest.node_pks,
                     });
 
                     if let Err(e) = write_adkg_keys(
@@ -263,7 +353,7 @@ where
                     }
                 },
                 Err(e) => {
-                    tracing::error!(error = ?e, "Failed to swap adkg output from G1 to G2");
+                    tracing::error!(error = ?e, "Failed to swap adkg output");
                 }
             }
         }
@@ -417,11 +507,15 @@ where
 
 /// Using an ADKG output, swap the public keys to a different generator of the same group.
 /// This protocol uses a pairing-based DLEQ proof to swap all the public keys from one group to another.
+///
+/// The identifier of the sender of a message in the group is obtained with `node_id`, which allows
+/// to execute the swap over a transport whose identifiers differ from the group's.
 async fn pairing_swap_g1_to_g2<E, T>(
     t_reconstruction: usize,
     adkg_output: AdkgOutput<E::G1>,
     g1: &E::G1,
     g2: &E::G2,
+    node_id: impl Fn(PartyId) -> Option<PartyId>,
     mut transport: T,
 ) -> anyhow::Result<AdkgOutput<E::G2>>
 where
@@ -467,6 +561,14 @@ where
             }
         };
 
+        let Some(sender) = node_id(sender) else {
+            tracing::warn!(
+                ?sender,
+                "Ignoring dleq message from node outside of the group"
+            );
+            continue;
+        };
+
         let dleq_j: AdkgSwapPairingGroupMessage<E::G2> = match bson::from_slice(&content) {
             Ok(dleq_j) => dleq_j,
             Err(e) => {
@@ -520,6 +622,200 @@ where
     }
 }
 
+/// Execute the adkg, and then the DLEQ swapping protocol to write an adkg output w.r.t. both the
+/// scheme's generator, and the output generator of the same group.
+#[allow(clippy::too_many_arguments)]
+async fn adkg_dleq_out_generator<S, TBT>(
+    adkg_sk: &str,
+    adkg_config: AdkgConfig,
+    group_config: &GroupConfig,
+    g_out: &str,
+    adkg_scheme: S,
+    topic_transport: Arc<TBT>,
+    writer: Option<InMemoryWriter>,
+    rng: impl AdkgRng + 'static,
+) -> anyhow::Result<()>
+where
+    S: DXKR23AdkgScheme,
+    S::Curve: NamedCurveGroup,
+    <S::Curve as PrimeGroup>::ScalarField: FqSerialize + FqDeserialize,
+    S::Hash: NamedDynDigest,
+    S::ABAConfig: AbaConfig<'static, PartyId, Input = AbaCrainInput<S::Curve>>,
+    <S::ACSSConfig as AcssConfig<'static, S::Curve, PartyId>>::Output:
+        Into<ShareWithPoly<S::Curve>>,
+    TBT: TopicBasedTransport<Identity = PartyId> + Send + Sync + 'static,
+{
+    let t_reconstruction = group_config.t_reconstruction.get();
+    let g = adkg_scheme.generator_g();
+    let g_out = S::Curve::deser_compressed_base64(g_out)?;
+
+    adkg_out_swap::<S, S::Curve, _, _, _>(
+        adkg_sk,
+        adkg_config,
+        group_config,
+        adkg_scheme,
+        topic_transport,
+        writer,
+        rng,
+        TOPIC_SWAP_GENERATOR,
+        move |adkg_out, transport| async move {
+            dleq_swap_generator::<_, S::Hash, _>(
+                t_reconstruction,
+                adkg_out,
+                &g,
+                &g_out,
+                Some,
+                transport,
+            )
+            .await
+        },
+    )
+    .await
+}
+
+/// Chaum-Pedersen DLEQ proof that there exists an s_j s.t. P = [s_j] G \land P_out = [s_j] G_out,
+/// where P = [s_j] G is the public key of the node output by the ADKG.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "CG: PointSerializeCompressed, CG::ScalarField: FqSerialize",
+    deserialize = "CG: PointDeserializeCompressed, CG::ScalarField: FqDeserialize"
+))]
+struct AdkgSwapDleqGeneratorMessage<CG, H>
+where
+    CG: CurveGroup,
+{
+    #[serde(with = "utils::serialize::point::base64")]
+    g_out_sj: CG,
+    pi: NIZKDleqProof<CG, H>,
+}
+
+/// Using an ADKG output, swap the public keys to a different generator of the same group.
+/// Unlike [`pairing_swap_g1_to_g2`], this protocol does not require a pairing, and each node
+/// attaches a Chaum-Pedersen DLEQ proof to its swapped public key instead.
+async fn dleq_swap_generator<CG, H, T>(
+    t_reconstruction: usize,
+    adkg_output: AdkgOutput<CG>,
+    g: &CG,
+    g_out: &CG,
+    node_id: impl Fn(PartyId) -> Option<PartyId>,
+    mut transport: T,
+) -> anyhow::Result<AdkgOutput<CG>>
+where
+    CG: CurveGroup + PointSerializeCompressed + PointDeserializeCompressed,
+    CG::ScalarField: FqSerialize + FqDeserialize,
+    H: Default + DynDigest + FixedOutputReset + BlockSizeUser + Clone,
+    T: Transport<Identity = PartyId>,
+{
+    let node_pks = &adkg_output
+        .node_pks
+        .ok_or(anyhow!("cannot swap group without node pks"))?;
+
+    let sender = transport
+        .sender()
+        .ok_or(anyhow!("failed to obtain transport sender"))?;
+    let mut receiver = transport
+        .receiver_stream()
+        .ok_or(anyhow!("failed to obtain transport sender"))?;
+
+    // Generate the public key w.r.t. the output generator, and prove that it uses the same secret
+    // as the public key w.r.t. the scheme's generator.
+    let g_sj = *g * adkg_output.sk;
+    let g_out_sj = *g_out * adkg_output.sk;
+    let pi = NIZKDleqProof::<CG, H>::prove(
+        &adkg_output.sk,
+        g,
+        g_out,
+        &g_sj,
+        &g_out_sj,
+        SWAP_GENERATOR_DLEQ_DST,
+        &mut thread_rng(),
+    )
+    .map_err(|_| anyhow!("failed to generate dleq proof"))?;
+    let dleq_m = bson::to_vec(&AdkgSwapDleqGeneratorMessage { g_out_sj, pi })?;
+    if let Err(e) = sender.send(dleq_m, Recipient::AllIncludingSelf).await {
+        tracing::error!(error = ?e, "Failed to send dleq generator swap message")
+    }
+
+    // Collect at least t_reconstruction + 1 valid evals to reconstruct the swapped group public key
+    let mut dleq_msgs = BTreeMap::new();
+    loop {
+        let ReceivedMessage {
+            sender, content, ..
+        } = match receiver.next().await {
+            Some(Ok(msg)) => msg,
+            Some(Err(e)) => {
+                tracing::error!(error = ?e, "Failed to receive dleq message");
+                continue;
+            }
+            None => {
+                anyhow::bail!("Stream closed: no more dleq message to receive")
+            }
+        };
+
+        let Some(sender) = node_id(sender) else {
+            tracing::warn!(
+                ?sender,
+                "Ignoring dleq message from node outside of the group"
+            );
+            continue;
+        };
+
+        let dleq_j: AdkgSwapDleqGeneratorMessage<CG, H> = match bson::from_slice(&content) {
+            Ok(dleq_j) => dleq_j,
+            Err(e) => {
+                tracing::warn!(error = ?e, "Failed to decode dleq message");
+                continue;
+            }
+        };
+
+        let Some(g_sj) = node_pks.get(sender.as_index()) else {
+            anyhow::bail!("adkg output's node_pks missing some ids")
+        };
+
+        if dleq_j
+            .pi
+            .verify(g, g_out, g_sj, &dleq_j.g_out_sj, SWAP_GENERATOR_DLEQ_DST)
+            .is_err()
+        {
+            tracing::warn!(?sender, "Failed to verify adkg swap dleq proof");
+            continue;
+        }
+
+        // Valid keys, insert.
+        dleq_msgs.insert(sender, dleq_j.g_out_sj);
+        #[allow(clippy::int_plus_one)]
+        if dleq_msgs.len() >= t_reconstruction + 1 {
+            // Enough messages, we can interpolate the remaining public keys, and the group public key
+            let points: Vec<_> = dleq_msgs
+                .iter()
+                .map(|(&j, g_out_sj)| (j.into(), *g_out_sj))
+                .collect();
+
+            let group_pk = lagrange_points_interpolate_at(&points, 0);
+            let node_pks = node_pks
+                .iter()
+                .enumerate()
+                .map(|(j, _)| {
+                    let j_node_idx = j + 1;
+                    if let Some(pk_j) = dleq_msgs.get(&PartyId::from(j_node_idx)) {
+                        *pk_j
+                    } else {
+                        lagrange_points_interpolate_at(&points, u64_from_usize(j_node_idx))
+                    }
+                })
+                .collect();
+
+            let adkg_out = AdkgOutput {
+                sk: adkg_output.sk,
+                used_sessions: adkg_output.used_sessions,
+                node_pks: Some(node_pks),
+                group_pk: Some(group_pk),
+            };
+            return Ok(adkg_out);
+        }
+    }
+}
+
 pub async fn adkg_dxkr23_bn254_g1_keccak256_out_g2_rescue(
     adkg_sk: &str,
     adkg_config: AdkgConfig,
@@ -684,6 +980,7 @@ where
         adkg_out,
         &g1,
         &g2,
+        Some,
         transport,
     )
     .await
@@ -708,6 +1005,844 @@ where
     Ok(())
 }
 
+/// Verify the public outputs and transcripts of an adkg for BN254 on G1 swapped on G2
+pub fn adkg_dxkr23_bn254_g1_keccak256_out_g2_verify(
+    group_config: &GroupConfig,
+    scheme_config: AdkgCliSchemeConfig,
+    publics: &[(PathBuf, AdkgPublic)],
+    decryption_key: Option<(PartyId, &str)>,
+    transcripts: Vec<(PathBuf, EncryptedAdkgTranscript)>,
+    report: &mut VerificationReport,
+) -> anyhow::Result<()> {
+    let scheme = DXKR23Bn254G1Keccak256::try_from(scheme_config.adkg_config)?;
+    adkg_pairing_out_g2_verify::<ark_bn254::Bn254, _>(
+        group_config,
+        &scheme_config.output_generator,
+        scheme,
+        publics,
+        decryption_key,
+        transcripts,
+        report,
+    )
+}
+
+/// Verify the public outputs and transcripts of an adkg for Bls12-381 on G1 swapped on G2
+pub fn adkg_dxkr23_bls12_381_g1_sha256_out_g2_verify(
+    group_config: &GroupConfig,
+    scheme_config: AdkgCliSchemeConfig,
+    publics: &[(PathBuf, AdkgPublic)],
+    decryption_key: Option<(PartyId, &str)>,
+    transcripts: Vec<(PathBuf, EncryptedAdkgTranscript)>,
+    report: &mut VerificationReport,
+) -> anyhow::Result<()> {
+    let scheme = DXKR23Bls12_381G1Sha256::try_from(scheme_config.adkg_config)?;
+    adkg_pairing_out_g2_verify::<ark_bls12_381::Bls12_381, _>(
+        group_config,
+        &scheme_config.output_generator,
+        scheme,
+        publics,
+        decryption_key,
+        transcripts,
+        report,
+    )
+}
+
+/// Verify the public outputs of an adkg swapped on G2, and optionally, the transcripts of the nodes.
+///
+/// For each public output, we check that the node public keys are consistent with the group public key
+/// on both the source group and the destination group, and that the keys on the destination group are
+/// the swapped source keys. The transcripts, which can be decrypted by any node of the group, are used
+/// to verify the proofs of knowledge and swap commitments broadcast by their sender, and to make sure
+/// that they match the published public keys.
+fn adkg_pairing_out_g2_verify<E, S>(
+    group_config: &GroupConfig,
+    g2: &str,
+    scheme: S,
+    publics: &[(PathBuf, AdkgPublic)],
+    decryption_key: Option<(PartyId, &str)>,
+    transcripts: Vec<(PathBuf, EncryptedAdkgTranscript)>,
+    report: &mut VerificationReport,
+) -> anyhow::Result<()>
+where
+    E: Pairing,
+    E::ScalarField: FqSerialize + FqDeserialize,
+    E::G1: PointSerializeCompressed + PointDeserializeCompressed,
+    E::G2: PointSerializeCompressed + PointDeserializeCompressed,
+    S: DXKR23AdkgScheme<Curve = E::G1>,
+    S::Curve: NamedCurveGroup,
+    S::Hash: NamedDynDigest,
+{
+    let g1 = scheme.generator_g();
+    let h1 = scheme.generator_h();
+    let g2 = E::G2::deser_compressed_base64(g2)?;
+    let t_reconstruction = group_config.t_reconstruction.get();
+
+    // Public keys of the first valid output, used to verify the transcripts
+    let mut published_pks: Option<(Vec<E::G1>, Vec<E::G2>)> = None;
+    for (file, public) in publics {
+        let source = (|| -> anyhow::Result<_> {
+            let group_pk = E::G1::deser_compressed_base64(&public.group_pk_source)?;
+            let node_pks = deser_node_pks::<E::G1>(&public.node_pks_source, group_config)?;
+            check_public_keys(&group_pk, &node_pks, t_reconstruction)?;
+            Ok(node_pks)
+        })();
+        let dest = (|| -> anyhow::Result<_> {
+            let group_pk = E::G2::deser_compressed_base64(&public.group_pk)?;
+            let node_pks = deser_node_pks::<E::G2>(&public.node_pks, group_config)?;
+            check_public_keys(&group_pk, &node_pks, t_reconstruction)?;
+            Ok(node_pks)
+        })();
+
+        let (source, dest) = match (source, dest) {
+            (Ok(source), Ok(dest)) => {
+                report.record("source_public_keys", file, None, Ok(()));
+                report.record("destination_public_keys", file, None, Ok(()));
+                (source, dest)
+            }
+            (source, dest) => {
+                report.record("source_public_keys", file, None, source.map(|_| ()));
+                report.record("destination_public_keys", file, None, dest.map(|_| ()));
+                continue;
+            }
+        };
+
+        // Make sure that the destination keys were obtained from the source keys by checking that
+        // e([s_j] G_1, G_2) == e(G_1, [s_j] G_2)
+        for (j, (g1_sj, g2_sj)) in
+            PartyId::iter_all(group_config.n.get()).zip(source.iter().zip(&dest))
+        {
+            let res = if E::multi_pairing([g1, *g1_sj], [*g2_sj, g2.neg()]).is_zero() {
+                Ok(())
+            } else {
+                Err(anyhow!(
+                    "destination public key is not the swapped source public key"
+                ))
+            };
+            report.record("swap_public_keys", file, Some(j), res);
+        }
+
+        published_pks.get_or_insert((source, dest));
+    }
+
+    let Some((adkg_id, adkg_sk)) = decryption_key else {
+        return Ok(());
+    };
+    let Some((source_pks, dest_pks)) = published_pks else {
+        for (file, _) in transcripts {
+            let res = Err(anyhow!(
+                "no valid public output to verify the transcript against"
+            ));
+            report.record("transcript_decryption", &file, None, res);
+        }
+        return Ok(());
+    };
+
+    let adkg_sk = E::ScalarField::deser_base64(adkg_sk)?;
+    let adkg_pks = group_config
+        .nodes
+        .iter()
+        .map(|p| E::G1::deser_compressed_base64(&p.public_key_material.adkg_pk))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for (file, transcript) in transcripts {
+        // Decrypt the broadcast messages of the transcript
+        let decrypted = (|| -> anyhow::Result<_> {
+            let transcript: DXKR23Transcript =
+                serde_json::from_slice(&transcript).context("failed to deserialize transcript")?;
+            let sender = transcript.id;
+            if sender.0 == 0 || sender.0 > group_config.n.get() {
+                Err(anyhow!("transcript created by unknown node {sender}"))?;
+            }
+
+            let TranscriptData { broadcasts, .. } =
+                decrypt_transcript(adkg_id, &adkg_sk, &adkg_pks, transcript)?;
+            Ok((sender, broadcasts))
+        })();
+        let (sender, broadcasts) = match decrypted {
+            Ok(decrypted) => {
+                report.record("transcript_decryption", &file, None, Ok(()));
+                decrypted
+            }
+            Err(e) => {
+                report.record("transcript_decryption", &file, None, Err(e));
+                continue;
+            }
+        };
+
+        // Find the KEY message and swap message broadcast by the sender of the transcript
+        let mut key_res = Err(anyhow!("no ADKG KEY message in transcript"));
+        let mut swap_res = Err(anyhow!("no swap message in transcript"));
+        for entry in broadcasts.0 {
+            let Some((topic, content)) = decode_topic_message(&entry.msg) else {
+                continue;
+            };
+
+            if topic == ADKG_TOPIC.as_bytes() {
+                key_res = match verify_key_message::<E::G1, S::Hash>(&g1, &h1, &content) {
+                    Ok(None) => continue, // another type of ADKG message
+                    Ok(Some(g_z_j)) if g_z_j == source_pks[sender] => Ok(()),
+                    Ok(Some(_)) => Err(anyhow!(
+                        "KEY message does not match the published source public key"
+                    )),
+                    Err(e) => Err(e).context("invalid ADKG KEY message"),
+                };
+            } else if topic == TOPIC_SWAP_G1_TO_G2.as_bytes() {
+                swap_res = match bson::from_slice::<AdkgSwapPairingGroupMessage<E::G2>>(&content) {
+                    Ok(dleq_j) if dleq_j.g2_sj != dest_pks[sender] => Err(anyhow!(
+                        "swap message does not match the published destination public key"
+                    )),
+                    Ok(dleq_j) => {
+                        if E::multi_pairing([g1, source_pks[sender]], [dleq_j.g2_sj, g2.neg()])
+                            .is_zero()
+                        {
+                            Ok(())
+                        } else {
+                            Err(anyhow!("failed to verify swap dleq proof"))
+                        }
+                    }
+                    Err(e) => Err(e).context("failed to decode swap message"),
+                };
+            }
+        }
+
+        report.record("transcript_key_pok", &file, Some(sender), key_res);
+        report.record("transcript_swap_commitment", &file, Some(sender), swap_res);
+    }
+
+    Ok(())
+}
+
+/// Refresh the shares of an adkg output for BN254 on G1, and swap the refreshed output on G2
+#[allow(clippy::too_many_arguments)]
+pub async fn adkg_dxkr23_bn254_g1_keccak256_out_g2_refresh<TBT>(
+    adkg_sk: &str,
+    previous_secret: &AdkgSecret,
+    previous_public: &AdkgPublic,
+    adkg_config: AdkgConfig,
+    group_config: &GroupConfig,
+    scheme_config: AdkgCliSchemeConfig,
+    topic_transport: Arc<TBT>,
+    rng: impl AdkgRng + 'static,
+) -> anyhow::Result<()>
+where
+    TBT: TopicBasedTransport<Identity = PartyId> + Send + Sync + 'static,
+{
+    let scheme = DXKR23Bn254G1Keccak256::try_from(scheme_config.adkg_config)?;
+    adkg_pairing_refresh_out_g2::<ark_bn254::Bn254, _, _>(
+        adkg_sk,
+        previous_secret,
+        previous_public,
+        adkg_config,
+        group_config,
+        &scheme_config.output_generator,
+        scheme,
+        topic_transport,
+        rng,
+    )
+    .await
+}
+
+/// Refresh the shares of an adkg output for Bls12-381 on G1, and swap the refreshed output on G2
+#[allow(clippy::too_many_arguments)]
+pub async fn adkg_dxkr23_bls12_381_g1_sha256_out_g2_refresh<TBT>(
+    adkg_sk: &str,
+    previous_secret: &AdkgSecret,
+    previous_public: &AdkgPublic,
+    adkg_config: AdkgConfig,
+    group_config: &GroupConfig,
+    scheme_config: AdkgCliSchemeConfig,
+    topic_transport: Arc<TBT>,
+    rng: impl AdkgRng + 'static,
+) -> anyhow::Result<()>
+where
+    TBT: TopicBasedTransport<Identity = PartyId> + Send + Sync + 'static,
+{
+    let scheme = DXKR23Bls12_381G1Sha256::try_from(scheme_config.adkg_config)?;
+    adkg_pairing_refresh_out_g2::<ark_bls12_381::Bls12_381, _, _>(
+        adkg_sk,
+        previous_secret,
+        previous_public,
+        adkg_config,
+        group_config,
+        &scheme_config.output_generator,
+        scheme,
+        topic_transport,
+        rng,
+    )
+    .await
+}
+
+/// Refresh the shares of a previous adkg output on g1, and then execute the swapping protocol to
+/// write the refreshed output on both g1 & g2.
+///
+/// This protocol is executed in the following stages:
+///  1. Execute the refresh protocol on G1, or exit if it times out / returns an error
+///  2. Write the priv/pub output to the specified files
+///  3. Execute the G1 to G2 swap protocol, and re-write the priv/pub output to the specified files
+///  4. Keep running the refresh for its grace period
+#[allow(clippy::too_many_arguments)]
+async fn adkg_pairing_refresh_out_g2<E, S, TBT>(
+    adkg_sk: &str,
+    previous_secret: &AdkgSecret,
+    previous_public: &AdkgPublic,
+    adkg_config: AdkgConfig,
+    group_config: &GroupConfig,
+    g2: &str,
+    adkg_scheme: S,
+    topic_transport: Arc<TBT>,
+    mut rng: impl AdkgRng + 'static,
+) -> anyhow::Result<()>
+where
+    E: Pairing,
+    E::ScalarField: FqSerialize + FqDeserialize,
+    E::G1: HashToCurve + PointSerializeCompressed + PointDeserializeCompressed,
+    E::G2: PointSerializeCompressed + PointDeserializeCompressed,
+    S: DXKR23AdkgScheme<Curve = E::G1>,
+    S::Curve: NamedCurveGroup,
+    S::Hash: NamedDynDigest,
+    S::ABAConfig: AbaConfig<'static, PartyId, Input = AbaCrainInput<S::Curve>>,
+    <S::ACSSConfig as AcssConfig<'static, S::Curve, PartyId>>::Output:
+        Into<ShareWithPoly<S::Curve>>,
+    TBT: TopicBasedTransport<Identity = PartyId> + Send + Sync + 'static,
+{
+    let sk = E::ScalarField::deser_base64(adkg_sk)?;
+    let pks = group_config
+        .nodes
+        .iter()
+        .map(|p| S::Curve::deser_compressed_base64(&p.public_key_material.adkg_pk))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Parse the outputs of the previous adkg on the source group
+    let previous = AdkgRefreshInput {
+        sk: E::ScalarField::deser_base64(&previous_secret.sk)
+            .context("failed to parse previous secret share")?,
+        group_pk: S::Curve::deser_compressed_base64(&previous_public.group_pk_source)
+            .context("failed to parse previous group public key")?,
+        node_pks: previous_public
+            .node_pks_source
+            .iter()
+            .map(|p| S::Curve::deser_compressed_base64(&p.pk))
+            .collect::<Result<Vec<_>, _>>()
+            .context("failed to parse previous node public keys")?,
+    };
+
+    let transport = topic_transport
+        .get_transport_for(TOPIC_SWAP_G1_TO_G2)
+        .context("failed to obtain transport")?;
+    let t_reconstruction = group_config.t_reconstruction.get();
+    let g = adkg_scheme.generator_g();
+    let g2 = E::G2::deser_compressed_base64(g2)?;
+
+    let mut adkg = adkg_scheme.new_adkg(
+        adkg_config.id,
+        group_config.n,
+        group_config.t,
+        group_config.t_reconstruction,
+        sk,
+        pks,
+    )?;
+
+    tracing::info!(
+        "Executing ADKG refresh with a timeout of {}",
+        humantime::format_duration(adkg_config.timeout)
+    );
+    let res = async {
+        let refresh_out = tokio::time::timeout(
+            adkg_config.timeout,
+            adkg.refresh(&previous, &mut rng, topic_transport),
+        )
+        .await
+        .map_err(|_| anyhow!("ADKG refresh has timed out"))?
+        .context("failed to refresh adkg shares")?;
+        tracing::info!(used_sessions = ?refresh_out.used_sessions, "Successfully refreshed secret share");
+
+        // Save the refreshed output on the source group
+        let mut adkg_dual_out = AdkgOutputDual::<E::G1, E::G2> {
+            sk: refresh_out.sk,
+            out_pub_source: AdkgPubOutput {
+                node_pks: refresh_out.node_pks.clone(),
+                group_pk: refresh_out.group_pk,
+            },
+            out_pub_dest: None,
+        };
+        if let Err(e) = write_adkg_keys(
+            &adkg_dual_out,
+            &adkg_config.priv_out,
+            &adkg_config.pub_out,
+            adkg_config.scheme_name.clone(),
+            group_config,
+        ) {
+            tracing::error!(error = ?e, "Failed to save refreshed adkg output");
+        }
+
+        // Swap the refreshed output to g2
+        let out_g2 = tokio::time::timeout(
+            adkg_config.timeout,
+            pairing_swap_g1_to_g2::<E, _>(
+                t_reconstruction,
+                refresh_out,
+                &g,
+                &g2,
+                Some,
+                transport,
+            ),
+        )
+        .await
+        .map_err(|_| anyhow!("G1 to G2 swap has timed out"))?
+        .context("failed to swap refreshed output from G1 to G2")?;
+
+        // The group public key on g2 must not have changed either
+        if !previous_public.group_pk.is_empty()
+            && out_g2.group_pk != Some(E::G2::deser_compressed_base64(&previous_public.group_pk)?)
+        {
+            anyhow::bail!("refreshed group public key on G2 does not match the previous one")
+        }
+
+        adkg_dual_out.out_pub_dest = Some(AdkgPubOutput {
+            group_pk: out_g2.group_pk,
+            node_pks: out_g2.node_pks,
+        });
+        write_adkg_keys(
+            &adkg_dual_out,
+            &adkg_config.priv_out,
+            &adkg_config.pub_out,
+            adkg_config.scheme_name.clone(),
+            group_config,
+        )
+        .context("failed to save final refreshed output")?;
+
+        tracing::info!(
+            "Running ADKG refresh until grace period of {}",
+            humantime::format_duration(adkg_config.grace_period)
+        );
+        tokio::time::sleep(adkg_config.grace_period).await;
+        Ok(())
+    }
+    .await;
+
+    tracing::warn!("Stopping ADKG refresh...");
+    adkg.stop().await;
+
+    res
+}
+
+/// Reshare an adkg output for BN254 on G1 to a new group, and swap the new output on G2
+#[allow(clippy::too_many_arguments)]
+pub async fn adkg_dxkr23_bn254_g1_keccak256_out_g2_reshare<TBT>(
+    adkg_sk: &str,
+    previous_secret: Option<&AdkgSecret>,
+    previous_public: &AdkgPublic,
+    reshare_config: ReshareConfig,
+    old_group_config: &GroupConfig,
+    new_group_config: &GroupConfig,
+    scheme_config: AdkgCliSchemeConfig,
+    topic_transport: Arc<TBT>,
+    rng: impl AdkgRng + 'static,
+) -> anyhow::Result<()>
+where
+    TBT: TopicBasedTransport<Identity = PartyId> + Send + Sync + 'static,
+{
+    let scheme = DXKR23Bn254G1Keccak256::try_from(scheme_config.adkg_config)?;
+    adkg_pairing_reshare_out_g2::<ark_bn254::Bn254, _, _>(
+        adkg_sk,
+        previous_secret,
+        previous_public,
+        reshare_config,
+        old_group_config,
+        new_group_config,
+        &scheme_config.output_generator,
+        scheme,
+        topic_transport,
+        rng,
+    )
+    .await
+}
+
+/// Reshare an adkg output for Bls12-381 on G1 to a new group, and swap the new output on G2
+#[allow(clippy::too_many_arguments)]
+pub async fn adkg_dxkr23_bls12_381_g1_sha256_out_g2_reshare<TBT>(
+    adkg_sk: &str,
+    previous_secret: Option<&AdkgSecret>,
+    previous_public: &AdkgPublic,
+    reshare_config: ReshareConfig,
+    old_group_config: &GroupConfig,
+    new_group_config: &GroupConfig,
+    scheme_config: AdkgCliSchemeConfig,
+    topic_transport: Arc<TBT>,
+    rng: impl AdkgRng + 'static,
+) -> anyhow::Result<()>
+where
+    TBT: TopicBasedTransport<Identity = PartyId> + Send + Sync + 'static,
+{
+    let scheme = DXKR23Bls12_381G1Sha256::try_from(scheme_config.adkg_config)?;
+    adkg_pairing_reshare_out_g2::<ark_bls12_381::Bls12_381, _, _>(
+        adkg_sk,
+        previous_secret,
+        previous_public,
+        reshare_config,
+        old_group_config,
+        new_group_config,
+        &scheme_config.output_generator,
+        scheme,
+        topic_transport,
+        rng,
+    )
+    .await
+}
+
+/// Reshare an adkg output for secp256k1 to a new group, and swap the new output to the standard
+/// secp256k1 generator
+#[allow(clippy::too_many_arguments)]
+pub async fn adkg_dxkr23_secp256k1_sha256_out_std_reshare<TBT>(
+    adkg_sk: &str,
+    previous_secret: Option<&AdkgSecret>,
+    previous_public: &AdkgPublic,
+    reshare_config: ReshareConfig,
+    old_group_config: &GroupConfig,
+    new_group_config: &GroupConfig,
+    scheme_config: AdkgCliSchemeConfig,
+    topic_transport: Arc<TBT>,
+    rng: impl AdkgRng + 'static,
+) -> anyhow::Result<()>
+where
+    TBT: TopicBasedTransport<Identity = PartyId> + Send + Sync + 'static,
+{
+    let scheme = DXKR23Secp256k1Sha256::try_from(scheme_config.adkg_config)?;
+    adkg_dleq_reshare_out_generator(
+        adkg_sk,
+        previous_secret,
+        previous_public,
+        reshare_config,
+        old_group_config,
+        new_group_config,
+        &scheme_config.output_generator,
+        scheme,
+        topic_transport,
+        rng,
+    )
+    .await
+}
+
+/// Reshare a previous adkg output on g1 from the old group to the new group, and then execute the
+/// G1 to G2 swapping protocol amongst the new group to write the new output on both g1 & g2.
+#[allow(clippy::too_many_arguments)]
+async fn adkg_pairing_reshare_out_g2<E, S, TBT>(
+    adkg_sk: &str,
+    previous_secret: Option<&AdkgSecret>,
+    previous_public: &AdkgPublic,
+    reshare_config: ReshareConfig,
+    old_group_config: &GroupConfig,
+    new_group_config: &GroupConfig,
+    g2: &str,
+    adkg_scheme: S,
+    topic_transport: Arc<TBT>,
+    rng: impl AdkgRng + 'static,
+) -> anyhow::Result<()>
+where
+    E: Pairing,
+    E::ScalarField: FqSerialize + FqDeserialize,
+    E::G1: HashToCurve + PointSerializeCompressed + PointDeserializeCompressed,
+    E::G2: PointSerializeCompressed + PointDeserializeCompressed,
+    S: DXKR23AdkgScheme<Curve = E::G1>,
+    S::Hash: 'static,
+    EcdhCoinToss<S::Curve, S::Hash>:
+        CoinToss<SecretKey = E::ScalarField, PublicParams = EcdhCoinTossParams<S::Curve>>,
+    TBT: TopicBasedTransport<Identity = PartyId> + Send + Sync + 'static,
+{
+    let g = adkg_scheme.generator_g();
+    let g2 = E::G2::deser_compressed_base64(g2)?;
+    let t_reconstruction = new_group_config.t_reconstruction.get();
+    let transport_ids = reshare_config.transport_ids.clone();
+
+    adkg_reshare_out_swap::<S, E::G2, _, _, _>(
+        adkg_sk,
+        previous_secret,
+        previous_public,
+        reshare_config,
+        old_group_config,
+        new_group_config,
+        adkg_scheme,
+        topic_transport,
+        rng,
+        TOPIC_SWAP_G1_TO_G2,
+        move |reshare_out, transport| async move {
+            // The transport identifiers are mapped to the new group's identifiers
+            pairing_swap_g1_to_g2::<E, _>(
+                t_reconstruction,
+                reshare_out,
+                &g,
+                &g2,
+                |id| {
+                    transport_ids
+                        .iter()
+                        .position(|i| *i == id)
+                        .map(PartyId::from_index)
+                },
+                transport,
+            )
+            .await
+        },
+    )
+    .await
+}
+
+/// Reshare a previous adkg output from the old group to the new group, and then execute the DLEQ
+/// swapping protocol amongst the new group to write the new output w.r.t. both the scheme's
+/// generator, and the output generator of the same group.
+#[allow(clippy::too_many_arguments)]
+async fn adkg_dleq_reshare_out_generator<S, TBT>(
+    adkg_sk: &str,
+    previous_secret: Option<&AdkgSecret>,
+    previous_public: &AdkgPublic,
+    reshare_config: ReshareConfig,
+    old_group_config: &GroupConfig,
+    new_group_config: &GroupConfig,
+    g_out: &str,
+    adkg_scheme: S,
+    topic_transport: Arc<TBT>,
+    rng: impl AdkgRng + 'static,
+) -> anyhow::Result<()>
+where
+    S: DXKR23AdkgScheme,
+    <S::Curve as PrimeGroup>::ScalarField: FqSerialize + FqDeserialize,
+    S::Hash: 'static,
+    EcdhCoinToss<S::Curve, S::Hash>: CoinToss<
+            SecretKey = <S::Curve as PrimeGroup>::ScalarField,
+            PublicParams = EcdhCoinTossParams<S::Curve>,
+        >,
+    TBT: TopicBasedTransport<Identity = PartyId> + Send + Sync + 'static,
+{
+    let g = adkg_scheme.generator_g();
+    let g_out = S::Curve::deser_compressed_base64(g_out)?;
+    let t_reconstruction = new_group_config.t_reconstruction.get();
+    let transport_ids = reshare_config.transport_ids.clone();
+
+    adkg_reshare_out_swap::<S, S::Curve, _, _, _>(
+        adkg_sk,
+        previous_secret,
+        previous_public,
+        reshare_config,
+        old_group_config,
+        new_group_config,
+        adkg_scheme,
+        topic_transport,
+        rng,
+        TOPIC_SWAP_GENERATOR,
+        move |reshare_out, transport| async move {
+            // The transport identifiers are mapped to the new group's identifiers
+            dleq_swap_generator::<_, S::Hash, _>(
+                t_reconstruction,
+                reshare_out,
+                &g,
+                &g_out,
+                |id| {
+                    transport_ids
+                        .iter()
+                        .position(|i| *i == id)
+                        .map(PartyId::from_index)
+                },
+                transport,
+            )
+            .await
+        },
+    )
+    .await
+}
+
+/// Reshare a previous adkg output from the old group to the new group, and then execute a swapping
+/// protocol amongst the new group to write the new output on both the source and destination groups.
+///
+/// This protocol is executed in the following stages:
+///  1. Execute the resharing protocol on the source group, or exit if it times out / returns an error
+///  2. Members of the new group write the priv/pub output to the specified files
+///  3. Members of the new group execute the swap protocol, and re-write the priv/pub output to the
+///     specified files
+///  4. Keep running the resharing for its grace period
+#[allow(clippy::too_many_arguments)]
+async fn adkg_reshare_out_swap<S, CGDest, TBT, F, Fut>(
+    adkg_sk: &str,
+    previous_secret: Option<&AdkgSecret>,
+    previous_public: &AdkgPublic,
+    reshare_config: ReshareConfig,
+    old_group_config: &GroupConfig,
+    new_group_config: &GroupConfig,
+    adkg_scheme: S,
+    topic_transport: Arc<TBT>,
+    mut rng: impl AdkgRng + 'static,
+    swap_topic: &'static str,
+    swap: F,
+) -> anyhow::Result<()>
+where
+    S: DXKR23AdkgScheme,
+    <S::Curve as PrimeGroup>::ScalarField: FqSerialize + FqDeserialize,
+    S::Hash: 'static,
+    EcdhCoinToss<S::Curve, S::Hash>: CoinToss<
+            SecretKey = <S::Curve as PrimeGroup>::ScalarField,
+            PublicParams = EcdhCoinTossParams<S::Curve>,
+        >,
+    CGDest: CurveGroup<ScalarField = <S::Curve as PrimeGroup>::ScalarField>
+        + PointSerializeCompressed
+        + PointDeserializeCompressed,
+    TBT: TopicBasedTransport<Identity = PartyId> + Send + Sync + 'static,
+    F: FnOnce(AdkgOutput<S::Curve>, TBT::Transport) -> Fut,
+    Fut: Future<Output = anyhow::Result<AdkgOutput<CGDest>>>,
+{
+    let g = adkg_scheme.generator_g();
+
+    // Public parameters of the old group, as output by the previous adkg on the source group
+    let old = OldCommittee {
+        n: old_group_config.n.get(),
+        t: old_group_config.t.get(),
+        t_reconstruction: old_group_config.t_reconstruction.get(),
+        group_pk: S::Curve::deser_compressed_base64(&previous_public.group_pk_source)
+            .context("failed to parse previous group public key")?,
+        node_pks: previous_public
+            .node_pks_source
+            .iter()
+            .map(|p| S::Curve::deser_compressed_base64(&p.pk))
+            .collect::<Result<Vec<_>, _>>()
+            .context("failed to parse previous node public keys")?,
+    };
+
+    // Public parameters of the new group, the shares are encrypted with the long-term keys
+    let new = NewCommittee {
+        n: new_group_config.n.get(),
+        t: new_group_config.t.get(),
+        t_reconstruction: new_group_config.t_reconstruction.get(),
+        pks: new_group_config
+            .nodes
+            .iter()
+            .map(|p| S::Curve::deser_compressed_base64(&p.public_key_material.adkg_pk))
+            .collect::<Result<Vec<_>, _>>()?,
+        transport_ids: reshare_config.transport_ids.clone(),
+    };
+
+    let dealer = match (reshare_config.old_id, previous_secret) {
+        (Some(old_id), Some(previous_secret)) => Some((
+            old_id,
+            <S::Curve as PrimeGroup>::ScalarField::deser_base64(&previous_secret.sk)
+                .context("failed to parse previous secret share")?,
+        )),
+        _ => None,
+    };
+    let receiver = match reshare_config.new_id {
+        Some(new_id) => Some((
+            new_id,
+            <S::Curve as PrimeGroup>::ScalarField::deser_base64(adkg_sk)?,
+        )),
+        None => None,
+    };
+
+    // The sub-protocols of the resharing are executed amongst the old group
+    let id = reshare_config.old_id.unwrap_or(PartyId(1)); // unused by non-members
+    let rbc_config = Rbc4RoundsConfig::new(id, old.n, old.t, &RetryStrategy::None);
+    let aba_config = AbaCrain20Config::<
+        EcdhCoinToss<S::Curve, S::Hash>,
+        CoinKeys<EcdhCoinToss<S::Curve, S::Hash>>,
+    >::new(id, old.n, old.t, RetryStrategy::None);
+
+    // Only the members of the new group execute the swap
+    let transport = match reshare_config.new_id {
+        Some(_) => Some(
+            topic_transport
+                .get_transport_for(swap_topic)
+                .context("failed to obtain transport")?,
+        ),
+        None => None,
+    };
+    let previous_group_pk = previous_public.group_pk.clone();
+
+    let mut reshare = Reshare::<S::Curve, S::Hash, _, _>::new(
+        old, new, g, dealer, receiver, rbc_config, aba_config,
+    )
+    .context("failed to create adkg resharing")?;
+
+    tracing::info!(
+        "Executing ADKG resharing with a timeout of {}",
+        humantime::format_duration(reshare_config.timeout)
+    );
+    let cancel = CancellationToken::new();
+    let res = async {
+        let reshare_out = tokio::time::timeout(
+            reshare_config.timeout,
+            reshare.reshare(&mut rng, topic_transport, &cancel),
+        )
+        .await
+        .map_err(|_| anyhow!("ADKG resharing has timed out"))?
+        .context("failed to reshare adkg output")?;
+
+        if let (Some(reshare_out), Some(transport), Some(priv_out), Some(pub_out)) = (
+            reshare_out,
+            transport,
+            &reshare_config.priv_out,
+            &reshare_config.pub_out,
+        ) {
+            tracing::info!(used_sessions = ?reshare_out.used_sessions, "Successfully obtained new secret share");
+
+            // Save the new output on the source group
+            let mut adkg_dual_out = AdkgOutputDual::<S::Curve, CGDest> {
+                sk: reshare_out.sk,
+                out_pub_source: AdkgPubOutput {
+                    node_pks: reshare_out.node_pks.clone(),
+                    group_pk: reshare_out.group_pk,
+                },
+                out_pub_dest: None,
+            };
+            if let Err(e) = write_adkg_keys(
+                &adkg_dual_out,
+                priv_out,
+                pub_out,
+                reshare_config.scheme_name.clone(),
+                new_group_config,
+            ) {
+                tracing::error!(error = ?e, "Failed to save reshared adkg output");
+            }
+
+            // Swap the new output to the destination group
+            let out_dest = tokio::time::timeout(reshare_config.timeout, swap(reshare_out, transport))
+                .await
+                .map_err(|_| anyhow!("swap of the reshared output has timed out"))?
+                .context("failed to swap reshared output")?;
+
+            // The group public key on the destination group must not have changed
+            if !previous_group_pk.is_empty()
+                && out_dest.group_pk != Some(CGDest::deser_compressed_base64(&previous_group_pk)?)
+            {
+                anyhow::bail!(
+                    "reshared group public key on the destination group does not match the previous one"
+                )
+            }
+
+            adkg_dual_out.out_pub_dest = Some(AdkgPubOutput {
+                group_pk: out_dest.group_pk,
+                node_pks: out_dest.node_pks,
+            });
+            write_adkg_keys(
+                &adkg_dual_out,
+                priv_out,
+                pub_out,
+                reshare_config.scheme_name.clone(),
+                new_group_config,
+            )
+            .context("failed to save final reshared output")?;
+        }
+
+        tracing::info!(
+            "Running ADKG resharing until grace period of {}",
+            humantime::format_duration(reshare_config.grace_period)
+        );
+        tokio::time::sleep(reshare_config.grace_period).await;
+        Ok(())
+    }
+    .await;
+
+    tracing::warn!("Stopping ADKG resharing...");
+    cancel.cancel();
+    reshare.stop().await;
+
+    res
+}
+
 /// An encrypted adkg transcript that can be stored and sent to nodes.
 /// Authenticity of the transcript is obtained by relying on hybrid encryption w/ static public keys.
 #[serde_with::serde_as]
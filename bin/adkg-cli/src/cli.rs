@@ -41,6 +41,24 @@ pub enum Commands {
     )]
     Rescue(Rescue),
 
+    /// Refresh the shares output by a previous ADKG.
+    #[command(
+        about = "Proactively refresh the shares output by a previous ADKG, keeping the same group public key"
+    )]
+    Refresh(RefreshAdkg),
+
+    /// Reshare the output of a previous ADKG to a new group.
+    #[command(
+        about = "Reshare the output of a previous ADKG to a new group, keeping the same group public key"
+    )]
+    Reshare(ReshareAdkg),
+
+    /// Verify the outputs of a completed ADKG ceremony.
+    #[command(
+        about = "Verify the public outputs and transcripts of a completed ADKG, and output a report"
+    )]
+    Verify(VerifyAdkg),
+
     #[command(about = "Turn dcipher keys into formats for other applications")]
     Transmogrify(TransmogrifyArgs),
 }
@@ -116,6 +134,12 @@ pub struct RunAdkg {
     )]
     pub transcript_out: Option<PathBuf>,
 
+    #[arg(
+        long,
+        help = "Encrypted journal used to resume the ADKG if the node restarts during the ceremony"
+    )]
+    pub journal: Option<PathBuf>,
+
     #[cfg(feature = "metrics")]
     #[command(flatten)]
     pub metrics_params: MetricsParams,
@@ -131,6 +155,171 @@ pub struct Rescue {
     pub transcript_files: Vec<PathBuf>,
 }
 
+/// Refresh the shares of a previous ADKG output.
+#[derive(Parser, Debug)]
+pub struct RefreshAdkg {
+    #[command(flatten)]
+    pub common: AdkgRunCommon,
+
+    #[arg(long, help = "The private output of the previous ADKG or refresh")]
+    pub adkg_priv: PathBuf,
+
+    #[arg(long, help = "The public output of the previous ADKG or refresh")]
+    pub adkg_pub: PathBuf,
+
+    #[arg(long, help = "The libp2p listen address for refresh messages")]
+    pub listen_address: Multiaddr,
+
+    #[arg(
+        long,
+        help = "The time at which the refresh starts, immediately if not specified"
+    )]
+    pub start_time: Option<chrono::DateTime<chrono::Utc>>,
+
+    #[arg(
+        long,
+        help = "Timeout after which to abort the refresh",
+        value_parser = humantime::parse_duration,
+        default_value = "1h"
+    )]
+    pub timeout: std::time::Duration,
+
+    #[arg(
+        long,
+        help = "Grace period for which we keep running a completed refresh",
+        value_parser = humantime::parse_duration,
+        default_value = "5m"
+    )]
+    pub grace_period: std::time::Duration,
+}
+
+/// Reshare the output of a previous ADKG from an old group to a new group.
+#[derive(Parser, Debug)]
+pub struct ReshareAdkg {
+    #[arg(long, help = "The scheme configuration in a toml file")]
+    pub scheme: PathBuf,
+
+    #[arg(
+        long = "old-group",
+        help = "The configuration of the group holding the shares in a toml file"
+    )]
+    pub old_group_file: PathBuf,
+
+    #[arg(
+        long = "new-group",
+        help = "The configuration of the group receiving the shares in a toml file"
+    )]
+    pub new_group_file: PathBuf,
+
+    #[arg(long = "priv", help = "The private key material stored in a toml file")]
+    pub priv_file: PathBuf,
+
+    #[arg(
+        long,
+        help = "The identifier of the node in the old group, if it is a member"
+    )]
+    pub old_id: Option<NonZeroUsize>,
+
+    #[arg(
+        long,
+        help = "The identifier of the node in the new group, if it is a member"
+    )]
+    pub new_id: Option<NonZeroUsize>,
+
+    #[arg(
+        long,
+        help = "The private output of the previous ADKG, required for members of the old group"
+    )]
+    pub adkg_priv: Option<PathBuf>,
+
+    #[arg(long, help = "The public output of the previous ADKG")]
+    pub adkg_pub: PathBuf,
+
+    #[arg(
+        long,
+        help = "The output file used to store the new ADKG private key, required for members of the new group"
+    )]
+    pub priv_out: Option<PathBuf>,
+
+    #[arg(
+        long,
+        short,
+        help = "The output file used to store the new ADKG public keys, required for members of the new group"
+    )]
+    pub pub_out: Option<PathBuf>,
+
+    #[arg(long, help = "The libp2p listen address for reshare messages")]
+    pub listen_address: Multiaddr,
+
+    #[arg(
+        long,
+        help = "The time at which the resharing starts, immediately if not specified"
+    )]
+    pub start_time: Option<chrono::DateTime<chrono::Utc>>,
+
+    #[arg(
+        long,
+        help = "Timeout after which to abort the resharing",
+        value_parser = humantime::parse_duration,
+        default_value = "1h"
+    )]
+    pub timeout: std::time::Duration,
+
+    #[arg(
+        long,
+        help = "Grace period for which we keep running a completed resharing",
+        value_parser = humantime::parse_duration,
+        default_value = "5m"
+    )]
+    pub grace_period: std::time::Duration,
+}
+
+/// Verify the public outputs and transcripts of a completed ADKG.
+#[derive(Parser, Debug)]
+pub struct VerifyAdkg {
+    #[arg(long, help = "The scheme configuration in a toml file")]
+    pub scheme: PathBuf,
+
+    #[arg(long = "group", help = "The group configuration in a toml file")]
+    pub group_file: PathBuf,
+
+    #[arg(
+        long = "pub",
+        required = true,
+        num_args = 1..,
+        help = "The public outputs of the ADKG published by the nodes"
+    )]
+    pub pub_files: Vec<PathBuf>,
+
+    #[arg(
+        long = "priv",
+        requires = "id",
+        help = "The private key material of a node, used to decrypt the transcripts"
+    )]
+    pub priv_file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        requires = "priv_file",
+        help = "The identifier of the node decrypting the transcripts"
+    )]
+    pub id: Option<NonZeroUsize>,
+
+    #[arg(
+        long = "transcripts",
+        num_args = 1..,
+        requires = "priv_file",
+        help = "A list of files containing encrypted transcripts"
+    )]
+    pub transcript_files: Vec<PathBuf>,
+
+    #[arg(
+        long,
+        help = "The output file used to store the verification report, printed to stdout if not specified"
+    )]
+    pub report_out: Option<PathBuf>,
+}
+
 #[derive(Parser, Debug)]
 pub struct AdkgRunCommon {
     #[arg(long, help = "The scheme configuration in a toml file")]
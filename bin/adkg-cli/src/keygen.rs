@@ -2,6 +2,7 @@ use crate::scheme::{AdkgCliSchemeConfig, SupportedAdkgScheme};
 use adkg::scheme::DXKR23AdkgScheme;
 use adkg::scheme::bls12_381::DXKR23Bls12_381G1Sha256;
 use adkg::scheme::bn254::DXKR23Bn254G1Keccak256;
+use adkg::scheme::secp256k1::DXKR23Secp256k1Sha256;
 use anyhow::Context;
 use config::adkg::{PrivateKeyMaterial, PublicKeyMaterial};
 use config::keys::Libp2pKeyWrapper;
@@ -59,5 +60,25 @@ pub fn keygen(
 
             Ok((sk, pk))
         }
+
+        SupportedAdkgScheme::DXKR23Secp256k1Sha256 => {
+            let scheme = DXKR23Secp256k1Sha256::try_from(scheme_config.adkg_config)?;
+            let (adkg_sk, adkg_pk) = scheme.keygen(&mut thread_rng());
+            let sk = PrivateKeyMaterial {
+                adkg_sk: adkg_sk
+                    .ser_base64()
+                    .context("failed to serialize adkg sk")?,
+                libp2p_sk: Libp2pKeyWrapper(libp2p_sk),
+            };
+
+            let pk = PublicKeyMaterial {
+                adkg_pk: adkg_pk
+                    .ser_compressed_base64()
+                    .context("failed to serialize adkg pk")?,
+                peer_id,
+            };
+
+            Ok((sk, pk))
+        }
     }
 }
@@ -8,24 +8,35 @@ mod metrics;
 mod scheme;
 mod transcripts;
 mod transmogrify;
+mod verify;
 
 use crate::adkg_dxkr23::{
-    adkg_dxkr23_bls12_381_g1_sha256_out_g2, adkg_dxkr23_bls12_381_g1_sha256_out_g2_rescue,
-    adkg_dxkr23_bn254_g1_keccak256_out_g2, adkg_dxkr23_bn254_g1_keccak256_out_g2_rescue,
+    adkg_dxkr23_bls12_381_g1_sha256_out_g2, adkg_dxkr23_bls12_381_g1_sha256_out_g2_refresh,
+    adkg_dxkr23_bls12_381_g1_sha256_out_g2_rescue, adkg_dxkr23_bls12_381_g1_sha256_out_g2_reshare,
+    adkg_dxkr23_bls12_381_g1_sha256_out_g2_verify, adkg_dxkr23_bn254_g1_keccak256_out_g2,
+    adkg_dxkr23_bn254_g1_keccak256_out_g2_refresh, adkg_dxkr23_bn254_g1_keccak256_out_g2_rescue,
+    adkg_dxkr23_bn254_g1_keccak256_out_g2_reshare, adkg_dxkr23_bn254_g1_keccak256_out_g2_verify,
+    adkg_dxkr23_secp256k1_sha256_out_std, adkg_dxkr23_secp256k1_sha256_out_std_reshare,
+};
+use crate::cli::{
+    AdkgRunCommon, Cli, Commands, Generate, NewScheme, RefreshAdkg, Rescue, ReshareAdkg, RunAdkg,
+    VerifyAdkg,
 };
-use crate::cli::{AdkgRunCommon, Cli, Commands, Generate, NewScheme, Rescue, RunAdkg};
 use crate::keygen::keygen;
 use crate::scheme::{AdkgCliSchemeConfig, SupportedAdkgScheme, new_scheme_config};
 use crate::transcripts::EncryptedAdkgTranscript;
 use crate::transmogrify::transmogrify;
+use crate::verify::VerificationReport;
 use adkg::helpers::PartyId;
-use adkg::rand::AdkgStdRng;
+use adkg::journal::{AdkgJournal, JournalEntries, JournalTransport, JournalWriter};
+use adkg::rand::{AdkgRng, AdkgStdRng};
 use anyhow::{Context, anyhow};
 use ark_ec::CurveGroup;
 use ark_std::rand;
+use base64::prelude::{BASE64_STANDARD, Engine as _};
 use clap::Parser;
 use config::adkg::PrivateKeyMaterial;
-use config::adkg::{AdkgNodePk, AdkgPublic, AdkgSecret, GroupConfig};
+use config::adkg::{AdkgNodePk, AdkgPublic, AdkgSecret, GroupConfig, NodeDetail};
 use dcipher_network::topic::dispatcher::{TopicBasedTransportImpl, TopicDispatcher};
 use dcipher_network::transports::libp2p::transport::Libp2pSender;
 use dcipher_network::transports::libp2p::{Libp2pNode, Libp2pNodeConfig};
@@ -35,6 +46,7 @@ use dcipher_network::transports::replayable::writer::TransportWriterSender;
 use libp2p::Multiaddr;
 use rand::rngs::OsRng;
 use std::fs;
+use std::num::NonZeroUsize;
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::Arc;
@@ -63,6 +75,12 @@ async fn main() -> anyhow::Result<()> {
 
         Commands::Rescue(args) => rescue_adkg(args).await?,
 
+        Commands::Refresh(args) => refresh_adkg(args).await?,
+
+        Commands::Reshare(args) => reshare_adkg(args).await?,
+
+        Commands::Verify(args) => verify_adkg(args)?,
+
         Commands::Transmogrify(args) => transmogrify(args)?,
     }
 
@@ -143,6 +161,7 @@ async fn run_adkg(args: RunAdkg) -> anyhow::Result<()> {
         timeout,
         grace_period,
         transcript_out,
+        journal,
         #[cfg(feature = "metrics")]
         metrics_params,
     } = args;
@@ -168,7 +187,36 @@ async fn run_adkg(args: RunAdkg) -> anyhow::Result<()> {
         .adkg_scheme_name
         .parse()
         .context("adkg scheme not supported")?;
-    let rng = AdkgStdRng::new(OsRng);
+
+    // Open the journal to resume a previous execution of the ADKG, if enabled.
+    // The randomness is derived from a seed stored in the journal such that a restarted node
+    // recomputes the same messages.
+    let journal = journal
+        .map(|path| {
+            let session = format!(
+                "{}_{}_{}",
+                adkg_config.scheme_name,
+                group_config.start_time.timestamp(),
+                id.get()
+            );
+            // Derive the encryption key of the journal from the serialized secret scalar
+            let secret = BASE64_STANDARD
+                .decode(&sk.adkg_sk)
+                .context("failed to decode adkg private key")?;
+            AdkgJournal::open(&path, &secret, session.as_bytes(), &mut OsRng)
+                .context("failed to open adkg journal")
+        })
+        .transpose()?;
+    let journal_rng = journal.as_ref().map(|(_, entries)| {
+        if entries.resumed {
+            tracing::info!(
+                received = entries.received.len(),
+                sent = entries.sent.len(),
+                "Resuming ADKG from journal"
+            );
+        }
+        entries.rng()
+    });
 
     // Start metrics server if enabled
     #[cfg(feature = "metrics")]
@@ -176,31 +224,31 @@ async fn run_adkg(args: RunAdkg) -> anyhow::Result<()> {
 
     // Start libp2p transport
     let transports =
-        get_libp2p_transports(adkg_config.id, &sk, listen_address, &group_config).await?;
-
-    let adkg_res = match adkg_scheme {
-        SupportedAdkgScheme::DXKR23Bn254G1Keccak256 => {
-            adkg_dxkr23_bn254_g1_keccak256_out_g2(
-                &sk.adkg_sk,
+        get_libp2p_transports(adkg_config.id, &sk, listen_address, &group_config, journal).await?;
+
+    // Only use deterministic randomness when it is required to resume from the journal
+    let adkg_res = match journal_rng {
+        Some(rng) => {
+            run_adkg_scheme(
+                adkg_scheme,
+                &sk,
                 adkg_config,
                 &group_config,
                 scheme_config,
-                transports.topic_transport.clone(),
-                Some(transports.writer),
+                &transports,
                 rng,
             )
             .await
         }
-
-        SupportedAdkgScheme::DXKR23Bls12_381G1Sha256 => {
-            adkg_dxkr23_bls12_381_g1_sha256_out_g2(
-                &sk.adkg_sk,
+        None => {
+            run_adkg_scheme(
+                adkg_scheme,
+                &sk,
                 adkg_config,
                 &group_config,
                 scheme_config,
-                transports.topic_transport.clone(),
-                Some(transports.writer),
-                rng,
+                &transports,
+                AdkgStdRng::new(OsRng),
             )
             .await
         }
@@ -221,6 +269,57 @@ async fn run_adkg(args: RunAdkg) -> anyhow::Result<()> {
     Ok(())
 }
 
+async fn run_adkg_scheme(
+    adkg_scheme: SupportedAdkgScheme,
+    sk: &PrivateKeyMaterial,
+    adkg_config: AdkgConfig,
+    group_config: &GroupConfig,
+    scheme_config: AdkgCliSchemeConfig,
+    transports: &Libp2pTransports,
+    rng: impl AdkgRng + 'static,
+) -> anyhow::Result<()> {
+    match adkg_scheme {
+        SupportedAdkgScheme::DXKR23Bn254G1Keccak256 => {
+            adkg_dxkr23_bn254_g1_keccak256_out_g2(
+                &sk.adkg_sk,
+                adkg_config,
+                group_config,
+                scheme_config,
+                transports.topic_transport.clone(),
+                Some(transports.writer.clone()),
+                rng,
+            )
+            .await
+        }
+
+        SupportedAdkgScheme::DXKR23Bls12_381G1Sha256 => {
+            adkg_dxkr23_bls12_381_g1_sha256_out_g2(
+                &sk.adkg_sk,
+                adkg_config,
+                group_config,
+                scheme_config,
+                transports.topic_transport.clone(),
+                Some(transports.writer.clone()),
+                rng,
+            )
+            .await
+        }
+
+        SupportedAdkgScheme::DXKR23Secp256k1Sha256 => {
+            adkg_dxkr23_secp256k1_sha256_out_std(
+                &sk.adkg_sk,
+                adkg_config,
+                group_config,
+                scheme_config,
+                transports.topic_transport.clone(),
+                Some(transports.writer.clone()),
+                rng,
+            )
+            .await
+        }
+    }
+}
+
 async fn rescue_adkg(args: Rescue) -> anyhow::Result<()> {
     let Rescue {
         common:
@@ -294,6 +393,10 @@ async fn rescue_adkg(args: Rescue) -> anyhow::Result<()> {
             )
             .await
         }
+
+        scheme @ SupportedAdkgScheme::DXKR23Secp256k1Sha256 => {
+            Err(anyhow!("rescue is not supported by the {scheme} scheme"))
+        }
     };
 
     if let Err(e) = adkg_res {
@@ -303,6 +406,549 @@ async fn rescue_adkg(args: Rescue) -> anyhow::Result<()> {
     Ok(())
 }
 
+async fn refresh_adkg(args: RefreshAdkg) -> anyhow::Result<()> {
+    let RefreshAdkg {
+        common:
+            AdkgRunCommon {
+                scheme,
+                group_file,
+                priv_file,
+                id,
+                priv_out,
+                pub_out,
+            },
+        adkg_priv,
+        adkg_pub,
+        listen_address,
+        start_time,
+        timeout,
+        grace_period,
+    } = args;
+
+    // Parse the previous adkg output before creating the output files
+    let previous_secret = AdkgSecret::from_str(
+        &fs::read_to_string(&adkg_priv).context("failed to read adkg private output")?,
+    )?;
+    let previous_public = AdkgPublic::from_str(
+        &fs::read_to_string(&adkg_pub).context("failed to read adkg public output")?,
+    )?;
+
+    // Reject schemes that cannot be refreshed before creating the output files
+    let adkg_scheme: SupportedAdkgScheme = previous_secret
+        .adkg_scheme_name
+        .parse()
+        .context("adkg scheme not supported")?;
+    if let scheme @ SupportedAdkgScheme::DXKR23Secp256k1Sha256 = adkg_scheme {
+        Err(anyhow!("refresh is not supported by the {scheme} scheme"))?
+    }
+
+    // Parse common inputs
+    let (scheme_config, group_config, sk) =
+        parse_adkg_common(&scheme, &group_file, &priv_file, &priv_out, &pub_out)?;
+
+    if previous_secret.adkg_scheme_name != scheme_config.adkg_scheme_name
+        || previous_public.adkg_scheme_name != scheme_config.adkg_scheme_name
+    {
+        Err(anyhow!(
+            "adkg outputs were not generated with scheme `{}`",
+            scheme_config.adkg_scheme_name
+        ))?
+    }
+    if previous_public.genesis_timestamp != group_config.start_time.timestamp() {
+        Err(anyhow!(
+            "adkg outputs were not generated with the specified group configuration"
+        ))?
+    }
+
+    let adkg_config = AdkgConfig {
+        id: PartyId(id.get()),
+        grace_period,
+        timeout,
+        priv_out,
+        pub_out,
+        transcript_out: None,
+        scheme_name: scheme_config.adkg_scheme_name.clone(),
+    };
+    let rng = AdkgStdRng::new(OsRng);
+
+    // Start libp2p transport
+    let transports =
+        get_libp2p_transports(adkg_config.id, &sk, listen_address, &group_config, None).await?;
+
+    // Calculate time to sleep before actively executing the refresh
+    if let Some(start_time) = start_time {
+        let sleep_duration = (start_time - chrono::Utc::now())
+            .to_std() // TimeDelta to positive duration
+            .unwrap_or_else(|_| Duration::from_secs(0));
+        tracing::info!(
+            "Sleeping for {} before starting refresh at {}",
+            humantime::format_duration(sleep_duration),
+            humantime::format_rfc3339(start_time.into()),
+        );
+        tokio::time::sleep(sleep_duration).await;
+    }
+
+    let refresh_res = match adkg_scheme {
+        SupportedAdkgScheme::DXKR23Bn254G1Keccak256 => {
+            adkg_dxkr23_bn254_g1_keccak256_out_g2_refresh(
+                &sk.adkg_sk,
+                &previous_secret,
+                &previous_public,
+                adkg_config,
+                &group_config,
+                scheme_config,
+                transports.topic_transport.clone(),
+                rng,
+            )
+            .await
+        }
+
+        SupportedAdkgScheme::DXKR23Bls12_381G1Sha256 => {
+            adkg_dxkr23_bls12_381_g1_sha256_out_g2_refresh(
+                &sk.adkg_sk,
+                &previous_secret,
+                &previous_public,
+                adkg_config,
+                &group_config,
+                scheme_config,
+                transports.topic_transport.clone(),
+                rng,
+            )
+            .await
+        }
+
+        SupportedAdkgScheme::DXKR23Secp256k1Sha256 => {
+            unreachable!("unsupported schemes are rejected before starting the transport")
+        }
+    };
+
+    if let Err(e) = refresh_res {
+        tracing::error!(error = ?e, "ADKG refresh returned an error");
+    }
+
+    tracing::info!("Stopping libp2p dispatcher...");
+    transports.topic_dispatcher.stop().await;
+
+    tracing::info!("Stopping libp2p transport...");
+    if let Err(e) = transports.node.stop().await {
+        tracing::error!(error = ?e, "Failed to stop libp2p node");
+    }
+
+    Ok(())
+}
+
+/// Various params used by the resharing
+#[derive(Clone, Debug)]
+struct ReshareConfig {
+    old_id: Option<PartyId>,
+    new_id: Option<PartyId>,
+    /// Transport identifiers of the members of the new group, ordered by identifier
+    transport_ids: Vec<PartyId>,
+    grace_period: Duration,
+    timeout: Duration,
+    priv_out: Option<PathBuf>,
+    pub_out: Option<PathBuf>,
+    scheme_name: String,
+}
+
+async fn reshare_adkg(args: ReshareAdkg) -> anyhow::Result<()> {
+    let ReshareAdkg {
+        scheme,
+        old_group_file,
+        new_group_file,
+        priv_file,
+        old_id,
+        new_id,
+        adkg_priv,
+        adkg_pub,
+        priv_out,
+        pub_out,
+        listen_address,
+        start_time,
+        timeout,
+        grace_period,
+    } = args;
+
+    // Deserialize the configs
+    let scheme_config: AdkgCliSchemeConfig =
+        toml::from_str(&fs::read_to_string(&scheme).context("failed to read scheme file")?)
+            .context("failed to parse scheme config")?;
+    let old_group_config = GroupConfig::from_str(
+        &fs::read_to_string(&old_group_file).context("failed to read old group file")?,
+    )
+    .context("failed to parse old group config")?;
+    let new_group_config = GroupConfig::from_str(
+        &fs::read_to_string(&new_group_file).context("failed to read new group file")?,
+    )
+    .context("failed to parse new group config")?;
+    let sk: PrivateKeyMaterial = toml::from_str(
+        &fs::read_to_string(&priv_file).context("failed to read private key material")?,
+    )
+    .context("failed to parse private key material")?;
+
+    // Parse the previous adkg output
+    let previous_public = AdkgPublic::from_str(
+        &fs::read_to_string(&adkg_pub).context("failed to read adkg public output")?,
+    )?;
+    if previous_public.adkg_scheme_name != scheme_config.adkg_scheme_name {
+        Err(anyhow!(
+            "adkg outputs were not generated with scheme `{}`",
+            scheme_config.adkg_scheme_name
+        ))?
+    }
+    if previous_public.genesis_timestamp != old_group_config.start_time.timestamp() {
+        Err(anyhow!(
+            "adkg outputs were not generated with the specified old group configuration"
+        ))?
+    }
+
+    // Members of the old group deal their previous share
+    let previous_secret = match (old_id, adkg_priv) {
+        (Some(_), Some(adkg_priv)) => {
+            let previous_secret = AdkgSecret::from_str(
+                &fs::read_to_string(&adkg_priv).context("failed to read adkg private output")?,
+            )?;
+            if previous_secret.adkg_scheme_name != scheme_config.adkg_scheme_name {
+                Err(anyhow!(
+                    "adkg outputs were not generated with scheme `{}`",
+                    scheme_config.adkg_scheme_name
+                ))?
+            }
+            Some(previous_secret)
+        }
+        (Some(_), None) => Err(anyhow!(
+            "adkg_priv must be specified by members of the old group"
+        ))?,
+        (None, _) => None,
+    };
+
+    // Members of the new group write the new outputs
+    match (new_id, &priv_out, &pub_out) {
+        (Some(_), Some(priv_out), Some(pub_out)) => prepare_output_files(priv_out, pub_out)?,
+        (Some(_), _, _) => Err(anyhow!(
+            "priv_out and pub_out must be specified by members of the new group"
+        ))?,
+        (None, _, _) => (),
+    }
+
+    let (union_group_config, transport_ids) =
+        reshare_union_group(&old_group_config, &new_group_config)?;
+
+    // Make sure that the identifiers refer to the same node, and get our transport identifier
+    let old_node = old_id
+        .map(|id| {
+            old_group_config
+                .nodes
+                .get(id.get() - 1)
+                .ok_or(anyhow!("node {id} not in old group"))
+        })
+        .transpose()?;
+    let new_node = new_id
+        .map(|id| {
+            new_group_config
+                .nodes
+                .get(id.get() - 1)
+                .ok_or(anyhow!("node {id} not in new group"))
+        })
+        .transpose()?;
+    let id = match (old_node, new_node) {
+        (Some(old_node), Some(new_node))
+            if old_node.public_key_material.peer_id != new_node.public_key_material.peer_id =>
+        {
+            Err(anyhow!("old_id and new_id do not refer to the same node"))?
+        }
+        (Some(old_node), _) => PartyId(old_node.id.get()),
+        (None, Some(new_node)) => transport_ids[new_node.id.get() - 1],
+        (None, None) => Err(anyhow!(
+            "node must be a member of the old group and / or the new group"
+        ))?,
+    };
+
+    let reshare_config = ReshareConfig {
+        old_id: old_id.map(|id| PartyId(id.get())),
+        new_id: new_id.map(|id| PartyId(id.get())),
+        transport_ids,
+        grace_period,
+        timeout,
+        priv_out,
+        pub_out,
+        scheme_name: scheme_config.adkg_scheme_name.clone(),
+    };
+    let adkg_scheme: SupportedAdkgScheme = scheme_config
+        .adkg_scheme_name
+        .parse()
+        .context("adkg scheme not supported")?;
+    let rng = AdkgStdRng::new(OsRng);
+
+    // Start libp2p transport with the nodes of both groups
+    let transports =
+        get_libp2p_transports(id, &sk, listen_address, &union_group_config, None).await?;
+
+    // Calculate time to sleep before actively executing the resharing
+    if let Some(start_time) = start_time {
+        let sleep_duration = (start_time - chrono::Utc::now())
+            .to_std() // TimeDelta to positive duration
+            .unwrap_or_else(|_| Duration::from_secs(0));
+        tracing::info!(
+            "Sleeping for {} before starting resharing at {}",
+            humantime::format_duration(sleep_duration),
+            humantime::format_rfc3339(start_time.into()),
+        );
+        tokio::time::sleep(sleep_duration).await;
+    }
+
+    let reshare_res = match adkg_scheme {
+        SupportedAdkgScheme::DXKR23Bn254G1Keccak256 => {
+            adkg_dxkr23_bn254_g1_keccak256_out_g2_reshare(
+                &sk.adkg_sk,
+                previous_secret.as_ref(),
+                &previous_public,
+                reshare_config,
+                &old_group_config,
+                &new_group_config,
+                scheme_config,
+                transports.topic_transport.clone(),
+                rng,
+            )
+            .await
+        }
+
+        SupportedAdkgScheme::DXKR23Bls12_381G1Sha256 => {
+            adkg_dxkr23_bls12_381_g1_sha256_out_g2_reshare(
+                &sk.adkg_sk,
+                previous_secret.as_ref(),
+                &previous_public,
+                reshare_config,
+                &old_group_config,
+                &new_group_config,
+                scheme_config,
+                transports.topic_transport.clone(),
+                rng,
+            )
+            .await
+        }
+
+        SupportedAdkgScheme::DXKR23Secp256k1Sha256 => {
+            adkg_dxkr23_secp256k1_sha256_out_std_reshare(
+                &sk.adkg_sk,
+                previous_secret.as_ref(),
+                &previous_public,
+                reshare_config,
+                &old_group_config,
+                &new_group_config,
+                scheme_config,
+                transports.topic_transport.clone(),
+                rng,
+            )
+            .await
+        }
+    };
+
+    if let Err(e) = reshare_res {
+        tracing::error!(error = ?e, "ADKG resharing returned an error");
+    }
+
+    tracing::info!("Stopping libp2p dispatcher...");
+    transports.topic_dispatcher.stop().await;
+
+    tracing::info!("Stopping libp2p transport...");
+    if let Err(e) = transports.node.stop().await {
+        tracing::error!(error = ?e, "Failed to stop libp2p node");
+    }
+
+    Ok(())
+}
+
+fn verify_adkg(args: VerifyAdkg) -> anyhow::Result<()> {
+    let VerifyAdkg {
+        scheme,
+        group_file,
+        pub_files,
+        priv_file,
+        id,
+        transcript_files,
+        report_out,
+    } = args;
+
+    // Deserialize the configs
+    let scheme_config: AdkgCliSchemeConfig =
+        toml::from_str(&fs::read_to_string(scheme).context("failed to read scheme file")?)
+            .context("failed to parse scheme config")?;
+    let group_config = GroupConfig::from_str(
+        &fs::read_to_string(group_file).context("failed to read group file")?,
+    )
+    .context("failed to parse group config")?;
+
+    // Deserialize the public outputs
+    let publics = pub_files
+        .into_iter()
+        .map(|pub_file| -> anyhow::Result<_> {
+            let public: AdkgPublic =
+                toml::from_str(&fs::read_to_string(&pub_file).with_context(|| {
+                    format!("failed to read public output `{}`", pub_file.display())
+                })?)
+                .with_context(|| {
+                    format!("failed to parse public output `{}`", pub_file.display())
+                })?;
+            Ok((pub_file, public))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Private key material and transcripts are only required to verify the transcripts
+    let sk = priv_file
+        .map(|priv_file| -> anyhow::Result<PrivateKeyMaterial> {
+            toml::from_str(
+                &fs::read_to_string(priv_file).context("failed to read private key material")?,
+            )
+            .context("failed to parse private key material")
+        })
+        .transpose()?;
+    let decryption_key = sk
+        .as_ref()
+        .zip(id)
+        .map(|(sk, id)| (PartyId(id.get()), sk.adkg_sk.as_str()));
+    let transcripts = transcript_files
+        .into_iter()
+        .map(|transcript_file| {
+            let transcript = fs::read(&transcript_file).with_context(|| {
+                format!("failed to read transcript `{}`", transcript_file.display())
+            })?;
+            Ok((transcript_file, transcript))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let mut report = VerificationReport::new(scheme_config.adkg_scheme_name.clone());
+    let genesis_timestamp = group_config.start_time.timestamp();
+    for (pub_file, public) in &publics {
+        let res = if public.adkg_scheme_name == scheme_config.adkg_scheme_name {
+            Ok(())
+        } else {
+            Err(anyhow!("unexpected scheme `{}`", public.adkg_scheme_name))
+        };
+        report.record("scheme", pub_file, None, res);
+
+        let res = if public.genesis_timestamp == genesis_timestamp {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "genesis timestamp {} does not match the group start time {genesis_timestamp}",
+                public.genesis_timestamp
+            ))
+        };
+        report.record("genesis_timestamp", pub_file, None, res);
+    }
+
+    // All the nodes must have obtained the same public output
+    if let Some(((_, expected), others)) = publics.split_first() {
+        let pks = |public: &AdkgPublic| {
+            let node_pks =
+                |pks: &[AdkgNodePk]| pks.iter().map(|pk| pk.pk.clone()).collect::<Vec<_>>();
+            (
+                public.group_pk.clone(),
+                node_pks(&public.node_pks),
+                public.group_pk_source.clone(),
+                node_pks(&public.node_pks_source),
+            )
+        };
+
+        for (pub_file, public) in others {
+            let res = if pks(public) == pks(expected) {
+                Ok(())
+            } else {
+                Err(anyhow!("public keys differ from the first public output"))
+            };
+            report.record("same_public_output", pub_file, None, res);
+        }
+    }
+
+    let adkg_scheme: SupportedAdkgScheme = scheme_config
+        .adkg_scheme_name
+        .parse()
+        .context("adkg scheme not supported")?;
+    match adkg_scheme {
+        SupportedAdkgScheme::DXKR23Bn254G1Keccak256 => {
+            adkg_dxkr23_bn254_g1_keccak256_out_g2_verify(
+                &group_config,
+                scheme_config,
+                &publics,
+                decryption_key,
+                transcripts,
+                &mut report,
+            )?
+        }
+
+        SupportedAdkgScheme::DXKR23Bls12_381G1Sha256 => {
+            adkg_dxkr23_bls12_381_g1_sha256_out_g2_verify(
+                &group_config,
+                scheme_config,
+                &publics,
+                decryption_key,
+                transcripts,
+                &mut report,
+            )?
+        }
+
+        scheme @ SupportedAdkgScheme::DXKR23Secp256k1Sha256 => {
+            Err(anyhow!("verify is not supported by the {scheme} scheme"))?
+        }
+    }
+
+    // Output the report as json
+    let report_json =
+        serde_json::to_string_pretty(&report).context("failed to serialize verification report")?;
+    match report_out {
+        Some(report_out) => {
+            fs::write(report_out, report_json).context("failed to write verification report")?
+        }
+        None => println!("{report_json}"),
+    }
+
+    if !report.valid {
+        Err(anyhow!("ADKG ceremony verification failed"))?
+    }
+
+    Ok(())
+}
+
+/// Build the group containing the nodes of both the old and the new group, used to create the
+/// transport of the resharing. Members of the old group keep their identifier, while the nodes
+/// that only belong to the new group are assigned the identifiers n + 1, n + 2, ...
+///
+/// Returns the union group, and the transport identifiers of the members of the new group.
+fn reshare_union_group(
+    old_group_config: &GroupConfig,
+    new_group_config: &GroupConfig,
+) -> anyhow::Result<(GroupConfig, Vec<PartyId>)> {
+    let mut nodes = old_group_config.nodes.clone();
+    let mut transport_ids = Vec::with_capacity(new_group_config.n.get());
+    for node in &new_group_config.nodes {
+        let existing = old_group_config
+            .nodes
+            .iter()
+            .find(|n| n.public_key_material.peer_id == node.public_key_material.peer_id);
+
+        let id = match existing {
+            Some(existing) => existing.id,
+            None => {
+                let id = NonZeroUsize::new(nodes.len() + 1).expect("len + 1 > 0");
+                nodes.push(NodeDetail { id, ..node.clone() });
+                id
+            }
+        };
+        transport_ids.push(PartyId(id.get()));
+    }
+
+    let union_group_config = GroupConfig {
+        n: NonZeroUsize::new(nodes.len()).ok_or(anyhow!("empty groups"))?,
+        t: new_group_config.t,
+        t_reconstruction: new_group_config.t_reconstruction,
+        start_time: new_group_config.start_time,
+        nodes,
+    };
+
+    Ok((union_group_config, transport_ids))
+}
+
 fn parse_adkg_common(
     scheme: &PathBuf,
     group_file: &PathBuf,
@@ -325,6 +971,13 @@ fn parse_adkg_common(
     )
     .context("failed to parse private key material")?;
 
+    prepare_output_files(priv_out, pub_out)?;
+
+    Ok((scheme_config, group_config, sk))
+}
+
+/// Make sure that the output files do not exist, and that they are writable.
+fn prepare_output_files(priv_out: &PathBuf, pub_out: &PathBuf) -> anyhow::Result<()> {
     // Make sure priv_out / pub_out do not exist
     if priv_out.exists() {
         Err(anyhow!(
@@ -341,7 +994,7 @@ fn parse_adkg_common(
     fs::write(priv_out, "").context("failed to write private key file")?;
     fs::write(pub_out, "").context("failed to write public key file")?;
 
-    Ok((scheme_config, group_config, sk))
+    Ok(())
 }
 
 #[cfg(feature = "metrics")]
@@ -482,7 +1135,10 @@ where
 }
 
 type TopicTransport = TopicBasedTransportImpl<
-    TransportWriterSender<writer::InMemoryWriter<PartyId, Vec<u8>>, Libp2pSender<PartyId>>,
+    TransportWriterSender<
+        JournalWriter,
+        TransportWriterSender<writer::InMemoryWriter<PartyId, Vec<u8>>, Libp2pSender<PartyId>>,
+    >,
 >;
 
 type InMemoryWriter = writer::InMemoryWriter<PartyId, Vec<u8>>;
@@ -499,6 +1155,7 @@ async fn get_libp2p_transports(
     sk: &PrivateKeyMaterial,
     listen_addr: Multiaddr,
     group_config: &GroupConfig,
+    journal: Option<(AdkgJournal, JournalEntries)>,
 ) -> anyhow::Result<Libp2pTransports> {
     // Make sure that the identifiers are unique
     let (peer_addrs, peer_ids, short_ids): (Vec<_>, Vec<_>, Vec<_>) = group_config
@@ -533,12 +1190,25 @@ async fn get_libp2p_transports(
     // is not dyn-compatible.
     let transport_writer = TransportWriter::new_in_memory(transport);
     let writer = transport_writer.writer().to_owned();
-    let mut topic_dispatcher = TopicDispatcher::new();
-    let topic_transport = topic_dispatcher.start(transport_writer).into();
+
+    // Similarly, always wrap the transport with a journal, which is simply disabled if not used.
+    let mut transport = match journal {
+        Some((journal, entries)) => JournalTransport::new(journal, entries, transport_writer),
+        None => JournalTransport::disabled(transport_writer),
+    };
 
     tracing::info!("Waiting a few seconds for networking to settle...");
     tokio::time::sleep(Duration::from_secs(2)).await;
 
+    // Re-deliver the messages sent before a restart, if any
+    let redelivered = transport.redeliver().await;
+    if redelivered > 0 {
+        tracing::info!("Re-delivered {redelivered} messages from the ADKG journal");
+    }
+
+    let mut topic_dispatcher = TopicDispatcher::new();
+    let topic_transport = topic_dispatcher.start(transport).into();
+
     Ok(Libp2pTransports {
         node,
         topic_transport,
@@ -1,6 +1,7 @@
 use adkg::scheme::AdkgSchemeConfig;
 use adkg::scheme::bls12_381::DXKR23Bls12_381G1Sha256;
 use adkg::scheme::bn254::DXKR23Bn254G1Keccak256;
+use adkg::scheme::secp256k1::DXKR23Secp256k1Sha256;
 use ark_ec::PrimeGroup;
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
@@ -37,6 +38,15 @@ pub fn new_scheme_config(
                     .ser_compressed_base64()?,
             })
         }
+        SupportedAdkgScheme::DXKR23Secp256k1Sha256 => {
+            let scheme_config = DXKR23Secp256k1Sha256::new(app_name).into();
+            Ok(AdkgCliSchemeConfig {
+                adkg_config: scheme_config,
+                adkg_scheme_name: scheme_id.to_string(),
+                // Output keys w.r.t. the standard generator used by ECDSA / BIP-340
+                output_generator: ark_secp256k1::Projective::generator().ser_compressed_base64()?,
+            })
+        }
     }
 }
 
@@ -44,6 +54,7 @@ pub fn new_scheme_config(
 pub enum SupportedAdkgScheme {
     DXKR23Bn254G1Keccak256,
     DXKR23Bls12_381G1Sha256,
+    DXKR23Secp256k1Sha256,
 }
 
 impl Display for SupportedAdkgScheme {
@@ -54,6 +65,8 @@ impl Display for SupportedAdkgScheme {
             SupportedAdkgScheme::DXKR23Bls12_381G1Sha256 => {
                 f.write_str("DXKR23-Bls12_381G1-Sha256")
             }
+
+            SupportedAdkgScheme::DXKR23Secp256k1Sha256 => f.write_str("DXKR23-Secp256k1-Sha256"),
         }
     }
 }
@@ -69,6 +82,7 @@ impl FromStr for SupportedAdkgScheme {
         match s {
             "DXKR23-Bn254G1-Keccak256" => Ok(Self::DXKR23Bn254G1Keccak256),
             "DXKR23-Bls12_381G1-Sha256" => Ok(Self::DXKR23Bls12_381G1Sha256),
+            "DXKR23-Secp256k1-Sha256" => Ok(Self::DXKR23Secp256k1Sha256),
             _ => Err(UnsupportedAdkgScheme),
         }
     }
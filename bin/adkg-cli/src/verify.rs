@@ -0,0 +1,175 @@
+//! Verification of the public outputs and transcripts of a completed ADKG ceremony.
+
+use adkg::helpers::{PartyId, lagrange_points_interpolate_at, u64_from_usize};
+use anyhow::anyhow;
+use ark_ec::CurveGroup;
+use config::adkg::{AdkgNodePk, GroupConfig};
+use serde::Serialize;
+use std::path::Path;
+use utils::serialize::point::PointDeserializeCompressed;
+
+/// Machine-readable report listing the checks executed on a ceremony.
+#[derive(Clone, Debug, Serialize)]
+pub struct VerificationReport {
+    pub adkg_scheme_name: String,
+
+    /// Whether all the checks passed
+    pub valid: bool,
+
+    /// The result of each check
+    pub checks: Vec<CheckResult>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct CheckResult {
+    /// Name of the check
+    pub check: &'static str,
+
+    /// File that was checked
+    pub file: String,
+
+    /// Node targeted by the check, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node: Option<PartyId>,
+
+    pub passed: bool,
+
+    /// Reason for the failure of the check
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl VerificationReport {
+    pub fn new(adkg_scheme_name: String) -> Self {
+        Self {
+            adkg_scheme_name,
+            valid: true,
+            checks: vec![],
+        }
+    }
+
+    /// Record the result of a check in the report.
+    pub fn record(
+        &mut self,
+        check: &'static str,
+        file: &Path,
+        node: Option<PartyId>,
+        res: anyhow::Result<()>,
+    ) {
+        self.valid &= res.is_ok();
+        self.checks.push(CheckResult {
+            check,
+            file: file.display().to_string(),
+            node,
+            passed: res.is_ok(),
+            error: res.err().map(|e| format!("{e:#}")),
+        });
+    }
+}
+
+/// Deserialize the node public keys of an ADKG public output, and make sure that they are listed in
+/// the same order as the nodes of the group.
+pub fn deser_node_pks<CG>(
+    node_pks: &[AdkgNodePk],
+    group_config: &GroupConfig,
+) -> anyhow::Result<Vec<CG>>
+where
+    CG: PointDeserializeCompressed,
+{
+    if node_pks.len() != group_config.n.get() {
+        Err(anyhow!(
+            "expected {} node public keys, got {}",
+            group_config.n.get(),
+            node_pks.len()
+        ))?;
+    }
+
+    node_pks
+        .iter()
+        .zip(group_config.nodes.iter())
+        .map(|(node_pk, node)| {
+            if node_pk.id != node.id || node_pk.peer_id != node.public_key_material.peer_id {
+                Err(anyhow!(
+                    "node public key with id {} does not match the group configuration",
+                    node_pk.id
+                ))?;
+            }
+
+            Ok(CG::deser_compressed_base64(&node_pk.pk)?)
+        })
+        .collect()
+}
+
+/// Check that the group public key and the node public keys all lie on a single polynomial of degree
+/// `t_reconstruction`, i.e., that the group public key is obtained by interpolating any
+/// `t_reconstruction + 1` node public keys.
+pub fn check_public_keys<CG>(
+    group_pk: &CG,
+    node_pks: &[CG],
+    t_reconstruction: usize,
+) -> anyhow::Result<()>
+where
+    CG: CurveGroup,
+{
+    #[allow(clippy::int_plus_one)]
+    if node_pks.len() < t_reconstruction + 1 {
+        Err(anyhow!(
+            "not enough node public keys to interpolate a polynomial of degree {t_reconstruction}"
+        ))?;
+    }
+
+    // Interpolate the polynomial with the first t_reconstruction + 1 keys
+    let points: Vec<_> = PartyId::iter_all(node_pks.len())
+        .zip(node_pks)
+        .take(t_reconstruction + 1)
+        .map(|(j, pk_j)| (u64::from(j), *pk_j))
+        .collect();
+
+    if lagrange_points_interpolate_at(&points, 0) != *group_pk {
+        Err(anyhow!(
+            "group public key does not match the interpolation of the node public keys"
+        ))?;
+    }
+
+    // The remaining keys must be evaluations of the same polynomial
+    for (j, pk_j) in node_pks.iter().enumerate().skip(t_reconstruction + 1) {
+        let j_node_idx = j + 1;
+        if lagrange_points_interpolate_at(&points, u64_from_usize(j_node_idx)) != *pk_j {
+            Err(anyhow!(
+                "node public key of node {j_node_idx} is not consistent with the other node public keys"
+            ))?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adkg::helpers::eval_poly;
+    use ark_ec::PrimeGroup;
+    use ark_std::UniformRand;
+    use rand::thread_rng;
+
+    #[test]
+    fn public_keys_consistency() {
+        let n = 7;
+        let t_reconstruction = 4;
+        let g = ark_bn254::G2Projective::generator();
+        let poly: Vec<_> = (0..=t_reconstruction)
+            .map(|_| ark_bn254::Fr::rand(&mut thread_rng()))
+            .collect();
+        let group_pk = g * poly[0];
+        let mut node_pks: Vec<_> = PartyId::iter_all(n)
+            .map(|i| g * eval_poly(&u64::from(i).into(), &poly))
+            .collect();
+
+        check_public_keys(&group_pk, &node_pks, t_reconstruction).unwrap();
+        assert!(check_public_keys(&(group_pk + g), &node_pks, t_reconstruction).is_err());
+
+        // Corrupt the key of the last node
+        node_pks[n - 1] += g;
+        assert!(check_public_keys(&group_pk, &node_pks, t_reconstruction).is_err());
+    }
+}
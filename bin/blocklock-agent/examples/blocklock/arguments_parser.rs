@@ -26,9 +26,15 @@ pub struct BlocklockArgs {
     #[arg(long, env = "BLOCKLOCK_HEALTHCHECK_PORT", default_value = "8080")]
     pub healthcheck_port: u16,
 
-    /// The path to a committee config file
-    #[arg(long, env = "BLOCKLOCK_COMMITTEE_CONFIG")]
-    pub committee_config: PathBuf,
+    /// The path to a committee config file, required unless the requests are signed by a remote
+    /// dsigner
+    #[arg(
+        long,
+        env = "BLOCKLOCK_COMMITTEE_CONFIG",
+        required_unless_present = "dsigner_url",
+        conflicts_with = "dsigner_url"
+    )]
+    pub committee_config: Option<PathBuf>,
 
     #[command(flatten)]
     pub chain: BlockchainArgs,
@@ -36,6 +42,9 @@ pub struct BlocklockArgs {
     #[command(flatten)]
     pub libp2p: Libp2pArgs,
 
+    #[command(flatten)]
+    pub dsigner: DSignerArgs,
+
     /// Location of the saved state of the blocklock agent
     #[arg(
         long,
@@ -146,9 +155,13 @@ pub struct BlockchainArgs {
 #[derive(Parser, Serialize, Deserialize, Debug)]
 #[command(author, version, about, long_about = None)]
 pub struct Libp2pArgs {
-    /// Libp2p private key
-    #[arg(long, env = "BLOCKLOCK_LIBP2P_KEY")]
-    pub libp2p_key: Libp2pKeyWrapper,
+    /// Libp2p private key, required unless the requests are signed by a remote dsigner
+    #[arg(
+        long,
+        env = "BLOCKLOCK_LIBP2P_KEY",
+        required_unless_present = "dsigner_url"
+    )]
+    pub libp2p_key: Option<Libp2pKeyWrapper>,
 
     /// Libp2p listen address
     #[arg(
@@ -159,9 +172,30 @@ pub struct Libp2pArgs {
     pub libp2p_listen_addr: ::libp2p::Multiaddr,
 }
 
+#[derive(Parser, Serialize, Deserialize, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct DSignerArgs {
+    /// Endpoint of a dsigner service used to sign the requests, instead of holding a key share
+    #[arg(long, env = "BLOCKLOCK_DSIGNER_URL", requires = "dsigner_scheme_id")]
+    pub dsigner_url: Option<String>,
+
+    /// Identifier of the scheme used to sign the requests on the dsigner service
+    #[arg(long, env = "BLOCKLOCK_DSIGNER_SCHEME_ID")]
+    pub dsigner_scheme_id: Option<String>,
+
+    /// API key used to authenticate with the dsigner service
+    #[arg(long, env = "BLOCKLOCK_DSIGNER_API_KEY")]
+    pub dsigner_api_key: Option<String>,
+
+    /// Maximum time in seconds to wait for a signature from the dsigner service
+    #[arg(long, env = "BLOCKLOCK_DSIGNER_TIMEOUT", default_value = "30")]
+    pub dsigner_timeout_secs: u64,
+}
+
 pub struct BlocklockConfig {
     pub config: BlocklockArgs,
-    pub committee_config: CommitteeConfig<ark_bn254::G2Affine>,
+    /// The committee config, or `None` if the requests are signed by a remote dsigner
+    pub committee_config: Option<CommitteeConfig<ark_bn254::G2Affine>>,
 }
 
 impl BlocklockConfig {
@@ -171,10 +205,21 @@ impl BlocklockConfig {
             .merge(Toml::file("config.toml"))
             .extract()?;
 
-        let committee_config = std::fs::read_to_string(&c.committee_config)
-            .context("failed to read committee config")?;
-        let committee_config =
-            toml::from_str(&committee_config).context("failed to parse committee config")?;
+        let committee_config = match (&c.committee_config, &c.dsigner.dsigner_url) {
+            (Some(committee_config), None) => {
+                let committee_config = std::fs::read_to_string(committee_config)
+                    .context("failed to read committee config")?;
+                Some(
+                    toml::from_str(&committee_config)
+                        .context("failed to parse committee config")?,
+                )
+            }
+            (None, Some(_)) => None,
+            (Some(_), Some(_)) => {
+                anyhow::bail!("committee config cannot be used along with a dsigner url")
+            }
+            (None, None) => anyhow::bail!("either a committee config or a dsigner url is required"),
+        };
         Ok(Self {
             config: c,
             committee_config,
@@ -6,6 +6,7 @@ use crate::healthcheck::start_api;
 use alloy::network::EthereumWallet;
 use alloy::providers::{Provider, ProviderBuilder, WalletProvider};
 use alloy::signers::local::PrivateKeySigner;
+use anyhow::Context;
 use ark_ec::{AffineRepr, CurveGroup};
 use blocklock_agent::{BN254_BLOCKLOCK_SCHEME_ID, NotifyTicker, run_agent};
 use config::signing::CommitteeConfig;
@@ -14,11 +15,12 @@ use dcipher_agents::agents::blocklock::fulfiller::BlocklockFulfiller;
 use dcipher_agents::decryption_sender::{DecryptionRequest, DecryptionSenderFulfillerConfig};
 use dcipher_agents::fulfiller::{RequestChannel, Stopper, TickerBasedFulfiller};
 use dcipher_agents::ibe_helper::IbeIdentityOnBn254G1Suite;
+use dcipher_agents::remote_signer::RemoteDSigner;
 use dcipher_network::transports::libp2p::{Libp2pNode, Libp2pNodeConfig};
-use dcipher_signer::bls::{BlsPairingSigner, BlsThresholdSigner};
+use dcipher_signer::bls::{AsyncThresholdSigner, BlsPairingSigner, BlsThresholdSigner};
 use dcipher_signer::dsigner::{
     ApplicationArgs, ApplicationBlocklockArgs, BlsSignatureAlgorithm, BlsSignatureCurve,
-    BlsSignatureHash, SignatureAlgorithm,
+    BlsSignatureHash, DSignerSchemeSigner, SignatureAlgorithm,
 };
 use generated::blocklock::blocklock_sender::BlocklockSender;
 use generated::blocklock::decryption_sender::DecryptionSender;
@@ -71,13 +73,24 @@ async fn main() -> anyhow::Result<()> {
         config.chain.chain_id.replace(chain_id);
     }
 
+    // Sign with a threshold signer if the node holds a key share, otherwise with a remote dsigner
+    let (signer, threshold_signer): (Box<dyn DSignerSchemeSigner + Send + Sync>, _) =
+        match &committee_config {
+            Some(committee_config) => {
+                let (libp2p_node, ts_stopper, signer) =
+                    create_threshold_signer(&config, committee_config)?;
+                (Box::new(signer), Some((libp2p_node, ts_stopper)))
+            }
+            None => (Box::new(create_remote_signer(&config)?), None),
+        };
+
     // Create a fulfiller
-    let (ticker, libp2p_node, ts_stopper, stopper, channel) = create_threshold_fulfiller(
+    let (ticker, stopper, channel) = create_fulfiller(
         &config,
-        &committee_config,
+        signer,
         decryption_sender_contract.clone(),
         blocklock_sender_contract,
-    )?;
+    );
 
     // Create the blocklock agent from a saved state
     let saved_state = std::fs::read(&config.state_file).unwrap_or_default();
@@ -131,10 +144,12 @@ async fn main() -> anyhow::Result<()> {
     };
 
     // Stop the various components
-    if let Err(e) = libp2p_node.stop().await {
-        tracing::error!(error = ?e, "Failed to stop libp2p node");
+    if let Some((mut libp2p_node, ts_stopper)) = threshold_signer {
+        if let Err(e) = libp2p_node.stop().await {
+            tracing::error!(error = ?e, "Failed to stop libp2p node");
+        }
+        ts_stopper.cancel();
     }
-    ts_stopper.cancel();
     stopper.stop().await;
 
     // On success, save the state of the agent
@@ -151,21 +166,20 @@ async fn main() -> anyhow::Result<()> {
     res
 }
 
-fn create_threshold_fulfiller<'lt_in, 'lt_out, P>(
-    args: &'lt_in BlocklockArgs,
-    committee_config: &'lt_in CommitteeConfig<ark_bn254::G2Affine>,
-    decryption_sender_contract: DecryptionSender::DecryptionSenderInstance<P>,
-    blocklock_sender_contract: BlocklockSender::BlocklockSenderInstance<P>,
+fn create_threshold_signer(
+    args: &BlocklockArgs,
+    committee_config: &CommitteeConfig<ark_bn254::G2Affine>,
 ) -> anyhow::Result<(
-    NotifyTicker,
     Libp2pNode<u16>,
     CancellationToken,
-    impl Stopper + 'lt_out,
-    impl RequestChannel<Request = DecryptionRequest> + 'lt_out,
-)>
-where
-    P: Provider + WalletProvider + Clone + 'static,
-{
+    AsyncThresholdSigner<BlsPairingSigner<ark_bn254::Bn254>>,
+)> {
+    let libp2p_key = args
+        .libp2p
+        .libp2p_key
+        .clone()
+        .context("a libp2p key is required to run a threshold signer")?;
+
     // Parse key
     let sk: ark_bn254::Fr = committee_config.secret_key.to_owned().0;
 
@@ -191,17 +205,9 @@ where
         pks_g2.push((committee_config.member_id.get(), pk.into_affine()));
     }
 
-    // Create a threshold signer
-    let cs = IbeIdentityOnBn254G1Suite::new(
-        b"BLOCKLOCK",
-        args.chain
-            .chain_id
-            .expect("chain id must have been set here"),
-    );
-
     // Create a libp2p transport and start it
     let mut node = Libp2pNodeConfig::new(
-        args.libp2p.libp2p_key.clone().into(),
+        libp2p_key.into(),
         committee_config.member_id.get(),
         addresses,
         peer_ids,
@@ -209,6 +215,7 @@ where
     )
     .run(args.libp2p.libp2p_listen_addr.clone())?;
 
+    // Create a threshold signer
     let signer = BlsPairingSigner::<ark_bn254::Bn254>::new(sk);
     let signer = BlsThresholdSigner::new(
         signer,
@@ -223,6 +230,47 @@ where
             .expect("newly created node should have a transport"),
     );
 
+    Ok((node, ts_stopper, signer))
+}
+
+fn create_remote_signer(args: &BlocklockArgs) -> anyhow::Result<RemoteDSigner> {
+    let (Some(dsigner_url), Some(scheme_id)) =
+        (&args.dsigner.dsigner_url, &args.dsigner.dsigner_scheme_id)
+    else {
+        anyhow::bail!("a dsigner url and scheme id are required to use a remote dsigner")
+    };
+
+    let signer = RemoteDSigner::from_endpoint(
+        dsigner_url,
+        scheme_id,
+        args.dsigner.dsigner_api_key.as_deref(),
+    )
+    .context("failed to create remote dsigner")?
+    .with_timeout(Duration::from_secs(args.dsigner.dsigner_timeout_secs));
+    Ok(signer)
+}
+
+fn create_fulfiller<'lt_in, 'lt_out, P>(
+    args: &'lt_in BlocklockArgs,
+    signer: Box<dyn DSignerSchemeSigner + Send + Sync>,
+    decryption_sender_contract: DecryptionSender::DecryptionSenderInstance<P>,
+    blocklock_sender_contract: BlocklockSender::BlocklockSenderInstance<P>,
+) -> (
+    NotifyTicker,
+    impl Stopper + 'lt_out,
+    impl RequestChannel<Request = DecryptionRequest> + 'lt_out,
+)
+where
+    P: Provider + WalletProvider + Clone + 'static,
+{
+    // Identity-based encryption suite used by blocklock
+    let cs = IbeIdentityOnBn254G1Suite::new(
+        b"BLOCKLOCK",
+        args.chain
+            .chain_id
+            .expect("chain id must have been set here"),
+    );
+
     // Create a transaction fulfiller
     let mut blocklock_tx_fulfiller = BlocklockFulfiller::new(
         decryption_sender_contract,
@@ -261,5 +309,5 @@ where
 
     let ticker = NotifyTicker::default();
     let (stopper, channel) = fulfiller.run(ticker.clone());
-    Ok((ticker, node, ts_stopper, stopper, channel))
+    (ticker, stopper, channel)
 }
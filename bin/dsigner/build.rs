@@ -4,8 +4,14 @@ fn main() -> std::io::Result<()> {
         .client_mod_attribute("attrs", "#[cfg(feature = \"client\")]")
         .bytes(["."])
         .compile_protos(
-            &["../../modules/dcipher-proto/dsigner/dsigner.proto"],
-            &["../../modules/dcipher-proto/dsigner/"],
+            &[
+                "../../modules/dcipher-proto/dsigner/dsigner.proto",
+                // Batch & streaming RPCs, until they are part of dcipher-proto
+                "proto/dsigner_batch.proto",
+                // FROST algorithms, until they are part of dcipher-proto
+                "proto/dsigner_frost.proto",
+            ],
+            &["../../modules/dcipher-proto/dsigner/", "proto/"],
         )?;
     Ok(())
 }
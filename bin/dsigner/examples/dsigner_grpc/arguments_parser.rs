@@ -2,6 +2,8 @@ use anyhow::{Context, anyhow};
 use ark_ec::pairing::Pairing;
 use ark_ff::{BigInteger, PrimeField};
 use clap::Parser;
+use dcipher_signer::dsigner::policy::SigningPolicy;
+use dcipher_signer::frost::{FrostCiphersuite, FrostEd25519Sha512, FrostSecp256k1Bip340, Scalar};
 use either::Either;
 use figment::Figment;
 use figment::providers::{Format, Serialized, Toml};
@@ -12,7 +14,7 @@ use std::collections::HashMap;
 use std::fmt::Formatter;
 use std::net::IpAddr;
 use std::num::{NonZeroU16, NonZeroUsize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use utils::serialize::point::PointDeserializeCompressed;
 use utils::serialize::point::PointSerializeCompressed;
 
@@ -27,6 +29,19 @@ pub struct SchemesConfig {
 pub enum SchemeConfigType {
     Bn254(BlsSchemeConfig<ark_bn254::Bn254>),
     Bls12_381(BlsSchemeConfig<ark_bls12_381::Bls12_381>),
+    FrostSecp256k1(FrostSchemeConfig<FrostSecp256k1Bip340>),
+    FrostEd25519(FrostSchemeConfig<FrostEd25519Sha512>),
+}
+
+impl SchemeConfigType {
+    pub fn policy(&self) -> Option<&SigningPolicy> {
+        match self {
+            SchemeConfigType::Bn254(bls) => bls.policy.as_ref(),
+            SchemeConfigType::Bls12_381(bls) => bls.policy.as_ref(),
+            SchemeConfigType::FrostSecp256k1(frost) => frost.policy.as_ref(),
+            SchemeConfigType::FrostEd25519(frost) => frost.policy.as_ref(),
+        }
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -42,6 +57,10 @@ pub struct BlsSchemeConfig<E: Pairing> {
     // nodes can be either specified directly, or through an external file
     #[serde(with = "either::serde_untagged")]
     pub nodes_config: Either<BlsNodesConfig<E>, PathBuf>,
+
+    // requests allowed by the scheme, all requests are allowed if unset
+    #[serde(default)]
+    pub policy: Option<SigningPolicy>,
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
@@ -66,6 +85,41 @@ pub struct BlsNodeConfig<E: Pairing> {
     pub pk_g2: E::G2Affine,
 }
 
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "FrostNodesConfig<C>: Serialize",
+    deserialize = "FrostNodesConfig<C>: Deserialize<'de>"
+))]
+pub struct FrostSchemeConfig<C: FrostCiphersuite> {
+    pub sk: FpWrapper<Scalar<C>>,
+    pub n: NonZeroU16,
+    pub t: NonZeroU16,
+
+    // nodes can be either specified directly, or through an external file
+    #[serde(with = "either::serde_untagged")]
+    pub nodes_config: Either<FrostNodesConfig<C>, PathBuf>,
+
+    // requests allowed by the scheme, all requests are allowed if unset
+    #[serde(default)]
+    pub policy: Option<SigningPolicy>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(bound(
+    serialize = "FrostNodeConfig<C>: Serialize",
+    deserialize = "FrostNodeConfig<C>: Deserialize<'de>"
+))]
+pub struct FrostNodesConfig<C: FrostCiphersuite> {
+    pub nodes: Vec<FrostNodeConfig<C>>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(bound = "")]
+pub struct FrostNodeConfig<C: FrostCiphersuite> {
+    pub id: NonZeroU16,
+    pub pk: FrostElementWrapper<C>,
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct NetworkConfig {
     pub id: NonZeroU16,
@@ -92,6 +146,9 @@ pub struct NetworkPeer {
 /// Wrapper around Fp that allows deserialization from hex
 pub struct FpWrapper<Fp>(pub Fp);
 
+/// Wrapper around a group element of a FROST ciphersuite that allows (de)serialization from base64
+pub struct FrostElementWrapper<C: FrostCiphersuite>(pub C::Group);
+
 /// Wrapper around libp2p::identity::Keypair with (de)serialization & cmd line parsing.
 #[derive(Clone, Debug)]
 pub struct Libp2pKeyWrapper(pub ::libp2p::identity::Keypair);
@@ -124,6 +181,47 @@ pub struct Args {
     /// LRU cache size used for partial signatures and signatures
     #[arg(long, env = "DSIGNER_LRU_CACHE_SIZE", default_value = "64")]
     pub lru_cache_size: NonZeroUsize,
+
+    /// PEM-encoded certificate used to serve the gRPC service over TLS
+    #[arg(long, env = "DSIGNER_TLS_CERT", requires = "tls_key")]
+    pub tls_cert: Option<PathBuf>,
+
+    /// PEM-encoded private key of the TLS certificate
+    #[arg(long, env = "DSIGNER_TLS_KEY", requires = "tls_cert")]
+    pub tls_key: Option<PathBuf>,
+
+    /// PEM-encoded CA certificate used to verify the certificates of the clients. Enables
+    /// mutual TLS, where clients are identified by the common name of their certificate
+    #[arg(long, env = "DSIGNER_TLS_CLIENT_CA", requires = "tls_cert")]
+    pub tls_client_ca: Option<PathBuf>,
+
+    /// File listing the clients allowed to authenticate with an API key
+    #[arg(long, env = "DSIGNER_API_KEYS")]
+    pub api_keys: Option<PathBuf>,
+
+    /// Secret used to verify HS256 JWTs
+    #[arg(long, env = "DSIGNER_JWT_SECRET", conflicts_with = "jwt_public_key")]
+    pub jwt_secret: Option<String>,
+
+    /// PEM-encoded public key used to verify JWTs
+    #[arg(long, env = "DSIGNER_JWT_PUBLIC_KEY", requires = "jwt_algorithm")]
+    pub jwt_public_key: Option<PathBuf>,
+
+    /// Algorithm of the JWTs signed with a private key, e.g., RS256, ES256 or EdDSA
+    #[arg(long, env = "DSIGNER_JWT_ALGORITHM")]
+    pub jwt_algorithm: Option<String>,
+
+    /// Expected issuer of the JWTs
+    #[arg(long, env = "DSIGNER_JWT_ISSUER")]
+    pub jwt_issuer: Option<String>,
+
+    /// Expected audience of the JWTs
+    #[arg(long, env = "DSIGNER_JWT_AUDIENCE")]
+    pub jwt_audience: Option<String>,
+
+    /// Accept requests from clients without credentials when authentication is enabled
+    #[arg(long, env = "DSIGNER_ALLOW_ANONYMOUS")]
+    pub allow_anonymous: bool,
 }
 
 pub struct DSignerConfig {
@@ -226,6 +324,12 @@ impl DSignerConfig {
                     SchemeConfigType::Bls12_381(scheme) => SchemeConfigType::Bls12_381(
                         Self::parse_bls_scheme(scheme, config.schemes_config.clone())?,
                     ),
+                    SchemeConfigType::FrostSecp256k1(scheme) => SchemeConfigType::FrostSecp256k1(
+                        Self::parse_frost_scheme(scheme, config.schemes_config.clone())?,
+                    ),
+                    SchemeConfigType::FrostEd25519(scheme) => SchemeConfigType::FrostEd25519(
+                        Self::parse_frost_scheme(scheme, config.schemes_config.clone())?,
+                    ),
                 };
 
                 Ok((scheme_id, scheme))
@@ -247,8 +351,67 @@ impl DSignerConfig {
         }
 
         // use nodes_config directly or parse from file
-        let mut nodes_config = match scheme.nodes_config {
-            Either::Left(nodes_config) => nodes_config,
+        let mut nodes_config = Self::load_nodes_config(scheme.nodes_config, &schemes_config_path)?;
+
+        nodes_config.nodes = nodes_config
+            .nodes
+            .into_iter()
+            .sorted_by(|a, b| a.id.cmp(&b.id))
+            .unique_by(|a| a.id)
+            .collect();
+
+        if nodes_config.nodes.len() != scheme.n.get() as usize {
+            Err(anyhow!(
+                "number of nodes does not match scheme's number of nodes"
+            ))?
+        }
+
+        scheme.nodes_config = Either::Left(nodes_config);
+        Ok(scheme)
+    }
+
+    fn parse_frost_scheme<C: FrostCiphersuite>(
+        mut scheme: FrostSchemeConfig<C>,
+        schemes_config_path: PathBuf,
+    ) -> anyhow::Result<FrostSchemeConfig<C>>
+    where
+        FrostNodesConfig<C>: Serialize + for<'de> Deserialize<'de>,
+    {
+        if scheme.t > scheme.n {
+            Err(anyhow!("t cannot be greater than n"))?
+        }
+
+        // use nodes_config directly or parse from file
+        let mut nodes_config = Self::load_nodes_config(scheme.nodes_config, &schemes_config_path)?;
+
+        nodes_config.nodes = nodes_config
+            .nodes
+            .into_iter()
+            .sorted_by(|a, b| a.id.cmp(&b.id))
+            .unique_by(|a| a.id)
+            .collect();
+
+        if nodes_config.nodes.len() != scheme.n.get() as usize {
+            Err(anyhow!(
+                "number of nodes does not match scheme's number of nodes"
+            ))?
+        }
+
+        scheme.nodes_config = Either::Left(nodes_config);
+        Ok(scheme)
+    }
+
+    /// Use the nodes configuration directly, or parse it from a file relative to the schemes
+    /// configuration.
+    fn load_nodes_config<N>(
+        nodes_config: Either<N, PathBuf>,
+        schemes_config_path: &Path,
+    ) -> anyhow::Result<N>
+    where
+        N: for<'de> Deserialize<'de>,
+    {
+        match nodes_config {
+            Either::Left(nodes_config) => Ok(nodes_config),
             Either::Right(nodes_config_path) => {
                 // Either use absolute or relative path
                 let nodes_config_path = if nodes_config_path.is_absolute() {
@@ -268,25 +431,9 @@ impl DSignerConfig {
                             "failed to parse nodes_config file: {}",
                             nodes_config_path.display()
                         )
-                    })?
+                    })
             }
-        };
-
-        nodes_config.nodes = nodes_config
-            .nodes
-            .into_iter()
-            .sorted_by(|a, b| a.id.cmp(&b.id))
-            .unique_by(|a| a.id)
-            .collect();
-
-        if nodes_config.nodes.len() != scheme.n.get() as usize {
-            Err(anyhow!(
-                "number of nodes does not match scheme's number of nodes"
-            ))?
         }
-
-        scheme.nodes_config = Either::Left(nodes_config);
-        Ok(scheme)
     }
 }
 
@@ -329,6 +476,47 @@ impl<'de, Fp: PrimeField> Deserialize<'de> for FpWrapper<Fp> {
     }
 }
 
+impl<C: FrostCiphersuite> std::fmt::Debug for FrostElementWrapper<C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+impl<C: FrostCiphersuite> Clone for FrostElementWrapper<C> {
+    fn clone(&self) -> Self {
+        FrostElementWrapper(self.0)
+    }
+}
+
+impl<C: FrostCiphersuite> Serialize for FrostElementWrapper<C> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        use base64::prelude::*;
+
+        serializer.serialize_str(&BASE64_STANDARD.encode(C::serialize_element(&self.0)))
+    }
+}
+
+impl<'de, C: FrostCiphersuite> Deserialize<'de> for FrostElementWrapper<C> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use base64::prelude::*;
+        use serde::de::Error;
+
+        let base64_str = String::deserialize(deserializer)?;
+        let bytes = BASE64_STANDARD
+            .decode(&base64_str)
+            .map_err(D::Error::custom)?;
+        Ok(FrostElementWrapper(
+            C::deserialize_element(&bytes).map_err(D::Error::custom)?,
+        ))
+    }
+}
+
 impl From<Libp2pKeyWrapper> for ::libp2p::identity::Keypair {
     fn from(value: Libp2pKeyWrapper) -> Self {
         value.0
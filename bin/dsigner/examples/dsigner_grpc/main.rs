@@ -1,5 +1,7 @@
-use crate::arguments_parser::{BlsSchemeConfig, DSignerConfig, NetworkConfig, SchemeConfigType};
-use anyhow::anyhow;
+use crate::arguments_parser::{
+    Args, BlsSchemeConfig, DSignerConfig, FrostSchemeConfig, NetworkConfig, SchemeConfigType,
+};
+use anyhow::{Context, anyhow};
 use ark_ec::pairing::Pairing;
 use dcipher_network::topic::TopicBasedTransport;
 use dcipher_network::topic::dispatcher::{TopicBasedTransportImpl, TopicDispatcher};
@@ -7,9 +9,14 @@ use dcipher_network::transports::libp2p::transport::Libp2pSender;
 use dcipher_network::transports::libp2p::{Libp2pNode, Libp2pNodeConfig};
 use dcipher_signer::bls::{BlsPairingSigner, BlsSigner, BlsThresholdSigner, BlsVerifier};
 use dcipher_signer::dsigner::DSignerScheme;
+use dcipher_signer::frost::{FrostCiphersuite, FrostThresholdSigner};
+use dsigner::proto_types::d_signer_batch_service_server::DSignerBatchServiceServer;
 use dsigner::proto_types::d_signer_service_server::DSignerServiceServer;
 use dsigner::server::DSignerSchemeManager;
 use dsigner::server::grpc::DSignerServiceImpl;
+use dsigner::server::grpc::auth::{ApiKeysConfig, Authenticator};
+use figment::Figment;
+use figment::providers::{Format, Toml};
 use std::sync::Arc;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
@@ -45,6 +52,7 @@ async fn main() -> anyhow::Result<()> {
             .topic_transport
             .get_transport_for(scheme_id.clone())
             .ok_or(anyhow!("failed to get transport for scheme"))?;
+        let policy = scheme.policy().cloned();
         let dsigner_scheme: Arc<dyn DSignerScheme + Send + Sync + 'static> = match scheme {
             SchemeConfigType::Bn254(bls) => {
                 let signer = get_bls_signer(schemes_config.node_id.get(), bls);
@@ -56,14 +64,48 @@ async fn main() -> anyhow::Result<()> {
                 let (_, dsigner_scheme) = signer.run(transport);
                 Arc::new(dsigner_scheme)
             }
+            SchemeConfigType::FrostSecp256k1(frost) => {
+                let signer = get_frost_signer(schemes_config.node_id.get(), frost)?;
+                let (_, dsigner_scheme) = signer.run(transport);
+                Arc::new(dsigner_scheme)
+            }
+            SchemeConfigType::FrostEd25519(frost) => {
+                let signer = get_frost_signer(schemes_config.node_id.get(), frost)?;
+                let (_, dsigner_scheme) = signer.run(transport);
+                Arc::new(dsigner_scheme)
+            }
         };
 
+        if let Some(policy) = policy {
+            manager.register_policy_mut(scheme_id.clone().into(), policy);
+        }
         manager.register_scheme_mut(scheme_id.into(), dsigner_scheme);
     }
 
+    let mut server = tonic::transport::Server::builder();
+    if let Some(tls_config) = get_tls_config(&config)? {
+        server = server.tls_config(tls_config)?;
+    }
+
     let dsigner_service = DSignerServiceImpl::new(Arc::new(manager));
-    tonic::transport::Server::builder()
-        .add_service(DSignerServiceServer::new(dsigner_service))
+    let router = match get_authenticator(&config)? {
+        Some(authenticator) => server
+            .add_service(DSignerServiceServer::with_interceptor(
+                dsigner_service.clone(),
+                authenticator.clone(),
+            ))
+            .add_service(DSignerBatchServiceServer::with_interceptor(
+                dsigner_service,
+                authenticator,
+            )),
+        None => {
+            tracing::warn!("Client authentication disabled, accepting all requests");
+            server
+                .add_service(DSignerServiceServer::new(dsigner_service.clone()))
+                .add_service(DSignerBatchServiceServer::new(dsigner_service))
+        }
+    };
+    router
         .serve((config.listen_addr, config.port).into())
         .await?;
 
@@ -78,6 +120,68 @@ async fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
+fn get_tls_config(config: &Args) -> anyhow::Result<Option<tonic::transport::ServerTlsConfig>> {
+    let (Some(cert), Some(key)) = (&config.tls_cert, &config.tls_key) else {
+        return Ok(None);
+    };
+
+    let read = |path: &std::path::PathBuf| {
+        std::fs::read(path).with_context(|| format!("failed to read {}", path.display()))
+    };
+    let mut tls_config = tonic::transport::ServerTlsConfig::new().identity(
+        tonic::transport::Identity::from_pem(read(cert)?, read(key)?),
+    );
+    if let Some(client_ca) = &config.tls_client_ca {
+        tls_config =
+            tls_config.client_ca_root(tonic::transport::Certificate::from_pem(read(client_ca)?));
+    }
+
+    Ok(Some(tls_config))
+}
+
+/// Build an authenticator if any client authentication method is configured.
+fn get_authenticator(config: &Args) -> anyhow::Result<Option<Authenticator>> {
+    let mut authenticator = Authenticator::new();
+    let mut enabled = false;
+
+    if config.tls_client_ca.is_some() {
+        authenticator = authenticator.with_mutual_tls();
+        enabled = true;
+    }
+
+    if let Some(api_keys) = &config.api_keys {
+        let api_keys: ApiKeysConfig = Figment::new()
+            .merge(Toml::file(api_keys))
+            .extract()
+            .with_context(|| format!("failed to parse api keys file: {}", api_keys.display()))?;
+        authenticator = authenticator.with_api_keys(api_keys)?;
+        enabled = true;
+    }
+
+    let (issuer, audience) = (config.jwt_issuer.as_deref(), config.jwt_audience.as_deref());
+    if let Some(secret) = &config.jwt_secret {
+        authenticator = authenticator.with_jwt_secret(secret.as_bytes(), issuer, audience);
+        enabled = true;
+    } else if let Some(public_key) = &config.jwt_public_key {
+        let alg = config
+            .jwt_algorithm
+            .as_deref()
+            .ok_or(anyhow!("jwt algorithm required"))?
+            .parse()
+            .context("failed to parse jwt algorithm")?;
+        let public_key = std::fs::read(public_key)
+            .with_context(|| format!("failed to read {}", public_key.display()))?;
+        authenticator = authenticator.with_jwt_public_key(alg, &public_key, issuer, audience)?;
+        enabled = true;
+    }
+
+    if config.allow_anonymous {
+        authenticator = authenticator.allow_anonymous();
+    }
+
+    Ok(enabled.then_some(authenticator))
+}
+
 fn get_bls_signer<E>(
     node_id: u16,
     bls_config: BlsSchemeConfig<E>,
@@ -98,14 +202,46 @@ where
         .collect();
 
     let signer = BlsPairingSigner::new(bls_config.sk.0);
-    BlsThresholdSigner::new(
+    let signer = BlsThresholdSigner::new(
         signer,
         bls_config.n.get(),
         bls_config.t.get(),
         node_id,
         pks_g1,
         pks_g2,
-    )
+    );
+
+    match bls_config.policy {
+        Some(policy) => signer.with_policy(policy),
+        None => signer,
+    }
+}
+
+fn get_frost_signer<C: FrostCiphersuite>(
+    node_id: u16,
+    frost_config: FrostSchemeConfig<C>,
+) -> anyhow::Result<FrostThresholdSigner<C>> {
+    let pks = frost_config
+        .nodes_config
+        .left()
+        .expect("nodes_config not parsed from file")
+        .nodes
+        .into_iter()
+        .map(|n| (n.id.get(), n.pk.0))
+        .collect();
+
+    let signer = FrostThresholdSigner::new(
+        frost_config.n.get(),
+        frost_config.t.get(),
+        node_id,
+        frost_config.sk.0,
+        pks,
+    )?;
+
+    Ok(match frost_config.policy {
+        Some(policy) => signer.with_policy(policy),
+        None => signer,
+    })
 }
 
 type TopicTransport = TopicBasedTransportImpl<Libp2pSender<u16>>;
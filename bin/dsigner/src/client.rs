@@ -0,0 +1,141 @@
+//! Client helpers to request signatures from a dsigner grpc service.
+
+use crate::proto_types::d_signer_batch_service_client::DSignerBatchServiceClient;
+use crate::proto_types::d_signer_service_client::DSignerServiceClient;
+use crate::proto_types::signature_result::Result as SignatureResultEnum;
+use crate::proto_types::{
+    API_KEY_METADATA, BatchGetSignaturesRequest, GetSignatureRequest,
+    GetVerificationParametersRequest, SignatureResult, StreamSignatureRequest,
+    VerificationParameters,
+};
+use bytes::Bytes;
+use futures_util::{Stream, StreamExt};
+use tonic::metadata::{Ascii, AsciiMetadataValue, MetadataKey};
+use tonic::transport::{Channel, Endpoint};
+use tonic::{Request, Status};
+
+/// Client of the dsigner grpc service.
+#[derive(Clone, Debug)]
+pub struct DSignerClient {
+    service: DSignerServiceClient<Channel>,
+    batch_service: DSignerBatchServiceClient<Channel>,
+    credentials: Option<(MetadataKey<Ascii>, AsciiMetadataValue)>,
+}
+
+impl DSignerClient {
+    /// Connect to a dsigner service.
+    pub async fn connect(endpoint: impl Into<Endpoint>) -> Result<Self, tonic::transport::Error> {
+        let channel = endpoint.into().connect().await?;
+        Ok(Self::new(channel))
+    }
+
+    /// Create a client from an existing channel, e.g., configured with TLS.
+    pub fn new(channel: Channel) -> Self {
+        Self {
+            service: DSignerServiceClient::new(channel.clone()),
+            batch_service: DSignerBatchServiceClient::new(channel),
+            credentials: None,
+        }
+    }
+
+    /// Authenticate the requests with an API key.
+    pub fn with_api_key(mut self, api_key: &str) -> Result<Self, Status> {
+        let value = api_key
+            .parse()
+            .map_err(|_| Status::invalid_argument("invalid API key"))?;
+        self.credentials = Some((MetadataKey::from_static(API_KEY_METADATA), value));
+        Ok(self)
+    }
+
+    /// Authenticate the requests with a JWT.
+    pub fn with_bearer_token(mut self, token: &str) -> Result<Self, Status> {
+        let value = format!("Bearer {token}")
+            .parse()
+            .map_err(|_| Status::invalid_argument("invalid bearer token"))?;
+        self.credentials = Some((MetadataKey::from_static("authorization"), value));
+        Ok(self)
+    }
+
+    /// Access the underlying grpc client, e.g., to list the schemes.
+    pub fn service(&mut self) -> &mut DSignerServiceClient<Channel> {
+        &mut self.service
+    }
+
+    /// Request a signature.
+    pub async fn get_signature(&mut self, req: GetSignatureRequest) -> Result<Bytes, Status> {
+        let request = self.request(req);
+        let response = self.service.get_signature(request).await?;
+        Ok(response.into_inner().signature)
+    }
+
+    /// Obtain the parameters required to verify the signatures of a scheme.
+    pub async fn get_verification_parameters(
+        &mut self,
+        req: GetVerificationParametersRequest,
+    ) -> Result<VerificationParameters, Status> {
+        let request = self.request(req);
+        let response = self.service.get_verification_parameters(request).await?;
+        response.into_inner().params.ok_or(Status::internal(
+            "missing verification parameters in response",
+        ))
+    }
+
+    /// Request multiple signatures at once, returning the outcome of each request in order.
+    pub async fn batch_get_signatures(
+        &mut self,
+        reqs: Vec<GetSignatureRequest>,
+    ) -> Result<Vec<Result<Bytes, Status>>, Status> {
+        let request = self.request(BatchGetSignaturesRequest { requests: reqs });
+        let response = self.batch_service.batch_get_signatures(request).await?;
+        Ok(response
+            .into_inner()
+            .results
+            .into_iter()
+            .map(SignatureResult::into_signature)
+            .collect())
+    }
+
+    /// Stream signature requests to the service, and obtain a stream that outputs the index of
+    /// each request along with its outcome, as soon as it is available.
+    pub async fn stream_signatures(
+        &mut self,
+        reqs: impl Stream<Item = GetSignatureRequest> + Send + 'static,
+    ) -> Result<impl Stream<Item = Result<(u64, Result<Bytes, Status>), Status>> + use<>, Status>
+    {
+        let reqs = reqs.enumerate().map(|(i, request)| StreamSignatureRequest {
+            request_id: i as u64,
+            request: Some(request),
+        });
+
+        let request = self.request(reqs);
+        let responses = self.batch_service.stream_signatures(request).await?;
+        Ok(responses.into_inner().map(|response| {
+            let response = response?;
+            let result = response
+                .result
+                .ok_or(Status::internal("missing result in response"))
+                .and_then(SignatureResult::into_signature);
+            Ok((response.request_id, result))
+        }))
+    }
+
+    /// Wrap a message in a request, along with the credentials of the client.
+    fn request<T>(&self, message: T) -> Request<T> {
+        let mut request = Request::new(message);
+        if let Some((key, value)) = &self.credentials {
+            request.metadata_mut().insert(key.clone(), value.clone());
+        }
+        request
+    }
+}
+
+impl SignatureResult {
+    /// Convert the result into the signature, or the error returned by the service.
+    pub fn into_signature(self) -> Result<Bytes, Status> {
+        match self.result {
+            Some(SignatureResultEnum::Signature(response)) => Ok(response.signature),
+            Some(SignatureResultEnum::Error(e)) => Err(Status::new(e.code.into(), e.message)),
+            None => Err(Status::internal("missing result in response")),
+        }
+    }
+}
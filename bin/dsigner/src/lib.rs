@@ -1,5 +1,7 @@
 //! Library to run a dsigner grpc service & client code to interact with the grpc service.
 
+#[cfg(feature = "client")]
+pub mod client;
 pub mod proto_types;
 
 #[cfg(feature = "server")]
@@ -30,6 +30,9 @@ pub mod dsigner {
     pub enum ParseProtoError {
         #[error("unspecified field: {0}")]
         UnspecifiedField(&'static str),
+
+        #[error("application not supported by the protocol")]
+        UnsupportedApplication,
     }
 
     impl TryFrom<Application> for dcipher_signer::dsigner::Application {
@@ -51,6 +54,7 @@ pub mod dsigner {
             match value {
                 dcipher_signer::dsigner::Application::Blocklock => Self::Blocklock,
                 dcipher_signer::dsigner::Application::Randomness => Self::Randomness,
+                dcipher_signer::dsigner::Application::EvmNet => Self::Evmnet,
                 dcipher_signer::dsigner::Application::Any => Self::Any,
                 _ => Self::Unspecified,
             }
@@ -116,6 +120,24 @@ pub mod dsigner {
         }
     }
 
+    impl TryFrom<dcipher_signer::dsigner::ApplicationArgs> for ApplicationArgs {
+        type Error = ParseProtoError;
+
+        fn try_from(value: dcipher_signer::dsigner::ApplicationArgs) -> Result<Self, Self::Error> {
+            use dcipher_signer::dsigner::ApplicationArgs as Args;
+
+            let args = match value {
+                Args::Blocklock(args) => ApplicationArgsEnum::Blocklock(args.into()),
+                Args::Randomness(args) => ApplicationArgsEnum::Randomness(args.into()),
+                Args::Any(args) => ApplicationArgsEnum::Any(args.into()),
+                Args::EvmNet => ApplicationArgsEnum::Evmnet(Default::default()),
+                _ => Err(Self::Error::UnsupportedApplication)?,
+            };
+
+            Ok(Self { args: Some(args) })
+        }
+    }
+
     impl TryFrom<ApplicationArgs> for dcipher_signer::dsigner::ApplicationArgs {
         type Error = ParseProtoError;
 
@@ -200,6 +222,67 @@ pub mod dsigner {
         }
     }
 
+    impl TryFrom<FrostSignatureAlgorithm> for dcipher_signer::dsigner::SignatureAlgorithm {
+        type Error = ParseProtoError;
+
+        fn try_from(value: FrostSignatureAlgorithm) -> Result<Self, Self::Error> {
+            match value {
+                FrostSignatureAlgorithm::Unspecified => {
+                    Err(Self::Error::UnspecifiedField("signature algorithm"))
+                }
+
+                FrostSignatureAlgorithm::Secp256k1Bip340 => Ok(Self::Frost(
+                    dcipher_signer::dsigner::FrostSignatureAlgorithm::Secp256k1Bip340,
+                )),
+
+                FrostSignatureAlgorithm::Ed25519 => Ok(Self::Frost(
+                    dcipher_signer::dsigner::FrostSignatureAlgorithm::Ed25519,
+                )),
+            }
+        }
+    }
+
+    impl From<dcipher_signer::dsigner::SignatureAlgorithm> for FrostSignatureAlgorithm {
+        fn from(value: dcipher_signer::dsigner::SignatureAlgorithm) -> Self {
+            match value {
+                dcipher_signer::dsigner::SignatureAlgorithm::Frost(
+                    dcipher_signer::dsigner::FrostSignatureAlgorithm::Secp256k1Bip340,
+                ) => Self::Secp256k1Bip340,
+
+                dcipher_signer::dsigner::SignatureAlgorithm::Frost(
+                    dcipher_signer::dsigner::FrostSignatureAlgorithm::Ed25519,
+                ) => Self::Ed25519,
+
+                _ => Self::Unspecified,
+            }
+        }
+    }
+
+    /// Parse the raw value of a `SignatureAlgorithm` field, which holds either a
+    /// [`SignatureAlgorithm`] or a [`FrostSignatureAlgorithm`].
+    pub fn parse_signature_algorithm(
+        alg: i32,
+    ) -> Result<dcipher_signer::dsigner::SignatureAlgorithm, ParseProtoError> {
+        match FrostSignatureAlgorithm::try_from(alg) {
+            Ok(frost_alg) if frost_alg != FrostSignatureAlgorithm::Unspecified => {
+                frost_alg.try_into()
+            }
+            _ => SignatureAlgorithm::try_from(alg)
+                .unwrap_or_default()
+                .try_into(),
+        }
+    }
+
+    /// Encode a signature algorithm as the raw value of a `SignatureAlgorithm` field.
+    pub fn encode_signature_algorithm(alg: dcipher_signer::dsigner::SignatureAlgorithm) -> i32 {
+        match alg {
+            dcipher_signer::dsigner::SignatureAlgorithm::Frost(_) => {
+                FrostSignatureAlgorithm::from(alg).into()
+            }
+            _ => SignatureAlgorithm::from(alg).into(),
+        }
+    }
+
     impl From<dcipher_signer::dsigner::SchemeAlgorithm> for SchemeAlgorithm {
         fn from(value: dcipher_signer::dsigner::SchemeAlgorithm) -> Self {
             Self {
@@ -207,7 +290,7 @@ pub mod dsigner {
                 algs: value
                     .algs
                     .into_iter()
-                    .map(|alg| SignatureAlgorithm::from(alg).into())
+                    .map(encode_signature_algorithm)
                     .filter(|&alg| alg != SignatureAlgorithm::Unknown as i32) // filter unknown algs
                     .collect(),
                 apps: value
@@ -236,5 +319,91 @@ pub mod dsigner {
     }
 }
 
+/// Metadata key used by clients to authenticate with an API key.
+pub const API_KEY_METADATA: &str = "x-api-key";
+
 /// Re-export dsigner proto types
 pub use dsigner::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dcipher_signer::dsigner::{
+        ApplicationAnyArgs, ApplicationBlocklockArgs, ApplicationRandomnessArgs,
+        BlsSignatureAlgorithm, BlsSignatureCurve, BlsSignatureHash, FrostSignatureAlgorithm,
+        OnlySwapsVerifierArgs, SignatureAlgorithm,
+    };
+
+    #[test]
+    fn signature_algorithms_round_trip() {
+        let algs = [
+            SignatureAlgorithm::Bls(BlsSignatureAlgorithm {
+                curve: BlsSignatureCurve::Bn254G1,
+                hash: BlsSignatureHash::Keccak256,
+                compression: false,
+            }),
+            SignatureAlgorithm::Bls(BlsSignatureAlgorithm {
+                curve: BlsSignatureCurve::Bls12_381G1,
+                hash: BlsSignatureHash::Sha256,
+                compression: true,
+            }),
+            SignatureAlgorithm::Frost(FrostSignatureAlgorithm::Secp256k1Bip340),
+            SignatureAlgorithm::Frost(FrostSignatureAlgorithm::Ed25519),
+        ];
+
+        for alg in algs {
+            let encoded = encode_signature_algorithm(alg);
+            assert_ne!(encoded, dsigner::SignatureAlgorithm::Unknown as i32);
+            assert_eq!(parse_signature_algorithm(encoded).unwrap(), alg);
+        }
+    }
+
+    #[test]
+    fn frost_algorithms_are_listed() {
+        let scheme_alg = dcipher_signer::dsigner::SchemeAlgorithm {
+            public_key: Default::default(),
+            algs: vec![SignatureAlgorithm::Frost(FrostSignatureAlgorithm::Ed25519)],
+            apps: vec![],
+        };
+
+        let scheme_alg = SchemeAlgorithm::from(scheme_alg);
+        assert_eq!(
+            scheme_alg.algs,
+            vec![dsigner::FrostSignatureAlgorithm::Ed25519 as i32]
+        );
+    }
+
+    #[test]
+    fn unspecified_algorithms_are_rejected() {
+        assert!(parse_signature_algorithm(0).is_err());
+        assert!(parse_signature_algorithm(-1).is_err());
+    }
+
+    #[test]
+    fn application_args_round_trip() {
+        use dcipher_signer::dsigner::ApplicationArgs as Args;
+
+        let args = [
+            Args::Blocklock(ApplicationBlocklockArgs { chain_id: 1 }),
+            Args::Randomness(ApplicationRandomnessArgs { chain_id: 2 }),
+            Args::EvmNet,
+            Args::Any(ApplicationAnyArgs {
+                dst_suffix: "test".to_owned(),
+            }),
+        ];
+
+        for arg in args {
+            let app = Application::from(arg.app());
+            let encoded = ApplicationArgs::try_from(arg.clone()).unwrap();
+            assert_eq!(encoded.args.as_ref().unwrap().application(), app);
+            assert_eq!(Args::try_from(encoded).unwrap(), arg);
+        }
+
+        // OnlySwaps is not part of the pinned dcipher-proto yet
+        let only_swaps = Args::OnlySwapsVerifier(OnlySwapsVerifierArgs { chain_id: 3 });
+        assert!(matches!(
+            ApplicationArgs::try_from(only_swaps),
+            Err(ParseProtoError::UnsupportedApplication)
+        ));
+    }
+}
@@ -1,6 +1,7 @@
 //! Server code used to run a DSigner service.
 
 use dcipher_signer::dsigner as dsigner_types;
+use dcipher_signer::dsigner::policy::{PolicyEnforcer, PolicyViolation, SigningPolicy};
 use dcipher_signer::dsigner::{
     DSignerScheme, DSignerSchemeError, SchemeDetails, VerificationParameters,
 };
@@ -13,11 +14,16 @@ pub mod grpc;
 
 type SchemeId = Cow<'static, str>;
 type Schemes = HashMap<SchemeId, Arc<dyn DSignerScheme + Send + Sync + 'static>>;
+type Policies = HashMap<SchemeId, Arc<PolicyEnforcer>>;
+
+/// Identity of the clients that request signatures through the manager without authenticating.
+pub const ANONYMOUS_CLIENT: &str = "anonymous";
 
 /// A scheme manager to request signatures from multiple dsigner schemes.
 #[derive(Default)]
 pub struct DSignerSchemeManager {
     schemes: tokio::sync::RwLock<Schemes>,
+    policies: tokio::sync::RwLock<Policies>,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -25,6 +31,9 @@ pub enum DSignerSchemeManagerError {
     #[error("scheme id unknown")]
     UnknownSchemeId,
 
+    #[error("the request was rejected by the signing policy")]
+    PolicyViolation(#[from] PolicyViolation),
+
     #[error("failed to sign due to scheme error")]
     SchemeError(#[from] DSignerSchemeError),
 }
@@ -34,6 +43,7 @@ impl DSignerSchemeManager {
     pub fn new() -> Self {
         Self {
             schemes: Default::default(),
+            policies: Default::default(),
         }
     }
 
@@ -66,6 +76,25 @@ impl DSignerSchemeManager {
         self.schemes.get_mut().insert(scheme_id, scheme);
     }
 
+    /// Push a signing policy for a scheme to the manager
+    pub fn push_policy(mut self, scheme_id: SchemeId, policy: SigningPolicy) -> Self {
+        self.register_policy_mut(scheme_id, policy);
+        self
+    }
+
+    /// Register a signing policy for a scheme with the manager, replacing the existing one
+    pub async fn register_policy(&self, scheme_id: SchemeId, policy: SigningPolicy) {
+        let mut policies = self.policies.write().await;
+        policies.insert(scheme_id, Arc::new(PolicyEnforcer::new(policy)));
+    }
+
+    /// Register a signing policy for a scheme with an exclusive reference to the manager
+    pub fn register_policy_mut(&mut self, scheme_id: SchemeId, policy: SigningPolicy) {
+        self.policies
+            .get_mut()
+            .insert(scheme_id, Arc::new(PolicyEnforcer::new(policy)));
+    }
+
     /// Sign a message using a specific scheme for a specific application.
     pub async fn sign(
         &self,
@@ -73,6 +102,21 @@ impl DSignerSchemeManager {
         alg: dsigner_types::SignatureAlgorithm,
         message: impl Into<bytes::Bytes>,
         args: dsigner_types::ApplicationArgs,
+    ) -> Result<bytes::Bytes, DSignerSchemeManagerError> {
+        self.sign_for_client(ANONYMOUS_CLIENT, scheme_id, alg, message, args)
+            .await
+    }
+
+    /// Sign a message on behalf of a client using a specific scheme for a specific application.
+    /// The request must be allowed by the policy of the scheme, if any, including its per-client
+    /// rate limits.
+    pub async fn sign_for_client(
+        &self,
+        client_id: &str,
+        scheme_id: impl AsRef<str>,
+        alg: dsigner_types::SignatureAlgorithm,
+        message: impl Into<bytes::Bytes>,
+        args: dsigner_types::ApplicationArgs,
     ) -> Result<bytes::Bytes, DSignerSchemeManagerError> {
         let scheme = {
             let schemes = self.schemes.read().await;
@@ -87,6 +131,20 @@ impl DSignerSchemeManager {
             alg,
             args,
         };
+
+        let policy = self.policies.read().await.get(scheme_id.as_ref()).cloned();
+        if let Some(policy) = policy {
+            policy
+                .check(client_id, &req.m, &req.args)
+                .inspect_err(|e| {
+                    tracing::warn!(
+                        scheme_id = scheme_id.as_ref(),
+                        client_id,
+                        error = %e,
+                        "Signing request rejected by the policy"
+                    );
+                })?;
+        }
         tracing::debug!(
             scheme_id = scheme_id.as_ref(),
             ?alg,
@@ -133,3 +191,99 @@ impl DSignerSchemeManager {
             .collect())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dcipher_signer::dsigner::{
+        ApplicationAnyArgs, ApplicationArgs, DSignerSchemeSigner, FrostSignatureAlgorithm,
+        SignatureAlgorithm, SignatureRequest,
+    };
+    use futures_util::FutureExt;
+    use futures_util::future::BoxFuture;
+
+    /// Scheme that outputs the message as signature.
+    struct EchoScheme;
+
+    impl DSignerSchemeSigner for EchoScheme {
+        fn async_sign(
+            &self,
+            req: SignatureRequest,
+        ) -> BoxFuture<'_, Result<bytes::Bytes, DSignerSchemeError>> {
+            futures_util::future::ready(Ok(req.m)).boxed()
+        }
+    }
+
+    impl DSignerScheme for EchoScheme {
+        fn details(&self) -> SchemeDetails {
+            SchemeDetails {
+                scheme_algs: vec![],
+                n: 1,
+                t: 1,
+            }
+        }
+
+        fn verification_parameters(
+            &self,
+            _alg: &SignatureAlgorithm,
+            _args: &ApplicationArgs,
+        ) -> Result<VerificationParameters, DSignerSchemeError> {
+            Err(DSignerSchemeError::AlgorithmNotSupported)
+        }
+    }
+
+    #[tokio::test]
+    async fn sign_with_policy() {
+        let policy: SigningPolicy = toml::from_str(
+            r#"
+            [applications.Any]
+            dst_suffixes = ["test-*"]
+            rate_limit = { requests = 1, period = "1h" }
+            "#,
+        )
+        .unwrap();
+        let manager = DSignerSchemeManager::new()
+            .push_scheme("echo".into(), Arc::new(EchoScheme))
+            .push_scheme("echo-no-policy".into(), Arc::new(EchoScheme))
+            .push_policy("echo".into(), policy);
+
+        let any = |dst_suffix: &str| {
+            ApplicationArgs::Any(ApplicationAnyArgs {
+                dst_suffix: dst_suffix.to_owned(),
+            })
+        };
+        let sign = |client_id, scheme_id, args| {
+            manager.sign_for_client(
+                client_id,
+                scheme_id,
+                SignatureAlgorithm::Frost(FrostSignatureAlgorithm::Secp256k1Bip340),
+                b"m".as_slice(),
+                args,
+            )
+        };
+
+        assert!(sign("alice", "echo", any("test-1")).await.is_ok());
+        assert!(matches!(
+            sign("alice", "echo", any("test-2")).await,
+            Err(DSignerSchemeManagerError::PolicyViolation(
+                PolicyViolation::RateLimited
+            ))
+        ));
+        assert!(sign("bob", "echo", any("test-1")).await.is_ok());
+        assert!(matches!(
+            sign("carol", "echo", any("other")).await,
+            Err(DSignerSchemeManagerError::PolicyViolation(
+                PolicyViolation::DstSuffixNotAllowed(_)
+            ))
+        ));
+        assert!(matches!(
+            sign("carol", "echo", ApplicationArgs::EvmNet).await,
+            Err(DSignerSchemeManagerError::PolicyViolation(
+                PolicyViolation::ApplicationNotAllowed(_)
+            ))
+        ));
+
+        // Schemes without policy sign anything
+        assert!(sign("alice", "echo-no-policy", any("other")).await.is_ok());
+    }
+}
@@ -1,14 +1,19 @@
 //! Implementation of a dsigner grpc service.
 
+pub mod auth;
+mod batch;
+
 use crate::proto_types::d_signer_service_server::DSignerService;
 use crate::proto_types::{
     GetSignatureRequest, GetSignatureResponse, GetVerificationParametersRequest,
     GetVerificationParametersResponse, ListSchemesResponse, ParseProtoError, Scheme,
-    SignatureStatus, VerificationParameters,
+    SignatureStatus, VerificationParameters, parse_signature_algorithm,
 };
+use crate::server::grpc::auth::ClientIdentity;
 use crate::server::{DSignerSchemeManager, DSignerSchemeManagerError};
 use dcipher_signer::dsigner as dsigner_types;
 use dcipher_signer::dsigner::DSignerSchemeError;
+use dcipher_signer::dsigner::policy::PolicyViolation;
 use std::sync::Arc;
 use tonic::{Request, Response, Status};
 
@@ -25,6 +30,41 @@ impl DSignerServiceImpl {
     pub fn manager(&self) -> Arc<DSignerSchemeManager> {
         self.manager.clone()
     }
+
+    /// Sign a message on behalf of a client.
+    async fn sign(
+        &self,
+        client: &ClientIdentity,
+        request: GetSignatureRequest,
+    ) -> Result<GetSignatureResponse, Status> {
+        let alg = parse_signature_algorithm(request.alg)?;
+        let args: dsigner_types::ApplicationArgs = request
+            .app_args
+            .ok_or(Status::invalid_argument("application args required"))?
+            .try_into()?;
+
+        tracing::info!(
+            client_id = client.id,
+            auth_method = ?client.method,
+            scheme_id = request.scheme_id,
+            app = ?args.app(),
+            ?alg,
+            "Received signature request"
+        );
+        let sig = self
+            .manager
+            .sign_for_client(&client.id, request.scheme_id, alg, request.message, args)
+            .await
+            .map_err(|e| {
+                tracing::error!(client_id = client.id, error = ?e, "Failed to sign message");
+                e
+            })?;
+
+        Ok(GetSignatureResponse {
+            signature: sig,
+            status: SignatureStatus::Completed.into(),
+        })
+    }
 }
 
 #[tonic::async_trait]
@@ -50,27 +90,9 @@ impl DSignerService for DSignerServiceImpl {
         &self,
         request: Request<GetSignatureRequest>,
     ) -> Result<Response<GetSignatureResponse>, Status> {
-        let request = request.into_inner();
-        let alg: dsigner_types::SignatureAlgorithm = request.alg().try_into()?;
-        let args: dsigner_types::ApplicationArgs = request
-            .app_args
-            .ok_or(Status::invalid_argument("application args required"))?
-            .try_into()?;
-
-        let sig = self
-            .manager
-            .sign(request.scheme_id, alg, request.message, args)
-            .await
-            .map_err(|e| {
-                tracing::error!(error = ?e, "Failed to sign message");
-                e
-            })?;
-
-        Ok(GetSignatureResponse {
-            signature: sig,
-            status: SignatureStatus::Completed.into(),
-        }
-        .into())
+        let client = ClientIdentity::from_request(&request);
+        let response = self.sign(&client, request.into_inner()).await?;
+        Ok(response.into())
     }
 
     async fn get_verification_parameters(
@@ -78,7 +100,7 @@ impl DSignerService for DSignerServiceImpl {
         request: Request<GetVerificationParametersRequest>,
     ) -> Result<Response<GetVerificationParametersResponse>, Status> {
         let request = request.into_inner();
-        let alg: dsigner_types::SignatureAlgorithm = request.alg().try_into()?;
+        let alg = parse_signature_algorithm(request.alg)?;
         let app_args: dsigner_types::ApplicationArgs = request
             .app_args
             .clone()
@@ -115,6 +137,10 @@ impl From<DSignerSchemeManagerError> for Status {
             DSignerSchemeManagerError::SchemeError(DSignerSchemeError::AlgorithmNotSupported) => {
                 Status::invalid_argument("algorithm not supported by this scheme")
             }
+            DSignerSchemeManagerError::PolicyViolation(e)
+            | DSignerSchemeManagerError::SchemeError(DSignerSchemeError::PolicyViolation(e)) => {
+                e.into()
+            }
             DSignerSchemeManagerError::SchemeError(DSignerSchemeError::Other(e)) => {
                 Status::internal(e.to_string())
             }
@@ -122,10 +148,21 @@ impl From<DSignerSchemeManagerError> for Status {
     }
 }
 
+impl From<PolicyViolation> for Status {
+    fn from(e: PolicyViolation) -> Self {
+        match e {
+            PolicyViolation::RateLimited => Status::resource_exhausted(e.to_string()),
+            _ => Status::permission_denied(e.to_string()),
+        }
+    }
+}
+
 impl From<ParseProtoError> for Status {
     fn from(e: ParseProtoError) -> Self {
         match e {
-            ParseProtoError::UnspecifiedField(_) => Status::invalid_argument(e.to_string()),
+            ParseProtoError::UnspecifiedField(_) | ParseProtoError::UnsupportedApplication => {
+                Status::invalid_argument(e.to_string())
+            }
         }
     }
 }
@@ -202,4 +239,116 @@ mod tests {
             .await
             .unwrap();
     }
+
+    #[cfg(feature = "client")]
+    mod mutual_tls {
+        use super::*;
+        use crate::proto_types::d_signer_service_client::DSignerServiceClient;
+        use crate::server::grpc::auth::{AuthMethod, Authenticator};
+        use rcgen::{
+            BasicConstraints, CertificateParams, CertifiedKey, DnType, ExtendedKeyUsagePurpose,
+            IsCa, KeyPair,
+        };
+        use tokio_stream::wrappers::TcpListenerStream;
+        use tonic::service::Interceptor;
+        use tonic::transport::{
+            Certificate, Channel, ClientTlsConfig, Identity, Server, ServerTlsConfig,
+        };
+
+        fn ca() -> CertifiedKey {
+            let key_pair = KeyPair::generate().unwrap();
+            let mut params = CertificateParams::new(vec![]).unwrap();
+            params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+            params
+                .distinguished_name
+                .push(DnType::CommonName, "dsigner test ca");
+            let cert = params.self_signed(&key_pair).unwrap();
+            CertifiedKey { cert, key_pair }
+        }
+
+        fn leaf(
+            ca: &CertifiedKey,
+            common_name: &str,
+            purpose: ExtendedKeyUsagePurpose,
+        ) -> Identity {
+            let key_pair = KeyPair::generate().unwrap();
+            let mut params = CertificateParams::new(vec!["localhost".to_owned()]).unwrap();
+            params
+                .distinguished_name
+                .push(DnType::CommonName, common_name);
+            params.extended_key_usages = vec![purpose];
+            let cert = params.signed_by(&key_pair, &ca.cert, &ca.key_pair).unwrap();
+            Identity::from_pem(cert.pem(), key_pair.serialize_pem())
+        }
+
+        #[tokio::test]
+        async fn client_certificate_identity() {
+            let ca = ca();
+            let server_identity = leaf(&ca, "dsigner", ExtendedKeyUsagePurpose::ServerAuth);
+            let client_identity = leaf(&ca, "verifier", ExtendedKeyUsagePurpose::ClientAuth);
+            let ca_cert = Certificate::from_pem(ca.cert.pem());
+
+            // Record the identity of the clients
+            let (tx_identity, rx_identity) = std::sync::mpsc::channel();
+            let mut authenticator = Authenticator::new().with_mutual_tls();
+            let interceptor = move |req: Request<()>| -> Result<Request<()>, Status> {
+                let req = authenticator.call(req)?;
+                tx_identity
+                    .send(ClientIdentity::from_request(&req))
+                    .unwrap();
+                Ok(req)
+            };
+
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            let service = DSignerServiceImpl::new(Arc::new(DSignerSchemeManager::new()));
+            let tls = ServerTlsConfig::new()
+                .identity(server_identity)
+                .client_ca_root(ca_cert.clone());
+            tokio::spawn(
+                Server::builder()
+                    .tls_config(tls)
+                    .unwrap()
+                    .add_service(DSignerServiceServer::with_interceptor(service, interceptor))
+                    .serve_with_incoming(TcpListenerStream::new(listener)),
+            );
+
+            let client_tls = ClientTlsConfig::new()
+                .ca_certificate(ca_cert)
+                .domain_name("localhost");
+            let endpoint = Channel::from_shared(format!("https://{addr}")).unwrap();
+
+            // Clients with a certificate signed by the CA are authenticated
+            let channel = endpoint
+                .clone()
+                .tls_config(client_tls.clone().identity(client_identity))
+                .unwrap()
+                .connect()
+                .await
+                .unwrap();
+            let schemes = DSignerServiceClient::new(channel)
+                .list_schemes(())
+                .await
+                .unwrap();
+            assert!(schemes.into_inner().schemes.is_empty());
+            assert_eq!(
+                rx_identity.try_recv().unwrap(),
+                ClientIdentity {
+                    id: "verifier".to_owned(),
+                    method: AuthMethod::MutualTls,
+                }
+            );
+
+            // Clients without certificate are rejected during the handshake
+            let res = match endpoint.tls_config(client_tls).unwrap().connect().await {
+                Ok(channel) => DSignerServiceClient::new(channel)
+                    .list_schemes(())
+                    .await
+                    .map(|_| ()),
+                Err(e) => Err(Status::unavailable(e.to_string())),
+            };
+            assert!(res.is_err());
+            assert!(rx_identity.try_recv().is_err());
+        }
+    }
 }
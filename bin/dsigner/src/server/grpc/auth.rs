@@ -0,0 +1,398 @@
+//! Authentication of the clients of the dsigner grpc service.
+//!
+//! Clients can authenticate with:
+//!  - an API key, sent through the `x-api-key` metadata,
+//!  - a JWT, sent through the `authorization` metadata as a bearer token, whose subject is the
+//!    client identity,
+//!  - a client certificate when the service uses mutual TLS, whose subject common name is the
+//!    client identity.
+//!
+//! The [`Authenticator`] is used as a tonic interceptor, and inserts the [`ClientIdentity`] in
+//! the extensions of the requests.
+
+pub use crate::proto_types::API_KEY_METADATA;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tonic::service::Interceptor;
+use tonic::{Request, Status};
+
+/// Identity of a client of the service.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ClientIdentity {
+    pub id: String,
+    pub method: AuthMethod,
+}
+
+/// How a client was authenticated.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AuthMethod {
+    ApiKey,
+    Jwt,
+    MutualTls,
+    /// The client did not authenticate, and is identified by its address if available.
+    Anonymous,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum AuthConfigError {
+    #[error("invalid API key hash, expected a hex-encoded sha256 digest")]
+    InvalidApiKeyHash,
+
+    #[error("invalid JWT decoding key")]
+    JwtKey(#[from] jsonwebtoken::errors::Error),
+}
+
+/// Clients registered with an API key.
+#[derive(Clone, Default, Debug, Deserialize)]
+pub struct ApiKeysConfig {
+    #[serde(default)]
+    pub clients: Vec<ApiKeyClient>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ApiKeyClient {
+    /// Identity of the client
+    pub id: String,
+    /// Hex-encoded sha256 digest of the API key, such that keys are not stored in plaintext
+    pub api_key_sha256: String,
+}
+
+/// Authenticates the requests of the clients, and attach their identity to the requests.
+#[derive(Clone, Default)]
+pub struct Authenticator {
+    // Map from the digest of the API keys to the client ids
+    api_keys: Arc<HashMap<[u8; 32], String>>,
+    jwt: Option<Arc<(DecodingKey, Validation)>>,
+    mutual_tls: bool,
+    allow_anonymous: bool,
+}
+
+#[derive(Deserialize)]
+struct JwtClaims {
+    sub: String,
+}
+
+impl Authenticator {
+    /// Create an authenticator that rejects all the requests.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Authenticate clients using API keys.
+    pub fn with_api_keys(mut self, config: ApiKeysConfig) -> Result<Self, AuthConfigError> {
+        let api_keys = config
+            .clients
+            .into_iter()
+            .map(|client| {
+                let digest: [u8; 32] = hex::decode(client.api_key_sha256.trim_start_matches("0x"))
+                    .ok()
+                    .and_then(|digest| digest.try_into().ok())
+                    .ok_or(AuthConfigError::InvalidApiKeyHash)?;
+                Ok((digest, client.id))
+            })
+            .collect::<Result<_, AuthConfigError>>()?;
+
+        self.api_keys = Arc::new(api_keys);
+        Ok(self)
+    }
+
+    /// Authenticate clients using JWTs signed with an HMAC secret (HS256).
+    pub fn with_jwt_secret(
+        self,
+        secret: &[u8],
+        issuer: Option<&str>,
+        audience: Option<&str>,
+    ) -> Self {
+        self.with_jwt(
+            DecodingKey::from_secret(secret),
+            Algorithm::HS256,
+            issuer,
+            audience,
+        )
+    }
+
+    /// Authenticate clients using JWTs signed with a private key, whose PEM-encoded public key
+    /// is specified. RSA (RS256), ECDSA (ES256) and Ed25519 (EdDSA) keys are supported.
+    pub fn with_jwt_public_key(
+        self,
+        alg: Algorithm,
+        public_key_pem: &[u8],
+        issuer: Option<&str>,
+        audience: Option<&str>,
+    ) -> Result<Self, AuthConfigError> {
+        let key = match alg {
+            Algorithm::RS256 | Algorithm::RS384 | Algorithm::RS512 => {
+                DecodingKey::from_rsa_pem(public_key_pem)?
+            }
+            Algorithm::ES256 | Algorithm::ES384 => DecodingKey::from_ec_pem(public_key_pem)?,
+            Algorithm::EdDSA => DecodingKey::from_ed_pem(public_key_pem)?,
+            _ => Err(jsonwebtoken::errors::Error::from(
+                jsonwebtoken::errors::ErrorKind::InvalidAlgorithm,
+            ))?,
+        };
+
+        Ok(self.with_jwt(key, alg, issuer, audience))
+    }
+
+    fn with_jwt(
+        mut self,
+        key: DecodingKey,
+        alg: Algorithm,
+        issuer: Option<&str>,
+        audience: Option<&str>,
+    ) -> Self {
+        let mut validation = Validation::new(alg);
+        if let Some(issuer) = issuer {
+            validation.set_issuer(&[issuer]);
+        }
+        match audience {
+            Some(audience) => validation.set_audience(&[audience]),
+            None => validation.validate_aud = false,
+        }
+
+        self.jwt = Some(Arc::new((key, validation)));
+        self
+    }
+
+    /// Authenticate clients using the common name of the certificate they presented during the
+    /// mutual TLS handshake.
+    pub fn with_mutual_tls(mut self) -> Self {
+        self.mutual_tls = true;
+        self
+    }
+
+    /// Accept the requests of clients that do not provide any credentials.
+    pub fn allow_anonymous(mut self) -> Self {
+        self.allow_anonymous = true;
+        self
+    }
+
+    /// Authenticate the client that issued a request. Requests with invalid credentials are
+    /// always rejected, even if anonymous clients are allowed.
+    pub fn authenticate<T>(&self, request: &Request<T>) -> Result<ClientIdentity, Status> {
+        if let Some(api_key) = request.metadata().get(API_KEY_METADATA) {
+            let digest: [u8; 32] = Sha256::digest(api_key.as_bytes()).into();
+            let id = self
+                .api_keys
+                .get(&digest)
+                .ok_or_else(|| Status::unauthenticated("invalid API key"))?;
+
+            return Ok(ClientIdentity {
+                id: id.to_owned(),
+                method: AuthMethod::ApiKey,
+            });
+        }
+
+        if let Some(authorization) = request.metadata().get("authorization") {
+            let Some((key, validation)) = self.jwt.as_deref() else {
+                Err(Status::unauthenticated("JWT authentication not supported"))?
+            };
+
+            let token = authorization
+                .to_str()
+                .ok()
+                .and_then(|v| v.strip_prefix("Bearer "))
+                .ok_or_else(|| Status::unauthenticated("invalid authorization metadata"))?;
+            let claims = jsonwebtoken::decode::<JwtClaims>(token, key, validation)
+                .map_err(|e| Status::unauthenticated(format!("invalid JWT: {e}")))?
+                .claims;
+
+            return Ok(ClientIdentity {
+                id: claims.sub,
+                method: AuthMethod::Jwt,
+            });
+        }
+
+        if self.mutual_tls
+            && let Some(id) = Self::peer_common_name(request)
+        {
+            return Ok(ClientIdentity {
+                id,
+                method: AuthMethod::MutualTls,
+            });
+        }
+
+        if self.allow_anonymous {
+            return Ok(ClientIdentity::anonymous(request));
+        }
+
+        Err(Status::unauthenticated("missing credentials"))
+    }
+
+    /// Common name of the certificate presented by the peer, if any.
+    fn peer_common_name<T>(request: &Request<T>) -> Option<String> {
+        let certs = request.peer_certs()?;
+        let (_, cert) = x509_parser::parse_x509_certificate(certs.first()?).ok()?;
+        let cn = cert.subject().iter_common_name().next()?;
+        cn.as_str().ok().map(str::to_owned)
+    }
+}
+
+impl ClientIdentity {
+    /// Identity of a client that did not authenticate.
+    pub fn anonymous<T>(request: &Request<T>) -> Self {
+        Self {
+            id: request
+                .remote_addr()
+                .map(|addr| addr.ip().to_string())
+                .unwrap_or_else(|| crate::server::ANONYMOUS_CLIENT.to_owned()),
+            method: AuthMethod::Anonymous,
+        }
+    }
+
+    /// Obtain the identity attached to a request by the [`Authenticator`], or an anonymous
+    /// identity if the service does not authenticate clients.
+    pub fn from_request<T>(request: &Request<T>) -> Self {
+        request
+            .extensions()
+            .get::<ClientIdentity>()
+            .cloned()
+            .unwrap_or_else(|| Self::anonymous(request))
+    }
+}
+
+impl Interceptor for Authenticator {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        let identity = self.authenticate(&request).inspect_err(|e| {
+            tracing::warn!(
+                remote_addr = ?request.remote_addr(),
+                error = e.message(),
+                "Rejected unauthenticated request"
+            );
+        })?;
+
+        request.extensions_mut().insert(identity);
+        Ok(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{EncodingKey, Header};
+    use serde::Serialize;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[derive(Serialize)]
+    struct Claims<'a> {
+        sub: &'a str,
+        iss: &'a str,
+        exp: u64,
+    }
+
+    fn jwt(secret: &[u8], sub: &str, iss: &str) -> String {
+        let exp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            + 60;
+        jsonwebtoken::encode(
+            &Header::default(),
+            &Claims { sub, iss, exp },
+            &EncodingKey::from_secret(secret),
+        )
+        .unwrap()
+    }
+
+    fn request(key: &'static str, value: &str) -> Request<()> {
+        let mut request = Request::new(());
+        request.metadata_mut().insert(key, value.parse().unwrap());
+        request
+    }
+
+    #[test]
+    fn api_keys() {
+        let authenticator = Authenticator::new()
+            .with_api_keys(ApiKeysConfig {
+                clients: vec![ApiKeyClient {
+                    id: "verifier".to_owned(),
+                    api_key_sha256: hex::encode(Sha256::digest(b"secret-key")),
+                }],
+            })
+            .unwrap();
+
+        assert_eq!(
+            authenticator
+                .authenticate(&request(API_KEY_METADATA, "secret-key"))
+                .unwrap(),
+            ClientIdentity {
+                id: "verifier".to_owned(),
+                method: AuthMethod::ApiKey,
+            }
+        );
+        assert!(
+            authenticator
+                .authenticate(&request(API_KEY_METADATA, "wrong-key"))
+                .is_err()
+        );
+        assert!(authenticator.authenticate(&Request::new(())).is_err());
+
+        assert!(matches!(
+            Authenticator::new().with_api_keys(ApiKeysConfig {
+                clients: vec![ApiKeyClient {
+                    id: "verifier".to_owned(),
+                    api_key_sha256: "0x1234".to_owned(),
+                }],
+            }),
+            Err(AuthConfigError::InvalidApiKeyHash)
+        ));
+    }
+
+    #[test]
+    fn jwts() {
+        let authenticator =
+            Authenticator::new().with_jwt_secret(b"jwt-secret", Some("dcipher"), None);
+
+        let token = jwt(b"jwt-secret", "verifier", "dcipher");
+        assert_eq!(
+            authenticator
+                .authenticate(&request("authorization", &format!("Bearer {token}")))
+                .unwrap(),
+            ClientIdentity {
+                id: "verifier".to_owned(),
+                method: AuthMethod::Jwt,
+            }
+        );
+
+        // Invalid signature, issuer, or format
+        for token in [
+            format!("Bearer {}", jwt(b"other-secret", "verifier", "dcipher")),
+            format!("Bearer {}", jwt(b"jwt-secret", "verifier", "other")),
+            token,
+        ] {
+            assert!(
+                authenticator
+                    .authenticate(&request("authorization", &token))
+                    .is_err()
+            );
+        }
+    }
+
+    #[test]
+    fn anonymous_clients() {
+        assert!(
+            Authenticator::new()
+                .authenticate(&Request::new(()))
+                .is_err()
+        );
+
+        let authenticator = Authenticator::new().allow_anonymous();
+        assert_eq!(
+            authenticator
+                .authenticate(&Request::new(()))
+                .unwrap()
+                .method,
+            AuthMethod::Anonymous
+        );
+
+        // Invalid credentials are rejected nonetheless
+        assert!(
+            authenticator
+                .authenticate(&request(API_KEY_METADATA, "wrong-key"))
+                .is_err()
+        );
+    }
+}
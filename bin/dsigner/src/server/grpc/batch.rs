@@ -0,0 +1,260 @@
+//! Implementation of the batch & streaming dsigner grpc service.
+
+use crate::proto_types::d_signer_batch_service_server::DSignerBatchService;
+use crate::proto_types::signature_result::Result as SignatureResultEnum;
+use crate::proto_types::{
+    BatchGetSignaturesRequest, BatchGetSignaturesResponse, GetSignatureResponse, SignatureError,
+    SignatureResult, StreamSignatureRequest, StreamSignatureResponse,
+};
+use crate::server::grpc::DSignerServiceImpl;
+use crate::server::grpc::auth::ClientIdentity;
+use futures_util::StreamExt;
+use futures_util::stream::FuturesUnordered;
+use std::pin::Pin;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status, Streaming};
+
+/// Maximum number of requests in a single batch.
+const MAX_BATCH_SIZE: usize = 1024;
+
+/// Maximum number of pending requests per stream, after which the stream is not polled for new
+/// requests until a signature is obtained.
+const MAX_PENDING_STREAM_REQUESTS: usize = 1024;
+
+type SignaturesStream =
+    Pin<Box<dyn futures_util::Stream<Item = Result<StreamSignatureResponse, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl DSignerBatchService for DSignerServiceImpl {
+    async fn batch_get_signatures(
+        &self,
+        request: Request<BatchGetSignaturesRequest>,
+    ) -> Result<Response<BatchGetSignaturesResponse>, Status> {
+        let client = ClientIdentity::from_request(&request);
+        let requests = request.into_inner().requests;
+        if requests.len() > MAX_BATCH_SIZE {
+            Err(Status::invalid_argument(format!(
+                "batch exceeds the maximum size of {MAX_BATCH_SIZE} requests"
+            )))?
+        }
+
+        tracing::debug!(
+            client_id = client.id,
+            requests_count = requests.len(),
+            "Received batch of signature requests"
+        );
+
+        // Requests are submitted concurrently such that the signers can process them in batches
+        let results = futures_util::future::join_all(
+            requests
+                .into_iter()
+                .map(|req| async { self.sign(&client, req).await.into() }),
+        )
+        .await;
+
+        Ok(BatchGetSignaturesResponse { results }.into())
+    }
+
+    type StreamSignaturesStream = SignaturesStream;
+
+    async fn stream_signatures(
+        &self,
+        request: Request<Streaming<StreamSignatureRequest>>,
+    ) -> Result<Response<Self::StreamSignaturesStream>, Status> {
+        let client = ClientIdentity::from_request(&request);
+        let mut requests = request.into_inner();
+        let (tx, rx) = tokio::sync::mpsc::channel(MAX_PENDING_STREAM_REQUESTS);
+
+        let service = self.clone();
+        tokio::task::spawn(async move {
+            let sign = |req: StreamSignatureRequest| {
+                let (service, client) = (&service, &client);
+                async move {
+                    let result = match req.request {
+                        Some(request) => service.sign(client, request).await,
+                        None => Err(Status::invalid_argument("signature request required")),
+                    };
+
+                    StreamSignatureResponse {
+                        request_id: req.request_id,
+                        result: Some(result.into()),
+                    }
+                }
+            };
+
+            let mut pending = FuturesUnordered::new();
+            let mut requests_closed = false;
+            loop {
+                tokio::select! {
+                    req = requests.message(), if !requests_closed && pending.len() < MAX_PENDING_STREAM_REQUESTS => {
+                        match req {
+                            Ok(Some(req)) => pending.push(sign(req)),
+                            Ok(None) => requests_closed = true,
+                            Err(e) => {
+                                tracing::warn!(client_id = client.id, error = ?e, "Failed to receive signature request from stream");
+                                requests_closed = true;
+                            }
+                        }
+                    }
+
+                    Some(response) = pending.next(), if !pending.is_empty() => {
+                        if tx.send(Ok(response)).await.is_err() {
+                            tracing::debug!(client_id = client.id, "Client closed the signatures stream");
+                            break;
+                        }
+                    }
+
+                    else => break,
+                }
+            }
+        });
+
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
+    }
+}
+
+impl From<Result<GetSignatureResponse, Status>> for SignatureResult {
+    fn from(value: Result<GetSignatureResponse, Status>) -> Self {
+        let result = match value {
+            Ok(signature) => SignatureResultEnum::Signature(signature),
+            Err(status) => SignatureResultEnum::Error(SignatureError {
+                code: status.code() as i32,
+                message: status.message().to_owned(),
+            }),
+        };
+
+        SignatureResult {
+            result: Some(result),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "client"))]
+mod tests {
+    use super::*;
+    use crate::client::DSignerClient;
+    use crate::proto_types::d_signer_batch_service_server::DSignerBatchServiceServer;
+    use crate::proto_types::d_signer_service_server::DSignerServiceServer;
+    use crate::proto_types::{
+        ApplicationAnyArgs, ApplicationArgs, ApplicationArgsEnum, GetSignatureRequest,
+        SignatureAlgorithm,
+    };
+    use crate::server::DSignerSchemeManager;
+    use ark_ec::AffineRepr;
+    use ark_ff::MontFp;
+    use dcipher_network::transports::in_memory::MemoryNetwork;
+    use dcipher_signer::bls::{BlsPairingSigner, BlsThresholdSigner};
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use tokio_stream::wrappers::TcpListenerStream;
+    use tonic::Code;
+    use tonic::transport::Server;
+
+    async fn start_service() -> DSignerClient {
+        let sk: ark_bn254::Fr =
+            MontFp!("7685086713915354683875500702831995067084988389812060097318430034144315778947");
+        let signer = BlsThresholdSigner::new(
+            BlsPairingSigner::<ark_bn254::Bn254>::new(sk),
+            1,
+            1,
+            1,
+            HashMap::from([(1, (ark_bn254::G1Affine::generator() * sk).into())]),
+            HashMap::from([(1, (ark_bn254::G2Affine::generator() * sk).into())]),
+        );
+        let transport = MemoryNetwork::get_transports(1u16..2u16)
+            .pop_front()
+            .unwrap();
+        let (_, scheme) = signer.run(transport);
+
+        let manager =
+            DSignerSchemeManager::default().push_scheme("test-bn254".into(), Arc::new(scheme));
+        let service = DSignerServiceImpl::new(Arc::new(manager));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(
+            Server::builder()
+                .add_service(DSignerServiceServer::new(service.clone()))
+                .add_service(DSignerBatchServiceServer::new(service))
+                .serve_with_incoming(TcpListenerStream::new(listener)),
+        );
+
+        DSignerClient::connect(format!("http://{addr}"))
+            .await
+            .unwrap()
+    }
+
+    fn request(scheme_id: &str, message: &'static [u8]) -> GetSignatureRequest {
+        GetSignatureRequest {
+            scheme_id: scheme_id.to_owned(),
+            alg: SignatureAlgorithm::Bn254SigOnG1Keccak256.into(),
+            message: message.into(),
+            app_args: Some(ApplicationArgs {
+                args: Some(ApplicationArgsEnum::Any(ApplicationAnyArgs {
+                    dst_suffix: "batch-test".to_owned(),
+                })),
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn batch_signatures() {
+        let mut client = start_service().await;
+        let expected = client
+            .get_signature(request("test-bn254", b"message 1"))
+            .await
+            .unwrap();
+
+        let results = client
+            .batch_get_signatures(vec![
+                request("test-bn254", b"message 1"),
+                request("unknown-scheme", b"message 1"),
+                request("test-bn254", b"message 2"),
+            ])
+            .await
+            .unwrap();
+
+        // Failed requests do not prevent the others from being signed
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap(), &expected);
+        assert!(results[1].is_err());
+        assert!(results[2].is_ok());
+
+        let too_large = vec![request("test-bn254", b"message"); MAX_BATCH_SIZE + 1];
+        let err = client.batch_get_signatures(too_large).await.unwrap_err();
+        assert_eq!(err.code(), Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn stream_signatures() {
+        let mut client = start_service().await;
+        let expected = client
+            .get_signature(request("test-bn254", b"message 1"))
+            .await
+            .unwrap();
+
+        let requests = futures_util::stream::iter([
+            request("test-bn254", b"message 1"),
+            request("unknown-scheme", b"message 1"),
+            request("test-bn254", b"message 2"),
+        ]);
+        let mut results: Vec<_> = client
+            .stream_signatures(requests)
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        // Responses may be received out of order
+        results.sort_by_key(|(request_id, _)| *request_id);
+        assert_eq!(
+            results.iter().map(|(id, _)| *id).collect::<Vec<_>>(),
+            vec![0, 1, 2]
+        );
+        assert_eq!(results[0].1.as_ref().unwrap(), &expected);
+        assert!(results[1].1.is_err());
+        assert!(results[2].1.is_ok());
+    }
+}
@@ -0,0 +1,34 @@
+-- Trades attempted by the solver
+CREATE TABLE IF NOT EXISTS trades (
+    request_id BLOB PRIMARY KEY NOT NULL,
+    src_chain_id VARCHAR(20) NOT NULL, -- can't completely store u64 in INTEGER. 20 digits for int repr.
+    dst_chain_id VARCHAR(20) NOT NULL,
+    token_in BLOB NOT NULL,
+    token_out BLOB NOT NULL,
+    amount_in TEXT NOT NULL, -- decimal repr of uint256
+    amount_out TEXT NOT NULL,
+    solver_fee TEXT NOT NULL,
+    solver_refund_amount TEXT NOT NULL,
+    created_at DATETIME NOT NULL,
+    refunded_at DATETIME, -- set once the solver has been refunded on the source chain
+    refund_usd REAL
+);
+
+-- Attempts at fulfilling trades
+CREATE TABLE IF NOT EXISTS executions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    request_id BLOB NOT NULL,
+    submitted_at DATETIME NOT NULL,
+    status TEXT NOT NULL, -- either 'fulfilled' or 'failed'
+    tx_hash BLOB,
+    block_number VARCHAR(20),
+    gas_used VARCHAR(20),
+    effective_gas_price TEXT, -- decimal repr of uint128
+    amount_out_usd REAL,
+    gas_cost_usd REAL,
+    error TEXT,
+    FOREIGN KEY (request_id) REFERENCES trades(request_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS executions_submitted_at ON executions(submitted_at);
+CREATE INDEX IF NOT EXISTS trades_refunded_at ON trades(refunded_at);
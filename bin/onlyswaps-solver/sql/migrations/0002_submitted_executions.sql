@@ -0,0 +1,3 @@
+-- Executions are recorded as 'submitted' along with their tx_hash before their receipt is known, and
+-- then updated to 'fulfilled', 'reverted' or 'failed'
+CREATE INDEX IF NOT EXISTS executions_status ON executions(status);
@@ -1,18 +1,28 @@
-use crate::config::ProfitabilityConfig;
+use crate::config::{
+    AppConfig, CompetitionPolicy, PriceFeedConfig, ProfitabilityConfig, TradeSelectionConfig,
+};
 use crate::executor::TradeExecutor;
 use crate::fee_adapter::DefaultFeeAdapter;
+use crate::journal::TradeJournal;
+use crate::mempool::MempoolWatcher;
 use crate::model::{RequestId, SolverEvent};
 use crate::network::Network;
+use crate::price_feed::ErasedTokenPriceFeed;
+use crate::price_feed::chainlink::ChainlinkPriceFeed;
 use crate::price_feed::coingecko::CoinGeckoClient;
+use crate::price_feed::composite::CompositePriceFeed;
+use crate::price_feed::fixed::StaticPriceFeed;
+use crate::price_feed::json_http::JsonHttpPriceFeed;
 use crate::profitability::{
     AlwaysProfitable, ErasedProfitabilityEstimator, StdProfitabilityEstimator,
 };
+use crate::rebalancer::{OnlySwapsBridgeAdapter, Rebalancer};
+use crate::selection::KnapsackSelection;
 use crate::solver::Solver;
 use alloy::providers::DynProvider;
 use alloy::signers::local::PrivateKeySigner;
 use anyhow::Context;
 use axum::http::{Request, Response};
-use config::timeout::TimeoutConfig;
 use futures::{Stream, StreamExt};
 use moka::future::Cache;
 use omnievent::proto_types::omni_event_service_client::OmniEventServiceClient;
@@ -36,10 +46,22 @@ impl App {
         signer: PrivateKeySigner,
         client: OnlySwapsClient,
         networks: HashMap<u64, Network<DynProvider>>,
-        timeout: &TimeoutConfig,
-        profitability: &ProfitabilityConfig,
+        config: &AppConfig,
         oes: OmniEventBoxService,
     ) -> anyhow::Result<()> {
+        // The profits compared by the knapsack selection must be in a common unit
+        anyhow::ensure!(
+            !matches!(
+                (&config.profitability, &config.trade_selection),
+                (
+                    ProfitabilityConfig::AlwaysProfitable,
+                    TradeSelectionConfig::Knapsack { .. }
+                )
+            ),
+            "knapsack trade selection requires a price feed, it cannot be used with always-profitable"
+        );
+
+        let timeout = &config.timeout;
         let mut omnievent_client = OmniEventServiceClient::new(oes);
         let swap_stream = swap_requested_stream(&mut omnievent_client, &networks).await?;
 
@@ -56,10 +78,56 @@ impl App {
         let chain_ticker = per_chain_ticker(networks.values()).map(SolverEvent::Poll);
         let mut stream = Box::pin(futures::stream::select(event_ticker, chain_ticker));
 
-        let pe = get_profitability_estimator(profitability).await?;
+        let price_feed = get_profitability_price_feed(&config.profitability, &networks).await?;
+        let pe = match &price_feed {
+            Some(price_feed) => ErasedProfitabilityEstimator::from_estimator(
+                StdProfitabilityEstimator::new(price_feed.clone()),
+            ),
+            None => ErasedProfitabilityEstimator::from_estimator(AlwaysProfitable),
+        };
         let fee_estimator = DefaultFeeAdapter::new();
+        let own_address = signer.address();
         let mut solver = Solver::new(&networks, &fee_estimator).await?;
-        let executor = TradeExecutor::new(signer, client, &networks, pe).await?;
+        if let TradeSelectionConfig::Knapsack { gas_estimate } = &config.trade_selection {
+            solver = solver.with_selection_strategy(KnapsackSelection::new(
+                pe.clone(),
+                &networks,
+                *gas_estimate,
+            ));
+        }
+        let rebalancer = match &config.rebalancing {
+            Some(rebalancing) => {
+                // don't undo our own rebalancing by fulfilling it
+                solver = solver.with_ignored_senders([own_address]);
+                let bridge = OnlySwapsBridgeAdapter::new(client.clone());
+                Some(Rebalancer::new(
+                    &networks,
+                    bridge,
+                    rebalancing,
+                    own_address,
+                )?)
+            }
+            None => None,
+        };
+        let mut executor = TradeExecutor::new(signer, client, &networks, pe)
+            .await?
+            .with_competition_config(config.competition.clone());
+        if config.competition.policy != CompetitionPolicy::Ignore {
+            executor = executor.with_pending_fulfilments(MempoolWatcher::new(&networks));
+        }
+        let journal = match &config.journal {
+            Some(journal_config) => {
+                let mut journal = TradeJournal::open(&journal_config.path)
+                    .await
+                    .context("failed to open trade journal")?;
+                if let Some(price_feed) = price_feed {
+                    journal = journal.with_price_feed(price_feed);
+                }
+                executor = executor.with_journal(journal.clone());
+                Some((journal, journal_config.refund_poll_interval))
+            }
+            None => None,
+        };
 
         // we pull new chain state every block, so inflight requests may not have been
         // completed yet, so we don't want to attempt to execute them again and waste gas.
@@ -69,18 +137,46 @@ impl App {
             .time_to_live(timeout.request_timeout.mul(2))
             .build();
 
-        while let Some(event) = stream.next().await {
-            let chain_id = event.chain_id();
-            let trades = solver.solve(chain_id, &inflight_requests).await?;
-            if !trades.is_empty() {
-                tracing::info!(chain_id, trade_count = trades.len(), "executing trades ");
-                executor
-                    .execute(trades, &mut inflight_requests, timeout)
-                    .await;
+        let solve_loop = async {
+            while let Some(event) = stream.next().await {
+                let chain_id = event.chain_id();
+                let trades = solver.solve(chain_id, &inflight_requests).await?;
+                if !trades.is_empty() {
+                    tracing::info!(chain_id, trade_count = trades.len(), "executing trades ");
+                    executor
+                        .execute(trades, &mut inflight_requests, timeout)
+                        .await;
+                }
             }
-        }
 
-        anyhow::bail!("stream of blocks ended unexpectedly");
+            Err::<(), _>(anyhow::anyhow!("stream of blocks ended unexpectedly"))
+        };
+
+        // optional tasks that are not configured never complete
+        let rebalancer_loop = async {
+            match rebalancer {
+                Some(rebalancer) => rebalancer.run().await,
+                None => std::future::pending().await,
+            }
+        };
+        let journal_loop = async {
+            match &journal {
+                Some((journal, interval)) => journal.track_trades(&networks, *interval).await,
+                None => std::future::pending().await,
+            }
+        };
+
+        tokio::select! {
+            res = solve_loop => res,
+            res = rebalancer_loop => match res {
+                Ok(_) => Err(anyhow::anyhow!("rebalancer stopped unexpectedly")),
+                Err(e) => Err(anyhow::anyhow!("rebalancer stopped unexpectedly: {}", e)),
+            },
+            res = journal_loop => match res {
+                Ok(_) => Err(anyhow::anyhow!("trade tracker stopped unexpectedly")),
+                Err(e) => Err(anyhow::anyhow!("trade tracker stopped unexpectedly: {}", e)),
+            },
+        }
     }
 }
 
@@ -138,14 +234,38 @@ async fn swap_requested_stream(
     Ok(event_stream)
 }
 
-async fn get_profitability_estimator(
+/// Price feed used to check the profitability of trades, if any.
+async fn get_profitability_price_feed(
     profitability: &ProfitabilityConfig,
-) -> anyhow::Result<ErasedProfitabilityEstimator> {
-    Ok(match profitability {
-        ProfitabilityConfig::AlwaysProfitable => {
-            ErasedProfitabilityEstimator::from_estimator(AlwaysProfitable)
+    networks: &HashMap<u64, Network<DynProvider>>,
+) -> anyhow::Result<Option<ErasedTokenPriceFeed>> {
+    let price_feed = match profitability {
+        ProfitabilityConfig::AlwaysProfitable => return Ok(None),
+        ProfitabilityConfig::CheckWithPriceFeed(config) => get_price_feed(config, networks).await?,
+        ProfitabilityConfig::CheckWithCompositeFeed(config) => {
+            let mut sources = Vec::with_capacity(config.sources.len());
+            for source in &config.sources {
+                sources.push(get_price_feed(source, networks).await?);
+            }
+
+            ErasedTokenPriceFeed::from_price_feed(CompositePriceFeed::new(
+                sources,
+                config.min_sources,
+                config.max_deviation,
+                config.cache_ttl,
+            ))
         }
-        ProfitabilityConfig::CheckWithCoinGecko { api_key, pro_api } => {
+    };
+
+    Ok(Some(price_feed))
+}
+
+async fn get_price_feed(
+    config: &PriceFeedConfig,
+    networks: &HashMap<u64, Network<DynProvider>>,
+) -> anyhow::Result<ErasedTokenPriceFeed> {
+    Ok(match config {
+        PriceFeedConfig::CoinGecko { api_key, pro_api } => {
             let mut builder = CoinGeckoClient::builder();
             if let Some(api_key) = api_key {
                 builder = builder.api_key(api_key.to_owned());
@@ -156,9 +276,20 @@ async fn get_profitability_estimator(
 
             let mut cg_price_feed = builder.build()?;
             cg_price_feed.init_chain_id_mapping().await?;
-            ErasedProfitabilityEstimator::from_estimator(StdProfitabilityEstimator::new(
-                cg_price_feed,
-            ))
+            ErasedTokenPriceFeed::from_price_feed(cg_price_feed)
+        }
+        PriceFeedConfig::Static(config) => ErasedTokenPriceFeed::from_price_feed(
+            StaticPriceFeed::from_config(config).context("failed to load static prices")?,
+        ),
+        PriceFeedConfig::JsonHttp(config) => {
+            ErasedTokenPriceFeed::from_price_feed(JsonHttpPriceFeed::new(config)?)
+        }
+        PriceFeedConfig::Chainlink(config) => {
+            let providers = networks
+                .iter()
+                .map(|(&chain_id, network)| (chain_id, network.provider.clone()))
+                .collect();
+            ErasedTokenPriceFeed::from_price_feed(ChainlinkPriceFeed::new(config, providers)?)
         }
     })
 }
@@ -1,8 +1,10 @@
-use alloy::primitives::Address;
+use alloy::primitives::{Address, U256};
+use chrono::{DateTime, Utc};
 use clap::{Parser, Subcommand};
 use config::agent::AgentConfig;
 use config::timeout::TimeoutConfig;
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::time::Duration;
 
 #[derive(Parser, Debug)]
@@ -32,6 +34,18 @@ pub enum Command {
     /// Setup the solver by submitting token approvals
     #[command(about = "Setup the solver")]
     Setup,
+
+    /// Report the profit and loss of the solver from its trade journal
+    #[command(about = "Report the profit and loss of the solver")]
+    Report {
+        /// Start of the reporting period (RFC 3339), defaults to the first trade
+        #[arg(long)]
+        from: Option<DateTime<Utc>>,
+
+        /// End of the reporting period (RFC 3339), defaults to now
+        #[arg(long)]
+        to: Option<DateTime<Utc>>,
+    },
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -44,6 +58,12 @@ pub(crate) struct AppConfig {
     pub timeout: TimeoutConfig,
     #[serde(default)]
     pub profitability: ProfitabilityConfig,
+    #[serde(default)]
+    pub trade_selection: TradeSelectionConfig,
+    pub rebalancing: Option<RebalancingConfig>,
+    #[serde(default)]
+    pub competition: CompetitionConfig,
+    pub journal: Option<JournalConfig>,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -93,27 +113,392 @@ fn default_poll_interval() -> Duration {
 /// ```toml
 /// profitability = "always-profitable"
 /// ```
+///
+/// Other price feeds can be used instead of CoinGecko, see [`PriceFeedConfig`]. To protect against
+/// the outage of a single price feed, several of them can be combined:
+/// ```toml
+/// [profitability.composite]
+/// # optional, number of agreeing sources required to accept a price
+/// min_sources = 2
+/// # optional, maximum relative deviation of a source from the median price
+/// max_deviation = 0.05
+/// # optional, how long prices are cached for
+/// cache_ttl = "30s"
+///
+/// [[profitability.composite.sources]]
+/// coin-gecko = { pro_api = false }
+///
+/// [[profitability.composite.sources]]
+/// [profitability.composite.sources.chainlink]
+/// feeds = [
+///     { chain_id = 43113, aggregator = "0x5498BB86BC934c8D34FDA08E81D444153d0D06aD" },
+/// ]
+/// ```
 #[derive(Deserialize, Debug, Clone)]
 pub(crate) enum ProfitabilityConfig {
     #[serde(rename = "always-profitable")]
     AlwaysProfitable,
 
+    #[serde(rename = "composite")]
+    CheckWithCompositeFeed(CompositePriceFeedConfig),
+
+    #[serde(untagged)]
+    CheckWithPriceFeed(PriceFeedConfig),
+}
+
+impl Default for ProfitabilityConfig {
+    fn default() -> Self {
+        Self::CheckWithPriceFeed(PriceFeedConfig::CoinGecko {
+            api_key: None,
+            pro_api: false,
+        })
+    }
+}
+
+/// A source of token prices.
+///
+/// # Examples
+/// Fixed prices, e.g. for testnets, specified inline and / or in a separate toml or json file
+/// with the same structure:
+/// ```toml
+/// [profitability.static]
+/// path = "/data/prices.toml"
+/// native = [{ chain_id = 43113, usd = 20.0 }]
+/// tokens = [{ chain_id = 43113, address = "0x1b0F6cF6f3185872a581BD2B5a738EB52CCd4d76", usd = 1.0, decimals = 18 }]
+/// ```
+///
+/// Any HTTP API returning JSON, where `{chain_id}` and `{address}` are substituted in the urls,
+/// and values are located with JSON pointers:
+/// ```toml
+/// [profitability.json-http]
+/// native_url = "https://prices.example.com/{chain_id}/native"
+/// token_url = "https://prices.example.com/{chain_id}/{address}"
+/// price_pointer = "/usd"
+/// # optional
+/// decimals_pointer = "/decimals"
+/// # unix timestamp in seconds
+/// timestamp_pointer = "/updated_at"
+/// max_age = "5m"
+/// headers = { "x-api-key" = "my-key" }
+/// ```
+///
+/// Chainlink aggregators read through the configured networks, where a feed without a token
+/// prices the chain's native currency. The aggregator may live on another configured chain:
+/// ```toml
+/// [profitability.chainlink]
+/// max_age = "2h"
+/// feeds = [
+///     { chain_id = 43113, aggregator = "0x5498BB86BC934c8D34FDA08E81D444153d0D06aD" },
+///     { chain_id = 84532, token = "0x1b0F6cF6f3185872a581BD2B5a738EB52CCd4d76", aggregator = "0xd30e2101a97dcbAeBCBC04F14C3f624E67A35165", aggregator_chain_id = 43113 },
+/// ]
+/// ```
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) enum PriceFeedConfig {
     #[serde(rename = "coin-gecko")]
-    CheckWithCoinGecko {
+    CoinGecko {
         api_key: Option<String>,
         pro_api: bool,
     },
+
+    #[serde(rename = "static")]
+    Static(StaticPriceFeedConfig),
+
+    #[serde(rename = "json-http")]
+    JsonHttp(JsonHttpPriceFeedConfig),
+
+    #[serde(rename = "chainlink")]
+    Chainlink(ChainlinkPriceFeedConfig),
 }
 
-impl Default for ProfitabilityConfig {
+#[derive(Deserialize, Debug, Clone, Default)]
+pub(crate) struct StaticPriceFeedConfig {
+    pub path: Option<String>,
+    #[serde(flatten)]
+    pub prices: StaticPrices,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub(crate) struct StaticPrices {
+    #[serde(default)]
+    pub native: Vec<StaticNativePrice>,
+    #[serde(default)]
+    pub tokens: Vec<StaticTokenPrice>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct StaticNativePrice {
+    pub chain_id: u64,
+    pub usd: f64,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct StaticTokenPrice {
+    pub chain_id: u64,
+    pub address: Address,
+    pub usd: f64,
+    pub decimals: u8,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct JsonHttpPriceFeedConfig {
+    pub native_url: String,
+    pub token_url: String,
+    pub price_pointer: String,
+    pub decimals_pointer: Option<String>,
+    pub timestamp_pointer: Option<String>,
+    #[serde(with = "humantime_serde", default = "default_price_max_age")]
+    pub max_age: Duration,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct ChainlinkPriceFeedConfig {
+    #[serde(with = "humantime_serde", default = "default_chainlink_max_age")]
+    pub max_age: Duration,
+    pub feeds: Vec<ChainlinkFeedConfig>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct ChainlinkFeedConfig {
+    pub chain_id: u64,
+    pub token: Option<Address>,
+    pub aggregator: Address,
+    pub aggregator_chain_id: Option<u64>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct CompositePriceFeedConfig {
+    pub sources: Vec<PriceFeedConfig>,
+    #[serde(default = "default_composite_min_sources")]
+    pub min_sources: usize,
+    #[serde(default = "default_composite_max_deviation")]
+    pub max_deviation: f64,
+    #[serde(with = "humantime_serde", default = "default_price_cache_ttl")]
+    pub cache_ttl: Duration,
+}
+
+/// default maximum age of a price obtained from an http api
+fn default_price_max_age() -> Duration {
+    Duration::from_secs(5 * 60)
+}
+
+/// chainlink feeds are typically updated at least once per day, or after large price moves
+fn default_chainlink_max_age() -> Duration {
+    Duration::from_secs(25 * 60 * 60)
+}
+
+/// a single source is enough by default
+fn default_composite_min_sources() -> usize {
+    1
+}
+
+/// sources may deviate by up to 5% from the median price by default
+fn default_composite_max_deviation() -> f64 {
+    0.05
+}
+
+/// default duration for which prices are cached
+fn default_price_cache_ttl() -> Duration {
+    Duration::from_secs(30)
+}
+
+/// Configure how the solver selects the trades to fulfil when its balances cannot cover all of
+/// the pending requests.
+///
+/// # Examples
+/// By default, requests are fulfilled greedily in the order they are found. To maximise the total
+/// expected profit instead, the following config may be used. Since profits must be comparable
+/// across tokens, it requires a price feed, i.e., a profitability config other than
+/// `always-profitable`:
+/// ```toml
+/// [trade_selection.knapsack]
+/// # optional, gas used to relay tokens when estimating the profit of a trade
+/// gas_estimate = 400000
+/// ```
+#[derive(Deserialize, Debug, Clone, Default)]
+pub(crate) enum TradeSelectionConfig {
+    #[default]
+    #[serde(rename = "greedy")]
+    Greedy,
+
+    #[serde(rename = "knapsack")]
+    Knapsack {
+        #[serde(default = "default_relay_gas_estimate")]
+        gas_estimate: u64,
+    },
+}
+
+/// upper bound of the gas used to relay tokens with permit2
+fn default_relay_gas_estimate() -> u64 {
+    500_000
+}
+
+/// Configure the inventory rebalancer of the solver.
+///
+/// The rebalancer periodically compares the solver's balances against per-chain, per-token
+/// targets. When a chain drops below its `min`, tokens are moved back up to its `target` by
+/// requesting swaps from chains holding more than their `max` (which defaults to `target`).
+/// Targets sharing the same `asset` are considered fungible across chains.
+///
+/// # Examples
+/// ```toml
+/// [rebalancing]
+/// # only log the swaps that would be requested
+/// dry_run = true
+/// interval = "5m"
+///
+/// [[rebalancing.targets]]
+/// asset = "RUSD"
+/// chain_id = 43113
+/// token = "0x1b0F6cF6f3185872a581BD2B5a738EB52CCd4d76"
+/// target = "1000000000000000000000"
+/// min = "250000000000000000000"
+///
+/// [[rebalancing.targets]]
+/// asset = "RUSD"
+/// chain_id = 84532
+/// token = "0x1b0F6cF6f3185872a581BD2B5a738EB52CCd4d76"
+/// target = "1000000000000000000000"
+/// min = "250000000000000000000"
+/// max = "1500000000000000000000"
+/// ```
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct RebalancingConfig {
+    #[serde(default)]
+    pub dry_run: bool,
+    #[serde(with = "humantime_serde", default = "default_rebalancing_interval")]
+    pub interval: Duration,
+    /// How long to wait for a rebalancing swap to land before topping up the same chain again
+    #[serde(with = "humantime_serde", default = "default_rebalancing_cooldown")]
+    pub cooldown: Duration,
+    pub targets: Vec<InventoryTarget>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub(crate) struct InventoryTarget {
+    pub asset: String,
+    pub chain_id: u64,
+    pub token: Address,
+    pub target: U256,
+    pub min: U256,
+    pub max: Option<U256>,
+}
+
+impl InventoryTarget {
+    /// Balance above which the chain may give away tokens to other chains
+    pub fn max(&self) -> U256 {
+        self.max.unwrap_or(self.target)
+    }
+}
+
+/// default rebalancing interval
+fn default_rebalancing_interval() -> Duration {
+    Duration::from_secs(5 * 60)
+}
+
+/// default cooldown between two rebalancing swaps to the same chain and token
+fn default_rebalancing_cooldown() -> Duration {
+    Duration::from_secs(30 * 60)
+}
+
+/// Configure how the solver deals with other solvers fulfilling the same requests.
+///
+/// Unless the policy is `ignore` (the default), the solver looks for pending `relayTokens`
+/// transactions of other solvers for the same request before sending a fulfilment, either through
+/// a `newPendingTransactions` subscription or, if the RPC does not support it, through the
+/// `txpool_content` API. The fulfilment is simulated with `eth_call`, and only sent if it would not
+/// revert.
+///
+/// # Examples
+/// By default, competing transactions are not watched. To try and get included first by outbidding
+/// their fees, the following config may be used:
+/// ```toml
+/// [competition]
+/// # one of "ignore" (default), "abort" or "replace-by-fee"
+/// policy = "replace-by-fee"
+/// # optional, percentage by which the fees of competing transactions are bumped
+/// fee_bump_percent = 15
+/// # optional, never pay more than this max fee per gas, in wei
+/// max_fee_per_gas = 100000000000
+/// # optional, simulate fulfilments before sending them
+/// simulate = true
+/// ```
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct CompetitionConfig {
+    #[serde(default)]
+    pub policy: CompetitionPolicy,
+    #[serde(default = "default_fee_bump_percent")]
+    pub fee_bump_percent: u64,
+    pub max_fee_per_gas: Option<u64>,
+    #[serde(default = "default_simulate")]
+    pub simulate: bool,
+}
+
+impl Default for CompetitionConfig {
     fn default() -> Self {
-        Self::CheckWithCoinGecko {
-            api_key: None,
-            pro_api: false,
+        Self {
+            policy: CompetitionPolicy::default(),
+            fee_bump_percent: default_fee_bump_percent(),
+            max_fee_per_gas: None,
+            simulate: default_simulate(),
         }
     }
 }
 
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum CompetitionPolicy {
+    /// Don't look for competing transactions
+    #[default]
+    #[serde(rename = "ignore")]
+    Ignore,
+
+    /// Don't fulfil requests with a pending competing transaction
+    #[serde(rename = "abort")]
+    Abort,
+
+    /// Send the fulfilment with higher fees than the competing transactions
+    #[serde(rename = "replace-by-fee")]
+    ReplaceByFee,
+}
+
+/// most clients require a 10% bump to replace a transaction, use a bit more
+fn default_fee_bump_percent() -> u64 {
+    12
+}
+
+/// fulfilments are simulated by default
+fn default_simulate() -> bool {
+    true
+}
+
+/// Configure the trade journal of the solver.
+///
+/// Every fulfilment attempt is recorded in an sqlite database along with its transaction receipt,
+/// the gas it consumed, and the refund eventually paid out on the source chain. Amounts are valued
+/// in USD with the price feed of the profitability check, if any. The profit and loss of the
+/// solver can then be reported with `onlyswaps-solver report`.
+///
+/// # Examples
+/// ```toml
+/// [journal]
+/// path = "~/.local/share/onlyswaps/solver/journal.sqlite"
+/// # optional, how often to check whether submitted fulfilments have been included, and fulfilled
+/// # trades refunded
+/// refund_poll_interval = "1m"
+/// ```
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct JournalConfig {
+    pub path: String,
+    #[serde(with = "humantime_serde", default = "default_refund_poll_interval")]
+    pub refund_poll_interval: Duration,
+}
+
+/// default interval between two checks of the submitted fulfilments and pending refunds
+fn default_refund_poll_interval() -> Duration {
+    Duration::from_secs(60)
+}
+
 #[derive(Deserialize, Debug, Clone, Default)]
 pub(crate) struct OmniEventConfig {
     pub endpoint: Option<String>,
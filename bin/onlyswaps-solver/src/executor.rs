@@ -1,17 +1,23 @@
+use crate::config::{CompetitionConfig, CompetitionPolicy};
+use crate::journal::{ExecutionOutcome, TradeJournal};
+use crate::mempool::{PendingFulfilment, PendingFulfilmentProvider};
+use crate::metrics::Metrics;
 use crate::model::{RequestId, Trade};
 use crate::network::Network;
 use crate::profitability::{ErasedProfitabilityEstimator, ProfitabilityEstimator};
 use crate::util::normalise_chain_id;
-use alloy::primitives::{Address, TxHash};
+use alloy::primitives::Address;
 use alloy::providers::Provider;
+use alloy::rpc::types::TransactionReceipt;
 use alloy::signers::Signer;
 use anyhow::Context;
+use chrono::Utc;
 use config::timeout::TimeoutConfig;
 use generated::onlyswaps::i_router::IRouter::IRouterInstance;
 use generated::onlyswaps::permit2_relayer::Permit2Relayer::Permit2RelayerInstance;
 use moka::future::Cache;
-use onlyswaps_client::client::OnlySwapsClient;
-use onlyswaps_client::client::solver::OnlySwapsTrade;
+use onlyswaps_client::client::solver::{OnlySwapsTrade, TransactionFees};
+use onlyswaps_client::client::{OnlySwapsClient, OnlySwapsClientError};
 use std::collections::HashMap;
 use tokio::time::timeout;
 
@@ -21,6 +27,9 @@ pub(crate) struct TradeExecutor<'a, P, S> {
     own_address: Address,
     configs: HashMap<u64, ChainConfig<'a, P>>,
     profitability_estimator: ErasedProfitabilityEstimator,
+    competition: CompetitionConfig,
+    pending_fulfilments: Option<Box<dyn PendingFulfilmentProvider + Send + Sync + 'a>>,
+    journal: Option<TradeJournal>,
 }
 
 pub(crate) struct ChainConfig<'a, P> {
@@ -70,8 +79,32 @@ where
             configs,
             own_address,
             profitability_estimator,
+            competition: CompetitionConfig::default(),
+            pending_fulfilments: None,
+            journal: None,
         })
     }
+
+    /// Set how fulfilments are checked against pending fulfilments of other solvers.
+    pub fn with_competition_config(mut self, competition: CompetitionConfig) -> Self {
+        self.competition = competition;
+        self
+    }
+
+    /// Set the source of pending fulfilments used to detect competing solvers.
+    pub fn with_pending_fulfilments(
+        mut self,
+        pending_fulfilments: impl PendingFulfilmentProvider + Send + Sync + 'a,
+    ) -> Self {
+        self.pending_fulfilments = Some(Box::new(pending_fulfilments));
+        self
+    }
+
+    /// Record the fulfilments sent by the executor in a trade journal.
+    pub fn with_journal(mut self, journal: TradeJournal) -> Self {
+        self.journal = Some(journal);
+        self
+    }
 }
 
 impl<'a, P, S> TradeExecutor<'a, P, S>
@@ -136,8 +169,10 @@ where
         &self,
         trade: Trade,
         chain_config: &ChainConfig<'aa, P>,
-    ) -> anyhow::Result<TxHash> {
-        let sendable_tx = self
+    ) -> anyhow::Result<TransactionReceipt> {
+        let dest_chain_id = normalise_chain_id(trade.dest_chain_id);
+        let provider = chain_config.router.provider();
+        let mut sendable_tx = self
             .client
             .relay_tokens_permit2(
                 &trade.clone().try_into().context("invalid trade")?,
@@ -149,9 +184,56 @@ where
             .await
             .context("failed to obtain sendable permit2 tx")?;
 
-        let gas_cost = estimate_gas_cost(chain_config.router.provider())
+        let mut gas_cost = estimate_gas_cost(provider)
             .await
             .context("gas cost estimation failed")?;
+
+        let competitors = self
+            .competing_fulfilments(dest_chain_id, trade.request_id)
+            .await;
+        if !competitors.is_empty() {
+            match self.competition.policy {
+                CompetitionPolicy::Ignore => {}
+                CompetitionPolicy::Abort => {
+                    tracing::warn!(
+                        request_id = %trade.request_id,
+                        ?competitors,
+                        "Competing fulfilment pending, refusing fulfillment"
+                    );
+                    Metrics::report_competing_fulfilment(dest_chain_id, "aborted");
+                    anyhow::bail!("competing fulfilment pending");
+                }
+                CompetitionPolicy::ReplaceByFee => {
+                    let fees =
+                        outbid_fees(&competitors, gas_cost, self.competition.fee_bump_percent);
+                    if self
+                        .competition
+                        .max_fee_per_gas
+                        .is_some_and(|max_fee| fees.max_fee_per_gas > max_fee.into())
+                    {
+                        tracing::warn!(
+                            request_id = %trade.request_id,
+                            ?competitors,
+                            ?fees,
+                            "Outbidding competing fulfilment exceeds the maximum fee, refusing fulfillment"
+                        );
+                        Metrics::report_competing_fulfilment(dest_chain_id, "too_expensive");
+                        anyhow::bail!("outbidding competing fulfilment is too expensive");
+                    }
+
+                    tracing::info!(
+                        request_id = %trade.request_id,
+                        ?competitors,
+                        ?fees,
+                        "Competing fulfilment pending, outbidding it"
+                    );
+                    Metrics::report_competing_fulfilment(dest_chain_id, "outbid");
+                    gas_cost = fees.max_fee_per_gas;
+                    sendable_tx = sendable_tx.with_fees(fees);
+                }
+            }
+        }
+
         if !self
             .profitability_estimator
             .is_profitable(&trade, sendable_tx.gas_estimate(), gas_cost)
@@ -162,16 +244,133 @@ where
             anyhow::bail!("trade not profitable");
         }
 
-        let receipt = sendable_tx
-            .send()
+        if self.competition.simulate {
+            let request = sendable_tx
+                .transaction_request()
+                .clone()
+                .from(self.own_address);
+            provider
+                .call(request)
+                .await
+                .context("simulation of the fulfilment failed")?;
+        }
+
+        let submitted_at = Utc::now();
+        let submitted = match sendable_tx.submit().await {
+            Ok(submitted) => submitted,
+            Err(e) => {
+                let e = anyhow::Error::from(e).context("failed to send permit2 tx");
+                if let Some(journal) = &self.journal
+                    && let Err(e) = journal
+                        .record_execution(
+                            &trade,
+                            submitted_at,
+                            ExecutionOutcome::Failed(format!("{e:#}")),
+                        )
+                        .await
+                {
+                    tracing::error!(request_id = %trade.request_id, error = ?e, "Failed to record trade in journal");
+                }
+                return Err(e);
+            }
+        };
+
+        // Record the submission before waiting for the receipt, such that transactions that land
+        // after the executor gave up on them are still journaled
+        let tx_hash = submitted.tx_hash();
+        let execution_id = match &self.journal {
+            Some(journal) => journal
+                .record_submission(&trade, submitted_at, tx_hash)
+                .await
+                .inspect_err(|e| tracing::error!(request_id = %trade.request_id, %tx_hash, error = ?e, "Failed to record trade in journal"))
+                .ok(),
+            None => None,
+        };
+
+        let res = submitted.receipt().await;
+        if let (Some(journal), Some(execution_id)) = (&self.journal, execution_id) {
+            // Other errors leave the execution as submitted, to be updated by the journal once the
+            // transaction is included or dropped
+            let outcome = match &res {
+                Ok(receipt) => Some(ExecutionOutcome::Fulfilled(receipt)),
+                Err(OnlySwapsClientError::RelayTokensReverted(receipt)) => {
+                    Some(ExecutionOutcome::Reverted(receipt))
+                }
+                Err(_) => None,
+            };
+            if let Some(outcome) = outcome
+                && let Err(e) = journal.record_outcome(execution_id, &trade, outcome).await
+            {
+                tracing::error!(request_id = %trade.request_id, %tx_hash, error = ?e, "Failed to record trade in journal");
+            }
+        }
+
+        res.context("failed to send permit2 tx")
+    }
+
+    /// Pending fulfilments of a request sent by other solvers
+    async fn competing_fulfilments(
+        &self,
+        chain_id: u64,
+        request_id: RequestId,
+    ) -> Vec<PendingFulfilment> {
+        let Some(pending_fulfilments) = &self.pending_fulfilments else {
+            return vec![];
+        };
+        if self.competition.policy == CompetitionPolicy::Ignore {
+            return vec![];
+        }
+
+        match pending_fulfilments
+            .pending_fulfilments(chain_id, request_id)
             .await
-            .context("failed to send permit2 tx")?;
-        Ok(receipt.transaction_hash)
+        {
+            Ok(fulfilments) => fulfilments
+                .into_iter()
+                .filter(|fulfilment| fulfilment.from != self.own_address)
+                .collect(),
+            Err(e) => {
+                tracing::warn!(
+                    chain_id,
+                    %request_id,
+                    error = ?e,
+                    "Failed to look for competing fulfilments"
+                );
+                vec![]
+            }
+        }
+    }
+}
+
+/// Fees required for a transaction to be included before the competing ones, bumping the highest
+/// competing fees by `bump_percent`. The max fee is never lower than `max_fee_per_gas`.
+fn outbid_fees(
+    competitors: &[PendingFulfilment],
+    max_fee_per_gas: u128,
+    bump_percent: u64,
+) -> TransactionFees {
+    let bump = |fee: u128| fee.saturating_mul(100 + u128::from(bump_percent)) / 100 + 1;
+    let max_priority_fee_per_gas = competitors
+        .iter()
+        .map(|competitor| bump(competitor.max_priority_fee_per_gas))
+        .max()
+        .unwrap_or_default();
+    let max_fee_per_gas = competitors
+        .iter()
+        .map(|competitor| bump(competitor.max_fee_per_gas))
+        .max()
+        .unwrap_or_default()
+        .max(max_fee_per_gas)
+        .max(max_priority_fee_per_gas);
+
+    TransactionFees {
+        max_fee_per_gas,
+        max_priority_fee_per_gas,
     }
 }
 
 /// Get an upper bound estimation of the current gas cost from the provider
-async fn estimate_gas_cost(provider: &impl Provider) -> anyhow::Result<u128> {
+pub(crate) async fn estimate_gas_cost(provider: &impl Provider) -> anyhow::Result<u128> {
     let gas_cost = match provider.estimate_eip1559_fees().await {
         Ok(fees) => fees.max_fee_per_gas,
         Err(e) => {
@@ -228,3 +427,45 @@ where
 
     Ok(permit2_addresses.into_iter())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::TxHash;
+
+    fn competitor(max_fee_per_gas: u128, max_priority_fee_per_gas: u128) -> PendingFulfilment {
+        PendingFulfilment {
+            tx_hash: TxHash::repeat_byte(1),
+            from: Address::repeat_byte(2),
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+        }
+    }
+
+    #[test]
+    fn outbid_fees_bump_the_highest_competitor() {
+        let competitors = [competitor(100, 10), competitor(80, 20)];
+
+        let fees = outbid_fees(&competitors, 50, 10);
+        assert_eq!(
+            fees,
+            TransactionFees {
+                max_fee_per_gas: 111,
+                max_priority_fee_per_gas: 23,
+            }
+        );
+
+        // our own estimate is used if it is higher than the competing max fees
+        let fees = outbid_fees(&competitors, 500, 10);
+        assert_eq!(fees.max_fee_per_gas, 500);
+        assert_eq!(fees.max_priority_fee_per_gas, 23);
+    }
+
+    #[test]
+    fn outbid_fees_cover_the_priority_fee() {
+        // legacy transactions have the same max fee and priority fee
+        let fees = outbid_fees(&[competitor(100, 100)], 10, 12);
+        assert_eq!(fees.max_fee_per_gas, 113);
+        assert_eq!(fees.max_priority_fee_per_gas, 113);
+    }
+}
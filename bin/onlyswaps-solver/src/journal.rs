@@ -0,0 +1,782 @@
+//! A persistent journal of the trades executed by the solver, backed by sqlite.
+
+pub(crate) mod report;
+
+use crate::model::{RequestId, Trade};
+use crate::network::Network;
+use crate::price_feed::{ErasedTokenPriceFeed, TokenPriceFeed};
+use crate::util::{normalise_chain_id, u256_to_f64};
+use alloy::primitives::{Address, TxHash, U256};
+use alloy::providers::Provider;
+use alloy::rpc::types::TransactionReceipt;
+use chrono::{DateTime, Utc};
+use sqlx::sqlite::{SqliteConnectOptions, SqliteRow};
+use sqlx::{Row, SqlitePool};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Number of wei in one unit of the native currency
+const NATIVE_EVM_TOKEN_UNIT: f64 = 1e18;
+
+/// Submitted fulfilments that are still not included after this long are considered dropped
+const SUBMISSION_EXPIRY: Duration = Duration::from_secs(60 * 60);
+
+#[derive(thiserror::Error, Debug)]
+pub enum TradeJournalError {
+    #[error("sqlx error: {1}")]
+    Sqlx(#[source] sqlx::Error, &'static str),
+
+    #[error("failed to run migrations")]
+    Migrate(#[from] sqlx::migrate::MigrateError),
+}
+
+/// Outcome of an attempt at fulfilling a trade.
+pub(crate) enum ExecutionOutcome<'a> {
+    Fulfilled(&'a TransactionReceipt),
+    /// The fulfilment was included, but reverted
+    Reverted(&'a TransactionReceipt),
+    Failed(String),
+}
+
+/// A fulfilled trade whose refund has not been paid out to the solver yet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct PendingRefund {
+    pub request_id: RequestId,
+    pub src_chain_id: u64,
+    pub token_in: Address,
+    pub amount: U256,
+}
+
+/// An attempt at fulfilling a trade, as stored in the journal.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct ExecutionRecord {
+    pub dst_chain_id: u64,
+    pub token_out: Address,
+    pub amount_out: U256,
+    pub fulfilled: bool,
+    /// Gas paid by the fulfilment, in wei, including reverted fulfilments
+    pub gas_cost: Option<U256>,
+    pub amount_out_usd: Option<f64>,
+    pub gas_cost_usd: Option<f64>,
+}
+
+/// A fulfilment submitted to the network whose outcome is not known yet.
+struct SubmittedExecution {
+    id: i64,
+    request_id: RequestId,
+    submitted_at: DateTime<Utc>,
+    tx_hash: TxHash,
+    dst_chain_id: u64,
+    token_out: Address,
+    amount_out: U256,
+}
+
+/// A refund paid out to the solver, as stored in the journal.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct RefundRecord {
+    pub src_chain_id: u64,
+    pub token_in: Address,
+    pub amount: U256,
+    pub refund_usd: Option<f64>,
+}
+
+/// Journal recording the trades of the solver, their fulfilments and refunds, valued in USD when
+/// a price feed is available.
+#[derive(Clone)]
+pub(crate) struct TradeJournal {
+    pool: SqlitePool,
+    price_feed: Option<ErasedTokenPriceFeed>,
+}
+
+impl TradeJournal {
+    /// Open the journal stored at `path`, creating it if required.
+    pub async fn open(path: &str) -> Result<Self, TradeJournalError> {
+        let opts = SqliteConnectOptions::new()
+            .filename(shellexpand::tilde(path).as_ref())
+            .create_if_missing(true);
+        Self::connect_with(opts).await
+    }
+
+    /// Connect to a journal from an sqlite url, e.g. `sqlite::memory:`.
+    #[allow(unused)]
+    pub async fn connect(url: &str) -> Result<Self, TradeJournalError> {
+        let opts = SqliteConnectOptions::from_str(url)
+            .map_err(|e| (e, "failed to create options"))?
+            .create_if_missing(true);
+        Self::connect_with(opts).await
+    }
+
+    async fn connect_with(opts: SqliteConnectOptions) -> Result<Self, TradeJournalError> {
+        let pool = SqlitePool::connect_with(opts)
+            .await
+            .map_err(|e| (e, "failed to connect"))?;
+        sqlx::migrate!("./sql/migrations").run(&pool).await?;
+
+        Ok(Self {
+            pool,
+            price_feed: None,
+        })
+    }
+
+    /// Value fulfilments and refunds in USD using the given price feed.
+    pub fn with_price_feed(mut self, price_feed: ErasedTokenPriceFeed) -> Self {
+        self.price_feed = Some(price_feed);
+        self
+    }
+
+    /// Records an attempt at fulfilling a trade that failed before it could be submitted.
+    pub async fn record_execution(
+        &self,
+        trade: &Trade,
+        submitted_at: DateTime<Utc>,
+        outcome: ExecutionOutcome<'_>,
+    ) -> Result<(), TradeJournalError> {
+        let execution_id = self.insert_execution(trade, submitted_at, None).await?;
+        self.record_outcome(execution_id, trade, outcome).await
+    }
+
+    /// Records a fulfilment submitted in transaction `tx_hash`, whose outcome is not known yet.
+    /// Returns the identifier of the execution, used to record its outcome.
+    pub async fn record_submission(
+        &self,
+        trade: &Trade,
+        submitted_at: DateTime<Utc>,
+        tx_hash: TxHash,
+    ) -> Result<i64, TradeJournalError> {
+        self.insert_execution(trade, submitted_at, Some(tx_hash))
+            .await
+    }
+
+    /// Records the outcome of an execution previously recorded.
+    pub async fn record_outcome(
+        &self,
+        execution_id: i64,
+        trade: &Trade,
+        outcome: ExecutionOutcome<'_>,
+    ) -> Result<(), TradeJournalError> {
+        self.update_execution(
+            execution_id,
+            normalise_chain_id(trade.dest_chain_id),
+            trade.token_out_addr,
+            trade.amount_out,
+            outcome,
+        )
+        .await?;
+
+        tracing::debug!(request_id = %trade.request_id, "Recorded trade execution in journal");
+        Ok(())
+    }
+
+    async fn insert_execution(
+        &self,
+        trade: &Trade,
+        submitted_at: DateTime<Utc>,
+        tx_hash: Option<TxHash>,
+    ) -> Result<i64, TradeJournalError> {
+        sqlx::query(
+            r#"
+                INSERT INTO trades (request_id, src_chain_id, dst_chain_id, token_in, token_out, amount_in, amount_out, solver_fee, solver_refund_amount, created_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+                ON CONFLICT(request_id) DO NOTHING
+            "#,
+        )
+        .bind(trade.request_id.to_vec())
+        .bind(normalise_chain_id(trade.src_chain_id).to_string())
+        .bind(normalise_chain_id(trade.dest_chain_id).to_string())
+        .bind(trade.token_in_addr.to_vec())
+        .bind(trade.token_out_addr.to_vec())
+        .bind(trade.amount_in.to_string())
+        .bind(trade.amount_out.to_string())
+        .bind(trade.solver_fee.to_string())
+        .bind(trade.solver_refund_amount.to_string())
+        .bind(submitted_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| (e, "failed to INSERT INTO trades"))?;
+
+        let res = sqlx::query(
+            r#"
+                INSERT INTO executions (request_id, submitted_at, status, tx_hash)
+                VALUES ($1, $2, 'submitted', $3)
+            "#,
+        )
+        .bind(trade.request_id.to_vec())
+        .bind(submitted_at)
+        .bind(tx_hash.map(|tx_hash| tx_hash.to_vec()))
+        .execute(&self.pool)
+        .await
+        .map_err(|e| (e, "failed to INSERT INTO executions"))?;
+
+        Ok(res.last_insert_rowid())
+    }
+
+    async fn update_execution(
+        &self,
+        execution_id: i64,
+        dst_chain_id: u64,
+        token_out: Address,
+        amount_out: U256,
+        outcome: ExecutionOutcome<'_>,
+    ) -> Result<(), TradeJournalError> {
+        let query = sqlx::query(
+            r#"
+                UPDATE executions
+                SET status = $2, tx_hash = COALESCE($3, tx_hash), block_number = $4, gas_used = $5, effective_gas_price = $6, amount_out_usd = $7, gas_cost_usd = $8, error = $9
+                WHERE id = $1
+            "#,
+        )
+        .bind(execution_id);
+        let (status, receipt, error) = match outcome {
+            ExecutionOutcome::Fulfilled(receipt) => ("fulfilled", Some(receipt), None),
+            ExecutionOutcome::Reverted(receipt) => (
+                "reverted",
+                Some(receipt),
+                Some("transaction reverted".to_owned()),
+            ),
+            ExecutionOutcome::Failed(error) => ("failed", None, Some(error)),
+        };
+
+        // Gas is paid by reverted transactions too, but no tokens are sent
+        let gas_cost = receipt
+            .map(|receipt| U256::from(receipt.gas_used) * U256::from(receipt.effective_gas_price));
+        let amount_out_usd = match status {
+            "fulfilled" => self.token_usd(dst_chain_id, token_out, amount_out).await,
+            _ => None,
+        };
+        let gas_cost_usd = match gas_cost {
+            Some(gas_cost) => self.native_usd(dst_chain_id, gas_cost).await,
+            None => None,
+        };
+        let query = query
+            .bind(status)
+            .bind(receipt.map(|receipt| receipt.transaction_hash.to_vec()))
+            .bind(receipt.and_then(|receipt| receipt.block_number.map(|n| n.to_string())))
+            .bind(receipt.map(|receipt| receipt.gas_used.to_string()))
+            .bind(receipt.map(|receipt| receipt.effective_gas_price.to_string()))
+            .bind(amount_out_usd)
+            .bind(gas_cost_usd)
+            .bind(error);
+        query
+            .execute(&self.pool)
+            .await
+            .map_err(|e| (e, "failed to UPDATE executions"))?;
+
+        Ok(())
+    }
+
+    /// Submitted fulfilments whose outcome is not known yet.
+    async fn submitted_executions(&self) -> Result<Vec<SubmittedExecution>, TradeJournalError> {
+        let rows = sqlx::query(
+            r#"
+                SELECT e.id, e.request_id, e.submitted_at, e.tx_hash, t.dst_chain_id, t.token_out, t.amount_out
+                FROM executions e INNER JOIN trades t ON e.request_id = t.request_id
+                WHERE e.status = 'submitted' AND e.tx_hash IS NOT NULL
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| (e, "failed to SELECT FROM executions"))?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(SubmittedExecution {
+                    id: row.try_get("id")?,
+                    request_id: decode_bytes(row, "request_id")?,
+                    submitted_at: row.try_get("submitted_at")?,
+                    tx_hash: decode_bytes(row, "tx_hash")?,
+                    dst_chain_id: decode_str(row, "dst_chain_id")?,
+                    token_out: decode_bytes(row, "token_out")?,
+                    amount_out: decode_str(row, "amount_out")?,
+                })
+            })
+            .collect::<Result<_, sqlx::Error>>()
+            .map_err(|e| (e, "failed to decode executions").into())
+    }
+
+    /// Fulfilled trades that have not been refunded yet.
+    pub async fn pending_refunds(&self) -> Result<Vec<PendingRefund>, TradeJournalError> {
+        let rows = sqlx::query(
+            r#"
+                SELECT request_id, src_chain_id, token_in, solver_refund_amount FROM trades t
+                WHERE t.refunded_at IS NULL AND EXISTS (
+                    SELECT 1 FROM executions e WHERE e.request_id = t.request_id AND e.status = 'fulfilled'
+                )
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| (e, "failed to SELECT FROM trades"))?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(PendingRefund {
+                    request_id: decode_bytes(row, "request_id")?,
+                    src_chain_id: decode_str(row, "src_chain_id")?,
+                    token_in: decode_bytes(row, "token_in")?,
+                    amount: decode_str(row, "solver_refund_amount")?,
+                })
+            })
+            .collect::<Result<_, sqlx::Error>>()
+            .map_err(|e| (e, "failed to decode trades").into())
+    }
+
+    /// Records the refund of a trade to the solver.
+    pub async fn record_refund(
+        &self,
+        refund: &PendingRefund,
+        refunded_at: DateTime<Utc>,
+    ) -> Result<(), TradeJournalError> {
+        let refund_usd = self
+            .token_usd(refund.src_chain_id, refund.token_in, refund.amount)
+            .await;
+        sqlx::query("UPDATE trades SET refunded_at = $2, refund_usd = $3 WHERE request_id = $1")
+            .bind(refund.request_id.to_vec())
+            .bind(refunded_at)
+            .bind(refund_usd)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| (e, "failed to UPDATE trades"))?;
+
+        Ok(())
+    }
+
+    /// Attempts at fulfilling trades submitted within `[from, to)`.
+    pub async fn executions_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<ExecutionRecord>, TradeJournalError> {
+        let rows = sqlx::query(
+            r#"
+                SELECT t.dst_chain_id, t.token_out, t.amount_out, e.status, e.gas_used, e.effective_gas_price, e.amount_out_usd, e.gas_cost_usd
+                FROM executions e INNER JOIN trades t ON e.request_id = t.request_id
+                WHERE e.submitted_at >= $1 AND e.submitted_at < $2 AND e.status != 'submitted'
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| (e, "failed to SELECT FROM executions"))?;
+
+        rows.iter()
+            .map(|row| {
+                let status: String = row.try_get("status")?;
+                let gas_used: Option<String> = row.try_get("gas_used")?;
+                let gas_price: Option<String> = row.try_get("effective_gas_price")?;
+                let gas_cost = match (gas_used, gas_price) {
+                    (Some(gas_used), Some(gas_price)) => Some(
+                        parse::<U256>(&gas_used, "gas_used")?
+                            * parse::<U256>(&gas_price, "effective_gas_price")?,
+                    ),
+                    _ => None,
+                };
+
+                Ok(ExecutionRecord {
+                    dst_chain_id: decode_str(row, "dst_chain_id")?,
+                    token_out: decode_bytes(row, "token_out")?,
+                    amount_out: decode_str(row, "amount_out")?,
+                    fulfilled: status == "fulfilled",
+                    gas_cost,
+                    amount_out_usd: row.try_get("amount_out_usd")?,
+                    gas_cost_usd: row.try_get("gas_cost_usd")?,
+                })
+            })
+            .collect::<Result<_, sqlx::Error>>()
+            .map_err(|e| (e, "failed to decode executions").into())
+    }
+
+    /// Refunds paid out to the solver within `[from, to)`.
+    pub async fn refunds_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<RefundRecord>, TradeJournalError> {
+        let rows = sqlx::query(
+            r#"
+                SELECT src_chain_id, token_in, solver_refund_amount, refund_usd FROM trades
+                WHERE refunded_at >= $1 AND refunded_at < $2
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| (e, "failed to SELECT FROM trades"))?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(RefundRecord {
+                    src_chain_id: decode_str(row, "src_chain_id")?,
+                    token_in: decode_bytes(row, "token_in")?,
+                    amount: decode_str(row, "solver_refund_amount")?,
+                    refund_usd: row.try_get("refund_usd")?,
+                })
+            })
+            .collect::<Result<_, sqlx::Error>>()
+            .map_err(|e| (e, "failed to decode trades").into())
+    }
+
+    /// Periodically records the outcome of submitted fulfilments that the executor stopped waiting
+    /// for, and the refunds of fulfilled trades. Solvers are refunded on the source chain through
+    /// `rebalanceSolver` once their fulfilment has been verified, which marks the swap request as
+    /// executed.
+    pub async fn track_trades<P: Provider>(
+        &self,
+        networks: &HashMap<u64, Network<P>>,
+        interval: Duration,
+    ) -> anyhow::Result<()> {
+        let mut interval = tokio::time::interval(interval);
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.update_submissions(networks).await {
+                tracing::error!(error = ?e, "failed to update submitted fulfilments");
+            }
+            if let Err(e) = self.update_refunds(networks).await {
+                tracing::error!(error = ?e, "failed to update trade refunds");
+            }
+        }
+    }
+
+    async fn update_submissions<P: Provider>(
+        &self,
+        networks: &HashMap<u64, Network<P>>,
+    ) -> anyhow::Result<()> {
+        for execution in self.submitted_executions().await? {
+            let Some(network) = networks.get(&execution.dst_chain_id) else {
+                continue;
+            };
+
+            let receipt = network
+                .router
+                .provider()
+                .get_transaction_receipt(execution.tx_hash)
+                .await?;
+            let expired = (Utc::now() - execution.submitted_at)
+                .to_std()
+                .is_ok_and(|elapsed| elapsed > SUBMISSION_EXPIRY);
+            let outcome = match &receipt {
+                Some(receipt) if receipt.status() => ExecutionOutcome::Fulfilled(receipt),
+                Some(receipt) => ExecutionOutcome::Reverted(receipt),
+                None if expired => {
+                    ExecutionOutcome::Failed("transaction was never included".to_owned())
+                }
+                None => continue,
+            };
+
+            self.update_execution(
+                execution.id,
+                execution.dst_chain_id,
+                execution.token_out,
+                execution.amount_out,
+                outcome,
+            )
+            .await?;
+            tracing::info!(
+                request_id = %execution.request_id,
+                tx_hash = %execution.tx_hash,
+                "Recorded outcome of submitted fulfilment"
+            );
+        }
+
+        Ok(())
+    }
+
+    async fn update_refunds<P: Provider>(
+        &self,
+        networks: &HashMap<u64, Network<P>>,
+    ) -> anyhow::Result<()> {
+        for refund in self.pending_refunds().await? {
+            let Some(network) = networks.get(&refund.src_chain_id) else {
+                continue;
+            };
+
+            let params = network
+                .router
+                .getSwapRequestParameters(refund.request_id)
+                .call()
+                .await?;
+            if params.executed {
+                self.record_refund(&refund, Utc::now()).await?;
+                tracing::info!(
+                    request_id = %refund.request_id,
+                    src_chain_id = refund.src_chain_id,
+                    amount = %refund.amount,
+                    "Solver refunded for trade"
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn token_usd(&self, chain_id: u64, token: Address, amount: U256) -> Option<f64> {
+        let price_feed = self.price_feed.as_ref()?;
+        let token = token.to_string();
+        let value = async {
+            let decimals = price_feed.token_decimals(chain_id, token.clone()).await?;
+            let price = price_feed.token_value(chain_id, token.clone()).await?;
+            anyhow::Ok(u256_to_f64(amount) / 10f64.powi(decimals.into()) * price)
+        };
+
+        value
+            .await
+            .inspect_err(
+                |e| tracing::warn!(chain_id, %token, error = ?e, "Failed to value tokens in USD"),
+            )
+            .ok()
+    }
+
+    async fn native_usd(&self, chain_id: u64, amount: U256) -> Option<f64> {
+        let price_feed = self.price_feed.as_ref()?;
+        price_feed
+            .native_value(chain_id)
+            .await
+            .map(|price| u256_to_f64(amount) / NATIVE_EVM_TOKEN_UNIT * price)
+            .inspect_err(
+                |e| tracing::warn!(chain_id, error = ?e, "Failed to value native currency in USD"),
+            )
+            .ok()
+    }
+}
+
+/// Convert (sqlx::Error, &'static str) into an [`TradeJournalError`] error.
+impl From<(sqlx::Error, &'static str)> for TradeJournalError {
+    fn from((e, msg): (sqlx::Error, &'static str)) -> Self {
+        Self::Sqlx(e, msg)
+    }
+}
+
+fn parse<T>(value: &str, column: &str) -> Result<T, sqlx::Error>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    T::from_str(value).map_err(|e| sqlx::Error::ColumnDecode {
+        index: column.to_owned(),
+        source: Box::new(e),
+    })
+}
+
+/// Decode a value stored as its string representation.
+fn decode_str<T>(row: &SqliteRow, column: &str) -> Result<T, sqlx::Error>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    let value: String = row.try_get(column)?;
+    parse(&value, column)
+}
+
+/// Decode a fixed-size value stored as bytes.
+fn decode_bytes<T>(row: &SqliteRow, column: &str) -> Result<T, sqlx::Error>
+where
+    T: for<'a> TryFrom<&'a [u8]>,
+    for<'a> <T as TryFrom<&'a [u8]>>::Error: std::error::Error + Send + Sync + 'static,
+{
+    let value: Vec<u8> = row.try_get(column)?;
+    T::try_from(value.as_slice()).map_err(|e| sqlx::Error::ColumnDecode {
+        index: column.to_owned(),
+        source: Box::new(e),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test::{generate_address, generate_request_id};
+    use chrono::TimeDelta;
+
+    fn trade(src_chain_id: u64, dst_chain_id: u64) -> Trade {
+        Trade {
+            token_in_addr: generate_address(),
+            token_out_addr: generate_address(),
+            src_chain_id: U256::from(src_chain_id),
+            dest_chain_id: U256::from(dst_chain_id),
+            sender_addr: generate_address(),
+            recipient_addr: generate_address(),
+            request_id: generate_request_id(),
+            amount_in: U256::from(1_000),
+            amount_out: U256::from(990),
+            solver_fee: U256::from(10),
+            solver_refund_amount: U256::from(1_000),
+            nonce: U256::from(1),
+            pre_hooks: vec![],
+            post_hooks: vec![],
+        }
+    }
+
+    fn receipt(gas_used: u64, effective_gas_price: u128) -> TransactionReceipt {
+        serde_json::from_value(serde_json::json!({
+            "type": "0x2",
+            "status": "0x1",
+            "cumulativeGasUsed": "0x0",
+            "logs": [],
+            "logsBloom": format!("0x{}", "00".repeat(256)),
+            "transactionHash": format!("0x{}", "11".repeat(32)),
+            "transactionIndex": "0x0",
+            "blockHash": format!("0x{}", "22".repeat(32)),
+            "blockNumber": "0x10",
+            "gasUsed": format!("{gas_used:#x}"),
+            "effectiveGasPrice": format!("{effective_gas_price:#x}"),
+            "from": format!("0x{}", "33".repeat(20)),
+            "to": format!("0x{}", "44".repeat(20)),
+            "contractAddress": null,
+        }))
+        .expect("valid receipt")
+    }
+
+    #[tokio::test]
+    async fn executions_and_refunds_are_recorded() {
+        let journal = TradeJournal::connect("sqlite::memory:")
+            .await
+            .expect("failed to open journal");
+        let now = Utc::now();
+
+        let fulfilled = trade(1, 2);
+        let failed = trade(2, 1);
+        journal
+            .record_execution(
+                &failed,
+                now,
+                ExecutionOutcome::Failed("reverted".to_owned()),
+            )
+            .await
+            .unwrap();
+        journal
+            .record_execution(
+                &fulfilled,
+                now,
+                ExecutionOutcome::Fulfilled(&receipt(100_000, 2)),
+            )
+            .await
+            .unwrap();
+
+        // only fulfilled trades are pending refunds
+        let pending = journal.pending_refunds().await.unwrap();
+        assert_eq!(
+            pending,
+            vec![PendingRefund {
+                request_id: fulfilled.request_id,
+                src_chain_id: 1,
+                token_in: fulfilled.token_in_addr,
+                amount: U256::from(1_000),
+            }]
+        );
+
+        let mut executions = journal
+            .executions_between(now - TimeDelta::minutes(1), now + TimeDelta::minutes(1))
+            .await
+            .unwrap();
+        executions.sort_by_key(|e| e.dst_chain_id);
+        assert_eq!(
+            executions,
+            vec![
+                ExecutionRecord {
+                    dst_chain_id: 1,
+                    token_out: failed.token_out_addr,
+                    amount_out: U256::from(990),
+                    fulfilled: false,
+                    gas_cost: None,
+                    amount_out_usd: None,
+                    gas_cost_usd: None,
+                },
+                ExecutionRecord {
+                    dst_chain_id: 2,
+                    token_out: fulfilled.token_out_addr,
+                    amount_out: U256::from(990),
+                    fulfilled: true,
+                    gas_cost: Some(U256::from(200_000)),
+                    amount_out_usd: None,
+                    gas_cost_usd: None,
+                },
+            ]
+        );
+        assert!(
+            journal
+                .executions_between(now + TimeDelta::minutes(1), now + TimeDelta::minutes(2))
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        journal.record_refund(&pending[0], now).await.unwrap();
+        assert!(journal.pending_refunds().await.unwrap().is_empty());
+        assert_eq!(
+            journal
+                .refunds_between(now - TimeDelta::minutes(1), now + TimeDelta::minutes(1))
+                .await
+                .unwrap(),
+            vec![RefundRecord {
+                src_chain_id: 1,
+                token_in: fulfilled.token_in_addr,
+                amount: U256::from(1_000),
+                refund_usd: None,
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn submitted_executions_are_updated() {
+        let journal = TradeJournal::connect("sqlite::memory:")
+            .await
+            .expect("failed to open journal");
+        let now = Utc::now();
+        let tx_hash = TxHash::repeat_byte(0x11);
+
+        let reverted = trade(1, 2);
+        let pending = trade(2, 1);
+        let reverted_id = journal
+            .record_submission(&reverted, now, tx_hash)
+            .await
+            .unwrap();
+        journal
+            .record_submission(&pending, now, tx_hash)
+            .await
+            .unwrap();
+
+        // outcomes are unknown until the receipts are recorded
+        let submitted = journal.submitted_executions().await.unwrap();
+        assert_eq!(submitted.len(), 2);
+        assert!(submitted.iter().all(|e| e.tx_hash == tx_hash));
+        let between = (now - TimeDelta::minutes(1), now + TimeDelta::minutes(1));
+        assert!(
+            journal
+                .executions_between(between.0, between.1)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        // reverted fulfilments still pay for gas, but are not refunded
+        journal
+            .record_outcome(
+                reverted_id,
+                &reverted,
+                ExecutionOutcome::Reverted(&receipt(50_000, 2)),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            journal
+                .executions_between(between.0, between.1)
+                .await
+                .unwrap(),
+            vec![ExecutionRecord {
+                dst_chain_id: 2,
+                token_out: reverted.token_out_addr,
+                amount_out: U256::from(990),
+                fulfilled: false,
+                gas_cost: Some(U256::from(100_000)),
+                amount_out_usd: None,
+                gas_cost_usd: None,
+            }]
+        );
+        assert!(journal.pending_refunds().await.unwrap().is_empty());
+
+        let submitted = journal.submitted_executions().await.unwrap();
+        assert_eq!(submitted.len(), 1);
+        assert_eq!(submitted[0].request_id, pending.request_id);
+    }
+}
@@ -0,0 +1,279 @@
+//! Profit and loss reporting from the trade journal.
+
+use crate::config::AppConfig;
+use crate::journal::{ExecutionRecord, RefundRecord, TradeJournal};
+use alloy::primitives::{Address, U256};
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+/// Asset whose flows are accounted for on a chain.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Asset {
+    /// The native currency, spent on gas
+    Native,
+    Token(Address),
+}
+
+/// Flows of an asset on a chain over the reporting period.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct PnlRow {
+    pub fulfilled: usize,
+    pub failed: usize,
+    pub refunded: usize,
+    /// Amount sent to users, or spent on gas for the native currency
+    pub spent: U256,
+    /// Amount refunded to the solver
+    pub received: U256,
+    pub spent_usd: f64,
+    pub received_usd: f64,
+    /// Number of flows that could not be valued in USD
+    pub unpriced: usize,
+}
+
+impl PnlRow {
+    pub fn net_usd(&self) -> f64 {
+        self.received_usd - self.spent_usd
+    }
+}
+
+/// Profit and loss of the solver per chain and asset.
+///
+/// Flows are accounted for when they happen: fulfilments and gas when the fulfilment is
+/// submitted, and refunds once they have been paid out on the source chain. Fulfilments whose
+/// outcome is not known yet are not accounted for.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct PnlReport {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub rows: BTreeMap<(u64, Asset), PnlRow>,
+}
+
+impl PnlReport {
+    pub fn new(
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        executions: &[ExecutionRecord],
+        refunds: &[RefundRecord],
+    ) -> Self {
+        let mut rows: BTreeMap<(u64, Asset), PnlRow> = BTreeMap::new();
+        for execution in executions {
+            let row = rows
+                .entry((execution.dst_chain_id, Asset::Token(execution.token_out)))
+                .or_default();
+            if execution.fulfilled {
+                row.fulfilled += 1;
+                row.spent += execution.amount_out;
+                add_usd(
+                    &mut row.spent_usd,
+                    &mut row.unpriced,
+                    execution.amount_out_usd,
+                );
+            } else {
+                row.failed += 1;
+            }
+
+            // Reverted fulfilments also pay for gas
+            if let Some(gas_cost) = execution.gas_cost {
+                let row = rows
+                    .entry((execution.dst_chain_id, Asset::Native))
+                    .or_default();
+                row.spent += gas_cost;
+                add_usd(
+                    &mut row.spent_usd,
+                    &mut row.unpriced,
+                    execution.gas_cost_usd,
+                );
+            }
+        }
+
+        for refund in refunds {
+            let row = rows
+                .entry((refund.src_chain_id, Asset::Token(refund.token_in)))
+                .or_default();
+            row.refunded += 1;
+            row.received += refund.amount;
+            add_usd(&mut row.received_usd, &mut row.unpriced, refund.refund_usd);
+        }
+
+        Self { from, to, rows }
+    }
+
+    pub fn net_usd(&self) -> f64 {
+        self.rows.values().map(PnlRow::net_usd).sum()
+    }
+
+    fn unpriced(&self) -> usize {
+        self.rows.values().map(|row| row.unpriced).sum()
+    }
+}
+
+fn add_usd(total: &mut f64, unpriced: &mut usize, value: Option<f64>) {
+    match value {
+        Some(value) => *total += value,
+        None => *unpriced += 1,
+    }
+}
+
+impl Display for PnlReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Profit and loss from {} to {}", self.from, self.to)?;
+        writeln!(
+            f,
+            "{:<10} {:<44} {:>9} {:>6} {:>8} {:>28} {:>28} {:>12} {:>12} {:>12}",
+            "Chain",
+            "Asset",
+            "Fulfilled",
+            "Failed",
+            "Refunded",
+            "Spent",
+            "Received",
+            "Spent USD",
+            "Received USD",
+            "Net USD"
+        )?;
+        writeln!(f, "{}", "-".repeat(180))?;
+        for ((chain_id, asset), row) in &self.rows {
+            let asset = match asset {
+                Asset::Native => "native (gas)".to_owned(),
+                Asset::Token(address) => address.to_string(),
+            };
+            writeln!(
+                f,
+                "{:<10} {:<44} {:>9} {:>6} {:>8} {:>28} {:>28} {:>12.2} {:>12.2} {:>12.2}",
+                chain_id,
+                asset,
+                row.fulfilled,
+                row.failed,
+                row.refunded,
+                row.spent,
+                row.received,
+                row.spent_usd,
+                row.received_usd,
+                row.net_usd()
+            )?;
+        }
+        writeln!(f, "{}", "-".repeat(180))?;
+        writeln!(f, "Net USD: {:.2}", self.net_usd())?;
+
+        let unpriced = self.unpriced();
+        if unpriced > 0 {
+            writeln!(
+                f,
+                "Warning: {unpriced} flow(s) could not be valued in USD and are excluded from USD totals"
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Prints the profit and loss of the solver within `[from, to)` from its trade journal.
+pub(crate) async fn report(
+    config: &AppConfig,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> anyhow::Result<()> {
+    let journal_config = config
+        .journal
+        .as_ref()
+        .context("no trade journal configured, add a [journal] section to the config")?;
+    let journal = TradeJournal::open(&journal_config.path)
+        .await
+        .context("failed to open trade journal")?;
+
+    let from = from.unwrap_or(DateTime::UNIX_EPOCH);
+    let to = to.unwrap_or_else(Utc::now);
+    anyhow::ensure!(from < to, "start of the period must be before its end");
+
+    let executions = journal.executions_between(from, to).await?;
+    let refunds = journal.refunds_between(from, to).await?;
+    println!("{}", PnlReport::new(from, to, &executions, &refunds));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn execution(fulfilled: bool, token_out: Address) -> ExecutionRecord {
+        ExecutionRecord {
+            dst_chain_id: 2,
+            token_out,
+            amount_out: U256::from(990),
+            fulfilled,
+            gas_cost: fulfilled.then_some(U256::from(1_000)),
+            amount_out_usd: fulfilled.then_some(9.9),
+            gas_cost_usd: fulfilled.then_some(0.25),
+        }
+    }
+
+    fn reverted(token_out: Address) -> ExecutionRecord {
+        ExecutionRecord {
+            gas_cost: Some(U256::from(1_000)),
+            gas_cost_usd: Some(0.25),
+            ..execution(false, token_out)
+        }
+    }
+
+    #[test]
+    fn flows_are_aggregated_per_chain_and_asset() {
+        let token_in = Address::repeat_byte(1);
+        let token_out = Address::repeat_byte(2);
+        let executions = [
+            execution(true, token_out),
+            execution(true, token_out),
+            execution(false, token_out),
+            reverted(token_out),
+        ];
+        let refunds = [
+            RefundRecord {
+                src_chain_id: 1,
+                token_in,
+                amount: U256::from(1_000),
+                refund_usd: Some(10.0),
+            },
+            RefundRecord {
+                src_chain_id: 1,
+                token_in,
+                amount: U256::from(1_000),
+                refund_usd: None,
+            },
+        ];
+
+        let report = PnlReport::new(DateTime::UNIX_EPOCH, Utc::now(), &executions, &refunds);
+        assert_eq!(report.rows.len(), 3);
+        assert_eq!(
+            report.rows[&(1, Asset::Token(token_in))],
+            PnlRow {
+                refunded: 2,
+                received: U256::from(2_000),
+                received_usd: 10.0,
+                unpriced: 1,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            report.rows[&(2, Asset::Token(token_out))],
+            PnlRow {
+                fulfilled: 2,
+                failed: 2,
+                spent: U256::from(1_980),
+                spent_usd: 19.8,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            report.rows[&(2, Asset::Native)],
+            PnlRow {
+                spent: U256::from(3_000),
+                spent_usd: 0.75,
+                ..Default::default()
+            }
+        );
+        assert!((report.net_usd() - (10.0 - 19.8 - 0.75)).abs() < 1e-9);
+        assert_eq!(report.unpriced(), 1);
+    }
+}
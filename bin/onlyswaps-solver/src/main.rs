@@ -2,10 +2,15 @@ mod app;
 mod config;
 mod executor;
 mod fee_adapter;
+mod journal;
+mod mempool;
+mod metrics;
 mod model;
 mod network;
 pub mod price_feed;
 mod profitability;
+mod rebalancer;
+mod selection;
 mod setup;
 mod solver;
 mod util;
@@ -44,6 +49,11 @@ async fn main() -> anyhow::Result<()> {
     let cli = CliArgs::parse();
     let command = cli.command();
     let config: AppConfig = load_config_file(cli.config_path)?;
+    if let Command::Report { from, to } = command {
+        // reports only rely on the journal, don't connect to the networks
+        return journal::report::report(&config, from, to).await;
+    }
+
     let private_key_signer: PrivateKeySigner = cli.private_key.parse()?;
     let networks = Network::create_many(&cli.private_key, &config.networks).await?;
     let client = create_onlyswaps_client(&config, &networks);
@@ -51,6 +61,7 @@ async fn main() -> anyhow::Result<()> {
     match command {
         Command::Run => run(config, private_key_signer, client, networks).await,
         Command::Setup => setup(&client, &networks).await,
+        Command::Report { .. } => unreachable!("reports are handled above"),
     }
 }
 
@@ -65,6 +76,8 @@ async fn run(
         config.agent.healthcheck_port,
     )
     .await?;
+    #[cfg(feature = "metrics")]
+    let healthcheck_server = healthcheck_server.with_metrics(get_metrics);
     init_monitoring(&config.agent)?;
 
     let (service, maybe_manager) =
@@ -76,7 +89,7 @@ async fn run(
 
     // listen for alllll the things!
     let out = tokio::select! {
-        res = App::start(private_key_signer, client, networks, &config.timeout, &config.profitability, service) => {
+        res = App::start(private_key_signer, client, networks, &config, service) => {
             match res {
                 Ok(_) => Err(anyhow!("event listener stopped unexpectedly")),
                 Err(e) => Err(anyhow!("event listener stopped unexpectedly: {}", e))
@@ -127,6 +140,18 @@ async fn setup(
     setup_allowances(client, networks).await
 }
 
+#[cfg(feature = "metrics")]
+async fn get_metrics() -> Result<Vec<u8>, axum::http::StatusCode> {
+    use prometheus::{Encoder, TextEncoder};
+
+    let encoder = TextEncoder::new();
+    let mut buffer = Vec::new();
+    match encoder.encode(&crate::metrics::Metrics::gather(), &mut buffer) {
+        Ok(()) => Ok(buffer),
+        Err(_) => Err(axum::http::StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
 type ArcManager = Arc<EventManager<MultiProvider<u64>, NopDatabase>>;
 
 /// Create an omnievent service either by relying on an external endpoint, or by initialising our own
@@ -0,0 +1,296 @@
+//! Detection of fulfilments of swap requests that are pending in the mempool.
+
+use crate::model::RequestId;
+use crate::network::Network;
+use alloy::consensus::Transaction as ConsensusTransaction;
+use alloy::network::TransactionResponse;
+use alloy::primitives::{Address, TxHash};
+use alloy::providers::Provider;
+use alloy::rpc::types::Transaction;
+use alloy::sol_types::SolCall;
+use anyhow::Context;
+use async_trait::async_trait;
+use futures::StreamExt;
+use generated::onlyswaps::i_router::IRouter::{relayTokensCall, relayTokensPermit2Call};
+use moka::future::Cache;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+/// How long pending transactions are remembered for once seen
+const PENDING_TX_TTL: Duration = Duration::from_secs(2 * 60);
+
+/// A pending transaction fulfilling a swap request.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct PendingFulfilment {
+    pub tx_hash: TxHash,
+    pub from: Address,
+    pub max_fee_per_gas: u128,
+    pub max_priority_fee_per_gas: u128,
+}
+
+impl PendingFulfilment {
+    fn from_transaction(tx: &Transaction, router: Address) -> Option<(RequestId, Self)> {
+        if ConsensusTransaction::to(tx) != Some(router) {
+            return None;
+        }
+
+        let request_id = decode_request_id(ConsensusTransaction::input(tx))?;
+        let max_fee_per_gas = ConsensusTransaction::max_fee_per_gas(tx);
+        Some((
+            request_id,
+            Self {
+                tx_hash: TransactionResponse::tx_hash(tx),
+                from: TransactionResponse::from(tx),
+                max_fee_per_gas,
+                // legacy transactions pay their whole gas price to the block producer
+                max_priority_fee_per_gas: ConsensusTransaction::max_priority_fee_per_gas(tx)
+                    .unwrap_or(max_fee_per_gas),
+            },
+        ))
+    }
+}
+
+#[async_trait]
+pub(crate) trait PendingFulfilmentProvider {
+    /// Returns the pending transactions fulfilling a request on its destination chain.
+    async fn pending_fulfilments(
+        &self,
+        chain_id: u64,
+        request_id: RequestId,
+    ) -> anyhow::Result<Vec<PendingFulfilment>>;
+}
+
+/// Watches the mempool of each chain for transactions fulfilling swap requests.
+///
+/// Pending transactions are streamed with a `newPendingTransactions` subscription where the RPC
+/// supports it. Otherwise, the pool of pending transactions is queried on demand with
+/// `txpool_content`.
+pub(crate) struct MempoolWatcher<P> {
+    chains: HashMap<u64, WatchedChain<P>>,
+    pending: Cache<(u64, RequestId), HashMap<TxHash, PendingFulfilment>>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+struct WatchedChain<P> {
+    provider: P,
+    router: Address,
+    subscribed: Arc<AtomicBool>,
+}
+
+#[derive(Deserialize)]
+struct TxpoolContent {
+    pending: HashMap<Address, HashMap<String, Transaction>>,
+}
+
+impl<P> MempoolWatcher<P>
+where
+    P: Provider + Clone + 'static,
+{
+    pub fn new(networks: &HashMap<u64, Network<P>>) -> Self {
+        let pending = Cache::builder()
+            .max_capacity(10_000)
+            .time_to_live(PENDING_TX_TTL)
+            .build();
+
+        let mut chains = HashMap::with_capacity(networks.len());
+        let mut tasks = Vec::with_capacity(networks.len());
+        for (&chain_id, network) in networks {
+            let router = *network.router.address();
+            let subscribed = Arc::new(AtomicBool::new(true));
+            tasks.push(tokio::spawn(watch_pending_transactions(
+                chain_id,
+                network.provider.clone(),
+                router,
+                subscribed.clone(),
+                pending.clone(),
+            )));
+            chains.insert(
+                chain_id,
+                WatchedChain {
+                    provider: network.provider.clone(),
+                    router,
+                    subscribed,
+                },
+            );
+        }
+
+        Self {
+            chains,
+            pending,
+            tasks,
+        }
+    }
+}
+
+impl<P> Drop for MempoolWatcher<P> {
+    fn drop(&mut self) {
+        self.tasks.iter().for_each(JoinHandle::abort);
+    }
+}
+
+#[async_trait]
+impl<P> PendingFulfilmentProvider for MempoolWatcher<P>
+where
+    P: Provider + Clone + 'static,
+{
+    async fn pending_fulfilments(
+        &self,
+        chain_id: u64,
+        request_id: RequestId,
+    ) -> anyhow::Result<Vec<PendingFulfilment>> {
+        let chain = self
+            .chains
+            .get(&chain_id)
+            .with_context(|| format!("mempool of chain {chain_id} is not watched"))?;
+
+        if chain.subscribed.load(Ordering::Relaxed) {
+            return Ok(self
+                .pending
+                .get(&(chain_id, request_id))
+                .await
+                .map(|fulfilments| fulfilments.into_values().collect())
+                .unwrap_or_default());
+        }
+
+        let content: TxpoolContent = chain
+            .provider
+            .raw_request("txpool_content".into(), ())
+            .await
+            .context("failed to query txpool content")?;
+        Ok(content
+            .pending
+            .into_values()
+            .flat_map(HashMap::into_values)
+            .filter_map(|tx| PendingFulfilment::from_transaction(&tx, chain.router))
+            .filter(|(id, _)| *id == request_id)
+            .map(|(_, fulfilment)| fulfilment)
+            .collect())
+    }
+}
+
+async fn watch_pending_transactions<P: Provider>(
+    chain_id: u64,
+    provider: P,
+    router: Address,
+    subscribed: Arc<AtomicBool>,
+    pending: Cache<(u64, RequestId), HashMap<TxHash, PendingFulfilment>>,
+) {
+    let subscription = match provider.subscribe_full_pending_transactions().await {
+        Ok(subscription) => subscription,
+        Err(e) => {
+            tracing::warn!(
+                chain_id,
+                error = ?e,
+                "Failed to subscribe to pending transactions, falling back to txpool queries"
+            );
+            subscribed.store(false, Ordering::Relaxed);
+            return;
+        }
+    };
+
+    let mut stream = subscription.into_stream();
+    while let Some(tx) = stream.next().await {
+        let Some((request_id, fulfilment)) = PendingFulfilment::from_transaction(&tx, router)
+        else {
+            continue;
+        };
+
+        tracing::debug!(
+            chain_id,
+            %request_id,
+            tx_hash = %fulfilment.tx_hash,
+            from = %fulfilment.from,
+            "Found pending fulfilment"
+        );
+        let key = (chain_id, request_id);
+        let mut fulfilments = pending.get(&key).await.unwrap_or_default();
+        fulfilments.insert(fulfilment.tx_hash, fulfilment);
+        pending.insert(key, fulfilments).await;
+    }
+
+    tracing::warn!(
+        chain_id,
+        "Pending transactions subscription ended, falling back to txpool queries"
+    );
+    subscribed.store(false, Ordering::Relaxed);
+}
+
+/// Extracts the request id of `relayTokens` and `relayTokensPermit2` calls.
+fn decode_request_id(input: &[u8]) -> Option<RequestId> {
+    if input.starts_with(&relayTokensCall::SELECTOR) {
+        relayTokensCall::abi_decode(input)
+            .ok()
+            .map(|call| call.requestId)
+    } else if input.starts_with(&relayTokensPermit2Call::SELECTOR) {
+        relayTokensPermit2Call::abi_decode(input)
+            .ok()
+            .map(|call| call.params.requestId)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{B256, Bytes, U256};
+    use generated::onlyswaps::i_router::IRouter::RelayTokensPermit2Params;
+
+    #[test]
+    fn request_ids_are_decoded() {
+        let request_id = B256::repeat_byte(0x42);
+
+        let relay_tokens = relayTokensCall {
+            solverRefundAddress: Address::repeat_byte(1),
+            requestId: request_id,
+            sender: Address::repeat_byte(2),
+            recipient: Address::repeat_byte(3),
+            tokenIn: Address::repeat_byte(4),
+            tokenOut: Address::repeat_byte(5),
+            amountOut: U256::from(1000),
+            srcChainId: U256::from(1),
+            nonce: U256::from(7),
+            preHooks: vec![],
+            postHooks: vec![],
+        };
+        assert_eq!(
+            decode_request_id(&relay_tokens.abi_encode()),
+            Some(request_id)
+        );
+
+        let relay_tokens_permit2 = relayTokensPermit2Call {
+            params: RelayTokensPermit2Params {
+                solver: Address::repeat_byte(1),
+                solverRefundAddress: Address::repeat_byte(1),
+                requestId: request_id,
+                sender: Address::repeat_byte(2),
+                recipient: Address::repeat_byte(3),
+                tokenIn: Address::repeat_byte(4),
+                tokenOut: Address::repeat_byte(5),
+                amountOut: U256::from(1000),
+                srcChainId: U256::from(1),
+                nonce: U256::from(7),
+                permitNonce: U256::from(8),
+                permitDeadline: U256::from(9),
+                signature: Bytes::from(vec![0u8; 64]),
+                preHooks: vec![],
+                postHooks: vec![],
+            },
+        };
+        assert_eq!(
+            decode_request_id(&relay_tokens_permit2.abi_encode()),
+            Some(request_id)
+        );
+
+        // other calls and truncated inputs are ignored
+        let mut truncated = relay_tokens.abi_encode();
+        truncated.truncate(40);
+        assert_eq!(decode_request_id(&truncated), None);
+        assert_eq!(decode_request_id(&[0xde, 0xad, 0xbe, 0xef]), None);
+        assert_eq!(decode_request_id(&[]), None);
+    }
+}
@@ -0,0 +1,131 @@
+#[cfg(feature = "metrics")]
+mod real_metrics {
+    use prometheus::{GaugeVec, IntCounterVec, Opts, Registry};
+    use std::sync::LazyLock;
+
+    pub struct Metrics {
+        pub(super) registry: Registry,
+        pub(super) inventory_balance: GaugeVec,
+        pub(super) inventory_target: GaugeVec,
+        pub(super) rebalance_requested: IntCounterVec,
+        pub(super) competing_fulfilments: IntCounterVec,
+    }
+
+    pub(super) static METRICS: LazyLock<Metrics> = LazyLock::new(|| {
+        let registry = Registry::new();
+
+        let inventory_balance = GaugeVec::new(
+            Opts::new(
+                "solver_inventory_balance",
+                "Latest token balance of the solver per (chain, asset, token) tuple",
+            ),
+            &["chain_id", "asset", "token"],
+        )
+        .expect("failed to create GaugeVec");
+
+        let inventory_target = GaugeVec::new(
+            Opts::new(
+                "solver_inventory_target",
+                "Configured token balance target of the solver per (chain, asset, token) tuple",
+            ),
+            &["chain_id", "asset", "token"],
+        )
+        .expect("failed to create GaugeVec");
+
+        let rebalance_requested = IntCounterVec::new(
+            Opts::new("solver_rebalance_requested", "Total number of rebalancing swaps per (source chain, destination chain, asset, status) tuple"),
+            &["src_chain_id", "dst_chain_id", "asset", "status"],
+        ).expect("failed to create IntCounterVec");
+
+        let competing_fulfilments = IntCounterVec::new(
+            Opts::new(
+                "solver_competing_fulfilments",
+                "Total number of trades with pending fulfilments from other solvers per (chain, action) tuple",
+            ),
+            &["chain_id", "action"],
+        )
+        .expect("failed to create IntCounterVec");
+
+        registry
+            .register(Box::new(inventory_balance.clone()))
+            .expect("metrics failed to initialise");
+        registry
+            .register(Box::new(inventory_target.clone()))
+            .expect("metrics failed to initialise");
+        registry
+            .register(Box::new(rebalance_requested.clone()))
+            .expect("metrics failed to initialise");
+        registry
+            .register(Box::new(competing_fulfilments.clone()))
+            .expect("metrics failed to initialise");
+
+        Metrics {
+            registry,
+            inventory_balance,
+            inventory_target,
+            rebalance_requested,
+            competing_fulfilments,
+        }
+    });
+}
+
+#[cfg(feature = "metrics")]
+pub use real_metrics::Metrics;
+
+#[cfg(not(feature = "metrics"))]
+pub struct Metrics;
+
+#[allow(unused)]
+impl Metrics {
+    pub(super) fn report_inventory(
+        chain_id: u64,
+        asset: &str,
+        token: alloy::primitives::Address,
+        balance: alloy::primitives::U256,
+        target: alloy::primitives::U256,
+    ) {
+        #[cfg(feature = "metrics")]
+        {
+            let labels = [chain_id.to_string(), asset.to_owned(), token.to_string()];
+            real_metrics::METRICS
+                .inventory_balance
+                .with_label_values(&labels)
+                .set(crate::util::u256_to_f64(balance));
+            real_metrics::METRICS
+                .inventory_target
+                .with_label_values(&labels)
+                .set(crate::util::u256_to_f64(target));
+        }
+    }
+
+    pub(super) fn report_rebalance(
+        src_chain_id: u64,
+        dst_chain_id: u64,
+        asset: &str,
+        status: &str,
+    ) {
+        #[cfg(feature = "metrics")]
+        real_metrics::METRICS
+            .rebalance_requested
+            .with_label_values(&[
+                src_chain_id.to_string(),
+                dst_chain_id.to_string(),
+                asset.to_owned(),
+                status.to_owned(),
+            ])
+            .inc();
+    }
+
+    pub(super) fn report_competing_fulfilment(chain_id: u64, action: &str) {
+        #[cfg(feature = "metrics")]
+        real_metrics::METRICS
+            .competing_fulfilments
+            .with_label_values(&[chain_id.to_string(), action.to_owned()])
+            .inc();
+    }
+
+    #[cfg(feature = "metrics")]
+    pub fn gather() -> Vec<prometheus::proto::MetricFamily> {
+        real_metrics::METRICS.registry.gather()
+    }
+}
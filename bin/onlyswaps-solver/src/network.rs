@@ -1,10 +1,13 @@
 use crate::config::NetworkConfig;
+use crate::executor::estimate_gas_cost;
 use crate::model::{ChainState, Transfer};
+use crate::selection::GasCostProvider;
 use crate::solver::ChainStateProvider;
 use alloy::network::EthereumWallet;
 use alloy::primitives::{Address, U256};
 use alloy::providers::{DynProvider, Provider, ProviderBuilder, WsConnect};
 use alloy::signers::local::PrivateKeySigner;
+use anyhow::Context;
 use async_trait::async_trait;
 use futures::future::try_join_all;
 use generated::onlyswaps::erc20_faucet_token::ERC20FaucetToken;
@@ -127,3 +130,13 @@ impl ChainStateProvider for Network<DynProvider> {
         })
     }
 }
+
+#[async_trait]
+impl<P: Provider> GasCostProvider for HashMap<u64, Network<P>> {
+    async fn gas_cost(&self, chain_id: u64) -> anyhow::Result<u128> {
+        let network = self
+            .get(&chain_id)
+            .with_context(|| format!("no network configured for chain {chain_id}"))?;
+        estimate_gas_cost(&network.provider).await
+    }
+}
@@ -1,7 +1,13 @@
+pub mod chainlink;
 pub mod coingecko;
+pub mod composite;
+pub mod fixed;
+pub mod json_http;
 
 use alloy::primitives::ChainId;
-use futures::FutureExt;
+use futures::future::BoxFuture;
+use futures::{FutureExt, TryFutureExt};
+use std::sync::Arc;
 
 /// Allows to fetch the USD value of various tokens on various chains.
 pub trait TokenPriceFeed {
@@ -33,3 +39,91 @@ pub trait TokenPriceFeed {
         token_addresses: impl IntoIterator<Item = String, IntoIter: Send> + Send,
     ) -> impl Future<Output = Result<Vec<f64>, Self::Error>> + Send;
 }
+
+/// A type-erased [`TokenPriceFeed`].
+#[derive(Clone)]
+pub struct ErasedTokenPriceFeed(Arc<dyn DynTokenPriceFeed + Send + Sync + 'static>);
+
+impl ErasedTokenPriceFeed {
+    pub fn from_price_feed<PF: TokenPriceFeed + Send + Sync + 'static>(value: PF) -> Self {
+        Self(Arc::new(value))
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error(transparent)]
+pub struct BoxedPriceFeedError(#[from] Box<dyn std::error::Error + Send + Sync + 'static>);
+
+type BoxedResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync + 'static>>;
+
+impl TokenPriceFeed for ErasedTokenPriceFeed {
+    type Error = BoxedPriceFeedError;
+
+    async fn native_value(&self, chain_id: ChainId) -> Result<f64, Self::Error> {
+        Ok(self.0.native_value(chain_id).await?)
+    }
+
+    async fn token_decimals(
+        &self,
+        chain_id: ChainId,
+        token_address: String,
+    ) -> Result<u8, Self::Error> {
+        Ok(self.0.token_decimals(chain_id, token_address).await?)
+    }
+
+    async fn token_price_batched(
+        &self,
+        chain_id: ChainId,
+        token_addresses: impl IntoIterator<Item = String, IntoIter: Send> + Send,
+    ) -> Result<Vec<f64>, Self::Error> {
+        let token_addresses = token_addresses.into_iter().collect();
+        Ok(self
+            .0
+            .token_price_batched(chain_id, token_addresses)
+            .await?)
+    }
+}
+
+/// A dyn-compatible [`TokenPriceFeed`].
+trait DynTokenPriceFeed {
+    fn native_value(&self, chain_id: ChainId) -> BoxFuture<'_, BoxedResult<f64>>;
+
+    fn token_decimals(
+        &self,
+        chain_id: ChainId,
+        token_address: String,
+    ) -> BoxFuture<'_, BoxedResult<u8>>;
+
+    fn token_price_batched(
+        &self,
+        chain_id: ChainId,
+        token_addresses: Vec<String>,
+    ) -> BoxFuture<'_, BoxedResult<Vec<f64>>>;
+}
+
+/// Blanket impl of [`DynTokenPriceFeed`] for all [`TokenPriceFeed`].
+impl<PF: TokenPriceFeed + Sync> DynTokenPriceFeed for PF {
+    fn native_value(&self, chain_id: ChainId) -> BoxFuture<'_, BoxedResult<f64>> {
+        PF::native_value(self, chain_id).map_err(Into::into).boxed()
+    }
+
+    fn token_decimals(
+        &self,
+        chain_id: ChainId,
+        token_address: String,
+    ) -> BoxFuture<'_, BoxedResult<u8>> {
+        PF::token_decimals(self, chain_id, token_address)
+            .map_err(Into::into)
+            .boxed()
+    }
+
+    fn token_price_batched(
+        &self,
+        chain_id: ChainId,
+        token_addresses: Vec<String>,
+    ) -> BoxFuture<'_, BoxedResult<Vec<f64>>> {
+        PF::token_price_batched(self, chain_id, token_addresses)
+            .map_err(Into::into)
+            .boxed()
+    }
+}
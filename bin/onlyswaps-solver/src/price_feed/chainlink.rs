@@ -0,0 +1,215 @@
+//! A price feed reading Chainlink USD aggregators through the solver's own RPC providers.
+
+use crate::config::ChainlinkPriceFeedConfig;
+use crate::price_feed::TokenPriceFeed;
+use alloy::primitives::{Address, ChainId, I256};
+use alloy::providers::DynProvider;
+use generated::onlyswaps::erc20_faucet_token::ERC20FaucetToken;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+alloy::sol! {
+    #[sol(rpc)]
+    interface AggregatorV3Interface {
+        function decimals() external view returns (uint8);
+        function latestRoundData() external view returns (
+            uint80 roundId,
+            int256 answer,
+            uint256 startedAt,
+            uint256 updatedAt,
+            uint80 answeredInRound
+        );
+    }
+}
+
+/// A [`TokenPriceFeed`] reading Chainlink aggregators.
+///
+/// Each (chain, token) pair is mapped to an aggregator quoting its price in USD, which may be
+/// deployed on another chain. A feed without a token quotes the native currency of the chain.
+pub struct ChainlinkPriceFeed {
+    providers: HashMap<ChainId, DynProvider>,
+    feeds: HashMap<(ChainId, Option<Address>), (ChainId, Address)>,
+    max_age: Duration,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ChainlinkPriceFeedError {
+    #[error("no chainlink feed configured for the native token of chain `{0}`")]
+    UnknownNative(ChainId),
+
+    #[error("no chainlink feed configured for token `{1}` on chain `{0}`")]
+    UnknownToken(ChainId, String),
+
+    #[error("invalid token address `{0}`")]
+    InvalidAddress(String),
+
+    #[error("no provider configured for chain `{0}`")]
+    MissingProvider(ChainId),
+
+    #[error("contract call failed")]
+    ContractCall(#[from] alloy::contract::Error),
+
+    #[error("aggregator returned a non-positive answer")]
+    InvalidAnswer,
+
+    #[error("stale quote, last updated {0:?} ago")]
+    StaleQuote(Duration),
+}
+
+impl ChainlinkPriceFeed {
+    pub(crate) fn new(
+        config: &ChainlinkPriceFeedConfig,
+        providers: HashMap<ChainId, DynProvider>,
+    ) -> Result<Self, ChainlinkPriceFeedError> {
+        let mut feeds = HashMap::new();
+        for feed in &config.feeds {
+            let aggregator_chain_id = feed.aggregator_chain_id.unwrap_or(feed.chain_id);
+            if !providers.contains_key(&aggregator_chain_id) {
+                Err(ChainlinkPriceFeedError::MissingProvider(
+                    aggregator_chain_id,
+                ))?
+            }
+            feeds.insert(
+                (feed.chain_id, feed.token),
+                (aggregator_chain_id, feed.aggregator),
+            );
+        }
+
+        Ok(Self {
+            providers,
+            feeds,
+            max_age: config.max_age,
+        })
+    }
+
+    fn provider(&self, chain_id: ChainId) -> Result<&DynProvider, ChainlinkPriceFeedError> {
+        self.providers
+            .get(&chain_id)
+            .ok_or(ChainlinkPriceFeedError::MissingProvider(chain_id))
+    }
+
+    async fn get_price(
+        &self,
+        (chain_id, aggregator): (ChainId, Address),
+    ) -> Result<f64, ChainlinkPriceFeedError> {
+        let aggregator = AggregatorV3Interface::new(aggregator, self.provider(chain_id)?.clone());
+        let decimals = aggregator.decimals().call().await?;
+        let round = aggregator.latestRoundData().call().await?;
+
+        check_freshness(
+            round.updatedAt.saturating_to(),
+            self.max_age,
+            SystemTime::now(),
+        )?;
+        answer_to_f64(round.answer, decimals)
+    }
+
+    fn token_feed(
+        &self,
+        chain_id: ChainId,
+        token_address: &str,
+    ) -> Result<(ChainId, Address), ChainlinkPriceFeedError> {
+        let token = Address::from_str(token_address)
+            .map_err(|_| ChainlinkPriceFeedError::InvalidAddress(token_address.to_owned()))?;
+        self.feeds
+            .get(&(chain_id, Some(token)))
+            .copied()
+            .ok_or_else(|| {
+                ChainlinkPriceFeedError::UnknownToken(chain_id, token_address.to_owned())
+            })
+    }
+}
+
+impl TokenPriceFeed for ChainlinkPriceFeed {
+    type Error = ChainlinkPriceFeedError;
+
+    async fn native_value(&self, chain_id: ChainId) -> Result<f64, Self::Error> {
+        let feed = self
+            .feeds
+            .get(&(chain_id, None))
+            .copied()
+            .ok_or(ChainlinkPriceFeedError::UnknownNative(chain_id))?;
+        self.get_price(feed).await
+    }
+
+    async fn token_decimals(
+        &self,
+        chain_id: ChainId,
+        token_address: String,
+    ) -> Result<u8, Self::Error> {
+        let token = Address::from_str(&token_address)
+            .map_err(|_| ChainlinkPriceFeedError::InvalidAddress(token_address))?;
+        let token = ERC20FaucetToken::new(token, self.provider(chain_id)?.clone());
+        Ok(token.decimals().call().await?)
+    }
+
+    async fn token_price_batched(
+        &self,
+        chain_id: ChainId,
+        token_addresses: impl IntoIterator<Item = String, IntoIter: Send> + Send,
+    ) -> Result<Vec<f64>, Self::Error> {
+        let feeds = token_addresses
+            .into_iter()
+            .map(|token_address| self.token_feed(chain_id, &token_address))
+            .collect::<Result<Vec<_>, _>>()?;
+        futures::future::try_join_all(feeds.into_iter().map(|feed| self.get_price(feed))).await
+    }
+}
+
+/// Converts an aggregator answer with the given number of decimals to a float.
+fn answer_to_f64(answer: I256, decimals: u8) -> Result<f64, ChainlinkPriceFeedError> {
+    if !answer.is_positive() {
+        Err(ChainlinkPriceFeedError::InvalidAnswer)?
+    }
+
+    Ok(crate::util::u256_to_f64(answer.into_raw()) / 10f64.powi(decimals.into()))
+}
+
+/// Ensures that a round updated at `updated_at` (unix timestamp in seconds) is not too old.
+fn check_freshness(
+    updated_at: u64,
+    max_age: Duration,
+    now: SystemTime,
+) -> Result<(), ChainlinkPriceFeedError> {
+    let updated_at = UNIX_EPOCH + Duration::from_secs(updated_at);
+    let age = now.duration_since(updated_at).unwrap_or_default();
+    if age > max_age {
+        Err(ChainlinkPriceFeedError::StaleQuote(age))?
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answers_are_scaled() {
+        let answer = I256::try_from(300_012_345_678i64).unwrap();
+        let price = answer_to_f64(answer, 8).unwrap();
+        assert!((price - 3000.12345678).abs() < 1e-9);
+
+        assert!(matches!(
+            answer_to_f64(I256::ZERO, 8),
+            Err(ChainlinkPriceFeedError::InvalidAnswer)
+        ));
+        assert!(matches!(
+            answer_to_f64(I256::MINUS_ONE, 8),
+            Err(ChainlinkPriceFeedError::InvalidAnswer)
+        ));
+    }
+
+    #[test]
+    fn stale_rounds_are_rejected() {
+        let now = UNIX_EPOCH + Duration::from_secs(100_000);
+        let max_age = Duration::from_secs(3600);
+
+        assert!(check_freshness(99_000, max_age, now).is_ok());
+        assert!(matches!(
+            check_freshness(90_000, max_age, now),
+            Err(ChainlinkPriceFeedError::StaleQuote(_))
+        ));
+    }
+}
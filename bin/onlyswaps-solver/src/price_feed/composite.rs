@@ -0,0 +1,377 @@
+//! A price feed aggregating the quotes of several other price feeds.
+
+use crate::price_feed::{ErasedTokenPriceFeed, TokenPriceFeed};
+use alloy::primitives::ChainId;
+use futures::future::join_all;
+use moka::future::Cache;
+use std::time::Duration;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum PriceKey {
+    Native(ChainId),
+    Token(ChainId, String),
+}
+
+/// A [`TokenPriceFeed`] querying multiple sources, and returning the median of their quotes.
+///
+/// Sources that fail to return a quote, e.g. due to an outage or a stale price, are ignored, and
+/// so are quotes deviating by more than `max_deviation` from the median of all the quotes. A price
+/// is only returned if at least `min_sources` quotes remain. Prices are cached for `cache_ttl`.
+pub struct CompositePriceFeed {
+    sources: Vec<ErasedTokenPriceFeed>,
+    min_sources: usize,
+    max_deviation: f64,
+    prices: Cache<PriceKey, f64>,
+    decimals: Cache<(ChainId, String), u8>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum CompositePriceFeedError {
+    #[error("not enough sources available: got {available}, required {required}")]
+    NotEnoughSources { available: usize, required: usize },
+
+    #[error("sources diverge: only {agreeing} agreeing, required {required}")]
+    Divergent { agreeing: usize, required: usize },
+
+    #[error("no source could provide the token decimals")]
+    NoDecimals,
+}
+
+impl CompositePriceFeed {
+    pub(crate) fn new(
+        sources: Vec<ErasedTokenPriceFeed>,
+        min_sources: usize,
+        max_deviation: f64,
+        cache_ttl: Duration,
+    ) -> Self {
+        Self {
+            sources,
+            min_sources: min_sources.max(1),
+            max_deviation,
+            prices: Cache::builder()
+                .max_capacity(1000)
+                .time_to_live(cache_ttl)
+                .build(),
+            decimals: Cache::builder().max_capacity(1000).build(),
+        }
+    }
+
+    fn aggregate(&self, key: &PriceKey, quotes: Vec<f64>) -> Result<f64, CompositePriceFeedError> {
+        let price = aggregate(quotes, self.min_sources, self.max_deviation);
+        if let Err(e) = &price {
+            tracing::warn!(?key, error = ?e, "Failed to aggregate price quotes");
+        }
+
+        price
+    }
+}
+
+impl TokenPriceFeed for CompositePriceFeed {
+    type Error = CompositePriceFeedError;
+
+    async fn native_value(&self, chain_id: ChainId) -> Result<f64, Self::Error> {
+        let key = PriceKey::Native(chain_id);
+        if let Some(price) = self.prices.get(&key).await {
+            return Ok(price);
+        }
+
+        let quotes = join_all(
+            self.sources
+                .iter()
+                .map(|source| source.native_value(chain_id)),
+        )
+        .await
+        .into_iter()
+        .enumerate()
+        .filter_map(|(source, quote)| {
+            quote
+                .inspect_err(|e| {
+                    tracing::debug!(source, chain_id, error = ?e, "Price source failed to provide native value")
+                })
+                .ok()
+        })
+        .collect();
+
+        let price = self.aggregate(&key, quotes)?;
+        self.prices.insert(key, price).await;
+        Ok(price)
+    }
+
+    async fn token_decimals(
+        &self,
+        chain_id: ChainId,
+        token_address: String,
+    ) -> Result<u8, Self::Error> {
+        let key = (chain_id, token_address.to_lowercase());
+        if let Some(decimals) = self.decimals.get(&key).await {
+            return Ok(decimals);
+        }
+
+        for (source, feed) in self.sources.iter().enumerate() {
+            match feed.token_decimals(chain_id, token_address.clone()).await {
+                Ok(decimals) => {
+                    self.decimals.insert(key, decimals).await;
+                    return Ok(decimals);
+                }
+                Err(e) => {
+                    tracing::debug!(source, chain_id, token_address, error = ?e, "Price source failed to provide token decimals")
+                }
+            }
+        }
+
+        Err(CompositePriceFeedError::NoDecimals)
+    }
+
+    async fn token_price_batched(
+        &self,
+        chain_id: ChainId,
+        token_addresses: impl IntoIterator<Item = String, IntoIter: Send> + Send,
+    ) -> Result<Vec<f64>, Self::Error> {
+        let keys: Vec<_> = token_addresses
+            .into_iter()
+            .map(|token_address| PriceKey::Token(chain_id, token_address.to_lowercase()))
+            .collect();
+
+        // only query the prices that are not cached yet
+        let mut prices = Vec::with_capacity(keys.len());
+        let mut missing = Vec::new();
+        for (i, key) in keys.iter().enumerate() {
+            let price = self.prices.get(key).await;
+            if price.is_none() {
+                missing.push(i);
+            }
+            prices.push(price);
+        }
+        if missing.is_empty() {
+            return Ok(prices.into_iter().flatten().collect());
+        }
+
+        let missing_addresses: Vec<_> = missing
+            .iter()
+            .map(|&i| match &keys[i] {
+                PriceKey::Token(_, token_address) => token_address.clone(),
+                PriceKey::Native(_) => unreachable!("only token keys are created above"),
+            })
+            .collect();
+        let source_quotes: Vec<_> = join_all(
+            self.sources
+                .iter()
+                .map(|source| source.token_price_batched(chain_id, missing_addresses.clone())),
+        )
+        .await
+        .into_iter()
+        .enumerate()
+        .filter_map(|(source, quotes)| {
+            quotes
+                .inspect_err(|e| {
+                    tracing::debug!(source, chain_id, error = ?e, "Price source failed to provide token prices")
+                })
+                .ok()
+                .filter(|quotes| quotes.len() == missing_addresses.len())
+        })
+        .collect();
+
+        for (j, &i) in missing.iter().enumerate() {
+            let quotes = source_quotes.iter().map(|quotes| quotes[j]).collect();
+            let price = self.aggregate(&keys[i], quotes)?;
+            self.prices.insert(keys[i].clone(), price).await;
+            prices[i] = Some(price);
+        }
+
+        Ok(prices.into_iter().flatten().collect())
+    }
+}
+
+/// Returns the median of the quotes agreeing with the median of all the quotes.
+fn aggregate(
+    mut quotes: Vec<f64>,
+    min_sources: usize,
+    max_deviation: f64,
+) -> Result<f64, CompositePriceFeedError> {
+    quotes.retain(|q| q.is_finite());
+    if quotes.len() < min_sources || quotes.is_empty() {
+        Err(CompositePriceFeedError::NotEnoughSources {
+            available: quotes.len(),
+            required: min_sources,
+        })?
+    }
+
+    let reference = median(&mut quotes);
+    quotes.retain(|q| (q - reference).abs() <= max_deviation * reference.abs());
+    if quotes.len() < min_sources || quotes.is_empty() {
+        Err(CompositePriceFeedError::Divergent {
+            agreeing: quotes.len(),
+            required: min_sources,
+        })?
+    }
+
+    Ok(median(&mut quotes))
+}
+
+/// Median of a non-empty list of finite values.
+fn median(values: &mut [f64]) -> f64 {
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    if values.len() % 2 == 1 {
+        values[mid]
+    } else {
+        (values[mid - 1] + values[mid]) / 2.
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(thiserror::Error, Debug)]
+    #[error("source unavailable")]
+    struct Unavailable;
+
+    struct StubFeed {
+        price: Option<f64>,
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl StubFeed {
+        fn erased(price: Option<f64>) -> ErasedTokenPriceFeed {
+            ErasedTokenPriceFeed::from_price_feed(Self {
+                price,
+                calls: Arc::default(),
+            })
+        }
+    }
+
+    impl TokenPriceFeed for StubFeed {
+        type Error = Unavailable;
+
+        async fn native_value(&self, _chain_id: ChainId) -> Result<f64, Self::Error> {
+            self.calls.fetch_add(1, Ordering::Relaxed);
+            self.price.ok_or(Unavailable)
+        }
+
+        async fn token_decimals(
+            &self,
+            _chain_id: ChainId,
+            _token_address: String,
+        ) -> Result<u8, Self::Error> {
+            self.price.map(|_| 18).ok_or(Unavailable)
+        }
+
+        async fn token_price_batched(
+            &self,
+            _chain_id: ChainId,
+            token_addresses: impl IntoIterator<Item = String, IntoIter: Send> + Send,
+        ) -> Result<Vec<f64>, Self::Error> {
+            self.calls.fetch_add(1, Ordering::Relaxed);
+            let price = self.price.ok_or(Unavailable)?;
+            Ok(token_addresses.into_iter().map(|_| price).collect())
+        }
+    }
+
+    fn composite(prices: &[Option<f64>], min_sources: usize) -> CompositePriceFeed {
+        CompositePriceFeed::new(
+            prices.iter().map(|p| StubFeed::erased(*p)).collect(),
+            min_sources,
+            0.05,
+            Duration::from_secs(60),
+        )
+    }
+
+    #[test]
+    fn median_of_agreeing_quotes() {
+        assert_eq!(aggregate(vec![1.0], 1, 0.05).unwrap(), 1.0);
+        assert_eq!(aggregate(vec![1.0, 1.02, 0.99], 1, 0.05).unwrap(), 1.0);
+        assert_eq!(aggregate(vec![1.0, 1.03125], 2, 0.05).unwrap(), 1.015625);
+        // the outlier is ignored
+        assert_eq!(
+            aggregate(vec![1.0, 1.03125, 5.0], 2, 0.05).unwrap(),
+            1.015625
+        );
+    }
+
+    #[test]
+    fn divergent_quotes_are_rejected() {
+        assert!(matches!(
+            aggregate(vec![1.0, 1.5, 2.0], 2, 0.05),
+            Err(CompositePriceFeedError::Divergent {
+                agreeing: 1,
+                required: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn min_sources_is_enforced() {
+        assert!(matches!(
+            aggregate(vec![1.0], 2, 0.05),
+            Err(CompositePriceFeedError::NotEnoughSources {
+                available: 1,
+                required: 2
+            })
+        ));
+        assert!(matches!(
+            aggregate(vec![], 1, 0.05),
+            Err(CompositePriceFeedError::NotEnoughSources { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn failing_sources_are_ignored() {
+        let feed = composite(&[Some(1.0), None, Some(1.03125)], 2);
+        assert_eq!(feed.native_value(1).await.unwrap(), 1.015625);
+        assert_eq!(
+            feed.token_price_batched(1, ["0xA".to_owned(), "0xB".to_owned()])
+                .await
+                .unwrap(),
+            vec![1.015625, 1.015625]
+        );
+        assert_eq!(feed.token_decimals(1, "0xA".to_owned()).await.unwrap(), 18);
+
+        let feed = composite(&[Some(1.0), None, None], 2);
+        assert!(feed.native_value(1).await.is_err());
+        assert!(feed.token_value(1, "0xA".to_owned()).await.is_err());
+        let feed = composite(&[None], 1);
+        assert!(matches!(
+            feed.token_decimals(1, "0xA".to_owned()).await,
+            Err(CompositePriceFeedError::NoDecimals)
+        ));
+    }
+
+    #[tokio::test]
+    async fn prices_are_cached() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let feed = CompositePriceFeed::new(
+            vec![ErasedTokenPriceFeed::from_price_feed(StubFeed {
+                price: Some(2.0),
+                calls: calls.clone(),
+            })],
+            1,
+            0.05,
+            Duration::from_secs(60),
+        );
+
+        assert_eq!(feed.native_value(1).await.unwrap(), 2.0);
+        assert_eq!(feed.native_value(1).await.unwrap(), 2.0);
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+
+        assert_eq!(feed.token_value(1, "0xA".to_owned()).await.unwrap(), 2.0);
+        assert_eq!(calls.load(Ordering::Relaxed), 2);
+        // only the uncached token is queried, and addresses are case-insensitive
+        assert_eq!(
+            feed.token_price_batched(1, ["0xa".to_owned(), "0xB".to_owned()])
+                .await
+                .unwrap(),
+            vec![2.0, 2.0]
+        );
+        assert_eq!(calls.load(Ordering::Relaxed), 3);
+        assert_eq!(
+            feed.token_price_batched(1, ["0xA".to_owned(), "0xB".to_owned()])
+                .await
+                .unwrap(),
+            vec![2.0, 2.0]
+        );
+        assert_eq!(calls.load(Ordering::Relaxed), 3);
+    }
+}
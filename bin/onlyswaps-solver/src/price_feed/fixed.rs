@@ -0,0 +1,150 @@
+//! A price feed returning fixed prices, mostly useful on testnets where tokens have no market.
+
+use crate::config::{StaticNativePrice, StaticPriceFeedConfig, StaticPrices, StaticTokenPrice};
+use crate::price_feed::TokenPriceFeed;
+use alloy::primitives::{Address, ChainId};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// A [`TokenPriceFeed`] backed by a fixed set of prices.
+#[derive(Clone, Debug, Default)]
+pub struct StaticPriceFeed {
+    native: HashMap<ChainId, f64>,
+    tokens: HashMap<(ChainId, Address), (f64, u8)>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum StaticPriceFeedError {
+    #[error("no static price for the native token of chain `{0}`")]
+    UnknownNative(ChainId),
+
+    #[error("no static price for token `{1}` on chain `{0}`")]
+    UnknownToken(ChainId, String),
+
+    #[error("invalid token address `{0}`")]
+    InvalidAddress(String),
+}
+
+impl StaticPriceFeed {
+    pub(crate) fn new(prices: &StaticPrices) -> Self {
+        let mut feed = Self::default();
+        feed.extend(prices);
+        feed
+    }
+
+    /// Creates a feed from the configured prices, with prices loaded from the configured file
+    /// taking precedence over the inline ones.
+    pub(crate) fn from_config(config: &StaticPriceFeedConfig) -> anyhow::Result<Self> {
+        let mut feed = Self::new(&config.prices);
+        if let Some(path) = &config.path {
+            let prices: StaticPrices = ::config::file::load_config_file(path.clone())?;
+            feed.extend(&prices);
+        }
+
+        Ok(feed)
+    }
+
+    /// Add prices to the feed, overriding existing ones.
+    pub(crate) fn extend(&mut self, prices: &StaticPrices) {
+        for StaticNativePrice { chain_id, usd } in &prices.native {
+            self.native.insert(*chain_id, *usd);
+        }
+        for StaticTokenPrice {
+            chain_id,
+            address,
+            usd,
+            decimals,
+        } in &prices.tokens
+        {
+            self.tokens.insert((*chain_id, *address), (*usd, *decimals));
+        }
+    }
+
+    fn token(
+        &self,
+        chain_id: ChainId,
+        token_address: &str,
+    ) -> Result<(f64, u8), StaticPriceFeedError> {
+        let address = Address::from_str(token_address)
+            .map_err(|_| StaticPriceFeedError::InvalidAddress(token_address.to_owned()))?;
+        self.tokens
+            .get(&(chain_id, address))
+            .copied()
+            .ok_or_else(|| StaticPriceFeedError::UnknownToken(chain_id, token_address.to_owned()))
+    }
+}
+
+impl TokenPriceFeed for StaticPriceFeed {
+    type Error = StaticPriceFeedError;
+
+    async fn native_value(&self, chain_id: ChainId) -> Result<f64, Self::Error> {
+        self.native
+            .get(&chain_id)
+            .copied()
+            .ok_or(StaticPriceFeedError::UnknownNative(chain_id))
+    }
+
+    async fn token_decimals(
+        &self,
+        chain_id: ChainId,
+        token_address: String,
+    ) -> Result<u8, Self::Error> {
+        Ok(self.token(chain_id, &token_address)?.1)
+    }
+
+    async fn token_price_batched(
+        &self,
+        chain_id: ChainId,
+        token_addresses: impl IntoIterator<Item = String, IntoIter: Send> + Send,
+    ) -> Result<Vec<f64>, Self::Error> {
+        token_addresses
+            .into_iter()
+            .map(|token_address| Ok(self.token(chain_id, &token_address)?.0))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::address;
+
+    static TOKEN_ADDR: Address = address!("0x1b0F6cF6f3185872a581BD2B5a738EB52CCd4d76");
+
+    #[tokio::test]
+    async fn static_prices() {
+        let mut feed = StaticPriceFeed::new(&StaticPrices {
+            native: vec![StaticNativePrice {
+                chain_id: 1,
+                usd: 3000.0,
+            }],
+            tokens: vec![StaticTokenPrice {
+                chain_id: 1,
+                address: TOKEN_ADDR,
+                usd: 1.0,
+                decimals: 6,
+            }],
+        });
+        feed.extend(&StaticPrices {
+            native: vec![StaticNativePrice {
+                chain_id: 1,
+                usd: 3500.0,
+            }],
+            tokens: vec![],
+        });
+
+        assert_eq!(feed.native_value(1).await.unwrap(), 3500.0);
+        // addresses are matched regardless of their case
+        let lowercase = TOKEN_ADDR.to_string().to_lowercase();
+        assert_eq!(feed.token_value(1, lowercase.clone()).await.unwrap(), 1.0);
+        assert_eq!(feed.token_decimals(1, lowercase).await.unwrap(), 6);
+
+        assert!(feed.native_value(2).await.is_err());
+        assert!(feed.token_value(2, TOKEN_ADDR.to_string()).await.is_err());
+        assert!(
+            feed.token_value(1, "not an address".to_owned())
+                .await
+                .is_err()
+        );
+    }
+}
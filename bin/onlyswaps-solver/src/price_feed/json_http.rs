@@ -0,0 +1,277 @@
+//! A price feed fetching prices from any HTTP API returning JSON.
+
+use crate::config::JsonHttpPriceFeedConfig;
+use crate::price_feed::TokenPriceFeed;
+use alloy::primitives::ChainId;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde_json::Value;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// A [`TokenPriceFeed`] querying a JSON-HTTP API.
+///
+/// The urls may contain the `{chain_id}` and `{address}` placeholders, substituted by the chain id
+/// and the lowercase token address respectively. Values are extracted from the responses using
+/// JSON pointers, and may either be numbers or strings. If a timestamp pointer is configured,
+/// quotes older than the maximum age are rejected.
+pub struct JsonHttpPriceFeed {
+    client: reqwest::Client,
+    native_url: String,
+    token_url: String,
+    price_pointer: String,
+    decimals_pointer: Option<String>,
+    timestamp_pointer: Option<String>,
+    max_age: Duration,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum JsonHttpPriceFeedError {
+    #[error("failed to build http client")]
+    BuildClient(#[source] reqwest::Error),
+
+    #[error("invalid header `{0}`")]
+    InvalidHeader(String),
+
+    #[error("http error")]
+    HttpError(#[source] reqwest::Error),
+
+    #[error("field `{0}` missing from response")]
+    MissingField(String),
+
+    #[error("field `{0}` is not a valid number")]
+    InvalidField(String),
+
+    #[error("stale quote, last updated {0:?} ago")]
+    StaleQuote(Duration),
+
+    #[error("quote updated {0:?} in the future")]
+    FutureQuote(Duration),
+
+    #[error("invalid quote timestamp `{0}`")]
+    InvalidTimestamp(f64),
+
+    #[error("token decimals are not provided by this feed")]
+    NoDecimals,
+}
+
+impl JsonHttpPriceFeed {
+    pub(crate) fn new(config: &JsonHttpPriceFeedConfig) -> Result<Self, JsonHttpPriceFeedError> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &config.headers {
+            let invalid = || JsonHttpPriceFeedError::InvalidHeader(name.clone());
+            headers.insert(
+                HeaderName::from_str(name).map_err(|_| invalid())?,
+                HeaderValue::from_str(value).map_err(|_| invalid())?,
+            );
+        }
+
+        let client = reqwest::Client::builder()
+            .default_headers(headers)
+            .build()
+            .map_err(JsonHttpPriceFeedError::BuildClient)?;
+
+        Ok(Self {
+            client,
+            native_url: config.native_url.clone(),
+            token_url: config.token_url.clone(),
+            price_pointer: config.price_pointer.clone(),
+            decimals_pointer: config.decimals_pointer.clone(),
+            timestamp_pointer: config.timestamp_pointer.clone(),
+            max_age: config.max_age,
+        })
+    }
+
+    async fn get_json(&self, url: String) -> Result<Value, JsonHttpPriceFeedError> {
+        let value = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(JsonHttpPriceFeedError::HttpError)?
+            .json()
+            .await
+            .map_err(JsonHttpPriceFeedError::HttpError)?;
+        tracing::trace!(%url, ?value, "Got response from json-http price feed");
+
+        Ok(value)
+    }
+
+    async fn get_price(&self, url: String) -> Result<f64, JsonHttpPriceFeedError> {
+        let value = self.get_json(url).await?;
+        if let Some(timestamp_pointer) = &self.timestamp_pointer {
+            let updated_at = read_number(&value, timestamp_pointer)?;
+            check_freshness(updated_at, self.max_age, SystemTime::now())?;
+        }
+
+        read_number(&value, &self.price_pointer)
+    }
+}
+
+impl TokenPriceFeed for JsonHttpPriceFeed {
+    type Error = JsonHttpPriceFeedError;
+
+    async fn native_value(&self, chain_id: ChainId) -> Result<f64, Self::Error> {
+        self.get_price(format_url(&self.native_url, chain_id, None))
+            .await
+    }
+
+    async fn token_decimals(
+        &self,
+        chain_id: ChainId,
+        token_address: String,
+    ) -> Result<u8, Self::Error> {
+        let decimals_pointer = self
+            .decimals_pointer
+            .as_ref()
+            .ok_or(JsonHttpPriceFeedError::NoDecimals)?;
+        let value = self
+            .get_json(format_url(&self.token_url, chain_id, Some(&token_address)))
+            .await?;
+
+        let decimals = read_number(&value, decimals_pointer)?;
+        if decimals.fract() != 0. || !(0. ..=u8::MAX as f64).contains(&decimals) {
+            Err(JsonHttpPriceFeedError::InvalidField(
+                decimals_pointer.to_owned(),
+            ))?
+        }
+
+        Ok(decimals as u8)
+    }
+
+    async fn token_price_batched(
+        &self,
+        chain_id: ChainId,
+        token_addresses: impl IntoIterator<Item = String, IntoIter: Send> + Send,
+    ) -> Result<Vec<f64>, Self::Error> {
+        futures::future::try_join_all(token_addresses.into_iter().map(|token_address| {
+            self.get_price(format_url(&self.token_url, chain_id, Some(&token_address)))
+        }))
+        .await
+    }
+}
+
+fn format_url(template: &str, chain_id: ChainId, token_address: Option<&str>) -> String {
+    let url = template.replace("{chain_id}", &chain_id.to_string());
+    match token_address {
+        Some(token_address) => url.replace("{address}", &token_address.to_lowercase()),
+        None => url,
+    }
+}
+
+/// Read a number, or a string containing a number, located at `pointer`.
+fn read_number(value: &Value, pointer: &str) -> Result<f64, JsonHttpPriceFeedError> {
+    let field = value
+        .pointer(pointer)
+        .ok_or_else(|| JsonHttpPriceFeedError::MissingField(pointer.to_owned()))?;
+    let number = match field {
+        Value::Number(number) => number.as_f64(),
+        Value::String(number) => number.parse().ok(),
+        _ => None,
+    };
+
+    number
+        .filter(|n: &f64| n.is_finite())
+        .ok_or_else(|| JsonHttpPriceFeedError::InvalidField(pointer.to_owned()))
+}
+
+/// Maximum clock skew tolerated for quotes updated in the future.
+const MAX_CLOCK_SKEW: Duration = Duration::from_secs(30);
+
+/// Ensures that a quote updated at `updated_at` (unix timestamp in seconds) is not too old, nor
+/// too far in the future, which would indicate a timestamp in another unit.
+fn check_freshness(
+    updated_at: f64,
+    max_age: Duration,
+    now: SystemTime,
+) -> Result<(), JsonHttpPriceFeedError> {
+    let updated_at = Duration::try_from_secs_f64(updated_at)
+        .ok()
+        .and_then(|since_epoch| UNIX_EPOCH.checked_add(since_epoch))
+        .ok_or(JsonHttpPriceFeedError::InvalidTimestamp(updated_at))?;
+    match now.duration_since(updated_at) {
+        Ok(age) if age > max_age => Err(JsonHttpPriceFeedError::StaleQuote(age))?,
+        Err(e) if e.duration() > MAX_CLOCK_SKEW => {
+            Err(JsonHttpPriceFeedError::FutureQuote(e.duration()))?
+        }
+        _ => (),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn urls_are_formatted() {
+        let template = "https://prices.example.com/{chain_id}/{address}";
+        assert_eq!(
+            format_url(template, 43113, Some("0xABCD")),
+            "https://prices.example.com/43113/0xabcd"
+        );
+        assert_eq!(
+            format_url("https://prices.example.com/{chain_id}/native", 1, None),
+            "https://prices.example.com/1/native"
+        );
+    }
+
+    #[test]
+    fn numbers_are_read_from_pointers() {
+        let value = json!({
+            "data": { "usd": 1.5, "usd_str": "2.5", "name": "token", "nan": "NaN" }
+        });
+
+        assert_eq!(read_number(&value, "/data/usd").unwrap(), 1.5);
+        assert_eq!(read_number(&value, "/data/usd_str").unwrap(), 2.5);
+        assert!(matches!(
+            read_number(&value, "/data/eur"),
+            Err(JsonHttpPriceFeedError::MissingField(_))
+        ));
+        assert!(matches!(
+            read_number(&value, "/data/name"),
+            Err(JsonHttpPriceFeedError::InvalidField(_))
+        ));
+        assert!(matches!(
+            read_number(&value, "/data/nan"),
+            Err(JsonHttpPriceFeedError::InvalidField(_))
+        ));
+    }
+
+    #[test]
+    fn stale_quotes_are_rejected() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_000);
+        let max_age = Duration::from_secs(60);
+
+        assert!(check_freshness(990., max_age, now).is_ok());
+        assert!(check_freshness(1_010., max_age, now).is_ok());
+        assert!(matches!(
+            check_freshness(900., max_age, now),
+            Err(JsonHttpPriceFeedError::StaleQuote(_))
+        ));
+        assert!(matches!(
+            check_freshness(1_100., max_age, now),
+            Err(JsonHttpPriceFeedError::FutureQuote(_))
+        ));
+    }
+
+    #[test]
+    fn invalid_timestamps_are_rejected() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let max_age = Duration::from_secs(60);
+
+        // a fresh timestamp in milliseconds
+        assert!(matches!(
+            check_freshness(1_700_000_000_000., max_age, now),
+            Err(JsonHttpPriceFeedError::FutureQuote(_))
+        ));
+        for updated_at in [f64::INFINITY, f64::NAN, -1., f64::MAX] {
+            assert!(matches!(
+                check_freshness(updated_at, max_age, now),
+                Err(JsonHttpPriceFeedError::InvalidTimestamp(_))
+            ));
+        }
+    }
+}
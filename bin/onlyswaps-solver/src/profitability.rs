@@ -6,6 +6,7 @@ mod standard;
 pub use standard::*;
 
 use crate::model::Trade;
+use crate::util::u256_to_f64;
 use futures::future::BoxFuture;
 use futures::{FutureExt, TryFutureExt};
 use std::convert::Infallible;
@@ -21,6 +22,15 @@ pub trait ProfitabilityEstimator {
         gas_estimate: u64,
         gas_cost: u128,
     ) -> impl Future<Output = Result<bool, Self::Error>> + Send;
+
+    /// Estimate the profit made by fulfilling a [`Trade`], allowing to compare trades between
+    /// them. A trade is expected to be profitable only if its profit is positive.
+    fn expected_profit(
+        &self,
+        trade: &Trade,
+        gas_estimate: u64,
+        gas_cost: u128,
+    ) -> impl Future<Output = Result<f64, Self::Error>> + Send;
 }
 
 /// A type-erased [`ProfitabilityEstimator`].
@@ -48,6 +58,18 @@ impl ProfitabilityEstimator for ErasedProfitabilityEstimator {
     ) -> Result<bool, Self::Error> {
         Ok(self.0.is_profitable(trade, gas_estimate, gas_cost).await?)
     }
+
+    async fn expected_profit(
+        &self,
+        trade: &Trade,
+        gas_estimate: u64,
+        gas_cost: u128,
+    ) -> Result<f64, Self::Error> {
+        Ok(self
+            .0
+            .expected_profit(trade, gas_estimate, gas_cost)
+            .await?)
+    }
 }
 
 /// A dyn-compatible [`ProfitabilityEstimator`].
@@ -58,6 +80,13 @@ trait DynProfitabilityEstimator {
         gas_estimate: u64,
         gas_cost: u128,
     ) -> BoxFuture<'a, Result<bool, Box<dyn std::error::Error + Send + Sync + 'static>>>;
+
+    fn expected_profit<'a>(
+        &'a self,
+        trade: &'a Trade,
+        gas_estimate: u64,
+        gas_cost: u128,
+    ) -> BoxFuture<'a, Result<f64, Box<dyn std::error::Error + Send + Sync + 'static>>>;
 }
 
 /// Blanket impl of [`DynProfitabilityEstimator`] for all [`ProfitabilityEstimator`].
@@ -72,6 +101,17 @@ impl<PE: ProfitabilityEstimator> DynProfitabilityEstimator for PE {
             .map_err(Into::into)
             .boxed()
     }
+
+    fn expected_profit<'a>(
+        &'a self,
+        trade: &'a Trade,
+        gas_estimate: u64,
+        gas_cost: u128,
+    ) -> BoxFuture<'a, Result<f64, Box<dyn std::error::Error + Send + Sync + 'static>>> {
+        PE::expected_profit(self, trade, gas_estimate, gas_cost)
+            .map_err(Into::into)
+            .boxed()
+    }
 }
 
 /// A [`ProfitabilityEstimator`] that always returns true.
@@ -88,4 +128,17 @@ impl ProfitabilityEstimator for AlwaysProfitable {
     ) -> impl Future<Output = Result<bool, Self::Error>> + Send {
         std::future::ready(Ok(true))
     }
+
+    /// Without market data, the solver fee (in base units of the source token) is used as the
+    /// profit of a trade. Since the fees of different tokens are not comparable, and the gas cost
+    /// is ignored, it must not be used to select trades with a
+    /// [`KnapsackSelection`](crate::selection::KnapsackSelection).
+    fn expected_profit(
+        &self,
+        trade: &Trade,
+        _gas_estimate: u64,
+        _gas_cost: u128,
+    ) -> impl Future<Output = Result<f64, Self::Error>> + Send {
+        std::future::ready(Ok(u256_to_f64(trade.solver_fee)))
+    }
 }
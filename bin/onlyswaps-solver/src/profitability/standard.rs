@@ -40,6 +40,17 @@ impl<PF: TokenPriceFeed + Sync> ProfitabilityEstimator for StdProfitabilityEstim
     ) -> Result<bool, Self::Error> {
         Ok(profitability_breaker(trade, gas_estimate, gas_cost, &self.price_feed).await?)
     }
+
+    async fn expected_profit(
+        &self,
+        trade: &Trade,
+        gas_estimate: u64,
+        gas_cost: u128,
+    ) -> Result<f64, Self::Error> {
+        let fulfillment =
+            evaluate_fulfillment(trade, gas_estimate, gas_cost, &self.price_feed).await?;
+        Ok(fulfillment.reward - fulfillment.cost)
+    }
 }
 
 /// Ensures that a trade is profitable by comparing the incurred costs in equivalent currencies using
@@ -51,6 +62,32 @@ async fn profitability_breaker(
     gas_cost: u128,
     price_feed: &impl TokenPriceFeed,
 ) -> anyhow::Result<bool> {
+    let fulfillment = evaluate_fulfillment(trade, gas_estimate, gas_cost, price_feed).await?;
+
+    if fulfillment.is_profitable() {
+        tracing::debug!(
+            fulfillment_cost = fulfillment.cost,
+            fulfillment_reward = fulfillment.reward,
+            "Trade is profitable"
+        );
+        Ok(true)
+    } else {
+        tracing::warn!(
+            fulfillment_cost = fulfillment.cost,
+            fulfillment_reward = fulfillment.reward,
+            "Trade not profitable"
+        );
+        Ok(false)
+    }
+}
+
+/// Computes the USD cost and reward of fulfilling a trade using a price feed.
+async fn evaluate_fulfillment(
+    trade: &Trade,
+    gas_estimate: u64,
+    gas_cost: u128,
+    price_feed: &impl TokenPriceFeed,
+) -> anyhow::Result<FulfillmentData> {
     let gas_cost_upper_bound = u128::from(gas_estimate)
         .checked_mul(gas_cost)
         .context("gas cost overflow")?;
@@ -64,28 +101,12 @@ async fn profitability_breaker(
     )
     .await?;
 
-    let fulfillment = FulfillmentData::evaluate(
+    FulfillmentData::evaluate(
         gas_cost_upper_bound,
         trade.solver_refund_amount,
         trade.amount_out,
         &market_data,
-    )?;
-
-    if fulfillment.is_profitable() {
-        tracing::debug!(
-            fulfillment_cost = fulfillment.cost,
-            fulfillment_reward = fulfillment.reward,
-            "Trade is profitable"
-        );
-        Ok(true)
-    } else {
-        tracing::warn!(
-            fulfillment_cost = fulfillment.cost,
-            fulfillment_reward = fulfillment.reward,
-            "Trade not profitable"
-        );
-        Ok(false)
-    }
+    )
 }
 
 struct FulfillmentData {
@@ -352,4 +373,26 @@ mod tests {
             .expect("to evaluate with Ok");
         assert!(!profitable, "trade should be unprofitable");
     }
+
+    #[tokio::test]
+    async fn expected_profit_matches_profitability() {
+        let (trade, gas_estimate, gas_price, mut market_data) = real_trade();
+
+        let price_feed = FakePriceFeed::from_trade_data(&trade, &market_data);
+        let estimator = StdProfitabilityEstimator::new(price_feed);
+        let profit = estimator
+            .expected_profit(&trade, gas_estimate, gas_price)
+            .await
+            .expect("to evaluate with Ok");
+        assert!(profit > 0f64, "trade should have a positive profit");
+
+        market_data.native_value_dst *= 100f64;
+        let price_feed = FakePriceFeed::from_trade_data(&trade, &market_data);
+        let estimator = StdProfitabilityEstimator::new(price_feed);
+        let profit = estimator
+            .expected_profit(&trade, gas_estimate, gas_price)
+            .await
+            .expect("to evaluate with Ok");
+        assert!(profit < 0f64, "trade should have a negative profit");
+    }
 }
@@ -0,0 +1,539 @@
+//! Keeps the solver's inventory balanced across chains.
+//!
+//! Fulfilling swaps drains the solver's tokens on destination chains while refunds accumulate on
+//! source chains. The [`Rebalancer`] periodically compares balances against the configured
+//! [`InventoryTarget`]s, and requests swaps through a [`BridgeAdapter`] to move tokens from chains
+//! with a surplus to chains below their minimum.
+
+use crate::config::{InventoryTarget, RebalancingConfig};
+use crate::metrics::Metrics;
+use crate::model::{ChainState, RequestId};
+use crate::solver::ChainStateProvider;
+use alloy::primitives::{Address, U256};
+use anyhow::Context;
+use async_trait::async_trait;
+use futures::future::try_join_all;
+use moka::future::Cache;
+use onlyswaps_client::FeeEstimator;
+use onlyswaps_client::client::routing::SwapRouting;
+use onlyswaps_client::client::{OnlySwapsClient, OnlySwapsRequestBuilder};
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+/// A transfer of tokens from one chain to another required to restore inventory targets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RebalanceAction {
+    pub asset: String,
+    pub src_chain_id: u64,
+    pub src_token: Address,
+    pub dst_chain_id: u64,
+    pub dst_token: Address,
+    /// Amount of tokens that must be received on the destination chain
+    pub amount: U256,
+}
+
+/// Moves tokens across chains on behalf of the [`Rebalancer`].
+#[async_trait]
+pub(crate) trait BridgeAdapter {
+    async fn bridge(
+        &self,
+        action: &RebalanceAction,
+        recipient: Address,
+    ) -> anyhow::Result<RequestId>;
+}
+
+/// A [`BridgeAdapter`] that requests swaps through onlyswaps, letting other solvers fulfil them.
+pub(crate) struct OnlySwapsBridgeAdapter {
+    client: OnlySwapsClient,
+}
+
+impl OnlySwapsBridgeAdapter {
+    pub fn new(client: OnlySwapsClient) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl BridgeAdapter for OnlySwapsBridgeAdapter {
+    async fn bridge(
+        &self,
+        action: &RebalanceAction,
+        recipient: Address,
+    ) -> anyhow::Result<RequestId> {
+        let request = OnlySwapsRequestBuilder::new()
+            .recipient(recipient)
+            .route(SwapRouting {
+                src_chain: action.src_chain_id,
+                dst_chain: action.dst_chain_id,
+                src_token: action.src_token,
+                dst_token: action.dst_token,
+            })
+            .exact_amount(action.amount, &FeeEstimator::default())
+            .await
+            .context("failed to estimate rebalancing fees")?
+            .build()
+            .context("incomplete rebalancing request")?;
+
+        let receipt = self
+            .client
+            .approve_and_swap(request)
+            .await
+            .context("failed to request rebalancing swap")?;
+        Ok(receipt.request_id)
+    }
+}
+
+pub(crate) struct Rebalancer<'a, CSP, BA> {
+    chains: &'a HashMap<u64, CSP>,
+    bridge: BA,
+    targets: Vec<InventoryTarget>,
+    recipient: Address,
+    dry_run: bool,
+    interval: Duration,
+    // destinations that recently received a rebalancing swap, which may not have landed yet
+    pending: Cache<(u64, Address), RequestId>,
+}
+
+impl<'a, CSP: ChainStateProvider, BA: BridgeAdapter> Rebalancer<'a, CSP, BA> {
+    pub fn new(
+        chains: &'a HashMap<u64, CSP>,
+        bridge: BA,
+        config: &RebalancingConfig,
+        recipient: Address,
+    ) -> anyhow::Result<Self> {
+        for target in &config.targets {
+            anyhow::ensure!(
+                chains.contains_key(&target.chain_id),
+                "rebalancing target configured for unknown chain {}",
+                target.chain_id
+            );
+            anyhow::ensure!(
+                target.min <= target.target && target.target <= target.max(),
+                "rebalancing target of {} on chain {} must satisfy min <= target <= max",
+                target.asset,
+                target.chain_id
+            );
+        }
+
+        Ok(Self {
+            chains,
+            bridge,
+            targets: config.targets.clone(),
+            recipient,
+            dry_run: config.dry_run,
+            interval: config.interval,
+            pending: Cache::builder()
+                .max_capacity(1000)
+                .time_to_live(config.cooldown)
+                .build(),
+        })
+    }
+
+    /// Periodically rebalance the inventory, only returning upon unexpected errors.
+    pub async fn run(&self) -> anyhow::Result<()> {
+        let mut interval = tokio::time::interval(self.interval);
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.rebalance().await {
+                tracing::error!(error = ?e, "failed to rebalance inventory");
+            }
+        }
+    }
+
+    /// Fetch the latest balances and request the swaps required to restore the inventory
+    /// targets. Returns the actions that have been attempted, or logged in dry-run mode.
+    pub async fn rebalance(&self) -> anyhow::Result<Vec<RebalanceAction>> {
+        let states = self.fetch_states().await?;
+        for target in &self.targets {
+            Metrics::report_inventory(
+                target.chain_id,
+                &target.asset,
+                target.token,
+                balance_of(target, &states),
+                target.target,
+            );
+        }
+
+        let actions = plan_rebalances(&self.targets, &states, |target| {
+            self.pending.contains_key(&(target.chain_id, target.token))
+        });
+
+        for action in &actions {
+            if self.dry_run {
+                tracing::info!(
+                    asset = %action.asset,
+                    src_chain_id = action.src_chain_id,
+                    dst_chain_id = action.dst_chain_id,
+                    amount = %action.amount,
+                    "dry run - skipping rebalancing swap"
+                );
+                Metrics::report_rebalance(
+                    action.src_chain_id,
+                    action.dst_chain_id,
+                    &action.asset,
+                    "dry_run",
+                );
+                continue;
+            }
+
+            match self.bridge.bridge(action, self.recipient).await {
+                Ok(request_id) => {
+                    tracing::info!(
+                        asset = %action.asset,
+                        src_chain_id = action.src_chain_id,
+                        dst_chain_id = action.dst_chain_id,
+                        amount = %action.amount,
+                        %request_id,
+                        "requested rebalancing swap"
+                    );
+                    self.pending
+                        .insert((action.dst_chain_id, action.dst_token), request_id)
+                        .await;
+                    Metrics::report_rebalance(
+                        action.src_chain_id,
+                        action.dst_chain_id,
+                        &action.asset,
+                        "requested",
+                    );
+                }
+                Err(e) => {
+                    tracing::error!(
+                        asset = %action.asset,
+                        src_chain_id = action.src_chain_id,
+                        dst_chain_id = action.dst_chain_id,
+                        amount = %action.amount,
+                        error = ?e,
+                        "failed to request rebalancing swap"
+                    );
+                    Metrics::report_rebalance(
+                        action.src_chain_id,
+                        action.dst_chain_id,
+                        &action.asset,
+                        "failed",
+                    );
+                }
+            }
+        }
+
+        Ok(actions)
+    }
+
+    async fn fetch_states(&self) -> anyhow::Result<HashMap<u64, ChainState>> {
+        let mut chain_ids: Vec<_> = self.targets.iter().map(|t| t.chain_id).collect();
+        chain_ids.sort_unstable();
+        chain_ids.dedup();
+
+        let states = try_join_all(chain_ids.into_iter().map(async |chain_id| {
+            let chain = self
+                .chains
+                .get(&chain_id)
+                .expect("targets are validated against configured chains");
+            chain
+                .fetch_state()
+                .await
+                .with_context(|| format!("failed to fetch state of chain {chain_id}"))
+                .map(|state| (chain_id, state))
+        }))
+        .await?;
+
+        Ok(states.into_iter().collect())
+    }
+}
+
+fn balance_of(target: &InventoryTarget, states: &HashMap<u64, ChainState>) -> U256 {
+    states
+        .get(&target.chain_id)
+        .and_then(|state| state.token_balances.get(&target.token))
+        .copied()
+        .unwrap_or_default()
+}
+
+/// Computes the transfers required to bring every chain below its minimum back to its target,
+/// drawing from the chains that hold more than their maximum, largest amounts first.
+/// Destinations for which `is_pending` returns true are left untouched.
+fn plan_rebalances(
+    targets: &[InventoryTarget],
+    states: &HashMap<u64, ChainState>,
+    is_pending: impl Fn(&InventoryTarget) -> bool,
+) -> Vec<RebalanceAction> {
+    let mut assets: BTreeMap<&str, Vec<&InventoryTarget>> = BTreeMap::new();
+    for target in targets {
+        assets.entry(&target.asset).or_default().push(target);
+    }
+
+    let mut actions = Vec::new();
+    for (asset, targets) in assets {
+        let mut deficits = Vec::new();
+        let mut surpluses = Vec::new();
+        for target in targets {
+            let balance = balance_of(target, states);
+            if balance < target.min && !is_pending(target) {
+                deficits.push((target, target.target - balance));
+            } else if balance > target.max() {
+                surpluses.push((target, balance - target.target));
+            }
+        }
+
+        // serve the largest deficits first, from the largest surpluses
+        deficits.sort_by(|(_, a), (_, b)| b.cmp(a));
+        for (dst, mut needed) in deficits {
+            while !needed.is_zero() {
+                let Some((src, available)) = surpluses
+                    .iter_mut()
+                    .filter(|(_, available)| !available.is_zero())
+                    .max_by_key(|(_, available)| *available)
+                else {
+                    tracing::warn!(
+                        asset,
+                        chain_id = dst.chain_id,
+                        missing = %needed,
+                        "not enough surplus on other chains to rebalance"
+                    );
+                    break;
+                };
+
+                let amount = needed.min(*available);
+                *available -= amount;
+                needed -= amount;
+                actions.push(RebalanceAction {
+                    asset: asset.to_owned(),
+                    src_chain_id: src.chain_id,
+                    src_token: src.token,
+                    dst_chain_id: dst.chain_id,
+                    dst_token: dst.token,
+                    amount,
+                });
+            }
+        }
+    }
+
+    actions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test::generate_request_id;
+    use alloy::primitives::address;
+    use speculoos::assert_that;
+    use speculoos::vec::VecAssertions;
+    use std::sync::Mutex;
+
+    static OWN_ADDR: Address = address!("0xdeadbeef6964af9d7eed9e03e53415d37aa96045");
+    static TOKEN_ADDR: Address = address!("0xd8da6bf26964af9d7eed9e03e53415d37aa96045");
+
+    #[test]
+    fn deficit_is_filled_from_surplus() {
+        let targets = [target(1, 100, 50, None), target(2, 100, 50, None)];
+        let states = states([(1, 20), (2, 300)]);
+
+        let actions = plan_rebalances(&targets, &states, |_| false);
+
+        assert_that!(actions).is_equal_to(vec![action(2, 1, 80)]);
+    }
+
+    #[test]
+    fn balances_within_bounds_are_left_alone() {
+        // chain 1 is below its target but above its min, chain 2 above its target but below max
+        let targets = [target(1, 100, 50, None), target(2, 100, 50, Some(200))];
+        let states = states([(1, 60), (2, 150)]);
+
+        let actions = plan_rebalances(&targets, &states, |_| false);
+
+        assert_that!(actions).is_empty();
+    }
+
+    #[test]
+    fn deficit_is_split_across_surpluses() {
+        let targets = [
+            target(1, 100, 50, None),
+            target(2, 100, 50, None),
+            target(3, 100, 50, None),
+        ];
+        let states = states([(1, 0), (2, 160), (3, 180)]);
+
+        let actions = plan_rebalances(&targets, &states, |_| false);
+
+        assert_that!(actions).is_equal_to(vec![action(3, 1, 80), action(2, 1, 20)]);
+    }
+
+    #[test]
+    fn insufficient_surplus_is_partially_rebalanced() {
+        let targets = [target(1, 100, 50, None), target(2, 100, 50, None)];
+        let states = states([(1, 0), (2, 130)]);
+
+        let actions = plan_rebalances(&targets, &states, |_| false);
+
+        assert_that!(actions).is_equal_to(vec![action(2, 1, 30)]);
+    }
+
+    #[test]
+    fn assets_are_not_mixed() {
+        let mut other_asset = target(2, 100, 50, None);
+        other_asset.asset = "OTHER".to_owned();
+        let targets = [target(1, 100, 50, None), other_asset];
+        let states = states([(1, 0), (2, 1000)]);
+
+        let actions = plan_rebalances(&targets, &states, |_| false);
+
+        assert_that!(actions).is_empty();
+    }
+
+    #[test]
+    fn pending_destinations_are_skipped() {
+        let targets = [target(1, 100, 50, None), target(2, 100, 50, None)];
+        let states = states([(1, 0), (2, 1000)]);
+
+        let actions = plan_rebalances(&targets, &states, |t| t.chain_id == 1);
+
+        assert_that!(actions).is_empty();
+    }
+
+    #[tokio::test]
+    async fn dry_run_does_not_bridge() {
+        let chains = stubbed_chains([(1, 0), (2, 1000)]);
+        let bridge = RecordingBridge::default();
+        let config = config(true);
+        let rebalancer = Rebalancer::new(&chains, &bridge, &config, OWN_ADDR).unwrap();
+
+        let actions = rebalancer.rebalance().await.unwrap();
+
+        assert_that!(actions).has_length(1);
+        assert_that!(*bridge.bridged.lock().unwrap()).is_empty();
+    }
+
+    #[tokio::test]
+    async fn requested_rebalance_is_not_repeated() {
+        let chains = stubbed_chains([(1, 0), (2, 1000)]);
+        let bridge = RecordingBridge::default();
+        let config = config(false);
+        let rebalancer = Rebalancer::new(&chains, &bridge, &config, OWN_ADDR).unwrap();
+
+        let first = rebalancer.rebalance().await.unwrap();
+        let second = rebalancer.rebalance().await.unwrap();
+
+        assert_that!(first).is_equal_to(vec![action(2, 1, 100)]);
+        assert_that!(second).is_empty();
+        assert_that!(*bridge.bridged.lock().unwrap())
+            .is_equal_to(vec![(action(2, 1, 100), OWN_ADDR)]);
+    }
+
+    #[tokio::test]
+    async fn failed_rebalance_is_retried() {
+        let chains = stubbed_chains([(1, 0), (2, 1000)]);
+        let bridge = RecordingBridge {
+            fail: true,
+            ..Default::default()
+        };
+        let config = config(false);
+        let rebalancer = Rebalancer::new(&chains, &bridge, &config, OWN_ADDR).unwrap();
+
+        rebalancer.rebalance().await.unwrap();
+        rebalancer.rebalance().await.unwrap();
+
+        assert_that!(*bridge.bridged.lock().unwrap()).has_length(2);
+    }
+
+    #[test]
+    fn invalid_targets_are_rejected() {
+        let chains = stubbed_chains([(1, 0)]);
+        let mut config = config(false);
+        config.targets = vec![target(1, 100, 150, None)];
+        assert!(Rebalancer::new(&chains, &RecordingBridge::default(), &config, OWN_ADDR).is_err());
+
+        config.targets = vec![target(3, 100, 50, None)];
+        assert!(Rebalancer::new(&chains, &RecordingBridge::default(), &config, OWN_ADDR).is_err());
+    }
+
+    fn target(chain_id: u64, target: u64, min: u64, max: Option<u64>) -> InventoryTarget {
+        InventoryTarget {
+            asset: "RUSD".to_owned(),
+            chain_id,
+            token: TOKEN_ADDR,
+            target: U256::from(target),
+            min: U256::from(min),
+            max: max.map(U256::from),
+        }
+    }
+
+    fn action(src_chain_id: u64, dst_chain_id: u64, amount: u64) -> RebalanceAction {
+        RebalanceAction {
+            asset: "RUSD".to_owned(),
+            src_chain_id,
+            src_token: TOKEN_ADDR,
+            dst_chain_id,
+            dst_token: TOKEN_ADDR,
+            amount: U256::from(amount),
+        }
+    }
+
+    fn config(dry_run: bool) -> RebalancingConfig {
+        RebalancingConfig {
+            dry_run,
+            interval: Duration::from_secs(60),
+            cooldown: Duration::from_secs(60),
+            targets: vec![target(1, 100, 50, None), target(2, 100, 50, None)],
+        }
+    }
+
+    fn states(balances: impl IntoIterator<Item = (u64, u64)>) -> HashMap<u64, ChainState> {
+        balances
+            .into_iter()
+            .map(|(chain_id, balance)| {
+                (
+                    chain_id,
+                    ChainState {
+                        native_balance: U256::from(1),
+                        token_balances: HashMap::from([(TOKEN_ADDR, U256::from(balance))]),
+                        transfers: vec![],
+                        already_fulfilled: vec![],
+                    },
+                )
+            })
+            .collect()
+    }
+
+    fn stubbed_chains(
+        balances: impl IntoIterator<Item = (u64, u64)>,
+    ) -> HashMap<u64, StubbedChain> {
+        states(balances)
+            .into_iter()
+            .map(|(chain_id, state)| (chain_id, StubbedChain { state }))
+            .collect()
+    }
+
+    struct StubbedChain {
+        state: ChainState,
+    }
+
+    #[async_trait]
+    impl ChainStateProvider for StubbedChain {
+        async fn fetch_state(&self) -> anyhow::Result<ChainState> {
+            Ok(self.state.clone())
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingBridge {
+        fail: bool,
+        bridged: Mutex<Vec<(RebalanceAction, Address)>>,
+    }
+
+    #[async_trait]
+    impl BridgeAdapter for &RecordingBridge {
+        async fn bridge(
+            &self,
+            action: &RebalanceAction,
+            recipient: Address,
+        ) -> anyhow::Result<RequestId> {
+            self.bridged
+                .lock()
+                .unwrap()
+                .push((action.clone(), recipient));
+            if self.fail {
+                anyhow::bail!("bridge failure");
+            }
+            Ok(generate_request_id())
+        }
+    }
+}
@@ -0,0 +1,376 @@
+//! Strategies used by the solver to select which transfers to fulfil when its balances cannot
+//! cover all of them.
+
+use crate::model::{ChainState, Trade, Transfer};
+use crate::profitability::{ErasedProfitabilityEstimator, ProfitabilityEstimator};
+use crate::util::normalise_chain_id;
+use alloy::primitives::{Address, U256};
+use async_trait::async_trait;
+use std::collections::{BTreeMap, HashMap};
+
+/// Number of slots used to discretise a token balance in the [`KnapsackSelection`].
+const KNAPSACK_RESOLUTION: u64 = 1_000;
+
+/// Select the trades to execute among a set of eligible transfers.
+#[async_trait]
+pub(crate) trait TradeSelectionStrategy {
+    /// Select the trades to execute among `candidates`, which can each be fulfilled individually,
+    /// without exceeding the destination token balances found in `states`.
+    async fn select(
+        &self,
+        candidates: Vec<Transfer>,
+        states: &HashMap<u64, ChainState>,
+    ) -> anyhow::Result<Vec<Trade>>;
+}
+
+/// Provides an upper bound of the current gas cost of a chain.
+#[async_trait]
+pub(crate) trait GasCostProvider {
+    async fn gas_cost(&self, chain_id: u64) -> anyhow::Result<u128>;
+}
+
+/// Commits balance to transfers in the order of the requests, skipping those that no longer fit.
+pub(crate) struct GreedySelection;
+
+#[async_trait]
+impl TradeSelectionStrategy for GreedySelection {
+    async fn select(
+        &self,
+        candidates: Vec<Transfer>,
+        states: &HashMap<u64, ChainState>,
+    ) -> anyhow::Result<Vec<Trade>> {
+        let mut balances = HashMap::new();
+        let mut trades = Vec::new();
+        for transfer in candidates {
+            let key = balance_key(&transfer);
+            let balance = balances
+                .entry(key)
+                .or_insert_with(|| balance_of(key, states));
+            if *balance < transfer.params.amountOut {
+                tracing::debug!(request_id = %transfer.request_id, "skipping - token balance too low");
+                continue;
+            }
+
+            // we commit some of our tokens to this trade so the next one doesn't fail
+            *balance -= transfer.params.amountOut;
+            trades.push((&transfer).into());
+        }
+
+        Ok(trades)
+    }
+}
+
+/// Selects the set of transfers maximising the total expected profit that can be covered by the
+/// destination token balances, by solving a knapsack problem per destination token.
+///
+/// Balances are discretised into [`KNAPSACK_RESOLUTION`] slots and the amount of each transfer is
+/// rounded up, so that the selected trades never exceed the actual balance. This may leave a
+/// small fraction of the balance unused.
+pub(crate) struct KnapsackSelection<'a, G> {
+    profitability_estimator: ErasedProfitabilityEstimator,
+    gas_costs: &'a G,
+    gas_estimate: u64,
+}
+
+impl<'a, G> KnapsackSelection<'a, G> {
+    pub fn new(
+        profitability_estimator: ErasedProfitabilityEstimator,
+        gas_costs: &'a G,
+        gas_estimate: u64,
+    ) -> Self {
+        Self {
+            profitability_estimator,
+            gas_costs,
+            gas_estimate,
+        }
+    }
+}
+
+#[async_trait]
+impl<G: GasCostProvider + Sync> TradeSelectionStrategy for KnapsackSelection<'_, G> {
+    async fn select(
+        &self,
+        candidates: Vec<Transfer>,
+        states: &HashMap<u64, ChainState>,
+    ) -> anyhow::Result<Vec<Trade>> {
+        let mut gas_costs = HashMap::new();
+        let mut groups: BTreeMap<(u64, Address), Vec<(usize, U256, f64)>> = BTreeMap::new();
+        let trades: Vec<Trade> = candidates.iter().map(Into::into).collect();
+        for (idx, (transfer, trade)) in candidates.iter().zip(&trades).enumerate() {
+            let key = balance_key(transfer);
+            let gas_cost = match gas_costs.get(&key.0) {
+                Some(gas_cost) => *gas_cost,
+                None => {
+                    let gas_cost = self.gas_costs.gas_cost(key.0).await?;
+                    gas_costs.insert(key.0, gas_cost);
+                    gas_cost
+                }
+            };
+
+            let profit = match self
+                .profitability_estimator
+                .expected_profit(trade, self.gas_estimate, gas_cost)
+                .await
+            {
+                Ok(profit) => profit,
+                Err(e) => {
+                    tracing::error!(request_id = %transfer.request_id, error = ?e, "failed to estimate profit");
+                    continue;
+                }
+            };
+            if profit.is_nan() || profit <= 0f64 {
+                tracing::debug!(request_id = %transfer.request_id, profit, "skipping - not profitable");
+                continue;
+            }
+
+            groups
+                .entry(key)
+                .or_default()
+                .push((idx, transfer.params.amountOut, profit));
+        }
+
+        let mut selected = Vec::new();
+        for (key, items) in groups {
+            let amounts: Vec<_> = items
+                .iter()
+                .map(|(_, amount, profit)| (*amount, *profit))
+                .collect();
+            let chosen = knapsack(balance_of(key, states), &amounts);
+            selected.extend(chosen.into_iter().map(|i| items[i].0));
+        }
+
+        // keep the order of the requests
+        selected.sort_unstable();
+        let mut trades: Vec<_> = trades.into_iter().map(Some).collect();
+        Ok(selected
+            .into_iter()
+            .filter_map(|idx| trades[idx].take())
+            .collect())
+    }
+}
+
+/// Solves a 0/1 knapsack over `(amount, profit)` items, returning the indices of the items to
+/// select so that their total amount does not exceed `capacity`.
+fn knapsack(capacity: U256, items: &[(U256, f64)]) -> Vec<usize> {
+    let resolution = U256::from(KNAPSACK_RESOLUTION);
+    let (scale, slots) = if capacity <= resolution {
+        (U256::from(1), capacity.as_limbs()[0] as usize)
+    } else {
+        (capacity / resolution, KNAPSACK_RESOLUTION as usize)
+    };
+
+    // amounts are rounded up, such that sum(amounts) <= scale * sum(weights) <= capacity
+    let weights: Vec<Option<usize>> = items
+        .iter()
+        .map(|(amount, _)| {
+            let remainder = if (*amount % scale).is_zero() {
+                U256::ZERO
+            } else {
+                U256::from(1)
+            };
+            let weight = *amount / scale + remainder;
+            (weight <= U256::from(slots)).then(|| weight.as_limbs()[0] as usize)
+        })
+        .collect();
+
+    let mut best = vec![0f64; slots + 1];
+    let mut keep = vec![vec![false; slots + 1]; items.len()];
+    for (i, (weight, (_, profit))) in weights.iter().zip(items).enumerate() {
+        let Some(weight) = *weight else {
+            continue;
+        };
+
+        for c in (weight..=slots).rev() {
+            let candidate = best[c - weight] + profit;
+            if candidate > best[c] {
+                best[c] = candidate;
+                keep[i][c] = true;
+            }
+        }
+    }
+
+    let mut chosen = Vec::new();
+    let mut c = slots;
+    for i in (0..items.len()).rev() {
+        if keep[i][c] {
+            chosen.push(i);
+            c -= weights[i].expect("kept items have a weight");
+        }
+    }
+
+    chosen.reverse();
+    chosen
+}
+
+fn balance_key(transfer: &Transfer) -> (u64, Address) {
+    (
+        normalise_chain_id(transfer.params.dstChainId),
+        transfer.params.tokenOut,
+    )
+}
+
+fn balance_of((chain_id, token): (u64, Address), states: &HashMap<u64, ChainState>) -> U256 {
+    states
+        .get(&chain_id)
+        .and_then(|state| state.token_balances.get(&token))
+        .copied()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test::generate_request_id;
+    use alloy::primitives::address;
+    use generated::onlyswaps::i_router::IRouter::SwapRequestParametersWithHooks;
+    use speculoos::assert_that;
+    use speculoos::vec::VecAssertions;
+    use std::convert::Infallible;
+
+    static USER_ADDR: Address = address!("0xdeadbeef6964af9d7eed9e03e53415d37aa96045");
+    static TOKEN_ADDR: Address = address!("0xd8da6bf26964af9d7eed9e03e53415d37aa96045");
+
+    #[test]
+    fn knapsack_maximises_profit() {
+        let items = [
+            (U256::from(100), 5f64),
+            (U256::from(50), 4f64),
+            (U256::from(50), 4f64),
+        ];
+
+        assert_that!(knapsack(U256::from(100), &items)).is_equal_to(vec![1, 2]);
+        assert_that!(knapsack(U256::from(99), &items)).is_equal_to(vec![1]);
+        assert_that!(knapsack(U256::from(49), &items)).is_empty();
+    }
+
+    #[test]
+    fn knapsack_never_exceeds_large_capacity() {
+        let capacity = U256::from(10).pow(U256::from(24));
+        let half = capacity / U256::from(2);
+        let items = [
+            (half + U256::from(1), 1f64),
+            (half, 1f64),
+            (half - U256::from(1), 1f64),
+        ];
+
+        let chosen = knapsack(capacity, &items);
+
+        let total = chosen.iter().fold(U256::ZERO, |acc, i| acc + items[*i].0);
+        // the first two items would exceed the capacity by one, the last two fit
+        assert_that!(chosen).is_equal_to(vec![1, 2]);
+        assert!(total <= capacity);
+    }
+
+    #[tokio::test]
+    async fn greedy_selection_follows_request_order() {
+        let transfers = vec![transfer(100, 1), transfer(50, 10), transfer(50, 10)];
+        let states = states(100);
+
+        let trades = GreedySelection
+            .select(transfers.clone(), &states)
+            .await
+            .unwrap();
+
+        assert_that!(trades).is_equal_to(vec![Trade::from(&transfers[0])]);
+    }
+
+    #[tokio::test]
+    async fn knapsack_selection_prefers_higher_total_fees() {
+        let transfers = vec![transfer(100, 1), transfer(50, 10), transfer(50, 10)];
+        let states = states(100);
+        let selection = KnapsackSelection::new(
+            ErasedProfitabilityEstimator::from_estimator(FeeProfit),
+            &FixedGasCost,
+            100_000,
+        );
+
+        let trades = selection.select(transfers.clone(), &states).await.unwrap();
+
+        assert_that!(trades)
+            .is_equal_to(vec![Trade::from(&transfers[1]), Trade::from(&transfers[2])]);
+    }
+
+    #[tokio::test]
+    async fn knapsack_selection_skips_unprofitable_trades() {
+        let transfers = vec![transfer(10, 0), transfer(10, 5)];
+        let states = states(100);
+        let selection = KnapsackSelection::new(
+            ErasedProfitabilityEstimator::from_estimator(FeeProfit),
+            &FixedGasCost,
+            100_000,
+        );
+
+        let trades = selection.select(transfers.clone(), &states).await.unwrap();
+
+        assert_that!(trades).is_equal_to(vec![Trade::from(&transfers[1])]);
+    }
+
+    fn transfer(amount_out: u64, solver_fee: u64) -> Transfer {
+        Transfer {
+            request_id: generate_request_id(),
+            params: SwapRequestParametersWithHooks {
+                srcChainId: U256::from(1),
+                dstChainId: U256::from(2),
+                sender: USER_ADDR,
+                recipient: USER_ADDR,
+                tokenIn: TOKEN_ADDR,
+                tokenOut: TOKEN_ADDR,
+                amountIn: U256::from(amount_out),
+                amountOut: U256::from(amount_out),
+                verificationFee: U256::from(0),
+                solverFee: U256::from(solver_fee),
+                nonce: U256::from(1),
+                executed: false,
+                requestedAt: U256::from(12345),
+                preHooks: Vec::new(),
+                postHooks: Vec::new(),
+            },
+        }
+    }
+
+    fn states(balance: u64) -> HashMap<u64, ChainState> {
+        HashMap::from([(
+            2,
+            ChainState {
+                native_balance: U256::from(1),
+                token_balances: HashMap::from([(TOKEN_ADDR, U256::from(balance))]),
+                transfers: vec![],
+                already_fulfilled: vec![],
+            },
+        )])
+    }
+
+    /// The profit of a trade is its solver fee minus a flat gas cost of 1
+    struct FeeProfit;
+
+    impl ProfitabilityEstimator for FeeProfit {
+        type Error = Infallible;
+
+        async fn is_profitable(
+            &self,
+            trade: &Trade,
+            gas_estimate: u64,
+            gas_cost: u128,
+        ) -> Result<bool, Self::Error> {
+            Ok(self.expected_profit(trade, gas_estimate, gas_cost).await? > 0f64)
+        }
+
+        async fn expected_profit(
+            &self,
+            trade: &Trade,
+            _gas_estimate: u64,
+            _gas_cost: u128,
+        ) -> Result<f64, Self::Error> {
+            Ok(crate::util::u256_to_f64(trade.solver_fee) - 1f64)
+        }
+    }
+
+    struct FixedGasCost;
+
+    #[async_trait]
+    impl GasCostProvider for FixedGasCost {
+        async fn gas_cost(&self, _chain_id: u64) -> anyhow::Result<u128> {
+            Ok(1)
+        }
+    }
+}
@@ -1,11 +1,12 @@
 use crate::model::{ChainState, RequestId, Trade, Transfer};
+use crate::selection::{GreedySelection, TradeSelectionStrategy};
 use crate::util::normalise_chain_id;
-use alloy::primitives::U256;
+use alloy::primitives::{Address, U256};
 use async_trait::async_trait;
 use generated::onlyswaps::i_router::IRouter::SwapRequestParametersWithHooks;
 use moka::future::Cache;
 use onlyswaps_client::FeeEstimate;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 #[async_trait]
 pub(crate) trait ChainStateProvider {
@@ -21,6 +22,8 @@ pub(crate) struct Solver<'a, CSP, FA> {
     states: HashMap<u64, ChainState>,
     chains: &'a HashMap<u64, CSP>,
     fee_estimator: &'a FA,
+    ignored_senders: HashSet<Address>,
+    selection: Box<dyn TradeSelectionStrategy + Send + Sync + 'a>,
 }
 
 impl<'a, CSP: ChainStateProvider, FA: FeeAdapter> Solver<'a, CSP, FA> {
@@ -36,9 +39,27 @@ impl<'a, CSP: ChainStateProvider, FA: FeeAdapter> Solver<'a, CSP, FA> {
             states,
             chains,
             fee_estimator,
+            ignored_senders: HashSet::new(),
+            selection: Box::new(GreedySelection),
         })
     }
 
+    /// Use a custom strategy to select the trades to execute, instead of greedily executing them
+    /// in the order of the requests.
+    pub fn with_selection_strategy(
+        mut self,
+        selection: impl TradeSelectionStrategy + Send + Sync + 'a,
+    ) -> Self {
+        self.selection = Box::new(selection);
+        self
+    }
+
+    /// Never fulfil requests created by the given senders, e.g. the solver's own rebalancing swaps.
+    pub fn with_ignored_senders(mut self, senders: impl IntoIterator<Item = Address>) -> Self {
+        self.ignored_senders.extend(senders);
+        self
+    }
+
     pub async fn solve(
         &mut self,
         chain_id: u64,
@@ -63,8 +84,7 @@ impl<'a, CSP: ChainStateProvider, FA: FeeAdapter> Solver<'a, CSP, FA> {
         states: &HashMap<u64, ChainState>,
         in_flight: &Cache<RequestId, ()>,
     ) -> anyhow::Result<Vec<Trade>> {
-        let mut trades = Vec::new();
-        let mut owned_states = states.clone();
+        let mut candidates = Vec::new();
         // we only want the current chain's transactions, as we may have trades in flight for other chains
         let transfers = &states
             .get(&chain_id)
@@ -76,6 +96,11 @@ impl<'a, CSP: ChainStateProvider, FA: FeeAdapter> Solver<'a, CSP, FA> {
                 continue;
             }
 
+            if self.ignored_senders.contains(&transfer.params.sender) {
+                tracing::debug!(request_id = %transfer.request_id, "skipping - sender is ignored");
+                continue;
+            }
+
             match self.fee_estimator.fetch_fee(transfer).await {
                 Err(e) => {
                     tracing::error!(
@@ -87,21 +112,24 @@ impl<'a, CSP: ChainStateProvider, FA: FeeAdapter> Solver<'a, CSP, FA> {
                     )
                 }
                 Ok(estimated_fees) => {
-                    update_trades(transfer, &estimated_fees, &mut trades, &mut owned_states)
+                    if is_eligible(transfer, &estimated_fees, states) {
+                        candidates.push(transfer.clone());
+                    }
                 }
             }
         }
 
-        Ok(trades)
+        // the strategy decides how to share our balances between the eligible transfers
+        self.selection.select(candidates, states).await
     }
 }
 
-fn update_trades(
+/// Whether a transfer could be fulfilled on its own, ignoring the balance required by other trades.
+fn is_eligible(
     transfer_request: &Transfer,
     estimated_fees: &FeeEstimate,
-    trades: &mut Vec<Trade>,
-    states: &mut HashMap<u64, ChainState>,
-) {
+    states: &HashMap<u64, ChainState>,
+) -> bool {
     let SwapRequestParametersWithHooks {
         dstChainId,
         amountOut,
@@ -110,14 +138,14 @@ fn update_trades(
         ..
     } = transfer_request.params;
 
-    let dest_state = match states.get_mut(&normalise_chain_id(dstChainId)) {
-        None => return,
+    let dest_state = match states.get(&normalise_chain_id(dstChainId)) {
+        None => return false,
         Some(state) => state,
     };
 
     if executed {
         tracing::debug!(request_id = %transfer_request.request_id, "skipping - tx already executed");
-        return;
+        return false;
     }
 
     if dest_state
@@ -125,12 +153,12 @@ fn update_trades(
         .contains(&transfer_request.request_id)
     {
         tracing::debug!(request_id = %transfer_request.request_id, "skipping - tx already fulfilled");
-        return;
+        return false;
     }
 
     if dest_state.native_balance == U256::from(0) {
         tracing::debug!(request_id = %transfer_request.request_id, "skipping - native balance too low");
-        return;
+        return false;
     }
 
     let token_balance = match dest_state
@@ -139,31 +167,27 @@ fn update_trades(
     {
         None => {
             tracing::debug!(request_id = %transfer_request.request_id, "skipping - token balance empty");
-            return;
+            return false;
         }
         Some(balance) => balance,
     };
     if *token_balance < amountOut {
         tracing::debug!(request_id = %transfer_request.request_id, "skipping - token balance too low");
-        return;
+        return false;
     }
 
     if amountOut > estimated_fees.amount_out {
         tracing::debug!(request_id = %transfer_request.request_id, "skipping - amount_out higher than recommended by fees API");
-        return;
+        return false;
     }
 
     // just takes a flat fee for the moment
     if solverFee < U256::from(1) {
         tracing::debug!(request_id = %transfer_request.request_id, "skipping - fee too low");
-        return;
+        return false;
     }
 
-    // we commit some of our tokens to this trade so the next one doesn't fail
-    dest_state
-        .token_balances
-        .insert(transfer_request.params.tokenOut, token_balance - amountOut);
-    trades.push(transfer_request.into())
+    true
 }
 
 #[cfg(test)]
@@ -762,6 +786,48 @@ mod tests {
         assert_that!(trades).has_length(0);
     }
 
+    #[tokio::test]
+    async fn transfers_from_ignored_senders_make_no_trade() {
+        // given
+        let own_addr = generate_address();
+        let transfer_params = create_transfer_params(own_addr, 1, 2, 100);
+        let transfer_params_2 = create_transfer_params(USER_ADDR, 1, 2, 100);
+
+        let src_chain_state = ChainState {
+            token_balances: HashMap::from([(TOKEN_ADDR, U256::from(0))]),
+            native_balance: U256::from(0),
+            transfers: vec![transfer_params.clone(), transfer_params_2.clone()],
+            already_fulfilled: vec![],
+        };
+        let dst_chain_state = ChainState {
+            token_balances: HashMap::from([(TOKEN_ADDR, U256::from(1000))]),
+            native_balance: U256::from(1000),
+            transfers: vec![],
+            already_fulfilled: vec![],
+        };
+        let chain_one = StubbedChain {
+            state: src_chain_state,
+        };
+        let chain_two = StubbedChain {
+            state: dst_chain_state,
+        };
+        let chains = HashMap::from([(1, chain_one), (2, chain_two)]);
+        let fee_estimater = StubbedFees {
+            response: create_fee_estimate(transfer_params.params.amountIn).clone(),
+        };
+        let mut solver = Solver::new(&chains, &fee_estimater)
+            .await
+            .unwrap()
+            .with_ignored_senders([own_addr]);
+
+        // when
+        let trades = solver.solve(1, &Cache::new(1)).await.unwrap();
+
+        // then
+        assert_that!(trades).has_length(1);
+        assert_that!(trades[0].request_id).is_equal_to(transfer_params_2.request_id);
+    }
+
     fn create_transfer_params(
         sender: Address,
         src_chain_id: u64,
@@ -4,10 +4,19 @@ pub fn normalise_chain_id(chain_id: U256) -> u64 {
     chain_id.as_limbs()[0]
 }
 
+/// Lossy conversion of a token amount, only suitable for comparisons and reporting
+pub fn u256_to_f64(v: U256) -> f64 {
+    v.as_limbs()
+        .iter()
+        .rev()
+        .fold(0f64, |acc, limb| acc * 2f64.powi(64) + *limb as f64)
+}
+
 #[cfg(test)]
 pub(crate) mod test {
+    use super::u256_to_f64;
     use crate::model::RequestId;
-    use alloy::primitives::Address;
+    use alloy::primitives::{Address, U256};
     use std::fs::File;
     use std::io::Read;
 
@@ -26,4 +35,10 @@ pub(crate) mod test {
             .expect("failed to read random bytes");
         buf.into()
     }
+
+    #[test]
+    fn u256_to_f64_converts_all_limbs() {
+        assert_eq!(u256_to_f64(U256::from(12345)), 12345f64);
+        assert_eq!(u256_to_f64(U256::from(1) << 128), 2f64.powi(128));
+    }
 }
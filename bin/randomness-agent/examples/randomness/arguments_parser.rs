@@ -25,9 +25,14 @@ pub struct RandomnessAgentArgs {
     #[arg(long, env = "RANDOMNESS_HEALTHCHECK_PORT", default_value = "8080")]
     pub healthcheck_port: u16,
 
-    /// The committee config
-    #[arg(long, env = "RANDOMNESS_COMMITTEE_CONFIG")]
-    pub committee_config: PathBuf,
+    /// The committee config, required unless the requests are signed by a remote dsigner
+    #[arg(
+        long,
+        env = "RANDOMNESS_COMMITTEE_CONFIG",
+        required_unless_present = "dsigner_url",
+        conflicts_with = "dsigner_url"
+    )]
+    pub committee_config: Option<PathBuf>,
 
     /// Whether to enable signature compression or not
     #[arg(long, env = "RANDOMNESS_SIG_COMPRESSION", default_value = "false")]
@@ -39,6 +44,9 @@ pub struct RandomnessAgentArgs {
     #[command(flatten)]
     pub libp2p: Libp2pArgs,
 
+    #[command(flatten)]
+    pub dsigner: DSignerArgs,
+
     /// The logging level parsed by [`EnvFilter`](tracing_subscriber::EnvFilter), see
     /// <https://docs.rs/tracing-subscriber/latest/tracing_subscriber/filter/struct.EnvFilter.html#directives>
     /// for more details on the syntax.
@@ -142,10 +150,13 @@ pub struct ChainArgs {
 #[derive(Parser, Serialize, Deserialize, Debug)]
 #[command(author, version, about, long_about = None)]
 pub struct Libp2pArgs {
-    /// Libp2p private key
-    #[arg(long, env = "RANDOMNESS_LIBP2P_KEY")]
-    #[serde(with = "serde_to_string_from_str")]
-    pub libp2p_key: Libp2pKeyWrapper,
+    /// Libp2p private key, required unless the requests are signed by a remote dsigner
+    #[arg(
+        long,
+        env = "RANDOMNESS_LIBP2P_KEY",
+        required_unless_present = "dsigner_url"
+    )]
+    pub libp2p_key: Option<Libp2pKeyWrapper>,
 
     /// Libp2p listen address
     #[arg(
@@ -156,9 +167,50 @@ pub struct Libp2pArgs {
     pub libp2p_listen_addr: ::libp2p::Multiaddr,
 }
 
+#[derive(Parser, Serialize, Deserialize, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct DSignerArgs {
+    /// Endpoint of a dsigner service used to sign the requests, instead of holding a key share
+    #[arg(long, env = "RANDOMNESS_DSIGNER_URL", requires = "dsigner_scheme_id")]
+    pub dsigner_url: Option<String>,
+
+    /// Identifier of the scheme used to sign the requests on the dsigner service
+    #[arg(long, env = "RANDOMNESS_DSIGNER_SCHEME_ID")]
+    pub dsigner_scheme_id: Option<String>,
+
+    /// API key used to authenticate with the dsigner service
+    #[arg(long, env = "RANDOMNESS_DSIGNER_API_KEY")]
+    pub dsigner_api_key: Option<String>,
+
+    /// Curve of the scheme used to sign the requests on the dsigner service
+    #[arg(
+        long,
+        env = "RANDOMNESS_DSIGNER_CURVE",
+        value_enum,
+        default_value_t = DSignerCurve::Bn254
+    )]
+    pub dsigner_curve: DSignerCurve,
+
+    /// Maximum time in seconds to wait for a signature from the dsigner service
+    #[arg(long, env = "RANDOMNESS_DSIGNER_TIMEOUT", default_value = "30")]
+    pub dsigner_timeout_secs: u64,
+}
+
+#[derive(clap::ValueEnum, Serialize, Deserialize, Clone, Copy, Debug)]
+pub enum DSignerCurve {
+    #[serde(rename = "bn254")]
+    #[value(name = "bn254")]
+    Bn254,
+
+    #[serde(rename = "bls12-381")]
+    #[value(name = "bls12-381")]
+    Bls12_381,
+}
+
 pub struct RandomnessAgentConfig {
     pub config: RandomnessAgentArgs,
-    pub committee_config: SupportedConfig,
+    /// The committee config, or `None` if the requests are signed by a remote dsigner
+    pub committee_config: Option<SupportedConfig>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -175,10 +227,21 @@ impl RandomnessAgentConfig {
             .merge(Toml::file("config.toml"))
             .extract()?;
 
-        let committee_config = std::fs::read_to_string(&c.committee_config)
-            .context("failed to read committee config")?;
-        let committee_config =
-            toml::from_str(&committee_config).context("failed to parse committee config")?;
+        let committee_config = match (&c.committee_config, &c.dsigner.dsigner_url) {
+            (Some(committee_config), None) => {
+                let committee_config = std::fs::read_to_string(committee_config)
+                    .context("failed to read committee config")?;
+                Some(
+                    toml::from_str(&committee_config)
+                        .context("failed to parse committee config")?,
+                )
+            }
+            (None, Some(_)) => None,
+            (Some(_), Some(_)) => {
+                anyhow::bail!("committee config cannot be used along with a dsigner url")
+            }
+            (None, None) => anyhow::bail!("either a committee config or a dsigner url is required"),
+        };
 
         Ok(Self {
             config: c,
@@ -1,23 +1,28 @@
 mod arguments_parser;
 mod healthcheck;
 
-use crate::arguments_parser::{RandomnessAgentArgs, RandomnessAgentConfig, SupportedConfig};
+use crate::arguments_parser::{
+    DSignerCurve, RandomnessAgentArgs, RandomnessAgentConfig, SupportedConfig,
+};
 use crate::healthcheck::start_api;
 use alloy::network::EthereumWallet;
 use alloy::providers::{Provider, ProviderBuilder, WalletProvider};
 use alloy::signers::local::PrivateKeySigner;
+use anyhow::Context;
+use ark_ec::CurveGroup;
 use ark_ec::pairing::Pairing;
 use config::signing::CommitteeConfig;
 use dcipher_agents::agents::randomness::RandomnessAgent;
 use dcipher_agents::agents::randomness::fulfiller::RandomnessFulfiller;
 use dcipher_agents::fulfiller::ticker::{OneshotStopper, UnboundedRequestChannel};
 use dcipher_agents::fulfiller::{Stopper, TickerBasedFulfiller};
+use dcipher_agents::remote_signer::RemoteDSigner;
 use dcipher_agents::signature_sender::{SignatureRequest, SignatureSenderFulfillerConfig};
 use dcipher_network::transports::libp2p::{Libp2pNode, Libp2pNodeConfig};
-use dcipher_signer::bls::{BlsPairingSigner, BlsSigner, BlsThresholdSigner};
+use dcipher_signer::bls::{AsyncThresholdSigner, BlsPairingSigner, BlsSigner, BlsThresholdSigner};
 use dcipher_signer::dsigner::{
     ApplicationArgs, ApplicationRandomnessArgs, BlsSignatureAlgorithm, BlsSignatureCurve,
-    BlsSignatureHash, SignatureAlgorithm,
+    BlsSignatureHash, DSignerSchemeSigner, SignatureAlgorithm,
 };
 use generated::randomness::randomness_sender::RandomnessSender;
 use generated::randomness::signature_sender::SignatureSender;
@@ -77,88 +82,84 @@ async fn main() -> anyhow::Result<()> {
         config.chain.chain_id.replace(chain_id);
     }
 
-    struct ServiceComponents<P, BLS> {
-        ticker: NotifyTicker,
-        libp2p_node: Libp2pNode<u16>,
-        ts_stopper: CancellationToken,
-        stopper: OneshotStopper,
-        agent: RandomnessAgent<BLS, P>,
-    }
-
-    macro_rules! create_service_components {
-        (
-            committee_config: $committee_config:expr,
-            signer_fn: $signer_fn:expr,
-            curve: $curve:expr,
-            hash: $hash:expr,
-            compression: $compression:expr,
-            randomness_scheme_id: $randomness_scheme_id:expr,
-        ) => {{
-            let (ticker, libp2p_node, ts_stopper, stopper, channel) = create_threshold_fulfiller(
-                &config,
-                $signer_fn($committee_config.secret_key.0),
-                SignatureAlgorithm::Bls(BlsSignatureAlgorithm {
-                    curve: $curve,
-                    hash: $hash,
-                    compression: $compression,
-                }),
-                &$committee_config,
-                signature_sender_contract.clone(),
-                randomness_sender_contract.clone(),
-            )?;
-
-            // Create a new randomness agent
-            let agent = RandomnessAgent::new(
-                $randomness_scheme_id,
-                config.chain.sync_batch_size,
-                channel,
-                signature_sender_contract_ro.clone(),
-            );
-            ServiceComponents {
-                ticker,
-                libp2p_node,
-                ts_stopper,
-                stopper,
-                agent,
-            }
-        }};
-    }
-
-    let mut service = match (config.sig_compression, committee_config) {
-        (false, SupportedConfig::Bn254(bn254_config)) => {
-            create_service_components!(
-                committee_config: bn254_config,
-                signer_fn: BlsPairingSigner::new_bn254,
-                curve: BlsSignatureCurve::Bn254G1,
-                hash: BlsSignatureHash::Keccak256,
-                compression: false,
-                randomness_scheme_id: BN254_RANDOMNESS_SCHEME_ID,
-            )
-        }
-        (false, SupportedConfig::Bls12_381(bls12_381_config)) => {
-            create_service_components!(
-                committee_config: bls12_381_config,
-                signer_fn: BlsPairingSigner::new_bls12_381,
-                curve: BlsSignatureCurve::Bls12_381G1,
-                hash: BlsSignatureHash::Sha256,
-                compression: false,
-                randomness_scheme_id: BLS12_381_RANDOMNESS_SCHEME_ID,
-            )
-        }
-        (true, SupportedConfig::Bls12_381(bls12_381_config)) => {
-            create_service_components!(
-                committee_config: bls12_381_config,
-                signer_fn: BlsPairingSigner::new_bls12_381,
-                curve: BlsSignatureCurve::Bls12_381G1,
-                hash: BlsSignatureHash::Sha256,
-                compression: true,
-                randomness_scheme_id: BLS12_381_COMPRESSED_RANDOMNESS_SCHEME_ID,
-            )
-        }
+    // Select the signature algorithm from the committee config, or from the dsigner curve
+    let curve = match &committee_config {
+        Some(SupportedConfig::Bn254(_)) => DSignerCurve::Bn254,
+        Some(SupportedConfig::Bls12_381(_)) => DSignerCurve::Bls12_381,
+        None => config.dsigner.dsigner_curve,
+    };
+    let (curve, hash, randomness_scheme_id) = match (config.sig_compression, curve) {
+        (false, DSignerCurve::Bn254) => (
+            BlsSignatureCurve::Bn254G1,
+            BlsSignatureHash::Keccak256,
+            BN254_RANDOMNESS_SCHEME_ID,
+        ),
+        (false, DSignerCurve::Bls12_381) => (
+            BlsSignatureCurve::Bls12_381G1,
+            BlsSignatureHash::Sha256,
+            BLS12_381_RANDOMNESS_SCHEME_ID,
+        ),
+        (true, DSignerCurve::Bls12_381) => (
+            BlsSignatureCurve::Bls12_381G1,
+            BlsSignatureHash::Sha256,
+            BLS12_381_COMPRESSED_RANDOMNESS_SCHEME_ID,
+        ),
         _ => {
             anyhow::bail!("Unsupported signature sig_compression / algorithm combination");
         }
     };
+    let algorithm = SignatureAlgorithm::Bls(BlsSignatureAlgorithm {
+        curve,
+        hash,
+        compression: config.sig_compression,
+    });
+
+    // Sign with a threshold signer if the node holds a key share, otherwise with a remote dsigner
+    let (signer, threshold_signer): (Box<dyn DSignerSchemeSigner + Send + Sync>, _) =
+        match committee_config {
+            Some(SupportedConfig::Bn254(committee_config)) => {
+                let (libp2p_node, ts_stopper, signer) = create_threshold_signer(
+                    &config,
+                    BlsPairingSigner::new_bn254(committee_config.secret_key.0),
+                    &committee_config,
+                )?;
+                (Box::new(signer), Some((libp2p_node, ts_stopper)))
+            }
+            Some(SupportedConfig::Bls12_381(committee_config)) => {
+                let (libp2p_node, ts_stopper, signer) = create_threshold_signer(
+                    &config,
+                    BlsPairingSigner::new_bls12_381(committee_config.secret_key.0),
+                    &committee_config,
+                )?;
+                (Box::new(signer), Some((libp2p_node, ts_stopper)))
+            }
+            None => (Box::new(create_remote_signer(&config)?), None),
+        };
+
+    let (ticker, stopper, channel) = match curve {
+        BlsSignatureCurve::Bn254G1 => create_fulfiller::<ark_bn254::G1Projective, _>(
+            &config,
+            signer,
+            algorithm,
+            signature_sender_contract.clone(),
+            randomness_sender_contract.clone(),
+        ),
+        _ => create_fulfiller::<ark_bls12_381::G1Projective, _>(
+            &config,
+            signer,
+            algorithm,
+            signature_sender_contract.clone(),
+            randomness_sender_contract.clone(),
+        ),
+    };
+
+    // Create a new randomness agent
+    let mut agent = RandomnessAgent::new(
+        randomness_scheme_id,
+        config.chain.sync_batch_size,
+        channel,
+        signature_sender_contract_ro.clone(),
+    );
 
     // Setup some signals
     let mut sigterm = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())?;
@@ -182,8 +183,8 @@ async fn main() -> anyhow::Result<()> {
         },
 
         err = run_agent(
-            &mut service.agent,
-            service.ticker,
+            &mut agent,
+            ticker,
             signature_sender_contract_ro.clone(),
             Duration::from_secs(config.chain.contract_sync_interval_secs),
             Duration::from_secs(config.chain.fulfillment_interval_secs),
@@ -199,38 +200,39 @@ async fn main() -> anyhow::Result<()> {
     };
 
     // Stop the various components
-    if let Err(e) = service.libp2p_node.stop().await {
-        tracing::error!(error = ?e, "Failed to stop libp2p node");
+    if let Some((mut libp2p_node, ts_stopper)) = threshold_signer {
+        if let Err(e) = libp2p_node.stop().await {
+            tracing::error!(error = ?e, "Failed to stop libp2p node");
+        }
+        ts_stopper.cancel();
     }
-    service.ts_stopper.cancel();
-    service.stopper.stop().await;
+    stopper.stop().await;
 
     res
 }
 
-#[allow(clippy::type_complexity)]
-fn create_threshold_fulfiller<P, BLS>(
+fn create_threshold_signer<BLS>(
     args: &RandomnessAgentArgs,
     signer: BLS,
-    algorithm: SignatureAlgorithm,
     committee_config: &CommitteeConfig<<BLS::E as Pairing>::G2Affine>,
-    signature_sender_contract: SignatureSender::SignatureSenderInstance<P>,
-    randomness_sender_contract: RandomnessSender::RandomnessSenderInstance<P>,
 ) -> anyhow::Result<(
-    NotifyTicker,
     Libp2pNode<u16>,
     CancellationToken,
-    OneshotStopper,
-    UnboundedRequestChannel<SignatureRequest>,
+    AsyncThresholdSigner<BLS>,
 )>
 where
-    P: Provider + WalletProvider + Clone + 'static,
     BLS: BlsSigner + Clone + Send + Sync + 'static,
     <BLS::E as Pairing>::G1Affine:
         PointSerializeCompressed + PointDeserializeCompressed + PointSerializeUncompressed,
     <BLS::E as Pairing>::G2Affine:
         PointSerializeCompressed + PointDeserializeCompressed + PointSerializeUncompressed,
 {
+    let libp2p_key = args
+        .libp2p
+        .libp2p_key
+        .clone()
+        .context("a libp2p key is required to run a threshold signer")?;
+
     // Get per-nodes config
     let (mut pks_g2, addresses, peer_ids, short_ids): (Vec<_>, Vec<_>, Vec<_>, Vec<_>) =
         committee_config
@@ -255,7 +257,7 @@ where
 
     // Create a libp2p transport and start it
     let mut libp2p_node = Libp2pNodeConfig::new(
-        args.libp2p.libp2p_key.clone().into(),
+        libp2p_key.into(),
         committee_config.member_id.get(),
         addresses,
         peer_ids,
@@ -277,6 +279,41 @@ where
             .expect("newly created node should have a transport"),
     );
 
+    Ok((libp2p_node, ts_stopper, signer))
+}
+
+fn create_remote_signer(args: &RandomnessAgentArgs) -> anyhow::Result<RemoteDSigner> {
+    let (Some(dsigner_url), Some(scheme_id)) =
+        (&args.dsigner.dsigner_url, &args.dsigner.dsigner_scheme_id)
+    else {
+        anyhow::bail!("a dsigner url and scheme id are required to use a remote dsigner")
+    };
+
+    let signer = RemoteDSigner::from_endpoint(
+        dsigner_url,
+        scheme_id,
+        args.dsigner.dsigner_api_key.as_deref(),
+    )
+    .context("failed to create remote dsigner")?
+    .with_timeout(Duration::from_secs(args.dsigner.dsigner_timeout_secs));
+    Ok(signer)
+}
+
+fn create_fulfiller<CG, P>(
+    args: &RandomnessAgentArgs,
+    signer: Box<dyn DSignerSchemeSigner + Send + Sync>,
+    algorithm: SignatureAlgorithm,
+    signature_sender_contract: SignatureSender::SignatureSenderInstance<P>,
+    randomness_sender_contract: RandomnessSender::RandomnessSenderInstance<P>,
+) -> (
+    NotifyTicker,
+    OneshotStopper,
+    UnboundedRequestChannel<SignatureRequest>,
+)
+where
+    CG: CurveGroup<Affine: PointDeserializeCompressed>,
+    P: Provider + WalletProvider + Clone + 'static,
+{
     // Create a transaction fulfiller
     let mut signature_tx_fulfiller = RandomnessFulfiller::new(
         signature_sender_contract,
@@ -294,7 +331,7 @@ where
     }
 
     // Create a ticker-based fulfiller
-    let fulfiller = SignatureSenderFulfillerConfig::<<BLS::E as Pairing>::G1, _, _>::new_fulfiller(
+    let fulfiller = SignatureSenderFulfillerConfig::<CG, _, _>::new_fulfiller(
         signer,
         algorithm,
         ApplicationArgs::Randomness(ApplicationRandomnessArgs {
@@ -310,5 +347,5 @@ where
 
     let ticker = NotifyTicker::default();
     let (stopper, channel) = fulfiller.run(ticker.clone());
-    Ok((ticker, libp2p_node, ts_stopper, stopper, channel))
+    (ticker, stopper, channel)
 }
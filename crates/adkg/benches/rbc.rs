@@ -0,0 +1,192 @@
+//! Compares the bandwidth and latency of the reliable broadcast protocols for various committee
+//! sizes and payload sizes.
+//!
+//! Run with `cargo bench -p adkg --bench rbc`.
+use adkg::helpers::PartyId;
+use adkg::network::RetryStrategy;
+use adkg::rbc::bracha::BrachaRbcConfig;
+use adkg::rbc::hashed_bracha::HashedBrachaRbcConfig;
+use adkg::rbc::r4::Rbc4RoundsConfig;
+use adkg::rbc::{AlwaysTruePredicate, ReliableBroadcast, ReliableBroadcastConfig};
+use dcipher_network::topic::dispatcher::TopicDispatcher;
+use dcipher_network::transports::in_memory::MemoryNetwork;
+use dcipher_network::{Recipient, Transport, TransportSender};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
+
+const COMMITTEE_SIZES: [usize; 3] = [4, 10, 30];
+const PAYLOAD_SIZES: [(&str, usize); 2] = [("small", 256), ("large", 64 * 1024)];
+const ITERATIONS: usize = 5;
+
+/// Transport that counts the number of bytes sent to other parties.
+struct CountingTransport<T> {
+    inner: T,
+    id: PartyId,
+    n: usize,
+    bytes: Arc<AtomicUsize>,
+}
+
+#[derive(Clone)]
+struct CountingSender<S> {
+    inner: S,
+    id: PartyId,
+    n: usize,
+    bytes: Arc<AtomicUsize>,
+}
+
+impl<T> Transport for CountingTransport<T>
+where
+    T: Transport<Identity = PartyId>,
+{
+    type Error = T::Error;
+    type Identity = PartyId;
+    type ReceiveMessageStream = T::ReceiveMessageStream;
+    type Sender = CountingSender<T::Sender>;
+
+    fn sender(&mut self) -> Option<Self::Sender> {
+        Some(CountingSender {
+            inner: self.inner.sender()?,
+            id: self.id,
+            n: self.n,
+            bytes: self.bytes.clone(),
+        })
+    }
+
+    fn receiver_stream(&mut self) -> Option<Self::ReceiveMessageStream> {
+        self.inner.receiver_stream()
+    }
+}
+
+impl<S> TransportSender for CountingSender<S>
+where
+    S: TransportSender<Identity = PartyId>,
+{
+    type Identity = PartyId;
+    type Error = S::Error;
+
+    async fn send(&self, msg: Vec<u8>, to: Recipient<PartyId>) -> Result<(), Self::Error> {
+        // Messages sent to self do not go through the network
+        let copies = match to {
+            Recipient::All | Recipient::AllIncludingSelf => self.n - 1,
+            Recipient::Single(j) => usize::from(j != self.id),
+        };
+        self.bytes.fetch_add(copies * msg.len(), Ordering::Relaxed);
+        self.inner.send(msg, to).await
+    }
+}
+
+/// Run a single reliable broadcast with n parties, and return the number of bytes sent over the
+/// network and the time required for all parties to output the message.
+async fn run_rbc<C>(n: usize, m: &[u8], new_config: impl Fn(PartyId) -> Arc<C>) -> (usize, Duration)
+where
+    C: ReliableBroadcastConfig<'static, PartyId>,
+{
+    let t = (n - 1) / 3;
+    let bytes = Arc::new(AtomicUsize::new(0));
+    let (dispatchers, mut transports): (Vec<_>, VecDeque<_>) =
+        MemoryNetwork::get_transports(PartyId::iter_all(n))
+            .into_iter()
+            .zip(PartyId::iter_all(n))
+            .map(|(inner, id)| {
+                let mut dispatcher = TopicDispatcher::new();
+                let tbt = dispatcher.start(CountingTransport {
+                    inner,
+                    id,
+                    n,
+                    bytes: bytes.clone(),
+                });
+                (dispatcher, tbt)
+            })
+            .collect();
+
+    let m = Arc::new(m.to_vec());
+    let start = Instant::now();
+    let mut tasks = JoinSet::new();
+    for i in PartyId::iter_all(n) {
+        let broadcast = new_config(i)
+            .new_instance_with_prefix(
+                "bench".to_owned(),
+                Arc::new(transports.pop_front().unwrap()),
+            )
+            .expect("failed to create rbc instance");
+
+        let m = m.clone();
+        tasks.spawn(async move {
+            if i == PartyId(1) {
+                broadcast.start(&m, CancellationToken::new()).await
+            } else {
+                broadcast
+                    .listen(&AlwaysTruePredicate, PartyId(1), CancellationToken::new())
+                    .await
+            }
+        });
+    }
+
+    while let Some(res) = tasks.join_next().await {
+        let out = res.expect("rbc task panicked").expect("rbc failed");
+        assert_eq!(&out, m.as_ref(), "rbc output mismatch (n = {n}, t = {t})");
+    }
+    let elapsed = start.elapsed();
+
+    for dispatcher in dispatchers {
+        dispatcher.stop().await;
+    }
+
+    (bytes.load(Ordering::Relaxed), elapsed)
+}
+
+/// Average the bytes sent and the latency over multiple iterations.
+async fn bench<C>(n: usize, m: &[u8], new_config: impl Fn(PartyId) -> Arc<C>) -> (usize, Duration)
+where
+    C: ReliableBroadcastConfig<'static, PartyId>,
+{
+    let mut total_bytes = 0;
+    let mut total_elapsed = Duration::ZERO;
+    for _ in 0..ITERATIONS {
+        let (bytes, elapsed) = run_rbc(n, m, &new_config).await;
+        total_bytes += bytes;
+        total_elapsed += elapsed;
+    }
+
+    (total_bytes / ITERATIONS, total_elapsed / ITERATIONS as u32)
+}
+
+#[tokio::main(flavor = "multi_thread")]
+async fn main() {
+    let retry_strategy = RetryStrategy::None;
+    println!(
+        "{:<8} {:>4} {:>8} {:>14} {:>12}",
+        "rbc", "n", "payload", "bytes sent", "latency"
+    );
+    for n in COMMITTEE_SIZES {
+        let t = (n - 1) / 3;
+        for (payload, size) in PAYLOAD_SIZES {
+            let m = vec![0xa5; size];
+            let results = [
+                (
+                    "r4",
+                    bench(n, &m, |i| Rbc4RoundsConfig::new(i, n, t, &retry_strategy)).await,
+                ),
+                (
+                    "bracha",
+                    bench(n, &m, |i| BrachaRbcConfig::new(i, n, t, &retry_strategy)).await,
+                ),
+                (
+                    "hashed",
+                    bench(n, &m, |i| {
+                        HashedBrachaRbcConfig::new(i, n, t, &retry_strategy)
+                    })
+                    .await,
+                ),
+            ];
+
+            for (name, (bytes, elapsed)) in results {
+                println!("{name:<8} {n:>4} {payload:>8} {bytes:>14} {elapsed:>12.2?}");
+            }
+        }
+    }
+}
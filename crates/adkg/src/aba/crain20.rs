@@ -1,6 +1,8 @@
 //! Implementation of the Tyler Crain's Asynchronous Byzantine Agreement described in https://arxiv.org/pdf/2002.08765.
 //! We specifically implement the Good-Case-Coin-Free variant described in https://eprint.iacr.org/2021/1591.pdf, Appendix B.
 
+#[cfg(feature = "bls-coin")]
+mod bls_coin_toss;
 mod broadcast;
 mod coin;
 mod ecdh_coin_toss;
@@ -8,6 +10,8 @@ pub mod messages;
 mod recv_handler;
 
 pub use crate::aba::crain20::coin::CoinToss;
+#[cfg(feature = "bls-coin")]
+pub use bls_coin_toss::{BlsCoinKeys, BlsCoinToss, BlsCoinTossParams};
 pub use ecdh_coin_toss::{EcdhCoinToss, EcdhCoinTossParams};
 
 use crate::aba::{Aba, AbaConfig, Estimate};
@@ -36,7 +40,7 @@ use tokio::task::JoinError;
 use tokio_util::sync::CancellationToken;
 use tracing::{Level, debug, error, event, info};
 
-const TOPIC: &str = "abacrain20";
+pub(crate) const TOPIC: &str = "abacrain20";
 
 /// Inputs required to start actively executing the ABA protocol
 pub struct AbaInput<CK> {
@@ -85,7 +89,9 @@ pub enum AbaError {
     BsonSer(#[source] bson::ser::Error, &'static str),
 }
 
-/// Structure used to specify various parameters required by the Crain20 ABA
+/// Structure used to specify various parameters required by the Crain20 ABA.
+/// The common coin is selected through `CT`, e.g., [`EcdhCoinToss`] with the coin keys output by the
+/// ADKG, or `BlsCoinToss` (feature `bls-coin`) once a threshold BLS key is available.
 pub struct AbaCrain20Config<CT, CK> {
     id: PartyId,
     n: usize,
@@ -177,6 +183,7 @@ struct AbaCrain20Instance<CT, CK, TS> {
     config: Arc<AbaCrain20Config<CT, CK>>,
     sid: SessionId,
     sender: TS,
+    cancel: CancellationToken,
 }
 
 impl<CT, CK, T> Aba for AbaCrain20<CT, CK, T>
@@ -211,6 +218,7 @@ where
             config: config.clone(),
             sid,
             sender: sender.clone(),
+            cancel: cancellation_token.clone(),
         };
 
         debug!("Node `{}` started ABA with sid `{sid}`", config.id);
@@ -334,8 +342,14 @@ where
                 round: r,
                 view: view_r_0,
             });
-            if let Err(e) =
-                broadcast_with_self(&msg_auxset, &self.config.retry_strategy, &self.sender).await
+            if let Err(e) = broadcast_with_self(
+                &msg_auxset,
+                self.config.n,
+                &self.config.retry_strategy,
+                &self.cancel,
+                &self.sender,
+            )
+            .await
             {
                 error!(
                     "Node `{}` failed to broadcast auxset message: {e:?}",
@@ -638,6 +652,8 @@ where
             sid: aba20.sid,
             config: aba20.config,
             sender: aba20.sender,
+            // The retries of the instance are never cancelled
+            cancel: CancellationToken::new(),
         }
     }
 }
@@ -666,16 +682,20 @@ mod tests {
     use ark_std::UniformRand;
     use chacha20poly1305::aead::rand_core::{CryptoRng, RngCore};
     use dcipher_network::Transport;
-    use dcipher_network::transports::in_memory::{BusMemoryTransport, MemoryNetwork};
+    use dcipher_network::transports::in_memory::MemoryNetwork;
+    use dcipher_network::transports::simulator::{
+        LinkConfig, NodeBehaviour, Partition, SimulatedNetwork, SimulatorConfig,
+    };
     use itertools::{Itertools, izip};
     use rand::rngs::OsRng;
-    use rand::{Rng, thread_rng};
+    use rand::{Rng, SeedableRng, thread_rng};
+    use rand_chacha::ChaCha20Rng;
     use serde::{Deserialize, Serialize};
     use std::collections::hash_map::Entry;
-    use std::collections::{BTreeMap, HashMap, VecDeque};
+    use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
     use std::sync::Arc;
+    use std::time::Duration;
     use tokio::sync::oneshot;
-    use tokio::task;
     use tokio::task::JoinSet;
     use tokio_util::sync::CancellationToken;
 
@@ -747,10 +767,15 @@ mod tests {
         }
     }
 
-    fn gen_keys(n: u16, t: u16, g: G) -> (BTreeMap<PartyId, Fr>, G, BTreeMap<PartyId, G>) {
+    fn gen_keys(
+        n: u16,
+        t: u16,
+        g: G,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> (BTreeMap<PartyId, Fr>, G, BTreeMap<PartyId, G>) {
         // Build polynomial from coefficients
         let poly_coeffs = (0..t)
-            .map(|_| <G as PrimeGroup>::ScalarField::rand(&mut OsRng))
+            .map(|_| <G as PrimeGroup>::ScalarField::rand(rng))
             .collect::<Vec<_>>();
         let p = DensePolynomial::from_coefficients_slice(&poly_coeffs);
 
@@ -773,7 +798,7 @@ mod tests {
         let sid = SessionId::const_from(0);
         let est = Estimate::One;
 
-        let (sks, pk, pks) = gen_keys(n as u16, t as u16, g);
+        let (sks, pk, pks) = gen_keys(n as u16, t as u16, g, &mut OsRng);
         let estimates: Vec<_> = vec![est; n];
 
         let coin_keys = sks
@@ -785,6 +810,47 @@ mod tests {
         assert_eq!(est, final_est);
     }
 
+    #[cfg(feature = "bls-coin")]
+    #[tokio::test]
+    async fn test_aba_disagreement_bls_coin() {
+        use crate::aba::crain20::{BlsCoinKeys, BlsCoinToss};
+        use crate::helpers::lagrange_points_interpolate_at;
+        use ark_ec::{AffineRepr, CurveGroup};
+
+        let t = 2;
+        let n = 3 * t + 1;
+        let g2 = <Bn254 as Pairing>::G2::generator();
+
+        // Threshold BLS keys with public keys on G2
+        let (sks, _, _) = gen_keys(n as u16, t as u16 + 1, G::generator(), &mut OsRng);
+        let pks: BTreeMap<_, _> = sks
+            .iter()
+            .map(|(&i, ski)| (i, (g2 * ski).into_affine()))
+            .collect();
+        let points: Vec<_> = pks
+            .iter()
+            .map(|(i, pk)| (u64::from(i), pk.into_group()))
+            .collect();
+        let pk = lagrange_points_interpolate_at(&points[..=t], 0).into_affine();
+
+        for sid in 0..10usize {
+            let estimates: Vec<_> = PartyId::iter_all(n)
+                .map(|i| {
+                    if i.as_usize() % 2 == 0 {
+                        Estimate::One
+                    } else {
+                        Estimate::Zero
+                    }
+                })
+                .collect();
+            let coin_keys = sks
+                .values()
+                .map(|&sk| BlsCoinKeys::<Bn254, sha3::Sha3_256>::new(t, sk, pk, pks.clone()));
+
+            run::<BlsCoinToss<_, _>>(n, t, estimates, sid.into(), coin_keys).await;
+        }
+    }
+
     #[tokio::test]
     async fn test_aba_disagreement() {
         let t = 2;
@@ -829,6 +895,167 @@ mod tests {
         assert!((0.5 - one_p).abs() <= 0.1, "coin toss is biased");
     }
 
+    /// Asynchronous links that reorder and duplicate messages.
+    fn asynchronous_config(config: SimulatorConfig<PartyId>) -> SimulatorConfig<PartyId> {
+        config.with_default_link(
+            LinkConfig::asynchronous(Duration::from_millis(1), Duration::from_millis(500))
+                .with_duplicate_probability(0.2),
+        )
+    }
+
+    /// Lossy links from each of the `faulty` parties.
+    fn lossy_config(
+        n: usize,
+        config: SimulatorConfig<PartyId>,
+        faulty: &[PartyId],
+    ) -> SimulatorConfig<PartyId> {
+        let lossy = LinkConfig::asynchronous(Duration::from_millis(1), Duration::from_millis(500))
+            .with_drop_probability(0.5);
+        faulty
+            .iter()
+            .cartesian_product(PartyId::iter_all(n))
+            .fold(config, |config, (&from, to)| {
+                config.with_link(from, to, lossy)
+            })
+    }
+
+    /// Execute the ABA over a simulated network, with mixed estimates and ECDH coin keys derived
+    /// from the seed of the simulator such that the coins are the same across executions.
+    async fn run_simulated(
+        n: usize,
+        t: usize,
+        config: SimulatorConfig<PartyId>,
+        faulty: &[PartyId],
+    ) -> Estimate {
+        let g = G::generator();
+        let sid = SessionId::const_from(0);
+
+        let mut rng = ChaCha20Rng::seed_from_u64(config.seed);
+        let (sks, pk, pks) = gen_keys(n as u16, t as u16, g, &mut rng);
+        let coin_keys: Vec<_> = sks
+            .values()
+            .map(|&sk| CoinKeys::new(t, sk, g, pk, pks.clone()))
+            .collect();
+        let estimates: Vec<_> = PartyId::iter_all(n)
+            .map(|i| {
+                if i.as_usize() % 2 == 0 {
+                    Estimate::One
+                } else {
+                    Estimate::Zero
+                }
+            })
+            .collect();
+
+        let (handle, transports) = SimulatedNetwork::get_transports(PartyId::iter_all(n), config);
+        let est = run_with_transports::<EcdhCoinToss<G, sha3::Sha3_256>, _>(
+            transports, n, t, estimates, sid, coin_keys, faulty,
+        )
+        .await;
+        handle.stop().await;
+
+        est
+    }
+
+    /// Execute the ABA over an asynchronous network with reordered and duplicated messages
+    #[tokio::test(start_paused = true)]
+    async fn test_aba_simulated_reorder() {
+        let t = 2;
+        let n = 3 * t + 1;
+
+        let config = asynchronous_config(SimulatorConfig::from_env("DCIPHER_SIMULATOR_SEED"));
+        let seed = config.seed;
+
+        let est = run_simulated(n, t, config, &[]).await;
+        assert_ne!(est, Estimate::Bot, "seed = {seed}");
+    }
+
+    /// Execute the ABA where the messages sent by t parties are randomly dropped
+    #[tokio::test(start_paused = true)]
+    async fn test_aba_simulated_drop() {
+        let t = 2;
+        let n = 3 * t + 1;
+        let faulty = [PartyId(n - 1), PartyId(n)];
+
+        let config = asynchronous_config(SimulatorConfig::from_env("DCIPHER_SIMULATOR_SEED"));
+        let seed = config.seed;
+        let config = lossy_config(n, config, &faulty);
+
+        let est = run_simulated(n, t, config, &faulty).await;
+        assert_ne!(est, Estimate::Bot, "seed = {seed}");
+    }
+
+    /// Execute the ABA where two parties are isolated from the others for some time
+    #[tokio::test(start_paused = true)]
+    async fn test_aba_simulated_partition() {
+        let t = 2;
+        let n = 3 * t + 1;
+
+        let config = asynchronous_config(SimulatorConfig::from_env("DCIPHER_SIMULATOR_SEED"));
+        let seed = config.seed;
+        let config = config.with_partition(Partition {
+            nodes: HashSet::from([PartyId(1), PartyId(2)]),
+            start: Duration::ZERO,
+            end: Some(Duration::from_secs(2)),
+        });
+
+        let est = run_simulated(n, t, config, &[]).await;
+        assert_ne!(est, Estimate::Bot, "seed = {seed}");
+    }
+
+    /// Execute the ABA where t parties crash, one before sending anything and one mid-execution
+    #[tokio::test(start_paused = true)]
+    async fn test_aba_simulated_crash() {
+        let t = 2;
+        let n = 3 * t + 1;
+        let faulty = [PartyId(n - 1), PartyId(n)];
+
+        let config = asynchronous_config(SimulatorConfig::from_env("DCIPHER_SIMULATOR_SEED"));
+        let seed = config.seed;
+        let config = config
+            .with_behaviour(PartyId(n - 1), NodeBehaviour::Crash { at: Duration::ZERO })
+            .with_behaviour(
+                PartyId(n),
+                NodeBehaviour::Crash {
+                    at: Duration::from_millis(300),
+                },
+            );
+
+        let est = run_simulated(n, t, config, &faulty).await;
+        assert_ne!(est, Estimate::Bot, "seed = {seed}");
+    }
+
+    /// Two executions with the same seed and schedule must decide on the same value
+    #[tokio::test(start_paused = true)]
+    async fn test_aba_simulated_same_seed() {
+        let t = 2;
+        let n = 3 * t + 1;
+        let faulty = [PartyId(n - 1), PartyId(n)];
+
+        let seed = SimulatorConfig::<PartyId>::from_env("DCIPHER_SIMULATOR_SEED").seed;
+        let config = || {
+            lossy_config(
+                n,
+                asynchronous_config(SimulatorConfig::new(seed)),
+                &faulty[..1],
+            )
+            .with_partition(Partition {
+                nodes: HashSet::from([PartyId(1), PartyId(2)]),
+                start: Duration::ZERO,
+                end: Some(Duration::from_secs(2)),
+            })
+            .with_behaviour(
+                PartyId(n),
+                NodeBehaviour::Crash {
+                    at: Duration::from_millis(300),
+                },
+            )
+        };
+
+        let first = run_simulated(n, t, config(), &faulty).await;
+        let second = run_simulated(n, t, config(), &faulty).await;
+        assert_eq!(first, second, "seed = {seed}");
+    }
+
     #[allow(clippy::too_many_arguments)]
     async fn run<CT>(
         n: usize,
@@ -840,75 +1067,87 @@ mod tests {
     where
         CT: CoinToss,
     {
-        let mut transports: VecDeque<_> = MemoryNetwork::get_transports(PartyId::iter_all(n));
-        let mut abas: VecDeque<_> = PartyId::iter_all(n)
-            .map(|i| AbaCrain20Config::<_, _>::new(i, n, t, RetryStrategy::None))
-            .collect();
+        let transports = MemoryNetwork::get_transports(PartyId::iter_all(n));
+        run_with_transports(transports, n, t, estimates, sid, cks, &[]).await
+    }
 
-        let mut tasks = JoinSet::new();
+    /// Execute the ABA with one transport per party, and return the value decided by the parties
+    /// that are not `faulty`. The remaining parties are stopped once every honest party decided.
+    async fn run_with_transports<CT, T>(
+        mut transports: VecDeque<T>,
+        n: usize,
+        t: usize,
+        estimates: Vec<Estimate>,
+        sid: SessionId,
+        cks: impl IntoIterator<Item: Into<CoinKeys<CT>> + Send + 'static>,
+        faulty: &[PartyId],
+    ) -> Estimate
+    where
+        CT: CoinToss,
+        T: Transport<Identity = PartyId> + Send + 'static,
+        T::Sender: Clone,
+    {
+        let cancel = CancellationToken::new();
+        let mut aba_tasks = JoinSet::new();
+        let mut honest_outputs = JoinSet::new();
+        let mut faulty_outputs = vec![];
         for (i, ck) in izip!(PartyId::iter_all(n), cks) {
-            tasks.spawn({
-                let mut transport = transports.pop_front().unwrap();
-                let aba_config = abas.pop_front().unwrap();
-                let v = estimates[i];
+            let mut transport = transports.pop_front().unwrap();
+            let aba_config = AbaCrain20Config::<_, _>::new(i, n, t, RetryStrategy::None);
+            let (isender, ireceiver) = oneshot::channel();
+            let (osender, oreceiver) = oneshot::channel();
+
+            // Create input with the estimate of the party
+            let (coin_keys_sender, coin_keys_receiver) = oneshot::channel();
+            coin_keys_sender
+                .send(ck)
+                .unwrap_or_else(|_| panic!("failed to send coin keys"));
+            isender
+                .send(AbaInput {
+                    v: estimates[i],
+                    coin_keys_receiver,
+                })
+                .unwrap_or_else(|_| panic!("failed to send estimate"));
 
+            // Spawn aba task
+            aba_tasks.spawn({
+                let cancel = cancel.clone();
                 async move {
-                    let (isender, ireceiver) = oneshot::channel();
-                    let (osender, oreceiver) = oneshot::channel();
-                    let cancel = CancellationToken::new();
-                    let cancel_cloned = cancel.clone();
-
-                    // Create input with One estimate
-                    let (coin_keys_sender, coin_keys_receiver) = oneshot::channel();
-                    coin_keys_sender
-                        .send(ck)
-                        .unwrap_or_else(|_| panic!("failed to send coin keys"));
-
-                    let est = AbaInput {
-                        v,
-                        coin_keys_receiver,
+                    let aba = AbaCrain20::<_, _, T> {
+                        config: aba_config,
+                        receiver: transport.receiver_stream().unwrap(),
+                        sender: transport.sender().unwrap(),
+                        sid,
                     };
-
-                    // Spawn aba task
-                    let aba_task = task::spawn(async move {
-                        let aba = AbaCrain20::<_, _, BusMemoryTransport<_>> {
-                            config: aba_config,
-                            receiver: transport.receiver_stream().unwrap(),
-                            sender: transport.sender().unwrap(),
-                            sid,
-                        };
-                        aba.propose(ireceiver, osender, cancel_cloned, &mut OsRng)
-                            .await
-                    });
-
-                    // Send the estimate through the channel
-                    isender
-                        .send(est)
-                        .unwrap_or_else(|_| panic!("failed to send estimate"));
-
-                    // Wait for the estimate
-                    let estimate = oreceiver.await.expect("aba thread dropped oneshot sender");
-                    cancel.cancel();
-
-                    // Wait for aba to complete
-                    aba_task
-                        .await
-                        .expect("failed to join thread")
-                        .expect("aba failed");
-                    (i, estimate)
+                    aba.propose(ireceiver, osender, cancel, &mut OsRng).await
                 }
             });
+
+            if faulty.contains(&i) {
+                // Faulty parties may never decide, keep the receiver until the end
+                faulty_outputs.push(oreceiver);
+            } else {
+                honest_outputs.spawn(async move {
+                    oreceiver.await.expect("aba thread dropped oneshot sender")
+                });
+            }
         }
 
         let mut ests = vec![];
-        while let Some(res) = tasks.join_next().await {
-            assert!(res.is_ok());
-            let (_, est) = res.unwrap();
+        while let Some(res) = honest_outputs.join_next().await {
+            let est = res.expect("failed to join thread");
             assert!([Estimate::Zero, Estimate::One].contains(&est));
             ests.push(est);
         }
         assert!(ests.iter().all_equal());
 
+        // Wait for aba to complete
+        cancel.cancel();
+        while let Some(res) = aba_tasks.join_next().await {
+            res.expect("failed to join thread").expect("aba failed");
+        }
+        drop(faulty_outputs);
+
         *ests.first().unwrap()
     }
 }
@@ -0,0 +1,285 @@
+//! Implementation of a common coin based on unique threshold BLS signatures, see https://eprint.iacr.org/2000/034.pdf, Section 4.
+//! Since BLS signatures are unique and publicly verifiable, the evaluations do not require a NIZK
+//! proof, and the coin is obtained with a single pairing check when all the evaluations are valid.
+//! It can be used once a threshold BLS key has been generated, e.g., by a previous ADKG.
+
+use crate::aba::crain20::CoinKeys;
+use crate::aba::crain20::coin::CoinToss;
+use crate::aba::crain20::ecdh_coin_toss::Coin;
+use crate::helpers::PartyId;
+use ark_ec::pairing::Pairing;
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_std::Zero;
+use dcipher_signer::bls::{
+    BlsPairingSigner, BlsSigner, BlsVerifier, lagrange_points_interpolate_at,
+};
+use digest::{FixedOutputReset, core_api::BlockSizeUser};
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+use std::collections::BTreeMap;
+use std::marker::PhantomData;
+use thiserror::Error;
+use utils::dst::{EncodingType, NamedCurveGroup, NamedDynDigest, Rfc9380DstBuilder};
+use utils::hash_to_curve::CustomHashToCurve;
+use utils::serialize::SerializationError;
+use utils::serialize::point::{PointDeserializeCompressed, PointSerializeCompressed};
+
+const APPNAME: &[u8] = b"BLS-COIN-TOSS-v1";
+const COIN_SUFFIX: &[u8] = b"COIN";
+
+pub struct BlsCoinToss<E, H> {
+    _e: PhantomData<fn() -> E>,
+    _h: PhantomData<fn(H)>,
+}
+
+pub struct BlsCoinTossParams<E: Pairing> {
+    /// malicious threshold / degree of the polynomial
+    pub t: usize,
+    /// threshold public key
+    pub pk: E::G2Affine,
+    /// public key of each party
+    pub pks: BTreeMap<PartyId, E::G2Affine>,
+}
+
+pub type BlsCoinKeys<E, H> = CoinKeys<BlsCoinToss<E, H>>;
+
+impl<E, H> BlsCoinKeys<E, H>
+where
+    BlsCoinToss<E, H>: CoinToss<SecretKey = E::ScalarField, PublicParams = BlsCoinTossParams<E>>,
+    E: Pairing,
+{
+    pub fn new(
+        t: usize,
+        sk: E::ScalarField,
+        pk: E::G2Affine,
+        pks: BTreeMap<PartyId, E::G2Affine>,
+    ) -> Self {
+        Self {
+            sk,
+            params: BlsCoinTossParams { t, pk, pks },
+        }
+    }
+}
+
+impl<E, H> CoinToss for BlsCoinToss<E, H>
+where
+    E: Pairing,
+    E::G1: CustomHashToCurve + NamedCurveGroup,
+    E::G2: CustomHashToCurve + NamedCurveGroup,
+    E::G1Affine: PointSerializeCompressed + PointDeserializeCompressed,
+    E::G2Affine: PointSerializeCompressed,
+    BlsPairingSigner<E>: BlsSigner<E = E>,
+    H: FixedOutputReset + BlockSizeUser + Default + Clone + NamedDynDigest + 'static,
+{
+    type Error = BlsCoinTossError;
+    type SecretKey = E::ScalarField;
+    type PublicParams = BlsCoinTossParams<E>;
+    type Eval = BlsCoinTossEval<E>;
+
+    fn eval(
+        sk: &Self::SecretKey,
+        params: &Self::PublicParams,
+        sid: usize,
+        round: u8,
+        _rng: &mut (impl CryptoRng + RngCore),
+    ) -> Result<Self::Eval, Self::Error> {
+        let coin_input = coin_input(sid, round, &params.pk).map_err(|_| BlsCoinTossError)?;
+        let sig = BlsPairingSigner::<E>::new(*sk)
+            .sign_g1::<H>(coin_input, coin_dst::<E, H>())
+            .map_err(|_| BlsCoinTossError)?;
+
+        Ok(BlsCoinTossEval { sig })
+    }
+
+    fn get_coin<'a, I>(
+        evals: I,
+        params: &Self::PublicParams,
+        sid: usize,
+        round: u8,
+    ) -> Result<Coin, Self::Error>
+    where
+        I: IntoIterator<Item = (PartyId, &'a Self::Eval)> + 'a,
+        Self::Eval: 'a,
+    {
+        let evals: Vec<_> = evals.into_iter().collect();
+        if evals.len() < params.t + 1 {
+            Err(BlsCoinTossError)?
+        }
+
+        let coin_input = coin_input(sid, round, &params.pk).map_err(|_| BlsCoinTossError)?;
+        let dst = coin_dst::<E, H>();
+
+        // Verification does not depend on the key of the signer
+        let verifier = BlsPairingSigner::<E>::new(E::ScalarField::zero());
+        let verify =
+            |sig: E::G1Affine, pk: E::G2Affine| verifier.verify_g1::<H>(&coin_input, &dst, sig, pk);
+
+        // Optimistically aggregate the first t + 1 evaluations, and verify the resulting signature
+        let points: Vec<_> = evals
+            .iter()
+            .map(|(j, eval)| (u64::from(j), eval.sig.into_group()))
+            .collect();
+        let mut sig = lagrange_points_interpolate_at(&points[..=params.t], 0).into_affine();
+        if !verify(sig, params.pk) {
+            // At least one of the evaluations is invalid, only aggregate the valid evaluations
+            let valid: Vec<_> = evals
+                .iter()
+                .zip(points)
+                .filter(|((j, eval), _)| {
+                    params
+                        .pks
+                        .get(j)
+                        .is_some_and(|pk_j| verify(eval.sig, *pk_j))
+                })
+                .map(|(_, point)| point)
+                .collect();
+            if valid.len() < params.t + 1 {
+                Err(BlsCoinTossError)?
+            }
+
+            sig = lagrange_points_interpolate_at(&valid[..=params.t], 0).into_affine();
+        }
+
+        // The coin is defined by the most significant bit of the hashed signature
+        let ser = sig.ser_compressed().map_err(|_| BlsCoinTossError)?;
+        let coin = (Sha3_256::digest(&ser)[0] >> 7) & 0b1;
+        Ok(coin.try_into().unwrap()) // coin is always 0 or 1 => cannot fail
+    }
+}
+
+fn coin_input<G>(sid: usize, round: u8, pk: &G) -> Result<Vec<u8>, SerializationError>
+where
+    G: PointSerializeCompressed,
+{
+    let m = [
+        sid.to_be_bytes().to_vec(),
+        round.to_be_bytes().to_vec(),
+        pk.ser_compressed()?,
+    ]
+    .concat();
+    Ok(m)
+}
+
+/// Generate a DST in the following format: BLS-COIN-TOSS-v1_%CURVE_NAME%_XMD:%HASH_NAME%_RO_COIN_
+/// e.g.: BLS-COIN-TOSS-v1_BN254G1_XMD:SHA3-256_RO_COIN_
+fn coin_dst<E, H>() -> Vec<u8>
+where
+    E: Pairing,
+    E::G1: NamedCurveGroup,
+    H: NamedDynDigest,
+{
+    Rfc9380DstBuilder::empty()
+        .with_application_name(APPNAME.to_vec())
+        .with_curve::<E::G1>()
+        .with_hash::<H>()
+        .with_encoding(EncodingType::Uniform)
+        .with_suffix(COIN_SUFFIX.to_vec())
+        .build()
+        .into()
+}
+
+#[derive(Error, Debug)]
+#[error("opaque bls coin toss error")]
+pub struct BlsCoinTossError;
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(bound(
+    serialize = "E::G1Affine: PointSerializeCompressed",
+    deserialize = "E::G1Affine: PointDeserializeCompressed"
+))]
+pub struct BlsCoinTossEval<E: Pairing> {
+    /// partial signature on the coin input
+    #[serde(with = "utils::serialize::point::base64")]
+    pub sig: E::G1Affine,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::eval_poly;
+    use ark_bn254::Bn254;
+    use ark_ec::PrimeGroup;
+    use ark_std::UniformRand;
+    use rand::thread_rng;
+
+    type CT = BlsCoinToss<Bn254, sha3::Sha3_256>;
+
+    fn get_keys(n: usize, t: usize) -> (Vec<ark_bn254::Fr>, BlsCoinTossParams<Bn254>) {
+        let poly: Vec<_> = (0..=t)
+            .map(|_| ark_bn254::Fr::rand(&mut thread_rng()))
+            .collect();
+        let g2 = ark_bn254::G2Projective::generator();
+
+        let sks: Vec<_> = PartyId::iter_all(n)
+            .map(|i| eval_poly(&u64::from(i).into(), &poly))
+            .collect();
+        let pks = PartyId::iter_all(n)
+            .map(|i| (i, (g2 * sks[i]).into_affine()))
+            .collect();
+        let pk = (g2 * poly[0]).into_affine();
+
+        (sks, BlsCoinTossParams { t, pk, pks })
+    }
+
+    fn evals(
+        sks: &[ark_bn254::Fr],
+        params: &BlsCoinTossParams<Bn254>,
+        sid: usize,
+        round: u8,
+    ) -> Vec<(PartyId, BlsCoinTossEval<Bn254>)> {
+        PartyId::iter_all(sks.len())
+            .map(|i| {
+                let eval = CT::eval(&sks[i], params, sid, round, &mut thread_rng()).unwrap();
+                (i, eval)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_consistency() {
+        let n = 7;
+        let t = 2;
+        let (sks, params) = get_keys(n, t);
+        let evals = evals(&sks, &params, 1, 0);
+
+        // Any subset of t + 1 evaluations outputs the same coin
+        let coin = CT::get_coin(evals[..=t].iter().map(|(i, e)| (*i, e)), &params, 1, 0).unwrap();
+        let other_coin =
+            CT::get_coin(evals[t..].iter().map(|(i, e)| (*i, e)), &params, 1, 0).unwrap();
+        assert_eq!(coin, other_coin);
+    }
+
+    #[test]
+    fn filters_invalid_evals() {
+        let n = 7;
+        let t = 2;
+        let (sks, params) = get_keys(n, t);
+        let mut evals = evals(&sks, &params, 1, 0);
+        let coin = CT::get_coin(evals.iter().map(|(i, e)| (*i, e)), &params, 1, 0).unwrap();
+
+        // We corrupt the first t evals by doubling the point
+        for (_, eval) in evals.iter_mut().take(t) {
+            eval.sig = (eval.sig + eval.sig).into_affine();
+        }
+
+        let filtered_coin =
+            CT::get_coin(evals.iter().map(|(i, e)| (*i, e)), &params, 1, 0).unwrap();
+        assert_eq!(coin, filtered_coin);
+    }
+
+    #[test]
+    fn too_many_invalid_evals() {
+        let n = 7;
+        let t = 2;
+        let (sks, params) = get_keys(n, t);
+        let mut evals = evals(&sks, &params, 1, 0);
+
+        // We corrupt the first two evals by doubling the point
+        for (_, eval) in evals.iter_mut().take(2) {
+            eval.sig = (eval.sig + eval.sig).into_affine();
+        }
+
+        assert!(CT::get_coin(evals[..=t].iter().map(|(i, e)| (*i, e)), &params, 1, 0).is_err());
+    }
+}
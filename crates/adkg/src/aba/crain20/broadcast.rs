@@ -34,8 +34,14 @@ where
             self.config.id,
             msg_est
         );
-        if let Err(e) =
-            broadcast_with_self(&msg_est, &self.config.retry_strategy, &self.sender).await
+        if let Err(e) = broadcast_with_self(
+            &msg_est,
+            self.config.n,
+            &self.config.retry_strategy,
+            &self.cancel,
+            &self.sender,
+        )
+        .await
         {
             error!(
                 "Node `{}` failed to broadcast estimate message: {e:?}",
@@ -92,8 +98,14 @@ where
                 msg_aux
             );
 
-            if let Err(e) =
-                broadcast_with_self(&msg_aux, &self.config.retry_strategy, &self.sender).await
+            if let Err(e) = broadcast_with_self(
+                &msg_aux,
+                self.config.n,
+                &self.config.retry_strategy,
+                &self.cancel,
+                &self.sender,
+            )
+            .await
             {
                 error!(
                     "Node `{}` failed to broadcast aux message: {e:?}",
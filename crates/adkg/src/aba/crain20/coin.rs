@@ -102,8 +102,14 @@ where
 
         let msg_coin_eval = AbaMessage::CoinEval(CoinEvalMessage::new(eval, r).unwrap());
 
-        if let Err(e) =
-            broadcast_with_self(&msg_coin_eval, &self.config.retry_strategy, &self.sender).await
+        if let Err(e) = broadcast_with_self(
+            &msg_coin_eval,
+            self.config.n,
+            &self.config.retry_strategy,
+            &self.cancel,
+            &self.sender,
+        )
+        .await
         {
             error!(
                 "Node `{}` failed to broadcast coin eval message: {e:?}",
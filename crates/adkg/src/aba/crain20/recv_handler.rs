@@ -33,7 +33,7 @@ where
                 info!("Node `{id}` in ABA with sid `{sid}` stopping recv_thread");
             }
 
-            _ = Self::recv_loop(config, receiver, sender, state).instrument(tracing::info_span!("recv_loop", ?sid)) => {}
+            _ = Self::recv_loop(config, receiver, sender, &cancel, state).instrument(tracing::info_span!("recv_loop", ?sid)) => {}
         }
     }
 
@@ -42,6 +42,7 @@ where
         config: Arc<AbaCrain20Config<CT, CK>>,
         mut receiver: T::ReceiveMessageStream,
         sender: T::Sender,
+        cancel: &CancellationToken,
         state: Arc<AbaState<CT>>,
     ) {
         // Local variables
@@ -110,8 +111,14 @@ where
                         // 5: if BVAL(v) received from t + 1 different nodes AND BVAL(v) was not sent, then
                         // 6: Send BVAL(v) to all nodes
                         let msg_est = AbaMessage::Estimate(est);
-                        if let Err(e) =
-                            broadcast_with_self(&msg_est, &config.retry_strategy, &sender).await
+                        if let Err(e) = broadcast_with_self(
+                            &msg_est,
+                            config.n,
+                            &config.retry_strategy,
+                            cancel,
+                            &sender,
+                        )
+                        .await
                         {
                             error!(
                                 "Node `{}` failed to broadcast estimate message: {e:?}",
@@ -1,11 +1,14 @@
 use futures::{FutureExt, pin_mut};
 mod randex;
+mod refresh;
 pub(crate) mod types;
 
 /// Re-export types required by ADKG trait bounds
 pub use types::AbaCrainInput;
 pub use types::ShareWithPoly;
 
+pub use refresh::AdkgRefreshInput;
+
 use crate::aba::crain20::AbaInput;
 use crate::aba::multi_aba::MultiAba;
 use crate::aba::{AbaConfig, Estimate};
@@ -49,6 +52,9 @@ use utils::serialize::point::{PointDeserializeCompressed, PointSerializeCompress
 const APPNAME: &[u8] = b"ADKG-v1";
 const KEY_POK_SUFFIX: &[u8] = b"KEY_POK";
 
+/// Topic used to exchange the messages of the randomness extraction and key derivation phases.
+pub const ADKG_TOPIC: &str = "adkg";
+
 pub struct Adkg<CG, H, RBCConfig, ACSSConfig, ABAConfig>
 where
     CG: CurveGroup,
@@ -103,6 +109,15 @@ pub enum AdkgError {
 
     #[error("failed to complete randomness extraction phase")]
     RandEx,
+
+    #[error("invalid refresh input: node public keys do not match the secret share")]
+    InvalidRefreshInput,
+
+    #[error("refresh requires the reconstruction threshold to be equal to t")]
+    UnsupportedRefreshThreshold,
+
+    #[error("failed to refresh shares: `{0}`")]
+    Refresh(&'static str),
 }
 
 pub struct AdkgOutput<CG: CurveGroup> {
@@ -151,15 +166,7 @@ where
         let multi_acss = MultiAcss::new(id, n, acss_config);
         let multi_aba = MultiAba::new(id, n, aba_config);
 
-        // Generate a DST in the following format: ADKG-v1_%CURVE_NAME%_XMD:%HASH_NAME%_RO_KEY_POK_
-        // e.g.: ADKG-v1_BN254G1_XMD:SHA3-256_RO_KEY_POK_
-        let pok_dst = Rfc9380DstBuilder::empty()
-            .with_application_name(APPNAME.to_vec())
-            .with_curve::<CG>()
-            .with_hash::<H>()
-            .with_suffix(KEY_POK_SUFFIX.to_vec())
-            .build()
-            .into();
+        let pok_dst = key_pok_dst::<CG, H>();
 
         Self {
             id,
@@ -316,7 +323,7 @@ where
 
         let state = self.shared_state.clone();
         let mut adkg_transport = transport
-            .get_transport_for("adkg".to_string())
+            .get_transport_for(ADKG_TOPIC)
             .ok_or(AdkgError::TransportInit)?;
         let adkg_sender = adkg_transport.sender().ok_or(AdkgError::TransportInit)?;
         let mut adkg_receiver = adkg_transport
@@ -344,6 +351,75 @@ where
             peds: pedersen_in,
         };
 
+        let final_sessions = self
+            .agree_on_sessions(start_signal, s, rng, transport)
+            .await?;
+
+        // We got the final list of parties, enough ACSS instances have completed
+        // 24: Let T be the output of the MVBA protocol
+        let acss_outputs: Vec<_> = state
+            .completed_acss_outputs
+            .filter_outputs(final_sessions.iter())
+            .collect();
+
+        // Randomness extraction phase: recover pedersen commits, shares z_i, z_hat_i, and, optionally,
+        // messages for the next round.
+        let (z_i, z_hat_i, ped_commits, key_messages) = self
+            .randex_phase::<T::Transport>(
+                shares_per_acss,
+                acss_outputs,
+                &adkg_sender,
+                &mut adkg_receiver,
+            )
+            .await?;
+
+        // Group & partial public key derivation phase
+        let key_der_out = self
+            .key_derivation_phase::<T::Transport>(
+                &z_i,
+                &z_hat_i,
+                &ped_commits,
+                key_messages,
+                &adkg_sender,
+                &mut adkg_receiver,
+                rng,
+            )
+            .await;
+        let (group_pk, node_pks) = match key_der_out {
+            Ok((group_pk, nodes_pks)) => (Some(group_pk), Some(nodes_pks)),
+            Err(e) => {
+                error!(
+                    error = ?e,
+                    "ADKG failed to derive public keys"
+                );
+
+                // If it fails, return no group_pk / nodes_pks
+                (None, None)
+            }
+        };
+
+        Ok(AdkgOutput {
+            sk: z_i,
+            used_sessions: final_sessions,
+            group_pk,
+            node_pks,
+        })
+    }
+
+    /// Execute the ACSS, RBC and ABA phases in order to agree on a common set of completed ACSS
+    /// sessions, with `s` as the input to the node's own ACSS.
+    async fn agree_on_sessions<T>(
+        &mut self,
+        start_signal: impl Future,
+        s: Hbacss0Input<CG::ScalarField>,
+        rng: &mut impl AdkgRng,
+        transport: Arc<T>,
+    ) -> Result<Vec<SessionId>, AdkgError>
+    where
+        T: TopicBasedTransport<Identity = PartyId>,
+    {
+        let state = self.shared_state.clone();
+
         // Generate predicates for each of the RBCs
         let rbc_predicates: Vec<_> = PartyId::iter_all(self.n)
             .map(|i| NotifyPredicate {
@@ -413,13 +489,13 @@ where
             self.id
         );
         let abas_task_result = abas_task.await;
-        let final_sessions = match abas_task_result {
+        match abas_task_result {
             Ok(Ok(final_parties)) => {
                 info!(
                     "ADKG main thread of node `{}` obtained the final list of parties: {final_parties:?}",
                     self.id
                 );
-                final_parties
+                Ok(final_parties)
             }
             Ok(Err(e)) => {
                 error!(
@@ -437,57 +513,7 @@ where
                     self.id
                 );
             }
-        };
-
-        // We got the final list of parties, enough ACSS instances have completed
-        // 24: Let T be the output of the MVBA protocol
-        let acss_outputs: Vec<_> = state
-            .completed_acss_outputs
-            .filter_outputs(final_sessions.iter())
-            .collect();
-
-        // Randomness extraction phase: recover pedersen commits, shares z_i, z_hat_i, and, optionally,
-        // messages for the next round.
-        let (z_i, z_hat_i, ped_commits, key_messages) = self
-            .randex_phase::<T::Transport>(
-                shares_per_acss,
-                acss_outputs,
-                &adkg_sender,
-                &mut adkg_receiver,
-            )
-            .await?;
-
-        // Group & partial public key derivation phase
-        let key_der_out = self
-            .key_derivation_phase::<T::Transport>(
-                &z_i,
-                &z_hat_i,
-                &ped_commits,
-                key_messages,
-                &adkg_sender,
-                &mut adkg_receiver,
-                rng,
-            )
-            .await;
-        let (group_pk, node_pks) = match key_der_out {
-            Ok((group_pk, nodes_pks)) => (Some(group_pk), Some(nodes_pks)),
-            Err(e) => {
-                error!(
-                    error = ?e,
-                    "ADKG failed to derive public keys"
-                );
-
-                // If it fails, return no group_pk / nodes_pks
-                (None, None)
-            }
-        };
-
-        Ok(AdkgOutput {
-            sk: z_i,
-            used_sessions: final_sessions,
-            group_pk,
-            node_pks,
-        })
+        }
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -524,7 +550,15 @@ where
             g_z_j: g_z_i,
             h_z_hat_j: h_z_hat_i,
         });
-        if let Err(e) = broadcast_with_self(&msg, &RetryStrategy::None, adkg_sender).await {
+        if let Err(e) = broadcast_with_self(
+            &msg,
+            self.n,
+            &RetryStrategy::None,
+            &self.cancel,
+            adkg_sender,
+        )
+        .await
+        {
             error!(
                 "Node `{}` failed to send ADKG KEY message to other nodes: {e:?}",
                 self.id
@@ -664,6 +698,7 @@ where
                 &msg,
                 Recipient::Single(j_id),
                 &RetryStrategy::None,
+                &self.cancel,
                 adkg_sender,
             )
             .await
@@ -1196,12 +1231,60 @@ where
     }
 }
 
+/// Generate a DST in the following format: ADKG-v1_%CURVE_NAME%_XMD:%HASH_NAME%_RO_KEY_POK_
+/// e.g.: ADKG-v1_BN254G1_XMD:SHA3-256_RO_KEY_POK_
+fn key_pok_dst<CG, H>() -> Vec<u8>
+where
+    CG: NamedCurveGroup,
+    H: NamedDynDigest,
+{
+    Rfc9380DstBuilder::empty()
+        .with_application_name(APPNAME.to_vec())
+        .with_curve::<CG>()
+        .with_hash::<H>()
+        .with_suffix(KEY_POK_SUFFIX.to_vec())
+        .build()
+        .into()
+}
+
+/// Verify the proofs of knowledge of a KEY message broadcast on the [`ADKG_TOPIC`] during the key
+/// derivation phase, and return the public key [z_j] G of its sender. Returns `None` if the message
+/// is a valid ADKG message of another type.
+///
+/// This allows third parties to audit the public keys output by an ADKG from its transcripts.
+pub fn verify_key_message<CG, H>(g: &CG, h: &CG, msg: &[u8]) -> Result<Option<CG>, Box<AdkgError>>
+where
+    CG: NamedCurveGroup + PointSerializeCompressed + PointDeserializeCompressed,
+    CG::ScalarField: FqDeserialize,
+    H: Default + NamedDynDigest + DynDigest + FixedOutputReset + BlockSizeUser + Clone,
+{
+    let msg: AdkgMessage<CG, H> = bson::from_slice(msg)
+        .map_err(|e| AdkgError::BsonDe(e, "failed to deserialize adkg message"))?;
+    let AdkgMessage::Key(msg) = msg else {
+        return Ok(None);
+    };
+
+    let pok_dst = key_pok_dst::<CG, H>();
+    msg.z_j_proof
+        .verify(g, &msg.g_z_j, &pok_dst)
+        .map_err(|e| AdkgError::Pok(e, "failed to verify z_j proof"))?;
+    msg.z_hat_j_proof
+        .verify(h, &msg.h_z_hat_j, &pok_dst)
+        .map_err(|e| AdkgError::Pok(e, "failed to verify z_hat_j proof"))?;
+
+    Ok(Some(msg.g_z_j))
+}
+
 #[cfg(test)]
 mod tests {
     use crate::aba::crain20::{AbaCrain20Config, EcdhCoinToss};
-    use crate::adkg::{APPNAME, Adkg, AdkgOutput};
-    use crate::helpers::{PartyId, lagrange_interpolate_at};
+    use crate::adkg::types::{AdkgKeyMessage, AdkgMessage, AdkgRandExMessage};
+    use crate::adkg::{
+        APPNAME, Adkg, AdkgError, AdkgOutput, AdkgRefreshInput, key_pok_dst, verify_key_message,
+    };
+    use crate::helpers::{PartyId, SessionId, lagrange_interpolate_at};
     use crate::network::RetryStrategy;
+    use crate::pok::PokProof;
     use crate::rand::{AdkgRng, AdkgRngType, get_rng};
     use crate::rbc::r4::Rbc4RoundsConfig;
     use crate::vss::acss::hbacss0::HbAcss0Config;
@@ -1209,11 +1292,17 @@ mod tests {
     use ark_std::UniformRand;
     use dcipher_network::topic::dispatcher::TopicDispatcher;
     use dcipher_network::transports::in_memory::MemoryNetwork;
+    use dcipher_network::transports::simulator::{
+        LinkConfig, NodeBehaviour, Partition, SimulatedNetwork, SimulatorConfig,
+    };
     use digest::FixedOutputReset;
     use digest::core_api::BlockSizeUser;
-    use itertools::izip;
-    use std::collections::{HashMap, VecDeque};
+    use itertools::{Itertools, izip};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+    use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
     use std::sync::Arc;
+    use std::time::Duration;
     use tokio::task::JoinSet;
     use tracing_subscriber::EnvFilter;
     use utils::dst::{NamedCurveGroup, NamedDynDigest, Rfc9380DstBuilder};
@@ -1247,6 +1336,53 @@ mod tests {
         CG::hash_to_curve_custom::<H>(b"ADKG_GENERATOR_G", &dst)
     }
 
+    #[test]
+    fn key_message_verification() {
+        type CG = ark_bn254::G1Projective;
+        type H = sha3::Sha3_256;
+
+        let g = get_generator_g::<CG, H>();
+        let h = CG::generator();
+        let z = ark_bn254::Fr::rand(&mut rand::thread_rng());
+        let z_hat = ark_bn254::Fr::rand(&mut rand::thread_rng());
+        let pok_dst = key_pok_dst::<CG, H>();
+        let mut msg = AdkgKeyMessage::<CG, H> {
+            z_j_proof: PokProof::prove(&z, &g, &(g * z), &pok_dst, &mut rand::thread_rng())
+                .unwrap(),
+            z_hat_j_proof: PokProof::prove(
+                &z_hat,
+                &h,
+                &(h * z_hat),
+                &pok_dst,
+                &mut rand::thread_rng(),
+            )
+            .unwrap(),
+            g_z_j: g * z,
+            h_z_hat_j: h * z_hat,
+        };
+
+        let encoded = bson::to_vec(&AdkgMessage::Key(msg.clone())).unwrap();
+        let g_z = verify_key_message::<CG, H>(&g, &h, &encoded).unwrap();
+        assert_eq!(g_z, Some(g * z));
+
+        // Other messages are ignored
+        let randex = AdkgMessage::<CG, H>::RandEx(AdkgRandExMessage {
+            z_j: z,
+            z_hat_j: z_hat,
+        });
+        let encoded = bson::to_vec(&randex).unwrap();
+        assert!(
+            verify_key_message::<CG, H>(&g, &h, &encoded)
+                .unwrap()
+                .is_none()
+        );
+
+        // Public key that does not match the proof
+        msg.g_z_j = g * z_hat;
+        let encoded = bson::to_vec(&AdkgMessage::Key(msg)).unwrap();
+        assert!(verify_key_message::<CG, H>(&g, &h, &encoded).is_err());
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 32)]
     #[ignore]
     async fn adkg_loop_bn254() {
@@ -1302,6 +1438,387 @@ mod tests {
         run_adkg_test::<_, sha3::Sha3_256>(2 * t, t, n, g, h, SEED).await;
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 32)]
+    async fn adkg_refresh_test_bn254() {
+        // Static configuration and long term keys
+        let t = 2;
+        let n = 3 * t + 1;
+
+        const SEED: &[u8] = b"ADKG_REFRESH_BN254_TEST_SEED";
+
+        let g = get_generator_g::<_, sha3::Sha3_256>();
+        let h = ark_bn254::G1Projective::generator();
+
+        run_adkg_refresh_test::<_, sha3::Sha3_256>(t, t, n, g, h, SEED).await;
+        run_adkg_refresh_test::<_, sha3::Sha3_256>(2 * t, t, n, g, h, SEED).await;
+    }
+
+    /// Asynchronous links that reorder and duplicate messages.
+    fn asynchronous_config(config: SimulatorConfig<PartyId>) -> SimulatorConfig<PartyId> {
+        config.with_default_link(
+            LinkConfig::asynchronous(Duration::from_millis(1), Duration::from_millis(200))
+                .with_duplicate_probability(0.1),
+        )
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn adkg_simulated_reorder_bn254() {
+        let t = 2;
+        let n = 3 * t + 1;
+        let g = get_generator_g::<_, sha3::Sha3_256>();
+        let h = ark_bn254::G1Projective::generator();
+
+        let config = asynchronous_config(SimulatorConfig::from_env("DCIPHER_SIMULATOR_SEED"));
+        run_adkg_simulated::<_, sha3::Sha3_256>(t, n, g, h, config, &[]).await;
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn adkg_simulated_drop_bn254() {
+        let t = 2;
+        let n = 3 * t + 1;
+        let g = get_generator_g::<_, sha3::Sha3_256>();
+        let h = ark_bn254::G1Projective::generator();
+        let faulty = [PartyId(n - 1), PartyId(n)];
+
+        // Half of the messages sent by t parties are lost
+        let lossy = LinkConfig::asynchronous(Duration::from_millis(1), Duration::from_millis(200))
+            .with_drop_probability(0.5);
+        let config = faulty.iter().cartesian_product(PartyId::iter_all(n)).fold(
+            asynchronous_config(SimulatorConfig::from_env("DCIPHER_SIMULATOR_SEED")),
+            |config, (&from, to)| config.with_link(from, to, lossy),
+        );
+        run_adkg_simulated::<_, sha3::Sha3_256>(t, n, g, h, config, &faulty).await;
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn adkg_simulated_partition_bn254() {
+        let t = 2;
+        let n = 3 * t + 1;
+        let g = get_generator_g::<_, sha3::Sha3_256>();
+        let h = ark_bn254::G1Projective::generator();
+
+        // Two parties are isolated from the others during the first seconds
+        let config = asynchronous_config(SimulatorConfig::from_env("DCIPHER_SIMULATOR_SEED"))
+            .with_partition(Partition {
+                nodes: HashSet::from([PartyId(1), PartyId(2)]),
+                start: Duration::ZERO,
+                end: Some(Duration::from_secs(5)),
+            });
+        run_adkg_simulated::<_, sha3::Sha3_256>(t, n, g, h, config, &[]).await;
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn adkg_simulated_crash_bn254() {
+        let t = 2;
+        let n = 3 * t + 1;
+        let g = get_generator_g::<_, sha3::Sha3_256>();
+        let h = ark_bn254::G1Projective::generator();
+        let faulty = [PartyId(n - 1), PartyId(n)];
+
+        // One party crashes before sending anything, the other one mid-execution
+        let config = asynchronous_config(SimulatorConfig::from_env("DCIPHER_SIMULATOR_SEED"))
+            .with_behaviour(PartyId(n - 1), NodeBehaviour::Crash { at: Duration::ZERO })
+            .with_behaviour(
+                PartyId(n),
+                NodeBehaviour::Crash {
+                    at: Duration::from_millis(500),
+                },
+            );
+        run_adkg_simulated::<_, sha3::Sha3_256>(t, n, g, h, config, &faulty).await;
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn adkg_simulated_same_seed_bn254() {
+        let t = 2;
+        let n = 3 * t + 1;
+        let g = get_generator_g::<_, sha3::Sha3_256>();
+        let h = ark_bn254::G1Projective::generator();
+        let faulty = [PartyId(n)];
+
+        let seed = SimulatorConfig::<PartyId>::from_env("DCIPHER_SIMULATOR_SEED").seed;
+        let config = || {
+            asynchronous_config(SimulatorConfig::new(seed))
+                .with_partition(Partition {
+                    nodes: HashSet::from([PartyId(1), PartyId(2)]),
+                    start: Duration::ZERO,
+                    end: Some(Duration::from_secs(5)),
+                })
+                .with_behaviour(
+                    PartyId(n),
+                    NodeBehaviour::Crash {
+                        at: Duration::from_millis(500),
+                    },
+                )
+        };
+
+        // The same seed must lead to the same sessions being selected, and the same group key
+        let first = run_adkg_simulated::<_, sha3::Sha3_256>(t, n, g, h, config(), &faulty).await;
+        let second = run_adkg_simulated::<_, sha3::Sha3_256>(t, n, g, h, config(), &faulty).await;
+        assert_eq!(first, second, "seed = {seed}");
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn new_adkg_instances<CG, H>(
+        t_reconstruction: usize,
+        t: usize,
+        n: usize,
+        g: CG,
+        h: CG,
+        sks: &[CG::ScalarField],
+        pks: &[CG],
+    ) -> Vec<
+        Adkg<
+            CG,
+            H,
+            Rbc4RoundsConfig,
+            HbAcss0Config<CG, sha3::Sha3_256, Rbc4RoundsConfig>,
+            AbaCrain20Config<
+                EcdhCoinToss<CG, sha3::Sha3_256>,
+                crate::adkg::types::LazyCoinKeys<CG>,
+            >,
+        >,
+    >
+    where
+        CG: NamedCurveGroup + PointSerializeCompressed + PointDeserializeCompressed + HashToCurve,
+        CG::ScalarField: FqSerialize + FqDeserialize,
+        H: Default + NamedDynDigest + FixedOutputReset + BlockSizeUser + Clone + 'static,
+    {
+        PartyId::iter_all(n)
+            .map(|i| {
+                let rbc_config = Rbc4RoundsConfig::new(i, n, t, &RetryStrategy::None);
+                let acss_config = HbAcss0Config::<_, sha3::Sha3_256, _>::new(
+                    i,
+                    sks[i.as_index()],
+                    pks.to_vec(),
+                    rbc_config.clone(),
+                    n,
+                    t,
+                    g,
+                    h,
+                    RetryStrategy::None,
+                );
+                let aba_config = AbaCrain20Config::new(i, n, t, RetryStrategy::None);
+
+                Adkg::<_, H, _, _, _>::new(
+                    i,
+                    n,
+                    t,
+                    t_reconstruction,
+                    g,
+                    h,
+                    rbc_config,
+                    acss_config,
+                    aba_config,
+                )
+            })
+            .collect()
+    }
+
+    async fn run_adkg_refresh_test<CG, H>(
+        t_reconstruction: usize,
+        t: usize,
+        n: usize,
+        g: CG,
+        h: CG,
+        seed: &[u8],
+    ) where
+        CG: NamedCurveGroup + PointSerializeCompressed + PointDeserializeCompressed + HashToCurve,
+        CG::ScalarField: FqSerialize + FqDeserialize,
+        H: Default + NamedDynDigest + FixedOutputReset + BlockSizeUser + Clone + 'static,
+    {
+        _ = tracing_subscriber::fmt()
+            .with_env_filter(
+                EnvFilter::try_from_env("ADKG_DEBUG").unwrap_or_else(|_| "warn".parse().unwrap()),
+            )
+            .try_init();
+
+        let sks: Vec<CG::ScalarField> = (1..=n)
+            .map(|_| CG::ScalarField::rand(&mut rand::thread_rng()))
+            .collect();
+        let pks: Vec<CG> = sks.iter().map(|sk| g * sk).collect();
+
+        // Execute the ADKG followed by a refresh, each with their own network
+        let mut outputs: HashMap<PartyId, AdkgOutput<CG>> = HashMap::new();
+        let refresh_seed = [seed, b"_REFRESH"].concat();
+        for refresh in [false, true] {
+            let (dispatchers, tbts): (Vec<_>, Vec<_>) =
+                MemoryNetwork::get_transports(PartyId::iter_all(n))
+                    .into_iter()
+                    .map(|t| {
+                        let mut dispatcher = TopicDispatcher::new();
+                        let tbt = dispatcher.start(t);
+                        (dispatcher, tbt)
+                    })
+                    .collect();
+            let instances = new_adkg_instances::<CG, H>(t_reconstruction, t, n, g, h, &sks, &pks);
+
+            let mut tasks = JoinSet::new();
+            for (id, transport, mut adkg) in
+                izip!(PartyId::iter_all(n), tbts.into_iter(), instances)
+            {
+                // the refresh must not reuse the randomness of the ADKG
+                let mut rng = get_rng(id, if refresh { &refresh_seed[..] } else { seed });
+                let previous = outputs.get(&id).map(|out| AdkgRefreshInput {
+                    sk: out.sk,
+                    group_pk: out.group_pk.unwrap(),
+                    node_pks: out.node_pks.clone().unwrap(),
+                });
+                tasks.spawn(async move {
+                    let out = match (refresh, previous) {
+                        (true, Some(previous)) => {
+                            adkg.refresh(&previous, &mut rng, Arc::new(transport)).await
+                        }
+                        _ => adkg.start(&mut rng, Arc::new(transport)).await,
+                    };
+                    adkg.stop().await;
+                    (id, out)
+                });
+            }
+
+            let previous_outputs = std::mem::take(&mut outputs);
+            let mut results = Vec::with_capacity(n);
+            while let Some(res) = tasks.join_next().await {
+                results.push(res.unwrap());
+            }
+
+            for d in dispatchers {
+                d.stop().await;
+            }
+
+            // Refreshes only re-randomize the coefficients up to degree t
+            if refresh && t_reconstruction != t {
+                for (_, out) in results {
+                    assert!(matches!(out, Err(AdkgError::UnsupportedRefreshThreshold)));
+                }
+                return;
+            }
+            outputs.extend(results.into_iter().map(|(id, out)| (id, out.unwrap())));
+
+            if !refresh {
+                continue;
+            }
+
+            // The group public key is preserved, while the shares have changed
+            let group_pk = previous_outputs[&PartyId(1)].group_pk.unwrap();
+            for id in PartyId::iter_all(n) {
+                let (old, new) = (&previous_outputs[&id], &outputs[&id]);
+                assert_eq!(new.group_pk.unwrap(), group_pk);
+                assert_ne!(new.sk, old.sk);
+                assert_eq!(new.node_pks.as_ref().unwrap()[id.as_index()], g * new.sk);
+            }
+
+            // Any t_reconstruction + 1 new shares still reconstruct the group secret
+            let points: Vec<(u64, CG::ScalarField)> = PartyId::iter_all(n)
+                .rev()
+                .take(t_reconstruction + 1)
+                .map(|id| (id.into(), outputs[&id].sk))
+                .collect();
+            let s: CG::ScalarField = lagrange_interpolate_at::<CG>(&points, 0);
+            assert_eq!((g * s).into_affine(), group_pk.into_affine());
+        }
+    }
+
+    /// Execute the ADKG over a simulated network and return the group public key and the sessions
+    /// used by the parties that are not `faulty`. The long term keys and the rngs of the parties
+    /// are derived from the seed of the simulator.
+    async fn run_adkg_simulated<CG, H>(
+        t: usize,
+        n: usize,
+        g: CG,
+        h: CG,
+        config: SimulatorConfig<PartyId>,
+        faulty: &[PartyId],
+    ) -> (CG::Affine, Vec<SessionId>)
+    where
+        CG: NamedCurveGroup + PointSerializeCompressed + PointDeserializeCompressed + HashToCurve,
+        CG::ScalarField: FqSerialize + FqDeserialize,
+        H: Default + NamedDynDigest + FixedOutputReset + BlockSizeUser + Clone + 'static,
+    {
+        _ = tracing_subscriber::fmt()
+            .with_env_filter(
+                EnvFilter::try_from_env("ADKG_DEBUG").unwrap_or_else(|_| "warn".parse().unwrap()),
+            )
+            .try_init();
+
+        let seed = config.seed;
+        let mut rng = ChaCha20Rng::seed_from_u64(seed);
+        let sks: Vec<CG::ScalarField> = (1..=n).map(|_| CG::ScalarField::rand(&mut rng)).collect();
+        let pks: Vec<CG> = sks.iter().map(|sk| g * sk).collect();
+
+        // Setup the networking
+        let (handle, transports) = SimulatedNetwork::get_transports(PartyId::iter_all(n), config);
+        let (dispatchers, tbts): (Vec<_>, Vec<_>) = transports
+            .into_iter()
+            .map(|t| {
+                let mut dispatcher = TopicDispatcher::new();
+                let tbt = dispatcher.start(t);
+                (dispatcher, tbt)
+            })
+            .collect();
+        let instances = new_adkg_instances::<CG, H>(t, t, n, g, h, &sks, &pks);
+
+        // Execute each node, faulty nodes may never terminate
+        let mut tasks = JoinSet::new();
+        let mut faulty_tasks = JoinSet::new();
+        for (id, transport, mut adkg) in izip!(PartyId::iter_all(n), tbts, instances) {
+            let mut rng = get_rng(id, &seed.to_be_bytes());
+            let task = async move {
+                let out = adkg.start(&mut rng, Arc::new(transport)).await;
+                (id, out, adkg)
+            };
+
+            if faulty.contains(&id) {
+                faulty_tasks.spawn(task);
+            } else {
+                tasks.spawn(task);
+            }
+        }
+
+        let mut shares = BTreeMap::new();
+        let mut adkgs = vec![];
+        while let Some(res) = tasks.join_next().await {
+            let (id, out, adkg) = res.unwrap();
+            shares.insert(
+                id,
+                out.unwrap_or_else(|e| panic!("adkg failed with seed = {seed}: {e:?}")),
+            );
+            adkgs.push(adkg);
+        }
+
+        // Stop the nodes once every honest node obtained its share
+        faulty_tasks.abort_all();
+        for adkg in adkgs {
+            adkg.stop().await;
+        }
+        for d in dispatchers {
+            d.stop().await;
+        }
+        handle.stop().await;
+
+        // Verify that each node has the same public key
+        let (_, first) = shares.first_key_value().unwrap();
+        let group_pk = first.group_pk.expect("failed to obtain group pk");
+        assert!(
+            shares
+                .values()
+                .all(|out| out.group_pk == Some(group_pk)
+                    && out.used_sessions == first.used_sessions),
+            "seed = {seed}"
+        );
+
+        // Verify that the group public key matches the group secret
+        let points: Vec<(u64, CG::ScalarField)> =
+            shares.iter().map(|(id, out)| (id.into(), out.sk)).collect();
+        let s: CG::ScalarField = lagrange_interpolate_at::<CG>(&points[0..=t], 0);
+        assert_eq!(
+            (g * s).into_affine(),
+            group_pk.into_affine(),
+            "seed = {seed}"
+        );
+
+        (group_pk.into_affine(), first.used_sessions.clone())
+    }
+
     async fn run_adkg_test<CG, H>(
         t_reconstruction: usize,
         t: usize,
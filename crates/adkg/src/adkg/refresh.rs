@@ -0,0 +1,214 @@
+//! Proactive refresh of the shares output by a previous ADKG execution.
+//!
+//! Each node deals a sharing of zero through the ACSS, and the nodes agree on a common set of
+//! completed sessions with the same RBC / ABA phases as the ADKG. The refreshed share of a node
+//! is its previous share plus the sum of the zero-shares it obtained from the selected sessions,
+//! which leaves the group secret, and therefore the group public key, unchanged.
+//!
+//! The zero-sharings are dealt as Pedersen sharings with constant terms s = r = 0, i.e., their
+//! first commitment is the identity, which lets every node reject sessions that do not share zero
+//! without any additional proof. Since the ACSS outputs degree t sharings, the refresh can only
+//! re-randomize the coefficients of degree up to t of the shared polynomial. With a reconstruction
+//! threshold greater than t, the higher coefficients would be left untouched, allowing old and new
+//! shares to be combined, hence refreshes are restricted to t_reconstruction == t.
+
+use super::{ADKG_TOPIC, Adkg, AdkgError, AdkgOutput, ShareWithPoly};
+use crate::aba::AbaConfig;
+use crate::adkg::types::AbaCrainInput;
+use crate::helpers::{PartyId, SessionId, eval_poly, lagrange_points_interpolate_at};
+use crate::rand::{AdkgRng, AdkgRngType};
+use crate::rbc::ReliableBroadcastConfig;
+use crate::vss::acss::AcssConfig;
+use crate::vss::acss::hbacss0::{Hbacss0Input, PedersenSecret};
+use ark_ec::CurveGroup;
+use ark_ff::Zero;
+use ark_std::UniformRand;
+use dcipher_network::Transport;
+use dcipher_network::topic::TopicBasedTransport;
+use digest::core_api::BlockSizeUser;
+use digest::{DynDigest, FixedOutputReset};
+use std::collections::HashSet;
+use std::sync::Arc;
+use tracing::{info, warn};
+use utils::serialize::fq::{FqDeserialize, FqSerialize};
+use utils::serialize::point::{PointDeserializeCompressed, PointSerializeCompressed};
+
+/// Output of a previous ADKG (or refresh) execution used as the input of a refresh.
+#[derive(Clone)]
+pub struct AdkgRefreshInput<CG: CurveGroup> {
+    /// The current secret share of the node.
+    pub sk: CG::ScalarField,
+
+    /// The group public key, which must be preserved by the refresh.
+    pub group_pk: CG,
+
+    /// The current public keys of each of the nodes, ordered by identifier.
+    pub node_pks: Vec<CG>,
+}
+
+impl<CG, H, RBCConfig, ACSSConfig, ABAConfig> Adkg<CG, H, RBCConfig, ACSSConfig, ABAConfig>
+where
+    CG: CurveGroup
+        + PointSerializeCompressed
+        + PointDeserializeCompressed
+        + utils::hash_to_curve::HashToCurve,
+    CG::ScalarField: FqSerialize + FqDeserialize,
+    H: Default + DynDigest + FixedOutputReset + BlockSizeUser + Clone + 'static,
+    RBCConfig: ReliableBroadcastConfig<'static, PartyId>,
+    ACSSConfig: AcssConfig<'static, CG, PartyId, Input = Hbacss0Input<CG::ScalarField>>,
+    ACSSConfig::Output: Into<ShareWithPoly<CG>>,
+    ABAConfig: AbaConfig<'static, PartyId, Input = AbaCrainInput<CG>>,
+{
+    /// Refresh the shares of a previous ADKG output immediately. The returned output contains
+    /// a new secret share and new node public keys for the same group public key.
+    ///
+    /// Like [`Adkg::start`], the sub-protocols keep running in the background until [`Adkg::stop`]
+    /// is called.
+    pub async fn refresh<T>(
+        &mut self,
+        previous: &AdkgRefreshInput<CG>,
+        rng: &mut impl AdkgRng,
+        transport: Arc<T>,
+    ) -> Result<AdkgOutput<CG>, AdkgError>
+    where
+        T: TopicBasedTransport<Identity = PartyId>,
+    {
+        if self.t_reconstruction != self.t {
+            Err(AdkgError::UnsupportedRefreshThreshold)?
+        }
+        if previous.node_pks.len() != self.n || self.g * previous.sk != previous.node_pks[self.id] {
+            Err(AdkgError::InvalidRefreshInput)?
+        }
+
+        let state = self.shared_state.clone();
+        let mut adkg_transport = transport
+            .get_transport_for(ADKG_TOPIC)
+            .ok_or(AdkgError::TransportInit)?;
+        let adkg_sender = adkg_transport.sender().ok_or(AdkgError::TransportInit)?;
+        let mut adkg_receiver = adkg_transport
+            .receiver_stream()
+            .ok_or(AdkgError::TransportInit)?;
+
+        // The feldman secret is still required to be random as it is used by the ABA coin,
+        // while the pedersen secret is a sharing of zero with no randomness in the constant term.
+        let mut acss_rng = rng
+            .get(AdkgRngType::AcssSecret)
+            .map_err(|e| AdkgError::Rng(e.into(), "failed to get acss secret rng"))?;
+        let s = Hbacss0Input {
+            feld: CG::ScalarField::rand(&mut acss_rng),
+            peds: vec![PedersenSecret {
+                s: CG::ScalarField::zero(),
+                r: CG::ScalarField::zero(),
+            }],
+        };
+
+        let final_sessions = self
+            .agree_on_sessions(std::future::ready(()), s, rng, transport)
+            .await?;
+
+        // Wait until the ACSS outputs of all the final sessions are available
+        let final_sessions_set: HashSet<SessionId> = final_sessions.iter().copied().collect();
+        while !state
+            .completed_acss_outputs
+            .is_superset(&final_sessions_set)
+        {
+            state.completed_acss_outputs.wait().await;
+        }
+
+        // Only keep the sessions that shared zero. The public polynomials are identical for all
+        // honest nodes, hence, so is the list of used sessions.
+        let zero_sharings: Vec<_> = state
+            .completed_acss_outputs
+            .filter_outputs(final_sessions.iter())
+            .filter_map(|(sid, out)| {
+                let (Some(share), Some(poly)) = (out.shares.first(), out.public_polys.first())
+                else {
+                    warn!(%sid, "Ignoring ACSS output without pedersen sharing");
+                    return None;
+                };
+
+                if poly.0.first().is_none_or(|v_0| !v_0.is_zero()) {
+                    warn!(%sid, "Ignoring ACSS output that does not share zero");
+                    return None;
+                }
+
+                Some((sid, share.clone(), poly.0.clone()))
+            })
+            .collect();
+        if zero_sharings.is_empty() {
+            Err(AdkgError::Refresh("no valid zero sharing"))?
+        }
+
+        // [[delta]]_i, [[delta_hat]]_i: the sum of our shares of zero
+        let delta_i: CG::ScalarField = zero_sharings.iter().map(|(_, share, _)| share.si).sum();
+        let delta_hat_i: CG::ScalarField = zero_sharings.iter().map(|(_, share, _)| share.ri).sum();
+
+        // Combined public polynomial, and the commitments to the shares of each party
+        let degree = zero_sharings
+            .iter()
+            .map(|(_, _, poly)| poly.len())
+            .max()
+            .unwrap_or_default();
+        let public_poly: Vec<CG> = (0..degree)
+            .map(|k| {
+                zero_sharings
+                    .iter()
+                    .filter_map(|(_, _, poly)| poly.get(k))
+                    .sum()
+            })
+            .collect();
+        let ped_commits: Vec<CG> = PartyId::iter_all(self.n)
+            .map(|j| eval_poly(&u64::from(j).into(), &public_poly))
+            .collect();
+
+        // Obtain g^{delta_j} for each of the parties using the same proofs as the key derivation
+        let (g_delta_0, g_delta_js) = self
+            .key_derivation_phase::<T::Transport>(
+                &delta_i,
+                &delta_hat_i,
+                &ped_commits,
+                [],
+                &adkg_sender,
+                &mut adkg_receiver,
+                rng,
+            )
+            .await?;
+        if !g_delta_0.is_zero() {
+            Err(AdkgError::Refresh(
+                "combined sharing is not a sharing of zero",
+            ))?
+        }
+
+        // New shares & public keys
+        let sk = previous.sk + delta_i;
+        let node_pks: Vec<CG> = previous
+            .node_pks
+            .iter()
+            .zip(g_delta_js)
+            .map(|(pk_j, g_delta_j)| *pk_j + g_delta_j)
+            .collect();
+
+        // Make sure that the new public keys still interpolate to the group public key
+        let points: Vec<_> = PartyId::iter_all(self.n)
+            .zip(node_pks.iter())
+            .take(self.t_reconstruction + 1)
+            .map(|(j, pk_j)| (j.into(), *pk_j))
+            .collect();
+        if lagrange_points_interpolate_at(&points, 0) != previous.group_pk {
+            Err(AdkgError::Refresh("group public key is not preserved"))?
+        }
+
+        let used_sessions: Vec<_> = zero_sharings.into_iter().map(|(sid, _, _)| sid).collect();
+        info!(
+            ?used_sessions,
+            "Node `{}` successfully refreshed its share", self.id
+        );
+
+        Ok(AdkgOutput {
+            sk,
+            used_sessions,
+            group_pk: Some(previous.group_pk),
+            node_pks: Some(node_pks),
+        })
+    }
+}
@@ -0,0 +1,725 @@
+//! Byzantine fault injection for the test suite.
+//!
+//! Parties are connected through a [`SimulatedNetwork`], where a malicious party executes the
+//! honest protocol with a [`NodeBehaviour::Equivocate`] behaviour that hands every message it sends
+//! on a topic to a [`Deviation`], once per recipient. Deviations decode the protocol messages they
+//! target, and either tamper with them, or send different messages to different recipients.
+//! Honest parties may use a [`Recorder`] in order to observe the messages they send.
+use crate::aba::crain20::{
+    self, AbaCrain20Config, AbaInput, CoinKeys, EcdhCoinToss, messages::AbaMessage,
+};
+use crate::aba::{Aba, AbaConfig, Estimate};
+use crate::adkg::{Adkg, AdkgOutput};
+use crate::helpers::{PartyId, SessionId, lagrange_interpolate_at, u64_from_usize};
+use crate::network::RetryStrategy;
+use crate::rand::get_rng;
+use crate::rbc::r4::{
+    self, Rbc4RoundsConfig,
+    messages::{Message, Propose},
+};
+use crate::rbc::{AlwaysTruePredicate, ReliableBroadcast, ReliableBroadcastConfig};
+use crate::vss::acss::hbacss0::{
+    self, AcssBroadcastMessage, AcssMessage, HbAcss0Config, Hbacss0Input, PedersenSecret,
+};
+use crate::vss::acss::{Acss, AcssConfig};
+use ark_ec::{CurveGroup, PrimeGroup};
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::{DenseUVPolynomial, Polynomial};
+use ark_std::UniformRand;
+use dcipher_network::topic::TopicBasedTransport;
+use dcipher_network::topic::dispatcher::{
+    TopicDispatcher, decode_topic_message, encode_topic_message,
+};
+use dcipher_network::transports::simulator::{
+    NodeBehaviour, SimulatedNetwork, SimulatorConfig, SimulatorHandle,
+};
+use itertools::Itertools;
+use rand::rngs::OsRng;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::collections::{BTreeMap, HashMap};
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
+use utils::hash_to_curve::HashToCurve;
+use utils::serialize::point::{PointDeserializeCompressed, PointSerializeCompressed};
+
+/// A deviation from the honest protocol, applied to the messages sent by a party.
+pub(crate) trait Deviation: Send + Sync + 'static {
+    /// Returns the message sent to party `to` on `topic` in place of `m`, or `None` to omit it.
+    fn deviate(&self, topic: &str, to: PartyId, m: &[u8]) -> Option<Vec<u8>>;
+}
+
+/// Simulator behaviour of a party that applies `deviation` to the messages it sends through a
+/// [`TopicDispatcher`].
+fn behaviour(deviation: Arc<dyn Deviation>) -> NodeBehaviour<PartyId> {
+    NodeBehaviour::Equivocate(Arc::new(move |to, m| {
+        let Some((topic, content)) = decode_topic_message(m) else {
+            return Some(m.to_vec());
+        };
+
+        let content = deviation.deviate(&String::from_utf8_lossy(&topic), to, &content)?;
+        Some(encode_topic_message(topic, content))
+    }))
+}
+
+/// Records the distinct messages sent by an honest party.
+#[derive(Default)]
+pub(crate) struct Recorder {
+    sent: Mutex<Vec<(String, Vec<u8>)>>,
+}
+
+impl Recorder {
+    /// Count the messages sent on topics ending with `topic` that satisfy `f`.
+    pub(crate) fn count<M>(&self, topic: &str, f: impl Fn(&M) -> bool) -> usize
+    where
+        M: DeserializeOwned,
+    {
+        self.sent
+            .lock()
+            .expect("an unpoisoned mutex")
+            .iter()
+            .filter(|(t, _)| t.ends_with(topic))
+            .filter_map(|(_, m)| bson::from_slice(m).ok())
+            .filter(|m| f(m))
+            .count()
+    }
+}
+
+impl Deviation for Recorder {
+    fn deviate(&self, topic: &str, _to: PartyId, m: &[u8]) -> Option<Vec<u8>> {
+        // The same message is observed once per recipient
+        let mut sent = self.sent.lock().expect("an unpoisoned mutex");
+        let msg = (topic.to_owned(), m.to_vec());
+        if !sent.contains(&msg) {
+            sent.push(msg);
+        }
+
+        Some(m.to_vec())
+    }
+}
+
+/// A lying RBC leader that proposes `alt` instead of its message to the `targets`.
+pub(crate) struct EquivocatingRbcLeader {
+    pub(crate) alt: Vec<u8>,
+    pub(crate) targets: Vec<PartyId>,
+}
+
+impl Deviation for EquivocatingRbcLeader {
+    fn deviate(&self, topic: &str, to: PartyId, m: &[u8]) -> Option<Vec<u8>> {
+        if !topic.ends_with(r4::TOPIC) || !self.targets.contains(&to) {
+            return Some(m.to_vec());
+        }
+
+        Some(rewrite(m, |msg| match msg {
+            Message::Propose(_) => Message::Propose(Propose {
+                m: self.alt.clone(),
+            }),
+            msg => msg,
+        }))
+    }
+}
+
+/// A malicious hbacss0 dealer whose dealing contains shares that cannot be decrypted by the
+/// `victims`.
+pub(crate) struct CorruptAcssShares<CG> {
+    victims: Vec<PartyId>,
+    _cg: PhantomData<fn() -> CG>,
+}
+
+impl<CG> CorruptAcssShares<CG> {
+    pub(crate) fn new(victims: Vec<PartyId>) -> Self {
+        Self {
+            victims,
+            _cg: PhantomData,
+        }
+    }
+}
+
+impl<CG> Deviation for CorruptAcssShares<CG>
+where
+    CG: CurveGroup + PointSerializeCompressed + PointDeserializeCompressed,
+{
+    fn deviate(&self, topic: &str, _to: PartyId, m: &[u8]) -> Option<Vec<u8>> {
+        Some(rewrite_dealing(
+            topic,
+            m,
+            |dealing: &mut AcssBroadcastMessage<CG>| {
+                // Replace the ciphertext of each victim by the ciphertext of a non-victim
+                let cts = &mut dealing.enc_shares.inner.cts;
+                let Some(valid) =
+                    (0..cts.len()).find(|&i| !self.victims.contains(&PartyId::from_index(i)))
+                else {
+                    return;
+                };
+
+                for victim in &self.victims {
+                    cts[victim.as_index()] = cts[valid].clone();
+                }
+            },
+        ))
+    }
+}
+
+/// A malicious hbacss0 dealer whose Feldman commitment does not match the shares it deals.
+pub(crate) struct InconsistentAcssCommitment<CG>(PhantomData<fn() -> CG>);
+
+impl<CG> Default for InconsistentAcssCommitment<CG> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<CG> Deviation for InconsistentAcssCommitment<CG>
+where
+    CG: CurveGroup + PointSerializeCompressed + PointDeserializeCompressed,
+{
+    fn deviate(&self, topic: &str, _to: PartyId, m: &[u8]) -> Option<Vec<u8>> {
+        Some(rewrite_dealing(
+            topic,
+            m,
+            |dealing: &mut AcssBroadcastMessage<CG>| {
+                let public_poly = &mut dealing.feld_public_poly.0;
+                public_poly[0] = public_poly[0] + public_poly[0];
+            },
+        ))
+    }
+}
+
+/// An ABA participant that votes for the opposite of its estimates towards the `targets`.
+pub(crate) struct EquivocatingAbaVoter {
+    pub(crate) targets: Vec<PartyId>,
+}
+
+impl Deviation for EquivocatingAbaVoter {
+    fn deviate(&self, topic: &str, to: PartyId, m: &[u8]) -> Option<Vec<u8>> {
+        if !topic.ends_with(crain20::TOPIC) || !self.targets.contains(&to) {
+            return Some(m.to_vec());
+        }
+
+        let flip = |estimate| match estimate {
+            Estimate::Zero => Estimate::One,
+            Estimate::One => Estimate::Zero,
+            Estimate::Bot => Estimate::Bot,
+        };
+        Some(rewrite(m, |msg| match msg {
+            AbaMessage::Estimate(mut est) => {
+                est.estimate = flip(est.estimate);
+                AbaMessage::Estimate(est)
+            }
+            AbaMessage::Auxiliary(mut aux) => {
+                aux.estimate = flip(aux.estimate);
+                AbaMessage::Auxiliary(aux)
+            }
+            msg => msg,
+        }))
+    }
+}
+
+/// Decode a message of type `M`, and re-encode the output of `f`. Messages of other types are
+/// returned as is.
+fn rewrite<M>(m: &[u8], f: impl FnOnce(M) -> M) -> Vec<u8>
+where
+    M: Serialize + DeserializeOwned,
+{
+    bson::from_slice(m)
+        .ok()
+        .and_then(|msg| bson::to_vec(&f(msg)).ok())
+        .unwrap_or_else(|| m.to_vec())
+}
+
+/// Tamper with the dealing proposed through the RBC of hbacss0.
+fn rewrite_dealing<CG>(
+    topic: &str,
+    m: &[u8],
+    f: impl FnOnce(&mut AcssBroadcastMessage<CG>),
+) -> Vec<u8>
+where
+    CG: CurveGroup + PointSerializeCompressed + PointDeserializeCompressed,
+{
+    if !topic.ends_with(&format!("{}/{}", hbacss0::TOPIC, r4::TOPIC)) {
+        return m.to_vec();
+    }
+
+    rewrite(m, |msg| match msg {
+        Message::Propose(Propose { m }) => {
+            let m = bson::from_slice::<AcssBroadcastMessage<CG>>(&m)
+                .ok()
+                .and_then(|mut dealing| {
+                    f(&mut dealing);
+                    bson::to_vec(&dealing).ok()
+                })
+                .unwrap_or(m);
+            Message::Propose(Propose { m })
+        }
+        msg => msg,
+    })
+}
+
+type G = ark_bn254::G1Projective;
+type Fr = ark_bn254::Fr;
+
+/// Time after which honest parties are considered stuck. The tests use a paused clock, hence
+/// the timeout only elapses once no more progress can be made.
+const STUCK: Duration = Duration::from_secs(600);
+
+/// Connect the parties through a simulated network, where the `byzantine` party deviates from
+/// the protocol according to `behaviour`, and the others record the messages they send.
+#[allow(clippy::type_complexity)]
+fn setup_network(
+    n: usize,
+    byzantine: PartyId,
+    behaviour: NodeBehaviour<PartyId>,
+) -> (
+    SimulatorHandle,
+    Vec<TopicDispatcher>,
+    Vec<impl TopicBasedTransport<Identity = PartyId> + Send + Sync>,
+    HashMap<PartyId, Arc<Recorder>>,
+) {
+    let mut recorders = HashMap::new();
+    let mut config =
+        SimulatorConfig::from_env("DCIPHER_SIMULATOR_SEED").with_behaviour(byzantine, behaviour);
+    for i in PartyId::iter_all(n).filter(|i| *i != byzantine) {
+        let recorder = Arc::new(Recorder::default());
+        recorders.insert(i, recorder.clone());
+        config = config.with_behaviour(i, self::behaviour(recorder));
+    }
+
+    let (handle, transports) = SimulatedNetwork::get_transports(PartyId::iter_all(n), config);
+    let (dispatchers, transports): (Vec<_>, Vec<_>) = transports
+        .into_iter()
+        .map(|t| {
+            let mut dispatcher = TopicDispatcher::new();
+            let tbt = dispatcher.start(t);
+            (dispatcher, tbt)
+        })
+        .unzip();
+
+    (handle, dispatchers, transports, recorders)
+}
+
+/// Execute an RBC where party 1 is the leader, and return the output of the other parties, if
+/// any.
+async fn run_rbc(
+    n: usize,
+    t: usize,
+    m: &'static [u8],
+    leader: Arc<dyn Deviation>,
+) -> Vec<Option<Vec<u8>>> {
+    let (_handle, _dispatchers, transports, _) = setup_network(n, PartyId(1), behaviour(leader));
+
+    let mut leader_task = None;
+    let mut tasks = JoinSet::new();
+    for (i, transport) in PartyId::iter_all(n).zip(transports) {
+        let broadcast = Rbc4RoundsConfig::new(i, n, t, &RetryStrategy::None)
+            .new_instance_with_prefix("byzantine_rbc".to_owned(), Arc::new(transport))
+            .expect("failed to create rbc instance");
+
+        if i == PartyId(1) {
+            leader_task = Some(tokio::spawn(async move {
+                broadcast.start(m, CancellationToken::new()).await
+            }));
+        } else {
+            tasks.spawn(async move {
+                let res =
+                    broadcast.listen(&AlwaysTruePredicate, PartyId(1), CancellationToken::new());
+                tokio::time::timeout(STUCK, res)
+                    .await
+                    .ok()
+                    .map(|res| res.expect("rbc failed"))
+            });
+        }
+    }
+
+    let outputs = tasks.join_all().await;
+    leader_task.unwrap().abort();
+    outputs
+}
+
+#[tokio::test(start_paused = true)]
+async fn rbc_lying_leader_cannot_prevent_delivery() {
+    let t = 2;
+    let n = 3 * t + 1;
+    let m = b"Hello World!";
+
+    // The leader proposes a different message to t parties
+    let leader = EquivocatingRbcLeader {
+        alt: b"Goodbye World!".to_vec(),
+        targets: PartyId::iter_all(n).skip(n - t).collect(),
+    };
+    let outputs = run_rbc(n, t, m, Arc::new(leader)).await;
+
+    // Every honest party delivers the message proposed to the majority
+    assert!(
+        outputs
+            .iter()
+            .all(|out| out.as_deref() == Some(m.as_slice()))
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn rbc_equivocating_leader_preserves_agreement() {
+    let t = 2;
+    let n = 3 * t + 1;
+    let m = b"Hello World!";
+
+    // The leader proposes a different message to half of the parties
+    let leader = EquivocatingRbcLeader {
+        alt: b"Goodbye World!".to_vec(),
+        targets: PartyId::iter_all(n).skip(n / 2).collect(),
+    };
+    let outputs = run_rbc(n, t, m, Arc::new(leader)).await;
+
+    // Honest parties that deliver a message deliver the same message
+    assert!(outputs.iter().flatten().all_equal());
+}
+
+/// Generate the keys used by the ECDH common coin.
+fn coin_keys(n: usize, t: usize) -> Vec<CoinKeys<EcdhCoinToss<G, sha3::Sha3_256>>> {
+    let g = G::generator();
+    let p = DensePolynomial::<Fr>::rand(t, &mut OsRng);
+    let vk = g * p.evaluate(&Fr::from(0u64));
+    let sks: BTreeMap<PartyId, Fr> = PartyId::iter_all(n)
+        .map(|i| (i, p.evaluate(&u64_from_usize(i.as_usize()).into())))
+        .collect();
+    let vks: BTreeMap<PartyId, G> = sks.iter().map(|(&i, sk)| (i, g * sk)).collect();
+
+    sks.values()
+        .map(|&sk| CoinKeys::new(t, sk, g, vk, vks.clone()))
+        .collect()
+}
+
+#[tokio::test(start_paused = true)]
+async fn aba_equivocating_voter_preserves_validity_and_agreement() {
+    let t = 2;
+    let n = 3 * t + 1;
+    let byzantine = PartyId(n);
+
+    // The last party votes both ways, while every honest party proposes One
+    let voter = EquivocatingAbaVoter {
+        targets: PartyId::iter_all(n).take(n / 2).collect(),
+    };
+    let (_handle, _dispatchers, transports, _) =
+        setup_network(n, byzantine, behaviour(Arc::new(voter)));
+
+    let cancel = CancellationToken::new();
+    let mut byzantine_task = None;
+    let mut tasks = JoinSet::new();
+    for (i, transport, ck) in itertools::izip!(PartyId::iter_all(n), transports, coin_keys(n, t)) {
+        let aba = AbaCrain20Config::<EcdhCoinToss<G, sha3::Sha3_256>, _>::new(
+            i,
+            n,
+            t,
+            RetryStrategy::None,
+        )
+        .new_instance(SessionId::const_from(1), Arc::new(transport))
+        .expect("failed to create aba instance");
+
+        let (coin_keys_sender, coin_keys_receiver) = oneshot::channel();
+        coin_keys_sender
+            .send(ck)
+            .unwrap_or_else(|_| panic!("failed to send coin keys"));
+        let (isender, ireceiver) = oneshot::channel();
+        let (osender, oreceiver) = oneshot::channel();
+        let v = if i == byzantine {
+            Estimate::Zero
+        } else {
+            Estimate::One
+        };
+        isender
+            .send(AbaInput {
+                v,
+                coin_keys_receiver,
+            })
+            .unwrap_or_else(|_| panic!("failed to send estimate"));
+
+        let aba_task = tokio::spawn({
+            let cancel = cancel.clone();
+            async move { aba.propose(ireceiver, osender, cancel, &mut OsRng).await }
+        });
+        if i == byzantine {
+            byzantine_task = Some(aba_task);
+        } else {
+            tasks.spawn(async move {
+                tokio::time::timeout(STUCK, oreceiver)
+                    .await
+                    .expect("honest party did not decide")
+                    .expect("aba task dropped oneshot sender")
+            });
+        }
+    }
+
+    let estimates = tasks.join_all().await;
+    cancel.cancel();
+    byzantine_task.unwrap().abort();
+
+    assert!(estimates.iter().all(|est| *est == Estimate::One));
+}
+
+/// Execute hbacss0 where party 1 is the dealer, and return the output of the other parties, if
+/// any, along with the messages they sent.
+async fn run_hbacss0(
+    n: usize,
+    t: usize,
+    dealer: Arc<dyn Deviation>,
+) -> (Vec<(PartyId, Option<Fr>)>, HashMap<PartyId, Arc<Recorder>>) {
+    let g = G::generator();
+    let h = G::hash_to_curve(b"PEDERSEN_H", b"TEST_DST_PEDERSEN_H");
+    let sks: Vec<Fr> = (0..n).map(|_| Fr::rand(&mut OsRng)).collect();
+    let pks: Vec<G> = sks.iter().map(|sk| g * sk).collect();
+    let (_handle, _dispatchers, transports, recorders) =
+        setup_network(n, PartyId(1), behaviour(dealer));
+
+    let cancel = CancellationToken::new();
+    let mut acss_tasks = JoinSet::new();
+    let mut outputs = vec![];
+    for (i, transport) in PartyId::iter_all(n).zip(transports) {
+        let rbc_config = Rbc4RoundsConfig::new(i, n, t, &RetryStrategy::None);
+        let acss_config = HbAcss0Config::<_, sha3::Sha3_256, _>::new(
+            i,
+            sks[i],
+            pks.clone(),
+            rbc_config,
+            n,
+            t,
+            g,
+            h,
+            RetryStrategy::None,
+        );
+
+        let (sender, receiver) = oneshot::channel();
+        let cancel = cancel.clone();
+        acss_tasks.spawn(async move {
+            let acss = acss_config
+                .new_instance_with_prefix("byzantine_hbacss0".to_owned(), Arc::new(transport))
+                .expect("failed to create acss instance");
+            if i == PartyId(1) {
+                let input = Hbacss0Input {
+                    feld: Fr::rand(&mut OsRng),
+                    peds: vec![PedersenSecret {
+                        s: Fr::rand(&mut OsRng),
+                        r: Fr::rand(&mut OsRng),
+                    }],
+                };
+                _ = acss.deal(input, cancel, sender, &mut OsRng).await;
+            } else {
+                _ = acss.get_share(PartyId(1), cancel, sender, &mut OsRng).await;
+            }
+        });
+
+        if i != PartyId(1) {
+            outputs.push((i, receiver));
+        }
+    }
+
+    // Wait until honest parties obtain their shares before stopping, since parties keep
+    // helping others recover their shares until then.
+    let mut shares = vec![];
+    for (i, receiver) in outputs {
+        let share = tokio::time::timeout(STUCK, receiver)
+            .await
+            .ok()
+            .map(|out| out.expect("acss task dropped oneshot sender").feld_share);
+        shares.push((i, share));
+    }
+    cancel.cancel();
+    acss_tasks.abort_all();
+
+    (shares, recorders)
+}
+
+#[tokio::test(start_paused = true)]
+async fn hbacss0_corrupt_shares_are_recovered() {
+    let t = 2;
+    let n = 3 * t + 1;
+    let victim = PartyId(2);
+
+    let dealer = CorruptAcssShares::<G>::new(vec![victim]);
+    let (shares, recorders) = run_hbacss0(n, t, Arc::new(dealer)).await;
+
+    // Each honest party, including the victim, outputs a share
+    let shares: Vec<(u64, Fr)> = shares
+        .into_iter()
+        .map(|(i, share)| {
+            (
+                i.into(),
+                share.expect("honest party did not output a share"),
+            )
+        })
+        .collect();
+
+    // The shares are consistent
+    let s = lagrange_interpolate_at::<G>(&shares[..=t], 0);
+    let s2 = lagrange_interpolate_at::<G>(&shares[shares.len() - t - 1..], 0);
+    assert_eq!(s, s2);
+
+    // The victim implicated the dealer, and the other parties helped it to recover its share
+    let is_implicate = |m: &AcssMessage| matches!(m, AcssMessage::Implicate(_));
+    let is_recovery = |m: &AcssMessage| matches!(m, AcssMessage::ShareRecovery(_));
+    assert_eq!(recorders[&victim].count(hbacss0::TOPIC, is_implicate), 1);
+    let recoveries: usize = recorders
+        .iter()
+        .filter(|(i, _)| **i != victim)
+        .map(|(_, r)| r.count(hbacss0::TOPIC, is_recovery))
+        .sum();
+    assert!(recoveries > t);
+}
+
+#[tokio::test(start_paused = true)]
+async fn hbacss0_inconsistent_commitment_is_rejected() {
+    let t = 2;
+    let n = 3 * t + 1;
+
+    let dealer = InconsistentAcssCommitment::<G>::default();
+    let (shares, _) = run_hbacss0(n, t, Arc::new(dealer)).await;
+
+    // No honest party accepts the dealing
+    assert!(shares.iter().all(|(_, share)| share.is_none()));
+}
+
+/// Execute the ADKG where the last party deviates from the protocol according to `byzantine`, and
+/// return the output of the honest parties.
+async fn run_adkg(
+    n: usize,
+    t: usize,
+    byzantine: NodeBehaviour<PartyId>,
+) -> Vec<(PartyId, AdkgOutput<G>)> {
+    let g = G::hash_to_curve(b"ADKG_GENERATOR_G", b"BYZANTINE_TEST_DST");
+    let h = G::generator();
+    let sks: Vec<Fr> = (0..n).map(|_| Fr::rand(&mut OsRng)).collect();
+    let pks: Vec<G> = sks.iter().map(|sk| g * sk).collect();
+    let (handle, dispatchers, transports, _) = setup_network(n, PartyId(n), byzantine);
+
+    let mut byzantine_task = None;
+    let mut tasks = JoinSet::new();
+    for (i, transport) in PartyId::iter_all(n).zip(transports) {
+        let rbc_config = Rbc4RoundsConfig::new(i, n, t, &RetryStrategy::None);
+        let acss_config = HbAcss0Config::<_, sha3::Sha3_256, _>::new(
+            i,
+            sks[i],
+            pks.clone(),
+            rbc_config.clone(),
+            n,
+            t,
+            g,
+            h,
+            RetryStrategy::None,
+        );
+        let aba_config = AbaCrain20Config::<EcdhCoinToss<_, sha3::Sha3_256>, _>::new(
+            i,
+            n,
+            t,
+            RetryStrategy::None,
+        );
+        let mut adkg = Adkg::<_, sha3::Sha3_256, _, _, _>::new(
+            i,
+            n,
+            t,
+            t,
+            g,
+            h,
+            rbc_config,
+            acss_config,
+            aba_config,
+        );
+
+        let mut rng = get_rng(i, b"BYZANTINE_ADKG_TEST_SEED");
+        let task = async move {
+            let out = adkg.start(&mut rng, Arc::new(transport)).await;
+            adkg.stop().await;
+            (i, out)
+        };
+        if i == PartyId(n) {
+            byzantine_task = Some(tokio::spawn(task));
+        } else {
+            tasks.spawn(task);
+        }
+    }
+
+    let outputs = tasks
+        .join_all()
+        .await
+        .into_iter()
+        .map(|(i, out)| (i, out.expect("honest party failed to complete the adkg")))
+        .collect();
+    byzantine_task.unwrap().abort();
+    for d in dispatchers {
+        d.stop().await;
+    }
+    handle.stop().await;
+
+    outputs
+}
+
+/// Honest parties agree on the group public key, and hold consistent shares of its secret.
+fn assert_consistent_outputs(t: usize, g: G, outputs: &[(PartyId, AdkgOutput<G>)]) {
+    let (_, first) = &outputs[0];
+    let group_pk = first.group_pk.expect("failed to obtain group pk");
+    for (_, out) in outputs {
+        assert_eq!(out.group_pk, Some(group_pk));
+        assert_eq!(out.used_sessions, first.used_sessions);
+    }
+
+    let points: Vec<(u64, Fr)> = outputs.iter().map(|(i, out)| (i.into(), out.sk)).collect();
+    let s = lagrange_interpolate_at::<G>(&points[..=t], 0);
+    let s2 = lagrange_interpolate_at::<G>(&points[points.len() - t - 1..], 0);
+    assert_eq!(s, s2);
+    assert_eq!(g * s, group_pk);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+async fn adkg_corrupt_dealer_bn254() {
+    let t = 2;
+    let n = 3 * t + 1;
+    let g = G::hash_to_curve(b"ADKG_GENERATOR_G", b"BYZANTINE_TEST_DST");
+
+    let dealer = CorruptAcssShares::<G>::new(vec![PartyId(1)]);
+    let outputs = run_adkg(n, t, behaviour(Arc::new(dealer))).await;
+    assert_consistent_outputs(t, g, &outputs);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+async fn adkg_inconsistent_dealer_bn254() {
+    let t = 2;
+    let n = 3 * t + 1;
+    let g = G::hash_to_curve(b"ADKG_GENERATOR_G", b"BYZANTINE_TEST_DST");
+
+    let dealer = InconsistentAcssCommitment::<G>::default();
+    let outputs = run_adkg(n, t, behaviour(Arc::new(dealer))).await;
+    assert_consistent_outputs(t, g, &outputs);
+
+    // The dealing of the malicious party is never used
+    let (_, out) = &outputs[0];
+    assert!(out.used_sessions.iter().all(|sid| *sid != PartyId(n)));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+async fn adkg_equivocating_voter_bn254() {
+    let t = 2;
+    let n = 3 * t + 1;
+    let g = G::hash_to_curve(b"ADKG_GENERATOR_G", b"BYZANTINE_TEST_DST");
+
+    let voter = EquivocatingAbaVoter {
+        targets: PartyId::iter_all(n).take(n / 2).collect(),
+    };
+    let outputs = run_adkg(n, t, behaviour(Arc::new(voter))).await;
+    assert_consistent_outputs(t, g, &outputs);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+async fn adkg_crashed_party_bn254() {
+    let t = 2;
+    let n = 3 * t + 1;
+    let g = G::hash_to_curve(b"ADKG_GENERATOR_G", b"BYZANTINE_TEST_DST");
+
+    let crashed = NodeBehaviour::Crash { at: Duration::ZERO };
+    let outputs = run_adkg(n, t, crashed).await;
+    assert_consistent_outputs(t, g, &outputs);
+
+    // The crashed party never dealt, hence its session cannot be used
+    let (_, out) = &outputs[0];
+    assert!(out.used_sessions.iter().all(|sid| *sid != PartyId(n)));
+}
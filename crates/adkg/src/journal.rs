@@ -0,0 +1,554 @@
+//! Encrypted on-disk journal used to resume an ADKG ceremony after a crash.
+//!
+//! The state of the ADKG (completed ACSS sessions, RBC outputs, ABA estimates, randex progress) is
+//! entirely determined by its randomness and by the messages received by the node. Hence, rather
+//! than serializing each component, the journal stores the seed of the [`DeterministicRand`] used by
+//! the ADKG and every message received and sent by the node. Upon restart, the received messages are
+//! replayed to rebuild the state of the ADKG, and the sent messages are re-delivered to the other
+//! nodes, which allows the node to rejoin the same session and continue from where it stopped.
+//!
+//! Since honest nodes only consider the first message of a sender at each step of the protocol,
+//! replaying the journal before any live message guarantees that the node keeps the view it had
+//! before the crash, even if a message computed during the replay differs from the original one.
+//!
+//! The journal is an append-only file of length-prefixed records, each encrypted with
+//! ChaCha20Poly1305 under a key derived from a secret of the node.
+//! A partially written record, caused by a crash while appending, is discarded when reopening.
+
+use crate::helpers::PartyId;
+use crate::rand::DeterministicRand;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use dcipher_network::transports::replayable::writer::{
+    MessageWriter, TransportWriter, TransportWriterSender,
+};
+use dcipher_network::{MessageType, ReceivedMessage, Recipient, Transport, TransportSender};
+use futures::StreamExt;
+use futures::stream::BoxStream;
+use hkdf::Hkdf;
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+use sha3::Sha3_256;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use thiserror::Error;
+
+const KDF_DST: &[u8] = b"ADKG-JOURNAL-v1_CHACHA20POLY1305_HKDF_SHA3-256";
+const NONCE_LENGTH: usize = 12;
+const LENGTH_PREFIX: usize = 4;
+
+#[derive(Error, Debug)]
+pub enum JournalError {
+    #[error("io error")]
+    Io(#[from] std::io::Error),
+
+    #[error("failed to serialize journal record")]
+    Serialize(#[from] bson::ser::Error),
+
+    #[error("failed to deserialize journal record")]
+    Deserialize(#[from] bson::de::Error),
+
+    #[error("failed to encrypt journal record")]
+    Encrypt,
+
+    #[error("failed to decrypt journal record: invalid key or corrupted journal")]
+    Decrypt,
+
+    #[error("journal does not start with a header")]
+    MissingHeader,
+
+    #[error("journal was created for a different session")]
+    SessionMismatch,
+}
+
+/// A record stored in the journal.
+#[serde_with::serde_as]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+enum JournalRecord {
+    /// First record of the journal.
+    Header {
+        #[serde_as(as = "utils::Base64OrBytes")]
+        session: Vec<u8>,
+        #[serde_as(as = "utils::Base64OrBytes")]
+        seed: Vec<u8>,
+    },
+
+    /// Message received by the node.
+    Received {
+        sender: PartyId,
+        message_type: MessageType,
+        #[serde_as(as = "utils::Base64OrBytes")]
+        msg: Vec<u8>,
+    },
+
+    /// Message sent by the node.
+    Sent {
+        recipient: Recipient<PartyId>,
+        #[serde_as(as = "utils::Base64OrBytes")]
+        msg: Vec<u8>,
+    },
+}
+
+/// Content of the journal recovered when opening it.
+pub struct JournalEntries {
+    /// Seed of the randomness used by the ADKG.
+    pub seed: [u8; 32],
+
+    /// Whether the journal already existed, i.e., the ADKG is being resumed.
+    pub resumed: bool,
+
+    /// Messages received before the restart, in order.
+    pub received: Vec<ReceivedMessage<PartyId>>,
+
+    /// Messages sent before the restart, in order.
+    pub sent: Vec<(Recipient<PartyId>, Vec<u8>)>,
+}
+
+impl JournalEntries {
+    /// Deterministic randomness to use for the ADKG, identical across restarts.
+    pub fn rng(&self) -> DeterministicRand {
+        DeterministicRand::new(self.seed)
+    }
+}
+
+/// An encrypted, append-only journal of the messages received and sent during an ADKG.
+#[derive(Clone)]
+pub struct AdkgJournal {
+    inner: Arc<Mutex<JournalFile>>,
+}
+
+struct JournalFile {
+    file: File,
+    cipher: ChaCha20Poly1305,
+}
+
+impl AdkgJournal {
+    /// Open the journal stored at `path`, or create it if it does not exist.
+    ///
+    /// The encryption key is derived from `secret`, which should be a secret of the node that
+    /// survives restarts, such as its long-term private key. The `session` must uniquely identify
+    /// the ceremony, and opening a journal created for a different session fails.
+    pub fn open(
+        path: impl AsRef<Path>,
+        secret: &[u8],
+        session: &[u8],
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> Result<(Self, JournalEntries), JournalError> {
+        let file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(path)?;
+
+        let mut key = Key::default();
+        Hkdf::<Sha3_256>::new(Some(KDF_DST), secret)
+            .expand(&[], key.as_mut_slice())
+            .map_err(|_| JournalError::Encrypt)?;
+
+        let mut journal = JournalFile {
+            file,
+            cipher: ChaCha20Poly1305::new(&key),
+        };
+        let records = journal.read_all()?;
+
+        let entries = if records.is_empty() {
+            // New journal, sample a seed and write the header
+            let mut seed = [0u8; 32];
+            rng.fill_bytes(&mut seed);
+            journal.append(&JournalRecord::Header {
+                session: session.to_vec(),
+                seed: seed.to_vec(),
+            })?;
+
+            JournalEntries {
+                seed,
+                resumed: false,
+                received: vec![],
+                sent: vec![],
+            }
+        } else {
+            Self::entries_from_records(session, records)?
+        };
+
+        Ok((
+            Self {
+                inner: Arc::new(Mutex::new(journal)),
+            },
+            entries,
+        ))
+    }
+
+    /// Obtain a [`MessageWriter`] that appends sent messages to the journal.
+    pub fn writer(&self) -> JournalWriter {
+        JournalWriter(Some(self.clone()))
+    }
+
+    fn entries_from_records(
+        session: &[u8],
+        records: Vec<JournalRecord>,
+    ) -> Result<JournalEntries, JournalError> {
+        let mut records = records.into_iter();
+        let Some(JournalRecord::Header {
+            session: journal_session,
+            seed,
+        }) = records.next()
+        else {
+            Err(JournalError::MissingHeader)?
+        };
+        if journal_session != session {
+            Err(JournalError::SessionMismatch)?
+        }
+
+        let mut entries = JournalEntries {
+            seed: seed.try_into().map_err(|_| JournalError::MissingHeader)?,
+            resumed: true,
+            received: vec![],
+            sent: vec![],
+        };
+        for record in records {
+            match record {
+                JournalRecord::Received {
+                    sender,
+                    message_type,
+                    msg,
+                } => entries
+                    .received
+                    .push(ReceivedMessage::new(sender, msg, message_type)),
+                JournalRecord::Sent { recipient, msg } => entries.sent.push((recipient, msg)),
+                JournalRecord::Header { .. } => Err(JournalError::MissingHeader)?,
+            }
+        }
+
+        Ok(entries)
+    }
+
+    fn append(&self, record: &JournalRecord) -> Result<(), JournalError> {
+        self.inner
+            .lock()
+            .expect("a thread panicked holding the mutex")
+            .append(record)
+    }
+
+    /// Append a record from an async context without blocking the runtime on file I/O.
+    async fn append_async(&self, record: JournalRecord) -> Result<(), JournalError> {
+        let journal = self.clone();
+        tokio::task::spawn_blocking(move || journal.append(&record))
+            .await
+            .expect("journal writer panicked")
+    }
+}
+
+impl JournalFile {
+    /// Read and decrypt all the records, discarding a partially written record at the end.
+    fn read_all(&mut self) -> Result<Vec<JournalRecord>, JournalError> {
+        let mut buf = vec![];
+        self.file.seek(SeekFrom::Start(0))?;
+        self.file.read_to_end(&mut buf)?;
+
+        let mut records = vec![];
+        let mut offset = 0;
+        while buf.len() - offset >= LENGTH_PREFIX {
+            let len_bytes = buf[offset..offset + LENGTH_PREFIX].try_into().unwrap();
+            let len = u32::from_be_bytes(len_bytes) as usize;
+            let Some(frame) = buf.get(offset + LENGTH_PREFIX..offset + LENGTH_PREFIX + len) else {
+                break; // partially written record
+            };
+
+            records.push(self.decrypt(frame)?);
+            offset += LENGTH_PREFIX + len;
+        }
+
+        if offset != buf.len() {
+            tracing::warn!(
+                discarded_bytes = buf.len() - offset,
+                "Discarding partially written journal record"
+            );
+            self.file.set_len(offset as u64)?;
+            self.file.sync_data()?;
+        }
+
+        Ok(records)
+    }
+
+    fn append(&mut self, record: &JournalRecord) -> Result<(), JournalError> {
+        let plaintext = bson::to_vec(record)?;
+        let mut nonce = Nonce::default();
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, plaintext.as_slice())
+            .map_err(|_| JournalError::Encrypt)?;
+
+        // Write the whole record at once to limit partial writes, and make sure that it reaches
+        // the disk before returning
+        let len =
+            u32::try_from(NONCE_LENGTH + ciphertext.len()).map_err(|_| JournalError::Encrypt)?;
+        let frame = [&len.to_be_bytes()[..], &nonce, &ciphertext].concat();
+        self.file.write_all(&frame)?;
+        self.file.sync_data()?;
+
+        Ok(())
+    }
+
+    fn decrypt(&self, frame: &[u8]) -> Result<JournalRecord, JournalError> {
+        if frame.len() < NONCE_LENGTH {
+            Err(JournalError::Decrypt)?
+        }
+
+        let (nonce, ciphertext) = frame.split_at(NONCE_LENGTH);
+        let plaintext = self
+            .cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| JournalError::Decrypt)?;
+
+        Ok(bson::from_slice(&plaintext)?)
+    }
+}
+
+/// A [`MessageWriter`] appending sent messages to an [`AdkgJournal`], if any.
+#[derive(Clone, Default)]
+pub struct JournalWriter(Option<AdkgJournal>);
+
+impl MessageWriter<PartyId, Vec<u8>> for JournalWriter {
+    type Error = JournalError;
+
+    async fn write(&self, msg: &Vec<u8>, to: Recipient<PartyId>) -> Result<(), Self::Error> {
+        let Some(journal) = &self.0 else {
+            return Ok(());
+        };
+
+        journal
+            .append_async(JournalRecord::Sent {
+                recipient: to,
+                msg: msg.to_owned(),
+            })
+            .await
+    }
+}
+
+/// A [`Transport`] that records the messages sent and received by the node in an [`AdkgJournal`],
+/// and replays the messages received before a restart.
+pub struct JournalTransport<T>
+where
+    T: Transport,
+{
+    journal: Option<AdkgJournal>,
+    transport: TransportWriter<JournalWriter, T>,
+    inner_sender: Option<T::Sender>,
+    replay: Vec<ReceivedMessage<PartyId>>,
+    redeliver: Vec<(Recipient<PartyId>, Vec<u8>)>,
+}
+
+impl<T> JournalTransport<T>
+where
+    T: Transport<Identity = PartyId>,
+{
+    /// Create a new [`JournalTransport`] replaying and re-delivering the entries of the journal.
+    pub fn new(journal: AdkgJournal, entries: JournalEntries, mut transport: T) -> Self {
+        Self {
+            inner_sender: transport.sender(),
+            transport: TransportWriter::new(journal.writer(), transport),
+            journal: Some(journal),
+            replay: entries.received,
+            redeliver: entries.sent,
+        }
+    }
+
+    /// Create a [`JournalTransport`] that does not record any message.
+    pub fn disabled(transport: T) -> Self {
+        Self {
+            inner_sender: None,
+            transport: TransportWriter::new(JournalWriter::default(), transport),
+            journal: None,
+            replay: vec![],
+            redeliver: vec![],
+        }
+    }
+
+    /// Re-deliver the messages sent before the restart, which may have been lost by the other nodes
+    /// during the crash, and return the number of messages re-delivered. The messages are sent
+    /// through the inner transport, and hence recorded by its writers, e.g., for transcripts, but
+    /// they are not recorded in the journal again.
+    pub async fn redeliver(&mut self) -> usize {
+        let redeliver = std::mem::take(&mut self.redeliver);
+        let Some(sender) = &self.inner_sender else {
+            return 0;
+        };
+
+        let mut count = 0;
+        for (recipient, msg) in redeliver {
+            match sender.send(msg, recipient).await {
+                Ok(()) => count += 1,
+                Err(e) => tracing::error!(error = ?e, "Failed to re-deliver journaled message"),
+            }
+        }
+
+        count
+    }
+}
+
+impl<T> Transport for JournalTransport<T>
+where
+    T: Transport<Identity = PartyId>,
+{
+    type Error = T::Error;
+    type Identity = PartyId;
+    type ReceiveMessageStream = BoxStream<'static, Result<ReceivedMessage<PartyId>, T::Error>>;
+    type Sender = TransportWriterSender<JournalWriter, T::Sender>;
+
+    fn sender(&mut self) -> Option<Self::Sender> {
+        self.transport.sender()
+    }
+
+    fn receiver_stream(&mut self) -> Option<Self::ReceiveMessageStream> {
+        let live = self.transport.receiver_stream()?;
+        let replay = futures::stream::iter(std::mem::take(&mut self.replay).into_iter().map(Ok));
+
+        // Record live messages before handing them to the node, such that the node never acts
+        // upon a message missing from the journal
+        let journal = self.journal.clone();
+        let live = live.then(move |m| {
+            let journal = journal.clone();
+            async move {
+                let (Some(journal), Ok(msg)) = (&journal, &m) else {
+                    return m;
+                };
+
+                let record = JournalRecord::Received {
+                    sender: msg.sender,
+                    message_type: msg.message_type,
+                    msg: msg.content.clone(),
+                };
+                if let Err(e) = journal.append_async(record).await {
+                    tracing::error!(error = ?e, "Failed to record received message in journal");
+                }
+                m
+            }
+        });
+
+        Some(replay.chain(live).boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dcipher_network::transports::in_memory::MemoryNetwork;
+    use dcipher_network::transports::replayable::writer::{InMemoryEntryType, InMemoryWriter};
+    use rand::thread_rng;
+    use std::path::PathBuf;
+
+    const SECRET: &[u8] = b"journal secret";
+    const SESSION: &[u8] = b"journal session";
+
+    fn journal_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("adkg-journal-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[tokio::test]
+    async fn resume_replays_and_redelivers() {
+        let path = journal_path("resume");
+        let ids = [PartyId(1), PartyId(2)];
+
+        // First run: receive a message from node 2 and broadcast a message
+        let (journal, entries) =
+            AdkgJournal::open(&path, SECRET, SESSION, &mut thread_rng()).unwrap();
+        assert!(!entries.resumed);
+        let seed = entries.seed;
+
+        let mut transports = MemoryNetwork::get_transports(ids);
+        let mut transport =
+            JournalTransport::new(journal, entries, transports.pop_front().unwrap());
+        let mut other = transports.pop_front().unwrap();
+        let mut other_stream = other.receiver_stream().unwrap();
+        let mut stream = transport.receiver_stream().unwrap();
+
+        other
+            .sender()
+            .unwrap()
+            .send(b"received".to_vec(), Recipient::Single(PartyId(1)))
+            .await
+            .unwrap();
+        assert_eq!(stream.next().await.unwrap().unwrap().content, b"received");
+        transport
+            .sender()
+            .unwrap()
+            .send(b"sent".to_vec(), Recipient::All)
+            .await
+            .unwrap();
+        assert_eq!(other_stream.next().await.unwrap().unwrap().content, b"sent");
+        drop((transport, stream));
+
+        // Second run: same seed, replay received messages and re-deliver sent messages
+        let (journal, entries) =
+            AdkgJournal::open(&path, SECRET, SESSION, &mut thread_rng()).unwrap();
+        assert!(entries.resumed);
+        assert_eq!(entries.seed, seed);
+        assert_eq!(entries.received.len(), 1);
+        assert_eq!(entries.sent, vec![(Recipient::All, b"sent".to_vec())]);
+
+        let mut transports = MemoryNetwork::get_transports(ids);
+        let inner: TransportWriter<InMemoryWriter<PartyId, Vec<u8>>, _> =
+            TransportWriter::new_in_memory(transports.pop_front().unwrap());
+        let transcript = inner.writer().clone();
+        let mut transport = JournalTransport::new(journal, entries, inner);
+        let mut other_stream = transports.pop_front().unwrap().receiver_stream().unwrap();
+        let mut stream = transport.receiver_stream().unwrap();
+
+        let replayed = stream.next().await.unwrap().unwrap();
+        assert_eq!(replayed.sender, PartyId(2));
+        assert_eq!(replayed.content, b"received");
+        assert_eq!(transport.redeliver().await, 1);
+        assert_eq!(other_stream.next().await.unwrap().unwrap().content, b"sent");
+
+        // re-delivered messages are part of the transcript of the resumed run
+        let transcript = transcript.take().await;
+        assert_eq!(transcript[&InMemoryEntryType::Broadcast].len(), 1);
+        assert_eq!(transcript[&InMemoryEntryType::Broadcast][0].msg, b"sent");
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn partially_written_record_is_discarded() {
+        let path = journal_path("partial");
+        let (journal, entries) =
+            AdkgJournal::open(&path, SECRET, SESSION, &mut thread_rng()).unwrap();
+        journal
+            .append(&JournalRecord::Sent {
+                recipient: Recipient::All,
+                msg: b"sent".to_vec(),
+            })
+            .unwrap();
+        drop(journal);
+
+        // Simulate a crash while writing a record
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&[0, 0, 1, 0, 42]).unwrap();
+        drop(file);
+
+        let (_, resumed_entries) =
+            AdkgJournal::open(&path, SECRET, SESSION, &mut thread_rng()).unwrap();
+        assert_eq!(resumed_entries.seed, entries.seed);
+        assert_eq!(resumed_entries.sent.len(), 1);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn invalid_secret_or_session_is_rejected() {
+        let path = journal_path("invalid");
+        let _ = AdkgJournal::open(&path, SECRET, SESSION, &mut thread_rng()).unwrap();
+
+        let res = AdkgJournal::open(&path, b"other secret", SESSION, &mut thread_rng());
+        assert!(matches!(res, Err(JournalError::Decrypt)));
+        let res = AdkgJournal::open(&path, SECRET, b"other session", &mut thread_rng());
+        assert!(matches!(res, Err(JournalError::SessionMismatch)));
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
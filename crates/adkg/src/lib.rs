@@ -1,13 +1,17 @@
 pub mod aba;
 pub mod adkg;
 pub mod helpers;
+pub mod journal;
 pub mod network;
 pub mod nizk;
 pub mod pke;
 pub mod rand;
 pub mod rbc;
+pub mod reshare;
 pub mod vss;
 
+#[cfg(test)]
+mod byzantine;
 mod pok;
 #[cfg(feature = "scheme")]
 pub mod scheme;
@@ -9,7 +9,8 @@
 use crate::helpers::PartyId;
 use dcipher_network::{Recipient, TransportSender};
 use std::{fmt::Debug, time::Duration};
-use tracing::{debug, error, trace};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, trace, warn};
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum RetryStrategy {
@@ -23,32 +24,119 @@ pub enum RetryStrategy {
     WithExponentialBackoff { n: usize, first_backoff: Duration },
 }
 
+impl RetryStrategy {
+    /// Maximum number of retries after the first attempt.
+    fn max_retries(&self) -> usize {
+        match *self {
+            RetryStrategy::None => 0,
+            RetryStrategy::Times { n }
+            | RetryStrategy::WithLinearBackoff { n, .. }
+            | RetryStrategy::WithExponentialBackoff { n, .. } => n,
+        }
+    }
+
+    /// Delay before the `retry`-th retry, starting at 1.
+    fn backoff(&self, retry: usize) -> Duration {
+        match *self {
+            RetryStrategy::None | RetryStrategy::Times { .. } => Duration::ZERO,
+            RetryStrategy::WithLinearBackoff { backoff, .. } => {
+                backoff.saturating_mul(retry.try_into().unwrap_or(u32::MAX))
+            }
+            RetryStrategy::WithExponentialBackoff { first_backoff, .. } => {
+                let factor = u32::try_from(retry.saturating_sub(1))
+                    .ok()
+                    .and_then(|exp| 2u32.checked_pow(exp));
+                match factor {
+                    Some(factor) => first_backoff.saturating_mul(factor),
+                    None if first_backoff.is_zero() => Duration::ZERO,
+                    None => Duration::MAX,
+                }
+            }
+        }
+    }
+}
+
 #[derive(thiserror::Error, Debug)]
 pub(crate) enum SendSerializeError {
     #[error("transport error")]
     Transport(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
 
+    #[error("failed to broadcast message to parties {0:?}")]
+    Broadcast(Vec<PartyId>),
+
+    #[error("retries cancelled through token")]
+    Cancelled,
+
     #[error("failed to serialize bson")]
     BsonSer(#[from] bson::ser::Error),
 }
 
+/// Try to send a message to all the nodes, including self.
+///
+/// If the broadcast fails and the retry strategy allows it, the message is sent to each of the
+/// `n` nodes independently, such that retries towards an unreachable node do not delay the others.
 pub(crate) async fn broadcast_with_self<T, M>(
     m: &M,
+    n: usize,
     retry_strategy: &RetryStrategy,
+    cancel: &CancellationToken,
     transport: &T,
 ) -> Result<(), SendSerializeError>
 where
     T: TransportSender<Identity = PartyId>,
     M: serde::Serialize,
 {
-    send_serialize_helper(m, Recipient::AllIncludingSelf, retry_strategy, transport).await
+    debug!("Attempting to broadcast message");
+    let m_vec = bson::to_vec(m)?;
+    match transport
+        .send(m_vec.clone(), Recipient::AllIncludingSelf)
+        .await
+    {
+        Ok(_) => {
+            trace!("Message broadcast");
+            return Ok(());
+        }
+        Err(e) if retry_strategy.max_retries() == 0 => {
+            error!("Failed to broadcast message: {e:?}");
+            Err(SendSerializeError::Transport(e.into()))?
+        }
+        Err(e) => {
+            warn!("Failed to broadcast message, retrying for each node: {e:?}");
+        }
+    }
+
+    let results = futures::future::join_all(PartyId::iter_all(n).map(|j| {
+        send_with_retries(
+            &m_vec,
+            Recipient::Single(j),
+            1,
+            retry_strategy,
+            cancel,
+            transport,
+        )
+    }))
+    .await;
+
+    let failed: Vec<_> = PartyId::iter_all(n)
+        .zip(results)
+        .filter_map(|(j, res)| res.is_err().then_some(j))
+        .collect();
+    if failed.is_empty() {
+        Ok(())
+    } else if cancel.is_cancelled() {
+        Err(SendSerializeError::Cancelled)
+    } else {
+        Err(SendSerializeError::Broadcast(failed))
+    }
 }
 
-/// Try to send a message to other nodes.
+/// Try to send a message to other nodes, retrying according to the retry strategy until the
+/// cancellation token is triggered.
 pub(crate) async fn send_serialize_helper<T, M>(
     m: &M,
     to: Recipient<PartyId>,
-    _retry_strategy: &RetryStrategy,
+    retry_strategy: &RetryStrategy,
+    cancel: &CancellationToken,
     transport: &T,
 ) -> Result<(), SendSerializeError>
 where
@@ -57,14 +145,282 @@ where
 {
     debug!("Attempting to send message to {to:?}");
     let m_vec = bson::to_vec(m)?;
-    match transport.send(m_vec, to).await {
-        Ok(_) => {
-            trace!("Message to {to:?} sent");
-            Ok(())
+    send_with_retries(&m_vec, to, 0, retry_strategy, cancel, transport).await
+}
+
+/// Send a serialized message, starting at the `first_retry`-th retry.
+async fn send_with_retries<T>(
+    m_vec: &[u8],
+    to: Recipient<PartyId>,
+    first_retry: usize,
+    retry_strategy: &RetryStrategy,
+    cancel: &CancellationToken,
+    transport: &T,
+) -> Result<(), SendSerializeError>
+where
+    T: TransportSender<Identity = PartyId>,
+{
+    let max_retries = retry_strategy.max_retries();
+    let mut retry = first_retry;
+    loop {
+        if retry > 0 {
+            let backoff = retry_strategy.backoff(retry);
+            tokio::select! {
+                biased;
+
+                _ = cancel.cancelled() => {
+                    debug!("Retries to send message to {to:?} cancelled through token");
+                    Err(SendSerializeError::Cancelled)?
+                }
+
+                _ = tokio::time::sleep(backoff) => {}
+            }
         }
-        Err(e) => {
-            error!("Failed to send message to node(s): {e:?}");
-            Err(SendSerializeError::Transport(e.into()))?
+
+        match transport.send(m_vec.to_vec(), to).await {
+            Ok(_) => {
+                trace!("Message to {to:?} sent");
+                return Ok(());
+            }
+            Err(e) if retry >= max_retries => {
+                error!("Failed to send message to node(s) {to:?}: {e:?}");
+                Err(SendSerializeError::Transport(e.into()))?
+            }
+            Err(e) => {
+                retry += 1;
+                warn!(
+                    "Failed to send message to node(s) {to:?}, retrying ({retry}/{max_retries}): {e:?}"
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+    use tokio::time::Instant;
+
+    #[derive(thiserror::Error, Debug)]
+    #[error("node unreachable")]
+    struct Unreachable;
+
+    /// Transport failing broadcasts, and the first messages sent to each node.
+    #[derive(Default)]
+    struct FailingTransport {
+        broadcast_failures: Mutex<usize>,
+        failures: Mutex<HashMap<PartyId, usize>>,
+        attempts: Mutex<HashMap<PartyId, usize>>,
+        delivered: Mutex<Vec<PartyId>>,
+    }
+
+    impl FailingTransport {
+        fn failing(failures: impl IntoIterator<Item = (usize, usize)>) -> Self {
+            Self {
+                failures: Mutex::new(
+                    failures
+                        .into_iter()
+                        .map(|(j, count)| (PartyId(j), count))
+                        .collect(),
+                ),
+                ..Default::default()
+            }
         }
+
+        fn with_broadcast_failures(self, count: usize) -> Self {
+            *self.broadcast_failures.lock().unwrap() = count;
+            self
+        }
+
+        fn attempts(&self, j: usize) -> usize {
+            self.attempts
+                .lock()
+                .unwrap()
+                .get(&PartyId(j))
+                .copied()
+                .unwrap_or_default()
+        }
+    }
+
+    impl TransportSender for FailingTransport {
+        type Identity = PartyId;
+        type Error = Unreachable;
+
+        async fn send(&self, _msg: Vec<u8>, to: Recipient<PartyId>) -> Result<(), Unreachable> {
+            let Recipient::Single(j) = to else {
+                let mut failures = self.broadcast_failures.lock().unwrap();
+                if *failures > 0 {
+                    *failures -= 1;
+                    Err(Unreachable)?
+                }
+                return Ok(());
+            };
+
+            *self.attempts.lock().unwrap().entry(j).or_default() += 1;
+            if let Some(failures) = self.failures.lock().unwrap().get_mut(&j)
+                && *failures > 0
+            {
+                *failures -= 1;
+                Err(Unreachable)?
+            }
+
+            self.delivered.lock().unwrap().push(j);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn retry_strategy_backoff() {
+        let ms = Duration::from_millis;
+        let linear = RetryStrategy::WithLinearBackoff {
+            n: 3,
+            backoff: ms(100),
+        };
+        let exponential = RetryStrategy::WithExponentialBackoff {
+            n: 3,
+            first_backoff: ms(100),
+        };
+
+        assert_eq!(RetryStrategy::None.max_retries(), 0);
+        assert_eq!(RetryStrategy::Times { n: 2 }.max_retries(), 2);
+        assert_eq!(RetryStrategy::Times { n: 2 }.backoff(1), Duration::ZERO);
+        assert_eq!(
+            (1..=3).map(|i| linear.backoff(i)).collect::<Vec<_>>(),
+            [ms(100), ms(200), ms(300)]
+        );
+        assert_eq!(
+            (1..=3).map(|i| exponential.backoff(i)).collect::<Vec<_>>(),
+            [ms(100), ms(200), ms(400)]
+        );
+        assert_eq!(exponential.backoff(usize::MAX), Duration::MAX);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn send_with_retry_strategies() {
+        let cancel = CancellationToken::new();
+        let to = Recipient::Single(PartyId(1));
+
+        // Not retried
+        let transport = FailingTransport::failing([(1, 1)]);
+        let res = send_serialize_helper(&"m", to, &RetryStrategy::None, &cancel, &transport).await;
+        assert!(matches!(res, Err(SendSerializeError::Transport(_))));
+        assert_eq!(transport.attempts(1), 1);
+
+        // Retried without delay
+        let transport = FailingTransport::failing([(1, 2)]);
+        let start = Instant::now();
+        let strategy = RetryStrategy::Times { n: 2 };
+        send_serialize_helper(&"m", to, &strategy, &cancel, &transport)
+            .await
+            .unwrap();
+        assert_eq!(transport.attempts(1), 3);
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        // Gives up after n retries
+        let transport = FailingTransport::failing([(1, 3)]);
+        let res = send_serialize_helper(&"m", to, &strategy, &cancel, &transport).await;
+        assert!(matches!(res, Err(SendSerializeError::Transport(_))));
+        assert_eq!(transport.attempts(1), 3);
+
+        // Linear backoff: 100ms + 200ms
+        let transport = FailingTransport::failing([(1, 2)]);
+        let start = Instant::now();
+        let strategy = RetryStrategy::WithLinearBackoff {
+            n: 3,
+            backoff: Duration::from_millis(100),
+        };
+        send_serialize_helper(&"m", to, &strategy, &cancel, &transport)
+            .await
+            .unwrap();
+        assert_eq!(start.elapsed(), Duration::from_millis(300));
+
+        // Exponential backoff: 100ms + 200ms + 400ms
+        let transport = FailingTransport::failing([(1, 3)]);
+        let start = Instant::now();
+        let strategy = RetryStrategy::WithExponentialBackoff {
+            n: 3,
+            first_backoff: Duration::from_millis(100),
+        };
+        send_serialize_helper(&"m", to, &strategy, &cancel, &transport)
+            .await
+            .unwrap();
+        assert_eq!(start.elapsed(), Duration::from_millis(700));
+        assert_eq!(*transport.delivered.lock().unwrap(), [PartyId(1)]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn broadcast_retries_each_node() {
+        let cancel = CancellationToken::new();
+        let strategy = RetryStrategy::WithExponentialBackoff {
+            n: 4,
+            first_backoff: Duration::from_secs(1),
+        };
+
+        // Successful broadcasts are not sent to each node
+        let transport = FailingTransport::default().with_broadcast_failures(1);
+        broadcast_with_self(&"m", 4, &RetryStrategy::None, &cancel, &transport)
+            .await
+            .unwrap_err();
+        broadcast_with_self(&"m", 4, &strategy, &cancel, &transport)
+            .await
+            .unwrap();
+        assert!(transport.delivered.lock().unwrap().is_empty());
+
+        // Node 2 recovers after two retries, node 3 is unreachable
+        let transport =
+            FailingTransport::failing([(2, 2), (3, usize::MAX)]).with_broadcast_failures(1);
+        let start = Instant::now();
+        let res = broadcast_with_self(&"m", 4, &strategy, &cancel, &transport).await;
+        assert!(
+            matches!(res, Err(SendSerializeError::Broadcast(failed)) if failed == [PartyId(3)])
+        );
+        assert_eq!(
+            (1..=4).map(|j| transport.attempts(j)).collect::<Vec<_>>(),
+            [1, 3, 4, 1]
+        );
+
+        // Nodes 1 and 4 did not wait for the retries of nodes 2 and 3
+        let delivered = transport.delivered.lock().unwrap().clone();
+        assert_eq!(delivered, [PartyId(1), PartyId(4), PartyId(2)]);
+        assert_eq!(start.elapsed(), Duration::from_secs(1 + 2 + 4 + 8));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn cancel_retries() {
+        let cancel = CancellationToken::new();
+        let transport = FailingTransport::failing([(1, usize::MAX)]);
+        let strategy = RetryStrategy::WithLinearBackoff {
+            n: usize::MAX,
+            backoff: Duration::from_secs(3600),
+        };
+
+        tokio::spawn({
+            let cancel = cancel.clone();
+            async move {
+                tokio::time::sleep(Duration::from_secs(5000)).await;
+                cancel.cancel();
+            }
+        });
+
+        let start = Instant::now();
+        let res = send_serialize_helper(
+            &"m",
+            Recipient::Single(PartyId(1)),
+            &strategy,
+            &cancel,
+            &transport,
+        )
+        .await;
+        assert!(matches!(res, Err(SendSerializeError::Cancelled)));
+        assert_eq!(transport.attempts(1), 2);
+        assert_eq!(start.elapsed(), Duration::from_secs(5000));
+
+        // Broadcasts are not retried once cancelled
+        let transport = FailingTransport::default().with_broadcast_failures(1);
+        let res = broadcast_with_self(&"m", 2, &strategy, &cancel, &transport).await;
+        assert!(matches!(res, Err(SendSerializeError::Cancelled)));
+        assert_eq!(transport.attempts(1) + transport.attempts(2), 0);
     }
 }
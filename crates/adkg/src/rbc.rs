@@ -1,4 +1,6 @@
 //! Module for reliable broadcast protocols.
+pub mod bracha;
+pub mod hashed_bracha;
 pub(crate) mod multi_rbc;
 pub mod r4;
 mod reed_solomon;
@@ -63,7 +65,7 @@ pub trait RbcPredicate: Send + Sync {
 
 /// This predicate can be used for basic RBC broadcast where any message is accepted.
 #[derive(Clone)]
-pub(crate) struct AlwaysTruePredicate;
+pub struct AlwaysTruePredicate;
 
 #[async_trait]
 impl RbcPredicate for AlwaysTruePredicate {
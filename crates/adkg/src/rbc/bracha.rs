@@ -0,0 +1,381 @@
+//! Implementation of Bracha's reliable broadcast described in https://doi.org/10.1016/0890-5401(87)90054-X.
+//! Echo and ready messages carry the full message, which makes it the simplest and the fastest
+//! option for small messages, at a cost of O(n^2 |m|) bits.
+use super::r4::RbcError;
+use super::{AlwaysTruePredicate, RbcPredicate, ReliableBroadcast, ReliableBroadcastConfig};
+use crate::helpers::PartyId;
+use crate::network::{RetryStrategy, broadcast_with_self};
+use dcipher_network::topic::TopicBasedTransport;
+use dcipher_network::{ReceivedMessage, Transport};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info, warn};
+
+const TOPIC: &str = "rbcbracha";
+
+/// Configuration for Bracha's reliable broadcast.
+#[derive(Clone, Debug)]
+pub struct BrachaRbcConfig {
+    n: usize,
+    t: usize,
+    id: PartyId,
+    retry_strategy: RetryStrategy,
+}
+
+/// Implementation of Bracha's reliable broadcast.
+struct BrachaRbc<T>
+where
+    T: Transport,
+{
+    config: Arc<BrachaRbcConfig>,
+    sender: T::Sender,
+    receiver: T::ReceiveMessageStream,
+}
+
+/// Messages sent during Bracha's RBC.
+#[serde_with::serde_as]
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+enum Message {
+    Propose(#[serde_as(as = "utils::Base64OrBytes")] Vec<u8>),
+    Echo(#[serde_as(as = "utils::Base64OrBytes")] Vec<u8>),
+    Ready(#[serde_as(as = "utils::Base64OrBytes")] Vec<u8>),
+}
+
+impl BrachaRbcConfig {
+    pub fn new(id: PartyId, n: usize, t: usize, retry_strategy: &RetryStrategy) -> Arc<Self> {
+        Arc::new(Self {
+            n,
+            t,
+            id,
+            retry_strategy: *retry_strategy,
+        })
+    }
+
+    fn get_topic(prefix: &str) -> String {
+        if prefix.is_empty() {
+            TOPIC.to_owned()
+        } else {
+            format!("{prefix}/{TOPIC}")
+        }
+    }
+}
+
+impl<'a> ReliableBroadcastConfig<'a, PartyId> for BrachaRbcConfig {
+    type Error = RbcError;
+
+    fn new_instance_with_prefix<T>(
+        self: &Arc<Self>,
+        topic_prefix: String,
+        transport: T,
+    ) -> Result<impl ReliableBroadcast<Identity = PartyId, Error = Self::Error> + 'a, Self::Error>
+    where
+        T: TopicBasedTransport<Identity = PartyId>,
+    {
+        // Create own transport instance for the specified topic
+        let mut transport = transport
+            .get_transport_for(Self::get_topic(&topic_prefix))
+            .ok_or(Self::Error::TransportInit)?;
+        let receiver = transport
+            .receiver_stream()
+            .ok_or(Self::Error::TransportInit)?;
+        let sender = transport.sender().ok_or(Self::Error::TransportInit)?;
+        Ok(BrachaRbc::<T::Transport> {
+            config: self.clone(),
+            sender,
+            receiver,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl<T> ReliableBroadcast for BrachaRbc<T>
+where
+    T: Transport<Identity = PartyId>,
+{
+    type Identity = PartyId;
+    type Error = RbcError;
+
+    async fn start(self, m: &[u8], cancel: CancellationToken) -> Result<Vec<u8>, Self::Error> {
+        let id = self.config.id;
+        tokio::select! {
+            _ = cancel.cancelled() => {
+                info!("Leader `{id}` aborting RBC due to cancellation token");
+                Err(RbcError::CancelledEarly)?
+            }
+
+            res = async {
+                // send ⟨PROPOSE, m⟩ to all
+                let msg = Message::Propose(m.to_vec());
+                let config = &self.config;
+                if let Err(e) =
+                    broadcast_with_self(&msg, config.n, &config.retry_strategy, &cancel, &self.sender).await
+                {
+                    error!("Leader `{id}` failed to send proposal... Aborting RBC.");
+                    return Err(RbcError::SendSerialize(e.into()));
+                }
+
+                // Start the RBC protocol as a standard node
+                self.rbc(&AlwaysTruePredicate, id, &cancel).await
+            } => {
+                res
+            }
+        }
+    }
+
+    async fn listen<P>(
+        self,
+        predicate: &P,
+        expected_sender: Self::Identity,
+        cancel: CancellationToken,
+    ) -> Result<Vec<u8>, Self::Error>
+    where
+        P: RbcPredicate,
+    {
+        let id = self.config.id;
+        tokio::select! {
+            _ = cancel.cancelled() => {
+                info!("Node `{id}` aborting RBC due to cancellation token");
+                Err(RbcError::CancelledEarly)?
+            }
+
+            res = self.rbc(predicate, expected_sender, &cancel) => {
+                res
+            }
+        }
+    }
+}
+
+impl<T> BrachaRbc<T>
+where
+    T: Transport<Identity = PartyId>,
+{
+    /// Protocol executed by the leader and the nodes.
+    async fn rbc(
+        self,
+        predicate: &impl RbcPredicate,
+        expected_sender: PartyId,
+        cancel: &CancellationToken,
+    ) -> Result<Vec<u8>, RbcError> {
+        let Self {
+            config,
+            sender,
+            mut receiver,
+        } = self;
+        let (id, n, t) = (config.id, config.n, config.t);
+        info!("Node `{id}` listening for Bracha RBC with parameters (n = `{n}`, t = `{t}`)");
+
+        let mut echo_sent = false;
+        let mut ready_sent = false;
+        let mut echos = Votes::default();
+        let mut readys = Votes::default();
+        let mut messages = HashMap::new(); // messages stored by digest
+        loop {
+            let mut output = None;
+            let ReceivedMessage {
+                sender: from,
+                content,
+                ..
+            } = match receiver.next().await {
+                Some(Ok(m)) => m,
+                Some(Err(e)) => {
+                    warn!("Node `{id}` failed to recv: {e:?}");
+                    continue;
+                }
+                None => {
+                    error!("Node `{id}` failed to recv: no more items in stream");
+                    return Err(RbcError::CancelledEarly);
+                }
+            };
+            let msg: Message = match bson::from_slice(&content) {
+                Ok(m) => m,
+                Err(e) => {
+                    error!(error = ?e, "Node `{id}` failed to deserialize message");
+                    continue;
+                }
+            };
+
+            let ready = match msg {
+                Message::Propose(m) => {
+                    if from != expected_sender || echo_sent {
+                        warn!("Node `{id}` refused proposal from node `{from}`");
+                        continue;
+                    }
+                    if !predicate.predicate(from, &m).await {
+                        warn!("Node `{id}` refused proposal: refused due to predicate");
+                        continue;
+                    }
+
+                    // Echo the proposal to all
+                    debug!("Node `{id}` echoing proposal to all nodes");
+                    echo_sent = true;
+                    if let Err(e) = broadcast_with_self(
+                        &Message::Echo(m),
+                        n,
+                        &config.retry_strategy,
+                        cancel,
+                        &sender,
+                    )
+                    .await
+                    {
+                        error!("Node `{id}` failed to send echo message, got error {e:?}");
+                    }
+                    None
+                }
+
+                Message::Echo(m) => {
+                    let h = Sha3_256::digest(&m).to_vec();
+                    let count = echos.insert_once(from, &h);
+                    if echos.voted_for(&from, &h) {
+                        messages.entry(h.clone()).or_insert(m);
+                    }
+
+                    // upon receiving ⌈(n + t + 1) / 2⌉ matching ⟨ECHO, m⟩, send ⟨READY, m⟩
+                    (count > (n + t) / 2).then_some(h)
+                }
+
+                Message::Ready(m) => {
+                    let h = Sha3_256::digest(&m).to_vec();
+                    let count = readys.insert_once(from, &h);
+                    if readys.voted_for(&from, &h) {
+                        messages.entry(h.clone()).or_insert(m);
+                    }
+
+                    // upon receiving 2t + 1 matching ⟨READY, m⟩, output m once ready has been sent
+                    if count > 2 * t {
+                        output = Some(h.clone());
+                    }
+
+                    // upon receiving t + 1 matching ⟨READY, m⟩, send ⟨READY, m⟩
+                    (count > t).then_some(h)
+                }
+            };
+
+            if let Some(h) = ready.filter(|_| !ready_sent) {
+                info!("Node `{id}` changing state to ready");
+                ready_sent = true;
+                let m = messages[&h].clone();
+                if let Err(e) = broadcast_with_self(
+                    &Message::Ready(m),
+                    n,
+                    &config.retry_strategy,
+                    cancel,
+                    &sender,
+                )
+                .await
+                {
+                    error!("Node `{id}` failed to send ready message, got error {e:?}");
+                }
+            }
+
+            if let Some(h) = output {
+                info!("Node `{id}` completed RBC");
+                return Ok(messages.remove(&h).expect("message inserted above"));
+            }
+        }
+    }
+}
+
+/// Votes of each party for a digest, where only the first vote of each party is counted.
+#[derive(Default)]
+pub(super) struct Votes {
+    voted: HashMap<PartyId, Vec<u8>>,
+    counts: HashMap<Vec<u8>, usize>,
+}
+
+impl Votes {
+    /// Record the vote of `party` for `h` if it has not voted yet, and return the number of votes
+    /// for `h`.
+    pub(super) fn insert_once(&mut self, party: PartyId, h: &[u8]) -> usize {
+        if let Entry::Vacant(e) = self.voted.entry(party) {
+            e.insert(h.to_vec());
+            *self.counts.entry(h.to_vec()).or_default() += 1;
+        }
+
+        self.count(h)
+    }
+
+    /// Number of votes for `h`.
+    pub(super) fn count(&self, h: &[u8]) -> usize {
+        self.counts.get(h).copied().unwrap_or_default()
+    }
+
+    /// Returns whether `party` voted for `h`.
+    pub(super) fn voted_for(&self, party: &PartyId, h: &[u8]) -> bool {
+        self.voted.get(party).is_some_and(|v| v == h)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dcipher_network::topic::dispatcher::TopicDispatcher;
+    use dcipher_network::transports::in_memory::MemoryNetwork;
+    use std::collections::VecDeque;
+    use tokio::task::JoinSet;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_rbc_all_parties() {
+        let t = 2;
+        run_rbc("test_bracha_all_parties", 3 * t + 1, t).await;
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_rbc_no_faults() {
+        // With t = 0, a single ready message is enough to output
+        run_rbc("test_bracha_no_faults", 3, 0).await;
+    }
+
+    async fn run_rbc(topic: &str, n: usize, t: usize) {
+        let m = b"Hello World!";
+
+        let (_dispatchers, mut transports): (Vec<_>, VecDeque<_>) =
+            MemoryNetwork::get_transports(PartyId::iter_all(n))
+                .into_iter()
+                .map(|t| {
+                    let mut dispatcher = TopicDispatcher::new();
+                    let tbt = dispatcher.start(t);
+                    (dispatcher, tbt)
+                })
+                .collect();
+
+        let mut tasks = JoinSet::new();
+        for i in PartyId::iter_all(n) {
+            let broadcast = BrachaRbcConfig::new(i, n, t, &RetryStrategy::None)
+                .new_instance_with_prefix(
+                    topic.to_owned(),
+                    Arc::new(transports.pop_front().unwrap()),
+                )
+                .expect("failed to create rbc instance");
+
+            tasks.spawn(async move {
+                if i == PartyId(1) {
+                    broadcast.start(m, CancellationToken::new()).await
+                } else {
+                    broadcast
+                        .listen(&AlwaysTruePredicate, PartyId(1), CancellationToken::new())
+                        .await
+                }
+            });
+        }
+
+        while let Some(res) = tasks.join_next().await {
+            assert_eq!(res.unwrap().unwrap(), m);
+        }
+    }
+
+    #[test]
+    fn votes_are_counted_once_per_party() {
+        let mut votes = Votes::default();
+        assert_eq!(votes.insert_once(PartyId(1), b"h1"), 1);
+        assert_eq!(votes.insert_once(PartyId(1), b"h1"), 1);
+        assert_eq!(votes.insert_once(PartyId(1), b"h2"), 0);
+        assert_eq!(votes.insert_once(PartyId(2), b"h2"), 1);
+        assert!(votes.voted_for(&PartyId(1), b"h1"));
+        assert!(!votes.voted_for(&PartyId(1), b"h2"));
+    }
+}
@@ -0,0 +1,426 @@
+//! Hash-based variant of Bracha's reliable broadcast, where echo and ready messages only carry the
+//! digest of the message. Parties that did not obtain the proposal receive the message from the
+//! parties that complete the broadcast, which costs O(n |m| + n^2 λ) bits when the leader is honest,
+//! without the Reed-Solomon overhead of [`super::r4`].
+use super::bracha::Votes;
+use super::r4::RbcError;
+use super::{AlwaysTruePredicate, RbcPredicate, ReliableBroadcast, ReliableBroadcastConfig};
+use crate::helpers::PartyId;
+use crate::network::{RetryStrategy, broadcast_with_self, send_serialize_helper};
+use dcipher_network::topic::TopicBasedTransport;
+use dcipher_network::{ReceivedMessage, Recipient, Transport};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info, warn};
+
+const TOPIC: &str = "rbchashedbracha";
+
+/// Configuration for the hash-based variant of Bracha's reliable broadcast.
+#[derive(Clone, Debug)]
+pub struct HashedBrachaRbcConfig {
+    n: usize,
+    t: usize,
+    id: PartyId,
+    retry_strategy: RetryStrategy,
+}
+
+/// Implementation of the hash-based variant of Bracha's reliable broadcast.
+struct HashedBrachaRbc<T>
+where
+    T: Transport,
+{
+    config: Arc<HashedBrachaRbcConfig>,
+    sender: T::Sender,
+    receiver: T::ReceiveMessageStream,
+}
+
+/// Messages sent during the hash-based Bracha RBC.
+#[serde_with::serde_as]
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+enum Message {
+    /// Message proposed by the leader.
+    Propose(#[serde_as(as = "utils::Base64OrBytes")] Vec<u8>),
+    /// Digest of the proposal.
+    Echo(#[serde_as(as = "utils::Base64OrBytes")] Vec<u8>),
+    /// Digest of the message about to be output.
+    Ready(#[serde_as(as = "utils::Base64OrBytes")] Vec<u8>),
+    /// Message sent upon completion to the parties that may not know it.
+    Deliver(#[serde_as(as = "utils::Base64OrBytes")] Vec<u8>),
+}
+
+impl HashedBrachaRbcConfig {
+    pub fn new(id: PartyId, n: usize, t: usize, retry_strategy: &RetryStrategy) -> Arc<Self> {
+        Arc::new(Self {
+            n,
+            t,
+            id,
+            retry_strategy: *retry_strategy,
+        })
+    }
+
+    fn get_topic(prefix: &str) -> String {
+        if prefix.is_empty() {
+            TOPIC.to_owned()
+        } else {
+            format!("{prefix}/{TOPIC}")
+        }
+    }
+}
+
+impl<'a> ReliableBroadcastConfig<'a, PartyId> for HashedBrachaRbcConfig {
+    type Error = RbcError;
+
+    fn new_instance_with_prefix<T>(
+        self: &Arc<Self>,
+        topic_prefix: String,
+        transport: T,
+    ) -> Result<impl ReliableBroadcast<Identity = PartyId, Error = Self::Error> + 'a, Self::Error>
+    where
+        T: TopicBasedTransport<Identity = PartyId>,
+    {
+        // Create own transport instance for the specified topic
+        let mut transport = transport
+            .get_transport_for(Self::get_topic(&topic_prefix))
+            .ok_or(Self::Error::TransportInit)?;
+        let receiver = transport
+            .receiver_stream()
+            .ok_or(Self::Error::TransportInit)?;
+        let sender = transport.sender().ok_or(Self::Error::TransportInit)?;
+        Ok(HashedBrachaRbc::<T::Transport> {
+            config: self.clone(),
+            sender,
+            receiver,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl<T> ReliableBroadcast for HashedBrachaRbc<T>
+where
+    T: Transport<Identity = PartyId>,
+{
+    type Identity = PartyId;
+    type Error = RbcError;
+
+    async fn start(self, m: &[u8], cancel: CancellationToken) -> Result<Vec<u8>, Self::Error> {
+        let id = self.config.id;
+        tokio::select! {
+            _ = cancel.cancelled() => {
+                info!("Leader `{id}` aborting RBC due to cancellation token");
+                Err(RbcError::CancelledEarly)?
+            }
+
+            res = async {
+                // send ⟨PROPOSE, m⟩ to all
+                let msg = Message::Propose(m.to_vec());
+                let config = &self.config;
+                if let Err(e) =
+                    broadcast_with_self(&msg, config.n, &config.retry_strategy, &cancel, &self.sender).await
+                {
+                    error!("Leader `{id}` failed to send proposal... Aborting RBC.");
+                    return Err(RbcError::SendSerialize(e.into()));
+                }
+
+                // Start the RBC protocol as a standard node
+                self.rbc(&AlwaysTruePredicate, id, &cancel).await
+            } => {
+                res
+            }
+        }
+    }
+
+    async fn listen<P>(
+        self,
+        predicate: &P,
+        expected_sender: Self::Identity,
+        cancel: CancellationToken,
+    ) -> Result<Vec<u8>, Self::Error>
+    where
+        P: RbcPredicate,
+    {
+        let id = self.config.id;
+        tokio::select! {
+            _ = cancel.cancelled() => {
+                info!("Node `{id}` aborting RBC due to cancellation token");
+                Err(RbcError::CancelledEarly)?
+            }
+
+            res = self.rbc(predicate, expected_sender, &cancel) => {
+                res
+            }
+        }
+    }
+}
+
+impl<T> HashedBrachaRbc<T>
+where
+    T: Transport<Identity = PartyId>,
+{
+    /// Protocol executed by the leader and the nodes.
+    async fn rbc(
+        self,
+        predicate: &impl RbcPredicate,
+        expected_sender: PartyId,
+        cancel: &CancellationToken,
+    ) -> Result<Vec<u8>, RbcError> {
+        let Self {
+            config,
+            sender,
+            mut receiver,
+        } = self;
+        let (id, n, t) = (config.id, config.n, config.t);
+        info!("Node `{id}` listening for hashed Bracha RBC with parameters (n = `{n}`, t = `{t}`)");
+
+        let mut proposal: Option<(Vec<u8>, Vec<u8>)> = None; // (h, m) of the accepted proposal
+        let mut ready_sent = false;
+        let mut output = None; // digest of the message to output
+        let mut echos = Votes::default();
+        let mut readys = Votes::default();
+        let mut delivered = HashMap::new(); // messages delivered by each party
+        loop {
+            let ReceivedMessage {
+                sender: from,
+                content,
+                ..
+            } = match receiver.next().await {
+                Some(Ok(m)) => m,
+                Some(Err(e)) => {
+                    warn!("Node `{id}` failed to recv: {e:?}");
+                    continue;
+                }
+                None => {
+                    error!("Node `{id}` failed to recv: no more items in stream");
+                    return Err(RbcError::CancelledEarly);
+                }
+            };
+            let msg: Message = match bson::from_slice(&content) {
+                Ok(m) => m,
+                Err(e) => {
+                    error!(error = ?e, "Node `{id}` failed to deserialize message");
+                    continue;
+                }
+            };
+
+            let ready = match msg {
+                Message::Propose(m) => {
+                    if from != expected_sender || proposal.is_some() {
+                        warn!("Node `{id}` refused proposal from node `{from}`");
+                        continue;
+                    }
+                    if !predicate.predicate(from, &m).await {
+                        warn!("Node `{id}` refused proposal: refused due to predicate");
+                        continue;
+                    }
+
+                    // Echo the digest of the proposal to all
+                    debug!("Node `{id}` echoing proposal to all nodes");
+                    let h = Sha3_256::digest(&m).to_vec();
+                    proposal = Some((h.clone(), m));
+                    if let Err(e) = broadcast_with_self(
+                        &Message::Echo(h),
+                        n,
+                        &config.retry_strategy,
+                        cancel,
+                        &sender,
+                    )
+                    .await
+                    {
+                        error!("Node `{id}` failed to send echo message, got error {e:?}");
+                    }
+                    None
+                }
+
+                Message::Echo(h) => {
+                    // upon receiving ⌈(n + t + 1) / 2⌉ matching ⟨ECHO, h⟩, send ⟨READY, h⟩
+                    let count = echos.insert_once(from, &h);
+                    (count > (n + t) / 2).then_some(h)
+                }
+
+                Message::Ready(h) => {
+                    // upon receiving t + 1 matching ⟨READY, h⟩, send ⟨READY, h⟩
+                    let count = readys.insert_once(from, &h);
+                    if count > 2 * t && output.is_none() {
+                        output = Some(h.clone());
+                    }
+                    (count > t).then_some(h)
+                }
+
+                Message::Deliver(m) => {
+                    // Only store the first message delivered by each party
+                    delivered.entry(from).or_insert(m);
+                    None
+                }
+            };
+
+            if let Some(h) = ready.filter(|_| !ready_sent) {
+                info!("Node `{id}` changing state to ready");
+                ready_sent = true;
+                if let Err(e) = broadcast_with_self(
+                    &Message::Ready(h),
+                    n,
+                    &config.retry_strategy,
+                    cancel,
+                    &sender,
+                )
+                .await
+                {
+                    error!("Node `{id}` failed to send ready message, got error {e:?}");
+                }
+            }
+
+            // upon receiving 2t + 1 matching ⟨READY, h⟩ and knowing m s.t. H(m) = h, output m
+            let Some(h) = &output else {
+                continue;
+            };
+            let m = match &proposal {
+                Some((h_proposal, m)) if h_proposal == h => Some(m.clone()),
+                _ => delivered
+                    .values()
+                    .find(|m| Sha3_256::digest(m)[..] == h[..])
+                    .cloned(),
+            };
+            let Some(m) = m else {
+                debug!("Node `{id}` waiting for message to be delivered");
+                continue;
+            };
+
+            // Parties that did not echo h may not know m, send it to them concurrently
+            let msg = Message::Deliver(m.clone());
+            let deliveries = PartyId::iter_all(n)
+                .filter(|j| *j != id && !echos.voted_for(j, h))
+                .map(|j| {
+                    let (msg, retry_strategy, sender) = (&msg, &config.retry_strategy, &sender);
+                    async move {
+                        if let Err(e) = send_serialize_helper(
+                            msg,
+                            Recipient::Single(j),
+                            retry_strategy,
+                            cancel,
+                            sender,
+                        )
+                        .await
+                        {
+                            error!("Node `{id}` failed to deliver message to node `{j}`: {e:?}");
+                        }
+                    }
+                });
+            futures::future::join_all(deliveries).await;
+
+            info!("Node `{id}` completed RBC");
+            return Ok(m);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dcipher_network::topic::dispatcher::TopicDispatcher;
+    use dcipher_network::transports::in_memory::MemoryNetwork;
+    use std::collections::VecDeque;
+    use tokio::task::JoinSet;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_rbc_all_parties() {
+        let topic = "test_hashed_bracha_all_parties";
+        let m = b"Hello World!";
+        let t = 2;
+        let n = 3 * t + 1;
+
+        let (_dispatchers, mut transports): (Vec<_>, VecDeque<_>) =
+            MemoryNetwork::get_transports(PartyId::iter_all(n))
+                .into_iter()
+                .map(|t| {
+                    let mut dispatcher = TopicDispatcher::new();
+                    let tbt = dispatcher.start(t);
+                    (dispatcher, tbt)
+                })
+                .collect();
+
+        let mut tasks = JoinSet::new();
+        for i in PartyId::iter_all(n) {
+            let broadcast = HashedBrachaRbcConfig::new(i, n, t, &RetryStrategy::None)
+                .new_instance_with_prefix(
+                    topic.to_owned(),
+                    Arc::new(transports.pop_front().unwrap()),
+                )
+                .expect("failed to create rbc instance");
+
+            tasks.spawn(async move {
+                if i == PartyId(1) {
+                    broadcast.start(m, CancellationToken::new()).await
+                } else {
+                    broadcast
+                        .listen(&AlwaysTruePredicate, PartyId(1), CancellationToken::new())
+                        .await
+                }
+            });
+        }
+
+        while let Some(res) = tasks.join_next().await {
+            assert_eq!(res.unwrap().unwrap(), m);
+        }
+    }
+
+    /// Parties that never receive the proposal still obtain the message.
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_rbc_message_delivered_without_proposal() {
+        let topic = "test_hashed_bracha_without_proposal";
+        let m = b"Hello World!";
+        let t = 2;
+        let n = 3 * t + 1;
+
+        let (_dispatchers, mut transports): (Vec<_>, VecDeque<_>) =
+            MemoryNetwork::get_transports(PartyId::iter_all(n))
+                .into_iter()
+                .map(|t| {
+                    let mut dispatcher = TopicDispatcher::new();
+                    let tbt = dispatcher.start(t);
+                    (dispatcher, tbt)
+                })
+                .collect();
+
+        // The leader only sends its proposal to n - t other parties, and does not take part in the RBC
+        let leader = transports.pop_front().unwrap();
+        let leader_sender = leader
+            .get_transport_for(HashedBrachaRbcConfig::get_topic(topic))
+            .unwrap()
+            .sender()
+            .unwrap();
+        for j in PartyId::iter_all(n).skip(1).take(n - t) {
+            send_serialize_helper(
+                &Message::Propose(m.to_vec()),
+                Recipient::Single(j),
+                &RetryStrategy::None,
+                &CancellationToken::new(),
+                &leader_sender,
+            )
+            .await
+            .unwrap();
+        }
+
+        let mut tasks = JoinSet::new();
+        for i in PartyId::iter_all(n).skip(1) {
+            let broadcast = HashedBrachaRbcConfig::new(i, n, t, &RetryStrategy::None)
+                .new_instance_with_prefix(
+                    topic.to_owned(),
+                    Arc::new(transports.pop_front().unwrap()),
+                )
+                .expect("failed to create rbc instance");
+
+            tasks.spawn(async move {
+                broadcast
+                    .listen(&AlwaysTruePredicate, PartyId(1), CancellationToken::new())
+                    .await
+            });
+        }
+
+        while let Some(res) = tasks.join_next().await {
+            assert_eq!(res.unwrap().unwrap(), m);
+        }
+    }
+}
@@ -19,7 +19,7 @@ use thiserror::Error;
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
 
-const TOPIC: &str = "rbcr4";
+pub(crate) const TOPIC: &str = "rbcr4";
 
 /// Configuration for the four-round RBC protocol for long messages of https://eprint.iacr.org/2021/777.pdf, Algorithm 4
 #[derive(Clone, Debug)]
@@ -111,7 +111,7 @@ where
 
     async fn start(self, m: &[u8], cancel: CancellationToken) -> Result<Vec<u8>, Self::Error> {
         tokio::select! {
-            _ = cancel.cancelled_owned() => {
+            _ = cancel.cancelled() => {
                 info!("Leader `{}` aborting RBC due to cancellation token", self.config.id);
                 Err(RbcError::CancelledEarly)?
             }
@@ -122,6 +122,7 @@ where
                 self.config.n,
                 self.config.t,
                 &self.config.retry_strategy,
+                &cancel,
                 self.sender,
                 self.receiver,
             ) => {
@@ -140,7 +141,7 @@ where
         P: RbcPredicate,
     {
         tokio::select! {
-            _ = cancel.cancelled_owned() => {
+            _ = cancel.cancelled() => {
                 info!("Node `{}` aborting RBC due to cancellation token", self.config.id);
                 Err(RbcError::CancelledEarly)?
             }
@@ -150,6 +151,7 @@ where
                 self.config.n,
                 self.config.t,
                 &self.config.retry_strategy,
+                &cancel,
                 self.sender,
                 self.receiver,
                 predicate,
@@ -186,16 +188,19 @@ struct StateMachine<'a, T> {
     h_message_crossed_threshold: HashMap<Vec<u8>, Vec<u8>>, // HashMap to store messages that were echoed at least t + 1 times per hash, i.e., HashMap<h, m>
 
     retry_strategy: &'a RetryStrategy,
+    cancel: &'a CancellationToken,
     sender: &'a T,
 }
 
 /// Beginning of the protocol executed by the leader
+#[allow(clippy::too_many_arguments)]
 async fn rbc_leader<T>(
     m: &[u8],
     i: PartyId,
     n: usize,
     t: usize,
     retry_strategy: &RetryStrategy,
+    cancel: &CancellationToken,
     sender: T::Sender,
     receiver: T::ReceiveMessageStream,
 ) -> Result<Vec<u8>, RbcError>
@@ -209,7 +214,7 @@ where
     // input M
     // send \langle PROPOSE, M \rangle to all
     let msg = Message::Propose(Propose { m: m.to_vec() });
-    if let Err(e) = broadcast_with_self(&msg, retry_strategy, &sender).await {
+    if let Err(e) = broadcast_with_self(&msg, n, retry_strategy, cancel, &sender).await {
         error!("Leader `{i}` failed to send proposal... Aborting RBC.");
         Err(RbcError::SendSerialize(e.into()))? // rewrap error
     }
@@ -220,6 +225,7 @@ where
         n,
         t,
         retry_strategy,
+        cancel,
         sender,
         receiver,
         &AlwaysTruePredicate,
@@ -235,6 +241,7 @@ async fn rbc<T>(
     n: usize,
     t: usize,
     retry_strategy: &RetryStrategy,
+    cancel: &CancellationToken,
     sender: T::Sender,
     mut receiver: T::ReceiveMessageStream,
     predicate: &impl RbcPredicate,
@@ -255,6 +262,7 @@ where
         t,
         id: i,
         retry_strategy,
+        cancel,
         sender: &sender,
     };
 
@@ -354,8 +362,12 @@ mod tests {
     };
     use dcipher_network::topic::dispatcher::TopicDispatcher;
     use dcipher_network::transports::in_memory::MemoryNetwork;
+    use dcipher_network::transports::simulator::{
+        LinkConfig, NodeBehaviour, SimulatedNetwork, SimulatorConfig,
+    };
     use std::collections::VecDeque;
     use std::sync::Arc;
+    use std::time::Duration;
     use tokio::task::JoinSet;
     use tokio_util::sync::CancellationToken;
 
@@ -420,4 +432,73 @@ mod tests {
             assert_eq!(v.unwrap(), m);
         }
     }
+
+    /// Execute the RBC over an asynchronous network with duplicated messages and a crashed party
+    #[tokio::test(start_paused = true)]
+    async fn test_rbc_simulated_network() {
+        let topic = "test_rbc_simulated_network";
+        let m = b"Hello World!";
+        let t = 2;
+        let n = 3 * t + 1;
+
+        let config = SimulatorConfig::from_env("DCIPHER_SIMULATOR_SEED");
+        let seed = config.seed;
+        let config = config
+            .with_default_link(
+                LinkConfig::asynchronous(Duration::from_millis(1), Duration::from_millis(500))
+                    .with_duplicate_probability(0.2),
+            )
+            .with_behaviour(PartyId(n), NodeBehaviour::Crash { at: Duration::ZERO });
+
+        let (handle, transports) = SimulatedNetwork::get_transports(PartyId::iter_all(n), config);
+        let (_dispatchers, mut transports): (Vec<_>, VecDeque<_>) = transports
+            .into_iter()
+            .map(|t| {
+                let mut dispatcher = TopicDispatcher::new();
+                let tbt = dispatcher.start(t);
+                (dispatcher, tbt)
+            })
+            .collect();
+        let mut rbcs: VecDeque<_> = PartyId::iter_all(n)
+            .map(|i| Rbc4RoundsConfig::new(i, n, t, &RetryStrategy::None))
+            .collect();
+
+        let mut tasks = JoinSet::new();
+        tasks.spawn({
+            let transport = transports.pop_front().unwrap();
+            let broadcast = rbcs
+                .pop_front()
+                .unwrap()
+                .new_instance_with_prefix(topic.to_owned(), Arc::new(transport))
+                .expect("failed to create rbc instance");
+
+            async move { broadcast.start(m, CancellationToken::new()).await }
+        });
+
+        // The last party has crashed and never outputs
+        for _ in 2..n {
+            tasks.spawn({
+                let transport = transports.pop_front().unwrap();
+                let broadcast = rbcs
+                    .pop_front()
+                    .unwrap()
+                    .new_instance_with_prefix(topic.to_owned(), Arc::new(transport))
+                    .expect("failed to create rbc instance");
+
+                async move {
+                    broadcast
+                        .listen(&AlwaysTruePredicate, PartyId(1), CancellationToken::new())
+                        .await
+                }
+            });
+        }
+
+        while let Some(res) = tasks.join_next().await {
+            let v = res.expect("task panicked");
+            assert_eq!(v.expect("rbc failed"), m, "seed = {seed}");
+        }
+
+        let stats = handle.stop().await;
+        assert!(stats.duplicated > 0);
+    }
 }
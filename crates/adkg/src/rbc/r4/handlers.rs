@@ -67,24 +67,32 @@ pub(super) async fn rbc_receive_proposal<T>(
     let mp = rs_encode_stripes(m, n, t + 1);
 
     // 10: send \langle ECHO, m_j, h \rangle to node j \in [n]
+    // Messages are sent concurrently such that retries towards a node do not delay the others
     debug!("Node `{}` echoing proposal to all nodes", st.id);
-    for j in PartyId::iter_all(n) {
+    let (id, retry_strategy, cancel, transport) = (st.id, st.retry_strategy, st.cancel, st.sender);
+    let echoes = PartyId::iter_all(n).map(|j| {
         let msg = Message::Echo(Echo {
             h: h.clone(),
             m: mp[j].clone(),
         });
-        debug!("Node `{}` Echoing encoded proposal to node `{j}`", st.id);
+        async move {
+            debug!("Node `{id}` Echoing encoded proposal to node `{j}`");
 
-        // Try to send message, ignore errors
-        if let Err(e) =
-            send_serialize_helper(&msg, Recipient::Single(j), st.retry_strategy, st.sender).await
-        {
-            error!(
-                "Node `{}` failed to send echo message, got error {e:?}",
-                st.id
-            );
+            // Try to send message, ignore errors
+            if let Err(e) = send_serialize_helper(
+                &msg,
+                Recipient::Single(j),
+                retry_strategy,
+                cancel,
+                transport,
+            )
+            .await
+            {
+                error!("Node `{id}` failed to send echo message, got error {e:?}");
+            }
         }
-    }
+    });
+    futures::future::join_all(echoes).await;
 
     // Update state machine
     st.status = RbcStatus::WaitingForEchos;
@@ -277,7 +285,14 @@ where
     let h = ready.h.clone();
 
     // Try to send message, ignore errors
-    if let Err(e) = broadcast_with_self(&Message::Ready(ready), st.retry_strategy, st.sender).await
+    if let Err(e) = broadcast_with_self(
+        &Message::Ready(ready),
+        st.n,
+        st.retry_strategy,
+        st.cancel,
+        st.sender,
+    )
+    .await
     {
         error!(
             "Node `{}` failed to send ready message, got error {e:?}",
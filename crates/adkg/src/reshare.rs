@@ -0,0 +1,1662 @@
+//! Resharing of an ADKG output from an old committee to a new committee with possibly different
+//! parameters (n, t, t_reconstruction), while preserving the group secret and public key.
+//!
+//! The protocol is executed as follows:
+//!  1. Each member i of the old committee deals its share s_i with a Feldman VSS of degree
+//!     t_reconstruction' towards the n' members of the new committee, where the constant term of
+//!     the commitment must match the public key of node i. The dealing is sent to the new committee,
+//!     and dispersed to the old committee through a reliable broadcast.
+//!  2. Members of the new committee verify their share and acknowledge valid dealings.
+//!  3. The old committee agrees on a set of dealings by executing one ABA per dealing. A member
+//!     inputs 1 to the i-th ABA once it has obtained the i-th dealing through the RBC, and n' - t'
+//!     acknowledgements for it. The common coin of the ABAs is derived from the shares being
+//!     reshared.
+//!  4. The old committee sends the first t_reconstruction + 1 agreed upon dealings to the new
+//!     committee, which accepts them upon receiving t + 1 identical sets. The new shares, node
+//!     public keys, and group public key are obtained through Lagrange interpolation at 0.
+//!
+//! Both committees communicate over the same transport. The members of the old committee must use
+//! the identifiers 1, ..., n, while the transport identifiers of the new committee members are
+//! specified by [`NewCommittee::transport_ids`]. A node may be a member of both committees.
+//!
+//! A member of the new committee whose share of a selected dealing is invalid implicates the dealer,
+//! as done by hbacss0, by revealing the key it shares with the dealer along with a DLEQ proof. The
+//! other members verify the implicate, and send it their own share of the dealing, from which it
+//! recovers its share through interpolation. Requiring n' - t' acknowledgements before inputting 1
+//! to an ABA ensures that each selected dealing is valid for at least n' - 2t' honest members of the
+//! new committee, such that the recovery succeeds if t_reconstruction' < n' - 2t'.
+
+use crate::aba::crain20::{AbaInput, CoinKeys, CoinToss, EcdhCoinToss, EcdhCoinTossParams};
+use crate::aba::multi_aba::MultiAba;
+use crate::aba::{AbaConfig, Estimate};
+use crate::adkg::AdkgOutput;
+use crate::helpers::{
+    PartyId, SessionId, eval_poly, lagrange_interpolate_at, lagrange_points_interpolate_at,
+};
+use crate::network::{RetryStrategy, broadcast_with_self, send_serialize_helper};
+use crate::nizk::{NIZKDleqProof, NizkError};
+use crate::pke::ec_hybrid_chacha20poly1305::{
+    self, EphemeralMultiHybridCiphertext, HybridEncryptionError,
+};
+use crate::rand::{AdkgRng, AdkgRngType};
+use crate::rbc::multi_rbc::MultiRbc;
+use crate::rbc::{RbcPredicate, ReliableBroadcastConfig};
+use crate::vss::feldman;
+use ark_ec::CurveGroup;
+use async_trait::async_trait;
+use dcipher_network::topic::TopicBasedTransport;
+use dcipher_network::{ReceivedMessage, Recipient, Transport, TransportSender};
+use digest::core_api::BlockSizeUser;
+use digest::{DynDigest, FixedOutputReset};
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::marker::PhantomData;
+use std::sync::Arc;
+use thiserror::Error;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+use utils::serialize::fq::{FqDeserialize, FqSerialize};
+use utils::serialize::point::{PointDeserializeCompressed, PointSerializeCompressed};
+
+const TOPIC: &str = "reshare";
+const IMPLICATE_DLEQ_DST: &[u8] = b"ADKG-RESHARE-IMPLICATE-DLEQ";
+
+/// Input of the ABAs used by the old committee, the coin is derived from the reshared keys.
+pub type ReshareAbaInput<CG, H> = AbaInput<CoinKeys<EcdhCoinToss<CG, H>>>;
+
+/// Public parameters of the committee holding the shares prior to the resharing.
+#[derive(Clone)]
+pub struct OldCommittee<CG> {
+    pub n: usize,
+    pub t: usize,
+    pub t_reconstruction: usize,
+
+    /// The group public key, preserved by the resharing.
+    pub group_pk: CG,
+
+    /// The public keys of each of the nodes, ordered by identifier.
+    pub node_pks: Vec<CG>,
+}
+
+/// Public parameters of the committee receiving the shares.
+#[derive(Clone)]
+pub struct NewCommittee<CG> {
+    pub n: usize,
+    pub t: usize,
+    pub t_reconstruction: usize,
+
+    /// Long-term public keys used to encrypt the dealings, ordered by identifier.
+    pub pks: Vec<CG>,
+
+    /// Transport identifiers of the members, ordered by identifier.
+    pub transport_ids: Vec<PartyId>,
+}
+
+#[derive(Error, Debug)]
+pub enum ReshareError {
+    #[error("invalid resharing parameters: {0}")]
+    InvalidParameters(String),
+
+    #[error("failed to initialize reshare transport")]
+    TransportInit,
+
+    #[error("failed to serialize bson: `{1}`")]
+    BsonSer(#[source] bson::ser::Error, &'static str),
+
+    #[error("failed to deserialize bson: `{1}`")]
+    BsonDe(#[source] bson::de::Error, &'static str),
+
+    #[error("hybrid encryption error: `{1}`")]
+    HybridEncryption(#[source] HybridEncryptionError, &'static str),
+
+    #[error("nizk error: `{1}`")]
+    Nizk(#[source] NizkError, &'static str),
+
+    #[error("rng error: `{1}`")]
+    Rng(
+        #[source] Box<dyn std::error::Error + Send + Sync + 'static>,
+        &'static str,
+    ),
+
+    #[error("received an invalid dealing from old committee member `{0}`")]
+    InvalidDealing(PartyId),
+
+    #[error("not enough valid dealings to derive a share: got {0}, expected {1}")]
+    NotEnoughDealings(usize, usize),
+
+    #[error("resharing does not preserve the group public key")]
+    GroupPublicKey,
+
+    #[error("no more messages to receive")]
+    StreamClosed,
+
+    #[error("resharing cancelled")]
+    Cancelled,
+}
+
+/// Resharing of an ADKG output, see the [module-level documentation](self) for details.
+pub struct Reshare<CG, H, RBCConfig, ABAConfig>
+where
+    CG: CurveGroup,
+    RBCConfig: ReliableBroadcastConfig<'static, PartyId>,
+    ABAConfig: AbaConfig<'static, PartyId>,
+{
+    old: OldCommittee<CG>,
+    new: NewCommittee<CG>,
+    g: CG,
+
+    dealer: Option<Dealer<CG, RBCConfig, ABAConfig>>,
+    receiver: Option<Receiver<CG>>,
+
+    /// Task answering the implicates of the other members of the new committee
+    recovery_task: Option<(CancellationToken, JoinHandle<()>)>,
+
+    _h: PhantomData<fn(H) -> H>,
+}
+
+/// State of a member of the old committee.
+struct Dealer<CG, RBCConfig, ABAConfig>
+where
+    CG: CurveGroup,
+    RBCConfig: ReliableBroadcastConfig<'static, PartyId>,
+    ABAConfig: AbaConfig<'static, PartyId>,
+{
+    id: PartyId,
+    share: CG::ScalarField,
+    multi_rbc: MultiRbc<RBCConfig>,
+    multi_aba: MultiAba<ABAConfig>,
+}
+
+/// State of a member of the new committee.
+struct Receiver<CG: CurveGroup> {
+    id: PartyId,
+    sk: CG::ScalarField,
+}
+
+/// A share dealt by a member of the old committee.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "CG: PointSerializeCompressed",
+    deserialize = "CG: PointDeserializeCompressed"
+))]
+struct Dealing<CG: CurveGroup> {
+    #[serde(with = "utils::serialize::point::base64::vec")]
+    public_poly: Vec<CG>,
+    enc_shares: EphemeralMultiHybridCiphertext<CG>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "F: FqSerialize", deserialize = "F: FqDeserialize"))]
+struct DealtShare<F> {
+    #[serde(with = "utils::serialize::fq::base64_or_bytes")]
+    share: F,
+}
+
+/// Proof that a dealing is invalid for a member of the new committee, which reveals the key shared
+/// by the member and the dealer, along with a DLEQ proof of its correctness.
+#[derive(Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "CG: PointSerializeCompressed, CG::ScalarField: FqSerialize",
+    deserialize = "CG: PointDeserializeCompressed, CG::ScalarField: FqDeserialize"
+))]
+struct Implicate<CG: CurveGroup, H> {
+    #[serde(with = "utils::serialize::point::base64")]
+    shared_key: CG,
+    pi: NIZKDleqProof<CG, H>,
+}
+
+/// Messages exchanged between the two committees.
+#[serde_with::serde_as]
+#[derive(Serialize, Deserialize)]
+enum ReshareMessage {
+    /// Dealing sent by a member of the old committee to the new committee.
+    Dealing(#[serde_as(as = "utils::Base64OrBytes")] Vec<u8>),
+
+    /// Acknowledgement of a valid dealing by a member of the new committee.
+    Ack {
+        dealer: PartyId,
+        #[serde_as(as = "utils::Base64OrBytes")]
+        digest: Vec<u8>,
+    },
+
+    /// Set of dealings agreed upon by the old committee.
+    Dealings(#[serde_as(as = "Vec<(_, utils::Base64OrBytes)>")] Vec<(PartyId, Vec<u8>)>),
+
+    /// Implicate of a selected dealing by a member of the new committee.
+    Implicate {
+        dealer: PartyId,
+        #[serde_as(as = "utils::Base64OrBytes")]
+        implicate: Vec<u8>,
+    },
+
+    /// Share of a selected dealing, sent to a member of the new committee that implicated its
+    /// dealer.
+    Recovery {
+        dealer: PartyId,
+        #[serde_as(as = "utils::Base64OrBytes")]
+        share: Vec<u8>,
+    },
+}
+
+/// Number of nodes of the reshare transport, i.e., of the union of both committees.
+fn transport_n<CG>(old: &OldCommittee<CG>, new: &NewCommittee<CG>) -> usize {
+    new.transport_ids
+        .iter()
+        .map(usize::from)
+        .max()
+        .unwrap_or_default()
+        .max(old.n)
+}
+
+/// Predicate used by the reliable broadcasts of the old committee.
+#[derive(Clone)]
+struct DealingPredicate<CG> {
+    expected_sender: PartyId,
+    sender_pk: CG,
+    n_new: usize,
+    t_reconstruction_new: usize,
+}
+
+impl<CG, H, RBCConfig, ABAConfig> Reshare<CG, H, RBCConfig, ABAConfig>
+where
+    CG: CurveGroup + PointSerializeCompressed + PointDeserializeCompressed,
+    CG::ScalarField: FqSerialize + FqDeserialize,
+    H: Default + DynDigest + FixedOutputReset + BlockSizeUser + Clone + 'static,
+    EcdhCoinToss<CG, H>:
+        CoinToss<SecretKey = CG::ScalarField, PublicParams = EcdhCoinTossParams<CG>>,
+    RBCConfig: ReliableBroadcastConfig<'static, PartyId>,
+    ABAConfig: AbaConfig<'static, PartyId, Input = ReshareAbaInput<CG, H>>,
+{
+    /// Create a new resharing instance.
+    ///
+    /// Members of the old committee must specify their identifier, their current share, and the
+    /// RBC / ABA configurations of the old committee. Members of the new committee must specify
+    /// their identifier and their long-term secret key.
+    pub fn new(
+        old: OldCommittee<CG>,
+        new: NewCommittee<CG>,
+        g: CG,
+        dealer: Option<(PartyId, CG::ScalarField)>,
+        receiver: Option<(PartyId, CG::ScalarField)>,
+        rbc_config: Arc<RBCConfig>,
+        aba_config: Arc<ABAConfig>,
+    ) -> Result<Self, Box<ReshareError>> {
+        // Check bounds
+        for (n, t, t_reconstruction) in [
+            (old.n, old.t, old.t_reconstruction),
+            (new.n, new.t, new.t_reconstruction),
+        ] {
+            if n < 3 * t + 1 {
+                Err(ReshareError::InvalidParameters(format!(
+                    "t must be <= (n - 1) / 3 = {}, got n = {n}, t = {t}",
+                    n.saturating_sub(1) / 3
+                )))?
+            }
+            if t_reconstruction < t || t_reconstruction >= n - t {
+                Err(ReshareError::InvalidParameters(format!(
+                    "reconstruction threshold ({t_reconstruction}) must be in [t, n - t - 1] == [{t}, {}]",
+                    n - t - 1
+                )))?
+            }
+        }
+        if old.node_pks.len() != old.n {
+            Err(ReshareError::InvalidParameters(format!(
+                "expected {} node public keys, got {}",
+                old.n,
+                old.node_pks.len()
+            )))?
+        }
+        if new.pks.len() != new.n || new.transport_ids.len() != new.n {
+            Err(ReshareError::InvalidParameters(format!(
+                "expected {} public keys and transport identifiers",
+                new.n
+            )))?
+        }
+        if let Some((id, _)) = dealer
+            && !(1..=old.n).contains(&usize::from(id))
+        {
+            Err(ReshareError::InvalidParameters(format!(
+                "old committee identifier must be in [1, {}], got {id}",
+                old.n
+            )))?
+        }
+        if let Some((id, _)) = receiver
+            && !(1..=new.n).contains(&usize::from(id))
+        {
+            Err(ReshareError::InvalidParameters(format!(
+                "new committee identifier must be in [1, {}], got {id}",
+                new.n
+            )))?
+        }
+
+        let dealer = dealer.map(|(id, share)| Dealer {
+            id,
+            share,
+            multi_rbc: MultiRbc::new(id, old.n, rbc_config),
+            multi_aba: MultiAba::new(id, old.n, aba_config),
+        });
+        let receiver = receiver.map(|(id, sk)| Receiver { id, sk });
+
+        Ok(Self {
+            old,
+            new,
+            g,
+            dealer,
+            receiver,
+            recovery_task: None,
+            _h: PhantomData,
+        })
+    }
+
+    /// Execute the resharing. Members of the new committee obtain an output with the new shares,
+    /// while members of the old committee only exit once they have sent the agreed upon dealings.
+    ///
+    /// Cancelling `cancel` aborts the resharing, and its pending messages. The sub-protocols, and
+    /// the recovery of the shares of the other members of the new committee, keep running in the
+    /// background until [`Reshare::stop`] is called.
+    pub async fn reshare<T>(
+        &mut self,
+        rng: &mut impl AdkgRng,
+        transport: Arc<T>,
+        cancel: &CancellationToken,
+    ) -> Result<Option<AdkgOutput<CG>>, Box<ReshareError>>
+    where
+        T: TopicBasedTransport<Identity = PartyId>,
+    {
+        let mut reshare_transport = transport
+            .get_transport_for(TOPIC)
+            .ok_or(ReshareError::TransportInit)?;
+        let sender = reshare_transport
+            .sender()
+            .ok_or(ReshareError::TransportInit)?;
+        let mut receiver = reshare_transport
+            .receiver_stream()
+            .ok_or(ReshareError::TransportInit)?;
+
+        let mut dealer_state = match self.dealer.as_mut() {
+            Some(dealer) => Some(
+                Self::start_dealer(
+                    &self.old, &self.new, &self.g, dealer, &sender, rng, transport, cancel,
+                )
+                .await?,
+            ),
+            None => None,
+        };
+        let mut receiver_state = self
+            .receiver
+            .as_ref()
+            .map(|_| ReceiverState::<CG, H>::default());
+        let mut output = None;
+
+        // Node is done once it has sent the dealings as an old member, and obtained its share as a
+        // new member
+        loop {
+            // Send the agreed upon dealings once they are all available
+            if let Some(state) = dealer_state.as_mut() {
+                state
+                    .try_send_dealings(&self.old, &self.new, &sender, cancel)
+                    .await;
+            }
+
+            // Derive our share once we have a valid share of each selected dealing
+            if let (Some(receiver), Some(selected), None) = (
+                self.receiver.as_ref(),
+                receiver_state.as_ref().and_then(|s| s.selected.as_ref()),
+                &output,
+            ) && selected.is_complete()
+            {
+                output = Some(self.derive_output(receiver, selected)?);
+            }
+
+            let dealer_done = dealer_state.as_ref().is_none_or(|s| s.dealings_sent);
+            let receiver_done = self.receiver.is_none() || output.is_some();
+            if dealer_done && receiver_done {
+                break;
+            }
+
+            // Wait for the next event
+            let event = match (self.dealer.as_mut(), dealer_state.as_mut()) {
+                (Some(dealer), Some(state)) if !state.dealings_sent => {
+                    tokio::select! {
+                        _ = cancel.cancelled() => Err(ReshareError::Cancelled)?,
+                        Some(res) = dealer.multi_rbc.join_next() => DealerEvent::Rbc(res),
+                        Some((sid, estimate)) = state.estimates.next() => DealerEvent::Aba(sid, estimate),
+                        msg = receiver.next() => DealerEvent::Message(msg),
+                    }
+                }
+                _ => {
+                    tokio::select! {
+                        _ = cancel.cancelled() => Err(ReshareError::Cancelled)?,
+                        msg = receiver.next() => DealerEvent::Message(msg),
+                    }
+                }
+            };
+
+            let (sender_id, msg) = match event {
+                DealerEvent::Rbc(res) => {
+                    let (dealer, state) = (self.dealer.as_mut(), dealer_state.as_mut());
+                    Self::on_rbc_output(&self.old, &self.new, &self.g, dealer, state, res).await;
+                    continue;
+                }
+                DealerEvent::Aba(sid, estimate) => {
+                    let (dealer, state) = (self.dealer.as_mut(), dealer_state.as_mut());
+                    Self::on_aba_output(&self.old, &self.g, dealer, state, sid, estimate).await;
+                    continue;
+                }
+                DealerEvent::Message(None) => Err(ReshareError::StreamClosed)?,
+                DealerEvent::Message(Some(Err(e))) => {
+                    warn!("Failed to recv reshare message: {e:?}");
+                    continue;
+                }
+                DealerEvent::Message(Some(Ok(ReceivedMessage {
+                    sender: sender_id,
+                    content,
+                    ..
+                }))) => match bson::from_slice::<ReshareMessage>(&content) {
+                    Ok(msg) => (sender_id, (msg, content)),
+                    Err(e) => {
+                        warn!("Received reshare message from {sender_id} with bad format: {e:?}");
+                        continue;
+                    }
+                },
+            };
+
+            match msg {
+                (ReshareMessage::Dealing(dealing), _) => {
+                    if let (Some(receiver), Some(_)) = (self.receiver.as_ref(), &receiver_state) {
+                        self.on_dealing(receiver, sender_id, dealing, &sender, cancel)
+                            .await;
+                    }
+                }
+                (ReshareMessage::Ack { dealer, digest }, _) => {
+                    if let (Some(d), Some(state)) = (self.dealer.as_mut(), dealer_state.as_mut()) {
+                        let Some(new_id) =
+                            self.new.transport_ids.iter().position(|i| *i == sender_id)
+                        else {
+                            warn!(sender = %sender_id, "Ignoring ack from node outside of new committee");
+                            continue;
+                        };
+                        state
+                            .acks
+                            .entry((dealer, digest))
+                            .or_default()
+                            .insert(PartyId::from_index(new_id));
+                        Self::try_input_one(&self.old, &self.new, &self.g, d, state, dealer).await;
+                    }
+                }
+                (ReshareMessage::Dealings(dealings), content) => {
+                    if let (Some(receiver), Some(state)) =
+                        (self.receiver.as_ref(), receiver_state.as_mut())
+                    {
+                        if usize::from(sender_id) > self.old.n || state.selected.is_some() {
+                            continue;
+                        }
+
+                        // Accept the set of dealings once t + 1 members of the old committee sent it
+                        let senders = state
+                            .dealings
+                            .entry(Sha3_256::digest(&content).to_vec())
+                            .or_default();
+                        senders.insert(sender_id);
+                        if senders.len() > self.old.t {
+                            let selected = self
+                                .select_dealings(receiver, dealings, rng, &sender, cancel)
+                                .await?;
+                            let selected = state.selected.insert(selected);
+
+                            // Answer the implicates received before the dealings were selected
+                            for (complainer, dealer, implicate) in
+                                std::mem::take(&mut state.pending_implicates)
+                            {
+                                selected
+                                    .on_implicate(complainer, dealer, &implicate, &sender, cancel)
+                                    .await;
+                            }
+                        }
+                    }
+                }
+                (ReshareMessage::Implicate { dealer, implicate }, _) => {
+                    if let Some(state) = receiver_state.as_mut() {
+                        match state.selected.as_mut() {
+                            Some(selected) => {
+                                selected
+                                    .on_implicate(sender_id, dealer, &implicate, &sender, cancel)
+                                    .await
+                            }
+                            None => state
+                                .pending_implicates
+                                .push((sender_id, dealer, implicate)),
+                        }
+                    }
+                }
+                (ReshareMessage::Recovery { dealer, share }, _) => {
+                    if let Some(selected) =
+                        receiver_state.as_mut().and_then(|s| s.selected.as_mut())
+                    {
+                        selected.on_recovery(sender_id, dealer, &share);
+                    }
+                }
+            }
+        }
+
+        // Keep helping the other members of the new committee to recover their shares
+        if let Some(selected) = receiver_state.and_then(|s| s.selected) {
+            let cancel = cancel.child_token();
+            let task = tokio::spawn(selected.answer_implicates(receiver, sender, cancel.clone()));
+            self.recovery_task = Some((cancel, task));
+        }
+
+        Ok(output)
+    }
+
+    /// Stop the sub-protocols of the old committee, and the recovery of the shares of the new
+    /// committee.
+    pub async fn stop(self) {
+        if let Some((cancel, task)) = self.recovery_task {
+            cancel.cancel();
+            if let Err(e) = task.await {
+                error!("Reshare recovery task stopped with an error: {e:?}");
+            }
+        }
+
+        let Some(dealer) = self.dealer else {
+            return;
+        };
+
+        if let Err(e) = dealer.multi_aba.stop().await {
+            error!(
+                "Reshare of node `{}` stopped multi ABA with some errors: {e:?}",
+                dealer.id
+            );
+        }
+        if let Err(e) = dealer.multi_rbc.stop().await {
+            error!(
+                "Reshare of node `{}` stopped multi RBC with some errors: {e:?}",
+                dealer.id
+            );
+        }
+    }
+
+    /// Deal our share towards the new committee, and start the RBCs / ABAs of the old committee.
+    #[allow(clippy::too_many_arguments)]
+    async fn start_dealer<T>(
+        old: &OldCommittee<CG>,
+        new: &NewCommittee<CG>,
+        g: &CG,
+        dealer: &mut Dealer<CG, RBCConfig, ABAConfig>,
+        sender: &<T::Transport as Transport>::Sender,
+        rng: &mut impl AdkgRng,
+        transport: Arc<T>,
+        cancel: &CancellationToken,
+    ) -> Result<DealerState, Box<ReshareError>>
+    where
+        T: TopicBasedTransport<Identity = PartyId>,
+    {
+        let mut dealing_rng = rng
+            .get(AdkgRngType::Other(b"RESHARE_DEALING"))
+            .map_err(|e| ReshareError::Rng(e.into(), "failed to get dealing rng"))?;
+
+        // Feldman sharing of degree t_reconstruction' of our share
+        let vss_share = feldman::share(
+            &dealer.share,
+            g,
+            new.n,
+            new.t_reconstruction,
+            &mut dealing_rng,
+        );
+        let shares = PartyId::iter_all(new.n)
+            .map(|j| {
+                bson::to_vec(&DealtShare {
+                    share: *vss_share
+                        .get_party_secrets(j)
+                        .expect("feldman output less than n shares"),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| ReshareError::BsonSer(e, "failed to serialize shares"))?;
+        let enc_shares =
+            ec_hybrid_chacha20poly1305::encrypt_multi(&shares, &new.pks, g, &mut dealing_rng)
+                .map_err(|e| ReshareError::HybridEncryption(e, "failed to encrypt shares"))?;
+        let dealing = bson::to_vec(&Dealing {
+            public_poly: vss_share.get_public_poly().to_vec(),
+            enc_shares,
+        })
+        .map_err(|e| ReshareError::BsonSer(e, "failed to serialize dealing"))?;
+
+        // Start the RBCs and ABAs of the old committee
+        let predicates: Vec<_> = PartyId::iter_all(old.n)
+            .map(|i| DealingPredicate {
+                expected_sender: i,
+                sender_pk: old.node_pks[i],
+                n_new: new.n,
+                t_reconstruction_new: new.t_reconstruction,
+            })
+            .collect();
+        dealer.multi_rbc.start(predicates, transport.clone());
+        dealer.multi_aba.start(rng, transport);
+
+        // Disperse the dealing to the old committee, and send it to the new committee
+        if dealer
+            .multi_rbc
+            .get_leader_sender()
+            .expect("failed to get rbc leader sender")
+            .send(dealing.clone())
+            .is_err()
+        {
+            error!("Node `{}` failed to set its own RBC input", dealer.id);
+        }
+        if let Err(e) = broadcast_with_self(
+            &ReshareMessage::Dealing(dealing),
+            transport_n(old, new),
+            &RetryStrategy::None,
+            cancel,
+            sender,
+        )
+        .await
+        {
+            error!(
+                "Node `{}` failed to send dealing to the new committee: {e:?}",
+                dealer.id
+            );
+        }
+
+        Ok(DealerState {
+            rbc_outputs: HashMap::new(),
+            acks: HashMap::new(),
+            estimates: dealer.multi_aba.iter_remaining_estimates().boxed(),
+            decided: BTreeMap::new(),
+            dealings_sent: false,
+        })
+    }
+
+    async fn on_rbc_output<E: std::fmt::Debug>(
+        old: &OldCommittee<CG>,
+        new: &NewCommittee<CG>,
+        g: &CG,
+        dealer: Option<&mut Dealer<CG, RBCConfig, ABAConfig>>,
+        state: Option<&mut DealerState>,
+        res: Result<(SessionId, Result<Vec<u8>, E>), tokio::task::JoinError>,
+    ) {
+        let (Some(dealer), Some(state)) = (dealer, state) else {
+            return;
+        };
+
+        let (sid, dealing) = match res {
+            Ok((sid, Ok(dealing))) => (sid, dealing),
+            Ok((sid, Err(e))) => {
+                error!(
+                    "Node `{}` failed to get output from RBC with sid `{sid}`: {e:?}",
+                    dealer.id
+                );
+                return;
+            }
+            Err(e) => {
+                error!("Node `{}` failed to join an RBC task: {e:?}", dealer.id);
+                return;
+            }
+        };
+
+        info!("Node `{}` obtained dealing with sid `{sid}`", dealer.id);
+        state.rbc_outputs.insert(sid, dealing);
+        Self::try_input_one(old, new, g, dealer, state, sid.into()).await;
+    }
+
+    async fn on_aba_output(
+        old: &OldCommittee<CG>,
+        g: &CG,
+        dealer: Option<&mut Dealer<CG, RBCConfig, ABAConfig>>,
+        state: Option<&mut DealerState>,
+        sid: SessionId,
+        estimate: Result<Estimate, oneshot::error::RecvError>,
+    ) {
+        let (Some(dealer), Some(state)) = (dealer, state) else {
+            return;
+        };
+
+        let Ok(estimate) = estimate else {
+            error!(
+                "Node `{}` failed to get output from ABA with sid {sid}: sender dropped",
+                dealer.id
+            );
+            return;
+        };
+
+        info!(
+            "Node `{}` completed ABA with sid {sid} and got estimate {estimate:?}",
+            dealer.id
+        );
+        state.decided.insert(sid, estimate);
+
+        // Input 0 to the remaining ABAs once n - t have decided 1
+        let ones = state
+            .decided
+            .values()
+            .filter(|e| matches!(e, Estimate::One))
+            .count();
+        if ones == old.n - old.t {
+            for (k, aba_sender) in dealer.multi_aba.iter_remaining_senders() {
+                let input = Self::aba_input(old, g, dealer.share, Estimate::Zero);
+                if aba_sender.send(input).is_err() {
+                    error!(
+                        "Node `{}` failed to input 0 to ABA with sid `{k}`",
+                        dealer.id
+                    );
+                }
+            }
+        }
+    }
+
+    /// Input 1 to the ABA of a dealer once its dealing has been obtained through the RBC and
+    /// acknowledged by n' - t' members of the new committee.
+    async fn try_input_one(
+        old: &OldCommittee<CG>,
+        new: &NewCommittee<CG>,
+        g: &CG,
+        dealer: &mut Dealer<CG, RBCConfig, ABAConfig>,
+        state: &mut DealerState,
+        j: PartyId,
+    ) {
+        let sid = SessionId::from(usize::from(j));
+        let Some(dealing) = state.rbc_outputs.get(&sid) else {
+            return;
+        };
+
+        let digest = Sha3_256::digest(dealing).to_vec();
+        let acks = state.acks.get(&(j, digest)).map(BTreeSet::len);
+        if acks.unwrap_or_default() < new.n - new.t {
+            return;
+        }
+
+        if let Some(aba_sender) = dealer.multi_aba.get_sender(&sid) {
+            info!("Node `{}` inputting 1 to ABA with sid `{sid}`", dealer.id);
+            let input = Self::aba_input(old, g, dealer.share, Estimate::One);
+            if aba_sender.send(input).is_err() {
+                error!(
+                    "Node `{}` failed to input 1 to ABA with sid `{sid}`",
+                    dealer.id
+                );
+            }
+        }
+    }
+
+    fn aba_input(
+        old: &OldCommittee<CG>,
+        g: &CG,
+        share: CG::ScalarField,
+        v: Estimate,
+    ) -> ReshareAbaInput<CG, H> {
+        let (coin_keys_sender, coin_keys_receiver) = oneshot::channel();
+        let _ = coin_keys_sender.send(CoinKeys {
+            sk: share,
+            params: EcdhCoinTossParams {
+                t: old.t_reconstruction,
+                g: *g,
+                vk: old.group_pk,
+                vks: PartyId::iter_all(old.n)
+                    .map(|i| (i, old.node_pks[i]))
+                    .collect(),
+            },
+        });
+
+        AbaInput {
+            v,
+            coin_keys_receiver,
+        }
+    }
+
+    /// Verify a dealing received from the old committee, and acknowledge it if it is valid.
+    async fn on_dealing(
+        &self,
+        receiver: &Receiver<CG>,
+        dealer: PartyId,
+        dealing: Vec<u8>,
+        sender: &impl TransportSender<Identity = PartyId>,
+        cancel: &CancellationToken,
+    ) {
+        if usize::from(dealer) > self.old.n {
+            warn!(sender = %dealer, "Ignoring dealing from node outside of old committee");
+            return;
+        }
+
+        let res = self.parse_dealing(dealer, &dealing).and_then(|d| {
+            let shared_key = d.enc_shares.derive_shared_key(&receiver.sk);
+            decrypt_verify_share(&self.new, &self.g, receiver.id, dealer, &d, &shared_key)
+        });
+        if let Err(e) = res {
+            warn!(%dealer, "Not acknowledging invalid dealing: {e:?}");
+            return;
+        }
+
+        let ack = ReshareMessage::Ack {
+            dealer,
+            digest: Sha3_256::digest(&dealing).to_vec(),
+        };
+        if let Err(e) = broadcast_with_self(
+            &ack,
+            transport_n(&self.old, &self.new),
+            &RetryStrategy::None,
+            cancel,
+            sender,
+        )
+        .await
+        {
+            error!(
+                "Node `{}` failed to acknowledge dealing of node `{dealer}`: {e:?}",
+                receiver.id
+            );
+        }
+    }
+
+    /// Deserialize a dealing and verify its public parameters, i.e., those that are the same for
+    /// every member of the new committee.
+    fn parse_dealing(
+        &self,
+        dealer: PartyId,
+        dealing: &[u8],
+    ) -> Result<Dealing<CG>, Box<ReshareError>> {
+        let dealing = bson::from_slice::<Dealing<CG>>(dealing)
+            .map_err(|e| ReshareError::BsonDe(e, "failed to deserialize dealing"))?;
+
+        if !(1..=self.old.n).contains(&usize::from(dealer))
+            || dealing.public_poly.len() != self.new.t_reconstruction + 1
+            || dealing.public_poly[0] != self.old.node_pks[dealer]
+            || dealing.enc_shares.inner.cts.len() != self.new.n
+        {
+            Err(ReshareError::InvalidDealing(dealer))?
+        }
+
+        Ok(dealing)
+    }
+
+    /// Verify the dealings agreed upon by the old committee, and implicate the dealers of those
+    /// with an invalid share.
+    async fn select_dealings(
+        &self,
+        receiver: &Receiver<CG>,
+        dealings: Vec<(PartyId, Vec<u8>)>,
+        rng: &mut impl AdkgRng,
+        sender: &impl TransportSender<Identity = PartyId>,
+        cancel: &CancellationToken,
+    ) -> Result<SelectedDealings<CG, H>, Box<ReshareError>> {
+        // Skip dealings with invalid public parameters, which are skipped by every member
+        let expected = self.old.t_reconstruction + 1;
+        let dealings: BTreeMap<_, _> = dealings
+            .into_iter()
+            .filter_map(|(i, dealing)| match self.parse_dealing(i, &dealing) {
+                Ok(dealing) => Some((i, dealing)),
+                Err(e) => {
+                    warn!(dealer = %i, "Skipping invalid selected dealing: {e:?}");
+                    None
+                }
+            })
+            .take(expected)
+            .collect();
+        if dealings.len() < expected {
+            Err(ReshareError::NotEnoughDealings(dealings.len(), expected))?
+        }
+
+        let mut selected = SelectedDealings {
+            id: receiver.id,
+            new: self.new.clone(),
+            g: self.g,
+            dealings,
+            shares: BTreeMap::new(),
+            recovery: BTreeMap::new(),
+            answered: BTreeSet::new(),
+            _h: PhantomData,
+        };
+        let mut implicates = vec![];
+        for (i, dealing) in &selected.dealings {
+            let shared_key = dealing.enc_shares.derive_shared_key(&receiver.sk);
+            match decrypt_verify_share(&self.new, &self.g, receiver.id, *i, dealing, &shared_key) {
+                Ok(share) => {
+                    selected.shares.insert(*i, share);
+                }
+                Err(e) => {
+                    warn!(dealer = %i, "Implicating dealer of invalid selected dealing: {e:?}");
+                    let mut implicate_rng = rng
+                        .get(AdkgRngType::Other(b"RESHARE_IMPLICATE"))
+                        .map_err(|e| ReshareError::Rng(e.into(), "failed to get implicate rng"))?;
+                    let implicate = implicate::<CG, H>(
+                        &self.new,
+                        &self.g,
+                        receiver,
+                        dealing,
+                        &mut implicate_rng,
+                    )?;
+                    implicates.push(ReshareMessage::Implicate {
+                        dealer: *i,
+                        implicate,
+                    });
+                }
+            }
+        }
+
+        for implicate in implicates {
+            if let Err(e) = broadcast_with_self(
+                &implicate,
+                transport_n(&self.old, &self.new),
+                &RetryStrategy::None,
+                cancel,
+                sender,
+            )
+            .await
+            {
+                error!("Node `{}` failed to send implicate: {e:?}", receiver.id);
+            }
+        }
+
+        Ok(selected)
+    }
+
+    /// Derive the new share and public keys from our shares of the dealings agreed upon by the old
+    /// committee.
+    fn derive_output(
+        &self,
+        receiver: &Receiver<CG>,
+        selected: &SelectedDealings<CG, H>,
+    ) -> Result<AdkgOutput<CG>, Box<ReshareError>> {
+        let shares: Vec<_> = selected
+            .shares
+            .iter()
+            .map(|(i, share)| (u64::from(i), *share))
+            .collect();
+        let polys: Vec<_> = selected
+            .dealings
+            .iter()
+            .map(|(i, dealing)| (u64::from(i), &dealing.public_poly))
+            .collect();
+
+        // The new share is the interpolation at 0 of the dealt shares
+        let sk = lagrange_interpolate_at::<CG>(&shares, 0);
+
+        // Same for the public polynomial
+        let public_poly: Vec<CG> = (0..=self.new.t_reconstruction)
+            .map(|k| {
+                let points: Vec<_> = polys.iter().map(|(i, poly)| (*i, poly[k])).collect();
+                lagrange_points_interpolate_at(&points, 0)
+            })
+            .collect();
+        if public_poly[0] != self.old.group_pk {
+            Err(ReshareError::GroupPublicKey)?
+        }
+
+        let node_pks: Vec<CG> = PartyId::iter_all(self.new.n)
+            .map(|j| eval_poly(&u64::from(j).into(), &public_poly))
+            .collect();
+        info!(
+            "Node `{}` obtained its share from the dealings of {:?}",
+            receiver.id,
+            selected.dealings.keys().collect::<Vec<_>>()
+        );
+
+        Ok(AdkgOutput {
+            sk,
+            used_sessions: selected
+                .dealings
+                .keys()
+                .map(|i| SessionId::from(usize::from(i)))
+                .collect(),
+            group_pk: Some(public_poly[0]),
+            node_pks: Some(node_pks),
+        })
+    }
+}
+
+/// Decrypt and verify the share of the j-th member of the new committee in a dealing, using the
+/// key shared by the member and the dealer.
+fn decrypt_verify_share<CG>(
+    new: &NewCommittee<CG>,
+    g: &CG,
+    j: PartyId,
+    dealer: PartyId,
+    dealing: &Dealing<CG>,
+    shared_key: &CG,
+) -> Result<CG::ScalarField, Box<ReshareError>>
+where
+    CG: CurveGroup + PointSerializeCompressed,
+    CG::ScalarField: FqDeserialize,
+{
+    let share = dealing
+        .enc_shares
+        .decrypt_one_with_shared_key(j.as_index(), shared_key, &new.pks[j])
+        .map_err(|e| ReshareError::HybridEncryption(e, "failed to decrypt share"))?;
+    let DealtShare { share } = bson::from_slice(&share)
+        .map_err(|e| ReshareError::BsonDe(e, "failed to deserialize share"))?;
+
+    feldman::eval_verify(&dealing.public_poly, j, &share, g)
+        .map_err(|_| ReshareError::InvalidDealing(dealer))?;
+
+    Ok(share)
+}
+
+/// Create a serialized implicate of a dealing that is invalid for the receiver.
+fn implicate<CG, H>(
+    new: &NewCommittee<CG>,
+    g: &CG,
+    receiver: &Receiver<CG>,
+    dealing: &Dealing<CG>,
+    rng: &mut (impl rand::RngCore + rand::CryptoRng),
+) -> Result<Vec<u8>, Box<ReshareError>>
+where
+    CG: CurveGroup + PointSerializeCompressed,
+    CG::ScalarField: FqSerialize,
+    H: Default + DynDigest + FixedOutputReset + BlockSizeUser + Clone,
+{
+    // Prove that shared_key = [sk] sender_pk, where pk = [sk] G
+    let shared_key = dealing.enc_shares.derive_shared_key(&receiver.sk);
+    let pi = NIZKDleqProof::<CG, H>::prove(
+        &receiver.sk,
+        g,
+        &dealing.enc_shares.sender_pk,
+        &new.pks[receiver.id],
+        &shared_key,
+        IMPLICATE_DLEQ_DST,
+        rng,
+    )
+    .map_err(|e| ReshareError::Nizk(e, "failed to compute implicate proof"))?;
+
+    bson::to_vec(&Implicate { shared_key, pi })
+        .map_err(|e| Box::new(ReshareError::BsonSer(e, "failed to serialize implicate")))
+}
+
+enum DealerEvent<R, M> {
+    Rbc(R),
+    Aba(SessionId, Result<Estimate, oneshot::error::RecvError>),
+    Message(Option<M>),
+}
+
+/// Progress of a member of the old committee.
+struct DealerState {
+    rbc_outputs: HashMap<SessionId, Vec<u8>>,
+    acks: HashMap<(PartyId, Vec<u8>), BTreeSet<PartyId>>,
+    estimates: futures::stream::BoxStream<
+        'static,
+        (SessionId, Result<Estimate, oneshot::error::RecvError>),
+    >,
+    decided: BTreeMap<SessionId, Estimate>,
+    dealings_sent: bool,
+}
+
+/// Progress of a member of the new committee.
+struct ReceiverState<CG: CurveGroup, H> {
+    /// Senders of each set of dealings, indexed by digest
+    dealings: HashMap<Vec<u8>, BTreeSet<PartyId>>,
+    /// Set of dealings accepted from the old committee
+    selected: Option<SelectedDealings<CG, H>>,
+    /// Implicates received before accepting the set of dealings, with their sender
+    pending_implicates: Vec<(PartyId, PartyId, Vec<u8>)>,
+}
+
+impl<CG: CurveGroup, H> Default for ReceiverState<CG, H> {
+    fn default() -> Self {
+        Self {
+            dealings: HashMap::new(),
+            selected: None,
+            pending_implicates: vec![],
+        }
+    }
+}
+
+/// Dealings agreed upon by the old committee, along with our share of each of them.
+struct SelectedDealings<CG: CurveGroup, H> {
+    id: PartyId,
+    new: NewCommittee<CG>,
+    g: CG,
+    dealings: BTreeMap<PartyId, Dealing<CG>>,
+    /// Our valid shares, either decrypted or recovered, by dealer
+    shares: BTreeMap<PartyId, CG::ScalarField>,
+    /// Shares of the other members for the dealings that are invalid for us, by dealer
+    recovery: BTreeMap<PartyId, BTreeMap<PartyId, CG::ScalarField>>,
+    /// Implicates already answered, by dealer and member of the new committee
+    answered: BTreeSet<(PartyId, PartyId)>,
+    _h: PhantomData<fn(H) -> H>,
+}
+
+impl<CG, H> SelectedDealings<CG, H>
+where
+    CG: CurveGroup + PointSerializeCompressed + PointDeserializeCompressed,
+    CG::ScalarField: FqSerialize + FqDeserialize,
+    H: Default + DynDigest + FixedOutputReset + BlockSizeUser + Clone,
+{
+    /// Whether we have a valid share of each dealing.
+    fn is_complete(&self) -> bool {
+        self.shares.len() == self.dealings.len()
+    }
+
+    /// Map a transport identifier to an identifier of the new committee.
+    fn new_id(&self, transport_id: PartyId) -> Option<PartyId> {
+        self.new
+            .transport_ids
+            .iter()
+            .position(|i| *i == transport_id)
+            .map(PartyId::from_index)
+    }
+
+    /// Verify the implicate of the j-th member of the new committee, and return our serialized
+    /// share of the dealing if the dealing is indeed invalid for that member.
+    fn answer_implicate(
+        &mut self,
+        j: PartyId,
+        dealer: PartyId,
+        implicate: &[u8],
+    ) -> Option<Vec<u8>> {
+        let dealing = self.dealings.get(&dealer)?;
+        let share = self.shares.get(&dealer)?;
+        if self.answered.contains(&(dealer, j)) {
+            return None;
+        }
+
+        let Implicate { shared_key, pi } = match bson::from_slice::<Implicate<CG, H>>(implicate) {
+            Ok(implicate) => implicate,
+            Err(e) => {
+                warn!(%j, %dealer, "Failed to deserialize implicate: {e:?}");
+                return None;
+            }
+        };
+        if pi
+            .verify(
+                &self.g,
+                &dealing.enc_shares.sender_pk,
+                &self.new.pks[j],
+                &shared_key,
+                IMPLICATE_DLEQ_DST,
+            )
+            .is_err()
+        {
+            warn!(%j, %dealer, "Cannot validate implicate: invalid proof");
+            return None;
+        }
+        if decrypt_verify_share(&self.new, &self.g, j, dealer, dealing, &shared_key).is_ok() {
+            warn!(%j, %dealer, "Cannot validate implicate: dealer share is valid");
+            return None;
+        }
+
+        warn!(%j, %dealer, "Dealer sent an invalid share, helping to recover it");
+        self.answered.insert((dealer, j));
+        bson::to_vec(&DealtShare { share: *share })
+            .inspect_err(|e| error!("Failed to serialize share for recovery: {e:?}"))
+            .ok()
+    }
+
+    /// Handle the implicate of a dealing by a member of the new committee, and send it our share
+    /// of the dealing if the implicate is valid.
+    async fn on_implicate(
+        &mut self,
+        complainer: PartyId,
+        dealer: PartyId,
+        implicate: &[u8],
+        sender: &impl TransportSender<Identity = PartyId>,
+        cancel: &CancellationToken,
+    ) {
+        let Some(j) = self.new_id(complainer) else {
+            warn!(sender = %complainer, "Ignoring implicate from node outside of new committee");
+            return;
+        };
+        let Some(share) = self.answer_implicate(j, dealer, implicate) else {
+            return;
+        };
+
+        if let Err(e) = send_serialize_helper(
+            &ReshareMessage::Recovery { dealer, share },
+            Recipient::Single(complainer),
+            &RetryStrategy::None,
+            cancel,
+            sender,
+        )
+        .await
+        {
+            error!(
+                "Node `{}` failed to send recovery share to node `{complainer}`: {e:?}",
+                self.id
+            );
+        }
+    }
+
+    /// Handle the share of a dealing that is invalid for us, and recover our share once we have
+    /// t_reconstruction' + 1 valid shares.
+    fn on_recovery(&mut self, helper: PartyId, dealer: PartyId, share: &[u8]) {
+        let Some(k) = self.new_id(helper) else {
+            warn!(sender = %helper, "Ignoring recovery share from node outside of new committee");
+            return;
+        };
+        let Some(dealing) = self.dealings.get(&dealer) else {
+            return;
+        };
+        if self.shares.contains_key(&dealer) {
+            return;
+        }
+
+        let share = match bson::from_slice::<DealtShare<CG::ScalarField>>(share) {
+            Ok(DealtShare { share }) => share,
+            Err(e) => {
+                warn!(%helper, %dealer, "Failed to deserialize recovery share: {e:?}");
+                return;
+            }
+        };
+        if feldman::eval_verify(&dealing.public_poly, k, &share, &self.g).is_err() {
+            warn!(%helper, %dealer, "Received an invalid recovery share");
+            return;
+        }
+
+        let points = self.recovery.entry(dealer).or_default();
+        points.insert(k, share);
+        if points.len() > self.new.t_reconstruction {
+            let points: Vec<_> = points.iter().map(|(k, s)| (u64::from(k), *s)).collect();
+            let share = lagrange_interpolate_at::<CG>(&points, u64::from(self.id));
+            info!(
+                "Node `{}` recovered its share of the dealing of node `{dealer}`",
+                self.id
+            );
+            self.shares.insert(dealer, share);
+            self.recovery.remove(&dealer);
+        }
+    }
+
+    /// Keep answering the implicates of the other members of the new committee until cancelled.
+    async fn answer_implicates<E: std::fmt::Debug>(
+        mut self,
+        mut receiver: impl Stream<Item = Result<ReceivedMessage<PartyId>, E>> + Unpin,
+        sender: impl TransportSender<Identity = PartyId>,
+        cancel: CancellationToken,
+    ) {
+        loop {
+            let msg = tokio::select! {
+                _ = cancel.cancelled() => return,
+                msg = receiver.next() => msg,
+            };
+
+            let ReceivedMessage {
+                sender: sender_id,
+                content,
+                ..
+            } = match msg {
+                Some(Ok(msg)) => msg,
+                Some(Err(e)) => {
+                    warn!("Failed to recv reshare message: {e:?}");
+                    continue;
+                }
+                None => return,
+            };
+
+            if let Ok(ReshareMessage::Implicate { dealer, implicate }) =
+                bson::from_slice::<ReshareMessage>(&content)
+            {
+                self.on_implicate(sender_id, dealer, &implicate, &sender, &cancel)
+                    .await;
+            }
+        }
+    }
+}
+
+impl DealerState {
+    /// Once all the ABAs have completed, and the dealings of each selected dealer have been
+    /// obtained, send the first t_reconstruction + 1 dealings to the new committee.
+    async fn try_send_dealings<CG>(
+        &mut self,
+        old: &OldCommittee<CG>,
+        new: &NewCommittee<CG>,
+        sender: &impl TransportSender<Identity = PartyId>,
+        cancel: &CancellationToken,
+    ) {
+        if self.dealings_sent || self.decided.len() < old.n {
+            return;
+        }
+
+        let selected: Vec<_> = self
+            .decided
+            .iter()
+            .filter(|(_, e)| matches!(e, Estimate::One))
+            .map(|(sid, _)| *sid)
+            .take(old.t_reconstruction + 1)
+            .collect();
+        let Some(dealings) = selected
+            .iter()
+            .map(|sid| Some((PartyId::from(*sid), self.rbc_outputs.get(sid)?.clone())))
+            .collect::<Option<Vec<_>>>()
+        else {
+            // Some dealings are still missing
+            return;
+        };
+
+        self.dealings_sent = true;
+        if let Err(e) = broadcast_with_self(
+            &ReshareMessage::Dealings(dealings),
+            transport_n(old, new),
+            &RetryStrategy::None,
+            cancel,
+            sender,
+        )
+        .await
+        {
+            error!("Failed to send dealings to the new committee: {e:?}");
+        }
+    }
+}
+
+#[async_trait]
+impl<CG> RbcPredicate for DealingPredicate<CG>
+where
+    CG: CurveGroup + PointDeserializeCompressed,
+{
+    async fn predicate(&self, sender: PartyId, m: &[u8]) -> bool {
+        if sender != self.expected_sender {
+            return false;
+        }
+
+        let dealing: Dealing<CG> = match bson::from_slice(m) {
+            Ok(dealing) => dealing,
+            Err(e) => {
+                warn!(
+                    "Dealing with invalid format rejected by the rbc predicate: bson error {e:?}"
+                );
+                return false;
+            }
+        };
+
+        // The dealing must share the sender's share towards each member of the new committee
+        dealing.public_poly.len() == self.t_reconstruction_new + 1
+            && dealing.public_poly[0] == self.sender_pk
+            && dealing.enc_shares.inner.cts.len() == self.n_new
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aba::crain20::AbaCrain20Config;
+    use crate::rand::get_rng;
+    use crate::rbc::r4::Rbc4RoundsConfig;
+    use ark_ec::PrimeGroup;
+    use ark_std::UniformRand;
+    use dcipher_network::topic::dispatcher::TopicDispatcher;
+    use dcipher_network::transports::in_memory::MemoryNetwork;
+    use tokio::task::JoinSet;
+    use tracing_subscriber::EnvFilter;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 32)]
+    async fn reshare_test_bn254() {
+        _ = tracing_subscriber::fmt()
+            .with_env_filter(
+                EnvFilter::try_from_env("ADKG_DEBUG").unwrap_or_else(|_| "warn".parse().unwrap()),
+            )
+            .try_init();
+
+        type CG = ark_bn254::G1Projective;
+        type H = sha3::Sha3_256;
+        type F = <CG as PrimeGroup>::ScalarField;
+        const SEED: &[u8] = b"RESHARE_BN254_TEST_SEED";
+
+        let g = CG::generator();
+        let mut rng = rand::thread_rng();
+
+        // The old committee holds a sharing of degree 1 of a random secret.
+        let (n_old, t_old, t_rec_old) = (4, 1, 1);
+        let secret = F::rand(&mut rng);
+        let old_sharing = feldman::share(&secret, &g, n_old, t_rec_old, &mut rng);
+        let old = OldCommittee {
+            n: n_old,
+            t: t_old,
+            t_reconstruction: t_rec_old,
+            group_pk: g * secret,
+            node_pks: PartyId::iter_all(n_old)
+                .map(|i| g * old_sharing.get_party_secrets(i).unwrap())
+                .collect(),
+        };
+
+        // Node 1 leaves, nodes 2 to 4 stay, and nodes 5 to 8 join the new committee.
+        let (n_new, t_new, t_rec_new) = (7, 2, 4);
+        let transport_ids: Vec<PartyId> = (2..=8).map(PartyId).collect();
+        let new_sks: Vec<F> = (0..n_new).map(|_| F::rand(&mut rng)).collect();
+        let new = NewCommittee {
+            n: n_new,
+            t: t_new,
+            t_reconstruction: t_rec_new,
+            pks: new_sks.iter().map(|sk| g * sk).collect(),
+            transport_ids: transport_ids.clone(),
+        };
+
+        let (dispatchers, tbts): (Vec<_>, Vec<_>) =
+            MemoryNetwork::get_transports(PartyId::iter_all(8))
+                .into_iter()
+                .map(|t| {
+                    let mut dispatcher = TopicDispatcher::new();
+                    let tbt = dispatcher.start(t);
+                    (dispatcher, tbt)
+                })
+                .collect();
+
+        let mut tasks = JoinSet::new();
+        for (id, transport) in PartyId::iter_all(8).zip(tbts) {
+            let dealer = (usize::from(id) <= n_old)
+                .then(|| (id, *old_sharing.get_party_secrets(id).unwrap()));
+            let receiver = transport_ids
+                .iter()
+                .position(|i| *i == id)
+                .map(|j| (PartyId::from_index(j), new_sks[j]));
+
+            let mut reshare = Reshare::<CG, H, _, _>::new(
+                old.clone(),
+                new.clone(),
+                g,
+                dealer,
+                receiver,
+                Rbc4RoundsConfig::new(id, n_old, t_old, &RetryStrategy::None),
+                AbaCrain20Config::<EcdhCoinToss<CG, H>, _>::new(
+                    id,
+                    n_old,
+                    t_old,
+                    RetryStrategy::None,
+                ),
+            )
+            .unwrap();
+            let mut rng = get_rng(id, SEED);
+            tasks.spawn(async move {
+                let out = reshare
+                    .reshare(&mut rng, Arc::new(transport), &CancellationToken::new())
+                    .await;
+                (id, out, reshare)
+            });
+        }
+
+        let mut outputs = BTreeMap::new();
+        let mut instances = vec![];
+        while let Some(res) = tasks.join_next().await {
+            let (id, out, reshare) = res.unwrap();
+            if let Some(out) = out.unwrap() {
+                outputs.insert(id, out);
+            }
+            instances.push(reshare);
+        }
+        for reshare in instances {
+            reshare.stop().await;
+        }
+        for d in dispatchers {
+            d.stop().await;
+        }
+
+        // Only the members of the new committee obtain an output
+        assert_eq!(outputs.keys().copied().collect::<Vec<_>>(), transport_ids);
+
+        // The group public key is preserved, and the node public keys match the new shares
+        let node_pks = outputs[&transport_ids[0]].node_pks.clone().unwrap();
+        for (j, id) in transport_ids.iter().enumerate() {
+            let out = &outputs[id];
+            assert_eq!(out.group_pk.unwrap(), old.group_pk);
+            assert_eq!(out.node_pks.as_ref().unwrap(), &node_pks);
+            assert_eq!(node_pks[j], g * out.sk);
+        }
+
+        // Any t_reconstruction' + 1 new shares reconstruct the group secret
+        let points: Vec<(u64, F)> = transport_ids
+            .iter()
+            .enumerate()
+            .rev()
+            .take(t_rec_new + 1)
+            .map(|(j, id)| (PartyId::from_index(j).into(), outputs[id].sk))
+            .collect();
+        assert_eq!(lagrange_interpolate_at::<CG>(&points, 0), secret);
+    }
+
+    #[test]
+    fn reshare_rejects_invalid_parameters() {
+        type CG = ark_bn254::G1Projective;
+        type H = sha3::Sha3_256;
+
+        let g = CG::generator();
+        let old = OldCommittee {
+            n: 4,
+            t: 1,
+            t_reconstruction: 1,
+            group_pk: g,
+            node_pks: vec![g; 4],
+        };
+
+        // n' = 3 cannot tolerate t' = 1
+        let new = NewCommittee {
+            n: 3,
+            t: 1,
+            t_reconstruction: 1,
+            pks: vec![g; 3],
+            transport_ids: (5..=7).map(PartyId).collect(),
+        };
+
+        let id = PartyId(1);
+        let res = Reshare::<CG, H, _, _>::new(
+            old,
+            new,
+            g,
+            Some((id, Default::default())),
+            None,
+            Rbc4RoundsConfig::new(id, 4, 1, &RetryStrategy::None),
+            AbaCrain20Config::<EcdhCoinToss<CG, H>, _>::new(id, 4, 1, RetryStrategy::None),
+        );
+        assert!(matches!(
+            res.map_err(|e| *e),
+            Err(ReshareError::InvalidParameters(_))
+        ));
+    }
+
+    #[test]
+    fn invalid_share_is_recovered_from_implicate() {
+        type CG = ark_bn254::G1Projective;
+        type H = sha3::Sha3_256;
+        type F = <CG as PrimeGroup>::ScalarField;
+
+        let g = CG::generator();
+        let mut rng = rand::thread_rng();
+
+        let (n, t, t_rec) = (4, 1, 1);
+        let sks: Vec<F> = (0..n).map(|_| F::rand(&mut rng)).collect();
+        let new = NewCommittee {
+            n,
+            t,
+            t_reconstruction: t_rec,
+            pks: sks.iter().map(|sk| g * sk).collect(),
+            transport_ids: PartyId::iter_all(n).collect(),
+        };
+        let receiver = |j: PartyId| Receiver::<CG> { id: j, sk: sks[j] };
+
+        // The dealer sends an invalid share to the first member of the new committee
+        let dealer = PartyId(1);
+        let vss_share = feldman::share(&F::rand(&mut rng), &g, n, t_rec, &mut rng);
+        let shares: Vec<_> = PartyId::iter_all(n)
+            .map(|j| {
+                let share = *vss_share.get_party_secrets(j).unwrap();
+                let share = if j == PartyId(1) {
+                    share + F::from(1u64)
+                } else {
+                    share
+                };
+                bson::to_vec(&DealtShare { share }).unwrap()
+            })
+            .collect();
+        let dealing = bson::to_vec(&Dealing {
+            public_poly: vss_share.get_public_poly().to_vec(),
+            enc_shares: ec_hybrid_chacha20poly1305::encrypt_multi(&shares, &new.pks, &g, &mut rng)
+                .unwrap(),
+        })
+        .unwrap();
+
+        let mut members: Vec<SelectedDealings<CG, H>> = PartyId::iter_all(n)
+            .map(|j| {
+                let dealing: Dealing<CG> = bson::from_slice(&dealing).unwrap();
+                let shared_key = dealing.enc_shares.derive_shared_key(&sks[j]);
+                let shares = decrypt_verify_share(&new, &g, j, dealer, &dealing, &shared_key)
+                    .ok()
+                    .map(|share| (dealer, share))
+                    .into_iter()
+                    .collect();
+                SelectedDealings {
+                    id: j,
+                    new: new.clone(),
+                    g,
+                    dealings: BTreeMap::from([(dealer, dealing)]),
+                    shares,
+                    recovery: BTreeMap::new(),
+                    answered: BTreeSet::new(),
+                    _h: PhantomData,
+                }
+            })
+            .collect();
+        assert!(!members[0].is_complete());
+        assert!(members[1..].iter().all(|m| m.is_complete()));
+
+        // Implicates of valid shares, or with a proof for another member, are not answered
+        let valid_implicate = implicate::<CG, H>(
+            &new,
+            &g,
+            &receiver(PartyId(2)),
+            &members[1].dealings[&dealer],
+            &mut rng,
+        )
+        .unwrap();
+        assert!(
+            members[2]
+                .answer_implicate(PartyId(2), dealer, &valid_implicate)
+                .is_none()
+        );
+        assert!(
+            members[2]
+                .answer_implicate(PartyId(1), dealer, &valid_implicate)
+                .is_none()
+        );
+
+        // The other members answer the implicate of the first member once
+        let implicate = implicate::<CG, H>(
+            &new,
+            &g,
+            &receiver(PartyId(1)),
+            &members[0].dealings[&dealer],
+            &mut rng,
+        )
+        .unwrap();
+        let recovery: Vec<_> = members[1..]
+            .iter_mut()
+            .map(|m| {
+                let share = m.answer_implicate(PartyId(1), dealer, &implicate);
+                (m.id, share.expect("implicate should be valid"))
+            })
+            .collect();
+        assert!(
+            members[1]
+                .answer_implicate(PartyId(1), dealer, &implicate)
+                .is_none()
+        );
+
+        // t_reconstruction' + 1 shares recover the valid share of the first member
+        for (k, share) in recovery.iter().take(t_rec + 1) {
+            assert!(!members[0].is_complete());
+            members[0].on_recovery(*k, dealer, share);
+        }
+        assert!(members[0].is_complete());
+        assert_eq!(
+            members[0].shares[&dealer],
+            *vss_share.get_party_secrets(PartyId(1)).unwrap()
+        );
+    }
+}
@@ -317,3 +317,40 @@ pub mod bls12_381 {
 
     pub type DXKR23Bls12_381G1Sha256 = DXKR23Scheme<ark_bls12_381::G1Projective, sha2::Sha256>;
 }
+
+#[cfg(feature = "secp256k1")]
+pub mod secp256k1 {
+    use super::*;
+
+    /// ADKG over secp256k1, the resulting keys can be used by threshold Schnorr / ECDSA protocols.
+    pub type DXKR23Secp256k1Sha256 = DXKR23Scheme<ark_secp256k1::Projective, sha2::Sha256>;
+}
+
+#[cfg(all(test, feature = "secp256k1"))]
+mod tests {
+    use super::secp256k1::DXKR23Secp256k1Sha256;
+    use super::*;
+
+    #[test]
+    fn secp256k1_scheme_config_roundtrip() {
+        let scheme = DXKR23Secp256k1Sha256::new("test".to_owned());
+        let (g, h) = (scheme.generator_g(), scheme.generator_h());
+        assert_ne!(g, h);
+
+        let config: AdkgSchemeConfig = scheme.into();
+        assert_eq!(config.curve_id, CurveId::Secp256k1);
+        assert_eq!(config.hash_id, HashId::Sha256);
+
+        let scheme = DXKR23Secp256k1Sha256::try_from(config.clone()).unwrap();
+        assert_eq!(scheme.generator_g(), g);
+        assert_eq!(scheme.generator_h(), h);
+
+        // Generators from another application must be rejected
+        let mut other: AdkgSchemeConfig = DXKR23Secp256k1Sha256::new("other".to_owned()).into();
+        other.app_name = config.app_name;
+        assert!(matches!(
+            DXKR23Secp256k1Sha256::try_from(other),
+            Err(SchemeError::BadGenerator)
+        ));
+    }
+}
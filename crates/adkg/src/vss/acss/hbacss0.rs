@@ -3,6 +3,7 @@ mod handlers;
 mod types;
 
 // Re-export public types
+pub(crate) use types::AcssBroadcastMessage;
 pub use types::{AcssError, AcssMessage, ImplicateMessage};
 
 use super::{Acss, AcssConfig};
@@ -34,14 +35,14 @@ use tokio::select;
 use tokio::sync::oneshot;
 use tokio_util::sync::CancellationToken;
 use tracing::{error, info, warn};
-use types::{AcssBroadcastMessage, AcssStatus, PedVerifyPredicate, StateMachine};
+use types::{AcssStatus, PedVerifyPredicate, StateMachine};
 use utils::dst::{NamedCurveGroup, NamedDynDigest, Rfc9380DstBuilder};
 use utils::serialize::{
     fq::{FqDeserialize, FqSerialize},
     point::{PointDeserializeCompressed, PointSerializeCompressed},
 };
 
-const TOPIC: &str = "hbacss0";
+pub(crate) const TOPIC: &str = "hbacss0";
 const APPNAME: &[u8] = b"HBACSS0-v1";
 const NIZK_DLEQ_SUFFIX: &[u8] = b"NIZK_DLEQ";
 
@@ -221,6 +222,7 @@ where
 {
     config: Arc<HbAcss0Config<CG, H, RBCConfig>>,
     sender: TS,
+    cancel: CancellationToken,
 }
 
 impl<CG, H, RBCConfig, RBC, T> Acss<CG, PartyId> for HbAcss0<CG, H, RBCConfig, RBC, T>
@@ -324,7 +326,7 @@ where
     async fn acss_dealer<RNG>(
         mut self,
         s: Hbacss0Input<CG::ScalarField>,
-        rbc_cancel: CancellationToken,
+        cancel: CancellationToken,
         output: oneshot::Sender<Hbacss0Output<CG>>,
         rng: &mut RNG,
     ) -> Result<(), Box<AcssError>>
@@ -391,13 +393,13 @@ where
         // Disperse encrypted shares and public polynomial through the broadcast channel
         let broadcast = AcssBroadcastMessage {
             enc_shares,
-            feld_public_poly: feld_public_poly.clone(),
-            ped_public_polys: ped_public_polys.clone(),
+            feld_public_poly,
+            ped_public_polys,
         };
         let m = bson::to_vec(&broadcast)
             .map_err(|e| AcssError::BsonSer(e, "dealer failed to serialize broadcast message"))?; // unexpected error, abort ACSS
 
-        match self.rbc.take().unwrap().start(&m, rbc_cancel).await {
+        match self.rbc.take().unwrap().start(&m, cancel.clone()).await {
             Ok(mp) => {
                 if mp != m {
                     error!("Leader obtained an invalid m after RBC");
@@ -413,22 +415,15 @@ where
         // Continue the execution of the acss protocol as a normal participant.
         let id = self.config.id;
         let shares = get_party_shares(id);
-        self.acss_continue(
-            Some(shares),
-            output,
-            &broadcast.enc_shares,
-            feld_public_poly,
-            ped_public_polys,
-            rng,
-        )
-        .await
+        self.acss_continue(Some(shares), cancel, output, broadcast, rng)
+            .await
     }
 
     /// Participate in the ACSS protocol to recover a secret share.
     async fn acss_client_participate<RNG>(
         mut self,
         expected_broadcaster: PartyId,
-        rbc_cancel: CancellationToken,
+        cancel: CancellationToken,
         output: oneshot::Sender<Hbacss0Output<CG>>,
         rng: &mut RNG,
     ) -> Result<(), Box<AcssError>>
@@ -451,7 +446,7 @@ where
             .rbc
             .take()
             .unwrap()
-            .listen(&pred, expected_broadcaster, rbc_cancel)
+            .listen(&pred, expected_broadcaster, cancel.clone())
             .await
             .map_err(|e| {
                 error!(
@@ -471,17 +466,14 @@ where
         })?;
 
         // Decrypt and validate share
-        let enc_shares = &m.enc_shares;
-        let shared_key = enc_shares.derive_shared_key(&self.config.sk);
-        let feld_public_poly = m.feld_public_poly;
-        let ped_public_polys = m.ped_public_polys;
+        let shared_key = m.enc_shares.derive_shared_key(&self.config.sk);
 
         // If the share is valid, the nodes enters the reconstruction process
         // otherwise, the node enters the recovery process
         let shares = dual_eval_verify(
-            enc_shares,
-            &feld_public_poly,
-            ped_public_polys.iter(),
+            &m.enc_shares,
+            &m.feld_public_poly,
+            m.ped_public_polys.iter(),
             &self.config.g,
             &self.config.h,
             self.config.id,
@@ -489,25 +481,16 @@ where
             &self.config.pks[self.config.id],
         )
         .ok();
-        self.acss_continue(
-            shares,
-            output,
-            &m.enc_shares,
-            feld_public_poly,
-            ped_public_polys,
-            rng,
-        )
-        .await
+        self.acss_continue(shares, cancel, output, m, rng).await
     }
 
     /// Execute the agreement / implication / share recovery part of the protocol.
     async fn acss_continue<RNG>(
         self,
         shares: Option<PartyShares<CG::ScalarField>>,
+        cancel: CancellationToken,
         output: oneshot::Sender<Hbacss0Output<CG>>,
-        enc_shares: &EphemeralMultiHybridCiphertext<CG>,
-        feld_public_poly: FeldPublicPoly<CG>,
-        ped_public_polys: Vec<PedPublicPoly<CG>>,
+        broadcast: AcssBroadcastMessage<CG>,
         rng: &mut RNG,
     ) -> Result<(), Box<AcssError>>
     where
@@ -522,9 +505,15 @@ where
             config,
             ..
         } = self;
+        let AcssBroadcastMessage {
+            enc_shares,
+            feld_public_poly,
+            ped_public_polys,
+        } = broadcast;
         let hbacss0 = HbAcss0Instance {
             config: config.clone(),
             sender,
+            cancel,
         };
 
         let mut state_machine = StateMachine::<CG> {
@@ -547,8 +536,14 @@ where
             state_machine.status = AcssStatus::WaitingForOks(shares);
 
             // Share is valid, send Ok to all other nodes
-            if let Err(e) =
-                broadcast_with_self(&AcssMessage::Ok, &config.retry_strategy, &hbacss0.sender).await
+            if let Err(e) = broadcast_with_self(
+                &AcssMessage::Ok,
+                config.n,
+                &config.retry_strategy,
+                &hbacss0.cancel,
+                &hbacss0.sender,
+            )
+            .await
             {
                 error!("Node `{}` failed to broadcast ok message: {e:?}", config.id)
             }
@@ -579,8 +574,14 @@ where
             // Share is invalid, send Implicate to all nodes
             let implicate_msg =
                 AcssMessage::Implicate(ImplicateMessage::from_curvegroup(&pi, &shared_secret)?);
-            if let Err(e) =
-                broadcast_with_self(&implicate_msg, &config.retry_strategy, &hbacss0.sender).await
+            if let Err(e) = broadcast_with_self(
+                &implicate_msg,
+                config.n,
+                &config.retry_strategy,
+                &hbacss0.cancel,
+                &hbacss0.sender,
+            )
+            .await
             {
                 error!(
                     "Node `{}` failed to broadcast implicate message: {e:?}",
@@ -632,7 +633,7 @@ where
                     hbacss0
                         .implicate_handler(
                             &ski,
-                            enc_shares,
+                            &enc_shares,
                             &feld_public_poly,
                             &ped_public_polys,
                             sender,
@@ -645,7 +646,7 @@ where
                     hbacss0
                         .recovery_handler(
                             &shared_key,
-                            enc_shares,
+                            &enc_shares,
                             &feld_public_poly,
                             &ped_public_polys,
                             sender,
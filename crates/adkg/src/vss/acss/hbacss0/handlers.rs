@@ -53,7 +53,9 @@ where
 
                 if let Err(e) = broadcast_with_self(
                     &AcssMessage::Ready,
+                    self.config.n,
                     &self.config.retry_strategy,
+                    &self.cancel,
                     &self.sender,
                 )
                 .await
@@ -114,7 +116,9 @@ where
 
                     if let Err(e) = broadcast_with_self(
                         &AcssMessage::Ready,
+                        self.config.n,
                         &self.config.retry_strategy,
+                        &self.cancel,
                         &self.sender,
                     )
                     .await
@@ -305,8 +309,14 @@ where
                 return;
             }
         };
-        if let Err(e) =
-            broadcast_with_self(&msg_recovery, &self.config.retry_strategy, &self.sender).await
+        if let Err(e) = broadcast_with_self(
+            &msg_recovery,
+            self.config.n,
+            &self.config.retry_strategy,
+            &self.cancel,
+            &self.sender,
+        )
+        .await
         {
             error!(
                 "Node `{}` failed to send share recovery message to node `{sender}`: {e:?}",
@@ -425,7 +435,9 @@ where
 
             if let Err(e) = broadcast_with_self(
                 &AcssMessage::Ready,
+                self.config.n,
                 &self.config.retry_strategy,
+                &self.cancel,
                 &self.sender,
             )
             .await
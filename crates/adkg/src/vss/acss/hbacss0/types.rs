@@ -103,10 +103,10 @@ pub(super) enum AcssStatus<CG: CurveGroup> {
     serialize = "CG: PointSerializeCompressed",
     deserialize = "CG: PointDeserializeCompressed"
 ))]
-pub(super) struct AcssBroadcastMessage<CG: CurveGroup> {
-    pub(super) enc_shares: EphemeralMultiHybridCiphertext<CG>,
-    pub(super) feld_public_poly: FeldPublicPoly<CG>,
-    pub(super) ped_public_polys: Vec<PedPublicPoly<CG>>,
+pub(crate) struct AcssBroadcastMessage<CG: CurveGroup> {
+    pub(crate) enc_shares: EphemeralMultiHybridCiphertext<CG>,
+    pub(crate) feld_public_poly: FeldPublicPoly<CG>,
+    pub(crate) ped_public_polys: Vec<PedPublicPoly<CG>>,
 }
 
 /// Shares obtained by the ACSS
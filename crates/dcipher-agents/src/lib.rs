@@ -13,6 +13,9 @@ pub mod fulfiller;
 #[cfg(feature = "ibe")]
 pub mod ibe_helper;
 
+#[cfg(feature = "remote_signer")]
+pub mod remote_signer;
+
 #[cfg(feature = "evm")]
 pub mod ser;
 
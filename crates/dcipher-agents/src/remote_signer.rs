@@ -0,0 +1,351 @@
+//! [`DSignerSchemeSigner`] backed by a remote dsigner service, such that the agents can request
+//! signatures from a dsigner cluster without holding a key share.
+
+use bytes::Bytes;
+use dcipher_signer::bls::verify::{VerifySignatureError, verify_signature};
+use dcipher_signer::dsigner::{
+    ApplicationArgs, DSignerSchemeError, DSignerSchemeSigner, SignatureAlgorithm, SignatureRequest,
+    VerificationParameters,
+};
+use dsigner::client::DSignerClient;
+use dsigner::proto_types::{
+    self, GetSignatureRequest, GetVerificationParametersRequest, ParseProtoError,
+};
+use futures_util::FutureExt;
+use futures_util::future::BoxFuture;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tonic::transport::Endpoint;
+use tonic::{Code, Status};
+
+/// Default timeout of a single request to the remote dsigner.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default number of retries after a transient failure.
+const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// Default delay before the first retry, doubled after each attempt.
+const DEFAULT_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+#[derive(thiserror::Error, Debug)]
+pub enum RemoteDSignerError {
+    #[error("invalid remote dsigner endpoint")]
+    Endpoint(#[from] tonic::transport::Error),
+
+    #[error("remote dsigner returned an error")]
+    Status(#[from] Status),
+
+    #[error("request to the remote dsigner timed out")]
+    Timeout,
+
+    #[error("failed to convert request to protobuf")]
+    Proto(#[from] ParseProtoError),
+
+    #[error("remote dsigner returned an invalid signature")]
+    InvalidSignature,
+
+    #[error("failed to verify the signature of the remote dsigner")]
+    Verification(#[from] VerifySignatureError),
+}
+
+impl From<RemoteDSignerError> for DSignerSchemeError {
+    fn from(error: RemoteDSignerError) -> Self {
+        match error {
+            RemoteDSignerError::Proto(ParseProtoError::UnsupportedApplication) => {
+                DSignerSchemeError::ApplicationNotSupported
+            }
+            _ => DSignerSchemeError::Other(error.into()),
+        }
+    }
+}
+
+/// Requests signatures from a scheme of a remote dsigner service, and verifies them using the
+/// verification parameters exposed by the service.
+pub struct RemoteDSigner {
+    client: DSignerClient,
+    scheme_id: String,
+    timeout: Duration,
+    max_retries: u32,
+    retry_delay: Duration,
+    verify_signatures: bool,
+    verification_params:
+        Mutex<HashMap<(SignatureAlgorithm, ApplicationArgs), VerificationParameters>>,
+}
+
+impl RemoteDSigner {
+    pub fn new(client: DSignerClient, scheme_id: impl Into<String>) -> Self {
+        Self {
+            client,
+            scheme_id: scheme_id.into(),
+            timeout: DEFAULT_TIMEOUT,
+            max_retries: DEFAULT_MAX_RETRIES,
+            retry_delay: DEFAULT_RETRY_DELAY,
+            verify_signatures: true,
+            verification_params: Mutex::default(),
+        }
+    }
+
+    /// Create a signer for a scheme of the dsigner service at `endpoint`, optionally authenticating
+    /// with an API key. The connection is established upon the first request, such that the
+    /// service does not need to be available yet.
+    pub fn from_endpoint(
+        endpoint: &str,
+        scheme_id: impl Into<String>,
+        api_key: Option<&str>,
+    ) -> Result<Self, RemoteDSignerError> {
+        let channel = Endpoint::from_shared(endpoint.to_owned())?.connect_lazy();
+        let mut client = DSignerClient::new(channel);
+        if let Some(api_key) = api_key {
+            client = client.with_api_key(api_key)?;
+        }
+
+        Ok(Self::new(client, scheme_id))
+    }
+
+    /// Timeout of a single request to the remote dsigner, including the time required by the
+    /// cluster to produce the signature.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Retry the requests that failed with a transient error up to `max_retries` times, with an
+    /// exponential backoff starting at `retry_delay`.
+    pub fn with_retries(mut self, max_retries: u32, retry_delay: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.retry_delay = retry_delay;
+        self
+    }
+
+    /// Trust the signatures returned by the remote dsigner, e.g., when they are verified by the
+    /// application, or for algorithms that cannot be verified locally.
+    pub fn without_signature_verification(mut self) -> Self {
+        self.verify_signatures = false;
+        self
+    }
+
+    async fn sign(&self, req: SignatureRequest) -> Result<Bytes, RemoteDSignerError> {
+        let app_args = proto_types::ApplicationArgs::try_from(req.args.clone())?;
+        let alg = proto_types::encode_signature_algorithm(req.alg);
+
+        let sig = self
+            .call_with_retries("get_signature", |mut client| {
+                let request = GetSignatureRequest {
+                    scheme_id: self.scheme_id.clone(),
+                    alg,
+                    message: req.m.clone(),
+                    app_args: Some(app_args.clone()),
+                    ..Default::default()
+                };
+                async move { client.get_signature(request).await }
+            })
+            .await?;
+
+        if self.verify_signatures {
+            let params = self
+                .verification_parameters(&req.alg, &req.args, alg, app_args)
+                .await?;
+            if !verify_signature(&req.m, &sig, &req.alg, &params)? {
+                tracing::error!(scheme_id = self.scheme_id, alg = ?req.alg, args = ?req.args, "Remote dsigner returned an invalid signature");
+                Err(RemoteDSignerError::InvalidSignature)?
+            }
+        }
+
+        Ok(sig)
+    }
+
+    /// Obtain the verification parameters of an algorithm and application from the cache, or
+    /// from the remote dsigner.
+    async fn verification_parameters(
+        &self,
+        alg: &SignatureAlgorithm,
+        args: &ApplicationArgs,
+        proto_alg: i32,
+        proto_args: proto_types::ApplicationArgs,
+    ) -> Result<VerificationParameters, RemoteDSignerError> {
+        let key = (*alg, args.clone());
+        let cached = self
+            .verification_params
+            .lock()
+            .expect("a thread panicked with the mutex")
+            .get(&key)
+            .cloned();
+        if let Some(params) = cached {
+            return Ok(params);
+        }
+
+        let params = self
+            .call_with_retries("get_verification_parameters", |mut client| {
+                let request = GetVerificationParametersRequest {
+                    scheme_id: self.scheme_id.clone(),
+                    alg: proto_alg,
+                    app_args: Some(proto_args.clone()),
+                    ..Default::default()
+                };
+                async move { client.get_verification_parameters(request).await }
+            })
+            .await?;
+
+        let params = VerificationParameters::new(params.public_key, params.dst);
+        self.verification_params
+            .lock()
+            .expect("a thread panicked with the mutex")
+            .insert(key, params.clone());
+        Ok(params)
+    }
+
+    /// Call the remote dsigner, retrying on timeouts and transient errors.
+    async fn call_with_retries<T, F, Fut>(
+        &self,
+        method: &'static str,
+        call: F,
+    ) -> Result<T, RemoteDSignerError>
+    where
+        F: Fn(DSignerClient) -> Fut,
+        Fut: Future<Output = Result<T, Status>>,
+    {
+        let mut retry_delay = self.retry_delay;
+        let mut attempt = 0;
+        loop {
+            let error = match tokio::time::timeout(self.timeout, call(self.client.clone())).await {
+                Ok(Ok(res)) => return Ok(res),
+                Ok(Err(status)) if !is_transient(&status) => return Err(status.into()),
+                Ok(Err(status)) => RemoteDSignerError::Status(status),
+                Err(_) => RemoteDSignerError::Timeout,
+            };
+
+            if attempt >= self.max_retries {
+                return Err(error);
+            }
+            attempt += 1;
+
+            tracing::warn!(method, attempt, error = ?error, ?retry_delay, "Request to remote dsigner failed, retrying");
+            tokio::time::sleep(retry_delay).await;
+            retry_delay = retry_delay.saturating_mul(2);
+        }
+    }
+}
+
+/// Whether the request may succeed if retried.
+fn is_transient(status: &Status) -> bool {
+    matches!(
+        status.code(),
+        Code::Unavailable | Code::DeadlineExceeded | Code::Aborted
+    )
+}
+
+impl DSignerSchemeSigner for RemoteDSigner {
+    fn async_sign(
+        &self,
+        req: SignatureRequest,
+    ) -> BoxFuture<'_, Result<Bytes, DSignerSchemeError>> {
+        async move { Ok(self.sign(req).await?) }.boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ec::AffineRepr;
+    use ark_ff::MontFp;
+    use dcipher_network::transports::in_memory::MemoryNetwork;
+    use dcipher_signer::bls::{BlsPairingSigner, BlsThresholdSigner};
+    use dcipher_signer::dsigner::{
+        ApplicationRandomnessArgs, BlsSignatureAlgorithm, BlsSignatureCurve, BlsSignatureHash,
+    };
+    use dsigner::proto_types::d_signer_service_server::DSignerServiceServer;
+    use dsigner::server::DSignerSchemeManager;
+    use dsigner::server::grpc::DSignerServiceImpl;
+    use std::sync::Arc;
+    use tokio_stream::wrappers::TcpListenerStream;
+    use tonic::transport::{Channel, Server};
+
+    async fn start_service() -> DSignerClient {
+        let sk: ark_bn254::Fr =
+            MontFp!("7685086713915354683875500702831995067084988389812060097318430034144315778947");
+        let signer = BlsThresholdSigner::new(
+            BlsPairingSigner::<ark_bn254::Bn254>::new(sk),
+            1,
+            1,
+            1,
+            HashMap::from([(1, (ark_bn254::G1Affine::generator() * sk).into())]),
+            HashMap::from([(1, (ark_bn254::G2Affine::generator() * sk).into())]),
+        );
+        let transport = MemoryNetwork::get_transports(1u16..2u16)
+            .pop_front()
+            .unwrap();
+        let (_, scheme) = signer.run(transport);
+
+        let manager =
+            DSignerSchemeManager::default().push_scheme("test-bn254".into(), Arc::new(scheme));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(
+            Server::builder()
+                .add_service(DSignerServiceServer::new(DSignerServiceImpl::new(
+                    Arc::new(manager),
+                )))
+                .serve_with_incoming(TcpListenerStream::new(listener)),
+        );
+
+        DSignerClient::connect(format!("http://{addr}"))
+            .await
+            .unwrap()
+    }
+
+    fn request(m: &'static [u8]) -> SignatureRequest {
+        SignatureRequest {
+            m: m.into(),
+            alg: SignatureAlgorithm::Bls(BlsSignatureAlgorithm {
+                curve: BlsSignatureCurve::Bn254G1,
+                hash: BlsSignatureHash::Keccak256,
+                compression: false,
+            }),
+            args: ApplicationArgs::Randomness(ApplicationRandomnessArgs { chain_id: 1 }),
+        }
+    }
+
+    #[tokio::test]
+    async fn remote_signatures() {
+        let client = start_service().await;
+        let signer = RemoteDSigner::new(client.clone(), "test-bn254");
+
+        let sig = DSignerSchemeSigner::async_sign(&signer, request(b"message"))
+            .await
+            .unwrap();
+        let params = signer
+            .verification_params
+            .lock()
+            .unwrap()
+            .values()
+            .next()
+            .cloned()
+            .expect("verification parameters should be cached");
+        assert!(verify_signature(b"message", &sig, &request(b"message").alg, &params).unwrap());
+
+        // Errors returned by the service are not retried
+        let signer = RemoteDSigner::new(client, "unknown-scheme")
+            .with_retries(u32::MAX, Duration::from_secs(3600));
+        assert!(
+            DSignerSchemeSigner::async_sign(&signer, request(b"message"))
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn unavailable_remote_signer() {
+        // Nothing listens on port 1, such that requests fail with transient errors
+        let channel = Channel::from_static("http://127.0.0.1:1").connect_lazy();
+        let signer = RemoteDSigner::new(DSignerClient::new(channel), "test-bn254")
+            .with_timeout(Duration::from_secs(1))
+            .with_retries(2, Duration::from_millis(10));
+
+        let err = signer.sign(request(b"message")).await.unwrap_err();
+        assert!(
+            matches!(&err, RemoteDSignerError::Status(status) if status.code() == Code::Unavailable),
+            "{err:?}"
+        );
+    }
+}
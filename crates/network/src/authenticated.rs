@@ -0,0 +1,551 @@
+//! Authenticated envelopes over any [`Transport`], used to run protocols over transports that do
+//! not authenticate the peers themselves, e.g., relays or the replayable reader.
+//!
+//! Each message is signed with the long-term key of its sender, and bound to a session, to its
+//! recipient, and to a nonce. Messages with an invalid signature, from another session, addressed
+//! to another node, or that were already received are dropped by the receivers, and reported
+//! through [`Metrics`](metrics::Metrics).
+
+pub mod metrics;
+
+use crate::{PartyIdentifier, ReceivedMessage, Recipient, Transport, TransportSender};
+use futures_util::StreamExt;
+use futures_util::stream::BoxStream;
+use metrics::Metrics;
+use prost::Message;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Domain separation tag prepended to the signed payloads.
+const SIGNATURE_DST: &[u8] = b"DCIPHER-NETWORK-AUTHENTICATED-ENVELOPE-V1";
+
+/// Number of nonces below the highest received nonce that are tracked for each sender. Older
+/// messages are rejected.
+const REPLAY_WINDOW: u64 = 1 << 16;
+
+/// Signs the messages sent by the local node with its long-term key.
+pub trait MessageSigner: Send + Sync + 'static {
+    type Error: std::error::Error + Send + Sync + 'static;
+
+    fn sign(&self, msg: &[u8]) -> Result<Vec<u8>, Self::Error>;
+}
+
+/// Verifies the signatures of the messages sent by other nodes.
+pub trait MessageVerifier<I>: Send + Sync + 'static {
+    /// Outputs true if `signature` is a valid signature on `msg` under the long-term key of
+    /// `sender`.
+    fn verify(&self, sender: &I, msg: &[u8], signature: &[u8]) -> bool;
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum AuthenticatedTransportError<E> {
+    #[error("inner transport error")]
+    Transport(#[source] E),
+
+    #[error("failed to sign message")]
+    Signing(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+}
+
+/// Reason for dropping a received message.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum RejectReason {
+    Malformed,
+    WrongSession,
+    WrongRecipient,
+    InvalidSignature,
+    Replay,
+}
+
+impl RejectReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RejectReason::Malformed => "malformed",
+            RejectReason::WrongSession => "wrong_session",
+            RejectReason::WrongRecipient => "wrong_recipient",
+            RejectReason::InvalidSignature => "invalid_signature",
+            RejectReason::Replay => "replay",
+        }
+    }
+}
+
+/// A [`Transport`] wrapping the messages of another transport into authenticated envelopes.
+///
+/// The senders of a session must use distinct nonces, hence, a new session identifier should be
+/// used for each execution of a protocol. Nonces are initialized from the current time, such that
+/// a node restarting during a session is not considered to replay its messages.
+pub struct AuthenticatedTransport<T: Transport, S, V> {
+    transport: T,
+    id: T::Identity,
+    session: Arc<[u8]>,
+    signer: Arc<S>,
+    verifier: Arc<V>,
+    nonce: Arc<AtomicU64>,
+}
+
+impl<T, S, V> AuthenticatedTransport<T, S, V>
+where
+    T: Transport,
+    S: MessageSigner,
+    V: MessageVerifier<T::Identity>,
+{
+    /// Wrap a transport, where `id` is the identifier of the local node on that transport.
+    pub fn new(
+        transport: T,
+        id: T::Identity,
+        session_id: impl Into<Vec<u8>>,
+        signer: S,
+        verifier: V,
+    ) -> Self {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default();
+        Self {
+            transport,
+            id,
+            session: session_id.into().into(),
+            signer: Arc::new(signer),
+            verifier: Arc::new(verifier),
+            nonce: Arc::new(AtomicU64::new(now.as_micros() as u64)),
+        }
+    }
+}
+
+impl<T, S, V> Transport for AuthenticatedTransport<T, S, V>
+where
+    T: Transport,
+    S: MessageSigner,
+    V: MessageVerifier<T::Identity>,
+{
+    type Error = AuthenticatedTransportError<T::Error>;
+    type Identity = T::Identity;
+    type ReceiveMessageStream =
+        BoxStream<'static, Result<ReceivedMessage<Self::Identity>, Self::Error>>;
+    type Sender = AuthenticatedSender<T::Sender, S>;
+
+    fn sender(&mut self) -> Option<Self::Sender> {
+        Some(AuthenticatedSender {
+            sender: self.transport.sender()?,
+            id: self.id,
+            session: self.session.clone(),
+            signer: self.signer.clone(),
+            nonce: self.nonce.clone(),
+        })
+    }
+
+    fn receiver_stream(&mut self) -> Option<Self::ReceiveMessageStream> {
+        let mut opener = EnvelopeOpener {
+            id: self.id,
+            session: self.session.clone(),
+            verifier: self.verifier.clone(),
+            replay_windows: HashMap::new(),
+        };
+
+        let stream = self.transport.receiver_stream()?.filter_map(move |res| {
+            let res = match res {
+                Ok(m) => match opener.open(m) {
+                    Ok(m) => Some(Ok(m)),
+                    Err((sender, reason)) => {
+                        tracing::warn!(%sender, reason = reason.as_str(), "Dropping unauthenticated message");
+                        Metrics::report_rejected_message(reason);
+                        None
+                    }
+                },
+                Err(e) => Some(Err(AuthenticatedTransportError::Transport(e))),
+            };
+            futures_util::future::ready(res)
+        });
+        Some(stream.boxed())
+    }
+}
+
+/// A [`TransportSender`] signing the messages before sending them through the inner sender.
+pub struct AuthenticatedSender<TS: TransportSender, S> {
+    sender: TS,
+    id: TS::Identity,
+    session: Arc<[u8]>,
+    signer: Arc<S>,
+    nonce: Arc<AtomicU64>,
+}
+
+impl<TS, S> Clone for AuthenticatedSender<TS, S>
+where
+    TS: TransportSender + Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+            id: self.id,
+            session: self.session.clone(),
+            signer: self.signer.clone(),
+            nonce: self.nonce.clone(),
+        }
+    }
+}
+
+impl<TS, S> TransportSender for AuthenticatedSender<TS, S>
+where
+    TS: TransportSender,
+    S: MessageSigner,
+{
+    type Identity = TS::Identity;
+    type Error = AuthenticatedTransportError<TS::Error>;
+
+    async fn send(&self, msg: Vec<u8>, to: Recipient<Self::Identity>) -> Result<(), Self::Error> {
+        let recipient = match to {
+            Recipient::All | Recipient::AllIncludingSelf => None,
+            Recipient::Single(i) => Some(i.to_string()),
+        };
+        let nonce = self.nonce.fetch_add(1, Ordering::Relaxed);
+        let payload = signed_payload(&self.session, &self.id, recipient.as_deref(), nonce, &msg);
+        let signature = self
+            .signer
+            .sign(&payload)
+            .map_err(|e| AuthenticatedTransportError::Signing(e.into()))?;
+
+        let envelope = Envelope {
+            session: self.session.to_vec(),
+            recipient,
+            nonce,
+            content: msg,
+            signature,
+        };
+        self.sender
+            .send(envelope.encode_to_vec(), to)
+            .await
+            .map_err(AuthenticatedTransportError::Transport)
+    }
+}
+
+/// Authenticates the envelopes received by the local node.
+struct EnvelopeOpener<I, V> {
+    id: I,
+    session: Arc<[u8]>,
+    verifier: Arc<V>,
+    replay_windows: HashMap<I, ReplayWindow>,
+}
+
+impl<I, V> EnvelopeOpener<I, V>
+where
+    I: PartyIdentifier,
+    V: MessageVerifier<I>,
+{
+    fn open(&mut self, m: ReceivedMessage<I>) -> Result<ReceivedMessage<I>, (I, RejectReason)> {
+        let sender = m.sender;
+        let reject = |reason| (sender, reason);
+
+        let envelope =
+            Envelope::decode(&*m.content).map_err(|_| reject(RejectReason::Malformed))?;
+        if *envelope.session != *self.session {
+            Err(reject(RejectReason::WrongSession))?
+        }
+        if let Some(recipient) = &envelope.recipient
+            && *recipient != self.id.to_string()
+        {
+            Err(reject(RejectReason::WrongRecipient))?
+        }
+
+        // The signature binds the message to the sender claimed by the transport
+        let payload = signed_payload(
+            &self.session,
+            &sender,
+            envelope.recipient.as_deref(),
+            envelope.nonce,
+            &envelope.content,
+        );
+        if !self.verifier.verify(&sender, &payload, &envelope.signature) {
+            Err(reject(RejectReason::InvalidSignature))?
+        }
+
+        // Only record the nonce once the message is authenticated
+        if !self
+            .replay_windows
+            .entry(sender)
+            .or_default()
+            .insert(envelope.nonce)
+        {
+            Err(reject(RejectReason::Replay))?
+        }
+
+        Ok(ReceivedMessage::new(
+            sender,
+            envelope.content,
+            m.message_type,
+        ))
+    }
+}
+
+/// Nonces received from a sender, within [`REPLAY_WINDOW`] of the highest nonce received.
+#[derive(Default)]
+struct ReplayWindow {
+    highest: u64,
+    seen: BTreeSet<u64>,
+}
+
+impl ReplayWindow {
+    /// Record a nonce, outputs false if it was already received, or is too old to be tracked.
+    fn insert(&mut self, nonce: u64) -> bool {
+        if nonce.saturating_add(REPLAY_WINDOW) <= self.highest || !self.seen.insert(nonce) {
+            return false;
+        }
+
+        if nonce > self.highest {
+            self.highest = nonce;
+            self.seen = self
+                .seen
+                .split_off(&self.highest.saturating_sub(REPLAY_WINDOW));
+        }
+        true
+    }
+}
+
+fn signed_payload<I: PartyIdentifier>(
+    session: &[u8],
+    sender: &I,
+    recipient: Option<&str>,
+    nonce: u64,
+    content: &[u8],
+) -> Vec<u8> {
+    let payload = SignedPayload {
+        session: session.to_vec(),
+        sender: sender.to_string(),
+        recipient: recipient.map(str::to_owned),
+        nonce,
+        content: content.to_vec(),
+    };
+
+    let mut buf = SIGNATURE_DST.to_vec();
+    payload.encode(&mut buf).expect("vec has enough capacity");
+    buf
+}
+
+#[derive(Clone, prost::Message)]
+struct Envelope {
+    #[prost(bytes, tag = "1")]
+    session: Vec<u8>,
+
+    /// Recipient of a direct message, or None for broadcasts
+    #[prost(string, optional, tag = "2")]
+    recipient: Option<String>,
+
+    #[prost(uint64, tag = "3")]
+    nonce: u64,
+
+    #[prost(bytes, tag = "4")]
+    content: Vec<u8>,
+
+    #[prost(bytes, tag = "5")]
+    signature: Vec<u8>,
+}
+
+#[derive(Clone, prost::Message)]
+struct SignedPayload {
+    #[prost(bytes, tag = "1")]
+    session: Vec<u8>,
+
+    #[prost(string, tag = "2")]
+    sender: String,
+
+    #[prost(string, optional, tag = "3")]
+    recipient: Option<String>,
+
+    #[prost(uint64, tag = "4")]
+    nonce: u64,
+
+    #[prost(bytes, tag = "5")]
+    content: Vec<u8>,
+}
+
+#[cfg(feature = "libp2p")]
+mod libp2p_keys {
+    use super::*;
+    use ::libp2p::identity::{Keypair, PublicKey, SigningError};
+
+    /// Sign messages with the libp2p identity of the node.
+    impl MessageSigner for Keypair {
+        type Error = SigningError;
+
+        fn sign(&self, msg: &[u8]) -> Result<Vec<u8>, Self::Error> {
+            Keypair::sign(self, msg)
+        }
+    }
+
+    /// Verify messages with the libp2p public keys of the nodes.
+    impl<I: PartyIdentifier> MessageVerifier<I> for HashMap<I, PublicKey> {
+        fn verify(&self, sender: &I, msg: &[u8], signature: &[u8]) -> bool {
+            self.get(sender).is_some_and(|pk| pk.verify(msg, signature))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::hash::{DefaultHasher, Hasher};
+
+    /// Insecure keys deriving the signatures from the identifier of the signer.
+    #[derive(Clone, Copy)]
+    struct TestSigner(u16);
+
+    struct TestVerifier;
+
+    fn test_signature(signer: u16, msg: &[u8]) -> Vec<u8> {
+        let mut hasher = DefaultHasher::new();
+        hasher.write_u16(signer);
+        hasher.write(msg);
+        hasher.finish().to_be_bytes().to_vec()
+    }
+
+    impl MessageSigner for TestSigner {
+        type Error = std::convert::Infallible;
+
+        fn sign(&self, msg: &[u8]) -> Result<Vec<u8>, Self::Error> {
+            Ok(test_signature(self.0, msg))
+        }
+    }
+
+    impl MessageVerifier<u16> for TestVerifier {
+        fn verify(&self, sender: &u16, msg: &[u8], signature: &[u8]) -> bool {
+            test_signature(*sender, msg) == signature
+        }
+    }
+
+    #[test]
+    fn replay_window() {
+        let mut window = ReplayWindow::default();
+        assert!(window.insert(REPLAY_WINDOW + 10));
+        assert!(!window.insert(REPLAY_WINDOW + 10));
+
+        // Out of order messages within the window are accepted once
+        assert!(window.insert(REPLAY_WINDOW + 5));
+        assert!(!window.insert(REPLAY_WINDOW + 5));
+        assert!(window.insert(11));
+        assert!(!window.insert(10));
+
+        // Moving the window forgets older nonces
+        assert!(window.insert(3 * REPLAY_WINDOW));
+        assert!(!window.insert(REPLAY_WINDOW + 6));
+        assert_eq!(window.seen.len(), 1);
+    }
+
+    #[test]
+    fn open_envelopes() {
+        let session: Arc<[u8]> = b"session".as_slice().into();
+        let mut opener = EnvelopeOpener {
+            id: 2u16,
+            session: session.clone(),
+            verifier: Arc::new(TestVerifier),
+            replay_windows: HashMap::new(),
+        };
+        let seal = |sender: u16, session: &[u8], recipient: Option<u16>, nonce: u64| {
+            let recipient = recipient.map(|i| i.to_string());
+            let payload = signed_payload(session, &sender, recipient.as_deref(), nonce, b"content");
+            let envelope = Envelope {
+                session: session.to_vec(),
+                recipient,
+                nonce,
+                content: b"content".to_vec(),
+                signature: test_signature(sender, &payload),
+            };
+            ReceivedMessage::new_broadcast(sender, envelope.encode_to_vec())
+        };
+
+        let m = opener.open(seal(1, &session, None, 1)).unwrap();
+        assert_eq!((m.sender, m.content.as_slice()), (1, b"content".as_slice()));
+        let m = opener.open(seal(1, &session, Some(2), 2)).unwrap();
+        assert_eq!(m.sender, 1);
+
+        // The same nonce may be used by distinct senders
+        assert!(opener.open(seal(3, &session, None, 1)).is_ok());
+
+        let reject = |opener: &mut EnvelopeOpener<_, _>, m| opener.open(m).unwrap_err().1;
+        assert_eq!(
+            reject(&mut opener, seal(1, &session, None, 1)),
+            RejectReason::Replay
+        );
+        assert_eq!(
+            reject(&mut opener, seal(1, b"other session", None, 3)),
+            RejectReason::WrongSession
+        );
+        assert_eq!(
+            reject(&mut opener, seal(1, &session, Some(3), 3)),
+            RejectReason::WrongRecipient
+        );
+        assert_eq!(
+            reject(
+                &mut opener,
+                ReceivedMessage::new_broadcast(1, vec![0xff; 8])
+            ),
+            RejectReason::Malformed
+        );
+
+        // Messages relayed under the identity of another node are rejected
+        let mut relayed = seal(1, &session, None, 3);
+        relayed.sender = 3;
+        assert_eq!(reject(&mut opener, relayed), RejectReason::InvalidSignature);
+
+        // Rejected messages do not consume nonces
+        assert!(opener.open(seal(1, &session, None, 3)).is_ok());
+    }
+
+    #[cfg(feature = "in_memory")]
+    #[tokio::test]
+    async fn authenticated_in_memory_transport() {
+        use crate::transports::in_memory::MemoryNetwork;
+        use std::time::Duration;
+
+        let mut transports = MemoryNetwork::get_transports(1u16..=3);
+        let mut raw_1 = transports.pop_front().unwrap();
+        let transport_2 = transports.pop_front().unwrap();
+        let mut raw_3 = transports.pop_front().unwrap();
+
+        let raw_sender_1 = raw_1.sender().unwrap();
+        let raw_sender_3 = raw_3.sender().unwrap();
+        let mut raw_receiver_3 = raw_3.receiver_stream().unwrap();
+        let other_session_sender_1 =
+            AuthenticatedTransport::new(raw_1.clone(), 1, "other", TestSigner(1), TestVerifier)
+                .sender()
+                .unwrap();
+        let mut transport_1 =
+            AuthenticatedTransport::new(raw_1, 1, "session", TestSigner(1), TestVerifier);
+        let mut transport_2 =
+            AuthenticatedTransport::new(transport_2, 2, "session", TestSigner(2), TestVerifier);
+        let sender_1 = transport_1.sender().unwrap();
+        let mut receiver_2 = transport_2.receiver_stream().unwrap();
+
+        sender_1.broadcast(b"broadcast".to_vec()).await.unwrap();
+        let m = receiver_2.next().await.unwrap().unwrap();
+        assert_eq!((m.sender, m.content), (1, b"broadcast".to_vec()));
+
+        // Envelopes observed by node 3
+        let broadcast_envelope = raw_receiver_3.next().await.unwrap().unwrap().content;
+        sender_1.send_single(b"direct".to_vec(), 3).await.unwrap();
+        let direct_envelope = raw_receiver_3.next().await.unwrap().unwrap().content;
+
+        // Replay the broadcast on behalf of node 1 and of node 3
+        raw_sender_1
+            .broadcast(broadcast_envelope.clone())
+            .await
+            .unwrap();
+        raw_sender_3.broadcast(broadcast_envelope).await.unwrap();
+
+        // Redirect the message sent to node 3 towards node 2
+        raw_sender_1.send_single(direct_envelope, 2).await.unwrap();
+
+        // Unauthenticated and out of session messages
+        raw_sender_3.broadcast(b"raw".to_vec()).await.unwrap();
+        other_session_sender_1
+            .broadcast(b"other session".to_vec())
+            .await
+            .unwrap();
+
+        // Only authenticated messages are output
+        sender_1.send_single(b"direct".to_vec(), 2).await.unwrap();
+        let m = tokio::time::timeout(Duration::from_secs(1), receiver_2.next())
+            .await
+            .expect("should receive message")
+            .unwrap()
+            .unwrap();
+        assert_eq!((m.sender, m.content), (1, b"direct".to_vec()));
+    }
+}
@@ -0,0 +1,57 @@
+use super::RejectReason;
+
+#[cfg(feature = "metrics")]
+mod real_metrics {
+    use prometheus::{IntCounterVec, Opts, Registry};
+    use std::sync::LazyLock;
+
+    pub struct Metrics {
+        pub(super) registry: Registry,
+        pub(super) rejected_messages: IntCounterVec,
+    }
+
+    pub(super) static METRICS: LazyLock<Metrics> = LazyLock::new(|| {
+        let registry = Registry::new();
+
+        let rejected_messages = IntCounterVec::new(
+            Opts::new(
+                "authenticated_transport_rejected_messages_total",
+                "Number of received messages that failed authentication",
+            ),
+            &["reason"],
+        )
+        .expect("metrics failed to initialise");
+
+        registry
+            .register(Box::new(rejected_messages.clone()))
+            .expect("metrics failed to initialise");
+
+        Metrics {
+            registry,
+            rejected_messages,
+        }
+    });
+}
+
+#[cfg(feature = "metrics")]
+pub use real_metrics::Metrics;
+
+#[cfg(not(feature = "metrics"))]
+pub struct Metrics;
+
+impl Metrics {
+    pub(super) fn report_rejected_message(reason: RejectReason) {
+        #[cfg(feature = "metrics")]
+        real_metrics::METRICS
+            .rejected_messages
+            .with_label_values(&[reason.as_str()])
+            .inc();
+        #[cfg(not(feature = "metrics"))]
+        let _ = reason;
+    }
+
+    #[cfg(feature = "metrics")]
+    pub fn gather() -> Vec<prometheus::proto::MetricFamily> {
+        real_metrics::METRICS.registry.gather()
+    }
+}
@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 use std::hash::Hash;
 
+pub mod authenticated;
 pub mod topic;
 #[cfg(feature = "transports")]
 pub mod transports;
@@ -18,6 +18,10 @@ type ChannelsMap<I, M> = HashMap<Cow<'static, [u8]>, ChannelsMapEntry<I, M>>;
 type Channels<I, M> = Arc<std::sync::Mutex<ChannelsMap<I, M>>>;
 
 /// A dispatcher that can be used to multiplex many topics in a single [`Transport`].
+///
+/// The dispatcher trusts the sender identities output by the transport. Transports that do not
+/// authenticate the peers can be wrapped in an
+/// [`AuthenticatedTransport`](crate::authenticated::AuthenticatedTransport) beforehand.
 pub struct TopicDispatcher {
     recv_task_handle: Option<tokio::task::JoinHandle<()>>,
     cancel: CancellationToken,
@@ -301,6 +305,20 @@ where
     })
 }
 
+/// Decode a raw message sent through a [`TopicDispatcher`] into its topic and content.
+///
+/// This can be used to inspect messages recorded below the dispatcher, e.g., by a transport writer.
+pub fn decode_topic_message(msg: &[u8]) -> Option<(Vec<u8>, Vec<u8>)> {
+    let MessageWithTopic { topic, content } = MessageWithTopic::decode(msg).ok()?;
+    Some((topic, content))
+}
+
+/// Encode `content` as a message sent through a [`TopicDispatcher`] on `topic`, i.e., the inverse of
+/// [`decode_topic_message`].
+pub fn encode_topic_message(topic: Vec<u8>, content: Vec<u8>) -> Vec<u8> {
+    MessageWithTopic { topic, content }.encode_to_vec()
+}
+
 #[derive(Clone, prost::Message)]
 struct MessageWithTopic {
     #[prost(bytes, tag = "1")]
@@ -6,6 +6,8 @@ pub mod in_memory;
 pub mod libp2p;
 #[cfg(feature = "replayable")]
 pub mod replayable;
+#[cfg(feature = "simulator")]
+pub mod simulator;
 
 #[derive(Clone, Debug)]
 pub enum TransportAction<I: PartyIdentifier> {
@@ -1,6 +1,7 @@
-//! Libp2p node that can be used to broadcast and receive arbitrary messages using floodsub and a
-//! peer whitelist.
+//! Libp2p node that can be used to broadcast and receive arbitrary messages using floodsub or
+//! gossipsub and a peer whitelist.
 
+mod chunked;
 mod dialer;
 mod events_handler;
 pub mod metrics;
@@ -8,6 +9,7 @@ mod point_to_point;
 pub mod transport;
 
 use crate::PartyIdentifier;
+use crate::transports::libp2p::chunked::{CHUNKED_BROADCAST_PROTOCOL, ChunkedBroadcastCodec};
 use crate::transports::libp2p::dialer::PeriodicDialBehaviour;
 use crate::transports::libp2p::events_handler::EventsHandler;
 use crate::transports::libp2p::point_to_point::{
@@ -18,10 +20,13 @@ use itertools::izip;
 use libp2p::allow_block_list::AllowedPeers;
 use libp2p::identity::Keypair;
 use libp2p::swarm::NetworkBehaviour;
+use libp2p::swarm::behaviour::toggle::Toggle;
 use libp2p::swarm::dial_opts::{DialOpts, PeerCondition};
 use libp2p::{
-    Multiaddr, PeerId, Swarm, allow_block_list, floodsub, noise, ping, request_response, tcp, yamux,
+    Multiaddr, PeerId, Swarm, allow_block_list, floodsub, gossipsub, noise, ping, request_response,
+    tcp, yamux,
 };
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 use std::time::Duration;
 use thiserror::Error;
@@ -33,6 +38,59 @@ const LIBP2P_MAIN_TOPIC: &str = "main";
 const DEFAULT_REDIAL_INTERVAL: Duration = Duration::from_secs(2 * 60); // 2mins
 const FLOODSUB_MAX_MESSAGE_LEN: usize = 8192;
 
+/// Upper bound on the overhead of the pubsub envelope of a message, i.e., the topic, sequence
+/// number, source, and signature.
+const PUBSUB_ENVELOPE_OVERHEAD: usize = 512;
+
+/// Application-specific score of peers that are not part of the committee, below the default
+/// graylist threshold of gossipsub.
+const UNKNOWN_PEER_SCORE: f64 = -100.0;
+
+/// Protocol used to broadcast messages to the other members of the committee.
+///
+/// With both protocols, messages exceeding the maximum message length are split in chunks and
+/// sent to each peer through point-to-point connections.
+#[derive(Clone, Debug, Default)]
+pub enum BroadcastProtocol {
+    /// Flood each message to every peer, with messages of at most 8 KiB.
+    #[default]
+    Floodsub,
+
+    /// Propagate messages through a gossipsub mesh.
+    Gossipsub(GossipsubOptions),
+}
+
+/// Options of the gossipsub protocol.
+#[derive(Clone, Debug)]
+pub struct GossipsubOptions {
+    /// Maximum length of a message broadcast through gossipsub
+    pub max_message_len: usize,
+    /// Interval between two gossipsub heartbeats
+    pub heartbeat_interval: Duration,
+    /// Duration during which the ids of received messages are kept to discard duplicates
+    pub duplicate_cache_time: Duration,
+}
+
+impl Default for GossipsubOptions {
+    fn default() -> Self {
+        Self {
+            max_message_len: 256 * 1024,
+            heartbeat_interval: Duration::from_secs(1),
+            duplicate_cache_time: Duration::from_secs(2 * 60),
+        }
+    }
+}
+
+impl BroadcastProtocol {
+    /// Maximum length of a message broadcast through the pubsub protocol.
+    fn max_message_len(&self) -> usize {
+        match self {
+            BroadcastProtocol::Floodsub => FLOODSUB_MAX_MESSAGE_LEN - PUBSUB_ENVELOPE_OVERHEAD,
+            BroadcastProtocol::Gossipsub(opts) => opts.max_message_len,
+        }
+    }
+}
+
 /// Holds configuration parameters and obtain a [`Libp2pNode`] by running
 /// [`Self::run`](Libp2pNodeConfig::run).
 pub struct Libp2pNodeConfig<ID> {
@@ -40,6 +98,7 @@ pub struct Libp2pNodeConfig<ID> {
     short_id: ID,
     peers: PeerDetails<ID>,
     redial_interval: Duration,
+    broadcast_protocol: BroadcastProtocol,
 }
 
 /// A libp2p node actively running in a background task.
@@ -69,6 +128,9 @@ pub enum Libp2pNodeError {
     #[error("libp2p noise protocol error")]
     Noise(#[from] noise::Error),
 
+    #[error("invalid gossipsub configuration: {0}")]
+    Gossipsub(String),
+
     #[error("failed to join background task")]
     Join(#[from] tokio::task::JoinError),
 }
@@ -103,6 +165,7 @@ impl<ID: PartyIdentifier> Libp2pNodeConfig<ID> {
             short_id,
             peers,
             redial_interval: DEFAULT_REDIAL_INTERVAL,
+            broadcast_protocol: BroadcastProtocol::default(),
         }
     }
 
@@ -112,6 +175,12 @@ impl<ID: PartyIdentifier> Libp2pNodeConfig<ID> {
         self
     }
 
+    /// Set the protocol used to broadcast messages
+    pub fn broadcast_protocol(&mut self, broadcast_protocol: BroadcastProtocol) -> &mut Self {
+        self.broadcast_protocol = broadcast_protocol;
+        self
+    }
+
     /// Runs a new libp2p node that listens on `listen_addr`, forwards messages from the swarm to `tx_received_messages`,
     /// and broadcasts messages from `rx_messages_to_send` to the swarm.
     pub fn run(self, listen_addr: Multiaddr) -> Result<Libp2pNode<ID>, Libp2pNodeError> {
@@ -120,6 +189,7 @@ impl<ID: PartyIdentifier> Libp2pNodeConfig<ID> {
             self.key.clone(),
             self.peers.values().cloned(),
             self.redial_interval,
+            &self.broadcast_protocol,
         )?;
 
         // Listen on all interfaces
@@ -134,10 +204,9 @@ impl<ID: PartyIdentifier> Libp2pNodeConfig<ID> {
                 .cloned()
                 .for_each(|multiaddr| swarm.add_peer_address(p.peer_id, multiaddr));
 
-            swarm
-                .behaviour_mut()
-                .floodsub
-                .add_node_to_partial_view(p.peer_id);
+            if let Some(floodsub) = swarm.behaviour_mut().floodsub.as_mut() {
+                floodsub.add_node_to_partial_view(p.peer_id);
+            }
 
             let dial_opts = DialOpts::peer_id(p.peer_id)
                 .addresses(p.multiaddrs.clone())
@@ -148,9 +217,16 @@ impl<ID: PartyIdentifier> Libp2pNodeConfig<ID> {
             }
         });
 
-        // Create a floodsub topic and subscribe
-        let topic = floodsub::Topic::new(LIBP2P_MAIN_TOPIC);
-        let _ = swarm.behaviour_mut().floodsub.subscribe(topic);
+        // Subscribe to the main topic
+        let behaviour = swarm.behaviour_mut();
+        if let Some(floodsub) = behaviour.floodsub.as_mut() {
+            let _ = floodsub.subscribe(floodsub::Topic::new(LIBP2P_MAIN_TOPIC));
+        }
+        if let Some(gossipsub) = behaviour.gossipsub.as_mut() {
+            gossipsub
+                .subscribe(&gossipsub::IdentTopic::new(LIBP2P_MAIN_TOPIC))
+                .map_err(|e| Libp2pNodeError::Gossipsub(e.to_string()))?;
+        }
 
         // Create channels for sending and receiving
         let (tx_received_message, rx_received_message) = unbounded_channel();
@@ -163,6 +239,7 @@ impl<ID: PartyIdentifier> Libp2pNodeConfig<ID> {
                 self.short_id,
                 swarm,
                 self.peers,
+                self.broadcast_protocol.max_message_len(),
                 tx_received_message,
                 rx_msg_to_send,
                 cancellation_token.clone(),
@@ -194,8 +271,9 @@ fn configure_swarm<ID: PartyIdentifier>(
     keypair: Keypair,
     peers: impl IntoIterator<Item = PeerDetail<ID>>,
     redial_interval: Duration,
+    broadcast_protocol: &BroadcastProtocol,
 ) -> Result<Swarm<Behaviour<ID>>, Libp2pNodeError> {
-    let peer_id = keypair.public().to_peer_id();
+    let behaviour = Behaviour::new(&keypair, peers, redial_interval, broadcast_protocol)?;
     Ok(libp2p::SwarmBuilder::with_existing_identity(keypair)
         .with_tokio()
         .with_tcp(
@@ -205,7 +283,7 @@ fn configure_swarm<ID: PartyIdentifier>(
         )?
         .with_dns()
         .expect("failed to create swarm with dns")
-        .with_behaviour(|_| Behaviour::new(peer_id, peers, redial_interval))
+        .with_behaviour(|_| behaviour)
         .unwrap() // infallible
         .with_swarm_config(|cfg| {
             cfg.with_idle_connection_timeout(Duration::from_secs(u64::MAX)) // stay connected to the peer even if idle
@@ -275,19 +353,26 @@ impl<ID: PartyIdentifier> PeerDetails<ID> {
     }
 }
 
-/// Libp2p Behaviour with floodsub and a peer whitelist.
+/// Libp2p Behaviour with floodsub or gossipsub, and a peer whitelist.
 #[derive(NetworkBehaviour)]
 struct Behaviour<ID> {
     allowed_peers: allow_block_list::Behaviour<AllowedPeers>,
-    floodsub: floodsub::Floodsub,
+    floodsub: Toggle<floodsub::Floodsub>,
+    gossipsub: Toggle<gossipsub::Behaviour>,
     point_to_point: request_response::Behaviour<DcipherPoint2PointMessageCodec>,
+    chunked_broadcast: request_response::Behaviour<ChunkedBroadcastCodec>,
     ping: ping::Behaviour,
     periodic_dial: PeriodicDialBehaviour<ID>,
 }
 
 impl<ID: PartyIdentifier> Behaviour<ID> {
     /// Create a new behaviour
-    fn new<I>(local_peer_id: PeerId, peers: I, redial_interval: Duration) -> Self
+    fn new<I>(
+        keypair: &Keypair,
+        peers: I,
+        redial_interval: Duration,
+        broadcast_protocol: &BroadcastProtocol,
+    ) -> Result<Self, Libp2pNodeError>
     where
         I: IntoIterator<Item = PeerDetail<ID>>,
     {
@@ -310,17 +395,77 @@ impl<ID: PartyIdentifier> Behaviour<ID> {
             request_response::Config::default(),
         );
 
-        Self {
+        let chunked_broadcast = request_response::Behaviour::new(
+            [(
+                CHUNKED_BROADCAST_PROTOCOL,
+                request_response::ProtocolSupport::Full,
+            )],
+            request_response::Config::default(),
+        );
+
+        let (floodsub, gossipsub) = match broadcast_protocol {
+            BroadcastProtocol::Floodsub => {
+                let floodsub = floodsub::Floodsub::new(keypair.public().to_peer_id())
+                    .with_max_message_len(FLOODSUB_MAX_MESSAGE_LEN);
+                (Some(floodsub), None)
+            }
+            BroadcastProtocol::Gossipsub(opts) => (None, Some(new_gossipsub(keypair, opts)?)),
+        };
+
+        Ok(Self {
             allowed_peers,
-            floodsub: floodsub::Floodsub::new(local_peer_id)
-                .with_max_message_len(FLOODSUB_MAX_MESSAGE_LEN),
+            floodsub: floodsub.into(),
+            gossipsub: gossipsub.into(),
             point_to_point,
+            chunked_broadcast,
             ping: ping::Behaviour::default(),
             periodic_dial: PeriodicDialBehaviour::new(redial_interval, peers),
-        }
+        })
     }
 }
 
+/// Create a gossipsub behaviour with signed messages, identified by their source and content.
+fn new_gossipsub(
+    keypair: &Keypair,
+    opts: &GossipsubOptions,
+) -> Result<gossipsub::Behaviour, Libp2pNodeError> {
+    let config = gossipsub::ConfigBuilder::default()
+        .max_transmit_size(opts.max_message_len + PUBSUB_ENVELOPE_OVERHEAD)
+        .heartbeat_interval(opts.heartbeat_interval)
+        .duplicate_cache_time(opts.duplicate_cache_time)
+        .validation_mode(gossipsub::ValidationMode::Strict)
+        .message_id_fn(|message: &gossipsub::Message| {
+            // Identical messages from the same source are only delivered once
+            let mut hasher = Sha256::new();
+            if let Some(source) = &message.source {
+                hasher.update(source.to_bytes());
+            }
+            hasher.update(&message.data);
+            gossipsub::MessageId::new(&hasher.finalize())
+        })
+        .build()
+        .map_err(|e| Libp2pNodeError::Gossipsub(e.to_string()))?;
+
+    let mut gossipsub = gossipsub::Behaviour::new(
+        gossipsub::MessageAuthenticity::Signed(keypair.clone()),
+        config,
+    )
+    .map_err(|e| Libp2pNodeError::Gossipsub(e.to_owned()))?;
+
+    // Only the application-specific score is used to penalize peers outside the committee. The
+    // committee is a fixed set of peers that may share an IP, hence no colocation penalty.
+    let params = gossipsub::PeerScoreParams {
+        app_specific_weight: 1.0,
+        ip_colocation_factor_weight: 0.0,
+        ..Default::default()
+    };
+    gossipsub
+        .with_peer_score(params, gossipsub::PeerScoreThresholds::default())
+        .map_err(Libp2pNodeError::Gossipsub)?;
+
+    Ok(gossipsub)
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
     use super::*;
@@ -332,6 +477,14 @@ pub(crate) mod tests {
     pub(crate) async fn start_nodes<ID: PartyIdentifier>(
         short_ids: &[ID],
         start_port: u16,
+    ) -> Vec<Libp2pNode<ID>> {
+        start_nodes_with_protocol(short_ids, start_port, BroadcastProtocol::default()).await
+    }
+
+    pub(crate) async fn start_nodes_with_protocol<ID: PartyIdentifier>(
+        short_ids: &[ID],
+        start_port: u16,
+        broadcast_protocol: BroadcastProtocol,
     ) -> Vec<Libp2pNode<ID>> {
         let n = short_ids.len().try_into().expect("too many peers");
 
@@ -354,15 +507,17 @@ pub(crate) mod tests {
 
         izip!(short_ids.iter(), libp2p_addrs.iter(), libp2p_sks)
             .map(|(i, listen_addr, libp2p_sk)| {
-                Libp2pNodeConfig::new(
+                let mut config = Libp2pNodeConfig::new(
                     libp2p_sk,
                     *i,
                     libp2p_addrs.clone(),
                     libp2p_peer_ids.clone(),
                     short_ids.to_vec(),
-                )
-                .run(listen_addr.to_owned())
-                .expect("failed to start node")
+                );
+                config.broadcast_protocol(broadcast_protocol.clone());
+                config
+                    .run(listen_addr.to_owned())
+                    .expect("failed to start node")
             })
             .collect()
     }
@@ -468,4 +623,69 @@ pub(crate) mod tests {
         assert_eq!(m3.message_type, MessageType::Broadcast);
         assert_eq!(m3.content, m.to_vec());
     }
+
+    async fn broadcast_messages(broadcast_protocol: BroadcastProtocol, start_port: u16) {
+        // Try to set logging options
+        let _ = tracing_subscriber::registry()
+            .with(tracing_subscriber::EnvFilter::from("debug"))
+            .with(tracing_subscriber::fmt::layer())
+            .try_init();
+
+        let global_timeout = Duration::from_millis(5000);
+        let max_message_len = broadcast_protocol.max_message_len();
+
+        let [mut node_1, mut node_2, mut node_3]: [Libp2pNode<_>; 3] =
+            start_nodes_with_protocol(&[1, 2, 3], start_port, broadcast_protocol)
+                .await
+                .try_into()
+                .unwrap_or_else(|_| panic!("failed to create node"));
+
+        let mut transport_1 = node_1.get_transport().unwrap();
+        let mut transport_2 = node_2.get_transport().unwrap();
+        let mut transport_3 = node_3.get_transport().unwrap();
+
+        let mut rx_2 = transport_2.receiver_stream().unwrap();
+        let mut rx_3 = transport_3.receiver_stream().unwrap();
+
+        let tx_1 = transport_1.sender().unwrap();
+
+        // Wait for the peers to subscribe to the topic
+        tokio::time::sleep(Duration::from_millis(1000)).await;
+
+        // Broadcast a small message through pubsub, and a large message in chunks
+        let small: Vec<u8> = b"broadcast from node 1".to_vec();
+        let large: Vec<u8> = (0..max_message_len + 2 * chunked::CHUNK_SIZE)
+            .map(|i| i as u8)
+            .collect();
+        for m in [small, large] {
+            tx_1.broadcast(m.clone()).await.expect("broadcast failed");
+
+            for rx in [&mut rx_2, &mut rx_3] {
+                let received: ReceivedMessage<_, Vec<u8>> =
+                    tokio::time::timeout(global_timeout, rx.next())
+                        .await
+                        .expect("failed to obtain message: timed out")
+                        .expect("stream closed")
+                        .expect("stream return err");
+                assert_eq!(received.sender, 1);
+                assert_eq!(received.message_type, MessageType::Broadcast);
+                assert_eq!(received.content, m);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn floodsub_broadcast_large_messages() {
+        broadcast_messages(BroadcastProtocol::Floodsub, 32400).await;
+    }
+
+    #[tokio::test]
+    async fn gossipsub_broadcast_messages() {
+        let opts = GossipsubOptions {
+            max_message_len: 1024,
+            heartbeat_interval: Duration::from_millis(100),
+            ..Default::default()
+        };
+        broadcast_messages(BroadcastProtocol::Gossipsub(opts), 32500).await;
+    }
 }
@@ -0,0 +1,401 @@
+//! Broadcast of messages exceeding the maximum length supported by the pubsub protocol. Such
+//! messages are split into chunks that are sent to each of the peers through point-to-point
+//! connections, and reassembled by the recipients.
+
+use crate::PartyIdentifier;
+use async_trait::async_trait;
+use futures::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use libp2p::request_response;
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+
+pub(super) const CHUNKED_BROADCAST_PROTOCOL: &str = "/dcipher/chunked-broadcast/v1";
+
+/// Maximum length of the data of a single chunk.
+pub(super) const CHUNK_SIZE: usize = 1 << 20; // 1 MiB
+
+/// Maximum number of chunks of a message, i.e., messages are limited to 256 MiB.
+pub(super) const MAX_CHUNKS: usize = 256;
+
+/// Maximum number of partially received messages per peer, after which the oldest ones are
+/// dropped.
+const MAX_PENDING_MESSAGES_PER_PEER: usize = 8;
+
+/// Maximum number of bytes of the partially received messages of a single peer, after which the
+/// oldest ones are dropped. Allows a peer to send two messages of maximum size concurrently.
+const MAX_PENDING_BYTES_PER_PEER: usize = 2 * MAX_CHUNKS * CHUNK_SIZE;
+
+/// Maximum number of bytes of the partially received messages of all the peers, after which the
+/// oldest ones are dropped.
+const MAX_PENDING_BYTES: usize = 2 * MAX_PENDING_BYTES_PER_PEER;
+
+/// Length of the header of a chunk: broadcast id (u64), index (u32), and total (u32).
+const CHUNK_HEADER_LEN: usize = 16;
+
+/// A chunk of a broadcast message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct Chunk {
+    /// Identifier chosen by the sender to distinguish its broadcast messages
+    pub broadcast_id: u64,
+    /// Index of the chunk within the message
+    pub index: u32,
+    /// Total number of chunks of the message
+    pub total: u32,
+    pub data: Vec<u8>,
+}
+
+impl Chunk {
+    /// Split a message into chunks, or None if the message is too large.
+    pub(super) fn split(broadcast_id: u64, msg: &[u8]) -> Option<Vec<Chunk>> {
+        let total = msg.len().div_ceil(CHUNK_SIZE).max(1);
+        if total > MAX_CHUNKS {
+            return None;
+        }
+
+        let chunks = (0..total)
+            .map(|index| Chunk {
+                broadcast_id,
+                index: index as u32,
+                total: total as u32,
+                data: msg[index * CHUNK_SIZE..msg.len().min((index + 1) * CHUNK_SIZE)].to_vec(),
+            })
+            .collect();
+        Some(chunks)
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(CHUNK_HEADER_LEN + self.data.len());
+        buf.extend_from_slice(&self.broadcast_id.to_be_bytes());
+        buf.extend_from_slice(&self.index.to_be_bytes());
+        buf.extend_from_slice(&self.total.to_be_bytes());
+        buf.extend_from_slice(&self.data);
+        buf
+    }
+
+    fn decode(mut buf: Vec<u8>) -> io::Result<Self> {
+        if buf.len() < CHUNK_HEADER_LEN {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "chunk shorter than header",
+            ))?
+        }
+
+        let data = buf.split_off(CHUNK_HEADER_LEN);
+        let broadcast_id = u64::from_be_bytes(buf[0..8].try_into().unwrap());
+        let index = u32::from_be_bytes(buf[8..12].try_into().unwrap());
+        let total = u32::from_be_bytes(buf[12..16].try_into().unwrap());
+        if index >= total || total as usize > MAX_CHUNKS {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "invalid chunk index",
+            ))?
+        }
+
+        Ok(Self {
+            broadcast_id,
+            index,
+            total,
+            data,
+        })
+    }
+}
+
+#[derive(Default, Clone)]
+pub(super) struct ChunkedBroadcastCodec;
+
+#[async_trait]
+impl request_response::Codec for ChunkedBroadcastCodec {
+    type Protocol = &'static str;
+    type Request = Chunk;
+    type Response = ();
+
+    async fn read_request<T>(
+        &mut self,
+        protocol: &Self::Protocol,
+        io: &mut T,
+    ) -> io::Result<Self::Request>
+    where
+        T: AsyncRead + Unpin + Send,
+    {
+        if *protocol != CHUNKED_BROADCAST_PROTOCOL {
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "unsupported protocol",
+            ))?
+        }
+
+        let mut buf = Vec::new();
+        io.take((CHUNK_HEADER_LEN + CHUNK_SIZE) as u64)
+            .read_to_end(&mut buf)
+            .await?;
+
+        Chunk::decode(buf)
+    }
+
+    async fn read_response<T>(
+        &mut self,
+        protocol: &Self::Protocol,
+        _io: &mut T,
+    ) -> io::Result<Self::Response>
+    where
+        T: AsyncRead + Unpin + Send,
+    {
+        if *protocol != CHUNKED_BROADCAST_PROTOCOL {
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "unsupported protocol",
+            ))?
+        }
+
+        Ok(())
+    }
+
+    async fn write_request<T>(
+        &mut self,
+        protocol: &Self::Protocol,
+        io: &mut T,
+        req: Self::Request,
+    ) -> io::Result<()>
+    where
+        T: AsyncWrite + Unpin + Send,
+    {
+        if *protocol != CHUNKED_BROADCAST_PROTOCOL {
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "unsupported protocol",
+            ))?
+        }
+
+        io.write_all(&req.encode()).await
+    }
+
+    async fn write_response<T>(
+        &mut self,
+        protocol: &Self::Protocol,
+        _io: &mut T,
+        _res: Self::Response,
+    ) -> io::Result<()>
+    where
+        T: AsyncWrite + Unpin + Send,
+    {
+        if *protocol != CHUNKED_BROADCAST_PROTOCOL {
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "unsupported protocol",
+            ))?
+        }
+
+        Ok(())
+    }
+}
+
+/// Reassembles the messages from the chunks received from each peer.
+pub(super) struct ChunksReassembler<ID> {
+    pending: HashMap<ID, BTreeMap<u64, PendingMessage>>,
+    /// Bytes of all the partially received messages
+    pending_bytes: usize,
+    max_pending_bytes_per_peer: usize,
+    max_pending_bytes: usize,
+    /// Sequence number of the next partially received message, used to find the oldest ones
+    next_seq: u64,
+}
+
+struct PendingMessage {
+    chunks: Vec<Option<Vec<u8>>>,
+    missing: usize,
+    bytes: usize,
+    seq: u64,
+}
+
+impl<ID: PartyIdentifier> ChunksReassembler<ID> {
+    pub(super) fn new() -> Self {
+        Self::with_budgets(MAX_PENDING_BYTES_PER_PEER, MAX_PENDING_BYTES)
+    }
+
+    /// Create a reassembler that buffers at most `max_pending_bytes_per_peer` bytes of partially
+    /// received messages per peer, and `max_pending_bytes` bytes in total.
+    fn with_budgets(max_pending_bytes_per_peer: usize, max_pending_bytes: usize) -> Self {
+        Self {
+            pending: HashMap::new(),
+            pending_bytes: 0,
+            max_pending_bytes_per_peer,
+            max_pending_bytes,
+            next_seq: 0,
+        }
+    }
+
+    /// Insert a chunk sent by a peer, and output the message once all of its chunks have been
+    /// received.
+    pub(super) fn insert(&mut self, sender: ID, chunk: Chunk) -> Option<Vec<u8>> {
+        let pending = self.pending.entry(sender).or_default();
+        let message = pending.entry(chunk.broadcast_id).or_insert_with(|| {
+            self.next_seq += 1;
+            PendingMessage {
+                chunks: vec![None; chunk.total as usize],
+                missing: chunk.total as usize,
+                bytes: 0,
+                seq: self.next_seq,
+            }
+        });
+
+        if message.chunks.len() != chunk.total as usize {
+            tracing::warn!(
+                ?sender,
+                broadcast_id = chunk.broadcast_id,
+                "Received chunk with inconsistent number of chunks"
+            );
+            return None;
+        }
+
+        let slot = &mut message.chunks[chunk.index as usize];
+        if slot.is_none() {
+            message.bytes += chunk.data.len();
+            self.pending_bytes += chunk.data.len();
+            *slot = Some(chunk.data);
+            message.missing -= 1;
+        }
+
+        if message.missing == 0 {
+            let message = pending
+                .remove(&chunk.broadcast_id)
+                .expect("message is pending");
+            self.pending_bytes -= message.bytes;
+            if pending.is_empty() {
+                self.pending.remove(&sender);
+            }
+            return Some(message.chunks.into_iter().flatten().flatten().collect());
+        }
+
+        // Drop the oldest messages that may never be completed, e.g., if the sender restarted, or
+        // if the sender exceeds its budget
+        while pending.len() > MAX_PENDING_MESSAGES_PER_PEER
+            || pending.values().map(|m| m.bytes).sum::<usize>() > self.max_pending_bytes_per_peer
+        {
+            let Some(broadcast_id) = oldest(pending) else {
+                break;
+            };
+            let message = pending.remove(&broadcast_id).expect("message is pending");
+            self.pending_bytes -= message.bytes;
+            tracing::warn!(?sender, broadcast_id, "Dropping partially received message");
+        }
+
+        // Drop the oldest messages of any peer once the global budget is exceeded
+        while self.pending_bytes > self.max_pending_bytes {
+            let Some((peer, broadcast_id)) = self
+                .pending
+                .iter()
+                .filter_map(|(peer, pending)| {
+                    let broadcast_id = oldest(pending)?;
+                    Some((pending[&broadcast_id].seq, *peer, broadcast_id))
+                })
+                .min_by_key(|(seq, _, _)| *seq)
+                .map(|(_, peer, broadcast_id)| (peer, broadcast_id))
+            else {
+                break;
+            };
+
+            let pending = self
+                .pending
+                .get_mut(&peer)
+                .expect("peer has pending messages");
+            let message = pending.remove(&broadcast_id).expect("message is pending");
+            self.pending_bytes -= message.bytes;
+            tracing::warn!(sender = ?peer, broadcast_id, "Dropping partially received message");
+        }
+
+        self.pending.retain(|_, pending| !pending.is_empty());
+        None
+    }
+}
+
+/// Identifier of the message that was received first.
+fn oldest(pending: &BTreeMap<u64, PendingMessage>) -> Option<u64> {
+    pending
+        .iter()
+        .min_by_key(|(_, message)| message.seq)
+        .map(|(broadcast_id, _)| *broadcast_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_decode_chunks() {
+        let msg = vec![7u8; 2 * CHUNK_SIZE + 3];
+        let chunks = Chunk::split(42, &msg).unwrap();
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[2].data.len(), 3);
+
+        for chunk in chunks {
+            assert_eq!(Chunk::decode(chunk.encode()).unwrap(), chunk);
+        }
+
+        assert!(Chunk::decode(vec![0u8; CHUNK_HEADER_LEN - 1]).is_err());
+        let invalid_index = Chunk {
+            broadcast_id: 1,
+            index: 1,
+            total: 1,
+            data: vec![],
+        };
+        assert!(Chunk::decode(invalid_index.encode()).is_err());
+
+        assert!(Chunk::split(1, &vec![0u8; MAX_CHUNKS * CHUNK_SIZE + 1]).is_none());
+    }
+
+    #[test]
+    fn reassemble_chunks() {
+        let msg_1: Vec<u8> = (0..2 * CHUNK_SIZE + 3).map(|i| i as u8).collect();
+        let msg_2 = vec![1u8; CHUNK_SIZE + 1];
+        let chunks_1 = Chunk::split(1, &msg_1).unwrap();
+        let chunks_2 = Chunk::split(2, &msg_2).unwrap();
+
+        // Interleave the messages of two senders, with chunks out of order and duplicated
+        let mut reassembler = ChunksReassembler::new();
+        assert_eq!(reassembler.insert(1u16, chunks_1[2].clone()), None);
+        assert_eq!(reassembler.insert(2u16, chunks_1[0].clone()), None);
+        assert_eq!(reassembler.insert(1u16, chunks_2[1].clone()), None);
+        assert_eq!(reassembler.insert(1u16, chunks_1[0].clone()), None);
+        assert_eq!(reassembler.insert(1u16, chunks_1[0].clone()), None);
+        assert_eq!(reassembler.insert(1u16, chunks_1[1].clone()), Some(msg_1));
+        assert_eq!(reassembler.insert(1u16, chunks_2[0].clone()), Some(msg_2));
+
+        // Oldest pending messages are dropped
+        for broadcast_id in 0..=MAX_PENDING_MESSAGES_PER_PEER as u64 {
+            let mut chunks = Chunk::split(broadcast_id, &[0u8; CHUNK_SIZE + 1]).unwrap();
+            assert_eq!(reassembler.insert(3u16, chunks.remove(0)), None);
+        }
+        assert_eq!(reassembler.pending[&3].len(), MAX_PENDING_MESSAGES_PER_PEER);
+        assert!(!reassembler.pending[&3].contains_key(&0));
+    }
+
+    #[test]
+    fn pending_bytes_are_bounded() {
+        let msg = vec![1u8; 3 * CHUNK_SIZE];
+        let chunks = |broadcast_id| Chunk::split(broadcast_id, &msg).unwrap();
+        let mut reassembler = ChunksReassembler::with_budgets(3 * CHUNK_SIZE, 5 * CHUNK_SIZE);
+
+        // The oldest message of a peer is dropped once it exceeds its budget
+        assert_eq!(reassembler.insert(1u16, chunks(1)[0].clone()), None);
+        assert_eq!(reassembler.insert(1u16, chunks(1)[1].clone()), None);
+        assert_eq!(reassembler.insert(1u16, chunks(2)[0].clone()), None);
+        assert_eq!(reassembler.insert(1u16, chunks(2)[1].clone()), None);
+        assert!(!reassembler.pending[&1].contains_key(&1));
+        assert_eq!(reassembler.pending_bytes, 2 * CHUNK_SIZE);
+
+        // The oldest message of any peer is dropped once the global budget is exceeded
+        assert_eq!(reassembler.insert(2u16, chunks(1)[0].clone()), None);
+        assert_eq!(reassembler.insert(2u16, chunks(1)[1].clone()), None);
+        assert_eq!(reassembler.insert(3u16, chunks(1)[0].clone()), None);
+        assert_eq!(reassembler.insert(3u16, chunks(1)[1].clone()), None);
+        assert!(!reassembler.pending.contains_key(&1));
+        assert_eq!(reassembler.pending_bytes, 4 * CHUNK_SIZE);
+
+        // Messages within the budgets are still reassembled, and release their bytes
+        assert_eq!(
+            reassembler.insert(2u16, chunks(1)[2].clone()),
+            Some(msg.clone())
+        );
+        assert_eq!(reassembler.pending_bytes, 2 * CHUNK_SIZE);
+    }
+}
@@ -1,15 +1,17 @@
+use crate::transports::libp2p::chunked::{Chunk, ChunksReassembler};
 use crate::transports::libp2p::dialer::PeriodicDialEvent;
 use crate::transports::libp2p::metrics::Metrics;
 use crate::transports::libp2p::{
-    Behaviour, BehaviourEvent, LIBP2P_MAIN_TOPIC, Libp2pNodeError, PeerDetails,
+    Behaviour, BehaviourEvent, LIBP2P_MAIN_TOPIC, Libp2pNodeError, PeerDetails, UNKNOWN_PEER_SCORE,
 };
 use crate::transports::{SendBroadcastMessage, SendDirectMessage, TransportAction};
 use crate::{PartyIdentifier, ReceivedMessage};
 use futures_util::StreamExt;
 use libp2p::floodsub::{FloodsubEvent, FloodsubMessage};
 use libp2p::request_response::{Event as RequestResponseEvent, Message as RequestResponseMessage};
-use libp2p::{Swarm, floodsub, ping, swarm::SwarmEvent};
+use libp2p::{Swarm, floodsub, gossipsub, ping, swarm::SwarmEvent};
 use std::num::NonZeroU32;
+use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 use tokio_util::sync::CancellationToken;
 
@@ -17,6 +19,9 @@ pub(super) struct EventsHandler<ID: PartyIdentifier> {
     short_id: ID,
     swarm: Swarm<Behaviour<ID>>,
     peers: PeerDetails<ID>,
+    max_broadcast_len: usize,
+    next_broadcast_id: u64,
+    chunks_reassembler: ChunksReassembler<ID>,
     tx_received_messages: UnboundedSender<ReceivedMessage<ID>>,
     rx_messages_to_send: UnboundedReceiver<TransportAction<ID>>,
     cancellation_token: CancellationToken,
@@ -27,14 +32,24 @@ impl<ID: PartyIdentifier> EventsHandler<ID> {
         short_id: ID,
         swarm: Swarm<Behaviour<ID>>,
         peers: PeerDetails<ID>,
+        max_broadcast_len: usize,
         tx_received_messages: UnboundedSender<ReceivedMessage<ID>>,
         rx_messages_to_send: UnboundedReceiver<TransportAction<ID>>,
         cancellation_token: CancellationToken,
     ) -> Self {
+        // Use the current time as the first broadcast id to avoid reusing ids after a restart
+        let next_broadcast_id = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default();
+
         Self {
             short_id,
             swarm,
             peers,
+            max_broadcast_len,
+            next_broadcast_id,
+            chunks_reassembler: ChunksReassembler::new(),
             tx_received_messages,
             rx_messages_to_send,
             cancellation_token,
@@ -116,10 +131,21 @@ impl<ID: PartyIdentifier> EventsHandler<ID> {
 
     fn send_broadcast_message_to_swarm(&mut self, msg: SendBroadcastMessage) {
         tracing::info!("Swarm broadcasting message to all connected peers");
-        self.swarm
-            .behaviour_mut()
-            .floodsub
-            .publish(floodsub::Topic::new(LIBP2P_MAIN_TOPIC), msg.msg.clone());
+        if msg.msg.len() > self.max_broadcast_len {
+            self.send_chunked_broadcast(&msg.msg);
+        } else {
+            let behaviour = self.swarm.behaviour_mut();
+            if let Some(floodsub) = behaviour.floodsub.as_mut() {
+                floodsub.publish(floodsub::Topic::new(LIBP2P_MAIN_TOPIC), msg.msg.clone());
+            } else if let Some(gossipsub) = behaviour.gossipsub.as_mut()
+                && let Err(e) = gossipsub.publish(
+                    gossipsub::IdentTopic::new(LIBP2P_MAIN_TOPIC),
+                    msg.msg.clone(),
+                )
+            {
+                tracing::error!(error = ?e, "Failed to publish message through gossipsub");
+            }
+        }
 
         if msg.broadcast_self {
             tracing::debug!("Sending broadcast to self");
@@ -133,6 +159,37 @@ impl<ID: PartyIdentifier> EventsHandler<ID> {
         }
     }
 
+    /// Broadcast a message that exceeds the maximum length of the pubsub protocol by sending it in
+    /// chunks to each of the peers.
+    fn send_chunked_broadcast(&mut self, msg: &[u8]) {
+        let broadcast_id = self.next_broadcast_id;
+        self.next_broadcast_id = self.next_broadcast_id.wrapping_add(1);
+
+        let Some(chunks) = Chunk::split(broadcast_id, msg) else {
+            tracing::error!(
+                len = msg.len(),
+                "Cannot broadcast message: message too large"
+            );
+            return;
+        };
+
+        tracing::debug!(
+            broadcast_id,
+            len = msg.len(),
+            n_chunks = chunks.len(),
+            "Broadcasting large message in chunks through point to point connections"
+        );
+        let peer_ids: Vec<_> = self.peers.values().map(|p| p.peer_id).collect();
+        for peer_id in peer_ids {
+            for chunk in &chunks {
+                self.swarm
+                    .behaviour_mut()
+                    .chunked_broadcast
+                    .send_request(&peer_id, chunk.clone());
+            }
+        }
+    }
+
     fn handle_swarm_event(
         &mut self,
         event: SwarmEvent<BehaviourEvent<ID>>,
@@ -168,6 +225,13 @@ impl<ID: PartyIdentifier> EventsHandler<ID> {
                     None
                 });
 
+                if short_id.is_none()
+                    && let Some(gossipsub) = self.swarm.behaviour_mut().gossipsub.as_mut()
+                {
+                    // Peers outside the committee are graylisted
+                    gossipsub.set_application_score(&peer_id, UNKNOWN_PEER_SCORE);
+                }
+
                 if num_established == const { NonZeroU32::new(1).unwrap() } {
                     // First connection established, report new peer connected
                     Metrics::report_peer_connected();
@@ -236,6 +300,14 @@ impl<ID: PartyIdentifier> EventsHandler<ID> {
                 self.handle_floodsub_event(event, ready_send_messages);
             }
 
+            BehaviourEvent::Gossipsub(event) => {
+                self.handle_gossipsub_event(event, ready_send_messages);
+            }
+
+            BehaviourEvent::ChunkedBroadcast(event) => {
+                self.handle_chunked_broadcast_event(event);
+            }
+
             BehaviourEvent::PointToPoint(event) => {
                 self.handle_point_to_point_event(event);
             }
@@ -294,6 +366,123 @@ impl<ID: PartyIdentifier> EventsHandler<ID> {
         }
     }
 
+    fn handle_gossipsub_event(&mut self, event: gossipsub::Event, ready_send_messages: &mut bool) {
+        match event {
+            gossipsub::Event::Message {
+                propagation_source,
+                message,
+                ..
+            } => {
+                let Some(sender_peer_id) = message.source else {
+                    tracing::error!(%propagation_source, "Libp2p node received message without source");
+                    return;
+                };
+                let Some(short_id) = self.peers.get_short_id(&sender_peer_id) else {
+                    tracing::error!(
+                        sender_peer_id = %sender_peer_id,
+                        "Libp2p node received message from an unknown peer"
+                    );
+                    return;
+                };
+
+                tracing::debug!(sender_peer_id = %sender_peer_id, sender_short_id = ?short_id, %propagation_source, "Libp2p node received message from peer");
+                if self
+                    .tx_received_messages
+                    .send(ReceivedMessage::new_broadcast(short_id, message.data))
+                    .is_err()
+                {
+                    tracing::error!(sender_peer_id = %sender_peer_id, sender_short_id = ?short_id, "Libp2p node failed to forward message through channel: channel closed");
+                }
+            }
+
+            gossipsub::Event::Subscribed { peer_id, topic } => {
+                let short_id = self.peers.get_short_id(&peer_id);
+
+                tracing::info!(%peer_id, ?short_id, ?topic, "Peer subscribed to topic");
+                // Once we've received at least one topic subscription from a remote peer, we should
+                // be able to send messages.
+                *ready_send_messages = true;
+            }
+
+            gossipsub::Event::Unsubscribed { peer_id, topic } => {
+                let short_id = self.peers.get_short_id(&peer_id);
+
+                tracing::info!(%peer_id, ?short_id, ?topic, "Peer unsubscribed to topic");
+            }
+
+            gossipsub::Event::GossipsubNotSupported { peer_id } => {
+                let short_id = self.peers.get_short_id(&peer_id);
+
+                tracing::warn!(%peer_id, ?short_id, "Peer does not support gossipsub");
+            }
+
+            _ => {}
+        }
+    }
+
+    fn handle_chunked_broadcast_event(&mut self, event: RequestResponseEvent<Chunk, ()>) {
+        match event {
+            RequestResponseEvent::Message {
+                peer: sender_peer_id,
+                message:
+                    RequestResponseMessage::Request {
+                        request: chunk,
+                        channel,
+                        ..
+                    },
+                ..
+            } => {
+                let Some(sender_short_id) = self.peers.get_short_id(&sender_peer_id) else {
+                    tracing::error!(%sender_peer_id, "Received chunk from an unknown peer");
+                    return;
+                };
+
+                if self
+                    .swarm
+                    .behaviour_mut()
+                    .chunked_broadcast
+                    .send_response(channel, ())
+                    .is_err()
+                {
+                    tracing::error!(%sender_peer_id, ?sender_short_id, "Failed to acknowledge chunk");
+                }
+
+                let Some(msg) = self.chunks_reassembler.insert(sender_short_id, chunk) else {
+                    return;
+                };
+
+                tracing::debug!(%sender_peer_id, ?sender_short_id, len = msg.len(), "Received chunked broadcast message from peer");
+                if self
+                    .tx_received_messages
+                    .send(ReceivedMessage::new_broadcast(sender_short_id, msg))
+                    .is_err()
+                {
+                    tracing::error!(%sender_peer_id, ?sender_short_id, "Libp2p node failed to forward message through channel: channel closed");
+                }
+            }
+
+            RequestResponseEvent::OutboundFailure {
+                peer: peer_id,
+                error,
+                ..
+            } => {
+                let short_id = self.peers.get_short_id(&peer_id);
+                tracing::error!(%peer_id, ?short_id, ?error, "Failed to send chunk to peer");
+            }
+
+            RequestResponseEvent::InboundFailure {
+                peer: peer_id,
+                error,
+                ..
+            } => {
+                let short_id = self.peers.get_short_id(&peer_id);
+                tracing::error!(%peer_id, ?short_id, ?error, "Failed to receive chunk from peer");
+            }
+
+            _ => {}
+        }
+    }
+
     fn handle_point_to_point_event(&mut self, event: RequestResponseEvent<Vec<u8>, ()>) {
         match event {
             RequestResponseEvent::Message {
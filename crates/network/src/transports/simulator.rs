@@ -0,0 +1,658 @@
+//! Simulated transport driven by a seeded scheduler, primarily designed for use in tests.
+//!
+//! Unlike the [in memory transport](super::in_memory), messages are not delivered in order. Each
+//! message sent from a node to another goes through a central scheduler which, based on the
+//! [`SimulatorConfig`], may delay, reorder, drop, duplicate, or hold the message while the two nodes
+//! are partitioned. Nodes can also be configured to crash at a specific time, or to equivocate by
+//! sending different messages to different recipients.
+//!
+//! Every decision of the scheduler is taken using a random number generator seeded by
+//! [`SimulatorConfig::seed`], in the order in which messages are submitted to the scheduler. Running
+//! the nodes on a `current_thread` runtime with a paused clock (e.g.,
+//! `#[tokio::test(start_paused = true)]`) makes the whole execution reproducible from the seed.
+
+use crate::{PartyIdentifier, ReceivedMessage, Recipient, Transport, TransportSender};
+use futures_util::StreamExt;
+use futures_util::stream::BoxStream;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tokio_util::sync::CancellationToken;
+
+#[derive(thiserror::Error, Debug)]
+pub enum SimulatorError {
+    #[error("the simulator has been stopped")]
+    Stopped,
+}
+
+/// Function used by an equivocating node to derive the message sent to a specific recipient.
+/// Returning `None` results in the message not being sent to the recipient.
+pub type EquivocateFn<ID> = Arc<dyn Fn(ID, &[u8]) -> Option<Vec<u8>> + Send + Sync>;
+
+/// Behaviour of a node in the simulation.
+#[derive(Clone, Default)]
+pub enum NodeBehaviour<ID> {
+    /// Messages are sent as is.
+    #[default]
+    Honest,
+
+    /// The node stops sending and receiving messages once `at` has elapsed since the start of the
+    /// simulation.
+    Crash { at: Duration },
+
+    /// The message sent to each recipient is obtained through the equivocation function.
+    Equivocate(EquivocateFn<ID>),
+}
+
+/// Properties of the (directed) link between two nodes.
+#[derive(Copy, Clone, Debug)]
+pub struct LinkConfig {
+    /// Minimum delay before a message is delivered.
+    pub min_delay: Duration,
+
+    /// Maximum delay before a message is delivered.
+    pub max_delay: Duration,
+
+    /// Whether messages can be delivered in a different order than the one they were sent in.
+    pub reorder: bool,
+
+    /// Probability that a message is dropped.
+    pub drop_probability: f64,
+
+    /// Probability that a message is delivered twice.
+    pub duplicate_probability: f64,
+}
+
+impl Default for LinkConfig {
+    fn default() -> Self {
+        Self::reliable()
+    }
+}
+
+impl LinkConfig {
+    /// A link that delivers each message immediately, and in order.
+    pub fn reliable() -> Self {
+        Self {
+            min_delay: Duration::ZERO,
+            max_delay: Duration::ZERO,
+            reorder: false,
+            drop_probability: 0.,
+            duplicate_probability: 0.,
+        }
+    }
+
+    /// An asynchronous link that delivers messages after a random delay in [min_delay, max_delay],
+    /// possibly out of order.
+    pub fn asynchronous(min_delay: Duration, max_delay: Duration) -> Self {
+        Self {
+            min_delay,
+            max_delay,
+            reorder: true,
+            ..Self::reliable()
+        }
+    }
+
+    pub fn with_drop_probability(mut self, drop_probability: f64) -> Self {
+        self.drop_probability = drop_probability;
+        self
+    }
+
+    pub fn with_duplicate_probability(mut self, duplicate_probability: f64) -> Self {
+        self.duplicate_probability = duplicate_probability;
+        self
+    }
+}
+
+/// Partition isolating a set of nodes from the remaining nodes between `start` and `end`, relative
+/// to the start of the simulation.
+///
+/// Messages sent between the two sides of the partition while it is active are held until the
+/// partition heals, or dropped if `end` is `None`.
+#[derive(Clone, Debug)]
+pub struct Partition<ID> {
+    pub nodes: HashSet<ID>,
+    pub start: Duration,
+    pub end: Option<Duration>,
+}
+
+/// Configuration of the simulated network.
+#[derive(Clone)]
+pub struct SimulatorConfig<ID> {
+    pub seed: u64,
+    pub default_link: LinkConfig,
+    pub links: HashMap<(ID, ID), LinkConfig>,
+    pub partitions: Vec<Partition<ID>>,
+    pub behaviours: HashMap<ID, NodeBehaviour<ID>>,
+}
+
+/// Counters updated by the scheduler.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct SimulatorStats {
+    pub sent: usize,
+    pub delivered: usize,
+    pub dropped: usize,
+    pub duplicated: usize,
+}
+
+impl<ID: PartyIdentifier> SimulatorConfig<ID> {
+    /// Create a new configuration with reliable links and honest nodes.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            default_link: LinkConfig::reliable(),
+            links: HashMap::new(),
+            partitions: vec![],
+            behaviours: HashMap::new(),
+        }
+    }
+
+    /// Create a new configuration with the seed stored in the `var` environment variable, or with
+    /// a random seed if the variable is not set. The seed is logged such that failures can be
+    /// replayed.
+    pub fn from_env(var: &str) -> Self {
+        let seed = std::env::var(var)
+            .ok()
+            .and_then(|seed| seed.parse().ok())
+            .unwrap_or_else(rand::random);
+        tracing::info!(seed, "Using simulator seed, set {var}={seed} to replay");
+
+        Self::new(seed)
+    }
+
+    /// Set the configuration used by links without a specific configuration.
+    pub fn with_default_link(mut self, link: LinkConfig) -> Self {
+        self.default_link = link;
+        self
+    }
+
+    /// Set the configuration of the link from `from` to `to`.
+    pub fn with_link(mut self, from: ID, to: ID, link: LinkConfig) -> Self {
+        self.links.insert((from, to), link);
+        self
+    }
+
+    pub fn with_partition(mut self, partition: Partition<ID>) -> Self {
+        self.partitions.push(partition);
+        self
+    }
+
+    pub fn with_behaviour(mut self, id: ID, behaviour: NodeBehaviour<ID>) -> Self {
+        self.behaviours.insert(id, behaviour);
+        self
+    }
+}
+
+/// Used to obtain a [`Transport`] for each node of the simulated network.
+pub struct SimulatedNetwork;
+
+/// Handle to the scheduler of a simulated network.
+pub struct SimulatorHandle {
+    seed: u64,
+    stats: Arc<Mutex<SimulatorStats>>,
+    cancel: CancellationToken,
+    task: tokio::task::JoinHandle<()>,
+}
+
+pub struct SimulatedTransport<ID: PartyIdentifier> {
+    id: ID,
+    tx_scheduler: mpsc::UnboundedSender<Envelope<ID>>,
+    rx_channel: Option<mpsc::UnboundedReceiver<ReceivedMessage<ID>>>,
+}
+
+#[derive(Clone)]
+pub struct SimulatedSender<ID: PartyIdentifier> {
+    id: ID,
+    tx_scheduler: mpsc::UnboundedSender<Envelope<ID>>,
+}
+
+struct Envelope<ID: PartyIdentifier> {
+    sender: ID,
+    recipient: Recipient<ID>,
+    m: Vec<u8>,
+}
+
+/// A message scheduled to be delivered at a specific time.
+struct Delivery<ID: PartyIdentifier> {
+    at: Instant,
+    seq: u64,
+    to: ID,
+    msg: ReceivedMessage<ID>,
+}
+
+struct Scheduler<ID: PartyIdentifier> {
+    config: SimulatorConfig<ID>,
+    rng: ChaCha20Rng,
+    start: Instant,
+    seq: u64,
+    ids: Vec<ID>,
+    queue: BinaryHeap<Delivery<ID>>,
+    last_delivery: HashMap<(ID, ID), Instant>,
+    nodes: HashMap<ID, mpsc::UnboundedSender<ReceivedMessage<ID>>>,
+    stats: Arc<Mutex<SimulatorStats>>,
+}
+
+impl SimulatedNetwork {
+    /// Get an individual transport for each of the nodes, and start the scheduler in the
+    /// background.
+    pub fn get_transports<ID: PartyIdentifier>(
+        ids: impl IntoIterator<Item = ID>,
+        config: SimulatorConfig<ID>,
+    ) -> (SimulatorHandle, VecDeque<SimulatedTransport<ID>>) {
+        let (tx_scheduler, rx_scheduler) = mpsc::unbounded_channel();
+        let mut nodes = HashMap::new();
+        let mut ids_vec = vec![];
+        let transports = ids
+            .into_iter()
+            .map(|id| {
+                let (tx, rx) = mpsc::unbounded_channel();
+                nodes.insert(id, tx);
+                ids_vec.push(id);
+                SimulatedTransport {
+                    id,
+                    tx_scheduler: tx_scheduler.clone(),
+                    rx_channel: Some(rx),
+                }
+            })
+            .collect();
+
+        let seed = config.seed;
+        let stats = Arc::new(Mutex::new(SimulatorStats::default()));
+        let cancel = CancellationToken::new();
+        let scheduler = Scheduler {
+            rng: ChaCha20Rng::seed_from_u64(config.seed),
+            config,
+            start: Instant::now(),
+            seq: 0,
+            ids: ids_vec,
+            queue: BinaryHeap::new(),
+            last_delivery: HashMap::new(),
+            nodes,
+            stats: stats.clone(),
+        };
+        let task = tokio::task::spawn(scheduler.run(rx_scheduler, cancel.clone()));
+
+        let handle = SimulatorHandle {
+            seed,
+            stats,
+            cancel,
+            task,
+        };
+        (handle, transports)
+    }
+}
+
+impl SimulatorHandle {
+    /// The seed used by the scheduler.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Current statistics of the scheduler.
+    pub fn stats(&self) -> SimulatorStats {
+        *self
+            .stats
+            .lock()
+            .expect("a thread panicked holding the mutex")
+    }
+
+    /// Stop the scheduler, messages that have not been delivered yet are discarded.
+    pub async fn stop(self) -> SimulatorStats {
+        self.cancel.cancel();
+        if let Err(e) = self.task.await {
+            tracing::error!(error = ?e, "Simulator scheduler task panicked");
+        }
+
+        *self
+            .stats
+            .lock()
+            .expect("a thread panicked holding the mutex")
+    }
+}
+
+impl<ID: PartyIdentifier> Scheduler<ID> {
+    async fn run(
+        mut self,
+        mut rx: mpsc::UnboundedReceiver<Envelope<ID>>,
+        cancel: CancellationToken,
+    ) {
+        let mut rx_closed = false;
+        loop {
+            let next_delivery = self.queue.peek().map(|d| d.at);
+            if rx_closed && next_delivery.is_none() {
+                break;
+            }
+
+            tokio::select! {
+                biased;
+
+                _ = cancel.cancelled() => break,
+
+                envelope = rx.recv(), if !rx_closed => match envelope {
+                    Some(envelope) => self.schedule(envelope),
+                    None => rx_closed = true,
+                },
+
+                _ = tokio::time::sleep_until(next_delivery.unwrap_or_else(Instant::now)), if next_delivery.is_some() => {
+                    self.deliver_due();
+                }
+            }
+        }
+    }
+
+    /// Schedule the delivery of a message to each of its recipients.
+    fn schedule(&mut self, envelope: Envelope<ID>) {
+        let Envelope {
+            sender,
+            recipient,
+            m,
+        } = envelope;
+        let now = Instant::now();
+        let elapsed = now - self.start;
+
+        let recipients: Vec<ID> = match recipient {
+            Recipient::All => self.ids.iter().copied().filter(|i| *i != sender).collect(),
+            Recipient::AllIncludingSelf => self.ids.clone(),
+            Recipient::Single(i) => vec![i],
+        };
+
+        for to in recipients {
+            // Crashed nodes do not send messages
+            if self.is_crashed(&sender, elapsed) {
+                self.update_stats(|s| s.dropped += 1);
+                continue;
+            }
+            self.update_stats(|s| s.sent += 1);
+
+            let m = match self.config.behaviours.get(&sender) {
+                Some(NodeBehaviour::Equivocate(f)) => match f(to, &m) {
+                    Some(m) => m,
+                    None => {
+                        self.update_stats(|s| s.dropped += 1);
+                        continue;
+                    }
+                },
+                _ => m.clone(),
+            };
+
+            let link = *self
+                .config
+                .links
+                .get(&(sender, to))
+                .unwrap_or(&self.config.default_link);
+            if self.rng.gen_bool(link.drop_probability.clamp(0., 1.)) {
+                self.update_stats(|s| s.dropped += 1);
+                continue;
+            }
+
+            // Messages crossing a partition are held until it heals, or dropped if it never does
+            let not_before = self
+                .config
+                .partitions
+                .iter()
+                .filter(|p| {
+                    let active = p.start <= elapsed && p.end.is_none_or(|end| elapsed < end);
+                    active && p.nodes.contains(&sender) != p.nodes.contains(&to)
+                })
+                .try_fold(now, |not_before, p| {
+                    p.end.map(|end| not_before.max(self.start + end))
+                });
+            let Some(not_before) = not_before else {
+                self.update_stats(|s| s.dropped += 1);
+                continue;
+            };
+
+            let copies = if self.rng.gen_bool(link.duplicate_probability.clamp(0., 1.)) {
+                self.update_stats(|s| s.duplicated += 1);
+                2
+            } else {
+                1
+            };
+
+            for _ in 0..copies {
+                let delay = if link.max_delay > link.min_delay {
+                    self.rng.gen_range(link.min_delay..=link.max_delay)
+                } else {
+                    link.min_delay
+                };
+
+                let mut at = not_before + delay;
+                if !link.reorder {
+                    // Preserve the order of the messages on this link
+                    if let Some(last) = self.last_delivery.get(&(sender, to)) {
+                        at = at.max(*last);
+                    }
+                    self.last_delivery.insert((sender, to), at);
+                }
+
+                let msg = ReceivedMessage::new(sender, m.clone(), recipient.into());
+                self.seq += 1;
+                self.queue.push(Delivery {
+                    at,
+                    seq: self.seq,
+                    to,
+                    msg,
+                });
+            }
+        }
+    }
+
+    /// Deliver all the messages that are due.
+    fn deliver_due(&mut self) {
+        let now = Instant::now();
+        let elapsed = now - self.start;
+        while self.queue.peek().is_some_and(|d| d.at <= now) {
+            let Delivery { to, msg, .. } = self.queue.pop().expect("peeked");
+
+            // Crashed nodes do not receive messages
+            if self.is_crashed(&to, elapsed) {
+                self.update_stats(|s| s.dropped += 1);
+                continue;
+            }
+
+            match self.nodes.get(&to) {
+                Some(tx) if tx.send(msg).is_ok() => self.update_stats(|s| s.delivered += 1),
+                _ => {
+                    tracing::debug!(recipient = %to, "Simulator failed to deliver message: no receiver");
+                    self.update_stats(|s| s.dropped += 1);
+                }
+            }
+        }
+    }
+
+    fn is_crashed(&self, id: &ID, elapsed: Duration) -> bool {
+        matches!(self.config.behaviours.get(id), Some(NodeBehaviour::Crash { at }) if *at <= elapsed)
+    }
+
+    fn update_stats(&self, f: impl FnOnce(&mut SimulatorStats)) {
+        f(&mut self
+            .stats
+            .lock()
+            .expect("a thread panicked holding the mutex"));
+    }
+}
+
+impl<ID: PartyIdentifier> Transport for SimulatedTransport<ID> {
+    type Error = SimulatorError;
+    type Identity = ID;
+    type ReceiveMessageStream = BoxStream<'static, Result<ReceivedMessage<ID>, Self::Error>>;
+    type Sender = SimulatedSender<ID>;
+
+    fn sender(&mut self) -> Option<Self::Sender> {
+        Some(SimulatedSender {
+            id: self.id,
+            tx_scheduler: self.tx_scheduler.clone(),
+        })
+    }
+
+    fn receiver_stream(&mut self) -> Option<Self::ReceiveMessageStream> {
+        Some(
+            UnboundedReceiverStream::new(self.rx_channel.take()?)
+                .map(Ok)
+                .boxed(),
+        )
+    }
+}
+
+impl<ID: PartyIdentifier> TransportSender for SimulatedSender<ID> {
+    type Identity = ID;
+    type Error = SimulatorError;
+
+    async fn send(&self, msg: Vec<u8>, to: Recipient<Self::Identity>) -> Result<(), Self::Error> {
+        self.tx_scheduler
+            .send(Envelope {
+                sender: self.id,
+                recipient: to,
+                m: msg,
+            })
+            .map_err(|_| SimulatorError::Stopped)
+    }
+}
+
+impl<ID: PartyIdentifier> PartialEq for Delivery<ID> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<ID: PartyIdentifier> Eq for Delivery<ID> {}
+
+impl<ID: PartyIdentifier> PartialOrd for Delivery<ID> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<ID: PartyIdentifier> Ord for Delivery<ID> {
+    /// Reversed ordering such that the [`BinaryHeap`] pops the earliest delivery first.
+    fn cmp(&self, other: &Self) -> Ordering {
+        (other.at, other.seq).cmp(&(self.at, self.seq))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Send `n_messages` from node 1 to node 2, and return the messages received by node 2.
+    async fn exchange(config: SimulatorConfig<u16>, n_messages: u8) -> (Vec<u8>, SimulatorStats) {
+        let (handle, mut transports) = SimulatedNetwork::get_transports([1u16, 2], config);
+        let sender = transports[0].sender().unwrap();
+        let mut receiver = transports[1].receiver_stream().unwrap();
+
+        for i in 0..n_messages {
+            sender.send_single(vec![i], 2).await.unwrap();
+        }
+
+        let mut received = vec![];
+        while let Ok(Some(Ok(msg))) =
+            tokio::time::timeout(Duration::from_secs(60), receiver.next()).await
+        {
+            assert_eq!(msg.sender, 1);
+            received.push(msg.content[0]);
+        }
+
+        (received, handle.stop().await)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn reliable_links_preserve_order() {
+        let (received, stats) = exchange(SimulatorConfig::new(0), 32).await;
+        assert_eq!(received, (0..32).collect::<Vec<_>>());
+        assert_eq!(stats.delivered, 32);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn same_seed_same_schedule() {
+        let config = |seed| {
+            SimulatorConfig::new(seed).with_default_link(
+                LinkConfig::asynchronous(Duration::from_millis(1), Duration::from_secs(1))
+                    .with_drop_probability(0.1)
+                    .with_duplicate_probability(0.1),
+            )
+        };
+
+        let (received_1, stats_1) = exchange(config(42), 64).await;
+        let (received_2, stats_2) = exchange(config(42), 64).await;
+        assert_eq!(received_1, received_2);
+        assert_eq!(stats_1, stats_2);
+
+        // Messages have been reordered, and some have been dropped / duplicated
+        assert_ne!(received_1, (0..64).collect::<Vec<_>>());
+        assert!(stats_1.dropped > 0);
+        assert!(stats_1.duplicated > 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn partition_holds_messages_until_healed() {
+        let config = SimulatorConfig::new(0).with_partition(Partition {
+            nodes: HashSet::from([2]),
+            start: Duration::ZERO,
+            end: Some(Duration::from_secs(10)),
+        });
+
+        let (handle, mut transports) = SimulatedNetwork::get_transports([1u16, 2], config);
+        let sender = transports[0].sender().unwrap();
+        let mut receiver = transports[1].receiver_stream().unwrap();
+        sender.broadcast(vec![0]).await.unwrap();
+
+        let start = Instant::now();
+        let msg = receiver.next().await.unwrap().unwrap();
+        assert_eq!(msg.content, vec![0]);
+        assert!(start.elapsed() >= Duration::from_secs(10));
+        handle.stop().await;
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn crashed_node_stops_sending() {
+        let config = SimulatorConfig::new(0).with_behaviour(
+            1,
+            NodeBehaviour::Crash {
+                at: Duration::from_secs(1),
+            },
+        );
+
+        let (handle, mut transports) = SimulatedNetwork::get_transports([1u16, 2], config);
+        let sender = transports[0].sender().unwrap();
+        let mut receiver = transports[1].receiver_stream().unwrap();
+        sender.broadcast(vec![0]).await.unwrap();
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        sender.broadcast(vec![1]).await.unwrap();
+
+        assert_eq!(receiver.next().await.unwrap().unwrap().content, vec![0]);
+        assert!(
+            tokio::time::timeout(Duration::from_secs(60), receiver.next())
+                .await
+                .is_err()
+        );
+        let stats = handle.stop().await;
+        assert_eq!(stats.sent, 1);
+        assert_eq!(stats.dropped, 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn equivocating_node_sends_different_messages() {
+        let config = SimulatorConfig::new(0).with_behaviour(
+            1,
+            NodeBehaviour::Equivocate(Arc::new(|to: u16, m: &[u8]| {
+                Some([m, &to.to_be_bytes()].concat())
+            })),
+        );
+
+        let (handle, mut transports) = SimulatedNetwork::get_transports([1u16, 2, 3], config);
+        let sender = transports[0].sender().unwrap();
+        sender.broadcast(vec![0]).await.unwrap();
+
+        for to in [2u16, 3] {
+            let mut receiver = transports[usize::from(to) - 1].receiver_stream().unwrap();
+            let msg = receiver.next().await.unwrap().unwrap();
+            assert_eq!(msg.content, [&[0u8][..], &to.to_be_bytes()].concat());
+        }
+        handle.stop().await;
+    }
+}
@@ -50,8 +50,8 @@ pub enum OnlySwapsClientError {
     Alloy(#[from] OnlySwapsClientOtherError),
 
     #[cfg(feature = "solver")]
-    #[error("relay tokens reverted")]
-    RelayTokensReverted,
+    #[error("relay tokens reverted in tx {}", .0.transaction_hash)]
+    RelayTokensReverted(Box<alloy::rpc::types::TransactionReceipt>),
 
     #[cfg(all(feature = "solver", feature = "permit2"))]
     #[error(transparent)]
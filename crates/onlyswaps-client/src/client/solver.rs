@@ -4,8 +4,8 @@
 mod gasless;
 
 use crate::client::{OnlySwapsClient, OnlySwapsClientError};
-use alloy::primitives::{Address, B256, U256};
-use alloy::rpc::types::TransactionReceipt;
+use alloy::primitives::{Address, B256, TxHash, U256};
+use alloy::rpc::types::{TransactionReceipt, TransactionRequest};
 use futures_util::FutureExt;
 use futures_util::future::BoxFuture;
 use generated::onlyswaps::i_router::IRouter::{Hook, IRouterInstance};
@@ -104,30 +104,47 @@ impl OnlySwapsClient {
             .await
             .map_err(|e| (e, "failed to estimate gas for relayTokens"))?;
 
-        let send_fut = async move {
-            let tx_hash = call
-                .send()
-                .await
-                .map_err(|e| (e, "failed to send relayTokens tx"))?
-                .with_required_confirmations(chain.config.required_confirmations)
-                .with_timeout(Some(chain.config.timeout))
-                .watch()
-                .await?;
-
-            // Fetch the receipt to get the tx status
-            let receipt = super::backoff::get_receipt(tx_hash, router.provider()).await?;
-            if !receipt.status() {
-                tracing::error!(?receipt, "error submitting relayTokens: tx reverted");
-                return Err(OnlySwapsClientError::RelayTokensReverted);
-            }
+        let request = call.clone().into_transaction_request();
+        let send_fn = move |fees: Option<TransactionFees>| {
+            async move {
+                let call = match fees {
+                    Some(fees) => call
+                        .max_fee_per_gas(fees.max_fee_per_gas)
+                        .max_priority_fee_per_gas(fees.max_priority_fee_per_gas),
+                    None => call,
+                };
+                let pending_tx = call
+                    .send()
+                    .await
+                    .map_err(|e| (e, "failed to send relayTokens tx"))?
+                    .with_required_confirmations(chain.config.required_confirmations)
+                    .with_timeout(Some(chain.config.timeout));
+                let tx_hash = *pending_tx.tx_hash();
+
+                let receipt = async move {
+                    let tx_hash = pending_tx.watch().await?;
+
+                    // Fetch the receipt to get the tx status
+                    let receipt = super::backoff::get_receipt(tx_hash, router.provider()).await?;
+                    if !receipt.status() {
+                        tracing::error!(?receipt, "error submitting relayTokens: tx reverted");
+                        return Err(OnlySwapsClientError::RelayTokensReverted(Box::new(receipt)));
+                    }
 
-            Ok(receipt)
-        }
-        .boxed();
+                    Ok(receipt)
+                }
+                .boxed();
+
+                Ok(SubmittedTransaction { tx_hash, receipt })
+            }
+            .boxed()
+        };
 
         Ok(SendableTransaction {
             gas_estimate,
-            send_fut,
+            request,
+            fees: None,
+            send_fn: Box::new(send_fn),
         })
     }
 
@@ -225,45 +242,116 @@ impl OnlySwapsClient {
             .await
             .map_err(|e| (e, "failed to estimate gas for relayTokens"))?;
 
-        let send_fut = async move {
-            let tx_hash = call
-                .send()
-                .await
-                .map_err(|e| (e, "failed to send relayTokensPermit2 tx"))?
-                .with_required_confirmations(chain.config.required_confirmations)
-                .with_timeout(Some(chain.config.timeout))
-                .watch()
-                .await?;
-
-            // Fetch the receipt to get the tx status
-            let receipt = super::backoff::get_receipt(tx_hash, router.provider()).await?;
-            if !receipt.status() {
-                tracing::error!(?receipt, "error submitting relayTokens: tx reverted");
-                return Err(OnlySwapsClientError::RelayTokensReverted);
-            }
+        let request = call.clone().into_transaction_request();
+        let send_fn = move |fees: Option<TransactionFees>| {
+            async move {
+                let call = match fees {
+                    Some(fees) => call
+                        .max_fee_per_gas(fees.max_fee_per_gas)
+                        .max_priority_fee_per_gas(fees.max_priority_fee_per_gas),
+                    None => call,
+                };
+                let pending_tx = call
+                    .send()
+                    .await
+                    .map_err(|e| (e, "failed to send relayTokensPermit2 tx"))?
+                    .with_required_confirmations(chain.config.required_confirmations)
+                    .with_timeout(Some(chain.config.timeout));
+                let tx_hash = *pending_tx.tx_hash();
+
+                let receipt = async move {
+                    let tx_hash = pending_tx.watch().await?;
 
-            Ok(receipt)
-        }
-        .boxed();
+                    // Fetch the receipt to get the tx status
+                    let receipt = super::backoff::get_receipt(tx_hash, router.provider()).await?;
+                    if !receipt.status() {
+                        tracing::error!(?receipt, "error submitting relayTokens: tx reverted");
+                        return Err(OnlySwapsClientError::RelayTokensReverted(Box::new(receipt)));
+                    }
+
+                    Ok(receipt)
+                }
+                .boxed();
+
+                Ok(SubmittedTransaction { tx_hash, receipt })
+            }
+            .boxed()
+        };
 
         Ok(SendableTransaction {
             gas_estimate,
-            send_fut,
+            request,
+            fees: None,
+            send_fn: Box::new(send_fn),
         })
     }
 }
 
+/// Fees overriding the ones filled in by the provider when sending a transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TransactionFees {
+    pub max_fee_per_gas: u128,
+    pub max_priority_fee_per_gas: u128,
+}
+
+type SendFn<'a> = Box<
+    dyn FnOnce(
+            Option<TransactionFees>,
+        ) -> BoxFuture<'a, Result<SubmittedTransaction<'a>, OnlySwapsClientError>>
+        + Send
+        + 'a,
+>;
+
 pub struct SendableTransaction<'a> {
     gas_estimate: u64,
-    send_fut: BoxFuture<'a, Result<TransactionReceipt, OnlySwapsClientError>>,
+    request: TransactionRequest,
+    fees: Option<TransactionFees>,
+    send_fn: SendFn<'a>,
 }
 
-impl SendableTransaction<'_> {
+impl<'a> SendableTransaction<'a> {
     pub fn gas_estimate(&self) -> u64 {
         self.gas_estimate
     }
 
+    /// The transaction that will be sent, e.g. to simulate it beforehand. The sender and
+    /// fees are only filled in when sending the transaction.
+    pub fn transaction_request(&self) -> &TransactionRequest {
+        &self.request
+    }
+
+    /// Send the transaction with the specified fees instead of the ones estimated by the provider.
+    pub fn with_fees(mut self, fees: TransactionFees) -> Self {
+        self.fees = Some(fees);
+        self
+    }
+
+    /// Submit the transaction to the network, without waiting for it to be included.
+    pub async fn submit(self) -> Result<SubmittedTransaction<'a>, OnlySwapsClientError> {
+        (self.send_fn)(self.fees).await
+    }
+
+    /// Submit the transaction and wait for its receipt.
     pub async fn send(self) -> Result<TransactionReceipt, OnlySwapsClientError> {
-        self.send_fut.await
+        self.submit().await?.receipt().await
+    }
+}
+
+/// A transaction that has been submitted to the network.
+pub struct SubmittedTransaction<'a> {
+    tx_hash: TxHash,
+    receipt: BoxFuture<'a, Result<TransactionReceipt, OnlySwapsClientError>>,
+}
+
+impl SubmittedTransaction<'_> {
+    pub fn tx_hash(&self) -> TxHash {
+        self.tx_hash
+    }
+
+    /// Wait for the transaction to be included, and obtain its receipt.
+    /// Transactions included but reverted return [`OnlySwapsClientError::RelayTokensReverted`]
+    /// along with their receipt.
+    pub async fn receipt(self) -> Result<TransactionReceipt, OnlySwapsClientError> {
+        self.receipt.await
     }
 }
@@ -0,0 +1,120 @@
+//! Compares the per-partial verification of BLS partial signatures with the batch verification
+//! for various batch sizes, with and without an invalid partial in the batch.
+//!
+//! Run with `cargo bench -p dcipher-signer --bench bls_batch_verify --features bn254,sha3`.
+use ark_bn254::{Bn254, Fr};
+use ark_ec::pairing::Pairing;
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_std::UniformRand;
+use dcipher_signer::bls::{BlsPairingSigner, BlsSigner, BlsVerifier};
+use sha3::Keccak256;
+use std::time::{Duration, Instant};
+
+const PARTIES: usize = 5;
+const BATCH_SIZES: [usize; 4] = [4, 16, 64, 256];
+const ITERATIONS: u32 = 5;
+const DST_G1: &[u8] = b"BENCH-BN254G1_XMD:KECCAK-256_SVDW_RO_";
+const DST_G2: &[u8] = b"BENCH-BN254G2_XMD:KECCAK-256_SVDW_RO_";
+
+type G1Affine = <Bn254 as Pairing>::G1Affine;
+type G2Affine = <Bn254 as Pairing>::G2Affine;
+
+/// Partials of all the parties on `batch_size / PARTIES` messages.
+struct Partials {
+    g1: Vec<(Vec<u8>, G1Affine, G2Affine)>,
+    g2: Vec<(Vec<u8>, G2Affine, G1Affine)>,
+}
+
+fn partials(signers: &[BlsPairingSigner<Bn254>], batch_size: usize, invalid: bool) -> Partials {
+    let mut g1 = Vec::with_capacity(batch_size);
+    let mut g2 = Vec::with_capacity(batch_size);
+    for i in 0..batch_size {
+        let signer = &signers[i % PARTIES];
+        let m = format!("message {}", i / PARTIES).into_bytes();
+        let sig_g1 = signer.sign_g1::<Keccak256>(&m, DST_G1).unwrap();
+        let sig_g2 = signer.sign_g2::<Keccak256>(&m, DST_G2).unwrap();
+        g1.push((m.clone(), sig_g1, signer.g2_public_key()));
+        g2.push((m, sig_g2, signer.g1_public_key()));
+    }
+
+    if invalid {
+        // Tamper with one of the partials
+        let i = batch_size / 2;
+        g1[i].1 = (g1[i].1 + G1Affine::generator()).into_affine();
+        g2[i].1 = (g2[i].1 + G2Affine::generator()).into_affine();
+    }
+
+    Partials { g1, g2 }
+}
+
+fn time(f: impl Fn() -> Vec<bool>, expected_valid: usize) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        let valid = f();
+        assert_eq!(valid.iter().filter(|v| **v).count(), expected_valid);
+    }
+    start.elapsed() / ITERATIONS
+}
+
+fn main() {
+    let rng = &mut ark_std::test_rng();
+    let signers: Vec<_> = (0..PARTIES)
+        .map(|_| BlsPairingSigner::new_bn254(Fr::rand(rng)))
+        .collect();
+    let verifier = &signers[0];
+
+    println!(
+        "{:<10} {:>6} {:>8} {:>14} {:>14} {:>8}",
+        "group", "batch", "invalid", "per-partial", "batch", "speedup"
+    );
+    for batch_size in BATCH_SIZES {
+        for invalid in [false, true] {
+            let partials = partials(&signers, batch_size, invalid);
+            let expected_valid = batch_size - usize::from(invalid);
+
+            let individual_g1 = time(
+                || {
+                    partials
+                        .g1
+                        .iter()
+                        .map(|(m, sig, pk)| verifier.verify_g1::<Keccak256>(m, DST_G1, *sig, *pk))
+                        .collect()
+                },
+                expected_valid,
+            );
+            let batch_g1 = time(
+                || verifier.batch_verify_g1::<Keccak256>(DST_G1, &partials.g1),
+                expected_valid,
+            );
+            let individual_g2 = time(
+                || {
+                    partials
+                        .g2
+                        .iter()
+                        .map(|(m, sig, pk)| verifier.verify_g2::<Keccak256>(m, DST_G2, *sig, *pk))
+                        .collect()
+                },
+                expected_valid,
+            );
+            let batch_g2 = time(
+                || verifier.batch_verify_g2::<Keccak256>(DST_G2, &partials.g2),
+                expected_valid,
+            );
+
+            for (group, individual, batch) in [
+                ("bn254 g1", individual_g1, batch_g1),
+                ("bn254 g2", individual_g2, batch_g2),
+            ] {
+                println!(
+                    "{:<10} {:>6} {:>8} {:>14?} {:>14?} {:>7.2}x",
+                    group,
+                    batch_size,
+                    invalid,
+                    individual,
+                    batch,
+                    individual.as_secs_f64() / batch.as_secs_f64()
+                );
+            }
+        }
+    }
+}
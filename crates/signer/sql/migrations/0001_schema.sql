@@ -0,0 +1,22 @@
+-- Aggregated signatures
+CREATE TABLE IF NOT EXISTS signatures (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    message BLOB NOT NULL,
+    dst BLOB NOT NULL,
+    alg BLOB NOT NULL, -- cbor encoded signature algorithm
+    signature BLOB NOT NULL, -- compressed point
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (message, dst)
+);
+
+-- Valid partial signatures
+CREATE TABLE IF NOT EXISTS partials (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    message BLOB NOT NULL,
+    dst BLOB NOT NULL,
+    alg BLOB NOT NULL, -- cbor encoded signature algorithm
+    party_id INTEGER NOT NULL,
+    partial BLOB NOT NULL, -- compressed point
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (message, dst, party_id)
+);
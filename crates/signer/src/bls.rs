@@ -1,17 +1,25 @@
 //! Implementation of a [`DSignerScheme`] for BLS signatures over any pairing-friendly curve.
 
 mod aggregation;
+mod batch;
+mod blame;
 mod dsigner_scheme_impl;
 mod filter;
 mod handlers;
 pub mod metrics;
 mod signer;
+pub mod storage;
+pub mod verify;
 
 pub use aggregation::lagrange_points_interpolate_at;
+pub use blame::{Misbehaviour, PartyMisbehaviour};
 pub use dsigner_scheme_impl::*;
 pub use signer::*;
 
+use crate::bls::blame::BlameRegistry;
 use crate::bls::filter::BlsFilter;
+use crate::bls::storage::{BlsSignatureStorage, StorageWriter};
+use crate::dsigner::policy::SigningPolicy;
 use crate::dsigner::{
     ApplicationArgs, BlsSignatureAlgorithm, BlsSignatureCurve, BlsSignatureHash, SchemeAlgorithm,
     SchemeDetails, SignatureAlgorithm, SignatureRequest,
@@ -43,6 +51,10 @@ type G2<BLS> = <E<BLS> as Pairing>::G2;
 type G1Affine<BLS> = <G1<BLS> as CurveGroup>::Affine;
 type G2Affine<BLS> = <G2<BLS> as CurveGroup>::Affine;
 
+// (message, signature, public key) to verify in a batch, with signatures on G1 / G2
+type BatchEntryG1<M, E> = (M, <E as Pairing>::G1Affine, <E as Pairing>::G2Affine);
+type BatchEntryG2<M, E> = (M, <E as Pairing>::G2Affine, <E as Pairing>::G1Affine);
+
 type Signature<BLS> = Either<G1Affine<BLS>, G2Affine<BLS>>;
 type SignatureOrChannel<BLS> =
     Either<Signature<BLS>, tokio::sync::watch::Sender<Option<Signature<BLS>>>>;
@@ -202,6 +214,12 @@ pub enum BlsThresholdSignerError {
     #[error("missing partial public key of party {0} on curve g2")]
     MissingPublicKeyG2(u16),
 
+    #[error("missing group public key on curve g1")]
+    MissingGroupPublicKeyG1,
+
+    #[error("missing group public key on curve g2")]
+    MissingGroupPublicKeyG2,
+
     #[error("unsupported hash function: {0:?} does not support {1:?}")]
     UnsupportedHash(BlsSignatureCurve, BlsSignatureHash),
 
@@ -242,6 +260,12 @@ where
     // Applications & algorithms filters
     filter: BlsFilter,
 
+    // Misbehaviour of the other parties
+    blame: BlameRegistry,
+
+    // Persistent storage of the signatures and partials
+    storage: Option<StorageWriter>,
+
     // Enable the node to broadcast a partial signature upon receiving a valid partial.
     // This mode is _insecure_ as it allows a single malicious node to sign arbitrary messages,
     // instead of a threshold of nodes. It must only be used in test deployment.
@@ -310,6 +334,8 @@ where
             pk_g1,
             pk_g2,
             filter: BlsFilter::new(Self::supported_bls_algorithms()),
+            blame: BlameRegistry::default(),
+            storage: None,
             // disable eager signing by default, i.e., automatically submitting a partial
             // signature upon receiving a valid partial from another node.
             eager_signing: false,
@@ -327,6 +353,22 @@ where
         self
     }
 
+    /// Persist the signatures and partials obtained by the signer to a storage, and restore
+    /// the most recent ones from the storage when the signer starts. Signatures are kept for
+    /// auditing, while the partials that can no longer be restored are regularly pruned.
+    pub fn with_storage(mut self, storage: impl BlsSignatureStorage + 'static) -> Self {
+        self.storage = Some(StorageWriter::new(Arc::new(storage)));
+        self
+    }
+
+    /// Only sign the requests allowed by a policy. The policy is evaluated before issuing a
+    /// partial signature, both for the requests of local clients, and for the requests received
+    /// from other nodes.
+    pub fn with_policy(mut self, policy: SigningPolicy) -> Self {
+        self.filter.set_policy(policy);
+        self
+    }
+
     /// Compute all possible supported algorithms for this curve
     fn supported_bls_algorithms() -> impl Iterator<Item = BlsSignatureAlgorithm> {
         let iter_all_hash = |curve, compression| {
@@ -410,6 +452,7 @@ where
             arc_self.signatures_cache.clone(),
             tx_registry_to_signer.clone(),
             arc_self.filter.clone(),
+            arc_self.blame.clone(),
         );
 
         let partials_stream = transport
@@ -419,20 +462,31 @@ where
             .sender()
             .expect("transport should provide at least one partial sender");
 
-        // Spawn task that handles signing requests from registry
-        tokio::task::spawn(arc_self.clone().sign_requests_loop(
+        let sign_requests_loop = arc_self.clone().sign_requests_loop(
             rx_signer_to_registry,
             tx_signer_to_network.clone(),
             cancellation_token.child_token(),
-        ));
-
-        // Spawn task that handles messages from other nodes
-        tokio::task::spawn(arc_self.clone().network_recv_loop(
+        );
+        let network_recv_loop = arc_self.clone().network_recv_loop(
             partials_stream,
             tx_registry_to_signer,
             tx_signer_to_network,
             cancellation_token.child_token(),
-        ));
+        );
+
+        arc_self.spawn_storage_writer(cancellation_token.child_token());
+        tokio::task::spawn(async move {
+            // Restore the caches before handling requests and messages
+            if let Err(e) = arc_self.restore_from_storage().await {
+                tracing::error!(error = ?e, "Failed to restore caches from storage");
+            }
+
+            // Spawn task that handles signing requests from registry
+            tokio::task::spawn(sign_requests_loop);
+
+            // Spawn task that handles messages from other nodes
+            tokio::task::spawn(network_recv_loop);
+        });
 
         (cancellation_token, signer)
     }
@@ -514,6 +568,42 @@ where
             }
         }?;
 
+        self.verify_with_public_key(m, dst, sig, pk, alg)
+    }
+
+    /// Verify an aggregated signature under the group public key using a specified algorithm if
+    /// supported, Err otherwise
+    fn try_verify_aggregate(
+        &self,
+        m: impl AsRef<[u8]>,
+        dst: impl AsRef<[u8]>,
+        sig: Group<BLS>,
+        alg: &BlsSignatureAlgorithm,
+    ) -> Result<bool, BlsThresholdSignerError> {
+        let pk = match &sig {
+            Group::G1Affine(_) => self
+                .pk_g2
+                .map(Group::G2Affine)
+                .ok_or(BlsThresholdSignerError::MissingGroupPublicKeyG2),
+            Group::G2Affine(_) => self
+                .pk_g1
+                .map(Group::G1Affine)
+                .ok_or(BlsThresholdSignerError::MissingGroupPublicKeyG1),
+        }?;
+
+        self.verify_with_public_key(m, dst, sig, pk, alg)
+    }
+
+    /// Verify a signature under a public key using a specified algorithm if supported, Err
+    /// otherwise
+    fn verify_with_public_key(
+        &self,
+        m: impl AsRef<[u8]>,
+        dst: impl AsRef<[u8]>,
+        sig: Group<BLS>,
+        pk: Group<BLS>,
+        alg: &BlsSignatureAlgorithm,
+    ) -> Result<bool, BlsThresholdSignerError> {
         match (alg.curve, alg.hash, sig, pk) {
             // Signature on G1, public key on G2
             (curve, hash, Group::G1Affine(sig), Group::G2Affine(pk))
@@ -591,6 +681,40 @@ where
         signature: <Self::E as Pairing>::G2Affine,
         public_key: <Self::E as Pairing>::G1Affine,
     ) -> bool;
+
+    /// Verify a batch of (message, signature, public key) sharing the same DST, and output
+    /// whether each signature is valid.
+    ///
+    /// The default implementation verifies each signature individually.
+    fn batch_verify_g1<H: FixedOutputReset + BlockSizeUser + Default + Clone>(
+        &self,
+        dst: impl AsRef<[u8]>,
+        batch: &[BatchEntryG1<impl AsRef<[u8]>, Self::E>],
+    ) -> Vec<bool> {
+        batch
+            .iter()
+            .map(|(m, signature, public_key)| {
+                self.verify_g1::<H>(m, dst.as_ref(), *signature, *public_key)
+            })
+            .collect()
+    }
+
+    /// Verify a batch of (message, signature, public key) sharing the same DST, and output
+    /// whether each signature is valid.
+    ///
+    /// The default implementation verifies each signature individually.
+    fn batch_verify_g2<H: FixedOutputReset + BlockSizeUser + Default + Clone>(
+        &self,
+        dst: impl AsRef<[u8]>,
+        batch: &[BatchEntryG2<impl AsRef<[u8]>, Self::E>],
+    ) -> Vec<bool> {
+        batch
+            .iter()
+            .map(|(m, signature, public_key)| {
+                self.verify_g2::<H>(m, dst.as_ref(), *signature, *public_key)
+            })
+            .collect()
+    }
 }
 
 pub trait BlsSigner: BlsVerifier {
@@ -1,6 +1,19 @@
-use ark_ec::{PrimeGroup, VariableBaseMSM};
+use crate::bls::blame::Misbehaviour;
+use crate::bls::{
+    BlsSigner, BlsThresholdSigner, G1, G1Affine, G2Affine, Group, PartialSignature, Signature,
+    StoredSignatureRequest,
+};
+use crate::dsigner::BlsSignatureAlgorithm;
+use ark_ec::{AffineRepr, CurveGroup, PrimeGroup, VariableBaseMSM};
 use ark_ff::{Field, PrimeField};
 use ark_std::One;
+use itertools::{Either, Itertools};
+use std::collections::BTreeMap;
+use utils::display::LogBytes;
+use utils::dst::NamedCurveGroup;
+use utils::serialize::point::{
+    PointDeserializeCompressed, PointSerializeCompressed, PointSerializeUncompressed,
+};
 
 /// Lagrange interpolation of the polynomial defined by its points, evaluated at point eval_x.
 pub fn lagrange_points_interpolate_at<G>(points: &[(u64, G)], eval_x: u64) -> G
@@ -38,3 +51,333 @@ where
     let bases = G::batch_convert_to_mul_base(&bases);
     G::msm(&bases, &scalars).expect("msm failed: bases and scalars have different lengths")
 }
+
+impl<BLS> BlsThresholdSigner<BLS>
+where
+    BLS: BlsSigner + Clone + Send + Sync + 'static,
+    G1Affine<BLS>:
+        PointSerializeCompressed + PointDeserializeCompressed + PointSerializeUncompressed,
+    G2Affine<BLS>:
+        PointSerializeCompressed + PointDeserializeCompressed + PointSerializeUncompressed,
+{
+    /// Aggregate the partials into a signature with Lagrange's interpolation.
+    pub(super) fn aggregate<'a>(
+        alg: &BlsSignatureAlgorithm,
+        partials: impl IntoIterator<Item = &'a PartialSignature<Group<BLS>>>,
+    ) -> Signature<BLS> {
+        let partials = partials.into_iter();
+        if <G1<BLS> as NamedCurveGroup>::CURVE_ID == alg.curve.into() {
+            // Collect the g1 partials
+            let points = partials
+                .map(|partial| {
+                    let sig = partial.sig.either().left();
+                    (
+                        u64::from(partial.id),
+                        sig.expect("g2 points stored in g1 request").into_group(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            Either::Left(lagrange_points_interpolate_at(&points, 0).into_affine())
+        } else {
+            // Collect the g2 partials
+            let points = partials
+                .map(|partial| {
+                    let sig = partial.sig.either().right();
+                    (
+                        u64::from(partial.id),
+                        sig.expect("g1 points stored in g2 request").into_group(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            Either::Right(lagrange_points_interpolate_at(&points, 0).into_affine())
+        }
+    }
+
+    /// Aggregate the partials into a signature, and output it iff it is valid under the group
+    /// public key. Without a group public key, the signature cannot be verified and None is
+    /// returned, such that callers fall back to the [robust aggregation](Self::robust_aggregate).
+    pub(super) fn optimistic_aggregate<'a>(
+        &self,
+        alg: &BlsSignatureAlgorithm,
+        req: &StoredSignatureRequest,
+        partials: impl IntoIterator<Item = &'a PartialSignature<Group<BLS>>>,
+    ) -> Option<Signature<BLS>> {
+        let sig = Self::aggregate(alg, partials);
+        match self.try_verify_aggregate(&req.m, &req.dst, sig.into(), alg) {
+            Ok(true) => Some(sig),
+            Ok(false) => None,
+            Err(e) => {
+                tracing::warn!(error = ?e, msg = %LogBytes(&req.m), "Cannot verify aggregated signature");
+                None
+            }
+        }
+    }
+
+    /// Aggregate the cached partials on a request with the [robust aggregation](Self::robust_aggregate),
+    /// and evict the invalid partials from the cache. The cache is not locked while aggregating,
+    /// since it requires pairings.
+    pub(super) fn aggregate_cached_partials(
+        &self,
+        alg: &BlsSignatureAlgorithm,
+        stored_req: &StoredSignatureRequest,
+    ) -> Option<Signature<BLS>> {
+        let mut partials = self
+            .partials_cache
+            .lock()
+            .expect("a thread panicked with the mutex")
+            .get(stored_req)?
+            .clone();
+
+        let ids: Vec<u16> = partials.keys().copied().collect();
+        let sig = self.robust_aggregate(alg, stored_req, &mut partials);
+        if partials.len() < ids.len() {
+            let mut partials_cache = self
+                .partials_cache
+                .lock()
+                .expect("a thread panicked with the mutex");
+            if let Some(cached_partials) = partials_cache.get_mut(stored_req) {
+                for id in ids.iter().filter(|id| !partials.contains_key(id)) {
+                    cached_partials.remove(id);
+                }
+            }
+        }
+
+        sig
+    }
+
+    /// Whether the aggregated signatures of `alg` can be verified under the group public key.
+    fn has_group_public_key(&self, alg: &BlsSignatureAlgorithm) -> bool {
+        if <G1<BLS> as NamedCurveGroup>::CURVE_ID == alg.curve.into() {
+            self.pk_g2.is_some()
+        } else {
+            self.pk_g1.is_some()
+        }
+    }
+
+    /// Aggregate t of the partials into a signature valid under the group public key, or None
+    /// if there are not enough valid partials.
+    /// If the first t partials do not aggregate into a valid signature, the partials are verified
+    /// individually, parties with invalid partials are blamed and their partials are evicted, and
+    /// the aggregation is retried with alternate subsets of t partials.
+    /// Without a group public key, nothing can be verified and the first t partials are
+    /// aggregated as is.
+    pub(super) fn robust_aggregate(
+        &self,
+        alg: &BlsSignatureAlgorithm,
+        req: &StoredSignatureRequest,
+        partials: &mut BTreeMap<u16, PartialSignature<Group<BLS>>>,
+    ) -> Option<Signature<BLS>> {
+        // Upper bound on the number of alternate subsets when no partial can be blamed
+        const MAX_ALTERNATE_SUBSETS: usize = 16;
+
+        let t = usize::from(self.t);
+        if partials.len() < t {
+            return None;
+        }
+
+        if let Some(sig) = self.optimistic_aggregate(alg, req, partials.values().take(t)) {
+            return Some(sig);
+        }
+
+        // The group public key is interpolated from the public key shares, hence neither the
+        // aggregate nor the partials can be verified without it
+        if !self.has_group_public_key(alg) {
+            tracing::warn!(msg = %LogBytes(&req.m), "Missing public keys, aggregating unverified partials");
+            return Some(Self::aggregate(alg, partials.values().take(t)));
+        }
+
+        tracing::error!(msg = %LogBytes(&req.m), partials_count = partials.len(), "Aggregated signature is invalid, verifying partials individually");
+        let invalid_ids: Vec<u16> = partials
+            .values()
+            .filter(|p| {
+                !matches!(
+                    self.try_verify(&req.m, &req.dst, p.sig, &p.id, alg),
+                    Ok(true)
+                )
+            })
+            .map(|p| p.id)
+            .collect();
+        for id in &invalid_ids {
+            partials.remove(id);
+            self.blame
+                .report(*id, Misbehaviour::InvalidAggregationShare);
+        }
+
+        // The first subset is known to be invalid unless we evicted some partials
+        let skip = usize::from(invalid_ids.is_empty());
+        let sig = partials
+            .values()
+            .combinations(t)
+            .skip(skip)
+            .take(MAX_ALTERNATE_SUBSETS)
+            .find_map(|subset| self.optimistic_aggregate(alg, req, subset));
+        if sig.is_none() {
+            tracing::error!(msg = %LogBytes(&req.m), partials_count = partials.len(), "Failed to aggregate a valid signature");
+        }
+
+        sig
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "bn254")]
+    mod bn254 {
+        use crate::bls::{
+            BlsPairingSigner, BlsSigner, BlsThresholdSigner, Group, PartialSignature,
+            PartyMisbehaviour, StoredSignatureRequest,
+        };
+        use crate::dsigner::{BlsSignatureAlgorithm, BlsSignatureCurve, BlsSignatureHash};
+        use ark_bn254::Fr;
+        use ark_ec::{AffineRepr, CurveGroup};
+        use ark_std::UniformRand;
+        use std::collections::{BTreeMap, HashMap};
+
+        #[test]
+        fn robust_aggregate_blames_invalid_partials() {
+            let rng = &mut ark_std::test_rng();
+            let (n, t) = (4u16, 2u16);
+            let (a0, a1) = (Fr::rand(rng), Fr::rand(rng));
+            let sks: Vec<_> = (1..=n).map(|i| a0 + a1 * Fr::from(i)).collect();
+            let pks_g1: HashMap<_, _> = (1..=n)
+                .map(|i| {
+                    let pk = ark_bn254::G1Affine::generator() * sks[usize::from(i) - 1];
+                    (i, pk.into_affine())
+                })
+                .collect();
+            let pks_g2: HashMap<_, _> = (1..=n)
+                .map(|i| {
+                    let pk = ark_bn254::G2Affine::generator() * sks[usize::from(i) - 1];
+                    (i, pk.into_affine())
+                })
+                .collect();
+
+            let signer = BlsThresholdSigner::new(
+                BlsPairingSigner::new_bn254(sks[0]),
+                n,
+                t,
+                1,
+                pks_g1,
+                pks_g2,
+            );
+
+            let dst = b"TEST-BN254G1_XMD:KECCAK-256_SVDW_RO_";
+            let req = StoredSignatureRequest {
+                m: b"robust_aggregate".to_vec().into(),
+                dst: dst.to_vec().into(),
+            };
+            let alg = BlsSignatureAlgorithm {
+                curve: BlsSignatureCurve::Bn254G1,
+                hash: BlsSignatureHash::Keccak256,
+                compression: false,
+            };
+
+            // Party 2 sends a partial on another message
+            let mut partials: BTreeMap<_, _> = [1u16, 2, 3]
+                .into_iter()
+                .map(|i| {
+                    let m: &[u8] = if i == 2 {
+                        b"another message"
+                    } else {
+                        &req.m[..]
+                    };
+                    let sig = BlsPairingSigner::new_bn254(sks[usize::from(i) - 1])
+                        .sign_g1::<sha3::Keccak256>(m, dst)
+                        .unwrap();
+                    let partial = PartialSignature {
+                        id: i,
+                        sig: Group::G1Affine(sig),
+                    };
+                    (i, partial)
+                })
+                .collect();
+
+            // Not enough partials
+            let mut not_enough = BTreeMap::from_iter([(1, partials[&1].clone())]);
+            assert!(
+                signer
+                    .robust_aggregate(&alg, &req, &mut not_enough)
+                    .is_none()
+            );
+
+            let sig = signer
+                .robust_aggregate(&alg, &req, &mut partials)
+                .expect("a valid signature from partials 1 and 3");
+            let expected = BlsPairingSigner::new_bn254(a0)
+                .sign_g1::<sha3::Keccak256>(&req.m, dst)
+                .unwrap();
+            assert_eq!(sig.left(), Some(expected));
+
+            // Party 2 has been blamed, and its partial evicted
+            assert!(!partials.contains_key(&2));
+            assert_eq!(
+                signer.blame.snapshot(),
+                BTreeMap::from_iter([(
+                    2,
+                    PartyMisbehaviour {
+                        invalid_partials: 0,
+                        invalid_aggregation_shares: 1,
+                    }
+                )])
+            );
+        }
+
+        #[test]
+        fn aggregate_without_group_public_key() {
+            let rng = &mut ark_std::test_rng();
+            let (n, t) = (3u16, 2u16);
+            let (a0, a1) = (Fr::rand(rng), Fr::rand(rng));
+            let sks: Vec<_> = (1..=n).map(|i| a0 + a1 * Fr::from(i)).collect();
+            let signer = BlsThresholdSigner::new(
+                BlsPairingSigner::new_bn254(sks[0]),
+                n,
+                t,
+                1,
+                HashMap::new(),
+                HashMap::new(),
+            );
+
+            let dst = b"TEST-BN254G1_XMD:KECCAK-256_SVDW_RO_";
+            let req = StoredSignatureRequest {
+                m: b"no group public key".to_vec().into(),
+                dst: dst.to_vec().into(),
+            };
+            let alg = BlsSignatureAlgorithm {
+                curve: BlsSignatureCurve::Bn254G1,
+                hash: BlsSignatureHash::Keccak256,
+                compression: false,
+            };
+            let mut partials: BTreeMap<_, _> = [1u16, 2]
+                .into_iter()
+                .map(|i| {
+                    let sig = BlsPairingSigner::new_bn254(sks[usize::from(i) - 1])
+                        .sign_g1::<sha3::Keccak256>(&req.m, dst)
+                        .unwrap();
+                    let partial = PartialSignature {
+                        id: i,
+                        sig: Group::G1Affine(sig),
+                    };
+                    (i, partial)
+                })
+                .collect();
+
+            // The aggregate cannot be verified optimistically
+            assert!(
+                signer
+                    .optimistic_aggregate(&alg, &req, partials.values())
+                    .is_none()
+            );
+
+            // But the robust aggregation outputs it without blaming anyone
+            let sig = signer
+                .robust_aggregate(&alg, &req, &mut partials)
+                .expect("an unverified signature");
+            let expected = BlsPairingSigner::new_bn254(a0)
+                .sign_g1::<sha3::Keccak256>(&req.m, dst)
+                .unwrap();
+            assert_eq!(sig.left(), Some(expected));
+            assert_eq!(partials.len(), 2);
+            assert!(signer.blame.snapshot().is_empty());
+        }
+    }
+}
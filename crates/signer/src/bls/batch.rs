@@ -0,0 +1,227 @@
+//! Batch verification of partial signatures received from other nodes.
+
+use crate::bls::blame::Misbehaviour;
+use crate::bls::metrics::Metrics;
+use crate::bls::{
+    BlsSignatureRequest, BlsSigner, BlsThresholdSigner, BlsThresholdSignerError, G1Affine,
+    G2Affine, Group, PartialSignature, StoredSignatureRequest,
+};
+use crate::dsigner::{BlsSignatureCurve, BlsSignatureHash};
+use bytes::Bytes;
+use std::collections::HashMap;
+use utils::serialize::point::{
+    PointDeserializeCompressed, PointSerializeCompressed, PointSerializeUncompressed,
+};
+
+/// Partial signature received from another node, pending verification.
+pub(super) struct PendingPartial<BLS>
+where
+    BLS: BlsSigner,
+{
+    pub(super) req: BlsSignatureRequest,
+    pub(super) stored_req: StoredSignatureRequest,
+    pub(super) partial: PartialSignature<Group<BLS>>,
+}
+
+/// Random 128-bit scalar used in random linear combinations, as little-endian limbs.
+pub(super) fn random_scalar() -> [u64; 2] {
+    let r = rand::random::<u128>().max(1);
+    [r as u64, (r >> 64) as u64]
+}
+
+/// Find the invalid entries of a batch by recursively bisecting it, where `check` outputs whether
+/// all the entries of a non-empty sub-batch are valid.
+pub(super) fn bisect_invalid<T: Copy>(batch: &[T], check: &mut impl FnMut(&[T]) -> bool) -> Vec<T> {
+    if batch.is_empty() || check(batch) {
+        return vec![];
+    }
+
+    if batch.len() == 1 {
+        return batch.to_vec();
+    }
+
+    let (left, right) = batch.split_at(batch.len() / 2);
+    let mut invalid = bisect_invalid(left, check);
+    invalid.extend(bisect_invalid(right, check));
+    invalid
+}
+
+impl<BLS> BlsThresholdSigner<BLS>
+where
+    BLS: BlsSigner + Clone + Send + Sync + 'static,
+    G1Affine<BLS>:
+        PointSerializeCompressed + PointDeserializeCompressed + PointSerializeUncompressed,
+    G2Affine<BLS>:
+        PointSerializeCompressed + PointDeserializeCompressed + PointSerializeUncompressed,
+{
+    /// Verify a batch of partial signatures and only keep the valid ones.
+    /// Partials are grouped by curve, hash and DST, and each group is verified at once.
+    pub(super) fn batch_verify_partials(
+        &self,
+        partials: Vec<PendingPartial<BLS>>,
+    ) -> Vec<PendingPartial<BLS>> {
+        type GroupKey = (BlsSignatureCurve, BlsSignatureHash, Bytes);
+
+        let mut groups: HashMap<GroupKey, Vec<PendingPartial<BLS>>> = HashMap::new();
+        for partial in partials {
+            let key = (
+                partial.req.alg.curve,
+                partial.req.alg.hash,
+                partial.stored_req.dst.clone(),
+            );
+            groups.entry(key).or_default().push(partial);
+        }
+
+        groups
+            .into_iter()
+            .flat_map(|((curve, hash, dst), partials)| {
+                let valid = if let [p] = partials.as_slice() {
+                    // Nothing to gain from a batch of a single partial
+                    self.try_verify(&p.req.m, &dst, p.partial.sig, &p.partial.id, &p.req.alg)
+                        .map(|valid| vec![valid])
+                } else {
+                    self.try_batch_verify(curve, hash, &dst, &partials)
+                };
+
+                let valid = match valid {
+                    Ok(valid) => valid,
+                    Err(e) => {
+                        // Algorithm should be supported at this point
+                        tracing::warn!(error = ?e, partials_count = partials.len(), "Failed to verify partials");
+                        return vec![];
+                    }
+                };
+
+                std::iter::zip(partials, valid)
+                    .filter_map(|(partial, valid)| {
+                        if !valid {
+                            tracing::error!(sender_id = partial.partial.id, "Received invalid partial signature");
+                            Metrics::report_invalid_partials(1);
+                            self.blame
+                                .report(partial.partial.id, Misbehaviour::InvalidPartial);
+                        }
+                        valid.then_some(partial)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Verify a batch of partials on the same curve, hash and DST, outputting whether each
+    /// partial is valid, or Err if the curve is not supported.
+    fn try_batch_verify(
+        &self,
+        curve: BlsSignatureCurve,
+        hash: BlsSignatureHash,
+        dst: &[u8],
+        partials: &[PendingPartial<BLS>],
+    ) -> Result<Vec<bool>, BlsThresholdSignerError> {
+        let mut valid = vec![false; partials.len()];
+
+        if Self::is_curve_g1(curve) {
+            // Signatures on G1, public keys on G2
+            let (indices, batch): (Vec<_>, Vec<_>) = partials
+                .iter()
+                .enumerate()
+                .filter_map(|(i, p)| {
+                    let Group::G1Affine(sig) = p.partial.sig else {
+                        None?
+                    };
+                    let Some(pk) = self.pks_g2.get(&p.partial.id) else {
+                        tracing::warn!(sender_id = p.partial.id, "Missing pk on g2 for party");
+                        None?
+                    };
+                    Some((i, (p.stored_req.m.as_ref(), sig, *pk)))
+                })
+                .unzip();
+
+            let batch_valid = match hash {
+                #[cfg(feature = "sha2")]
+                BlsSignatureHash::Sha256 => {
+                    self.signer.batch_verify_g1::<sha2::Sha256>(dst, &batch)
+                }
+
+                #[cfg(feature = "sha3")]
+                BlsSignatureHash::Keccak256 => {
+                    self.signer.batch_verify_g1::<sha3::Keccak256>(dst, &batch)
+                }
+            };
+            std::iter::zip(indices, batch_valid).for_each(|(i, v)| valid[i] = v);
+        } else if Self::is_curve_g2(curve) {
+            // Signatures on G2, public keys on G1
+            let (indices, batch): (Vec<_>, Vec<_>) = partials
+                .iter()
+                .enumerate()
+                .filter_map(|(i, p)| {
+                    let Group::G2Affine(sig) = p.partial.sig else {
+                        None?
+                    };
+                    let Some(pk) = self.pks_g1.get(&p.partial.id) else {
+                        tracing::warn!(sender_id = p.partial.id, "Missing pk on g1 for party");
+                        None?
+                    };
+                    Some((i, (p.stored_req.m.as_ref(), sig, *pk)))
+                })
+                .unzip();
+
+            let batch_valid = match hash {
+                #[cfg(feature = "sha2")]
+                BlsSignatureHash::Sha256 => {
+                    self.signer.batch_verify_g2::<sha2::Sha256>(dst, &batch)
+                }
+
+                #[cfg(feature = "sha3")]
+                BlsSignatureHash::Keccak256 => {
+                    self.signer.batch_verify_g2::<sha3::Keccak256>(dst, &batch)
+                }
+            };
+            std::iter::zip(indices, batch_valid).for_each(|(i, v)| valid[i] = v);
+        } else {
+            // Curve is neither G1 nor G2
+            Err(BlsThresholdSignerError::UnsupportedCurve(curve))?
+        }
+
+        Ok(valid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bisect_finds_all_invalid_entries() {
+        let batch: Vec<usize> = (0..37).collect();
+        let invalid_entries = [0, 5, 6, 36];
+
+        let invalid = bisect_invalid(&batch, &mut |sub_batch: &[usize]| {
+            sub_batch.iter().all(|i| !invalid_entries.contains(i))
+        });
+        assert_eq!(invalid, invalid_entries);
+    }
+
+    #[test]
+    fn bisect_checks_count() {
+        let batch: Vec<usize> = (0..64).collect();
+
+        // A valid batch requires a single check
+        let mut checks = 0;
+        let invalid = bisect_invalid(&batch, &mut |_: &[usize]| {
+            checks += 1;
+            true
+        });
+        assert!(invalid.is_empty());
+        assert_eq!(checks, 1);
+
+        // A single invalid entry requires 1 + 2 * log2(n) checks
+        let mut checks = 0;
+        let invalid = bisect_invalid(&batch, &mut |sub_batch: &[usize]| {
+            checks += 1;
+            !sub_batch.contains(&42)
+        });
+        assert_eq!(invalid, [42]);
+        assert_eq!(checks, 13);
+
+        assert!(bisect_invalid(&[] as &[usize], &mut |_: &[usize]| false).is_empty());
+    }
+}
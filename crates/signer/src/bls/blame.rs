@@ -0,0 +1,98 @@
+//! Record the misbehaviour of the other committee members.
+
+use crate::bls::metrics::Metrics;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+/// Kinds of misbehaviour that can be attributed to a party.
+#[derive(Clone, Copy, Hash, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Misbehaviour {
+    /// The party sent a partial signature that failed verification upon reception.
+    InvalidPartial,
+
+    /// A partial signature of the party was found to be invalid while aggregating a signature.
+    InvalidAggregationShare,
+}
+
+impl Misbehaviour {
+    pub(super) fn as_str(&self) -> &'static str {
+        match self {
+            Misbehaviour::InvalidPartial => "invalid_partial",
+            Misbehaviour::InvalidAggregationShare => "invalid_aggregation_share",
+        }
+    }
+}
+
+/// Misbehaviour counters of a party, as observed by the local node.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct PartyMisbehaviour {
+    pub invalid_partials: u64,
+    pub invalid_aggregation_shares: u64,
+}
+
+/// Per party misbehaviour counters, shared between the threshold signer and its handle.
+#[derive(Clone, Default)]
+pub(super) struct BlameRegistry(Arc<Mutex<BTreeMap<u16, PartyMisbehaviour>>>);
+
+impl BlameRegistry {
+    /// Blame a party for a misbehaviour.
+    pub(super) fn report(&self, party_id: u16, misbehaviour: Misbehaviour) {
+        tracing::warn!(
+            party_id,
+            misbehaviour = misbehaviour.as_str(),
+            "Blaming party"
+        );
+        Metrics::report_misbehaviour(party_id, misbehaviour);
+
+        let mut parties = self.0.lock().expect("a thread panicked with the mutex");
+        let party = parties.entry(party_id).or_default();
+        match misbehaviour {
+            Misbehaviour::InvalidPartial => party.invalid_partials += 1,
+            Misbehaviour::InvalidAggregationShare => party.invalid_aggregation_shares += 1,
+        }
+    }
+
+    /// Obtain a snapshot of the misbehaviour of each party that has been blamed at least once.
+    pub(super) fn snapshot(&self) -> BTreeMap<u16, PartyMisbehaviour> {
+        self.0
+            .lock()
+            .expect("a thread panicked with the mutex")
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blame_registry() {
+        let registry = BlameRegistry::default();
+        assert!(registry.snapshot().is_empty());
+
+        registry.report(2, Misbehaviour::InvalidPartial);
+        registry.report(2, Misbehaviour::InvalidPartial);
+        registry.report(2, Misbehaviour::InvalidAggregationShare);
+        registry
+            .clone()
+            .report(3, Misbehaviour::InvalidAggregationShare);
+
+        let snapshot = registry.snapshot();
+        assert_eq!(snapshot.len(), 2);
+        assert_eq!(
+            snapshot[&2],
+            PartyMisbehaviour {
+                invalid_partials: 2,
+                invalid_aggregation_shares: 1,
+            }
+        );
+        assert_eq!(
+            snapshot[&3],
+            PartyMisbehaviour {
+                invalid_partials: 0,
+                invalid_aggregation_shares: 1,
+            }
+        );
+    }
+}
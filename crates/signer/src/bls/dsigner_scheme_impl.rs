@@ -1,8 +1,10 @@
 //! Concrete implementation of [`DSignerScheme`].
 
+use crate::bls::blame::BlameRegistry;
 use crate::bls::filter::BlsFilter;
 use crate::bls::{BlsSignatureRequest, SharedSignatureCache, StoredSignatureRequest};
-use crate::bls::{BlsVerifier, G1Affine, G2Affine};
+use crate::bls::{BlsVerifier, G1Affine, G2Affine, PartyMisbehaviour};
+use crate::dsigner::policy::PolicyViolation;
 use crate::dsigner::{
     ApplicationArgs, DSignerScheme, DSignerSchemeError, DSignerSchemeSigner, SchemeDetails,
     SignatureAlgorithm, SignatureRequest, VerificationParameters,
@@ -11,6 +13,7 @@ use bytes::Bytes;
 use futures_util::FutureExt;
 use futures_util::future::BoxFuture;
 use itertools::Either;
+use std::collections::BTreeMap;
 use utils::serialize::point::{
     PointDeserializeCompressed, PointSerializeCompressed, PointSerializeUncompressed,
 };
@@ -20,6 +23,7 @@ pub struct AsyncThresholdSigner<BLS: BlsVerifier> {
     signatures_cache: SharedSignatureCache<BLS>,
     new_sig_request: tokio::sync::mpsc::UnboundedSender<BlsSignatureRequest>,
     filter: BlsFilter,
+    blame: BlameRegistry,
 }
 
 impl<BLS: BlsVerifier> AsyncThresholdSigner<BLS> {
@@ -28,14 +32,21 @@ impl<BLS: BlsVerifier> AsyncThresholdSigner<BLS> {
         signatures_cache: SharedSignatureCache<BLS>,
         new_sig_request: tokio::sync::mpsc::UnboundedSender<BlsSignatureRequest>,
         filter: BlsFilter,
+        blame: BlameRegistry,
     ) -> Self {
         Self {
             scheme_details,
             signatures_cache,
             new_sig_request,
             filter,
+            blame,
         }
     }
+
+    /// Misbehaviour counters of each party that sent invalid partial signatures to this node.
+    pub fn misbehaviours(&self) -> BTreeMap<u16, PartyMisbehaviour> {
+        self.blame.snapshot()
+    }
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -46,6 +57,9 @@ pub enum AsyncThresholdSignerError {
     #[error("the specified algorithm is not supported by the signer")]
     AlgorithmNotSupported,
 
+    #[error("the request was rejected by the signing policy")]
+    PolicyViolation(#[from] PolicyViolation),
+
     #[error("the message to sign has been dropped from cache")]
     DroppedFromCache,
 
@@ -68,6 +82,7 @@ impl From<AsyncThresholdSignerError> for DSignerSchemeError {
             AsyncThresholdSignerError::AlgorithmNotSupported => {
                 DSignerSchemeError::AlgorithmNotSupported
             }
+            AsyncThresholdSignerError::PolicyViolation(e) => DSignerSchemeError::PolicyViolation(e),
             _ => DSignerSchemeError::Other(error.into()),
         }
     }
@@ -131,6 +146,8 @@ where
             let Some(dst) = self.filter.get_rfc9380_dst_if_supported(&req.args, &alg) else {
                 Err(AsyncThresholdSignerError::ApplicationNotSupported)?
             };
+            self.filter.check_policy(&req.m, &req.args)?;
+
             let stored_req = StoredSignatureRequest {
                 m: req.m.clone(),
                 dst,
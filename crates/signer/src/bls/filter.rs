@@ -1,8 +1,10 @@
 //! Filters supported applications and build rfc9380 DSTs.
 
+use crate::dsigner::policy::{PolicyViolation, SigningPolicy};
 use crate::dsigner::{Application, ApplicationArgs, BlsSignatureAlgorithm, BlsSignatureCurve};
 use bytes::Bytes;
 use std::collections::HashSet;
+use std::sync::Arc;
 use strum::VariantArray;
 use utils::dst::{CurveId, EncodingType, HashId, Rfc9380Dst, Rfc9380DstBuilder};
 
@@ -11,6 +13,7 @@ use utils::dst::{CurveId, EncodingType, HashId, Rfc9380Dst, Rfc9380DstBuilder};
 pub(super) struct BlsFilter {
     supported_apps: HashSet<Application>,
     supported_algs: HashSet<BlsSignatureAlgorithm>,
+    policy: Option<Arc<SigningPolicy>>,
 }
 
 impl BlsFilter {
@@ -21,6 +24,7 @@ impl BlsFilter {
         Self {
             supported_apps: HashSet::from_iter(Application::VARIANTS.iter().copied()),
             supported_algs: HashSet::from_iter(algs),
+            policy: None,
         }
     }
 
@@ -28,6 +32,22 @@ impl BlsFilter {
         self.supported_apps = apps.into_iter().collect();
     }
 
+    pub(super) fn set_policy(&mut self, policy: SigningPolicy) {
+        self.policy = Some(Arc::new(policy));
+    }
+
+    /// Evaluate the signing policy, if any, on a request.
+    pub(super) fn check_policy(
+        &self,
+        m: &[u8],
+        app_args: &ApplicationArgs,
+    ) -> Result<(), PolicyViolation> {
+        match &self.policy {
+            Some(policy) => policy.check(m, app_args),
+            None => Ok(()),
+        }
+    }
+
     pub(super) fn is_supported(&self, app: &Application, alg: &BlsSignatureAlgorithm) -> bool {
         self.supported_algs.contains(alg) && self.supported_apps.contains(app)
     }
@@ -1,37 +1,24 @@
 //! Handle internal signing requests and partial signatures received from other nodes.
 
+use crate::bls::batch::PendingPartial;
 use crate::bls::metrics::Metrics;
 use crate::bls::{
     BatchKnownPartials, BatchReplayPartials, BlsSignatureRequest, BlsSigner, BlsThresholdSigner,
-    G1, G1Affine, G2, G2Affine, Group, NetworkMessage, PartialSignature,
-    PartialSignatureWithRequest, ReplayPartial, StoredSignatureRequest,
-    lagrange_points_interpolate_at,
+    G1Affine, G2Affine, Group, NetworkMessage, PartialSignature, PartialSignatureWithRequest,
+    ReplayPartial, StoredSignatureRequest,
 };
-use crate::dsigner::BlsSignatureAlgorithm;
-use ark_ec::{AffineRepr, CurveGroup};
 use dcipher_network::{ReceivedMessage, TransportSender};
 use futures_util::{Stream, StreamExt};
 use itertools::{Either, izip};
-use std::collections::{BTreeMap, BTreeSet};
+use std::collections::BTreeSet;
 use std::sync::Arc;
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 use tokio_util::sync::CancellationToken;
 use utils::display::LogBytes;
-use utils::dst::NamedCurveGroup;
 use utils::serialize::point::{
     PointDeserializeCompressed, PointSerializeCompressed, PointSerializeUncompressed,
 };
 
-/// Map either with the same expression
-macro_rules! map_either {
-    ($value:expr, $pattern:pat => $result:expr) => {
-        match $value {
-            Either::Left($pattern) => Either::Left($result),
-            Either::Right($pattern) => Either::Right($result),
-        }
-    };
-}
-
 impl<BLS> BlsThresholdSigner<BLS>
 where
     BLS: BlsSigner + Clone + Send + Sync + 'static,
@@ -97,28 +84,33 @@ where
                     }
                 }).collect();
 
-                // Collect points to aggregate
-                let to_aggregate: Vec<Either<Vec<_>, Vec<_>>> = {
+                // Collect partials to aggregate
+                let to_aggregate: Vec<_> = {
                     let mut partials_cache = self
                         .partials_cache
                         .lock()
                         .expect("a thread panicked with the mutex");
+                    let signatures_cache = self
+                        .signatures_cache
+                        .lock()
+                        .expect("a thread panicked with the mutex");
 
                     // We filter with a sequential iterator here due to side effects
                     izip!(partials.iter(), reqs.iter()).filter_map(|(partial_sig, (req, stored_req))| {
                         tracing::info!(msg = %LogBytes(&stored_req.m), party_id = self.id, "Storing partial signature on message");
                         let partials = partials_cache.get_or_insert_mut(stored_req.to_owned(), Default::default);
-                        partials.insert(
-                            self.id,
-                            PartialSignature {
-                                id: self.id,
-                                sig: *partial_sig,
-                            },
-                        );
-
-                        // Do we have exactly t partials?
-                        if partials.len() == usize::from(self.t) {
-                            Some(Self::collect_partials_into_points(&req.alg, partials))
+                        let partial = PartialSignature {
+                            id: self.id,
+                            sig: *partial_sig,
+                        };
+                        self.persist_partial(&req.alg, stored_req, &partial);
+                        partials.insert(self.id, partial);
+
+                        // Do we have at least t partials, and no signature yet?
+                        let signed = matches!(signatures_cache.peek(stored_req), Some(Either::Left(_)));
+                        if partials.len() >= usize::from(self.t) && !signed {
+                            let partials: Vec<_> = partials.values().take(usize::from(self.t)).cloned().collect();
+                            Some((req, stored_req, partials))
                         } else {
                             None
                         }
@@ -144,8 +136,26 @@ where
                 #[cfg(not(feature = "rayon"))]
                 let iter = to_aggregate.into_iter();
                 let signatures: Vec<_> = iter
-                    .map(|points| {
-                        map_either!(points, points => lagrange_points_interpolate_at(&points, 0).into_affine())
+                    .map(|(req, stored_req, partials)| {
+                        let sig = self.optimistic_aggregate(&req.alg, stored_req, &partials);
+                        (req, stored_req, sig)
+                    })
+                    .collect();
+
+                // Fall back to the robust aggregation if the signature is invalid
+                let signatures: Vec<_> = signatures
+                    .into_iter()
+                    .filter_map(|(req, stored_req, sig)| {
+                        let sig = sig.or_else(|| {
+                            let mut partials_cache = self
+                                .partials_cache
+                                .lock()
+                                .expect("a thread panicked with the mutex");
+                            let partials = partials_cache.get_mut(stored_req)?;
+                            self.robust_aggregate(&req.alg, stored_req, partials)
+                        })?;
+                        self.persist_signature(&req.alg, stored_req, sig);
+                        Some((stored_req, sig))
                     })
                     .collect();
 
@@ -157,7 +167,7 @@ where
                         .expect("a thread panicked with the mutex");
 
                     // side effects, sequential iterator
-                    for (sig, (_, stored_req)) in izip!(signatures.into_iter(), reqs.iter()) {
+                    for (stored_req, sig) in signatures {
                         if let Some(Either::Right(tx_channel)) =
                             signatures_cache.put(stored_req.to_owned(), Either::Left(sig))
                         {
@@ -224,6 +234,12 @@ where
                 continue;
             };
 
+            // Never release a partial on requests rejected by the policy
+            if let Err(e) = self.filter.check_policy(&req.m, &req.args) {
+                tracing::warn!(error = %e, app = ?req.args.app(), msg = %LogBytes(&req.m), "Refusing to sign request rejected by the policy");
+                continue;
+            }
+
             let mut to_sign = true;
 
             // Has the request already been signed by any node?
@@ -267,50 +283,9 @@ where
         (reqs_to_sign, missing_partials)
     }
 
-    #[allow(clippy::type_complexity)]
-    fn collect_partials_into_points(
-        alg: &BlsSignatureAlgorithm,
-        partials: &mut BTreeMap<u16, PartialSignature<Group<BLS>>>,
-    ) -> Either<Vec<(u64, G1<BLS>)>, Vec<(u64, G2<BLS>)>> {
-        let points = partials.values();
-        if <G1<BLS> as NamedCurveGroup>::CURVE_ID == alg.curve.into() {
-            // Collect the g1 partials
-            let points = points
-                .map(|partial| {
-                    (
-                        u64::from(partial.id),
-                        partial
-                            .sig
-                            .either()
-                            .left()
-                            .expect("g2 points stored in g1 request")
-                            .into_group(),
-                    )
-                })
-                .collect::<Vec<_>>();
-            Either::Left(points)
-        } else {
-            // Collect the g2 partials
-            let points = points
-                .map(|partial| {
-                    (
-                        u64::from(partial.id),
-                        partial
-                            .sig
-                            .either()
-                            .right()
-                            .expect("g1 points stored in g2 request")
-                            .into_group(),
-                    )
-                })
-                .collect::<Vec<_>>();
-            Either::Right(points)
-        }
-    }
-
     pub(super) async fn network_recv_loop<T, E>(
         self: Arc<Self>,
-        mut network_stream: impl Stream<Item = Result<ReceivedMessage<u16>, E>> + Unpin + Send,
+        network_stream: impl Stream<Item = Result<ReceivedMessage<u16>, E>> + Unpin + Send,
         tx_new_message_to_sign: UnboundedSender<BlsSignatureRequest>,
         tx_to_network: T,
         cancellation_token: CancellationToken,
@@ -318,56 +293,63 @@ where
         T: TransportSender<Identity = u16>,
         E: std::error::Error + Send + Sync + 'static,
     {
+        // Maximum number of messages that are readily available and processed at once
+        const MAX_BATCH_SIZE: usize = 256;
+
         let inner_fn = async move {
+            let mut network_stream = network_stream.ready_chunks(MAX_BATCH_SIZE);
             loop {
-                let ReceivedMessage {
-                    sender: sender_id,
-                    content: partial,
-                    ..
-                } = match network_stream.next().await {
-                    Some(Ok(m)) => m,
-                    Some(Err(e)) => {
-                        tracing::error!(error = ?e, "Failed to receive message");
-                        continue; // receive next message
-                    }
-                    None => {
-                        tracing::warn!("Libp2p node has dropped sender, exiting recv loop");
-                        break; // stop the loop
-                    }
+                let Some(messages) = network_stream.next().await else {
+                    tracing::warn!("Libp2p node has dropped sender, exiting recv loop");
+                    break; // stop the loop
                 };
 
-                let m: NetworkMessage<_> = match serde_cbor::from_slice(&partial) {
-                    Ok(m) => m,
-                    Err(e) => {
-                        tracing::error!(sender_id, error = ?e, "Failed to decode network message");
-                        continue;
-                    }
-                };
+                // Partials received in this batch of messages, verified at once
+                let mut partials = vec![];
+                for message in messages {
+                    let ReceivedMessage {
+                        sender: sender_id,
+                        content: partial,
+                        ..
+                    } = match message {
+                        Ok(m) => m,
+                        Err(e) => {
+                            tracing::error!(error = ?e, "Failed to receive message");
+                            continue; // receive next message
+                        }
+                    };
 
-                match m {
-                    NetworkMessage::PartialSignature(partial) => self.handle_partial_from_network(
-                        partial,
-                        sender_id,
-                        &tx_new_message_to_sign,
-                    ),
-                    NetworkMessage::ReplayPartials(reqs) => {
-                        for req in reqs {
-                            self.handle_replay_partials_from_network(
-                                req,
-                                sender_id,
-                                &tx_to_network,
-                            )
-                            .await;
+                    let m: NetworkMessage<_> = match serde_cbor::from_slice(&partial) {
+                        Ok(m) => m,
+                        Err(e) => {
+                            tracing::error!(sender_id, error = ?e, "Failed to decode network message");
+                            continue;
+                        }
+                    };
+
+                    match m {
+                        NetworkMessage::PartialSignature(partial) => {
+                            partials.extend(self.handle_partial_from_network(partial, sender_id))
+                        }
+                        NetworkMessage::ReplayPartials(reqs) => {
+                            for req in reqs {
+                                self.handle_replay_partials_from_network(
+                                    req,
+                                    sender_id,
+                                    &tx_to_network,
+                                )
+                                .await;
+                            }
+                        }
+                        NetworkMessage::KnownPartials(known_partials) => {
+                            partials.extend(
+                                self.handle_known_partials_from_network(known_partials, sender_id),
+                            );
                         }
-                    }
-                    NetworkMessage::KnownPartials(partials) => {
-                        self.handle_known_partials_from_network(
-                            partials,
-                            sender_id,
-                            &tx_new_message_to_sign,
-                        );
                     }
                 }
+
+                self.process_partials_from_network(partials, &tx_new_message_to_sign);
             }
         };
 
@@ -380,50 +362,63 @@ where
         }
     }
 
+    /// Parse a partial received from the network into a partial pending verification, if the
+    /// request is supported.
     fn handle_partial_from_network(
         &self,
         partial: PartialSignatureWithRequest<BLS>,
         sender: u16,
-        new_message_to_sign: &UnboundedSender<BlsSignatureRequest>,
-    ) {
+    ) -> Option<PendingPartial<BLS>> {
         let PartialSignatureWithRequest { sig, req } = partial;
 
         Metrics::report_partials_received(1);
 
         let Some(stored_req) = self.try_parse_req(req.clone()) else {
             tracing::warn!(sender_id = sender, app = ?req.args.app(), alg = ?req.alg, "Received partial with unsupported app");
-            return;
+            return None;
         };
 
-        // Verify the validity of the partial signature for the specified id
-        match self.try_verify(&req.m, &stored_req.dst, sig, &sender, &req.alg) {
-            Ok(true) => (),
-            Ok(false) => {
-                tracing::error!(sender_id = sender, "Received invalid partial signature");
-                Metrics::report_invalid_partials(1);
-                return;
-            }
-            Err(e) => {
-                // Algorithm should be supported at this point
-                tracing::warn!(sender_id = sender, error = ?e, "Failed to verify partial");
-                return;
-            }
+        Some(PendingPartial {
+            req,
+            stored_req,
+            partial: PartialSignature { id: sender, sig },
+        })
+    }
+
+    /// Verify a batch of partials received from the network, and store the valid ones.
+    fn process_partials_from_network(
+        &self,
+        partials: Vec<PendingPartial<BLS>>,
+        new_message_to_sign: &UnboundedSender<BlsSignatureRequest>,
+    ) {
+        if partials.is_empty() {
+            return;
         }
 
-        // Valid signature, add it to our cache
-        self.store_and_process_partial(
-            stored_req.clone(),
-            PartialSignature { id: sender, sig },
-            &req,
+        // Verify the validity of the partial signatures for the specified ids
+        tracing::debug!(
+            partials_count = partials.len(),
+            "Verifying batch of partials"
         );
+        let partials = self.batch_verify_partials(partials);
 
-        if self.eager_signing {
-            // If eager signing is enabled and the message has not been signed already,
-            // request to broadcast a partial signature on that message
-            if !self.partial_issued(&stored_req) {
-                new_message_to_sign
-                    .send(req)
-                    .expect("failed to forward message to signer");
+        for PendingPartial {
+            req,
+            stored_req,
+            partial,
+        } in partials
+        {
+            // Valid signature, add it to our cache
+            self.store_and_process_partial(stored_req.clone(), partial, &req);
+
+            if self.eager_signing {
+                // If eager signing is enabled and the message has not been signed already,
+                // request to broadcast a partial signature on that message
+                if !self.partial_issued(&stored_req) {
+                    new_message_to_sign
+                        .send(req)
+                        .expect("failed to forward message to signer");
+                }
             }
         }
     }
@@ -501,8 +496,7 @@ where
         &self,
         known_partials_batch: BatchKnownPartials<BLS>,
         sender_id: u16,
-        new_message_to_sign: &UnboundedSender<BlsSignatureRequest>,
-    ) {
+    ) -> Vec<PendingPartial<BLS>> {
         let known_partials_batch = {
             let mut partials_cache = self.partials_cache.lock().expect("an unpoisoned mutex");
             let signatures_cache = self.signatures_cache.lock().expect("an unpoisoned mutex");
@@ -542,6 +536,7 @@ where
             .collect::<Vec<_>>() // collect to drop the mutexes
         };
 
+        let mut pending_partials = vec![];
         for (req, partials) in known_partials_batch {
             tracing::info!(sender_id, partials_count = partials.len(), msg = %LogBytes(&req.m), "Received partials from node");
 
@@ -550,9 +545,12 @@ where
                     sig: partial.sig,
                     req: req.clone(),
                 };
-                self.handle_partial_from_network(partial_w_req, partial.id, new_message_to_sign)
+                pending_partials
+                    .extend(self.handle_partial_from_network(partial_w_req, partial.id));
             }
         }
+
+        pending_partials
     }
 
     /// Verify whether a partial has already been issued or not.
@@ -576,36 +574,49 @@ where
         req: &BlsSignatureRequest,
     ) {
         tracing::info!(msg = %LogBytes(&stored_req.m), party_id = partial.id, "Storing partial signature on message");
-        let mut partials_cache = self
-            .partials_cache
-            .lock()
-            .expect("a thread panicked with the mutex");
-        let partials = partials_cache.get_or_insert_mut(stored_req.clone(), Default::default);
-        partials.insert(
-            partial.id,
-            PartialSignature {
-                id: partial.id,
-                sig: partial.sig,
-            },
-        );
-
-        // Do we have exactly t partials?
-        if partials.len() == usize::from(self.t) {
-            // Aggregate the partials with Lagrange's interpolation
-            let points = Self::collect_partials_into_points(&req.alg, partials);
-            let sig = map_either!(points, points => lagrange_points_interpolate_at(&points, 0).into_affine());
-
-            // We now have a signature, store it
-            let mut signatures_cache = self
-                .signatures_cache
+        self.persist_partial(&req.alg, &stored_req, &partial);
+        let partials_count = {
+            let mut partials_cache = self
+                .partials_cache
                 .lock()
                 .expect("a thread panicked with the mutex");
-            if let Some(Either::Right(tx_channel)) =
-                signatures_cache.put(stored_req, Either::Left(sig))
-            {
-                // If there previously was a channel stored at the entry, also send signature through it
-                tx_channel.send_replace(Some(sig));
-            }
+            let partials = partials_cache.get_or_insert_mut(stored_req.clone(), Default::default);
+            partials.insert(partial.id, partial);
+            partials.len()
+        };
+
+        // Do we have at least t partials, and no signature yet?
+        if partials_count < usize::from(self.t) || self.is_signed(&stored_req) {
+            return;
+        }
+
+        // Aggregate the partials into a valid signature
+        let Some(sig) = self.aggregate_cached_partials(&req.alg, &stored_req) else {
+            return;
+        };
+
+        // We now have a signature, store it unless it was aggregated concurrently
+        let mut signatures_cache = self
+            .signatures_cache
+            .lock()
+            .expect("a thread panicked with the mutex");
+        if matches!(signatures_cache.peek(&stored_req), Some(Either::Left(_))) {
+            return;
         }
+        self.persist_signature(&req.alg, &stored_req, sig);
+        if let Some(Either::Right(tx_channel)) = signatures_cache.put(stored_req, Either::Left(sig))
+        {
+            // If there previously was a channel stored at the entry, also send signature through it
+            tx_channel.send_replace(Some(sig));
+        }
+    }
+
+    /// Whether a signature has already been aggregated for the request.
+    fn is_signed(&self, req: &StoredSignatureRequest) -> bool {
+        let signatures_cache = self
+            .signatures_cache
+            .lock()
+            .expect("a thread panicked with the mutex");
+        matches!(signatures_cache.peek(req), Some(Either::Left(_)))
     }
 }
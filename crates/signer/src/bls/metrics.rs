@@ -1,5 +1,6 @@
+use crate::bls::blame::Misbehaviour;
 use prometheus::proto::MetricFamily;
-use prometheus::{IntCounter, Registry};
+use prometheus::{IntCounter, IntCounterVec, Opts, Registry};
 use std::sync::LazyLock;
 
 pub struct Metrics {
@@ -7,6 +8,7 @@ pub struct Metrics {
     partials_sent: IntCounter,
     partials_received: IntCounter,
     invalid_partials: IntCounter,
+    misbehaviours: IntCounterVec,
 }
 
 static METRICS: LazyLock<Metrics> = LazyLock::new(|| {
@@ -30,6 +32,15 @@ static METRICS: LazyLock<Metrics> = LazyLock::new(|| {
     )
     .expect("metrics failed to initialise");
 
+    let misbehaviours = IntCounterVec::new(
+        Opts::new(
+            "party_misbehaviours",
+            "Number of misbehaviours attributed to each party",
+        ),
+        &["party_id", "misbehaviour"],
+    )
+    .expect("metrics failed to initialise");
+
     registry
         .register(Box::new(partials_sent.clone()))
         .expect("metrics failed to initialise");
@@ -39,12 +50,16 @@ static METRICS: LazyLock<Metrics> = LazyLock::new(|| {
     registry
         .register(Box::new(invalid_partials.clone()))
         .expect("metrics failed to initialise");
+    registry
+        .register(Box::new(misbehaviours.clone()))
+        .expect("metrics failed to initialise");
 
     Metrics {
         registry,
         partials_received,
         partials_sent,
         invalid_partials,
+        misbehaviours,
     }
 });
 
@@ -61,6 +76,13 @@ impl Metrics {
         METRICS.invalid_partials.inc_by(count)
     }
 
+    pub(super) fn report_misbehaviour(party_id: u16, misbehaviour: Misbehaviour) {
+        METRICS
+            .misbehaviours
+            .with_label_values(&[party_id.to_string().as_str(), misbehaviour.as_str()])
+            .inc()
+    }
+
     pub fn gather() -> Vec<MetricFamily> {
         METRICS.registry.gather()
     }
@@ -1,6 +1,7 @@
 //! Implementation of a [`BlsVerifier`] and [`BlsSigner`] for any pairing-friendly curve.
 
 use super::*;
+use crate::bls::batch::{bisect_invalid, random_scalar};
 use ark_ec::pairing::Pairing;
 use ark_ec::short_weierstrass::{Affine, SWCurveConfig};
 use ark_ec::{AffineRepr, PrimeGroup};
@@ -9,6 +10,11 @@ use digest::FixedOutputReset;
 use digest::core_api::BlockSizeUser;
 use std::ops::Neg;
 
+// Public keys along with the combination of the hashes of the messages they signed, for
+// signatures on G1 / G2
+type HashesPerPkG1<E> = Vec<(<E as Pairing>::G2Affine, <E as Pairing>::G1)>;
+type HashesPerPkG2<E> = Vec<(<E as Pairing>::G1Affine, <E as Pairing>::G2)>;
+
 /// Concrete implementation of a [`BlsSigner`] on any pairing-friendly curve w/ signatures on G1 or G2.
 #[derive(Clone)]
 pub struct BlsPairingSigner<E: Pairing> {
@@ -55,10 +61,7 @@ where
         public_key: <Self::E as Pairing>::G2Affine,
     ) -> bool {
         let affine_signature: Affine<_> = signature.into();
-        if !affine_signature.is_on_curve()
-            || !affine_signature.is_in_correct_subgroup_assuming_on_curve()
-            || affine_signature.is_zero()
-        {
+        if !is_valid_signature(affine_signature) {
             return false;
         }
 
@@ -78,10 +81,7 @@ where
         public_key: <Self::E as Pairing>::G1Affine,
     ) -> bool {
         let affine_signature: Affine<_> = signature.into();
-        if !affine_signature.is_on_curve()
-            || !affine_signature.is_in_correct_subgroup_assuming_on_curve()
-            || affine_signature.is_zero()
-        {
+        if !is_valid_signature(affine_signature) {
             return false;
         }
 
@@ -95,6 +95,119 @@ where
         )
         .is_zero()
     }
+
+    /// Verify a batch of signatures with a random linear combination, i.e., check that
+    /// e(sum_i r_i sig_i, g2) == prod_j e(sum_{i: pk_i = pk_j} r_i H(m_i), pk_j) for random r_i,
+    /// which requires one pairing per distinct public key plus one.
+    /// The invalid signatures of a failing batch are identified by bisection.
+    fn batch_verify_g1<H: FixedOutputReset + BlockSizeUser + Default + Clone>(
+        &self,
+        dst: impl AsRef<[u8]>,
+        batch: &[BatchEntryG1<impl AsRef<[u8]>, Self::E>],
+    ) -> Vec<bool> {
+        // Points that are not in the prime order subgroup must not be part of the combination
+        let candidates: Vec<usize> = (0..batch.len())
+            .filter(|&i| {
+                let affine_signature: Affine<_> = batch[i].1.into();
+                is_valid_signature(affine_signature)
+            })
+            .collect();
+        let hashes: Vec<_> = batch
+            .iter()
+            .map(|(m, ..)| {
+                <Self::E as Pairing>::G1::hash_to_curve_custom::<H>(m.as_ref(), dst.as_ref())
+            })
+            .collect();
+
+        let invalid = bisect_invalid(&candidates, &mut |indices: &[usize]| {
+            let mut signature = <Self::E as Pairing>::G1::zero();
+            let mut hashes_per_pk: HashesPerPkG1<Self::E> = vec![];
+            for &i in indices {
+                let (_, signature_i, public_key_i) = &batch[i];
+                let r_i = random_scalar();
+                signature += signature_i.mul_bigint(r_i);
+                let m_i = hashes[i].mul_bigint(r_i);
+                match hashes_per_pk.iter_mut().find(|(pk, _)| pk == public_key_i) {
+                    Some((_, m)) => *m += m_i,
+                    None => hashes_per_pk.push((*public_key_i, m_i)),
+                }
+            }
+
+            <Self::E as Pairing>::multi_pairing(
+                std::iter::once(signature).chain(hashes_per_pk.iter().map(|(_, m)| m.neg())),
+                std::iter::once(<Self::E as Pairing>::G2Affine::generator())
+                    .chain(hashes_per_pk.iter().map(|(pk, _)| *pk)),
+            )
+            .is_zero()
+        });
+
+        let mut valid = vec![false; batch.len()];
+        candidates
+            .into_iter()
+            .filter(|i| !invalid.contains(i))
+            .for_each(|i| valid[i] = true);
+        valid
+    }
+
+    /// Verify a batch of signatures with a random linear combination, i.e., check that
+    /// e(g1, sum_i r_i sig_i) == prod_j e(pk_j, sum_{i: pk_i = pk_j} r_i H(m_i)) for random r_i,
+    /// which requires one pairing per distinct public key plus one.
+    /// The invalid signatures of a failing batch are identified by bisection.
+    fn batch_verify_g2<H: FixedOutputReset + BlockSizeUser + Default + Clone>(
+        &self,
+        dst: impl AsRef<[u8]>,
+        batch: &[BatchEntryG2<impl AsRef<[u8]>, Self::E>],
+    ) -> Vec<bool> {
+        // Points that are not in the prime order subgroup must not be part of the combination
+        let candidates: Vec<usize> = (0..batch.len())
+            .filter(|&i| {
+                let affine_signature: Affine<_> = batch[i].1.into();
+                is_valid_signature(affine_signature)
+            })
+            .collect();
+        let hashes: Vec<_> = batch
+            .iter()
+            .map(|(m, ..)| {
+                <Self::E as Pairing>::G2::hash_to_curve_custom::<H>(m.as_ref(), dst.as_ref())
+            })
+            .collect();
+
+        let invalid = bisect_invalid(&candidates, &mut |indices: &[usize]| {
+            let mut signature = <Self::E as Pairing>::G2::zero();
+            let mut hashes_per_pk: HashesPerPkG2<Self::E> = vec![];
+            for &i in indices {
+                let (_, signature_i, public_key_i) = &batch[i];
+                let r_i = random_scalar();
+                signature += signature_i.mul_bigint(r_i);
+                let m_i = hashes[i].mul_bigint(r_i);
+                match hashes_per_pk.iter_mut().find(|(pk, _)| pk == public_key_i) {
+                    Some((_, m)) => *m += m_i,
+                    None => hashes_per_pk.push((*public_key_i, m_i)),
+                }
+            }
+
+            <Self::E as Pairing>::multi_pairing(
+                std::iter::once(<Self::E as Pairing>::G1::generator())
+                    .chain(hashes_per_pk.iter().map(|(pk, _)| pk.into_group().neg())),
+                std::iter::once(signature).chain(hashes_per_pk.iter().map(|(_, m)| *m)),
+            )
+            .is_zero()
+        });
+
+        let mut valid = vec![false; batch.len()];
+        candidates
+            .into_iter()
+            .filter(|i| !invalid.contains(i))
+            .for_each(|i| valid[i] = true);
+        valid
+    }
+}
+
+/// Whether a signature is a point of the prime order subgroup distinct from the identity.
+fn is_valid_signature<C: SWCurveConfig>(signature: Affine<C>) -> bool {
+    signature.is_on_curve()
+        && signature.is_in_correct_subgroup_assuming_on_curve()
+        && !signature.is_zero()
 }
 
 impl<E> BlsSigner for BlsPairingSigner<E>
@@ -145,6 +258,7 @@ mod tests {
         use ark_bn254::Fr;
         use ark_ec::{AffineRepr, CurveGroup};
         use ark_ff::MontFp;
+        use ark_std::{UniformRand, Zero};
 
         #[test]
         fn test_g1_g2_consistency() {
@@ -164,5 +278,65 @@ mod tests {
             assert!(signer.verify_g1::<sha3::Keccak256>(m, dst_g1, sig_g1, pk_g2.into_affine()));
             assert!(signer.verify_g2::<sha3::Keccak256>(m, dst_g2, sig_g2, pk_g1.into_affine()));
         }
+
+        #[test]
+        fn test_batch_verify() {
+            let rng = &mut ark_std::test_rng();
+            let signers: Vec<_> = (0..3)
+                .map(|_| BlsPairingSigner::new_bn254(Fr::rand(rng)))
+                .collect();
+
+            let dst_g1 = b"TEST-BN254G1_XMD:KECCAK-256_SVDW_RO_";
+            let dst_g2 = b"TEST-BN254G2_XMD:KECCAK-256_SVDW_RO_";
+            let mut batch_g1 = vec![];
+            let mut batch_g2 = vec![];
+            for i in 0..12 {
+                let signer = &signers[i % signers.len()];
+                let m = format!("test_batch_verify {}", i / 2);
+                let sig_g1 = signer.sign_g1::<sha3::Keccak256>(&m, dst_g1).unwrap();
+                let sig_g2 = signer.sign_g2::<sha3::Keccak256>(&m, dst_g2).unwrap();
+                batch_g1.push((m.clone(), sig_g1, signer.g2_public_key()));
+                batch_g2.push((m, sig_g2, signer.g1_public_key()));
+            }
+
+            let verifier = &signers[0];
+            assert!(
+                verifier
+                    .batch_verify_g1::<sha3::Keccak256>(dst_g1, &batch_g1)
+                    .into_iter()
+                    .all(|v| v)
+            );
+            assert!(
+                verifier
+                    .batch_verify_g2::<sha3::Keccak256>(dst_g2, &batch_g2)
+                    .into_iter()
+                    .all(|v| v)
+            );
+
+            // Tamper with some of the partials: wrong signature, wrong public key and identity
+            batch_g1[1].1 = (batch_g1[1].1 + ark_bn254::G1Affine::generator()).into_affine();
+            batch_g1[6].2 = signers[2].g2_public_key();
+            batch_g1[11].1 = ark_bn254::G1Affine::zero();
+            batch_g2[1].1 = (batch_g2[1].1 + ark_bn254::G2Affine::generator()).into_affine();
+            batch_g2[6].2 = signers[2].g1_public_key();
+            batch_g2[11].1 = ark_bn254::G2Affine::zero();
+
+            let expected: Vec<_> = (0..12).map(|i| ![1, 6, 11].contains(&i)).collect();
+            assert_eq!(
+                verifier.batch_verify_g1::<sha3::Keccak256>(dst_g1, &batch_g1),
+                expected
+            );
+            assert_eq!(
+                verifier.batch_verify_g2::<sha3::Keccak256>(dst_g2, &batch_g2),
+                expected
+            );
+
+            // Same result with the default, per-signature, implementation
+            let individual: Vec<_> = batch_g1
+                .iter()
+                .map(|(m, sig, pk)| verifier.verify_g1::<sha3::Keccak256>(m, dst_g1, *sig, *pk))
+                .collect();
+            assert_eq!(individual, expected);
+        }
     }
 }
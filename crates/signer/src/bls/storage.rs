@@ -0,0 +1,506 @@
+//! Persistent storage of the signatures and partials obtained by a [`BlsThresholdSigner`].
+//!
+//! The in-memory caches of the signer remain the source of truth while it is running. Every
+//! partial and signature added to the caches is also queued for writing to the storage by a
+//! background task, and the caches are filled back from the storage when the signer starts.
+//! Signatures are kept so that they can be audited later, while the partials that can no longer
+//! be restored are regularly pruned.
+
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+use crate::bls::{
+    BlsSigner, BlsThresholdSigner, G1Affine, G2Affine, Group, PartialSignature, Signature,
+    StoredSignatureRequest,
+};
+use crate::dsigner::BlsSignatureAlgorithm;
+use bytes::Bytes;
+use futures_util::future::BoxFuture;
+use itertools::Either;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use utils::display::LogBytes;
+use utils::serialize::point::{
+    PointDeserializeCompressed, PointSerializeCompressed, PointSerializeUncompressed,
+};
+
+/// An aggregated signature on a message.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SignatureRecord {
+    pub m: Bytes,
+    pub dst: Bytes,
+    pub alg: BlsSignatureAlgorithm,
+    /// Compressed signature
+    pub signature: Bytes,
+}
+
+/// A valid partial signature on a message.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PartialRecord {
+    pub m: Bytes,
+    pub dst: Bytes,
+    pub alg: BlsSignatureAlgorithm,
+    pub party_id: u16,
+    /// Compressed partial signature
+    pub partial: Bytes,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum BlsStorageError {
+    #[error("storage backend error")]
+    Backend(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+
+    #[error("failed to decode stored signature")]
+    Decode(#[from] utils::serialize::SerializationError),
+
+    #[error("stored signature uses an unsupported curve")]
+    UnsupportedCurve,
+}
+
+/// Storage backend for the signatures and partials of a [`BlsThresholdSigner`].
+pub trait BlsSignatureStorage: Send + Sync {
+    /// Store an aggregated signature. Storing a signature on a message that already has one is a
+    /// no-op.
+    fn store_signature(
+        &self,
+        record: SignatureRecord,
+    ) -> BoxFuture<'_, Result<(), BlsStorageError>>;
+
+    /// Store a partial signature. Storing a partial from a party that already sent one for the
+    /// same message is a no-op.
+    fn store_partial(&self, record: PartialRecord) -> BoxFuture<'_, Result<(), BlsStorageError>>;
+
+    /// Load up to `limit` of the most recently stored signatures.
+    fn load_signatures(
+        &self,
+        limit: usize,
+    ) -> BoxFuture<'_, Result<Vec<SignatureRecord>, BlsStorageError>>;
+
+    /// Load up to `limit` of the most recently stored partials on messages without a signature.
+    fn load_partials(
+        &self,
+        limit: usize,
+    ) -> BoxFuture<'_, Result<Vec<PartialRecord>, BlsStorageError>>;
+
+    /// Delete the partials that can no longer be loaded, i.e., the partials on messages with a
+    /// signature, and all but the `max_partials` most recent other partials. Signatures are
+    /// never deleted.
+    fn prune_partials(&self, max_partials: usize) -> BoxFuture<'_, Result<(), BlsStorageError>>;
+}
+
+/// A record queued for writing to the storage.
+enum StorageWrite {
+    Signature(SignatureRecord),
+    Partial(PartialRecord),
+}
+
+/// Storage of a [`BlsThresholdSigner`], along with the queue of records to write to it.
+pub(super) struct StorageWriter {
+    storage: Arc<dyn BlsSignatureStorage>,
+    tx: mpsc::UnboundedSender<StorageWrite>,
+    rx: Mutex<Option<mpsc::UnboundedReceiver<StorageWrite>>>,
+}
+
+impl StorageWriter {
+    /// Number of writes between two prunings of the storage.
+    const PRUNE_INTERVAL: usize = 256;
+
+    pub(super) fn new(storage: Arc<dyn BlsSignatureStorage>) -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        Self {
+            storage,
+            tx,
+            rx: Mutex::new(Some(rx)),
+        }
+    }
+
+    fn queue(&self, write: StorageWrite) {
+        if self.tx.send(write).is_err() {
+            tracing::error!("Storage writer stopped, record not persisted");
+        }
+    }
+
+    /// Write the queued records in order until cancelled, and prune the partials every
+    /// [`Self::PRUNE_INTERVAL`] writes.
+    async fn write_loop(
+        storage: Arc<dyn BlsSignatureStorage>,
+        mut rx: mpsc::UnboundedReceiver<StorageWrite>,
+        max_partials: usize,
+        cancellation_token: CancellationToken,
+    ) {
+        let mut writes_since_pruning = 0;
+        loop {
+            let write = tokio::select! {
+                write = rx.recv() => match write {
+                    Some(write) => write,
+                    None => break,
+                },
+                _ = cancellation_token.cancelled() => break,
+            };
+
+            let res = match write {
+                StorageWrite::Signature(record) => storage.store_signature(record).await,
+                StorageWrite::Partial(record) => storage.store_partial(record).await,
+            };
+            if let Err(e) = res {
+                tracing::error!(error = ?e, "Failed to persist record");
+            }
+
+            writes_since_pruning += 1;
+            if writes_since_pruning == Self::PRUNE_INTERVAL {
+                writes_since_pruning = 0;
+                if let Err(e) = storage.prune_partials(max_partials).await {
+                    tracing::error!(error = ?e, "Failed to prune partials");
+                }
+            }
+        }
+
+        tracing::info!("Storage writer stopped");
+    }
+}
+
+impl<BLS> BlsThresholdSigner<BLS>
+where
+    BLS: BlsSigner + Clone + Send + Sync + 'static,
+    G1Affine<BLS>:
+        PointSerializeCompressed + PointDeserializeCompressed + PointSerializeUncompressed,
+    G2Affine<BLS>:
+        PointSerializeCompressed + PointDeserializeCompressed + PointSerializeUncompressed,
+{
+    /// Maximum number of signatures and partials restored from the storage, i.e., enough to
+    /// fill the caches.
+    fn storage_limits(&self) -> (usize, usize) {
+        let cache_size = self
+            .signatures_cache
+            .lock()
+            .expect("a thread panicked with the mutex")
+            .cap()
+            .get();
+        (cache_size, cache_size * usize::from(self.n))
+    }
+
+    /// Spawn the task writing the queued records to the storage, if any.
+    pub(super) fn spawn_storage_writer(&self, cancellation_token: CancellationToken) {
+        let Some(writer) = &self.storage else {
+            return;
+        };
+        let Some(rx) = writer
+            .rx
+            .lock()
+            .expect("a thread panicked with the mutex")
+            .take()
+        else {
+            tracing::warn!("Storage writer already started");
+            return;
+        };
+
+        let (_, max_partials) = self.storage_limits();
+        tokio::task::spawn(StorageWriter::write_loop(
+            writer.storage.clone(),
+            rx,
+            max_partials,
+            cancellation_token,
+        ));
+    }
+
+    /// Fill the caches with the most recent signatures and partials of the storage, and
+    /// aggregate the messages that already have enough partials.
+    pub(super) async fn restore_from_storage(&self) -> Result<(), BlsStorageError> {
+        let Some(writer) = &self.storage else {
+            return Ok(());
+        };
+
+        let (max_signatures, max_partials) = self.storage_limits();
+        let signatures = writer.storage.load_signatures(max_signatures).await?;
+        let partials = writer.storage.load_partials(max_partials).await?;
+        tracing::info!(
+            signatures_count = signatures.len(),
+            partials_count = partials.len(),
+            "Restoring caches from storage"
+        );
+
+        // Insert the oldest records first to keep the most recent ones in the LRU caches
+        let mut restored: HashMap<StoredSignatureRequest, BlsSignatureAlgorithm> = HashMap::new();
+        {
+            let mut partials_cache = self
+                .partials_cache
+                .lock()
+                .expect("a thread panicked with the mutex");
+            for record in partials.into_iter().rev() {
+                let sig = match Self::decode_point(&record.alg, &record.partial) {
+                    Ok(sig) => sig,
+                    Err(e) => {
+                        tracing::error!(error = ?e, msg = %LogBytes(&record.m), "Failed to decode stored partial");
+                        continue;
+                    }
+                };
+
+                let stored_req = StoredSignatureRequest {
+                    m: record.m,
+                    dst: record.dst,
+                };
+                partials_cache
+                    .get_or_insert_mut(stored_req.clone(), Default::default)
+                    .insert(
+                        record.party_id,
+                        PartialSignature {
+                            id: record.party_id,
+                            sig,
+                        },
+                    );
+                restored.insert(stored_req, record.alg);
+            }
+        }
+
+        for record in signatures.into_iter().rev() {
+            match Self::decode_point(&record.alg, &record.signature) {
+                Ok(sig) => self.put_signature(
+                    StoredSignatureRequest {
+                        m: record.m,
+                        dst: record.dst,
+                    },
+                    sig.either(),
+                ),
+                Err(e) => {
+                    tracing::error!(error = ?e, msg = %LogBytes(&record.m), "Failed to decode stored signature");
+                }
+            }
+        }
+
+        // The signer may have stopped before aggregating the partials
+        for (stored_req, alg) in restored {
+            if let Some(sig) = self.aggregate_cached_partials(&alg, &stored_req) {
+                self.persist_signature(&alg, &stored_req, sig);
+                self.put_signature(stored_req, sig);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Store a signature in the signatures cache, and notify the requests waiting for it.
+    fn put_signature(&self, stored_req: StoredSignatureRequest, sig: Signature<BLS>) {
+        let mut signatures_cache = self
+            .signatures_cache
+            .lock()
+            .expect("a thread panicked with the mutex");
+        if let Some(Either::Right(tx_channel)) = signatures_cache.put(stored_req, Either::Left(sig))
+        {
+            // If there previously was a channel stored at the entry, also send signature through it
+            tx_channel.send_replace(Some(sig));
+        }
+    }
+
+    /// Queue a partial for writing to the storage, if any.
+    pub(super) fn persist_partial(
+        &self,
+        alg: &BlsSignatureAlgorithm,
+        stored_req: &StoredSignatureRequest,
+        partial: &PartialSignature<Group<BLS>>,
+    ) {
+        let Some(writer) = &self.storage else {
+            return;
+        };
+
+        writer.queue(StorageWrite::Partial(PartialRecord {
+            m: stored_req.m.clone(),
+            dst: stored_req.dst.clone(),
+            alg: *alg,
+            party_id: partial.id,
+            partial: Self::encode_point(partial.sig),
+        }));
+    }
+
+    /// Queue a signature for writing to the storage, if any.
+    pub(super) fn persist_signature(
+        &self,
+        alg: &BlsSignatureAlgorithm,
+        stored_req: &StoredSignatureRequest,
+        sig: Signature<BLS>,
+    ) {
+        let Some(writer) = &self.storage else {
+            return;
+        };
+
+        writer.queue(StorageWrite::Signature(SignatureRecord {
+            m: stored_req.m.clone(),
+            dst: stored_req.dst.clone(),
+            alg: *alg,
+            signature: Self::encode_point(sig.into()),
+        }));
+    }
+
+    fn encode_point(sig: Group<BLS>) -> Bytes {
+        match sig {
+            Group::G1Affine(sig) => sig.ser_compressed(),
+            Group::G2Affine(sig) => sig.ser_compressed(),
+        }
+        .expect("point serialization should always work")
+        .into()
+    }
+
+    fn decode_point(
+        alg: &BlsSignatureAlgorithm,
+        bytes: &[u8],
+    ) -> Result<Group<BLS>, BlsStorageError> {
+        if Self::is_curve_g1(alg.curve) {
+            Ok(Group::G1Affine(G1Affine::<BLS>::deser_compressed(bytes)?))
+        } else if Self::is_curve_g2(alg.curve) {
+            Ok(Group::G2Affine(G2Affine::<BLS>::deser_compressed(bytes)?))
+        } else {
+            Err(BlsStorageError::UnsupportedCurve)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(all(feature = "bn254", feature = "sha3"))]
+    mod bn254 {
+        use super::super::*;
+        use crate::bls::{BlsPairingSigner, BlsSigner};
+        use crate::dsigner::{BlsSignatureCurve, BlsSignatureHash};
+        use ark_bn254::Fr;
+        use ark_std::UniformRand;
+        use futures_util::FutureExt;
+        use std::sync::{Arc, Mutex};
+
+        /// Simple in-memory storage.
+        #[derive(Default)]
+        struct MemoryStorage {
+            signatures: Mutex<Vec<SignatureRecord>>,
+            partials: Mutex<Vec<PartialRecord>>,
+        }
+
+        impl BlsSignatureStorage for Arc<MemoryStorage> {
+            fn store_signature(
+                &self,
+                record: SignatureRecord,
+            ) -> BoxFuture<'_, Result<(), BlsStorageError>> {
+                self.signatures.lock().unwrap().push(record);
+                futures_util::future::ready(Ok(())).boxed()
+            }
+
+            fn store_partial(
+                &self,
+                record: PartialRecord,
+            ) -> BoxFuture<'_, Result<(), BlsStorageError>> {
+                self.partials.lock().unwrap().push(record);
+                futures_util::future::ready(Ok(())).boxed()
+            }
+
+            fn load_signatures(
+                &self,
+                _limit: usize,
+            ) -> BoxFuture<'_, Result<Vec<SignatureRecord>, BlsStorageError>> {
+                let signatures = self.signatures.lock().unwrap().clone();
+                futures_util::future::ready(Ok(signatures)).boxed()
+            }
+
+            fn load_partials(
+                &self,
+                _limit: usize,
+            ) -> BoxFuture<'_, Result<Vec<PartialRecord>, BlsStorageError>> {
+                let partials = self.partials.lock().unwrap().clone();
+                futures_util::future::ready(Ok(partials)).boxed()
+            }
+
+            fn prune_partials(
+                &self,
+                _max_partials: usize,
+            ) -> BoxFuture<'_, Result<(), BlsStorageError>> {
+                futures_util::future::ready(Ok(())).boxed()
+            }
+        }
+
+        #[tokio::test]
+        async fn restore_from_storage() {
+            let rng = &mut ark_std::test_rng();
+            let (n, t) = (3u16, 2u16);
+            let (a0, a1) = (Fr::rand(rng), Fr::rand(rng));
+            let signers: Vec<_> = (1..=n)
+                .map(|i| BlsPairingSigner::new_bn254(a0 + a1 * Fr::from(i)))
+                .collect();
+            let pks_g1: HashMap<_, _> = (1..=n)
+                .zip(&signers)
+                .map(|(i, s)| (i, s.g1_public_key()))
+                .collect();
+            let pks_g2: HashMap<_, _> = (1..=n)
+                .zip(&signers)
+                .map(|(i, s)| (i, s.g2_public_key()))
+                .collect();
+
+            let dst = Bytes::from_static(b"TEST-BN254G1_XMD:KECCAK-256_SVDW_RO_");
+            let alg = BlsSignatureAlgorithm {
+                curve: BlsSignatureCurve::Bn254G1,
+                hash: BlsSignatureHash::Keccak256,
+                compression: false,
+            };
+            let sign = |signer: &BlsPairingSigner<_>, m: &[u8]| -> Bytes {
+                let sig = signer.sign_g1::<sha3::Keccak256>(m, &dst).unwrap();
+                sig.ser_compressed().unwrap().into()
+            };
+
+            // A signature on m1, and enough partials to aggregate a signature on m2
+            let storage = Arc::new(MemoryStorage::default());
+            let group_signer = BlsPairingSigner::new_bn254(a0);
+            let m1 = Bytes::from_static(b"m1");
+            let m2 = Bytes::from_static(b"m2");
+            storage.signatures.lock().unwrap().push(SignatureRecord {
+                m: m1.clone(),
+                dst: dst.clone(),
+                alg,
+                signature: sign(&group_signer, &m1),
+            });
+            for party_id in [3, 2] {
+                storage.partials.lock().unwrap().push(PartialRecord {
+                    m: m2.clone(),
+                    dst: dst.clone(),
+                    alg,
+                    party_id,
+                    partial: sign(&signers[usize::from(party_id) - 1], &m2),
+                });
+            }
+
+            let signer = BlsThresholdSigner::new(signers[0].clone(), n, t, 1, pks_g1, pks_g2)
+                .with_storage(storage.clone());
+            signer.spawn_storage_writer(CancellationToken::new());
+            signer.restore_from_storage().await.unwrap();
+
+            for m in [m1, m2.clone()] {
+                let stored_req = StoredSignatureRequest {
+                    m: m.clone(),
+                    dst: dst.clone(),
+                };
+                let expected = group_signer.sign_g1::<sha3::Keccak256>(&m, &dst).unwrap();
+                let signatures_cache = signer.signatures_cache.lock().unwrap();
+                let cached = signatures_cache.peek(&stored_req);
+                assert!(
+                    matches!(cached, Some(Either::Left(Either::Left(sig))) if *sig == expected)
+                );
+            }
+
+            // The signature aggregated upon restoring is persisted by the storage writer
+            tokio::time::timeout(std::time::Duration::from_secs(1), async {
+                while storage.signatures.lock().unwrap().len() < 2 {
+                    tokio::task::yield_now().await;
+                }
+            })
+            .await
+            .expect("signature should be persisted");
+            let signatures = storage.signatures.lock().unwrap().clone();
+            assert_eq!(signatures.len(), 2);
+            assert_eq!(signatures[1].m, m2);
+            assert_eq!(
+                signatures[1].signature,
+                group_signer
+                    .sign_g1::<sha3::Keccak256>(&m2, &dst)
+                    .unwrap()
+                    .ser_compressed()
+                    .unwrap()
+            );
+        }
+    }
+}
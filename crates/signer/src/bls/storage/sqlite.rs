@@ -0,0 +1,391 @@
+//! A sqlite-based [`BlsSignatureStorage`]
+
+use super::{BlsSignatureStorage, BlsStorageError, PartialRecord, SignatureRecord};
+use crate::dsigner::BlsSignatureAlgorithm;
+use futures_util::FutureExt;
+use futures_util::future::BoxFuture;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteRow};
+use sqlx::{Row, SqlitePool};
+use std::str::FromStr;
+
+#[derive(thiserror::Error, Debug)]
+pub enum SqliteSignatureStorageError {
+    #[error("sqlx error: {1}")]
+    Sqlx(#[source] sqlx::Error, &'static str),
+
+    #[error("failed to run migrations")]
+    Migrate(#[from] sqlx::migrate::MigrateError),
+
+    #[error("failed to encode / decode signature algorithm")]
+    Cbor(#[from] serde_cbor::Error),
+
+    #[error("invalid party id stored in database")]
+    InvalidPartyId,
+}
+
+/// A sqlite-based [`BlsSignatureStorage`]
+#[derive(Clone, Debug)]
+pub struct SqliteSignatureStorage {
+    pool: SqlitePool,
+}
+
+impl SqliteSignatureStorage {
+    /// Connect to a sqlite database, creating it if missing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dcipher_signer::bls::storage::sqlite::SqliteSignatureStorage;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let storage = SqliteSignatureStorage::connect("sqlite::memory:").await.expect("failed to connect");
+    ///     storage.maybe_initialize_schema().await.expect("failed to init schema");
+    /// }
+    /// ```
+    pub async fn connect(url: &str) -> Result<Self, SqliteSignatureStorageError> {
+        let opts = SqliteConnectOptions::from_str(url)
+            .map_err(|e| SqliteSignatureStorageError::Sqlx(e, "failed to create options"))?
+            .create_if_missing(true);
+
+        let pool = SqlitePool::connect_with(opts)
+            .await
+            .map_err(|e| (e, "failed to connect"))?;
+
+        Ok(Self { pool })
+    }
+
+    /// Executes the schema initialization script.
+    pub async fn maybe_initialize_schema(&self) -> Result<(), SqliteSignatureStorageError> {
+        sqlx::migrate!("./sql/migrations").run(&self.pool).await?;
+
+        Ok(())
+    }
+
+    async fn insert_signature(
+        &self,
+        record: SignatureRecord,
+    ) -> Result<(), SqliteSignatureStorageError> {
+        let alg = serde_cbor::to_vec(&record.alg)?;
+        sqlx::query(
+            r#"
+                INSERT INTO signatures (message, dst, alg, signature)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT(message, dst) DO NOTHING;
+            "#,
+        )
+        .bind(record.m.as_ref())
+        .bind(record.dst.as_ref())
+        .bind(alg)
+        .bind(record.signature.as_ref())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| (e, "failed to INSERT INTO signatures"))?;
+
+        Ok(())
+    }
+
+    async fn insert_partial(
+        &self,
+        record: PartialRecord,
+    ) -> Result<(), SqliteSignatureStorageError> {
+        let alg = serde_cbor::to_vec(&record.alg)?;
+        sqlx::query(
+            r#"
+                INSERT INTO partials (message, dst, alg, party_id, partial)
+                VALUES ($1, $2, $3, $4, $5)
+                ON CONFLICT(message, dst, party_id) DO NOTHING;
+            "#,
+        )
+        .bind(record.m.as_ref())
+        .bind(record.dst.as_ref())
+        .bind(alg)
+        .bind(i64::from(record.party_id))
+        .bind(record.partial.as_ref())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| (e, "failed to INSERT INTO partials"))?;
+
+        Ok(())
+    }
+
+    async fn select_signatures(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<SignatureRecord>, SqliteSignatureStorageError> {
+        let rows = sqlx::query(
+            r#"
+                SELECT message, dst, alg, signature FROM signatures
+                ORDER BY id DESC
+                LIMIT $1;
+            "#,
+        )
+        .bind(i64::try_from(limit).unwrap_or(i64::MAX))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| (e, "failed to SELECT FROM signatures"))?;
+
+        rows.iter()
+            .map(|row| -> Result<_, SqliteSignatureStorageError> {
+                Ok(SignatureRecord {
+                    m: get_bytes(row, "message")?.into(),
+                    dst: get_bytes(row, "dst")?.into(),
+                    alg: get_alg(row)?,
+                    signature: get_bytes(row, "signature")?.into(),
+                })
+            })
+            .collect()
+    }
+
+    async fn select_partials(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<PartialRecord>, SqliteSignatureStorageError> {
+        let rows = sqlx::query(
+            r#"
+                SELECT p.message, p.dst, p.alg, p.party_id, p.partial FROM partials p
+                WHERE NOT EXISTS (
+                    SELECT 1 FROM signatures s WHERE s.message = p.message AND s.dst = p.dst
+                )
+                ORDER BY p.id DESC
+                LIMIT $1;
+            "#,
+        )
+        .bind(i64::try_from(limit).unwrap_or(i64::MAX))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| (e, "failed to SELECT FROM partials"))?;
+
+        rows.iter()
+            .map(|row| -> Result<_, SqliteSignatureStorageError> {
+                let party_id: i64 = row
+                    .try_get("party_id")
+                    .map_err(|e| (e, "failed to get party_id"))?;
+                Ok(PartialRecord {
+                    m: get_bytes(row, "message")?.into(),
+                    dst: get_bytes(row, "dst")?.into(),
+                    alg: get_alg(row)?,
+                    party_id: u16::try_from(party_id)
+                        .map_err(|_| SqliteSignatureStorageError::InvalidPartyId)?,
+                    partial: get_bytes(row, "partial")?.into(),
+                })
+            })
+            .collect()
+    }
+
+    async fn delete_old_partials(
+        &self,
+        max_partials: usize,
+    ) -> Result<(), SqliteSignatureStorageError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| (e, "failed to begin transaction"))?;
+
+        // Partials on signed messages are never loaded
+        sqlx::query(
+            r#"
+                DELETE FROM partials WHERE EXISTS (
+                    SELECT 1 FROM signatures s WHERE s.message = partials.message AND s.dst = partials.dst
+                );
+            "#,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| (e, "failed to DELETE FROM partials"))?;
+
+        sqlx::query(
+            r#"
+                DELETE FROM partials WHERE id NOT IN (
+                    SELECT id FROM partials ORDER BY id DESC LIMIT $1
+                );
+            "#,
+        )
+        .bind(i64::try_from(max_partials).unwrap_or(i64::MAX))
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| (e, "failed to DELETE FROM partials"))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| (e, "failed to commit transaction"))?;
+
+        Ok(())
+    }
+}
+
+fn get_bytes(
+    row: &SqliteRow,
+    column: &'static str,
+) -> Result<Vec<u8>, SqliteSignatureStorageError> {
+    row.try_get(column)
+        .map_err(|e| SqliteSignatureStorageError::Sqlx(e, column))
+}
+
+fn get_alg(row: &SqliteRow) -> Result<BlsSignatureAlgorithm, SqliteSignatureStorageError> {
+    Ok(serde_cbor::from_slice(&get_bytes(row, "alg")?)?)
+}
+
+impl BlsSignatureStorage for SqliteSignatureStorage {
+    fn store_signature(
+        &self,
+        record: SignatureRecord,
+    ) -> BoxFuture<'_, Result<(), BlsStorageError>> {
+        self.insert_signature(record)
+            .map(|r| r.map_err(Into::into))
+            .boxed()
+    }
+
+    fn store_partial(&self, record: PartialRecord) -> BoxFuture<'_, Result<(), BlsStorageError>> {
+        self.insert_partial(record)
+            .map(|r| r.map_err(Into::into))
+            .boxed()
+    }
+
+    fn load_signatures(
+        &self,
+        limit: usize,
+    ) -> BoxFuture<'_, Result<Vec<SignatureRecord>, BlsStorageError>> {
+        self.select_signatures(limit)
+            .map(|r| r.map_err(Into::into))
+            .boxed()
+    }
+
+    fn load_partials(
+        &self,
+        limit: usize,
+    ) -> BoxFuture<'_, Result<Vec<PartialRecord>, BlsStorageError>> {
+        self.select_partials(limit)
+            .map(|r| r.map_err(Into::into))
+            .boxed()
+    }
+
+    fn prune_partials(&self, max_partials: usize) -> BoxFuture<'_, Result<(), BlsStorageError>> {
+        self.delete_old_partials(max_partials)
+            .map(|r| r.map_err(Into::into))
+            .boxed()
+    }
+}
+
+/// Convert (sqlx::Error, &'static str) into an [`SqliteSignatureStorageError`] error.
+impl From<(sqlx::Error, &'static str)> for SqliteSignatureStorageError {
+    fn from((e, msg): (sqlx::Error, &'static str)) -> Self {
+        Self::Sqlx(e, msg)
+    }
+}
+
+impl From<SqliteSignatureStorageError> for BlsStorageError {
+    fn from(value: SqliteSignatureStorageError) -> Self {
+        BlsStorageError::Backend(value.into())
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "bn254")]
+mod tests {
+    use super::*;
+    use crate::dsigner::{BlsSignatureCurve, BlsSignatureHash};
+    use bytes::Bytes;
+
+    fn alg() -> BlsSignatureAlgorithm {
+        BlsSignatureAlgorithm {
+            curve: BlsSignatureCurve::Bn254G1,
+            hash: BlsSignatureHash::Keccak256,
+            compression: false,
+        }
+    }
+
+    fn partial(m: &'static [u8], party_id: u16) -> PartialRecord {
+        PartialRecord {
+            m: Bytes::from_static(m),
+            dst: Bytes::from_static(b"dst"),
+            alg: alg(),
+            party_id,
+            partial: Bytes::from(vec![party_id as u8; 32]),
+        }
+    }
+
+    #[tokio::test]
+    async fn sqlite_storage() {
+        let storage = SqliteSignatureStorage::connect("sqlite::memory:")
+            .await
+            .unwrap();
+        storage.maybe_initialize_schema().await.unwrap();
+        let storage: &dyn BlsSignatureStorage = &storage;
+
+        for p in [partial(b"m1", 1), partial(b"m1", 2), partial(b"m2", 1)] {
+            storage.store_partial(p).await.unwrap();
+        }
+        // duplicated partials are ignored
+        storage.store_partial(partial(b"m1", 1)).await.unwrap();
+
+        let partials = storage.load_partials(10).await.unwrap();
+        assert_eq!(
+            partials,
+            [partial(b"m2", 1), partial(b"m1", 2), partial(b"m1", 1)]
+        );
+        assert_eq!(storage.load_partials(1).await.unwrap(), [partial(b"m2", 1)]);
+
+        // partials on signed messages are not loaded
+        let signature = SignatureRecord {
+            m: Bytes::from_static(b"m1"),
+            dst: Bytes::from_static(b"dst"),
+            alg: alg(),
+            signature: Bytes::from(vec![42u8; 32]),
+        };
+        storage.store_signature(signature.clone()).await.unwrap();
+        storage
+            .store_signature(SignatureRecord {
+                signature: Bytes::from(vec![0u8; 32]),
+                ..signature.clone()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(storage.load_signatures(10).await.unwrap(), [signature]);
+        assert_eq!(
+            storage.load_partials(10).await.unwrap(),
+            [partial(b"m2", 1)]
+        );
+    }
+
+    #[tokio::test]
+    async fn sqlite_storage_prune() {
+        let storage = SqliteSignatureStorage::connect("sqlite::memory:")
+            .await
+            .unwrap();
+        storage.maybe_initialize_schema().await.unwrap();
+        let storage: &dyn BlsSignatureStorage = &storage;
+
+        let signature = |m: &'static [u8]| SignatureRecord {
+            m: Bytes::from_static(m),
+            dst: Bytes::from_static(b"dst"),
+            alg: alg(),
+            signature: Bytes::from(vec![42u8; 32]),
+        };
+        for p in [
+            partial(b"m2", 1),
+            partial(b"m3", 1),
+            partial(b"m3", 2),
+            partial(b"m1", 1),
+        ] {
+            storage.store_partial(p).await.unwrap();
+        }
+        for m in [b"m1", b"m4", b"m5"] {
+            storage.store_signature(signature(m)).await.unwrap();
+        }
+
+        storage.prune_partials(2).await.unwrap();
+        // signatures are kept
+        assert_eq!(
+            storage.load_signatures(10).await.unwrap(),
+            [signature(b"m5"), signature(b"m4"), signature(b"m1")]
+        );
+        // the partial on the signed m1 is pruned, along with the oldest partial
+        assert_eq!(
+            storage.load_partials(10).await.unwrap(),
+            [partial(b"m3", 2), partial(b"m3", 1)]
+        );
+    }
+}
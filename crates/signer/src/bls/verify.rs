@@ -0,0 +1,151 @@
+//! Verification of the signatures output by a BLS [`DSignerScheme`](crate::dsigner::DSignerScheme)
+//! using only its public [`VerificationParameters`], e.g., when obtained from a remote dsigner.
+
+use crate::bls::{BlsPairingSigner, BlsVerifier, G1Affine, G2Affine};
+use crate::dsigner::{
+    BlsSignatureAlgorithm, BlsSignatureCurve, BlsSignatureHash, SignatureAlgorithm,
+    VerificationParameters,
+};
+use ark_std::Zero;
+use utils::serialize::SerializationError;
+use utils::serialize::point::{PointDeserializeCompressed, PointDeserializeUncompressed};
+
+#[derive(thiserror::Error, Debug)]
+pub enum VerifySignatureError {
+    #[error("unsupported signature algorithm")]
+    UnsupportedAlgorithm,
+
+    #[error("failed to deserialize signature or public key")]
+    Deserialization(#[from] SerializationError),
+}
+
+/// Outputs true if `sig` is a valid signature on `m` with the specified algorithm, under the
+/// public key and dst of the verification parameters.
+pub fn verify_signature(
+    m: impl AsRef<[u8]>,
+    sig: &[u8],
+    alg: &SignatureAlgorithm,
+    params: &VerificationParameters,
+) -> Result<bool, VerifySignatureError> {
+    let SignatureAlgorithm::Bls(alg) = alg else {
+        Err(VerifySignatureError::UnsupportedAlgorithm)?
+    };
+
+    // Verification does not make use of the secret key
+    match alg.curve {
+        #[cfg(feature = "bn254")]
+        BlsSignatureCurve::Bn254G1 | BlsSignatureCurve::Bn254G2 => verify_with(
+            &BlsPairingSigner::new_bn254(Zero::zero()),
+            m,
+            sig,
+            alg,
+            params,
+        ),
+
+        #[cfg(feature = "bls12-381")]
+        BlsSignatureCurve::Bls12_381G1 | BlsSignatureCurve::Bls12_381G2 => verify_with(
+            &BlsPairingSigner::new_bls12_381(Zero::zero()),
+            m,
+            sig,
+            alg,
+            params,
+        ),
+    }
+}
+
+fn verify_with<BLS: BlsVerifier>(
+    verifier: &BLS,
+    m: impl AsRef<[u8]>,
+    sig: &[u8],
+    alg: &BlsSignatureAlgorithm,
+    params: &VerificationParameters,
+) -> Result<bool, VerifySignatureError>
+where
+    G1Affine<BLS>: PointDeserializeCompressed + PointDeserializeUncompressed,
+    G2Affine<BLS>: PointDeserializeCompressed + PointDeserializeUncompressed,
+{
+    fn deser<P: PointDeserializeCompressed + PointDeserializeUncompressed>(
+        sig: &[u8],
+        compression: bool,
+    ) -> Result<P, SerializationError> {
+        if compression {
+            P::deser_compressed(sig)
+        } else {
+            P::deser_uncompressed(sig)
+        }
+    }
+
+    let dst = &params.dst;
+    let valid = if is_curve_g1(alg.curve) {
+        let sig = deser::<G1Affine<BLS>>(sig, alg.compression)?;
+        let pk = G2Affine::<BLS>::deser_compressed(&params.public_key)?;
+        match alg.hash {
+            #[cfg(feature = "sha2")]
+            BlsSignatureHash::Sha256 => verifier.verify_g1::<sha2::Sha256>(m, dst, sig, pk),
+            #[cfg(feature = "sha3")]
+            BlsSignatureHash::Keccak256 => verifier.verify_g1::<sha3::Keccak256>(m, dst, sig, pk),
+        }
+    } else {
+        let sig = deser::<G2Affine<BLS>>(sig, alg.compression)?;
+        let pk = G1Affine::<BLS>::deser_compressed(&params.public_key)?;
+        match alg.hash {
+            #[cfg(feature = "sha2")]
+            BlsSignatureHash::Sha256 => verifier.verify_g2::<sha2::Sha256>(m, dst, sig, pk),
+            #[cfg(feature = "sha3")]
+            BlsSignatureHash::Keccak256 => verifier.verify_g2::<sha3::Keccak256>(m, dst, sig, pk),
+        }
+    };
+
+    Ok(valid)
+}
+
+fn is_curve_g1(curve: BlsSignatureCurve) -> bool {
+    match curve {
+        #[cfg(feature = "bn254")]
+        BlsSignatureCurve::Bn254G1 => true,
+        #[cfg(feature = "bn254")]
+        BlsSignatureCurve::Bn254G2 => false,
+        #[cfg(feature = "bls12-381")]
+        BlsSignatureCurve::Bls12_381G1 => true,
+        #[cfg(feature = "bls12-381")]
+        BlsSignatureCurve::Bls12_381G2 => false,
+    }
+}
+
+#[cfg(all(test, feature = "bn254", feature = "sha3"))]
+mod tests {
+    use super::*;
+    use crate::bls::BlsSigner;
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_ff::MontFp;
+    use utils::serialize::point::{PointSerializeCompressed, PointSerializeUncompressed};
+
+    #[test]
+    fn verify_bn254_signature() {
+        let sk: ark_bn254::Fr =
+            MontFp!("7685086713915354683875500702831995067084988389812060097318430034144315778947");
+        let pk = (ark_bn254::G2Affine::generator() * sk).into_affine();
+        let signer = BlsPairingSigner::new_bn254(sk);
+        let dst = b"BLS_SIG_BN254G1_XMD:KECCAK-256_SVDW_RO_NUL_";
+        let sig = signer.sign_g1::<sha3::Keccak256>(b"message", dst).unwrap();
+
+        let params = VerificationParameters {
+            public_key: pk.ser_compressed().unwrap().into(),
+            dst: dst.as_slice().into(),
+        };
+        let alg = |compression| {
+            SignatureAlgorithm::Bls(BlsSignatureAlgorithm {
+                curve: BlsSignatureCurve::Bn254G1,
+                hash: BlsSignatureHash::Keccak256,
+                compression,
+            })
+        };
+
+        let compressed = sig.ser_compressed().unwrap();
+        let uncompressed = sig.ser_uncompressed().unwrap();
+        assert!(verify_signature(b"message", &compressed, &alg(true), &params).unwrap());
+        assert!(verify_signature(b"message", &uncompressed, &alg(false), &params).unwrap());
+        assert!(!verify_signature(b"other message", &compressed, &alg(true), &params).unwrap());
+        assert!(verify_signature(b"message", &compressed, &alg(false), &params).is_err());
+    }
+}
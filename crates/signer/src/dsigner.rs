@@ -4,6 +4,8 @@ use bytes::Bytes;
 use futures_util::future::BoxFuture;
 use serde::{Deserialize, Serialize};
 
+pub mod policy;
+
 /// Enum for supported signature algorithms
 #[derive(Clone, Copy, Hash, PartialEq, Eq, Debug, Serialize, Deserialize)]
 #[non_exhaustive]
@@ -11,6 +13,9 @@ pub enum SignatureAlgorithm {
     #[cfg(feature = "bls")]
     Bls(BlsSignatureAlgorithm),
 
+    #[cfg(feature = "frost")]
+    Frost(FrostSignatureAlgorithm),
+
     #[non_exhaustive]
     PlaceHolder(),
 }
@@ -52,6 +57,19 @@ pub enum BlsSignatureHash {
     Keccak256,
 }
 
+/// Ciphersuites supported for FROST threshold Schnorr signatures
+#[cfg(feature = "frost")]
+#[derive(strum::VariantArray, Clone, Copy, Hash, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum FrostSignatureAlgorithm {
+    /// BIP-340 signatures over secp256k1, as used by Bitcoin Taproot
+    #[cfg(feature = "secp256k1")]
+    Secp256k1Bip340,
+    /// RFC 8032 Ed25519 signatures, as used by Solana
+    #[cfg(feature = "ed25519")]
+    Ed25519,
+}
+
 /// Applications supported by dsigner
 #[derive(strum::VariantArray, Clone, Copy, Hash, PartialEq, Eq, Debug, Serialize, Deserialize)]
 #[non_exhaustive]
@@ -106,6 +124,12 @@ pub struct VerificationParameters {
     pub dst: Bytes,
 }
 
+impl VerificationParameters {
+    pub fn new(public_key: Bytes, dst: Bytes) -> Self {
+        Self { public_key, dst }
+    }
+}
+
 /// A signature request composed of a message, a signature algorithm, and application-specific argument(s).
 #[derive(Clone, Hash, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub struct SignatureRequest {
@@ -122,6 +146,9 @@ pub enum DSignerSchemeError {
     #[error("the specified algorithm is not supported by the signer")]
     AlgorithmNotSupported,
 
+    #[error("the request was rejected by the signing policy")]
+    PolicyViolation(#[from] policy::PolicyViolation),
+
     #[error("other scheme error")]
     Other(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
 }
@@ -153,6 +180,15 @@ pub trait DSignerSchemeSigner {
     -> BoxFuture<'_, Result<Bytes, DSignerSchemeError>>;
 }
 
+impl<S: DSignerSchemeSigner + ?Sized> DSignerSchemeSigner for Box<S> {
+    fn async_sign(
+        &self,
+        req: SignatureRequest,
+    ) -> BoxFuture<'_, Result<Bytes, DSignerSchemeError>> {
+        (**self).async_sign(req)
+    }
+}
+
 pub trait DSignerScheme: DSignerSchemeSigner {
     /// Obtain the scheme details.
     fn details(&self) -> SchemeDetails;
@@ -176,6 +212,16 @@ impl ApplicationArgs {
             ApplicationArgs::EvmNet => Application::EvmNet,
         }
     }
+
+    /// The chain id of applications with chain-specific signatures.
+    pub fn chain_id(&self) -> Option<u64> {
+        match self {
+            ApplicationArgs::Blocklock(args) => Some(args.chain_id),
+            ApplicationArgs::Randomness(args) => Some(args.chain_id),
+            ApplicationArgs::OnlySwapsVerifier(args) => Some(args.chain_id),
+            ApplicationArgs::EvmNet | ApplicationArgs::Any(_) => None,
+        }
+    }
 }
 
 #[cfg(feature = "bls")]
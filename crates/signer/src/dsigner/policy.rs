@@ -0,0 +1,391 @@
+//! Declarative policies restricting the requests that a signer accepts to sign.
+//!
+//! A [`SigningPolicy`] lists the applications that may be signed for, along with the rules that
+//! requests of each application must follow. The stateless rules (chain ids, dst suffixes,
+//! message length & format) are evaluated by the signers before releasing a partial signature,
+//! while the per-client rate limits are enforced by a [`PolicyEnforcer`] at the entry point of
+//! the requests.
+
+use crate::dsigner::{Application, ApplicationArgs};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::num::NonZeroU32;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Policy applied to the signing requests of a scheme.
+#[derive(Clone, Default, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct SigningPolicy {
+    /// Rules applied to each application. Requests for applications that are not listed are
+    /// rejected.
+    #[serde(default)]
+    pub applications: HashMap<Application, ApplicationPolicy>,
+}
+
+/// Rules applied to the requests of a specific application.
+#[derive(Clone, Default, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct ApplicationPolicy {
+    /// Chain ids allowed for applications with chain-specific signatures, all chains are
+    /// allowed if unset.
+    #[serde(default)]
+    pub chain_ids: Option<HashSet<u64>>,
+
+    /// Patterns of the dst suffixes allowed for [`Application::Any`], where `*` matches any
+    /// sequence of characters. All suffixes are allowed if unset.
+    #[serde(default)]
+    pub dst_suffixes: Option<Vec<String>>,
+
+    /// Maximum length of the messages, in bytes.
+    #[serde(default)]
+    pub max_message_len: Option<usize>,
+
+    /// Expected structure of the messages.
+    #[serde(default)]
+    pub message_format: Option<MessageFormat>,
+
+    /// Maximum rate of requests from a single client.
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
+}
+
+/// Structures that the messages can be required to follow.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum MessageFormat {
+    /// An abi-encoded onlyswaps verification message, as output by `create_message`, i.e.,
+    /// `(address solver, address sender, address recipient, address tokenIn, address tokenOut,
+    /// uint256 amountIn, uint256 amountOut, uint256 srcChainId, uint256 dstChainId,
+    /// uint256 nonce, bytes32 preHooks, bytes32 postHooks)`.
+    /// For [`Application::OnlySwapsVerifier`], the source chain id must match the one of the
+    /// request.
+    OnlySwapsCreateMessage,
+}
+
+/// Maximum number of requests allowed during a period of time.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct RateLimit {
+    pub requests: NonZeroU32,
+    #[serde(with = "humantime_serde")]
+    pub period: Duration,
+}
+
+#[derive(thiserror::Error, Clone, PartialEq, Eq, Debug)]
+pub enum PolicyViolation {
+    #[error("application {0:?} not allowed by the policy")]
+    ApplicationNotAllowed(Application),
+
+    #[error("chain id {0} not allowed by the policy")]
+    ChainIdNotAllowed(u64),
+
+    #[error("dst suffix `{0}` not allowed by the policy")]
+    DstSuffixNotAllowed(String),
+
+    #[error("message of {len} bytes exceeds the maximum length of {max} bytes")]
+    MessageTooLong { len: usize, max: usize },
+
+    #[error("message does not follow the {0:?} format: {1}")]
+    InvalidMessageFormat(MessageFormat, &'static str),
+
+    #[error("rate limit exceeded for client")]
+    RateLimited,
+}
+
+impl SigningPolicy {
+    /// Evaluate the stateless rules of the policy on a request.
+    pub fn check(&self, m: &[u8], args: &ApplicationArgs) -> Result<(), PolicyViolation> {
+        let app = args.app();
+        let Some(policy) = self.applications.get(&app) else {
+            Err(PolicyViolation::ApplicationNotAllowed(app))?
+        };
+
+        policy.check(m, args)
+    }
+}
+
+impl ApplicationPolicy {
+    /// Evaluate the stateless rules on a request.
+    pub fn check(&self, m: &[u8], args: &ApplicationArgs) -> Result<(), PolicyViolation> {
+        if let (Some(chain_ids), Some(chain_id)) = (&self.chain_ids, args.chain_id())
+            && !chain_ids.contains(&chain_id)
+        {
+            Err(PolicyViolation::ChainIdNotAllowed(chain_id))?
+        }
+
+        if let (Some(patterns), ApplicationArgs::Any(any_args)) = (&self.dst_suffixes, args)
+            && !patterns
+                .iter()
+                .any(|pattern| wildcard_match(pattern, &any_args.dst_suffix))
+        {
+            Err(PolicyViolation::DstSuffixNotAllowed(
+                any_args.dst_suffix.clone(),
+            ))?
+        }
+
+        if let Some(max) = self.max_message_len
+            && m.len() > max
+        {
+            Err(PolicyViolation::MessageTooLong { len: m.len(), max })?
+        }
+
+        if let Some(format) = self.message_format {
+            format
+                .validate(m, args)
+                .map_err(|e| PolicyViolation::InvalidMessageFormat(format, e))?
+        }
+
+        Ok(())
+    }
+}
+
+impl MessageFormat {
+    /// Validate the structure of a message, returning the reason of the failure otherwise.
+    fn validate(&self, m: &[u8], args: &ApplicationArgs) -> Result<(), &'static str> {
+        match self {
+            MessageFormat::OnlySwapsCreateMessage => {
+                const WORDS: usize = 12;
+                const ADDRESSES: usize = 5;
+                const SRC_CHAIN_ID_WORD: usize = 7;
+
+                if m.len() != WORDS * 32 {
+                    Err("invalid length")?
+                }
+
+                let words: Vec<&[u8]> = m.chunks_exact(32).collect();
+                if words[..ADDRESSES].iter().any(|w| w[..12] != [0u8; 12]) {
+                    Err("invalid address encoding")?
+                }
+
+                if let ApplicationArgs::OnlySwapsVerifier(args) = args {
+                    let src_chain_id = words[SRC_CHAIN_ID_WORD];
+                    if src_chain_id[..24] != [0u8; 24]
+                        || src_chain_id[24..] != args.chain_id.to_be_bytes()
+                    {
+                        Err("source chain id does not match the request")?
+                    }
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Match a string against a pattern where `*` matches any sequence of characters.
+fn wildcard_match(pattern: &str, s: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = s.strip_prefix(first) else {
+        return false;
+    };
+
+    let parts: Vec<_> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // No wildcard in the pattern
+        return rest.is_empty();
+    };
+
+    for part in middle {
+        match rest.find(part) {
+            Some(idx) => rest = &rest[idx + part.len()..],
+            None => return false,
+        }
+    }
+
+    rest.ends_with(last)
+}
+
+/// Enforces a [`SigningPolicy`], keeping track of the requests of each client to apply the rate
+/// limits.
+#[derive(Debug)]
+pub struct PolicyEnforcer {
+    policy: SigningPolicy,
+    windows: Mutex<HashMap<(String, Application), RateWindow>>,
+}
+
+/// Requests of a client within the current period.
+#[derive(Debug)]
+struct RateWindow {
+    end: Instant,
+    requests: u32,
+}
+
+impl PolicyEnforcer {
+    pub fn new(policy: SigningPolicy) -> Self {
+        Self {
+            policy,
+            windows: Mutex::default(),
+        }
+    }
+
+    pub fn policy(&self) -> &SigningPolicy {
+        &self.policy
+    }
+
+    /// Evaluate the policy on a request issued by a client.
+    pub fn check(
+        &self,
+        client_id: &str,
+        m: &[u8],
+        args: &ApplicationArgs,
+    ) -> Result<(), PolicyViolation> {
+        self.check_at(client_id, m, args, Instant::now())
+    }
+
+    fn check_at(
+        &self,
+        client_id: &str,
+        m: &[u8],
+        args: &ApplicationArgs,
+        now: Instant,
+    ) -> Result<(), PolicyViolation> {
+        self.policy.check(m, args)?;
+
+        let app = args.app();
+        let Some(rate_limit) = self.policy.applications[&app].rate_limit else {
+            return Ok(());
+        };
+
+        let mut windows = self
+            .windows
+            .lock()
+            .expect("a thread panicked with the mutex");
+
+        // Drop the expired windows to prevent the map from growing indefinitely
+        windows.retain(|_, w| w.end > now);
+
+        let window = windows
+            .entry((client_id.to_owned(), app))
+            .or_insert(RateWindow {
+                end: now + rate_limit.period,
+                requests: 0,
+            });
+        if window.requests >= rate_limit.requests.get() {
+            Err(PolicyViolation::RateLimited)?
+        }
+
+        window.requests += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dsigner::{ApplicationAnyArgs, ApplicationBlocklockArgs, OnlySwapsVerifierArgs};
+
+    fn any(dst_suffix: &str) -> ApplicationArgs {
+        ApplicationArgs::Any(ApplicationAnyArgs {
+            dst_suffix: dst_suffix.to_owned(),
+        })
+    }
+
+    fn onlyswaps_message(src_chain_id: u64) -> Vec<u8> {
+        let mut m = vec![0u8; 12 * 32];
+        for (i, word) in m.chunks_exact_mut(32).enumerate().take(5) {
+            word[31] = i as u8 + 1; // addresses
+        }
+        m[7 * 32 + 24..8 * 32].copy_from_slice(&src_chain_id.to_be_bytes());
+        m
+    }
+
+    #[test]
+    fn wildcard_patterns() {
+        assert!(wildcard_match("test", "test"));
+        assert!(!wildcard_match("test", "test2"));
+        assert!(wildcard_match("*", ""));
+        assert!(wildcard_match("test-*", "test-1"));
+        assert!(wildcard_match("*-v1", "app-v1"));
+        assert!(!wildcard_match("*-v1", "app-v2"));
+        assert!(wildcard_match("app-*-v*", "app-1-v2"));
+        assert!(!wildcard_match("app-*-v*", "app-1"));
+        assert!(!wildcard_match("a*a", "a"));
+    }
+
+    #[test]
+    fn stateless_rules() {
+        let policy: SigningPolicy = toml::from_str(
+            r#"
+            [applications.Blocklock]
+            chain_ids = [1, 10]
+            max_message_len = 4
+
+            [applications.Any]
+            dst_suffixes = ["app-*"]
+
+            [applications.OnlySwapsVerifier]
+            message_format = "OnlySwapsCreateMessage"
+            "#,
+        )
+        .unwrap();
+
+        let blocklock =
+            |chain_id| ApplicationArgs::Blocklock(ApplicationBlocklockArgs { chain_id });
+        assert_eq!(policy.check(b"m", &blocklock(10)), Ok(()));
+        assert_eq!(
+            policy.check(b"m", &blocklock(2)),
+            Err(PolicyViolation::ChainIdNotAllowed(2))
+        );
+        assert_eq!(
+            policy.check(b"12345", &blocklock(1)),
+            Err(PolicyViolation::MessageTooLong { len: 5, max: 4 })
+        );
+
+        assert_eq!(policy.check(b"m", &any("app-1")), Ok(()));
+        assert_eq!(
+            policy.check(b"m", &any("other")),
+            Err(PolicyViolation::DstSuffixNotAllowed("other".to_owned()))
+        );
+
+        assert_eq!(
+            policy.check(b"m", &ApplicationArgs::EvmNet),
+            Err(PolicyViolation::ApplicationNotAllowed(Application::EvmNet))
+        );
+
+        let onlyswaps =
+            |chain_id| ApplicationArgs::OnlySwapsVerifier(OnlySwapsVerifierArgs { chain_id });
+        assert_eq!(policy.check(&onlyswaps_message(10), &onlyswaps(10)), Ok(()));
+        assert!(matches!(
+            policy.check(&onlyswaps_message(10), &onlyswaps(1)),
+            Err(PolicyViolation::InvalidMessageFormat(..))
+        ));
+        assert!(matches!(
+            policy.check(b"m", &onlyswaps(10)),
+            Err(PolicyViolation::InvalidMessageFormat(..))
+        ));
+
+        let mut m = onlyswaps_message(10);
+        m[0] = 1; // address with dirty upper bytes
+        assert!(matches!(
+            policy.check(&m, &onlyswaps(10)),
+            Err(PolicyViolation::InvalidMessageFormat(..))
+        ));
+    }
+
+    #[test]
+    fn rate_limits() {
+        let policy: SigningPolicy = toml::from_str(
+            r#"
+            [applications.Any]
+            rate_limit = { requests = 2, period = "1m" }
+            "#,
+        )
+        .unwrap();
+        let enforcer = PolicyEnforcer::new(policy);
+
+        let now = Instant::now();
+        let args = any("test");
+        assert_eq!(enforcer.check_at("alice", b"m", &args, now), Ok(()));
+        assert_eq!(enforcer.check_at("alice", b"m", &args, now), Ok(()));
+        assert_eq!(
+            enforcer.check_at("alice", b"m", &args, now),
+            Err(PolicyViolation::RateLimited)
+        );
+
+        // Rate limits are per client
+        assert_eq!(enforcer.check_at("bob", b"m", &args, now), Ok(()));
+
+        // And reset after the period
+        let later = now + Duration::from_secs(60);
+        assert_eq!(enforcer.check_at("alice", b"m", &args, later), Ok(()));
+    }
+}
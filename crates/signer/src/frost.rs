@@ -0,0 +1,490 @@
+//! Implementation of a [`DSignerScheme`] for FROST threshold Schnorr signatures, see RFC 9591.
+//!
+//! Signatures are obtained in two rounds over a [`Transport`]:
+//!  1. Upon receiving a signing request, each node takes a pair of nonces from its preprocessed
+//!     pool and broadcasts the commitments. The coordinator of the signing attempt, derived from
+//!     the request and the attempt number, selects the first t commitments it receives, and
+//!     broadcasts them as a signing package.
+//!  2. The nodes listed in the signing package broadcast their signature share, which are then
+//!     verified and aggregated by every node.
+//!
+//! If no signature is obtained before a timeout, e.g., due to an unresponsive coordinator or
+//! signer, a new attempt is started with a different coordinator and fresh nonces.
+//! Similarly to BLS, nodes only commit to nonces for requests they received themselves, such that
+//! a threshold of nodes must agree on signing a message.
+//!
+//! Unlike BLS, Schnorr verifiers (BIP-340, RFC 8032) do not support domain separation tags: the
+//! message is signed as is, and only [`Application::Any`] is supported by default.
+//!
+//! [`DSignerScheme`]: crate::dsigner::DSignerScheme
+
+mod ciphersuite;
+mod dsigner_scheme_impl;
+mod handlers;
+mod nonces;
+mod protocol;
+
+pub use ciphersuite::*;
+pub use dsigner_scheme_impl::*;
+
+use crate::dsigner::policy::SigningPolicy;
+use crate::dsigner::{
+    Application, ApplicationArgs, FrostSignatureAlgorithm, SchemeAlgorithm, SchemeDetails,
+    SignatureAlgorithm, SignatureRequest,
+};
+use crate::frost::nonces::NoncePool;
+use crate::frost::protocol::{KeyPackage, SigningContext, SigningNonces};
+use bytes::Bytes;
+use dcipher_network::Transport;
+use digest::Digest;
+use itertools::Either;
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+type SignatureOrChannel = Either<Bytes, tokio::sync::watch::Sender<Option<Bytes>>>;
+
+type SharedSignatureCache =
+    Arc<std::sync::Mutex<LruCache<FrostSignatureRequest, SignatureOrChannel>>>;
+
+type SharedSessionsCache<C> =
+    Arc<std::sync::Mutex<LruCache<FrostSignatureRequest, SigningSession<C>>>>;
+
+#[derive(Clone, Hash, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct FrostSignatureRequest {
+    m: Bytes,
+    args: ApplicationArgs,
+    alg: FrostSignatureAlgorithm,
+}
+
+impl TryFrom<SignatureRequest> for FrostSignatureRequest {
+    type Error = FrostThresholdSignerError;
+
+    fn try_from(value: SignatureRequest) -> Result<Self, Self::Error> {
+        let SignatureAlgorithm::Frost(alg) = value.alg else {
+            Err(Self::Error::UnsupportedAlgorithm)?
+        };
+
+        Ok(Self {
+            alg,
+            args: value.args,
+            m: value.m,
+        })
+    }
+}
+
+impl From<FrostSignatureRequest> for SignatureRequest {
+    fn from(value: FrostSignatureRequest) -> Self {
+        Self {
+            alg: SignatureAlgorithm::Frost(value.alg),
+            args: value.args,
+            m: value.m,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+enum NetworkMessage {
+    /// Round one, commitments to a pair of nonces
+    Commitment(CommitmentMessage),
+    /// Commitments selected by the coordinator of an attempt
+    SigningPackage(SigningPackageMessage),
+    /// Round two, signature share of a signer
+    SignatureShare(SignatureShareMessage),
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct EncodedCommitment {
+    id: u16,
+    hiding: Bytes,
+    binding: Bytes,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct CommitmentMessage {
+    req: FrostSignatureRequest,
+    attempt: u32,
+    commitment: EncodedCommitment,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct SigningPackageMessage {
+    req: FrostSignatureRequest,
+    attempt: u32,
+    commitments: Vec<EncodedCommitment>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct SignatureShareMessage {
+    req: FrostSignatureRequest,
+    attempt: u32,
+    share: Bytes,
+}
+
+/// State of the signing attempts of a request.
+struct SigningSession<C: FrostCiphersuite> {
+    /// Latest attempt started by this node, None if the request was not received by this node
+    attempt: Option<u32>,
+    attempts: BTreeMap<u32, SigningAttempt<C>>,
+}
+
+/// State of a single signing attempt.
+struct SigningAttempt<C: FrostCiphersuite> {
+    /// Nonces committed to by this node, consumed upon signing
+    nonces: Option<SigningNonces<C>>,
+    /// Commitments received by the coordinator
+    commitments: BTreeMap<u16, protocol::SigningCommitment<C>>,
+    /// Whether the coordinator has already sent the signing package
+    package_sent: bool,
+    /// Signing context built from the signing package of the coordinator
+    context: Option<SigningContext<C>>,
+    /// Verified signature shares
+    shares: BTreeMap<u16, Scalar<C>>,
+    /// Signature shares received before the signing package
+    pending_shares: BTreeMap<u16, Scalar<C>>,
+}
+
+impl<C: FrostCiphersuite> Default for SigningSession<C> {
+    fn default() -> Self {
+        Self {
+            attempt: None,
+            attempts: BTreeMap::new(),
+        }
+    }
+}
+
+impl<C: FrostCiphersuite> Default for SigningAttempt<C> {
+    fn default() -> Self {
+        Self {
+            nonces: None,
+            commitments: BTreeMap::new(),
+            package_sent: false,
+            context: None,
+            shares: BTreeMap::new(),
+            pending_shares: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum FrostThresholdSignerError {
+    #[error("missing public key share of party {0}")]
+    MissingPublicKey(u16),
+
+    #[error("the secret share does not match the public key share")]
+    InvalidSecretShare,
+
+    #[error("not enough public key shares to derive the group public key")]
+    NotEnoughPublicKeys,
+
+    #[error("the public key share of party {0} is inconsistent with the other shares")]
+    InconsistentPublicKeys(u16),
+
+    #[error("the nonces do not match the commitment of the signing package")]
+    CommitmentMismatch,
+
+    #[error("unsupported algorithm")]
+    UnsupportedAlgorithm,
+}
+
+/// Threshold signer that exchanges nonce commitments and signature shares with other nodes to
+/// issue FROST signatures.
+pub struct FrostThresholdSigner<C>
+where
+    C: FrostCiphersuite,
+{
+    // Signatures cache
+    signatures_cache: SharedSignatureCache,
+
+    // Map from requests to the state of the signing attempts
+    sessions: SharedSessionsCache<C>,
+
+    // Nonces generated ahead of signing requests
+    nonce_pool: std::sync::Mutex<NoncePool<C>>,
+
+    // Secret share, public key shares, and group public key
+    key: KeyPackage<C>,
+
+    // Threshold parameters
+    n: u16,
+    t: u16,
+    id: u16,
+
+    // Applications filter
+    supported_apps: HashSet<Application>,
+
+    // Policy evaluated before committing to nonces for a request
+    policy: Option<Arc<SigningPolicy>>,
+
+    // Delay before starting a new signing attempt, and maximum number of attempts
+    attempt_timeout: Duration,
+    max_attempts: u32,
+}
+
+impl<C> FrostThresholdSigner<C>
+where
+    C: FrostCiphersuite,
+{
+    /// Create a new threshold signer by specifying the various threshold scheme parameters.
+    pub fn new(
+        n: u16,
+        t: u16,
+        id: u16,
+        secret_share: Scalar<C>,
+        public_shares: HashMap<u16, C::Group>,
+    ) -> Result<Self, FrostThresholdSignerError> {
+        Self::new_with_cache_size(
+            n,
+            t,
+            id,
+            secret_share,
+            public_shares,
+            const { NonZeroUsize::new(64).unwrap() },
+        )
+    }
+
+    /// New threshold signer with a custom LRU cache size.
+    pub fn new_with_cache_size(
+        n: u16,
+        t: u16,
+        id: u16,
+        secret_share: Scalar<C>,
+        public_shares: HashMap<u16, C::Group>,
+        lru_cache_size: NonZeroUsize,
+    ) -> Result<Self, FrostThresholdSignerError> {
+        let key = KeyPackage::new(id, t, secret_share, public_shares.into_iter().collect())?;
+
+        Ok(Self {
+            signatures_cache: Arc::new(std::sync::Mutex::new(LruCache::new(lru_cache_size))),
+            sessions: Arc::new(std::sync::Mutex::new(LruCache::new(lru_cache_size))),
+            nonce_pool: std::sync::Mutex::new(NoncePool::new(lru_cache_size.get())),
+            key,
+            n,
+            t,
+            id,
+            supported_apps: HashSet::from([Application::Any]),
+            policy: None,
+            attempt_timeout: Duration::from_secs(10),
+            // give each node a chance to coordinate an attempt
+            max_attempts: n.into(),
+        })
+    }
+
+    /// Restrict the signer to the specified applications.
+    ///
+    /// # Warning
+    /// Signatures do not depend on the application, a signature requested by an application can
+    /// therefore be used by any other application on the same message.
+    pub fn with_applications(mut self, apps: impl IntoIterator<Item = Application>) -> Self {
+        self.supported_apps = apps.into_iter().collect();
+        self
+    }
+
+    /// Only sign the requests allowed by a policy.
+    pub fn with_policy(mut self, policy: SigningPolicy) -> Self {
+        self.policy = Some(Arc::new(policy));
+        self
+    }
+
+    /// Set the number of nonces generated ahead of signing requests.
+    pub fn with_nonce_pool_size(mut self, size: usize) -> Self {
+        self.nonce_pool = std::sync::Mutex::new(NoncePool::new(size));
+        self
+    }
+
+    /// Set the delay before starting a new signing attempt with a different coordinator.
+    pub fn with_attempt_timeout(mut self, timeout: Duration) -> Self {
+        self.attempt_timeout = timeout;
+        self
+    }
+
+    fn scheme_details(&self) -> SchemeDetails {
+        SchemeDetails {
+            n: self.n,
+            t: self.t,
+            scheme_algs: vec![SchemeAlgorithm {
+                public_key: C::serialize_public_key(&self.key.group_pk).into(),
+                algs: vec![SignatureAlgorithm::Frost(C::ALGORITHM)],
+                apps: self.supported_apps.iter().copied().collect(),
+            }],
+        }
+    }
+
+    /// Whether the request can be signed by this signer.
+    fn is_supported(&self, req: &FrostSignatureRequest) -> bool {
+        req.alg == C::ALGORITHM && self.supported_apps.contains(&req.args.app())
+    }
+
+    /// Deterministically select the coordinator of an attempt, such that consecutive attempts
+    /// are coordinated by different nodes.
+    fn coordinator(&self, req: &FrostSignatureRequest, attempt: u32) -> u16 {
+        let req_bytes = serde_cbor::to_vec(req).expect("serialization should always work");
+        let digest = sha2::Sha256::digest(&req_bytes);
+        let offset = u64::from_le_bytes(digest[..8].try_into().expect("digest is 32 bytes"));
+
+        let idx = offset.wrapping_add(attempt.into()) % u64::from(self.n);
+        u16::try_from(idx).expect("idx lower than n") + 1
+    }
+
+    /// Runs the threshold signer in a background task and obtain a cancellation token and a registry.
+    pub fn run<T>(self, mut transport: T) -> (CancellationToken, AsyncFrostSigner)
+    where
+        T: Transport<Identity = u16>,
+    {
+        // Preprocess nonces before accepting requests
+        self.nonce_pool
+            .lock()
+            .expect("a thread panicked with the mutex")
+            .refill(&self.key);
+
+        let arc_self = Arc::new(self);
+        let cancellation_token = CancellationToken::new();
+        let (tx_registry_to_signer, rx_signer_to_registry) = tokio::sync::mpsc::unbounded_channel();
+
+        // Create a [`DSignerScheme`]
+        let signer = AsyncFrostSigner::new(
+            arc_self.scheme_details(),
+            C::ALGORITHM,
+            arc_self.signatures_cache.clone(),
+            tx_registry_to_signer,
+            arc_self.supported_apps.clone(),
+            arc_self.policy.clone(),
+        );
+
+        let messages_stream = transport
+            .receiver_stream()
+            .expect("transport should provide at least one receiver stream");
+        let tx_signer_to_network = transport
+            .sender()
+            .expect("transport should provide at least one sender");
+
+        // Spawn task that handles signing requests from registry
+        tokio::task::spawn(arc_self.clone().sign_requests_loop(
+            rx_signer_to_registry,
+            tx_signer_to_network.clone(),
+            cancellation_token.child_token(),
+        ));
+
+        // Spawn task that handles messages from other nodes
+        tokio::task::spawn(arc_self.clone().network_recv_loop(
+            messages_stream,
+            tx_signer_to_network,
+            cancellation_token.child_token(),
+        ));
+
+        (cancellation_token, signer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dsigner::{ApplicationAnyArgs, DSignerScheme, DSignerSchemeSigner};
+    use crate::frost::protocol::tests::trusted_dealer;
+    use dcipher_network::transports::in_memory::MemoryNetwork;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    async fn sign_with_signers<C: FrostCiphersuite>(ids: &[u16]) -> (Bytes, Vec<Bytes>) {
+        let n = 3;
+        let t = 2;
+        let (sks, pks) = trusted_dealer::<C>(n, t, &mut StdRng::seed_from_u64(0));
+        let pks: HashMap<_, _> = pks.into_iter().collect();
+
+        // Get transports
+        let mut transports = MemoryNetwork::get_transports(1..=n);
+
+        // Start the threshold signers, nodes not in ids are offline
+        let signers: Vec<_> = (1..=n)
+            .zip(sks)
+            .map(|(id, sk)| {
+                let transport = transports.pop_front().unwrap();
+                let signer = FrostThresholdSigner::<C>::new(n, t, id, sk, pks.clone())
+                    .unwrap()
+                    .with_attempt_timeout(Duration::from_millis(200));
+                ids.contains(&id).then(|| signer.run(transport).1)
+            })
+            .collect();
+
+        let req = SignatureRequest {
+            m: b"my test message".to_vec().into(),
+            args: ApplicationArgs::Any(ApplicationAnyArgs {
+                dst_suffix: "TEST".to_owned(),
+            }),
+            alg: SignatureAlgorithm::Frost(C::ALGORITHM),
+        };
+        let signers: Vec<_> = signers.into_iter().flatten().collect();
+        let public_key = signers[0]
+            .verification_parameters(&req.alg, &req.args)
+            .unwrap()
+            .public_key;
+
+        // Wait for signatures up to 5 seconds
+        let sigs = tokio::time::timeout(
+            Duration::from_secs(5),
+            futures_util::future::try_join_all(signers.iter().map(|s| s.async_sign(req.clone()))),
+        )
+        .await
+        .expect("to get threshold sig within 5s")
+        .expect("all sigs to succeed");
+
+        assert_eq!(sigs.len(), ids.len());
+        assert!(sigs.iter().all(|sig| sig == &sigs[0]));
+        (public_key, sigs)
+    }
+
+    #[cfg(feature = "secp256k1")]
+    #[tokio::test]
+    async fn frost_threshold_signer_secp256k1() {
+        use crate::frost::ciphersuite::verify_bip340;
+
+        let (pk, sigs) = sign_with_signers::<FrostSecp256k1Bip340>(&[1, 2, 3]).await;
+        assert!(verify_bip340(&pk, b"my test message", &sigs[0]));
+
+        // A single node is offline, the coordinator of the first attempt may be unresponsive
+        let (pk, sigs) = sign_with_signers::<FrostSecp256k1Bip340>(&[1, 3]).await;
+        assert!(verify_bip340(&pk, b"my test message", &sigs[0]));
+    }
+
+    #[cfg(feature = "ed25519")]
+    #[tokio::test]
+    async fn frost_threshold_signer_ed25519() {
+        use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+
+        let (pk, sigs) = sign_with_signers::<FrostEd25519Sha512>(&[1, 2, 3]).await;
+        let pk = VerifyingKey::from_bytes(pk.as_ref().try_into().unwrap()).unwrap();
+        let sig = Signature::from_bytes(sigs[0].as_ref().try_into().unwrap());
+        assert!(pk.verify(b"my test message", &sig).is_ok());
+    }
+
+    #[cfg(feature = "secp256k1")]
+    #[test]
+    fn unsupported_application() {
+        let (sks, pks) =
+            trusted_dealer::<FrostSecp256k1Bip340>(3, 2, &mut StdRng::seed_from_u64(0));
+        let signer = FrostThresholdSigner::<FrostSecp256k1Bip340>::new(
+            3,
+            2,
+            1,
+            sks[0],
+            pks.into_iter().collect(),
+        )
+        .unwrap();
+
+        let details = signer.scheme_details();
+        assert_eq!(details.scheme_algs.len(), 1);
+        assert_eq!(details.scheme_algs[0].apps, vec![Application::Any]);
+        assert_eq!(details.scheme_algs[0].public_key.len(), 32);
+
+        let req = FrostSignatureRequest {
+            m: Bytes::from_static(b"m"),
+            args: ApplicationArgs::EvmNet,
+            alg: FrostSignatureAlgorithm::Secp256k1Bip340,
+        };
+        assert!(!signer.is_supported(&req));
+    }
+}
@@ -0,0 +1,72 @@
+//! Ciphersuites supported by the FROST threshold signer, see RFC 9591, § 6.
+
+#[cfg(feature = "ed25519")]
+mod ed25519;
+#[cfg(feature = "secp256k1")]
+mod secp256k1;
+
+#[cfg(feature = "ed25519")]
+pub use ed25519::FrostEd25519Sha512;
+#[cfg(feature = "secp256k1")]
+pub use secp256k1::FrostSecp256k1Bip340;
+#[cfg(all(test, feature = "secp256k1"))]
+pub(crate) use secp256k1::verify_bip340;
+
+use crate::dsigner::FrostSignatureAlgorithm;
+use ark_ec::{CurveGroup, PrimeGroup};
+use utils::serialize::SerializationError;
+
+/// Scalar field of a ciphersuite's group.
+pub type Scalar<C> = <<C as FrostCiphersuite>::Group as PrimeGroup>::ScalarField;
+
+/// A FROST ciphersuite, i.e., a prime-order group and the hash functions H1 to H5 of RFC 9591.
+pub trait FrostCiphersuite: Send + Sync + 'static {
+    type Group: CurveGroup;
+
+    /// The signature algorithm implemented by this ciphersuite.
+    const ALGORITHM: FrostSignatureAlgorithm;
+
+    /// Encode a group element into bytes.
+    fn serialize_element(p: &Self::Group) -> Vec<u8>;
+
+    /// Decode a group element, rejecting the identity element and invalid encodings.
+    fn deserialize_element(buf: &[u8]) -> Result<Self::Group, SerializationError>;
+
+    /// Encode a scalar into bytes.
+    fn serialize_scalar(s: &Scalar<Self>) -> Vec<u8>;
+
+    /// Decode a scalar, rejecting non-canonical encodings.
+    fn deserialize_scalar(buf: &[u8]) -> Result<Scalar<Self>, SerializationError>;
+
+    /// H1, used to derive the binding factors.
+    fn h1(m: &[u8]) -> Scalar<Self>;
+
+    /// Compute the challenge of a signature with group commitment `r`, and public key `pk`,
+    /// i.e., H2 of RFC 9591.
+    fn challenge(r: &Self::Group, pk: &Self::Group, m: &[u8]) -> Scalar<Self>;
+
+    /// H3, used to generate nonces.
+    fn h3(m: &[u8]) -> Scalar<Self>;
+
+    /// H4, used to hash the message to sign.
+    fn h4(m: &[u8]) -> Vec<u8>;
+
+    /// H5, used to hash the encoded list of commitments.
+    fn h5(m: &[u8]) -> Vec<u8>;
+
+    /// Whether a public key or a group commitment must be negated before being used, e.g., BIP-340
+    /// only supports points with an even y coordinate.
+    fn requires_negation(_p: &Self::Group) -> bool {
+        false
+    }
+
+    /// Encode the group public key in the format expected by verifiers.
+    fn serialize_public_key(pk: &Self::Group) -> Vec<u8> {
+        Self::serialize_element(pk)
+    }
+
+    /// Encode a signature (R, z) in the format expected by verifiers.
+    fn serialize_signature(r: &Self::Group, z: &Scalar<Self>) -> Vec<u8> {
+        [Self::serialize_element(r), Self::serialize_scalar(z)].concat()
+    }
+}
@@ -0,0 +1,164 @@
+//! FROST(Ed25519, SHA-512) ciphersuite as specified by RFC 9591, § 6.1. Signatures can be verified
+//! with any RFC 8032 Ed25519 verifier, e.g., by Solana programs.
+
+use super::{FrostCiphersuite, Scalar};
+use crate::dsigner::FrostSignatureAlgorithm;
+use ark_ec::CurveGroup;
+use ark_ec::twisted_edwards::Affine;
+use ark_ed25519::{EdwardsConfig, EdwardsProjective, Fq, Fr};
+use ark_ff::{BigInteger, PrimeField, Zero};
+use digest::Digest;
+use sha2::Sha512;
+use utils::serialize::SerializationError;
+
+const CONTEXT_STRING: &[u8] = b"FROST-ED25519-SHA512-v1";
+const ELEMENT_SIZE: usize = 32;
+const SCALAR_SIZE: usize = 32;
+
+/// FROST ciphersuite over edwards25519 outputting Ed25519 signatures.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct FrostEd25519Sha512;
+
+/// SHA-512 of the concatenated inputs
+fn hash(inputs: &[&[u8]]) -> Vec<u8> {
+    inputs
+        .iter()
+        .fold(Sha512::new(), |h, input| h.chain_update(input))
+        .finalize()
+        .to_vec()
+}
+
+/// SHA-512 of the concatenated inputs, interpreted as a little endian integer modulo L.
+fn hash_to_scalar(inputs: &[&[u8]]) -> Fr {
+    Fr::from_le_bytes_mod_order(&hash(inputs))
+}
+
+impl FrostCiphersuite for FrostEd25519Sha512 {
+    type Group = EdwardsProjective;
+
+    const ALGORITHM: FrostSignatureAlgorithm = FrostSignatureAlgorithm::Ed25519;
+
+    /// RFC 8032 encoding, i.e., the little endian encoding of y, with the most significant bit
+    /// set to the least significant bit of x.
+    fn serialize_element(p: &Self::Group) -> Vec<u8> {
+        let p = p.into_affine();
+        let mut buf = p.y.into_bigint().to_bytes_le();
+        if p.x.into_bigint().is_odd() {
+            buf[ELEMENT_SIZE - 1] |= 0x80;
+        }
+        buf
+    }
+
+    fn deserialize_element(buf: &[u8]) -> Result<Self::Group, SerializationError> {
+        if buf.len() != ELEMENT_SIZE {
+            Err(SerializationError::InvalidData)?
+        }
+
+        let x_odd = buf[ELEMENT_SIZE - 1] & 0x80 != 0;
+        let mut y_buf = buf.to_vec();
+        y_buf[ELEMENT_SIZE - 1] &= 0x7f;
+
+        let y = Fq::from_le_bytes_mod_order(&y_buf);
+        if y.into_bigint().to_bytes_le() != y_buf {
+            // value greater than the modulus
+            Err(SerializationError::InvalidData)?
+        }
+
+        let (x0, x1) = Affine::<EdwardsConfig>::get_xs_from_y_unchecked(y)
+            .ok_or(SerializationError::InvalidData)?;
+        let x = if x0.into_bigint().is_odd() == x_odd {
+            x0
+        } else {
+            x1
+        };
+        if x.is_zero() && x_odd {
+            // x = 0 has no odd representation
+            Err(SerializationError::InvalidData)?
+        }
+
+        let p = Affine::<EdwardsConfig>::new_unchecked(x, y);
+        if p.is_zero() || !p.is_in_correct_subgroup_assuming_on_curve() {
+            Err(SerializationError::InvalidData)?
+        }
+
+        Ok(p.into())
+    }
+
+    fn serialize_scalar(s: &Scalar<Self>) -> Vec<u8> {
+        s.into_bigint().to_bytes_le()
+    }
+
+    fn deserialize_scalar(buf: &[u8]) -> Result<Scalar<Self>, SerializationError> {
+        if buf.len() != SCALAR_SIZE {
+            Err(SerializationError::InvalidData)?
+        }
+
+        let s = Fr::from_le_bytes_mod_order(buf);
+        if Self::serialize_scalar(&s) != buf {
+            // value greater than the group order
+            Err(SerializationError::InvalidData)?
+        }
+
+        Ok(s)
+    }
+
+    fn h1(m: &[u8]) -> Scalar<Self> {
+        hash_to_scalar(&[CONTEXT_STRING, b"rho", m])
+    }
+
+    fn challenge(r: &Self::Group, pk: &Self::Group, m: &[u8]) -> Scalar<Self> {
+        hash_to_scalar(&[&Self::serialize_element(r), &Self::serialize_element(pk), m])
+    }
+
+    fn h3(m: &[u8]) -> Scalar<Self> {
+        hash_to_scalar(&[CONTEXT_STRING, b"nonce", m])
+    }
+
+    fn h4(m: &[u8]) -> Vec<u8> {
+        hash(&[CONTEXT_STRING, b"msg", m])
+    }
+
+    fn h5(m: &[u8]) -> Vec<u8> {
+        hash(&[CONTEXT_STRING, b"com", m])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ec::PrimeGroup;
+
+    #[test]
+    fn serialization() {
+        let g = EdwardsProjective::generator();
+        let enc = FrostEd25519Sha512::serialize_element(&g);
+        assert_eq!(
+            hex::encode(&enc),
+            "5866666666666666666666666666666666666666666666666666666666666666"
+        );
+        assert_eq!(FrostEd25519Sha512::deserialize_element(&enc).unwrap(), g);
+
+        let neg_enc = FrostEd25519Sha512::serialize_element(&-g);
+        assert_eq!(neg_enc[ELEMENT_SIZE - 1] & 0x80, 0x80);
+        assert_eq!(
+            FrostEd25519Sha512::deserialize_element(&neg_enc).unwrap(),
+            -g
+        );
+
+        // identity element
+        let identity = FrostEd25519Sha512::serialize_element(&EdwardsProjective::zero());
+        assert!(FrostEd25519Sha512::deserialize_element(&identity).is_err());
+
+        // (0, -1), a point of order 2
+        let low_order =
+            hex::decode("ecffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f")
+                .unwrap();
+        assert!(FrostEd25519Sha512::deserialize_element(&low_order).is_err());
+
+        let s = Fr::from(42u64);
+        let enc = FrostEd25519Sha512::serialize_scalar(&s);
+        assert_eq!(enc.len(), SCALAR_SIZE);
+        assert_eq!(FrostEd25519Sha512::deserialize_scalar(&enc).unwrap(), s);
+        assert!(FrostEd25519Sha512::deserialize_scalar(&[0xff; SCALAR_SIZE]).is_err());
+    }
+}
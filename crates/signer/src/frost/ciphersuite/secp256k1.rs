@@ -0,0 +1,191 @@
+//! FROST(secp256k1, SHA-256) ciphersuite producing BIP-340 Schnorr signatures, i.e., signatures
+//! that can be used for Bitcoin Taproot key path spends.
+//! The ciphersuite follows RFC 9591, § 6.5, except for the challenge, computed with BIP-340's
+//! tagged hash, and the group public key and group commitment, which must have an even y
+//! coordinate.
+//! Note that the group public key is used as is, i.e., without a Taproot tweak.
+
+use super::{FrostCiphersuite, Scalar};
+use crate::dsigner::FrostSignatureAlgorithm;
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::field_hashers::{DefaultFieldHasher, HashToField};
+use ark_ff::{BigInteger, PrimeField, Zero};
+use ark_secp256k1::{Fr, Projective};
+use digest::Digest;
+use sha2::Sha256;
+use utils::serialize::SerializationError;
+use utils::serialize::point::{PointDeserializeCompressed, PointSerializeCompressed};
+
+const CONTEXT_STRING: &[u8] = b"FROST-secp256k1-SHA256-TR-v1";
+const SCALAR_SIZE: usize = 32;
+
+/// FROST ciphersuite over secp256k1 outputting BIP-340 signatures.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct FrostSecp256k1Bip340;
+
+/// Hash a message to a scalar using RFC 9380's hash_to_field with DST = contextString || tag.
+fn hash_to_scalar(tag: &[u8], m: &[u8]) -> Fr {
+    let dst = [CONTEXT_STRING, tag].concat();
+    let hasher = <DefaultFieldHasher<Sha256, 128> as HashToField<Fr>>::new(&dst);
+    let [s] = hasher.hash_to_field::<1>(m);
+    s
+}
+
+/// SHA-256(contextString || tag || m)
+fn hash(tag: &[u8], m: &[u8]) -> Vec<u8> {
+    Sha256::new()
+        .chain_update(CONTEXT_STRING)
+        .chain_update(tag)
+        .chain_update(m)
+        .finalize()
+        .to_vec()
+}
+
+/// BIP-340 tagged hash, i.e., SHA-256(SHA-256(tag) || SHA-256(tag) || m).
+fn tagged_hash(tag: &[u8], m: &[u8]) -> [u8; 32] {
+    let tag_hash = Sha256::digest(tag);
+    Sha256::new()
+        .chain_update(tag_hash)
+        .chain_update(tag_hash)
+        .chain_update(m)
+        .finalize()
+        .into()
+}
+
+/// Big endian encoding of the x coordinate of a point, the identity being encoded as zero.
+fn x_only(p: &Projective) -> Vec<u8> {
+    match p.into_affine().xy() {
+        Some((x, _)) => x.into_bigint().to_bytes_be(),
+        None => vec![0u8; 32],
+    }
+}
+
+impl FrostCiphersuite for FrostSecp256k1Bip340 {
+    type Group = Projective;
+
+    const ALGORITHM: FrostSignatureAlgorithm = FrostSignatureAlgorithm::Secp256k1Bip340;
+
+    fn serialize_element(p: &Self::Group) -> Vec<u8> {
+        p.ser_compressed()
+            .expect("SEC1 encoding should always work")
+    }
+
+    fn deserialize_element(buf: &[u8]) -> Result<Self::Group, SerializationError> {
+        let p = Projective::deser_compressed(buf)?;
+        if p.is_zero() {
+            Err(SerializationError::InvalidData)?
+        }
+
+        Ok(p)
+    }
+
+    fn serialize_scalar(s: &Scalar<Self>) -> Vec<u8> {
+        s.into_bigint().to_bytes_be()
+    }
+
+    fn deserialize_scalar(buf: &[u8]) -> Result<Scalar<Self>, SerializationError> {
+        if buf.len() != SCALAR_SIZE {
+            Err(SerializationError::InvalidData)?
+        }
+
+        let s = Fr::from_be_bytes_mod_order(buf);
+        if Self::serialize_scalar(&s) != buf {
+            // value greater than the group order
+            Err(SerializationError::InvalidData)?
+        }
+
+        Ok(s)
+    }
+
+    fn h1(m: &[u8]) -> Scalar<Self> {
+        hash_to_scalar(b"rho", m)
+    }
+
+    fn challenge(r: &Self::Group, pk: &Self::Group, m: &[u8]) -> Scalar<Self> {
+        let input = [x_only(r), x_only(pk), m.to_vec()].concat();
+        Fr::from_be_bytes_mod_order(&tagged_hash(b"BIP0340/challenge", &input))
+    }
+
+    fn h3(m: &[u8]) -> Scalar<Self> {
+        hash_to_scalar(b"nonce", m)
+    }
+
+    fn h4(m: &[u8]) -> Vec<u8> {
+        hash(b"msg", m)
+    }
+
+    fn h5(m: &[u8]) -> Vec<u8> {
+        hash(b"com", m)
+    }
+
+    fn requires_negation(p: &Self::Group) -> bool {
+        p.into_affine()
+            .xy()
+            .is_some_and(|(_, y)| y.into_bigint().is_odd())
+    }
+
+    fn serialize_public_key(pk: &Self::Group) -> Vec<u8> {
+        x_only(pk)
+    }
+
+    fn serialize_signature(r: &Self::Group, z: &Scalar<Self>) -> Vec<u8> {
+        [x_only(r), Self::serialize_scalar(z)].concat()
+    }
+}
+
+/// Verify a BIP-340 signature, used to test the ciphersuite against external signatures.
+#[cfg(test)]
+pub(crate) fn verify_bip340(pk: &[u8], m: &[u8], sig: &[u8]) -> bool {
+    use ark_ec::PrimeGroup;
+
+    if pk.len() != 32 || sig.len() != 64 {
+        return false;
+    }
+    let (r_x, s) = sig.split_at(32);
+
+    // lift_x(pk), i.e., the point with an even y coordinate
+    let p = Projective::deser_compressed(&[&[0x02u8][..], pk].concat());
+    let (Ok(p), Ok(s)) = (p, FrostSecp256k1Bip340::deserialize_scalar(s)) else {
+        return false;
+    };
+
+    let e = Fr::from_be_bytes_mod_order(&tagged_hash(b"BIP0340/challenge", &[r_x, pk, m].concat()));
+    let r = Projective::generator() * s - p * e;
+    !r.is_zero() && !FrostSecp256k1Bip340::requires_negation(&r) && x_only(&r) == r_x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ec::PrimeGroup;
+
+    #[test]
+    fn bip340_verification_vector() {
+        // Test vector 0 of BIP-340
+        let pk = hex::decode("f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9")
+            .unwrap();
+        let sig = hex::decode("e907831f80848d1069a5371b402410364bdf1c5f8307b0084c55f1ce2dca821525f66a4a85ea8b71e482a74f382d2ce5ebeee8fdb2172f477df4900d310536c0").unwrap();
+        let m = [0u8; 32];
+
+        assert!(verify_bip340(&pk, &m, &sig));
+        assert!(!verify_bip340(&pk, &[1u8; 32], &sig));
+    }
+
+    #[test]
+    fn serialization() {
+        let g = Projective::generator();
+        let enc = FrostSecp256k1Bip340::serialize_element(&g);
+        assert_eq!(
+            hex::encode(&enc),
+            "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"
+        );
+        assert_eq!(FrostSecp256k1Bip340::deserialize_element(&enc).unwrap(), g);
+        assert!(FrostSecp256k1Bip340::deserialize_element(&[0x00]).is_err());
+
+        let s = Fr::from(42u64);
+        let enc = FrostSecp256k1Bip340::serialize_scalar(&s);
+        assert_eq!(enc.len(), SCALAR_SIZE);
+        assert_eq!(FrostSecp256k1Bip340::deserialize_scalar(&enc).unwrap(), s);
+        assert!(FrostSecp256k1Bip340::deserialize_scalar(&[0xff; SCALAR_SIZE]).is_err());
+    }
+}
@@ -0,0 +1,201 @@
+//! Concrete implementation of [`DSignerScheme`].
+
+use crate::dsigner::policy::{PolicyViolation, SigningPolicy};
+use crate::dsigner::{
+    Application, ApplicationArgs, DSignerScheme, DSignerSchemeError, DSignerSchemeSigner,
+    FrostSignatureAlgorithm, SchemeDetails, SignatureAlgorithm, SignatureRequest,
+    VerificationParameters,
+};
+use crate::frost::{FrostSignatureRequest, SharedSignatureCache};
+use bytes::Bytes;
+use futures_util::FutureExt;
+use futures_util::future::BoxFuture;
+use itertools::Either;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+pub struct AsyncFrostSigner {
+    scheme_details: SchemeDetails,
+    alg: FrostSignatureAlgorithm,
+    signatures_cache: SharedSignatureCache,
+    new_sig_request: tokio::sync::mpsc::UnboundedSender<FrostSignatureRequest>,
+    supported_apps: HashSet<Application>,
+    policy: Option<Arc<SigningPolicy>>,
+}
+
+impl AsyncFrostSigner {
+    pub(super) fn new(
+        scheme_details: SchemeDetails,
+        alg: FrostSignatureAlgorithm,
+        signatures_cache: SharedSignatureCache,
+        new_sig_request: tokio::sync::mpsc::UnboundedSender<FrostSignatureRequest>,
+        supported_apps: HashSet<Application>,
+        policy: Option<Arc<SigningPolicy>>,
+    ) -> Self {
+        Self {
+            scheme_details,
+            alg,
+            signatures_cache,
+            new_sig_request,
+            supported_apps,
+            policy,
+        }
+    }
+
+    /// Make sure that both the algorithm and the application are supported.
+    fn check_supported(
+        &self,
+        alg: &SignatureAlgorithm,
+        args: &ApplicationArgs,
+    ) -> Result<FrostSignatureAlgorithm, AsyncFrostSignerError> {
+        let SignatureAlgorithm::Frost(alg) = *alg else {
+            Err(AsyncFrostSignerError::AlgorithmNotSupported)?
+        };
+        if alg != self.alg {
+            Err(AsyncFrostSignerError::AlgorithmNotSupported)?
+        }
+        if !self.supported_apps.contains(&args.app()) {
+            Err(AsyncFrostSignerError::ApplicationNotSupported)?
+        }
+
+        Ok(alg)
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum AsyncFrostSignerError {
+    #[error("the specified application is not supported by the signer")]
+    ApplicationNotSupported,
+
+    #[error("the specified algorithm is not supported by the signer")]
+    AlgorithmNotSupported,
+
+    #[error("the request was rejected by the signing policy")]
+    PolicyViolation(#[from] PolicyViolation),
+
+    #[error("the watch sender has been dropped")]
+    WatchSenderDropped,
+
+    #[error("the channel used to request signatures has been closed")]
+    CannotRequestNewSignatures,
+}
+
+impl From<AsyncFrostSignerError> for DSignerSchemeError {
+    fn from(error: AsyncFrostSignerError) -> Self {
+        match error {
+            AsyncFrostSignerError::ApplicationNotSupported => {
+                DSignerSchemeError::ApplicationNotSupported
+            }
+            AsyncFrostSignerError::AlgorithmNotSupported => {
+                DSignerSchemeError::AlgorithmNotSupported
+            }
+            AsyncFrostSignerError::PolicyViolation(e) => DSignerSchemeError::PolicyViolation(e),
+            _ => DSignerSchemeError::Other(error.into()),
+        }
+    }
+}
+
+impl DSignerScheme for AsyncFrostSigner {
+    fn details(&self) -> SchemeDetails {
+        self.scheme_details.clone()
+    }
+
+    fn verification_parameters(
+        &self,
+        alg: &SignatureAlgorithm,
+        args: &ApplicationArgs,
+    ) -> Result<VerificationParameters, DSignerSchemeError> {
+        self.check_supported(alg, args)?;
+
+        let public_key = self
+            .scheme_details
+            .scheme_algs
+            .iter()
+            .find(|scheme| scheme.algs.contains(alg))
+            .ok_or(AsyncFrostSignerError::AlgorithmNotSupported)?
+            .public_key
+            .clone();
+
+        // Schnorr signatures are computed on the raw message, without any dst
+        Ok(VerificationParameters {
+            public_key,
+            dst: Bytes::new(),
+        })
+    }
+}
+
+impl DSignerSchemeSigner for AsyncFrostSigner {
+    fn async_sign(
+        &self,
+        req: SignatureRequest,
+    ) -> BoxFuture<'_, Result<Bytes, DSignerSchemeError>> {
+        async move {
+            let alg = self.check_supported(&req.alg, &req.args)?;
+            if let Some(policy) = &self.policy {
+                policy.check(&req.m, &req.args)?;
+            }
+
+            let req = FrostSignatureRequest {
+                m: req.m,
+                args: req.args,
+                alg,
+            };
+
+            // Similarly to BLS, either the signature is cached, or we insert / subscribe to a
+            // watch channel, and notify the signer of the request.
+            let signature_or_receiver = {
+                let mut signatures_cache = self
+                    .signatures_cache
+                    .lock()
+                    .expect("a thread panicked with the mutex");
+
+                // This may drop the LRU entry from the map, which results in the
+                // future owning the corresponding receiver resolving in an error.
+                let signature_or_sender = signatures_cache.get_or_insert(req.clone(), || {
+                    let (tx, _) = tokio::sync::watch::channel(None);
+                    Either::Right(tx)
+                });
+
+                match signature_or_sender {
+                    Either::Left(signature) => {
+                        Result::<_, AsyncFrostSignerError>::Ok(Either::Left(signature.to_owned()))
+                    }
+
+                    Either::Right(tx) => {
+                        let rx = tx.subscribe();
+
+                        // Notify of the new message to sign
+                        self.new_sig_request
+                            .send(req)
+                            .map_err(|_| AsyncFrostSignerError::CannotRequestNewSignatures)?;
+
+                        Ok(Either::Right(rx))
+                    }
+                }
+            }?;
+
+            let sig = match signature_or_receiver {
+                Either::Left(signature) => signature,
+                Either::Right(mut rx) => {
+                    // A signature may already be in the channel, borrow it and mark it as seen
+                    let signature = rx.borrow_and_update().to_owned();
+
+                    if let Some(sig) = signature {
+                        sig
+                    } else {
+                        match rx.changed().await {
+                            Ok(()) => rx
+                                .borrow_and_update()
+                                .to_owned()
+                                .expect("watch channel updated but sig is None"),
+                            Err(_) => Err(AsyncFrostSignerError::WatchSenderDropped)?,
+                        }
+                    }
+                }
+            };
+
+            Ok(sig)
+        }
+        .boxed()
+    }
+}
@@ -0,0 +1,635 @@
+//! Handle internal signing requests and messages received from other nodes.
+
+use crate::frost::ciphersuite::{FrostCiphersuite, Scalar};
+use crate::frost::protocol::{SigningCommitment, SigningContext, SigningPackage};
+use crate::frost::{
+    CommitmentMessage, EncodedCommitment, FrostSignatureRequest, FrostThresholdSigner,
+    NetworkMessage, SignatureShareMessage, SigningAttempt, SigningPackageMessage, SigningSession,
+};
+use bytes::Bytes;
+use dcipher_network::{ReceivedMessage, TransportSender};
+use futures_util::{Stream, StreamExt};
+use itertools::Either;
+use lru::LruCache;
+use std::sync::Arc;
+use tokio::sync::mpsc::UnboundedReceiver;
+use tokio_util::sync::CancellationToken;
+use utils::display::LogBytes;
+
+impl<C> FrostThresholdSigner<C>
+where
+    C: FrostCiphersuite,
+{
+    pub(super) async fn sign_requests_loop<T>(
+        self: Arc<Self>,
+        mut rx_reqs: UnboundedReceiver<FrostSignatureRequest>,
+        tx_to_network: T,
+        cancellation_token: CancellationToken,
+    ) where
+        T: TransportSender<Identity = u16> + Clone + 'static,
+    {
+        let inner_fn = async {
+            loop {
+                let Some(req) = rx_reqs.recv().await else {
+                    tracing::warn!("Registry has dropped message sender, exiting recv loop");
+                    break;
+                };
+
+                if !self.is_supported(&req) {
+                    tracing::error!(app = ?req.args.app(), alg = ?req.alg, "Received a request to sign an unsupported request");
+                    continue;
+                }
+
+                if let Some(policy) = &self.policy
+                    && let Err(e) = policy.check(&req.m, &req.args)
+                {
+                    tracing::warn!(error = %e, app = ?req.args.app(), msg = %LogBytes(&req.m), "Refusing to sign request rejected by the policy");
+                    continue;
+                }
+
+                tracing::info!(msg = %LogBytes(&req.m), app = ?req.args.app(), alg = ?req.alg, "Received new message to sign");
+                self.start_attempt(&req, 0, &tx_to_network).await;
+
+                // Start new attempts in the background until a signature is obtained
+                tokio::task::spawn(self.clone().attempts_loop(
+                    req,
+                    tx_to_network.clone(),
+                    cancellation_token.child_token(),
+                ));
+
+                // Replace the nonces used by the request
+                self.nonce_pool
+                    .lock()
+                    .expect("a thread panicked with the mutex")
+                    .refill(&self.key);
+            }
+        };
+
+        tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                tracing::info!("Stopping sign requests loop due to cancellation token");
+            },
+
+            _ = inner_fn => (),
+        }
+    }
+
+    /// Start new signing attempts until a signature is obtained, or the maximum number of attempts
+    /// is reached.
+    async fn attempts_loop<T>(
+        self: Arc<Self>,
+        req: FrostSignatureRequest,
+        tx_to_network: T,
+        cancellation_token: CancellationToken,
+    ) where
+        T: TransportSender<Identity = u16>,
+    {
+        let inner_fn = async {
+            for attempt in 1..self.max_attempts {
+                tokio::time::sleep(self.attempt_timeout).await;
+                if self.is_signed(&req) {
+                    return;
+                }
+
+                tracing::warn!(msg = %LogBytes(&req.m), attempt, "Failed to obtain signature before timeout, starting new attempt");
+                self.start_attempt(&req, attempt, &tx_to_network).await;
+            }
+
+            tokio::time::sleep(self.attempt_timeout).await;
+            if !self.is_signed(&req) {
+                tracing::error!(msg = %LogBytes(&req.m), "Failed to obtain signature after all attempts");
+
+                // Drop the channel, if any, to notify pending requests of the failure
+                let mut signatures_cache = self
+                    .signatures_cache
+                    .lock()
+                    .expect("a thread panicked with the mutex");
+                if let Some(Either::Right(_)) = signatures_cache.peek(&req) {
+                    signatures_cache.pop(&req);
+                }
+            }
+        };
+
+        tokio::select! {
+            _ = cancellation_token.cancelled() => (),
+            _ = inner_fn => (),
+        }
+    }
+
+    /// Commit to a fresh pair of nonces for an attempt, and send the commitments to the network.
+    async fn start_attempt<T>(&self, req: &FrostSignatureRequest, attempt: u32, tx_to_network: &T)
+    where
+        T: TransportSender<Identity = u16>,
+    {
+        if self.is_signed(req) {
+            tracing::debug!(msg = %LogBytes(&req.m), "Received signing request, but message was already signed");
+            return;
+        }
+
+        let commitment = {
+            let mut sessions = self
+                .sessions
+                .lock()
+                .expect("a thread panicked with the mutex");
+            let session = sessions.get_or_insert_mut(req.clone(), Default::default);
+
+            // Attempt already started
+            if session.attempt.is_some_and(|a| a >= attempt) {
+                return;
+            }
+            session.attempt = Some(attempt);
+
+            let nonces = self
+                .nonce_pool
+                .lock()
+                .expect("a thread panicked with the mutex")
+                .take(&self.key);
+            let commitment = nonces.commitment;
+            session.attempts.entry(attempt).or_default().nonces = Some(nonces);
+            commitment
+        };
+
+        // Echo the message such that the coordinator receives its own commitment
+        let m = serde_cbor::to_vec(&NetworkMessage::Commitment(CommitmentMessage {
+            req: req.clone(),
+            attempt,
+            commitment: encode_commitment(&commitment),
+        }))
+        .expect("serialization should always work");
+        if let Err(e) = tx_to_network.broadcast_echo_self(m).await {
+            tracing::error!(error = ?e, "Failed to send commitment to the network");
+        }
+    }
+
+    pub(super) async fn network_recv_loop<T, E>(
+        self: Arc<Self>,
+        mut network_stream: impl Stream<Item = Result<ReceivedMessage<u16>, E>> + Unpin + Send,
+        tx_to_network: T,
+        cancellation_token: CancellationToken,
+    ) where
+        T: TransportSender<Identity = u16>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let inner_fn = async {
+            loop {
+                let ReceivedMessage {
+                    sender: sender_id,
+                    content,
+                    ..
+                } = match network_stream.next().await {
+                    Some(Ok(m)) => m,
+                    Some(Err(e)) => {
+                        tracing::error!(error = ?e, "Failed to receive message");
+                        continue; // receive next message
+                    }
+                    None => {
+                        tracing::warn!("Transport has dropped sender, exiting recv loop");
+                        break; // stop the loop
+                    }
+                };
+
+                let m: NetworkMessage = match serde_cbor::from_slice(&content) {
+                    Ok(m) => m,
+                    Err(e) => {
+                        tracing::error!(sender_id, error = ?e, "Failed to decode network message");
+                        continue;
+                    }
+                };
+
+                match m {
+                    NetworkMessage::Commitment(m) => {
+                        self.handle_commitment(m, sender_id, &tx_to_network).await
+                    }
+                    NetworkMessage::SigningPackage(m) => {
+                        self.handle_signing_package(m, sender_id, &tx_to_network)
+                            .await
+                    }
+                    NetworkMessage::SignatureShare(m) => self.handle_signature_share(m, sender_id),
+                }
+            }
+        };
+
+        tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                tracing::info!("Stopping recv loop due to cancellation token");
+            },
+
+            _ = inner_fn => (),
+        }
+    }
+
+    /// Store the commitment of a node if we coordinate the attempt, and send the signing package
+    /// once t commitments have been received.
+    async fn handle_commitment<T>(&self, m: CommitmentMessage, sender_id: u16, tx_to_network: &T)
+    where
+        T: TransportSender<Identity = u16>,
+    {
+        let CommitmentMessage {
+            req,
+            attempt,
+            commitment,
+        } = m;
+        if !self.is_valid_attempt(&req, attempt, sender_id)
+            || self.coordinator(&req, attempt) != self.id
+        {
+            return;
+        }
+
+        let Some(commitment) = self.decode_commitment(&commitment, Some(sender_id)) else {
+            tracing::warn!(sender_id, "Received invalid commitment");
+            return;
+        };
+
+        let signing_intent = self.has_signing_intent(&req);
+        let package = {
+            let mut sessions = self
+                .sessions
+                .lock()
+                .expect("a thread panicked with the mutex");
+            let Some(session) = peer_session(&mut sessions, &req, signing_intent) else {
+                tracing::debug!(
+                    sender_id,
+                    attempt,
+                    "Ignoring commitment for a request we were not asked to sign"
+                );
+                return;
+            };
+            let state = session.attempts.entry(attempt).or_default();
+            if state.package_sent {
+                return;
+            }
+
+            state.commitments.entry(sender_id).or_insert(commitment);
+            if state.commitments.len() < usize::from(self.t) {
+                return;
+            }
+
+            state.package_sent = true;
+            state.commitments.values().map(encode_commitment).collect()
+        };
+
+        tracing::info!(msg = %LogBytes(&req.m), attempt, "Sending signing package");
+        let m = serde_cbor::to_vec(&NetworkMessage::SigningPackage(SigningPackageMessage {
+            req,
+            attempt,
+            commitments: package,
+        }))
+        .expect("serialization should always work");
+        if let Err(e) = tx_to_network.broadcast_echo_self(m).await {
+            tracing::error!(error = ?e, "Failed to send signing package to the network");
+        }
+    }
+
+    /// Sign using the nonces committed to in the signing package, if any, and send the signature
+    /// share to the network.
+    async fn handle_signing_package<T>(
+        &self,
+        m: SigningPackageMessage,
+        sender_id: u16,
+        tx_to_network: &T,
+    ) where
+        T: TransportSender<Identity = u16>,
+    {
+        let SigningPackageMessage {
+            req,
+            attempt,
+            commitments,
+        } = m;
+        if !self.is_valid_attempt(&req, attempt, sender_id) {
+            return;
+        }
+
+        if self.coordinator(&req, attempt) != sender_id {
+            tracing::warn!(
+                sender_id,
+                attempt,
+                "Received signing package from a node that does not coordinate the attempt"
+            );
+            return;
+        }
+
+        let package = (commitments.len() == usize::from(self.t))
+            .then(|| {
+                let commitments: Option<Vec<_>> = commitments
+                    .iter()
+                    .map(|c| self.decode_commitment(c, None))
+                    .collect();
+                SigningPackage::new(commitments?)
+            })
+            .flatten();
+        let Some(package) = package else {
+            tracing::warn!(sender_id, attempt, "Received invalid signing package");
+            return;
+        };
+        let context = SigningContext::new(package, &self.key.group_pk, &req.m);
+
+        let signing_intent = self.has_signing_intent(&req);
+        let (share, signature) = {
+            let mut sessions = self
+                .sessions
+                .lock()
+                .expect("a thread panicked with the mutex");
+            let Some(session) = peer_session(&mut sessions, &req, signing_intent) else {
+                tracing::debug!(
+                    sender_id,
+                    attempt,
+                    "Ignoring signing package for a request we were not asked to sign"
+                );
+                return;
+            };
+            let state = session.attempts.entry(attempt).or_default();
+            if state.context.is_some() {
+                tracing::debug!(sender_id, attempt, "Ignoring duplicated signing package");
+                return;
+            }
+
+            // Sign iff we are part of the signers, consuming the nonces
+            let share = if context.package().get(&self.id).is_some() {
+                match state
+                    .nonces
+                    .take()
+                    .map(|nonces| context.sign(&self.key, nonces))
+                {
+                    Some(Ok(share)) => Some(share),
+                    Some(Err(e)) => {
+                        tracing::error!(error = ?e, attempt, "Failed to sign with signing package");
+                        None
+                    }
+                    None => {
+                        tracing::error!(
+                            attempt,
+                            "Signing package contains our commitment, but nonces are missing"
+                        );
+                        None
+                    }
+                }
+            } else {
+                None
+            };
+
+            // Verify shares received before the signing package
+            for (id, z) in std::mem::take(&mut state.pending_shares) {
+                self.verify_and_store_share(&context, state, id, z);
+            }
+            if let Some(z) = share {
+                state.shares.insert(self.id, z);
+            }
+            state.context = Some(context);
+
+            (share, self.try_aggregate(state))
+        };
+
+        if let Some(signature) = signature {
+            self.store_signature(req.clone(), signature);
+        }
+
+        if let Some(z) = share {
+            tracing::info!(msg = %LogBytes(&req.m), attempt, "Sending signature share");
+            let m = serde_cbor::to_vec(&NetworkMessage::SignatureShare(SignatureShareMessage {
+                req,
+                attempt,
+                share: C::serialize_scalar(&z).into(),
+            }))
+            .expect("serialization should always work");
+            if let Err(e) = tx_to_network.broadcast(m).await {
+                tracing::error!(error = ?e, "Failed to send signature share to the network");
+            }
+        }
+    }
+
+    /// Verify and store a signature share, and aggregate the shares if possible.
+    fn handle_signature_share(&self, m: SignatureShareMessage, sender_id: u16) {
+        let SignatureShareMessage {
+            req,
+            attempt,
+            share,
+        } = m;
+        if !self.is_valid_attempt(&req, attempt, sender_id) {
+            return;
+        }
+
+        let Ok(z) = C::deserialize_scalar(&share) else {
+            tracing::warn!(sender_id, "Received invalid signature share encoding");
+            return;
+        };
+
+        let signing_intent = self.has_signing_intent(&req);
+        let signature = {
+            let mut sessions = self
+                .sessions
+                .lock()
+                .expect("a thread panicked with the mutex");
+            let Some(session) = peer_session(&mut sessions, &req, signing_intent) else {
+                tracing::debug!(
+                    sender_id,
+                    attempt,
+                    "Ignoring signature share for a request we were not asked to sign"
+                );
+                return;
+            };
+            let state = session.attempts.entry(attempt).or_default();
+
+            match state.context.take() {
+                Some(context) => {
+                    self.verify_and_store_share(&context, state, sender_id, z);
+                    state.context = Some(context);
+                }
+                None => {
+                    // Signing package not yet received, verify later
+                    state.pending_shares.insert(sender_id, z);
+                }
+            }
+
+            self.try_aggregate(state)
+        };
+
+        if let Some(signature) = signature {
+            self.store_signature(req, signature);
+        }
+    }
+
+    fn verify_and_store_share(
+        &self,
+        context: &SigningContext<C>,
+        state: &mut SigningAttempt<C>,
+        id: u16,
+        z: Scalar<C>,
+    ) {
+        let Some(pk) = self.key.public_shares.get(&id) else {
+            tracing::warn!(sender_id = id, "Missing public key share for party");
+            return;
+        };
+
+        if context.verify_share(id, pk, &z) {
+            state.shares.insert(id, z);
+        } else {
+            tracing::error!(sender_id = id, "Received invalid signature share");
+        }
+    }
+
+    /// Aggregate the signature shares of an attempt once all the signers have sent theirs.
+    fn try_aggregate(&self, state: &SigningAttempt<C>) -> Option<Bytes> {
+        let context = state.context.as_ref()?;
+        if state.shares.len() != usize::from(self.t) {
+            None?
+        }
+
+        // Should always be valid since every share has been verified
+        if !context.verify_aggregate(&self.key.group_pk, state.shares.values()) {
+            tracing::error!("Aggregated signature is invalid");
+            None?
+        }
+
+        Some(context.aggregate(state.shares.values()).into())
+    }
+
+    /// Store a signature in the cache and notify the pending requests.
+    fn store_signature(&self, req: FrostSignatureRequest, signature: Bytes) {
+        tracing::info!(msg = %LogBytes(&req.m), "Obtained threshold signature on message");
+
+        // The session, and remaining nonces, are no longer required
+        self.sessions
+            .lock()
+            .expect("a thread panicked with the mutex")
+            .pop(&req);
+
+        let mut signatures_cache = self
+            .signatures_cache
+            .lock()
+            .expect("a thread panicked with the mutex");
+        if let Some(Either::Right(tx_channel)) =
+            signatures_cache.put(req, Either::Left(signature.clone()))
+        {
+            // If there previously was a channel stored at the entry, also send signature through it
+            tx_channel.send_replace(Some(signature));
+        }
+    }
+
+    /// Whether a signature has already been obtained for that request.
+    fn is_signed(&self, req: &FrostSignatureRequest) -> bool {
+        let signatures_cache = self
+            .signatures_cache
+            .lock()
+            .expect("a thread panicked with the mutex");
+        matches!(signatures_cache.peek(req), Some(Either::Left(_)))
+    }
+
+    /// Whether a local client is waiting for a signature on that request.
+    fn has_signing_intent(&self, req: &FrostSignatureRequest) -> bool {
+        let signatures_cache = self
+            .signatures_cache
+            .lock()
+            .expect("a thread panicked with the mutex");
+        matches!(signatures_cache.peek(req), Some(Either::Right(_)))
+    }
+
+    /// Filter messages for unsupported requests, invalid attempts, or requests already signed.
+    fn is_valid_attempt(&self, req: &FrostSignatureRequest, attempt: u32, sender_id: u16) -> bool {
+        if !self.is_supported(req) {
+            tracing::warn!(sender_id, app = ?req.args.app(), alg = ?req.alg, "Received message for unsupported request");
+            return false;
+        }
+
+        if attempt >= self.max_attempts {
+            tracing::warn!(sender_id, attempt, "Received message with invalid attempt");
+            return false;
+        }
+
+        !self.is_signed(req)
+    }
+
+    /// Decode a commitment, making sure that the identifier matches the expected one if provided.
+    fn decode_commitment(
+        &self,
+        c: &EncodedCommitment,
+        expected_id: Option<u16>,
+    ) -> Option<SigningCommitment<C>> {
+        if expected_id.is_some_and(|id| id != c.id) || !self.key.public_shares.contains_key(&c.id) {
+            None?
+        }
+
+        Some(SigningCommitment {
+            id: c.id,
+            hiding: C::deserialize_element(&c.hiding).ok()?,
+            binding: C::deserialize_element(&c.binding).ok()?,
+        })
+    }
+}
+
+/// Get the session of a request in order to handle a peer message.
+/// Sessions are only created for requests that a local client asked to sign, such that peers cannot
+/// evict in-progress sessions by flooding the cache with arbitrary requests.
+fn peer_session<'a, C: FrostCiphersuite>(
+    sessions: &'a mut LruCache<FrostSignatureRequest, SigningSession<C>>,
+    req: &FrostSignatureRequest,
+    signing_intent: bool,
+) -> Option<&'a mut SigningSession<C>> {
+    if signing_intent {
+        Some(sessions.get_or_insert_mut(req.clone(), Default::default))
+    } else {
+        sessions.get_mut(req)
+    }
+}
+
+fn encode_commitment<C: FrostCiphersuite>(c: &SigningCommitment<C>) -> EncodedCommitment {
+    EncodedCommitment {
+        id: c.id,
+        hiding: C::serialize_element(&c.hiding).into(),
+        binding: C::serialize_element(&c.binding).into(),
+    }
+}
+
+#[cfg(all(test, feature = "secp256k1"))]
+mod tests {
+    use super::*;
+    use crate::dsigner::{ApplicationAnyArgs, ApplicationArgs, FrostSignatureAlgorithm};
+    use crate::frost::FrostSecp256k1Bip340;
+    use crate::frost::ciphersuite::Scalar;
+    use crate::frost::protocol::tests::trusted_dealer;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn peer_messages_do_not_create_sessions() {
+        let (sks, pks) =
+            trusted_dealer::<FrostSecp256k1Bip340>(3, 2, &mut StdRng::seed_from_u64(0));
+        let signer = FrostThresholdSigner::<FrostSecp256k1Bip340>::new(
+            3,
+            2,
+            1,
+            sks[0],
+            pks.into_iter().collect(),
+        )
+        .unwrap();
+
+        let req = FrostSignatureRequest {
+            m: Bytes::from_static(b"m"),
+            args: ApplicationArgs::Any(ApplicationAnyArgs {
+                dst_suffix: "TEST".to_owned(),
+            }),
+            alg: FrostSignatureAlgorithm::Secp256k1Bip340,
+        };
+        let m = SignatureShareMessage {
+            req: req.clone(),
+            attempt: 0,
+            share: FrostSecp256k1Bip340::serialize_scalar(&Scalar::<FrostSecp256k1Bip340>::from(
+                1u64,
+            ))
+            .into(),
+        };
+
+        // Messages for requests that were not asked locally are ignored
+        signer.handle_signature_share(m.clone(), 2);
+        assert!(signer.sessions.lock().unwrap().is_empty());
+
+        // Messages are stored once a local client waits for the signature
+        let (tx, _rx) = tokio::sync::watch::channel(None);
+        signer
+            .signatures_cache
+            .lock()
+            .unwrap()
+            .put(req.clone(), Either::Right(tx));
+        signer.handle_signature_share(m, 2);
+        assert!(signer.sessions.lock().unwrap().contains(&req));
+    }
+}
@@ -0,0 +1,77 @@
+//! Pool of nonces preprocessed ahead of signing requests.
+
+use crate::frost::ciphersuite::FrostCiphersuite;
+use crate::frost::protocol::{KeyPackage, SigningNonces};
+use std::collections::VecDeque;
+
+/// A pool of nonces generated ahead of time, such that the first round of FROST does not require
+/// any scalar multiplication upon receiving a signing request. Nonces are removed from the pool
+/// when taken, and can therefore only be used once.
+pub(super) struct NoncePool<C: FrostCiphersuite> {
+    nonces: VecDeque<SigningNonces<C>>,
+    capacity: usize,
+}
+
+impl<C: FrostCiphersuite> NoncePool<C> {
+    /// Create an empty pool holding up to `capacity` nonces.
+    pub(super) fn new(capacity: usize) -> Self {
+        Self {
+            nonces: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Take a pair of nonces from the pool, or generate fresh nonces if the pool is empty.
+    pub(super) fn take(&mut self, key: &KeyPackage<C>) -> SigningNonces<C> {
+        self.nonces.pop_front().unwrap_or_else(|| {
+            tracing::debug!("Nonce pool is empty, generating fresh nonces");
+            SigningNonces::generate(key, &mut rand::rngs::OsRng)
+        })
+    }
+
+    /// Fill the pool up to its capacity.
+    pub(super) fn refill(&mut self, key: &KeyPackage<C>) {
+        let missing = self.capacity.saturating_sub(self.nonces.len());
+        self.nonces.extend(
+            std::iter::repeat_with(|| SigningNonces::generate(key, &mut rand::rngs::OsRng))
+                .take(missing),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frost::protocol::tests::trusted_dealer;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn nonce_pool() {
+        #[cfg(feature = "secp256k1")]
+        type C = crate::frost::ciphersuite::FrostSecp256k1Bip340;
+        #[cfg(not(feature = "secp256k1"))]
+        type C = crate::frost::ciphersuite::FrostEd25519Sha512;
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let (sks, pks) = trusted_dealer::<C>(3, 2, &mut rng);
+        let key = KeyPackage::<C>::new(1, 2, sks[0], pks).unwrap();
+
+        let mut pool = NoncePool::<C>::new(4);
+        assert_eq!(pool.nonces.len(), 0);
+
+        // Empty pool still outputs nonces
+        let first = pool.take(&key);
+
+        pool.refill(&key);
+        assert_eq!(pool.nonces.len(), 4);
+
+        let taken: Vec<_> = (0..5).map(|_| pool.take(&key).commitment).collect();
+        assert_eq!(pool.nonces.len(), 0);
+        for (i, c) in taken.iter().enumerate() {
+            assert_eq!(c.id, 1);
+            assert!(first.commitment != *c);
+            assert!(taken[i + 1..].iter().all(|other| other != c));
+        }
+    }
+}
@@ -0,0 +1,465 @@
+//! Core operations of FROST, as specified in RFC 9591, § 4 and § 5.
+
+use crate::frost::FrostThresholdSignerError;
+use crate::frost::ciphersuite::{FrostCiphersuite, Scalar};
+use ark_ec::PrimeGroup;
+use ark_ff::{Field, One};
+use rand::RngCore;
+use std::collections::BTreeMap;
+
+/// Commitments to a pair of hiding and binding nonces, i.e., (D, E) = (d * G, e * G).
+pub(super) struct SigningCommitment<C: FrostCiphersuite> {
+    pub(super) id: u16,
+    pub(super) hiding: C::Group,
+    pub(super) binding: C::Group,
+}
+
+impl<C: FrostCiphersuite> Clone for SigningCommitment<C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<C: FrostCiphersuite> Copy for SigningCommitment<C> {}
+
+impl<C: FrostCiphersuite> PartialEq for SigningCommitment<C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id && self.hiding == other.hiding && self.binding == other.binding
+    }
+}
+
+/// Secret nonces of a signer. Nonces must never be used for more than a single signature share,
+/// hence the lack of [`Clone`].
+pub(super) struct SigningNonces<C: FrostCiphersuite> {
+    hiding: Scalar<C>,
+    binding: Scalar<C>,
+    pub(super) commitment: SigningCommitment<C>,
+}
+
+impl<C: FrostCiphersuite> SigningNonces<C> {
+    /// Generate a pair of nonces and the associated commitments, see RFC 9591, § 5.1.
+    pub(super) fn generate(key: &KeyPackage<C>, rng: &mut impl RngCore) -> Self {
+        let hiding = nonce_generate::<C>(&key.secret_share, rng);
+        let binding = nonce_generate::<C>(&key.secret_share, rng);
+        let g = C::Group::generator();
+
+        Self {
+            hiding,
+            binding,
+            commitment: SigningCommitment {
+                id: key.id,
+                hiding: g * hiding,
+                binding: g * binding,
+            },
+        }
+    }
+}
+
+/// nonce_generate of RFC 9591, § 4.1.
+fn nonce_generate<C: FrostCiphersuite>(secret: &Scalar<C>, rng: &mut impl RngCore) -> Scalar<C> {
+    let mut random_bytes = [0u8; 32];
+    rng.fill_bytes(&mut random_bytes);
+    C::h3(&[random_bytes.as_slice(), &C::serialize_scalar(secret)].concat())
+}
+
+/// Key material of a signer. The keys are normalized such that the group public key satisfies the
+/// requirements of the ciphersuite, e.g., an even y coordinate for BIP-340.
+pub(super) struct KeyPackage<C: FrostCiphersuite> {
+    pub(super) id: u16,
+    secret_share: Scalar<C>,
+    pub(super) public_shares: BTreeMap<u16, C::Group>,
+    pub(super) group_pk: C::Group,
+}
+
+impl<C: FrostCiphersuite> KeyPackage<C> {
+    /// Create the key package of a signer from its secret share, and the public shares of all the
+    /// signers.
+    pub(super) fn new(
+        id: u16,
+        t: u16,
+        secret_share: Scalar<C>,
+        mut public_shares: BTreeMap<u16, C::Group>,
+    ) -> Result<Self, FrostThresholdSignerError> {
+        let own_pk = public_shares
+            .get(&id)
+            .ok_or(FrostThresholdSignerError::MissingPublicKey(id))?;
+        if C::Group::generator() * secret_share != *own_pk {
+            Err(FrostThresholdSignerError::InvalidSecretShare)?
+        }
+
+        if t == 0 || public_shares.len() < usize::from(t) {
+            Err(FrostThresholdSignerError::NotEnoughPublicKeys)?
+        }
+
+        // Interpolate the polynomial with the first t public shares, and make sure that the
+        // remaining shares lie on the same polynomial.
+        let (points, others): (Vec<_>, Vec<_>) = public_shares
+            .iter()
+            .map(|(&j, &pk_j)| (j, pk_j))
+            .enumerate()
+            .partition(|(i, _)| *i < usize::from(t));
+        let points: Vec<_> = points.into_iter().map(|(_, p)| p).collect();
+        for (_, (j, pk_j)) in others {
+            if interpolate_at::<C>(&points, j) != pk_j {
+                Err(FrostThresholdSignerError::InconsistentPublicKeys(j))?
+            }
+        }
+
+        let mut group_pk = interpolate_at::<C>(&points, 0);
+        let mut secret_share = secret_share;
+        if C::requires_negation(&group_pk) {
+            group_pk = -group_pk;
+            secret_share = -secret_share;
+            public_shares.values_mut().for_each(|pk| *pk = -*pk);
+        }
+
+        Ok(Self {
+            id,
+            secret_share,
+            public_shares,
+            group_pk,
+        })
+    }
+}
+
+/// Lagrange coefficient of `id` at `x`, over the set of `ids`.
+fn lagrange_coefficient<C: FrostCiphersuite>(ids: &[u16], id: u16, x: u16) -> Scalar<C> {
+    let x = Scalar::<C>::from(x);
+    let x_i = Scalar::<C>::from(id);
+    let (num, den) = ids
+        .iter()
+        .filter(|&&j| j != id)
+        .map(|&j| Scalar::<C>::from(j))
+        .fold(
+            (Scalar::<C>::one(), Scalar::<C>::one()),
+            |(num, den), x_j| (num * (x - x_j), den * (x_i - x_j)),
+        );
+
+    num * den.inverse().expect("identifiers should be distinct")
+}
+
+/// derive_interpolating_value of RFC 9591, § 4.2, i.e., the Lagrange coefficient at 0.
+fn derive_interpolating_value<C: FrostCiphersuite>(ids: &[u16], id: u16) -> Scalar<C> {
+    lagrange_coefficient::<C>(ids, id, 0)
+}
+
+/// Evaluate the polynomial interpolated in the exponent by `points` at `x`.
+fn interpolate_at<C: FrostCiphersuite>(points: &[(u16, C::Group)], x: u16) -> C::Group {
+    let ids: Vec<_> = points.iter().map(|(id, _)| *id).collect();
+    points
+        .iter()
+        .map(|(id, p)| *p * lagrange_coefficient::<C>(&ids, *id, x))
+        .sum()
+}
+
+/// Commitments of the signers taking part in a signature, sorted by identifier.
+pub(super) struct SigningPackage<C: FrostCiphersuite> {
+    commitments: BTreeMap<u16, SigningCommitment<C>>,
+}
+
+impl<C: FrostCiphersuite> SigningPackage<C> {
+    /// Create a signing package, returns None if multiple commitments are provided for a signer.
+    pub(super) fn new(commitments: impl IntoIterator<Item = SigningCommitment<C>>) -> Option<Self> {
+        let mut map = BTreeMap::new();
+        for c in commitments {
+            if map.insert(c.id, c).is_some() {
+                None?
+            }
+        }
+
+        Some(Self { commitments: map })
+    }
+
+    /// Identifiers of the signers, in increasing order.
+    pub(super) fn signers(&self) -> impl Iterator<Item = u16> + '_ {
+        self.commitments.keys().copied()
+    }
+
+    pub(super) fn commitments(&self) -> impl Iterator<Item = &SigningCommitment<C>> {
+        self.commitments.values()
+    }
+
+    pub(super) fn get(&self, id: &u16) -> Option<&SigningCommitment<C>> {
+        self.commitments.get(id)
+    }
+
+    /// encode_group_commitment_list of RFC 9591, § 4.3.
+    fn encode_group_commitment_list(&self) -> Vec<u8> {
+        self.commitments
+            .values()
+            .flat_map(|c| {
+                [
+                    C::serialize_scalar(&Scalar::<C>::from(c.id)),
+                    C::serialize_element(&c.hiding),
+                    C::serialize_element(&c.binding),
+                ]
+            })
+            .flatten()
+            .collect()
+    }
+}
+
+/// Values derived from a signing package, a message, and the group public key, that are required
+/// to compute, verify and aggregate signature shares.
+pub(super) struct SigningContext<C: FrostCiphersuite> {
+    package: SigningPackage<C>,
+    ids: Vec<u16>,
+    binding_factors: BTreeMap<u16, Scalar<C>>,
+    group_commitment: C::Group,
+    negate_nonces: bool,
+    challenge: Scalar<C>,
+}
+
+impl<C: FrostCiphersuite> SigningContext<C> {
+    pub(super) fn new(package: SigningPackage<C>, group_pk: &C::Group, m: &[u8]) -> Self {
+        let ids: Vec<_> = package.signers().collect();
+
+        // compute_binding_factors, RFC 9591, § 4.4
+        let rho_input_prefix = [
+            C::serialize_element(group_pk),
+            C::h4(m),
+            C::h5(&package.encode_group_commitment_list()),
+        ]
+        .concat();
+        let binding_factors: BTreeMap<_, _> = ids
+            .iter()
+            .map(|&id| {
+                let rho_input = [
+                    rho_input_prefix.as_slice(),
+                    &C::serialize_scalar(&Scalar::<C>::from(id)),
+                ]
+                .concat();
+                (id, C::h1(&rho_input))
+            })
+            .collect();
+
+        // compute_group_commitment, RFC 9591, § 4.5
+        let group_commitment: C::Group = package
+            .commitments()
+            .map(|c| c.hiding + c.binding * binding_factors[&c.id])
+            .sum();
+
+        // Negate the nonces of all signers if required by the ciphersuite
+        let negate_nonces = C::requires_negation(&group_commitment);
+        let group_commitment = if negate_nonces {
+            -group_commitment
+        } else {
+            group_commitment
+        };
+
+        let challenge = C::challenge(&group_commitment, group_pk, m);
+        Self {
+            package,
+            ids,
+            binding_factors,
+            group_commitment,
+            negate_nonces,
+            challenge,
+        }
+    }
+
+    pub(super) fn package(&self) -> &SigningPackage<C> {
+        &self.package
+    }
+
+    /// Compute a signature share, consuming the nonces of the signer, see RFC 9591, § 5.2.
+    pub(super) fn sign(
+        &self,
+        key: &KeyPackage<C>,
+        nonces: SigningNonces<C>,
+    ) -> Result<Scalar<C>, FrostThresholdSignerError> {
+        if self.package.get(&key.id) != Some(&nonces.commitment) {
+            Err(FrostThresholdSignerError::CommitmentMismatch)?
+        }
+
+        let lambda = derive_interpolating_value::<C>(&self.ids, key.id);
+        let nonce = nonces.hiding + nonces.binding * self.binding_factors[&key.id];
+        let nonce = if self.negate_nonces { -nonce } else { nonce };
+        Ok(nonce + lambda * key.secret_share * self.challenge)
+    }
+
+    /// Verify the signature share of signer `id` with public share `pk`, see RFC 9591, § 5.4.
+    pub(super) fn verify_share(&self, id: u16, pk: &C::Group, z: &Scalar<C>) -> bool {
+        let Some(commitment) = self.package.get(&id) else {
+            return false;
+        };
+
+        let r = commitment.hiding + commitment.binding * self.binding_factors[&id];
+        let r = if self.negate_nonces { -r } else { r };
+        let lambda = derive_interpolating_value::<C>(&self.ids, id);
+        C::Group::generator() * z == r + *pk * (self.challenge * lambda)
+    }
+
+    /// Aggregate the signature shares into an encoded signature, see RFC 9591, § 5.3.
+    pub(super) fn aggregate<'a>(&self, shares: impl IntoIterator<Item = &'a Scalar<C>>) -> Vec<u8> {
+        let z: Scalar<C> = shares.into_iter().sum();
+        C::serialize_signature(&self.group_commitment, &z)
+    }
+
+    /// Verify an aggregated signature (R, z) on the group public key before encoding it.
+    pub(super) fn verify_aggregate<'a>(
+        &self,
+        group_pk: &C::Group,
+        shares: impl IntoIterator<Item = &'a Scalar<C>>,
+    ) -> bool {
+        let z: Scalar<C> = shares.into_iter().sum();
+        C::Group::generator() * z == self.group_commitment + *group_pk * self.challenge
+    }
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+    use ark_std::UniformRand;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    /// Generate the key packages of n signers with a threshold t using a trusted dealer.
+    pub(crate) fn trusted_dealer<C: FrostCiphersuite>(
+        n: u16,
+        t: u16,
+        rng: &mut StdRng,
+    ) -> (Vec<Scalar<C>>, BTreeMap<u16, C::Group>) {
+        let poly: Vec<_> = (0..t).map(|_| Scalar::<C>::rand(rng)).collect();
+        let secret_shares: Vec<_> = (1..=n)
+            .map(|i| {
+                let x = Scalar::<C>::from(i);
+                poly.iter()
+                    .rev()
+                    .fold(Scalar::<C>::from(0u64), |acc, a| acc * x + a)
+            })
+            .collect();
+        let public_shares = secret_shares
+            .iter()
+            .enumerate()
+            .map(|(i, sk)| (i as u16 + 1, C::Group::generator() * sk))
+            .collect();
+
+        (secret_shares, public_shares)
+    }
+
+    /// Sign a message with the signers 1..=t, and output the group public key and the signature.
+    fn sign_with_dealer<C: FrostCiphersuite>(rng: &mut StdRng, m: &[u8]) -> (Vec<u8>, Vec<u8>) {
+        let (n, t) = (5, 3);
+        let (sks, pks) = trusted_dealer::<C>(n, t, rng);
+        let keys: Vec<_> = (1..=n)
+            .zip(sks)
+            .map(|(id, sk)| KeyPackage::<C>::new(id, t, sk, pks.clone()).unwrap())
+            .collect();
+
+        // Take the last t signers
+        let signers = &keys[usize::from(n - t)..];
+        let nonces: Vec<_> = signers
+            .iter()
+            .map(|key| SigningNonces::generate(key, rng))
+            .collect();
+        let package = SigningPackage::new(nonces.iter().map(|n| n.commitment)).unwrap();
+        let ctx = SigningContext::new(package, &keys[0].group_pk, m);
+
+        let shares: Vec<_> = signers
+            .iter()
+            .zip(nonces)
+            .map(|(key, nonces)| ctx.sign(key, nonces).unwrap())
+            .collect();
+
+        for (key, z) in signers.iter().zip(shares.iter()) {
+            assert!(ctx.verify_share(key.id, &key.public_shares[&key.id], z));
+            assert!(!ctx.verify_share(
+                key.id,
+                &key.public_shares[&key.id],
+                &(*z + Scalar::<C>::one())
+            ));
+        }
+        assert!(ctx.verify_aggregate(&keys[0].group_pk, &shares));
+
+        (
+            C::serialize_public_key(&keys[0].group_pk),
+            ctx.aggregate(&shares),
+        )
+    }
+
+    #[test]
+    fn key_package_errors() {
+        #[cfg(feature = "secp256k1")]
+        type C = crate::frost::ciphersuite::FrostSecp256k1Bip340;
+        #[cfg(not(feature = "secp256k1"))]
+        type C = crate::frost::ciphersuite::FrostEd25519Sha512;
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let (sks, mut pks) = trusted_dealer::<C>(4, 2, &mut rng);
+
+        assert!(matches!(
+            KeyPackage::<C>::new(1, 2, sks[1], pks.clone()),
+            Err(FrostThresholdSignerError::InvalidSecretShare)
+        ));
+        assert!(matches!(
+            KeyPackage::<C>::new(1, 5, sks[0], pks.clone()),
+            Err(FrostThresholdSignerError::NotEnoughPublicKeys)
+        ));
+
+        *pks.get_mut(&4).unwrap() += <C as FrostCiphersuite>::Group::generator();
+        assert!(matches!(
+            KeyPackage::<C>::new(1, 2, sks[0], pks),
+            Err(FrostThresholdSignerError::InconsistentPublicKeys(4))
+        ));
+    }
+
+    #[test]
+    fn nonces_reuse_with_other_commitments_fails() {
+        #[cfg(feature = "secp256k1")]
+        type C = crate::frost::ciphersuite::FrostSecp256k1Bip340;
+        #[cfg(not(feature = "secp256k1"))]
+        type C = crate::frost::ciphersuite::FrostEd25519Sha512;
+
+        let mut rng = StdRng::seed_from_u64(1);
+        let (sks, pks) = trusted_dealer::<C>(2, 2, &mut rng);
+        let key1 = KeyPackage::<C>::new(1, 2, sks[0], pks.clone()).unwrap();
+        let key2 = KeyPackage::<C>::new(2, 2, sks[1], pks).unwrap();
+
+        let nonces1 = SigningNonces::generate(&key1, &mut rng);
+        let nonces2 = SigningNonces::generate(&key2, &mut rng);
+        let package = SigningPackage::new([nonces2.commitment]).unwrap();
+        let ctx = SigningContext::new(package, &key1.group_pk, b"m");
+        assert!(matches!(
+            ctx.sign(&key1, nonces1),
+            Err(FrostThresholdSignerError::CommitmentMismatch)
+        ));
+
+        assert!(SigningPackage::new([nonces2.commitment, nonces2.commitment]).is_none());
+    }
+
+    #[cfg(feature = "secp256k1")]
+    #[test]
+    fn frost_secp256k1_bip340() {
+        use crate::frost::ciphersuite::FrostSecp256k1Bip340;
+        use crate::frost::ciphersuite::verify_bip340;
+
+        // Multiple iterations to cover both parities of the public key and group commitment
+        let mut rng = StdRng::seed_from_u64(42);
+        for i in 0..16u8 {
+            let m = [i; 32];
+            let (pk, sig) = sign_with_dealer::<FrostSecp256k1Bip340>(&mut rng, &m);
+            assert_eq!(pk.len(), 32);
+            assert_eq!(sig.len(), 64);
+            assert!(verify_bip340(&pk, &m, &sig));
+            assert!(!verify_bip340(&pk, &[i + 1; 32], &sig));
+        }
+    }
+
+    #[cfg(feature = "ed25519")]
+    #[test]
+    fn frost_ed25519() {
+        use crate::frost::ciphersuite::FrostEd25519Sha512;
+        use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+
+        let mut rng = StdRng::seed_from_u64(42);
+        for i in 0..4u8 {
+            let m = [i; 32];
+            let (pk, sig) = sign_with_dealer::<FrostEd25519Sha512>(&mut rng, &m);
+            let pk = VerifyingKey::from_bytes(&pk.try_into().unwrap()).unwrap();
+            let sig = Signature::from_bytes(&sig.try_into().unwrap());
+            assert!(pk.verify(&m, &sig).is_ok());
+            assert!(pk.verify(&[i + 1; 32], &sig).is_err());
+        }
+    }
+}
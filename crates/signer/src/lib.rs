@@ -9,6 +9,9 @@
 pub mod bls;
 #[cfg(feature = "dsigner")]
 pub mod dsigner;
+/// frost requires at least a ciphersuite
+#[cfg(all(feature = "frost", any(feature = "secp256k1", feature = "ed25519")))]
+pub mod frost;
 
 /// An asynchronous signer is used to generate a signature asynchronously.
 pub trait AsynchronousSigner<M> {
@@ -34,6 +34,7 @@ pub enum CurveId {
     Bn254G2,
     Bls12_381G1,
     Bls12_381G2,
+    Secp256k1,
     Custom(Cow<'static, [u8]>, MapId),
 }
 
@@ -41,7 +42,7 @@ impl CurveId {
     pub fn default_mapping(&self) -> MapId {
         match self {
             CurveId::Bn254G1 | CurveId::Bn254G2 => MapId::SVDW,
-            CurveId::Bls12_381G1 | CurveId::Bls12_381G2 => MapId::SSWU,
+            CurveId::Bls12_381G1 | CurveId::Bls12_381G2 | CurveId::Secp256k1 => MapId::SSWU,
             CurveId::Custom(_, map_id) => *map_id,
         }
     }
@@ -66,6 +67,10 @@ impl From<CurveId> for Cow<'static, [u8]> {
                 const CURVE_ID: Cow<'static, [u8]> = Cow::Borrowed(b"BLS12381G2");
                 CURVE_ID
             }
+            CurveId::Secp256k1 => {
+                const CURVE_ID: Cow<'static, [u8]> = Cow::Borrowed(b"secp256k1");
+                CURVE_ID
+            }
             CurveId::Custom(name, _) => name,
         }
     }
@@ -285,6 +290,15 @@ mod bls12_381_named {
     }
 }
 
+#[cfg(feature = "secp256k1")]
+mod secp256k1_named {
+    use super::{CurveId, NamedCurveGroup};
+
+    impl NamedCurveGroup for ark_ec::short_weierstrass::Projective<ark_secp256k1::Config> {
+        const CURVE_ID: CurveId = CurveId::Secp256k1;
+    }
+}
+
 #[cfg(feature = "sha3")]
 mod sha3_named {
     use super::{HashId, NamedDynDigest};
@@ -364,4 +378,24 @@ mod tests {
             );
         }
     }
+
+    #[cfg(feature = "secp256k1")]
+    mod secp256k1 {
+        use super::super::*;
+        use crate::hash_to_curve::HashToCurve;
+
+        #[test]
+        fn secp256k1_ciphersuite() {
+            let secp256k1_ciphersuite_ro = Rfc9380DstBuilder::empty()
+                .with_curve::<ark_secp256k1::Projective>()
+                .with_hash::<sha2::Sha256>()
+                .with_mapping(CurveId::Secp256k1.default_mapping())
+                .with_encoding(EncodingType::Uniform)
+                .build();
+            assert_eq!(
+                secp256k1_ciphersuite_ro.0.as_slice(),
+                ark_secp256k1::Projective::CIPHERSUITE.as_bytes()
+            );
+        }
+    }
 }
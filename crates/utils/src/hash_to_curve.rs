@@ -1,8 +1,36 @@
 use ark_ec::{pairing::Pairing, CurveGroup};
 use digest::{core_api::BlockSizeUser, FixedOutputReset};
 
+/// Macro used to generate a [`CustomHashToCurve`] and [`HashToCurve`] implementation for a given
+/// [`SWCurveConfig`], a default hash function, and a hash to curve function.
+macro_rules! gen_hash_to_curve {
+    ($cg_config:ty, $f:ident, $h:ty, $d:literal) => {
+        impl CustomHashToCurve for ark_ec::short_weierstrass::Projective<$cg_config> {
+            fn hash_to_curve_custom<H: FixedOutputReset + BlockSizeUser + Default + Clone>(
+                message: &[u8],
+                dst: &[u8],
+            ) -> Self {
+                $f::<H>(message, dst)
+            }
+        }
+
+        impl HashToCurve for ark_ec::short_weierstrass::Projective<$cg_config> {
+            const CIPHERSUITE: &'static str = $d;
+            type DefaultInnerHash = $h;
+
+            fn hash_to_curve(message: &[u8], dst: &[u8]) -> Self {
+                $f::<Self::DefaultInnerHash>(message, dst)
+            }
+        }
+    };
+}
+
 #[cfg(any(feature = "bn254", feature = "bls12-381"))]
 mod bn254_bls12_381;
+#[cfg(feature = "secp256k1")]
+mod secp256k1;
+#[cfg(feature = "svdw")]
+mod svdw;
 
 /// Custom trait to provide hash to curve implementations for ark's CurveGroups
 pub trait CustomHashToCurve: CurveGroup {
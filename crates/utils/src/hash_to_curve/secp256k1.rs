@@ -0,0 +1,171 @@
+//! Hash to curve for secp256k1 using the simplified SWU method, as specified by the
+//! `secp256k1_XMD:SHA-256_SSWU_RO_` ciphersuite of RFC 9380 § 8.7.
+//! Since A = 0 on secp256k1, points are first mapped onto the 3-isogenous curve E' with the
+//! simplified SWU map, and then sent back to secp256k1 through the isogeny map.
+
+use crate::hash_to_curve::{CustomHashToCurve, HashToCurve};
+use ark_ec::short_weierstrass::{Affine, Projective};
+use ark_ff::{Field, MontFp, Zero};
+use ark_secp256k1::Fq;
+use digest::core_api::BlockSizeUser;
+use digest::FixedOutputReset;
+
+gen_hash_to_curve!(
+    ark_secp256k1::Config,
+    secp256k1_hash_to_curve_custom,
+    sha2::Sha256,
+    "secp256k1_XMD:SHA-256_SSWU_RO_"
+);
+
+/// Hash message into a point on the secp256k1 curve using a custom hash function and dst.
+fn secp256k1_hash_to_curve_custom<H: FixedOutputReset + BlockSizeUser + Default + Clone>(
+    message: &[u8],
+    dst: &[u8],
+) -> ark_secp256k1::Projective {
+    use ark_ec::AffineRepr;
+    use ark_ff::field_hashers::{DefaultFieldHasher, HashToField};
+
+    let htf = <DefaultFieldHasher<H, 128> as HashToField<Fq>>::new(dst);
+    let m = htf.hash_to_field::<2>(message);
+    let q0 = iso_map(map_to_curve_simple_swu(m[0]));
+    let q1 = iso_map(map_to_curve_simple_swu(m[1]));
+
+    // secp256k1 has a cofactor of 1, no need to clear it
+    let p: Affine<ark_secp256k1::Config> = (q0 + q1).into();
+    debug_assert!(p.is_on_curve());
+    p.into_group()
+}
+
+// Parameters of the curve E' isogenous to secp256k1, RFC 9380 § 8.7
+const ISO_A: Fq =
+    MontFp!("28734576633528757162648956269730739219262246272443394170905244663053633733939");
+const ISO_B: Fq = MontFp!("1771");
+const ISO_Z: Fq = MontFp!("-11");
+
+// https://www.rfc-editor.org/rfc/rfc9380.html#section-6.6.2
+// map_to_curve_simple_swu(u)
+//
+// Input: u, an element of F.
+// Output: (x, y), a point on E'.
+//
+fn map_to_curve_simple_swu(u: Fq) -> (Fq, Fq) {
+    use crate::hash_to_curve::svdw::{inv0, sgn0};
+
+    let g = |x: Fq| x.square() * x + ISO_A * x + ISO_B;
+
+    // 1. tv1 = inv0(Z^2 * u^4 + Z * u^2)
+    let z_u2 = ISO_Z * u.square();
+    let tv1 = inv0::<ark_secp256k1::Config>(&(z_u2.square() + z_u2));
+    // 2.  x1 = (-B / A) * (1 + tv1)
+    // 3.  If tv1 == 0, set x1 = B / (Z * A)
+    let x1 = if tv1.is_zero() {
+        ISO_B / (ISO_Z * ISO_A)
+    } else {
+        -ISO_B / ISO_A * (Fq::ONE + tv1)
+    };
+    // 4. gx1 = x1^3 + A * x1 + B
+    let gx1 = g(x1);
+    // 5.  x2 = Z * u^2 * x1
+    // 6. gx2 = x2^3 + A * x2 + B
+    // 7.  If is_square(gx1), set x = x1 and y = sqrt(gx1)
+    // 8.  Else set x = x2 and y = sqrt(gx2)
+    let (x, mut y) = match gx1.sqrt() {
+        Some(y1) => (x1, y1),
+        None => {
+            let x2 = z_u2 * x1;
+            let y2 = g(x2).sqrt().expect("gx2 is square when gx1 is not");
+            (x2, y2)
+        }
+    };
+    // 9.  If sgn0(u) != sgn0(y), set y = -y
+    if sgn0::<ark_secp256k1::Config>(&u) != sgn0::<ark_secp256k1::Config>(&y) {
+        y = -y;
+    }
+    // 10. return (x, y)
+    (x, y)
+}
+
+// https://www.rfc-editor.org/rfc/rfc9380.html#appendix-E.1
+// 3-isogeny map from E' to secp256k1, the point at infinity is returned on exceptional cases.
+fn iso_map((x, y): (Fq, Fq)) -> Projective<ark_secp256k1::Config> {
+    const X_NUM: [Fq; 4] = [
+        MontFp!("64328938465175664124206102782604393251816658147578091133031991115504908150983"),
+        MontFp!("3540463234204664767867377763959255381561641196938647754971861192896365225345"),
+        MontFp!("37676595701789655284650173187508961899444205326770530105295841645151729341026"),
+        MontFp!("64328938465175664124206102782604393251816658147578091133031991115504908150924"),
+    ];
+    const X_DEN: [Fq; 3] = [
+        MontFp!("95592507323525948732419199626899895302164312317343489384240252208201861084315"),
+        MontFp!("107505182841474506714709588670204841388457878609653642868747406790547894725908"),
+        Fq::ONE,
+    ];
+    const Y_NUM: [Fq; 4] = [
+        MontFp!("34308767181427020866243254817389009734302217678708315270950395261602617680444"),
+        MontFp!("90176424683627901097894375140309208301239340832535417794535213712559228940707"),
+        MontFp!("18838297850894827642325086593754480949722102663385265052647920822575864670513"),
+        MontFp!("21442979488391888041402034260868131083938886049192697044343997038501636050308"),
+    ];
+    const Y_DEN: [Fq; 4] = [
+        MontFp!("115792089237316195423570985008687907853269984665640564039457584007908834670907"),
+        MontFp!("55193343495945455350115628863323870199952967620749340073805588608787913909619"),
+        MontFp!("45465685024895564648493397996619354229416833248839900263663526177913007417199"),
+        Fq::ONE,
+    ];
+
+    // Evaluate the polynomial with coefficients k_0, ..., k_n at x
+    let eval = |k: &[Fq]| k.iter().rev().fold(Fq::ZERO, |acc, k_i| acc * x + k_i);
+
+    let x_den = eval(&X_DEN);
+    let y_den = eval(&Y_DEN);
+    if x_den.is_zero() || y_den.is_zero() {
+        return Projective::zero();
+    }
+
+    let p = Affine::new_unchecked(eval(&X_NUM) / x_den, y * eval(&Y_NUM) / y_den);
+    debug_assert!(p.is_on_curve());
+    p.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hash_to_curve::CustomHashToCurve;
+    use ark_ec::CurveGroup;
+    use ark_ff::{BigInteger, PrimeField};
+    use rstest::*;
+
+    // Test vectors from RFC 9380 § J.8.1
+    #[rstest]
+    #[case(
+        b"",
+        "c1cae290e291aee617ebaef1be6d73861479c48b841eaba9b7b5852ddfeb1346",
+        "64fa678e07ae116126f08b022a94af6de15985c996c3a91b64c406a960e51067"
+    )]
+    #[case(
+        b"abc",
+        "3377e01eab42db296b512293120c6cee72b6ecf9f9205760bd9ff11fb3cb2c4b",
+        "7f95890f33efebd1044d382a01b1bee0900fb6116f94688d487c6c7b9c8371f6"
+    )]
+    #[case(
+        b"abcdef0123456789",
+        "bac54083f293f1fe08e4a70137260aa90783a5cb84d3f35848b324d0674b0e3a",
+        "4436476085d4c3c4508b60fcf4389c40176adce756b398bdee27bca19758d828"
+    )]
+    #[case(
+        b"q128_qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqq",
+        "e2167bc785333a37aa562f021f1e881defb853839babf52a7f72b102e41890e9",
+        "f2401dd95cc35867ffed4f367cd564763719fbc6a53e969fb8496a1e6685d873"
+    )]
+    #[case(
+        b"a512_aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+        "e3c8d35aaaf0b9b647e88a0a0a7ee5d5bed5ad38238152e4e6fd8c1f8cb7c998",
+        "8446eeb6181bf12f56a9d24e262221cc2f0c4725c7e3803024b5888ee5823aa6"
+    )]
+    fn test_secp256k1_hash_to_point(#[case] msg: &[u8], #[case] p_x: &str, #[case] p_y: &str) {
+        const DST: &[u8] = b"QUUX-V01-CS02-with-secp256k1_XMD:SHA-256_SSWU_RO_";
+
+        let p =
+            ark_secp256k1::Projective::hash_to_curve_custom::<sha2::Sha256>(msg, DST).into_affine();
+        assert_eq!(hex::encode(p.x.into_bigint().to_bytes_be()), p_x);
+        assert_eq!(hex::encode(p.y.into_bigint().to_bytes_be()), p_y);
+    }
+}
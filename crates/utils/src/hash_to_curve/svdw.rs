@@ -0,0 +1,141 @@
+//! Shallue-van de Woestijne method for curves with A = 0, as described in RFC 9380 § 6.6.1.
+
+use ark_ec::short_weierstrass::{Projective, SWCurveConfig};
+
+// https://www.ietf.org/archive/id/draft-irtf-cfrg-hash-to-curve-10.html#name-the-sgn0-function-2
+// sgn0(x)
+//
+// Input: x, an element of GF(p^m).
+// Output: 0 or 1.
+//
+pub(crate) fn sgn0<C: SWCurveConfig>(x: &C::BaseField) -> u8 {
+    use ark_ff::{BigInteger, Field, PrimeField, Zero};
+
+    let mut sign = 0;
+    let mut zero = 1;
+    for x_i in x.to_base_prime_field_elements() {
+        let x_big = x_i.into_bigint();
+        let sign_i = x_big.is_odd() as u8;
+        let zero_i = x_i.is_zero() as u8;
+        sign |= zero & sign_i;
+        zero &= zero_i;
+    }
+
+    sign
+}
+
+// https://www.ietf.org/archive/id/draft-irtf-cfrg-hash-to-curve-10.html#section-4-4.6.1
+// inv0(x)
+//
+// Input: x, an element of GF(p^m).
+// Output: x^{q - 2}
+//
+pub(crate) fn inv0<C: SWCurveConfig>(x: &C::BaseField) -> C::BaseField {
+    use {ark_ec::AdditiveGroup, ark_ff::Field};
+    x.inverse().unwrap_or(C::BaseField::ZERO)
+}
+
+pub(crate) fn map_to_curve_const_a_zero<C: SWCurveConfig>(
+    u: C::BaseField,
+    c: [C::BaseField; 4],
+    z: C::BaseField,
+) -> Projective<C> {
+    #![allow(clippy::assign_op_pattern)]
+    use ark_ff::{Field, Zero};
+
+    assert!(C::COEFF_A.is_zero());
+    let is_square = |f: C::BaseField| f.legendre().is_qr();
+
+    // https://www.ietf.org/archive/id/draft-irtf-cfrg-hash-to-curve-10.html#section-f.1
+    //    1. c1 = g(Z)
+    let c1 = c[0];
+    //    2. c2 = -Z / 2
+    let c2 = c[1];
+    //    3. c3 = sqrt(-g(Z) * (3 * Z^2 + 4 * A))     # sgn0(c3) MUST equal 0
+    let c3 = c[2];
+    //    4. c4 = -4 * g(Z) / (3 * Z^2 + 4 * A)
+    let c4 = c[3];
+
+    //    1.  tv1 = u^2
+    let mut tv1 = u.square();
+    //    2.  tv1 = tv1 * c1
+    tv1 = tv1 * c1;
+    //    3.  tv2 = 1 + tv1
+    let tv2 = C::BaseField::ONE + tv1;
+    //    4.  tv1 = 1 - tv1
+    tv1 = C::BaseField::ONE - tv1;
+    //    5.  tv3 = tv1 * tv2
+    let mut tv3 = tv1 * tv2;
+    //    6.  tv3 = inv0(tv3)
+    tv3 = inv0::<C>(&tv3);
+    //    7.  tv4 = u * tv1
+    let mut tv4 = u * tv1;
+    //    8.  tv4 = tv4 * tv3
+    tv4 = tv4 * tv3;
+    //    9.  tv4 = tv4 * c3
+    tv4 = tv4 * c3;
+    //    10.  x1 = c2 - tv4
+    let x1 = c2 - tv4;
+    //    11. gx1 = x1^2
+    let mut gx1 = x1.square();
+    //    12. gx1 = gx1 + A
+    //    gx1 = gx1 + C::COEFF_A; // a is 0 for used curves.
+
+    //    13. gx1 = gx1 * x1
+    gx1 = gx1 * x1;
+    //    14. gx1 = gx1 + B
+    gx1 = gx1 + C::COEFF_B;
+
+    //    15.  e1 = is_square(gx1)
+    let e1 = is_square(gx1);
+    //    16.  x2 = c2 + tv4
+    let x2 = c2 + tv4;
+    //    17. gx2 = x2^2
+    let mut gx2 = x2.square();
+    //    18. gx2 = gx2 + A
+    //    gx2 = gx2 + C::COEFF_A; // a is 0 for used curves.
+
+    //    19. gx2 = gx2 * x2
+    gx2 = gx2 * x2;
+    //    20. gx2 = gx2 + B
+    gx2 = gx2 + C::COEFF_B;
+    //    21.  e2 = is_square(gx2) AND NOT e1
+    let e2 = is_square(gx2) && !e1;
+    //    22.  x3 = tv2^2
+    let mut x3 = tv2.square();
+    //    23.  x3 = x3 * tv3
+    x3 = x3 * tv3;
+    //    24.  x3 = x3^2
+    x3 = x3.square();
+    //    25.  x3 = x3 * c4
+    x3 = x3 * c4;
+    //    26.  x3 = x3 + Z
+    x3 = x3 + z;
+
+    // CMOV requires `subtle`, not supported by arkworks.
+    //    27.  x = CMOV(x3, x1, e1)      # x = x1 if gx1 is square, else x = x3
+    let mut x = if e1 { x1 } else { x3 };
+    //    28.  x = CMOV(x, x2, e2)       # x = x2 if gx2 is square and gx1 is not
+    if e2 {
+        x = x2;
+    }
+    //    29.  gx = x^2
+    let mut gx = x.square();
+    //    30.  gx = gx + A
+    //    gx = gx + C::COEFF_A; // a is 0 for used curves.
+
+    //    31.  gx = gx * x
+    gx = gx * x;
+    //    32.  gx = gx + B
+    gx = gx + C::COEFF_B;
+    //    33.   y = sqrt(gx)
+    let mut y = gx.sqrt().unwrap();
+    //    34.  e3 = sgn0(u) == sgn0(y)
+    let e3 = sgn0::<C>(&u) == sgn0::<C>(&y);
+    //    35. y = CMOV(-y, y, e3)       # Select correct sign of y
+    if !e3 {
+        y = -y;
+    }
+
+    Projective::new_unchecked(x, y, C::BaseField::ONE)
+}
@@ -5,6 +5,7 @@
 //! point is at infinity. The rest of the bits encode the x coordinate in big endian.
 //! For extension fields, we encode the element x = x_0 + x_1 i + ... + x_m i^m, as
 //! to_be_bytes(x) = to_be_bytes(x_0) || to_be_bytes(x_1) || ... || to_be_bytes(x_m)
+//! Secp256k1 points are encoded following SEC1.
 
 /// Module for serde serialization in Base64 format.
 pub mod base64;
@@ -343,6 +344,129 @@ mod bls12_381 {
     gen_ser_uncompressed_ark!(ark_bls12_381::g2::Config, 192);
 }
 
+#[cfg(feature = "secp256k1")]
+mod secp256k1 {
+    use super::*;
+    use crate::serialize::{
+        fq::{FqDeserialize, FqSerialize},
+        SerializationError,
+    };
+    use ark_ec::{
+        short_weierstrass::{Affine, Projective},
+        AffineRepr, CurveGroup,
+    };
+    use ark_ff::{BigInteger, PrimeField};
+    use ark_secp256k1::{Config, Fq};
+
+    const FQ_SIZE: usize = 32;
+
+    /// Decode a big endian, canonical, field element.
+    fn deser_canonical_fq(v: &[u8]) -> Result<Fq, SerializationError> {
+        let x = Fq::deser(v)?;
+        if x.ser()? != v {
+            // value greater than the modulus
+            Err(SerializationError::InvalidData)?;
+        }
+        Ok(x)
+    }
+
+    /// Follows the SEC1 encoding used by most secp256k1 libraries, see
+    /// <https://www.secg.org/sec1-v2.pdf>, § 2.3.3:
+    /// The point at infinity is encoded as a single 0x00 byte, other points are encoded as
+    /// 0x02 || x if y is even, 0x03 || x if y is odd, with x in big endian.
+    impl PointSerializeCompressed for Affine<Config> {
+        fn ser_compressed(&self) -> Result<Vec<u8>, SerializationError> {
+            let Some((x, y)) = self.xy() else {
+                return Ok(vec![0x00]);
+            };
+
+            let mut buf = Vec::with_capacity(1 + FQ_SIZE);
+            buf.push(if y.into_bigint().is_odd() { 0x03 } else { 0x02 });
+            buf.extend(x.ser()?);
+            Ok(buf)
+        }
+    }
+
+    impl PointDeserializeCompressed for Affine<Config> {
+        fn deser_compressed(v: &[u8]) -> Result<Self, SerializationError> {
+            match v {
+                [0x00] => Ok(Self::zero()),
+                [tag @ (0x02 | 0x03), x @ ..] if x.len() == FQ_SIZE => {
+                    let x = deser_canonical_fq(x)?;
+                    let (y0, y1) =
+                        Self::get_ys_from_x_unchecked(x).ok_or(SerializationError::InvalidData)?;
+                    let y = if y0.into_bigint().is_odd() == (*tag == 0x03) {
+                        y0
+                    } else {
+                        y1
+                    };
+
+                    // secp256k1 has a cofactor of 1, any point on the curve is valid
+                    Ok(Self::new_unchecked(x, y))
+                }
+                _ => Err(SerializationError::InvalidData),
+            }
+        }
+    }
+
+    /// SEC1 uncompressed encoding, 0x04 || x || y, with the point at infinity encoded as 0x00.
+    impl PointSerializeUncompressed for Affine<Config> {
+        fn ser_uncompressed(&self) -> Result<Vec<u8>, SerializationError> {
+            let Some((x, y)) = self.xy() else {
+                return Ok(vec![0x00]);
+            };
+
+            let mut buf = Vec::with_capacity(1 + 2 * FQ_SIZE);
+            buf.push(0x04);
+            buf.extend(x.ser()?);
+            buf.extend(y.ser()?);
+            Ok(buf)
+        }
+    }
+
+    impl PointDeserializeUncompressed for Affine<Config> {
+        fn deser_uncompressed(v: &[u8]) -> Result<Self, SerializationError> {
+            match v {
+                [0x00] => Ok(Self::zero()),
+                [0x04, xy @ ..] if xy.len() == 2 * FQ_SIZE => {
+                    let x = deser_canonical_fq(&xy[..FQ_SIZE])?;
+                    let y = deser_canonical_fq(&xy[FQ_SIZE..])?;
+                    let p = Self::new_unchecked(x, y);
+                    if !p.is_on_curve() {
+                        Err(SerializationError::InvalidData)?;
+                    }
+                    Ok(p)
+                }
+                _ => Err(SerializationError::InvalidData),
+            }
+        }
+    }
+
+    impl PointSerializeCompressed for Projective<Config> {
+        fn ser_compressed(&self) -> Result<Vec<u8>, SerializationError> {
+            self.into_affine().ser_compressed()
+        }
+    }
+
+    impl PointDeserializeCompressed for Projective<Config> {
+        fn deser_compressed(v: &[u8]) -> Result<Self, SerializationError> {
+            Affine::<Config>::deser_compressed(v).map(AffineRepr::into_group)
+        }
+    }
+
+    impl PointSerializeUncompressed for Projective<Config> {
+        fn ser_uncompressed(&self) -> Result<Vec<u8>, SerializationError> {
+            self.into_affine().ser_uncompressed()
+        }
+    }
+
+    impl PointDeserializeUncompressed for Projective<Config> {
+        fn deser_uncompressed(v: &[u8]) -> Result<Self, SerializationError> {
+            Affine::<Config>::deser_uncompressed(v).map(AffineRepr::into_group)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #[cfg(feature = "bn254")]
@@ -568,4 +692,83 @@ mod tests {
             assert_eq!(G2Affine::zero().ser_compressed().unwrap(), v)
         }
     }
+
+    #[cfg(feature = "secp256k1")]
+    mod secp256k1 {
+        use ark_ec::{AffineRepr, PrimeGroup};
+        use ark_secp256k1::{Affine, Projective};
+        use rstest::*;
+
+        use crate::serialize::point::{
+            PointDeserializeCompressed, PointDeserializeUncompressed, PointSerializeCompressed,
+            PointSerializeUncompressed,
+        };
+
+        #[rstest]
+        #[case(
+            1,
+            "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+            "0479be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8"
+        )]
+        #[case(
+            2,
+            "02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5",
+            "04c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee51ae168fea63dc339a3c58419466ceaeef7f632653266d0e1236431a950cfe52a"
+        )]
+        #[case(
+            3,
+            "02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9",
+            "04f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9388f7b0f632de8140fe337e62a37f3566500a99934c2231b6cb9fd7584b8e672"
+        )]
+        fn test_serialize_sec1(
+            #[case] k: u64,
+            #[case] compressed: &str,
+            #[case] uncompressed: &str,
+        ) {
+            let p = Projective::generator() * ark_secp256k1::Fr::from(k);
+
+            let v = hex::decode(compressed).unwrap();
+            assert_eq!(p.ser_compressed().unwrap(), v);
+            assert_eq!(Projective::deser_compressed(&v).unwrap(), p);
+
+            let v = hex::decode(uncompressed).unwrap();
+            assert_eq!(p.ser_uncompressed().unwrap(), v);
+            assert_eq!(Projective::deser_uncompressed(&v).unwrap(), p);
+        }
+
+        #[test]
+        fn test_serialize_sec1_odd_y() {
+            let p = -Projective::generator();
+            let v = p.ser_compressed().unwrap();
+            assert_eq!(v[0], 0x03);
+            assert_eq!(Projective::deser_compressed(&v).unwrap(), p);
+        }
+
+        #[test]
+        fn test_serialize_sec1_infinity() {
+            assert_eq!(Affine::zero().ser_compressed().unwrap(), vec![0x00]);
+            assert_eq!(Affine::deser_compressed(&[0x00]).unwrap(), Affine::zero());
+            assert_eq!(Affine::zero().ser_uncompressed().unwrap(), vec![0x00]);
+            assert_eq!(Affine::deser_uncompressed(&[0x00]).unwrap(), Affine::zero());
+        }
+
+        #[test]
+        fn test_deserialize_sec1_invalid() {
+            // x = p is not canonical
+            let v =
+                hex::decode("02fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f")
+                    .unwrap();
+            assert!(Affine::deser_compressed(&v).is_err());
+
+            // invalid tag
+            let mut v = Affine::generator().ser_compressed().unwrap();
+            v[0] = 0x04;
+            assert!(Affine::deser_compressed(&v).is_err());
+
+            // point not on curve
+            let mut v = Affine::generator().ser_uncompressed().unwrap();
+            v[64] ^= 1;
+            assert!(Affine::deser_uncompressed(&v).is_err());
+        }
+    }
 }